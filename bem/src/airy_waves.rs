@@ -1,7 +1,5 @@
 //! Airy wave theory implementation
 
-use super::*;
-
 /// Airy wave parameters
 pub struct AiryWaveParams {
     pub amplitude: f64,
@@ -27,7 +25,7 @@ pub struct AiryWaveTheory;
 
 impl AiryWaveTheory {
     /// Calculate wave elevation at given position and time
-    pub fn elevation(&self, params: &AiryWaveParams, x: f64, y: f64, t: f64) -> f64 {
+    pub fn elevation(&self, params: &AiryWaveParams, x: f64, _y: f64, t: f64) -> f64 {
         params.amplitude * (params.wave_number * x - params.frequency * t + params.phase).cos()
     }
 } 
\ No newline at end of file
@@ -0,0 +1,151 @@
+//! Checkpoint/restart of frequency sweeps
+//!
+//! Sweeps solve frequencies in batches and hand the completed ones to a
+//! `CheckpointStore` after each batch. An interrupted sweep resumes from the
+//! stored checkpoint and only solves the frequencies still missing. Storage
+//! is left to the store implementation (`wavecore_io::FileCheckpoint` writes
+//! JSON files).
+
+use super::*;
+use crate::diagnostics::QualityDiagnostics;
+use crate::drift::MeanDriftForce;
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+
+/// Default number of frequencies solved between checkpoints
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 4;
+
+/// Coefficients of one sweep frequency, row-major
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequencyRecord {
+    /// Wave frequency (rad/s)
+    pub frequency: f64,
+    /// Added mass, n_dof × n_dof
+    pub added_mass: Vec<f64>,
+    /// Radiation damping, n_dof × n_dof
+    pub damping: Vec<f64>,
+    /// Excitation per direction, directions × n_dof
+    pub excitation: Vec<Complex64>,
+    /// Froude-Krylov part of the excitation, directions × n_dof
+    pub froude_krylov: Vec<Complex64>,
+    /// Haskind discrepancy per direction
+    pub haskind_discrepancy: Vec<Option<f64>>,
+    /// Far-field mean drift per direction
+    pub mean_drift: Vec<Option<MeanDriftForce>>,
+    /// Quality diagnostics of the frequency
+    pub diagnostics: QualityDiagnostics,
+    /// Whether every solve converged
+    pub converged: bool,
+}
+
+impl FrequencyRecord {
+    /// All coefficients as one vector of refinement channels
+    pub(crate) fn values(&self) -> Vec<f64> {
+        let excitation = self.excitation.iter().flat_map(|f| [f.re, f.im]);
+        self.added_mass.iter().chain(&self.damping).copied().chain(excitation).collect()
+    }
+}
+
+/// Completed frequencies of a sweep and the sweep they belong to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepCheckpoint {
+    /// Requested sweep frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Number of degrees of freedom of the bodies
+    pub n_dof: usize,
+    /// Frequencies solved between checkpoints
+    pub interval: usize,
+    /// Completed frequencies
+    pub records: Vec<FrequencyRecord>,
+}
+
+impl SweepCheckpoint {
+    /// Create an empty checkpoint for a sweep
+    pub fn new(frequencies: Vec<f64>, directions: Vec<f64>, n_dof: usize, interval: usize) -> Self {
+        Self {
+            frequencies,
+            directions,
+            n_dof,
+            interval: interval.max(1),
+            records: Vec::new(),
+        }
+    }
+
+    /// Requested frequencies without a completed record
+    pub fn pending(&self) -> Vec<f64> {
+        self.frequencies
+            .iter()
+            .copied()
+            .filter(|f| !self.records.iter().any(|r| r.frequency == *f))
+            .collect()
+    }
+
+    /// Check if every requested frequency is completed
+    pub fn is_complete(&self) -> bool {
+        self.pending().is_empty()
+    }
+
+    /// Check that the checkpoint belongs to bodies with `n_dof` modes
+    pub fn validate(&self, n_dof: usize) -> Result<()> {
+        let consistent = self.n_dof == n_dof
+            && self.records.iter().all(|r| {
+                r.added_mass.len() == n_dof * n_dof
+                    && r.damping.len() == n_dof * n_dof
+                    && r.excitation.len() == n_dof * self.directions.len()
+                    && r.froude_krylov.len() == r.excitation.len()
+            });
+        if consistent {
+            Ok(())
+        } else {
+            Err(BEMError::InvalidProblem {
+                message: format!("Checkpoint does not match bodies with {} modes", n_dof),
+            })
+        }
+    }
+}
+
+/// Persistent storage of sweep checkpoints
+pub trait CheckpointStore: Sync {
+    /// Store a checkpoint, replacing any previous one
+    fn save(&self, checkpoint: &SweepCheckpoint) -> Result<()>;
+
+    /// Load the stored checkpoint, if any
+    fn load(&self) -> Result<Option<SweepCheckpoint>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_checkpointed_sweep_resumes() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let frequencies = [0.5, 1.0, 1.5];
+        let bodies = || vec![tetrahedron_body("a", 0.0)];
+        let reference = solver.solve_sweep_bodies(&frequencies, &[0.0], bodies()).unwrap();
+
+        let store = MemoryStore::default();
+        assert!(solver.resume_from(&store, bodies()).is_err());
+        let sweep = solver.solve_sweep_checkpointed(&frequencies, &[0.0], bodies(), &store, 2).unwrap();
+        assert_eq!(store.saves.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(sweep.added_mass, reference.added_mass);
+
+        // Interrupted after the first frequency
+        let mut checkpoint = store.load().unwrap().unwrap();
+        assert!(checkpoint.is_complete());
+        checkpoint.records.retain(|r| r.frequency == 1.0);
+        assert_eq!(checkpoint.pending(), vec![0.5, 1.5]);
+        store.save(&checkpoint).unwrap();
+
+        let resumed = solver.resume_from(&store, bodies()).unwrap();
+        assert_eq!(resumed.frequencies, frequencies.to_vec());
+        assert!(resumed.is_complete());
+        assert_eq!(resumed.added_mass, reference.added_mass);
+        assert_eq!(resumed.excitation, reference.excitation);
+        assert!(store.load().unwrap().unwrap().is_complete());
+        assert!(solver.resume_from(&store, vec![tetrahedron_body("a", 0.0), tetrahedron_body("b", 5.0)]).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Hydrodynamic coefficient datasets
+//!
+//! Result of a frequency sweep: added mass and damping per frequency and
+//! excitation per frequency and wave direction, indexed by global mode
+//! (each body's six rigid-body modes followed by its generalized modes). Adaptive sweeps bisect intervals whose samples
+//! depart from linear interpolation, so narrow resonance peaks are resolved.
+
+use crate::diagnostics::QualityDiagnostics;
+use crate::drift::{MeanDriftForce, WaveDriftDamping};
+use ndarray::{Array2, Array3, Array4, ArrayView1, ArrayView2};
+use num_complex::Complex64;
+
+/// Hydrodynamic coefficients over a frequency/direction grid
+#[derive(Debug, Clone)]
+pub struct HydroCoefficients {
+    /// Wave frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Added mass, shape (frequency, mode, mode)
+    pub added_mass: Array3<f64>,
+    /// Radiation damping, shape (frequency, mode, mode)
+    pub damping: Array3<f64>,
+    /// Complex wave excitation per unit wave amplitude, shape (frequency, direction, mode)
+    pub excitation: Array3<Complex64>,
+    /// Froude-Krylov part of `excitation`, shape (frequency, direction, mode)
+    pub froude_krylov: Array3<Complex64>,
+    /// Relative difference of direct and Haskind excitation, shape (frequency, direction)
+    pub haskind_discrepancy: Array2<f64>,
+    /// Far-field mean drift (surge, sway, yaw), shape (frequency, direction, 3)
+    pub mean_drift: Array3<f64>,
+    /// Worst quality diagnostics of each frequency, with reciprocity of the full matrices
+    pub diagnostics: Vec<QualityDiagnostics>,
+    /// Whether every solve at each frequency converged
+    pub converged: Vec<bool>,
+    /// Whether each frequency was solved before the time budget ran out
+    pub completed: Vec<bool>,
+    /// Total computation time in seconds
+    pub computation_time: f64,
+}
+
+impl HydroCoefficients {
+    /// Create a dataset with all values unset (NaN)
+    pub fn new(frequencies: Vec<f64>, directions: Vec<f64>, n_dof: usize) -> Self {
+        let (nf, nd) = (frequencies.len(), directions.len());
+        Self {
+            added_mass: Array3::from_elem((nf, n_dof, n_dof), f64::NAN),
+            damping: Array3::from_elem((nf, n_dof, n_dof), f64::NAN),
+            excitation: Array3::from_elem((nf, nd, n_dof), Complex64::new(f64::NAN, f64::NAN)),
+            froude_krylov: Array3::from_elem((nf, nd, n_dof), Complex64::new(f64::NAN, f64::NAN)),
+            haskind_discrepancy: Array2::from_elem((nf, nd), f64::NAN),
+            mean_drift: Array3::from_elem((nf, nd, 3), f64::NAN),
+            diagnostics: vec![QualityDiagnostics::default(); nf],
+            converged: vec![false; nf],
+            completed: vec![false; nf],
+            frequencies,
+            directions,
+            computation_time: 0.0,
+        }
+    }
+
+    /// Number of degrees of freedom
+    pub fn n_dof(&self) -> usize {
+        self.added_mass.shape()[1]
+    }
+
+    /// Store the row-major coefficients of frequency `index`
+    pub fn set_frequency(&mut self, index: usize, added_mass: &[f64], damping: &[f64], excitation: &[Complex64], converged: bool) {
+        let n = self.n_dof();
+        let nd = self.directions.len();
+        self.added_mass
+            .index_axis_mut(ndarray::Axis(0), index)
+            .assign(&ArrayView2::from_shape((n, n), added_mass).expect("added mass shape"));
+        self.damping
+            .index_axis_mut(ndarray::Axis(0), index)
+            .assign(&ArrayView2::from_shape((n, n), damping).expect("damping shape"));
+        self.excitation
+            .index_axis_mut(ndarray::Axis(0), index)
+            .assign(&ArrayView2::from_shape((nd, n), excitation).expect("excitation shape"));
+        self.converged[index] = converged;
+        self.completed[index] = true;
+    }
+
+    /// Store the row-major Froude-Krylov excitation (direction × mode) of frequency `index`
+    pub fn set_froude_krylov(&mut self, index: usize, froude_krylov: &[Complex64]) {
+        let (nd, n) = (self.directions.len(), self.n_dof());
+        self.froude_krylov
+            .index_axis_mut(ndarray::Axis(0), index)
+            .assign(&ArrayView2::from_shape((nd, n), froude_krylov).expect("Froude-Krylov shape"));
+    }
+
+    /// Store the mean drift at frequency and direction indices
+    pub fn set_mean_drift(&mut self, frequency: usize, direction: usize, drift: &MeanDriftForce) {
+        self.mean_drift[[frequency, direction, 0]] = drift.surge;
+        self.mean_drift[[frequency, direction, 1]] = drift.sway;
+        self.mean_drift[[frequency, direction, 2]] = drift.yaw;
+    }
+
+    /// Store the Haskind discrepancy at frequency and direction indices
+    pub fn set_haskind_discrepancy(&mut self, frequency: usize, direction: usize, discrepancy: f64) {
+        self.haskind_discrepancy[[frequency, direction]] = discrepancy;
+    }
+
+    /// Store the quality diagnostics of frequency `index`
+    pub fn set_diagnostics(&mut self, index: usize, diagnostics: QualityDiagnostics) {
+        self.diagnostics[index] = diagnostics;
+    }
+
+    /// Added mass matrix at frequency `index`
+    pub fn added_mass_at(&self, index: usize) -> Array2<f64> {
+        self.added_mass.index_axis(ndarray::Axis(0), index).to_owned()
+    }
+
+    /// Damping matrix at frequency `index`
+    pub fn damping_at(&self, index: usize) -> Array2<f64> {
+        self.damping.index_axis(ndarray::Axis(0), index).to_owned()
+    }
+
+    /// Added mass and damping linearly interpolated at `frequency`
+    ///
+    /// Only completed frequencies are used; `None` outside their range.
+    pub fn radiation_at(&self, frequency: f64) -> Option<(Array2<f64>, Array2<f64>)> {
+        let mut solved: Vec<usize> = (0..self.frequencies.len()).filter(|&f| self.completed[f]).collect();
+        solved.sort_by(|&a, &b| self.frequencies[a].total_cmp(&self.frequencies[b]));
+        let upper = solved.iter().position(|&f| self.frequencies[f] >= frequency)?;
+        let hi = solved[upper];
+        if self.frequencies[hi] == frequency {
+            return Some((self.added_mass_at(hi), self.damping_at(hi)));
+        }
+        let lo = *solved.get(upper.checked_sub(1)?)?;
+        let t = (frequency - self.frequencies[lo]) / (self.frequencies[hi] - self.frequencies[lo]);
+        let blend = |a: Array2<f64>, b: Array2<f64>| a * (1.0 - t) + b * t;
+        Some((
+            blend(self.added_mass_at(lo), self.added_mass_at(hi)),
+            blend(self.damping_at(lo), self.damping_at(hi)),
+        ))
+    }
+
+    /// Excitation vector at frequency and direction indices
+    pub fn excitation_at(&self, frequency: usize, direction: usize) -> ArrayView1<'_, Complex64> {
+        self.excitation.slice(ndarray::s![frequency, direction, ..])
+    }
+
+    /// Whether every frequency completed and converged
+    pub fn is_complete(&self) -> bool {
+        self.completed.iter().zip(&self.converged).all(|(&c, &v)| c && v)
+    }
+
+    /// Wave drift damping from the mean drift by Aranha's formula, shape (frequency, direction, 3, 2)
+    ///
+    /// Frequency and heading derivatives are differences between neighbouring
+    /// samples of the sorted grid, so at least two frequencies and two
+    /// directions are needed; entries are NaN otherwise.
+    pub fn wave_drift_damping(&self) -> Array4<f64> {
+        let (nf, nd) = (self.frequencies.len(), self.directions.len());
+        let drift = |f: usize, d: usize| MeanDriftForce {
+            surge: self.mean_drift[[f, d, 0]],
+            sway: self.mean_drift[[f, d, 1]],
+            yaw: self.mean_drift[[f, d, 2]],
+        };
+        let mut damping = Array4::from_elem((nf, nd, 3, 2), f64::NAN);
+        for f in 0..nf {
+            for d in 0..nd {
+                let d_frequency = derivative(&self.frequencies, f, |i| drift(i, d));
+                let d_heading = derivative(&self.directions, d, |i| drift(f, i));
+                let (Some(d_frequency), Some(d_heading)) = (d_frequency, d_heading) else { continue };
+                let b = WaveDriftDamping::aranha(self.frequencies[f], self.directions[d], 9.81, &drift(f, d), &d_frequency, &d_heading);
+                for (i, row) in b.matrix.iter().enumerate() {
+                    for (j, value) in row.iter().enumerate() {
+                        damping[[f, d, i, j]] = *value;
+                    }
+                }
+            }
+        }
+        damping
+    }
+}
+
+/// Difference quotient of `value` at sample `i` of the sorted grid `x`
+fn derivative<F: Fn(usize) -> MeanDriftForce>(x: &[f64], i: usize, value: F) -> Option<MeanDriftForce> {
+    if x.len() < 2 {
+        return None;
+    }
+    let (lo, hi) = (i.saturating_sub(1), (i + 1).min(x.len() - 1));
+    let (a, b, dx) = (value(lo), value(hi), x[hi] - x[lo]);
+    Some(MeanDriftForce {
+        surge: (b.surge - a.surge) / dx,
+        sway: (b.sway - a.sway) / dx,
+        yaw: (b.yaw - a.yaw) / dx,
+    })
+}
+
+/// Adaptive frequency refinement settings
+#[derive(Debug, Clone)]
+pub struct AdaptiveRefinement {
+    /// Allowed deviation from linear interpolation, relative to each coefficient's range
+    pub tolerance: f64,
+    /// Smallest interval that may be bisected (rad/s)
+    pub min_spacing: f64,
+    /// Maximum total number of frequencies
+    pub max_frequencies: usize,
+    /// Maximum refinement passes
+    pub max_passes: usize,
+}
+
+impl Default for AdaptiveRefinement {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.05,
+            min_spacing: 1e-3,
+            max_frequencies: 200,
+            max_passes: 8,
+        }
+    }
+}
+
+impl AdaptiveRefinement {
+    /// New frequencies to solve, given sorted sampled frequencies and their values
+    ///
+    /// Each sample carries the same set of coefficients; missing samples
+    /// (e.g. skipped by a time budget) are ignored. At an interior sample whose
+    /// value of any coefficient deviates from the chord between its neighbours
+    /// by more than `tolerance` times that coefficient's range, both adjacent
+    /// intervals are bisected.
+    pub fn refine(&self, frequencies: &[f64], values: &[Option<Vec<f64>>]) -> Vec<f64> {
+        let known: Vec<(f64, &Vec<f64>)> = frequencies
+            .iter()
+            .zip(values)
+            .filter_map(|(&f, v)| v.as_ref().map(|v| (f, v)))
+            .collect();
+        if known.len() < 3 {
+            return Vec::new();
+        }
+
+        let n_channels = known[0].1.len();
+        let range: Vec<f64> = (0..n_channels)
+            .map(|c| {
+                let (lo, hi) = known.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, v)| {
+                    (lo.min(v[c]), hi.max(v[c]))
+                });
+                hi - lo
+            })
+            .collect();
+
+        let mut flagged = vec![false; known.len() - 1];
+        for i in 1..known.len() - 1 {
+            let (f0, v0) = known[i - 1];
+            let (f1, v1) = known[i];
+            let (f2, v2) = known[i + 1];
+            let t = (f1 - f0) / (f2 - f0);
+            let rapid = (0..n_channels).any(|c| {
+                let chord = v0[c] + t * (v2[c] - v0[c]);
+                range[c] > 0.0 && (v1[c] - chord).abs() > self.tolerance * range[c]
+            });
+            if rapid {
+                flagged[i - 1] = true;
+                flagged[i] = true;
+            }
+        }
+
+        let budget = self.max_frequencies.saturating_sub(frequencies.len());
+        flagged
+            .iter()
+            .enumerate()
+            .filter(|(i, &f)| f && known[i + 1].0 - known[*i].0 > 2.0 * self.min_spacing)
+            .map(|(i, _)| 0.5 * (known[i].0 + known[i + 1].0))
+            .take(budget)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{AssemblyConfig, BEMSolverImpl};
+    use crate::{BEMConfig, BEMSolver, ProblemType, SolverEngine};
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_set_frequency_layout() {
+        let mut coefficients = HydroCoefficients::new(vec![0.5, 1.0], vec![0.0, 1.57], 6);
+        let added_mass: Vec<f64> = (0..36).map(|i| i as f64).collect();
+        let excitation: Vec<Complex64> = (0..12).map(|i| Complex64::new(-(i as f64), 1.0)).collect();
+        coefficients.set_frequency(1, &added_mass, &vec![0.0; 36], &excitation, true);
+
+        assert_eq!(coefficients.added_mass_at(1)[[2, 3]], 15.0);
+        assert_eq!(coefficients.excitation_at(1, 1)[0], Complex64::new(-6.0, 1.0));
+        assert!(coefficients.added_mass_at(0)[[0, 0]].is_nan());
+        assert!(!coefficients.is_complete());
+    }
+
+    #[test]
+    fn test_wave_drift_damping_from_sweep() {
+        // Head-sea drift independent of frequency and heading
+        let mut coefficients = HydroCoefficients::new(vec![0.5, 1.0], vec![3.0, std::f64::consts::PI], 6);
+        let drift = MeanDriftForce { surge: -1e3, sway: 0.0, yaw: 0.0 };
+        for f in 0..2 {
+            for d in 0..2 {
+                coefficients.set_mean_drift(f, d, &drift);
+            }
+        }
+        let damping = coefficients.wave_drift_damping();
+        assert_eq!(damping.shape(), &[2, 2, 3, 2]);
+        assert!((damping[[1, 1, 0, 0]] - 4e3 / 9.81).abs() < 1e-9);
+        assert!(damping[[1, 1, 1, 0]].abs() < 1e-9);
+
+        let single = HydroCoefficients::new(vec![1.0], vec![0.0], 6);
+        assert!(single.wave_drift_damping().iter().all(|b| b.is_nan()));
+    }
+
+    #[test]
+    fn test_refinement_targets_peak() {
+        // Lorentzian peak at 1.0 rad/s sampled coarsely
+        let frequencies: Vec<f64> = (0..11).map(|i| 0.5 + 0.1 * i as f64).collect();
+        let values: Vec<Option<Vec<f64>>> = frequencies
+            .iter()
+            .map(|&f| Some(vec![1.0 / ((f - 1.0).powi(2) + 0.01), f]))
+            .collect();
+
+        let new = AdaptiveRefinement::default().refine(&frequencies, &values);
+        assert!(!new.is_empty());
+        assert!(new.iter().all(|&f| (f - 1.0).abs() < 0.35));
+
+        // A linear response needs no refinement
+        let linear: Vec<Option<Vec<f64>>> = frequencies.iter().map(|&f| Some(vec![2.0 * f])).collect();
+        assert!(AdaptiveRefinement::default().refine(&frequencies, &linear).is_empty());
+    }
+
+    #[test]
+    fn test_frequency_sweep_matches_single_solves() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let frequencies = [0.5, 1.0, 1.5];
+        let directions = [0.0, std::f64::consts::FRAC_PI_2];
+        let sweep = solver
+            .solve_sweep_bodies(&frequencies, &directions, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+
+        assert_eq!(sweep.added_mass.shape(), &[3, 6, 6]);
+        assert_eq!(sweep.excitation.shape(), &[3, 2, 6]);
+        assert!(sweep.completed.iter().all(|&c| c));
+        assert_eq!(sweep.mean_drift.shape(), &[3, 2, 3]);
+        assert!(sweep.mean_drift.iter().all(|v| v.is_finite()));
+        assert!(sweep.haskind_discrepancy.iter().all(|v| v.is_finite()));
+
+        let single = solver
+            .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 2 }, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+        let expected = single.added_mass().unwrap().get(2, 2).unwrap();
+        assert!((sweep.added_mass_at(1)[[2, 2]] - expected).abs() < 1e-9 * (1.0 + expected.abs()));
+    }
+
+    #[test]
+    fn test_adaptive_sweep_respects_limits() {
+        let refinement = AdaptiveRefinement {
+            tolerance: 0.0,
+            max_frequencies: 7,
+            ..Default::default()
+        };
+        let sweep = BEMSolverImpl::new(BEMConfig::default())
+            .solve_adaptive_sweep(vec![tetrahedron_body("a", 0.0)], &[1.5, 0.5, 1.0], &[0.0], AssemblyConfig::default(), &refinement)
+            .unwrap();
+
+        assert!(sweep.frequencies.len() <= 7);
+        assert!(sweep.frequencies.windows(2).all(|w| w[0] < w[1]));
+        assert!(sweep.completed.iter().all(|&c| c));
+    }
+}
@@ -0,0 +1,243 @@
+//! Dynamic positioning controllers for time-domain simulations
+//!
+//! A [`Controller`] acts on the position errors from the setpoints of
+//! [`ControlForces`] and is sampled at the start of every time step, holding
+//! its forces over the step as a thruster command would. Forces are clipped to
+//! the thrust limit of each DOF, and the PID integrator stops winding up while
+//! saturation is driven further. The LQR gain is designed on the linear model
+//! (M + A∞) ẍ + B ẋ + C x = u, discretized with the same zero-order hold.
+
+use super::*;
+use crate::time_domain::{BodyProperties, ControlForces, ControllerType};
+use nalgebra::{DMatrix, DVector};
+use wavecore_matrices::Matrix;
+
+/// Sampled controller of a time-domain simulation
+#[derive(Debug, Clone)]
+pub struct Controller {
+    /// Control law and its state
+    law: ControlLaw,
+    /// Target positions
+    targets: Vec<f64>,
+    /// Largest force of each DOF
+    limits: Vec<f64>,
+    /// Sampling interval (s)
+    dt: f64,
+}
+
+#[derive(Debug, Clone)]
+enum ControlLaw {
+    /// Diagonal gains and the integrated position errors
+    Pid { proportional: Vec<f64>, integral: Vec<f64>, derivative: Vec<f64>, integrated: Vec<f64> },
+    /// State feedback u = −K [x − x₀; ẋ]
+    Lqr { gain: DMatrix<f64> },
+}
+
+impl Controller {
+    /// Controller of `control` for a body of mass `mass` including A∞, sampled every `dt`
+    pub fn new(control: &ControlForces, mass: &Matrix, properties: &BodyProperties, dt: f64) -> Result<Self> {
+        let n = mass.rows;
+        let check = |name: &str, values: &[f64]| -> Result<()> {
+            if values.len() != n || values.iter().any(|v| v.is_nan() || *v < 0.0) {
+                return Err(BEMError::InvalidProblem {
+                    message: format!("Controller {} needs {} non-negative values, got {:?}", name, n, values),
+                });
+            }
+            Ok(())
+        };
+        if control.targets.len() != n {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Controller needs {} targets, got {}", n, control.targets.len()),
+            });
+        }
+        if !control.thrust_limits.is_empty() {
+            check("thrust limits", &control.thrust_limits)?;
+        }
+        let law = match &control.controller_type {
+            ControllerType::PID { proportional, integral, derivative } => {
+                check("proportional gains", proportional)?;
+                check("integral gains", integral)?;
+                check("derivative gains", derivative)?;
+                ControlLaw::Pid {
+                    proportional: proportional.clone(),
+                    integral: integral.clone(),
+                    derivative: derivative.clone(),
+                    integrated: vec![0.0; n],
+                }
+            },
+            ControllerType::LQR { position_weights, velocity_weights, force_weights } => {
+                check("position weights", position_weights)?;
+                check("velocity weights", velocity_weights)?;
+                check("force weights", force_weights)?;
+                if force_weights.contains(&0.0) {
+                    return Err(BEMError::InvalidProblem { message: "LQR force weights must be positive".to_string() });
+                }
+                let (a, b) = zero_order_hold(mass, properties, dt)?;
+                let q = DMatrix::from_diagonal(&DVector::from_iterator(2 * n, position_weights.iter().chain(velocity_weights).copied()));
+                let r = DMatrix::from_diagonal(&DVector::from_vec(force_weights.clone()));
+                ControlLaw::Lqr { gain: discrete_lqr(&a, &b, &q, &r)? }
+            },
+            other => {
+                return Err(BEMError::SolverError { message: format!("Unsupported controller {:?}", other) });
+            }
+        };
+        Ok(Self { law, targets: control.targets.clone(), limits: control.thrust_limits.clone(), dt })
+    }
+
+    /// Control forces for the state at the start of a step, advancing the integrator
+    pub fn update(&mut self, positions: &[f64], velocities: &[f64]) -> Vec<f64> {
+        let errors: Vec<f64> = positions.iter().zip(&self.targets).map(|(x, target)| x - target).collect();
+        let mut forces: Vec<f64> = match &self.law {
+            ControlLaw::Pid { proportional, integral, derivative, integrated } => (0..errors.len())
+                .map(|i| -(proportional[i] * errors[i] + integral[i] * integrated[i] + derivative[i] * velocities[i]))
+                .collect(),
+            ControlLaw::Lqr { gain } => {
+                let state = DVector::from_iterator(2 * errors.len(), errors.iter().chain(velocities).copied());
+                (-(gain * state)).iter().copied().collect()
+            },
+        };
+
+        for (i, force) in forces.iter_mut().enumerate() {
+            let limit = self.limits.get(i).copied().unwrap_or(f64::INFINITY);
+            let saturated = force.abs() > limit;
+            if let ControlLaw::Pid { integrated, .. } = &mut self.law {
+                // Integrating an error that pushes the force further past its limit would wind up
+                if !(saturated && errors[i] * *force < 0.0) {
+                    integrated[i] += errors[i] * self.dt;
+                }
+            }
+            *force = force.clamp(-limit, limit);
+        }
+        forces
+    }
+}
+
+/// State-space model of the body for states [x; ẋ] under forces held over `dt`
+fn zero_order_hold(mass: &Matrix, properties: &BodyProperties, dt: f64) -> Result<(DMatrix<f64>, DMatrix<f64>)> {
+    let n = mass.rows;
+    let dense = |matrix: &Matrix| -> Result<DMatrix<f64>> {
+        if matrix.rows != n || matrix.cols != n {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Body matrix of shape {:?} does not match {} DOFs", matrix.dimensions(), n),
+            });
+        }
+        Ok(DMatrix::from_row_slice(n, n, &matrix.data))
+    };
+    let inverse = dense(mass)?.try_inverse().ok_or_else(|| BEMError::NumericalError {
+        message: "Singular mass matrix".to_string(),
+    })?;
+
+    // exp of [[A, B], [0, 0]] Δt holds the discrete A and B in its first block row
+    let mut augmented = DMatrix::zeros(3 * n, 3 * n);
+    augmented.view_mut((0, n), (n, n)).fill_with_identity();
+    augmented.view_mut((n, 0), (n, n)).copy_from(&(-&inverse * dense(&properties.hydrostatic)?));
+    augmented.view_mut((n, n), (n, n)).copy_from(&(-&inverse * dense(&properties.linear_damping)?));
+    augmented.view_mut((n, 2 * n), (n, n)).copy_from(&inverse);
+    let exponential = (augmented * dt).exp();
+    Ok((exponential.view((0, 0), (2 * n, 2 * n)).into_owned(), exponential.view((0, 2 * n), (2 * n, n)).into_owned()))
+}
+
+/// Feedback gain K of the discrete regulator minimizing Σ xᵀQx + uᵀRu for x' = Ax + Bu
+///
+/// Solves the discrete algebraic Riccati equation by the structure-preserving
+/// doubling algorithm, which converges quadratically.
+pub fn discrete_lqr(a: &DMatrix<f64>, b: &DMatrix<f64>, q: &DMatrix<f64>, r: &DMatrix<f64>) -> Result<DMatrix<f64>> {
+    let singular = || BEMError::NumericalError { message: "Singular matrix in the Riccati iteration".to_string() };
+    let identity = DMatrix::<f64>::identity(a.nrows(), a.ncols());
+    let r_inverse = r.clone().try_inverse().ok_or_else(singular)?;
+    let (mut ak, mut g, mut h) = (a.clone(), b * r_inverse * b.transpose(), q.clone());
+    for _ in 0..100 {
+        let w = (&identity + &g * &h).try_inverse().ok_or_else(singular)?;
+        let next_h = &h + ak.transpose() * &h * &w * &ak;
+        let next_g = &g + &ak * &w * &g * ak.transpose();
+        ak = &ak * &w * &ak;
+        let change = (&next_h - &h).norm();
+        h = next_h;
+        g = next_g;
+        if change <= 1e-13 * h.norm() {
+            let btp = b.transpose() * &h;
+            let gain = (r + &btp * b).try_inverse().ok_or_else(singular)? * btp * a;
+            return Ok(gain);
+        }
+    }
+    Err(BEMError::NumericalError { message: "Riccati iteration did not converge".to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_domain::*;
+    use wavecore_meshes::{Mesh, Point};
+
+    /// Surge of a 1e6 kg body with 1e5 N s/m damping and no stiffness, pushed by `push`
+    fn station_keeping(control: ControlForces, position: f64, push: f64, steps: usize) -> TimeDomainResults {
+        let one = |v: f64| Matrix::from_vec(1, 1, vec![v]).unwrap();
+        let mut solver = TimeDomainSolver::new(TimeDomainConfig::default());
+        solver.time_params = TimeParameters { dt: 0.5, num_steps: steps, integration_scheme: IntegrationScheme::RungeKutta4, ..Default::default() };
+        let problem = TimeDomainProblem {
+            mesh: Mesh::new(vec![Point::new(0.0, 0.0, -1.0), Point::new(1.0, 0.0, -1.0), Point::new(0.0, 1.0, -1.0)], vec![[0, 1, 2]]).unwrap(),
+            initial_conditions: InitialConditions { positions: vec![position], velocities: vec![0.0], accelerations: vec![0.0] },
+            external_forces: ExternalForces {
+                time_forces: Vec::new(),
+                constant_forces: vec![push],
+                control_forces: Some(control),
+                force_models: Vec::new(),
+            },
+            wave_environment: WaveConditions::default(),
+            body_properties: BodyProperties { mass: one(1e6), hydrostatic: one(0.0), linear_damping: one(1e5), cog: Point::origin() },
+        };
+        solver.solve_time_domain(&problem).unwrap()
+    }
+
+    fn pid(thrust_limits: Vec<f64>) -> ControlForces {
+        ControlForces {
+            controller_type: ControllerType::PID { proportional: vec![4e5], integral: vec![2e4], derivative: vec![2e6] },
+            targets: vec![2.0],
+            thrust_limits,
+        }
+    }
+
+    #[test]
+    fn test_pid_holds_setpoint() {
+        let results = station_keeping(pid(Vec::new()), 0.0, 5e4, 1200);
+        let last = results.time.len() - 1;
+        assert_eq!(results.control_forces[&0].len(), results.time.len());
+        assert!((results.motions[&0][last] - 2.0).abs() < 1e-3, "{}", results.motions[&0][last]);
+        assert!((results.control_forces[&0][last] + 5e4).abs() < 1e-2 * 5e4);
+
+        // Saturated thrust stays within its limit and the integrator recovers
+        let limited = station_keeping(pid(vec![1e5]), -20.0, 5e4, 1200);
+        assert!(limited.control_forces[&0].iter().all(|f| f.abs() <= 1e5));
+        assert!(limited.control_forces[&0].iter().any(|f| f.abs() == 1e5));
+        assert!((limited.motions[&0][last] - 2.0).abs() < 1e-2, "{}", limited.motions[&0][last]);
+
+        let mut unsupported = pid(Vec::new());
+        unsupported.controller_type = ControllerType::MPC;
+        let one = Matrix::from_vec(1, 1, vec![1.0]).unwrap();
+        let properties = BodyProperties { mass: one.clone(), hydrostatic: one.clone(), linear_damping: one.clone(), cog: Point::origin() };
+        assert!(Controller::new(&unsupported, &one, &properties, 0.1).is_err());
+    }
+
+    #[test]
+    fn test_discrete_lqr() {
+        // Scalar x' = a x + b u: P solves b²P² + (r(1 − a²) − q b²) P − q r = 0
+        let (a, b, q, r): (f64, f64, f64, f64) = (1.1, 0.5, 2.0, 3.0);
+        let one = |v: f64| DMatrix::from_element(1, 1, v);
+        let gain = discrete_lqr(&one(a), &one(b), &one(q), &one(r)).unwrap()[(0, 0)];
+        let linear = r * (1.0 - a * a) - q * b * b;
+        let p = (-linear + (linear * linear + 4.0 * b * b * q * r).sqrt()) / (2.0 * b * b);
+        assert!((gain - a * b * p / (r + b * b * p)).abs() < 1e-12, "{}", gain);
+
+        // The regulator returns the body from an offset without overshooting far
+        let control = ControlForces {
+            controller_type: ControllerType::LQR { position_weights: vec![1.0], velocity_weights: vec![1.0], force_weights: vec![1e-10] },
+            targets: vec![0.0],
+            thrust_limits: Vec::new(),
+        };
+        let results = station_keeping(control, 5.0, 0.0, 400);
+        let last = results.time.len() - 1;
+        assert!(results.motions[&0][last].abs() < 1e-3, "{}", results.motions[&0][last]);
+        assert!(results.motions[&0].iter().all(|&x| x > -1.0));
+        assert!(results.control_forces[&0][0] < 0.0);
+    }
+}
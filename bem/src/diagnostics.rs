@@ -0,0 +1,175 @@
+//! Quality diagnostics of a solve
+//!
+//! Identities that hold for the exact solution and are broken by meshing or
+//! assembly errors:
+//! - energy conservation: the damping of a radiating mode equals the energy
+//!   flux of its far field, B_mm = (ρg/4πω) ∫ |H_m|² dθ;
+//! - reciprocity: the added mass and damping matrices are symmetric;
+//! - Haskind: excitation from the diffraction and radiation potentials agree.
+//!
+//! Each check reports a relative error, or `None` when the result does not
+//! contain what it needs (e.g. reciprocity needs every radiation mode).
+
+use serde::{Deserialize, Serialize};
+use wavecore_matrices::Matrix;
+
+/// Default tolerance on each relative error
+pub const DEFAULT_DIAGNOSTIC_TOLERANCE: f64 = 0.05;
+
+/// Relative errors of the solution identities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityDiagnostics {
+    /// |B_mm - B_far| / |B_far| of the radiating mode (indirect formulation)
+    pub energy_error: Option<f64>,
+    /// Damping of the radiating mode from the far-field energy flux
+    pub far_field_damping: Option<f64>,
+    /// Largest |A_ij - A_ji| relative to the largest |A_ij|
+    pub added_mass_asymmetry: Option<f64>,
+    /// Largest |B_ij - B_ji| relative to the largest |B_ij|
+    pub damping_asymmetry: Option<f64>,
+    /// Relative difference of direct and Haskind excitation
+    pub haskind_discrepancy: Option<f64>,
+    /// Tolerance on each relative error
+    pub tolerance: f64,
+}
+
+impl Default for QualityDiagnostics {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIAGNOSTIC_TOLERANCE)
+    }
+}
+
+impl QualityDiagnostics {
+    /// Create diagnostics with no checks performed
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            energy_error: None,
+            far_field_damping: None,
+            added_mass_asymmetry: None,
+            damping_asymmetry: None,
+            haskind_discrepancy: None,
+            tolerance,
+        }
+    }
+
+    /// Compare the near-field damping of a mode with its far-field value
+    pub fn check_energy(&mut self, damping: f64, far_field_damping: f64) {
+        let scale = far_field_damping.abs().max(f64::MIN_POSITIVE);
+        self.far_field_damping = Some(far_field_damping);
+        self.energy_error = Some((damping - far_field_damping).abs() / scale);
+    }
+
+    /// Check symmetry of complete added mass and damping matrices
+    pub fn check_reciprocity(&mut self, added_mass: &Matrix, damping: &Matrix) {
+        self.added_mass_asymmetry = Some(asymmetry(added_mass));
+        self.damping_asymmetry = Some(asymmetry(damping));
+    }
+
+    /// Descriptions of the checks exceeding the tolerance
+    pub fn failures(&self) -> Vec<String> {
+        [
+            ("energy conservation", self.energy_error),
+            ("added mass reciprocity", self.added_mass_asymmetry),
+            ("damping reciprocity", self.damping_asymmetry),
+            ("Haskind relations", self.haskind_discrepancy),
+        ]
+        .into_iter()
+        .filter_map(|(name, error)| match error {
+            Some(e) if e.is_nan() || e > self.tolerance => Some(format!("{}: relative error {:.3e} exceeds {:.3e}", name, e, self.tolerance)),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// Whether every performed check is within tolerance
+    pub fn passed(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Worst value of each check over several diagnostics
+    pub fn worst<'a, I>(diagnostics: I, tolerance: f64) -> Self
+    where
+        I: IntoIterator<Item = &'a QualityDiagnostics>,
+    {
+        let max = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some(if a.is_nan() || b.is_nan() { f64::NAN } else { a.max(b) }),
+            (a, b) => a.or(b),
+        };
+        diagnostics.into_iter().fold(Self::new(tolerance), |acc, d| Self {
+            energy_error: max(acc.energy_error, d.energy_error),
+            far_field_damping: None,
+            added_mass_asymmetry: max(acc.added_mass_asymmetry, d.added_mass_asymmetry),
+            damping_asymmetry: max(acc.damping_asymmetry, d.damping_asymmetry),
+            haskind_discrepancy: max(acc.haskind_discrepancy, d.haskind_discrepancy),
+            tolerance,
+        })
+    }
+}
+
+/// Largest |M_ij - M_ji| relative to the largest |M_ij|
+fn asymmetry(matrix: &Matrix) -> f64 {
+    let n = matrix.rows.min(matrix.cols);
+    let at = |i: usize, j: usize| matrix.data[i * matrix.cols + j];
+    let scale = matrix.data.iter().fold(0.0, |m: f64, v| m.max(v.abs()));
+    let difference = (0..n)
+        .flat_map(|i| (0..i).map(move |j| (i, j)))
+        .fold(0.0, |m: f64, (i, j)| m.max((at(i, j) - at(j, i)).abs()));
+    if scale > 0.0 {
+        difference / scale
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BEMSolver, ProblemType, SolverEngine};
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_reciprocity_and_failures() {
+        let symmetric = Matrix::from_vec(2, 2, vec![2.0, 1.0, 1.0, 3.0]).unwrap();
+        let skewed = Matrix::from_vec(2, 2, vec![2.0, 1.0, 0.5, 3.0]).unwrap();
+
+        let mut diagnostics = QualityDiagnostics::default();
+        assert!(diagnostics.passed());
+        diagnostics.check_reciprocity(&symmetric, &skewed);
+        assert_eq!(diagnostics.added_mass_asymmetry, Some(0.0));
+        assert!((diagnostics.damping_asymmetry.unwrap() - 0.5 / 3.0).abs() < 1e-12);
+        assert_eq!(diagnostics.failures().len(), 1);
+
+        diagnostics.check_energy(1.0, 1.02);
+        assert!(diagnostics.energy_error.unwrap() < diagnostics.tolerance);
+        diagnostics.check_energy(f64::NAN, 1.0);
+        assert!(!diagnostics.passed());
+        assert_eq!(diagnostics.failures().len(), 2);
+
+        let worst = QualityDiagnostics::worst([&QualityDiagnostics::default(), &diagnostics], 0.05);
+        assert_eq!(worst.damping_asymmetry, diagnostics.damping_asymmetry);
+    }
+
+    #[test]
+    fn test_quality_diagnostics() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let heave = solver
+            .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 2 }, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+        let diagnostics = heave.diagnostics();
+        assert!(diagnostics.energy_error.unwrap().is_finite());
+        assert!(diagnostics.far_field_damping.unwrap() > 0.0);
+        // Reciprocity needs every radiation mode
+        assert!(diagnostics.added_mass_asymmetry.is_none());
+
+        let sweep = solver
+            .solve_sweep_bodies(&[0.8, 1.2], &[0.0], vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+        assert_eq!(sweep.diagnostics.len(), 2);
+        for diagnostics in &sweep.diagnostics {
+            assert!(diagnostics.added_mass_asymmetry.unwrap() < diagnostics.tolerance);
+            assert!(diagnostics.damping_asymmetry.unwrap() < diagnostics.tolerance);
+            assert!(diagnostics.haskind_discrepancy.unwrap() < diagnostics.tolerance);
+            assert!(diagnostics.energy_error.is_some());
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Far-field mean drift forces
+//!
+//! The Kochin function H(θ) is the angular amplitude of the waves radiated
+//! and scattered by the body. In deep water, for a source distribution σ and
+//! unit incident amplitude, H(θ) = (ω/g) ∫ σ e^{kζ - ik(ξ cos θ + η sin θ)} dS,
+//! normalized so that the far-field elevation is H(θ) √(k / 2πR) e^{i(kR - π/4)}.
+//!
+//! Momentum conservation over a large control surface (Maruo, Newman) then
+//! gives the mean second-order horizontal force and yaw moment per unit
+//! incident amplitude squared:
+//!
+//! F̄x = -(ρg/2) [ (k/4π) ∫ |H|² cos θ dθ + Re H(β) cos β ]
+//! F̄y = -(ρg/2) [ (k/4π) ∫ |H|² sin θ dθ + Re H(β) sin β ]
+//! M̄z = -(ρg/2k) [ (k/4π) Im ∫ H* H' dθ + Im H'(β) ]
+//!
+//! For a radiation source distribution of unit motion amplitude, the energy
+//! flux through the same surface equals the power absorbed by damping, so
+//! B = (ρg/4πω) ∫ |H|² dθ.
+//!
+//! Wave drift damping is the decrease of the mean drift with a slow body
+//! velocity U in direction α. Aranha's formula relates the drift at small
+//! speed to the zero-speed drift at shifted frequency and heading, with
+//! τ = Uω/g and γ = β - α,
+//!
+//! F̄(ω, β; U) = (1 - 4τ cos γ) F̄⁰(ω (1 + τ cos γ), β - 2τ sin γ),
+//!
+//! so that B = -∂F̄/∂U = (ω/g) [4 cos γ F̄ - ω cos γ ∂F̄/∂ω + 2 sin γ ∂F̄/∂β].
+
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use wavecore_meshes::Point;
+
+/// Number of angles used for the far-field integrals
+pub const DEFAULT_KOCHIN_ANGLES: usize = 180;
+
+/// Mean drift force and moment per unit wave amplitude squared
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeanDriftForce {
+    /// Surge force (N/m²)
+    pub surge: f64,
+    /// Sway force (N/m²)
+    pub sway: f64,
+    /// Yaw moment (N·m/m²)
+    pub yaw: f64,
+}
+
+impl MeanDriftForce {
+    /// Surge, sway and yaw
+    pub fn components(&self) -> [f64; 3] {
+        [self.surge, self.sway, self.yaw]
+    }
+}
+
+/// Wave drift damping per unit wave amplitude squared
+///
+/// Rows are surge force, sway force and yaw moment; columns the slow surge
+/// and sway velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WaveDriftDamping {
+    /// -∂F̄_i/∂U_j at zero speed (N·s/m³, N·s/m² for yaw)
+    pub matrix: [[f64; 2]; 3],
+}
+
+impl WaveDriftDamping {
+    /// Aranha's formula from the zero-speed drift and its frequency and heading derivatives
+    pub fn aranha(
+        frequency: f64,
+        heading: f64,
+        gravity: f64,
+        drift: &MeanDriftForce,
+        d_frequency: &MeanDriftForce,
+        d_heading: &MeanDriftForce,
+    ) -> Self {
+        let (f, df, dh) = (drift.components(), d_frequency.components(), d_heading.components());
+        let mut matrix = [[0.0; 2]; 3];
+        for (j, alpha) in [0.0, std::f64::consts::FRAC_PI_2].into_iter().enumerate() {
+            let (s, c) = (heading - alpha).sin_cos();
+            for i in 0..3 {
+                matrix[i][j] = frequency / gravity * (4.0 * c * f[i] - frequency * c * df[i] + 2.0 * s * dh[i]);
+            }
+        }
+        Self { matrix }
+    }
+
+    /// Central differences of drift forces computed at slow velocities ∓`step`
+    /// along x (`surge`) and along y (`sway`)
+    pub fn finite_difference(step: f64, surge: [&MeanDriftForce; 2], sway: [&MeanDriftForce; 2]) -> Self {
+        let mut matrix = [[0.0; 2]; 3];
+        for (j, [minus, plus]) in [surge, sway].into_iter().enumerate() {
+            let (minus, plus) = (minus.components(), plus.components());
+            for i in 0..3 {
+                matrix[i][j] = -(plus[i] - minus[i]) / (2.0 * step);
+            }
+        }
+        Self { matrix }
+    }
+
+    /// Drift force at the slow horizontal velocity `velocity`, linearized about zero speed
+    pub fn drift_at(&self, drift: &MeanDriftForce, velocity: [f64; 2]) -> MeanDriftForce {
+        let force = |i: usize, value: f64| value - self.matrix[i][0] * velocity[0] - self.matrix[i][1] * velocity[1];
+        MeanDriftForce {
+            surge: force(0, drift.surge),
+            sway: force(1, drift.sway),
+            yaw: force(2, drift.yaw),
+        }
+    }
+}
+
+/// Kochin function of a source distribution
+#[derive(Debug, Clone)]
+pub struct KochinFunction {
+    /// Wave number (rad/m)
+    pub wave_number: f64,
+    /// Wave frequency (rad/s)
+    pub frequency: f64,
+    /// Gravitational acceleration (m/s²)
+    pub gravity: f64,
+    sources: Vec<(Point, Complex64)>,
+}
+
+impl KochinFunction {
+    /// Build from source points, strengths and integration weights (areas)
+    pub fn from_sources(points: &[Point], strengths: &[Complex64], weights: &[f64], frequency: f64, gravity: f64) -> Self {
+        let sources = points
+            .iter()
+            .zip(strengths.iter().zip(weights))
+            .map(|(p, (s, w))| (*p, s * w))
+            .collect();
+        Self {
+            wave_number: frequency * frequency / gravity,
+            frequency,
+            gravity,
+            sources,
+        }
+    }
+
+    /// H(θ)
+    pub fn evaluate(&self, theta: f64) -> Complex64 {
+        self.sum(theta, |_| Complex64::new(1.0, 0.0))
+    }
+
+    /// dH/dθ
+    pub fn derivative(&self, theta: f64) -> Complex64 {
+        let k = self.wave_number;
+        let (s, c) = theta.sin_cos();
+        self.sum(theta, |p| Complex64::new(0.0, -k * (-p.x * s + p.y * c)))
+    }
+
+    fn sum<F: Fn(&Point) -> Complex64>(&self, theta: f64, factor: F) -> Complex64 {
+        let k = self.wave_number;
+        let (s, c) = theta.sin_cos();
+        let total: Complex64 = self
+            .sources
+            .iter()
+            .map(|(p, q)| {
+                let phase = Complex64::new(k * p.z, -k * (p.x * c + p.y * s));
+                q * phase.exp() * factor(p)
+            })
+            .sum();
+        total * self.frequency / self.gravity
+    }
+
+    /// Radiation damping implied by the radiated energy flux
+    pub fn radiated_damping(&self, density: f64, n_angles: usize) -> f64 {
+        let n = n_angles.max(8);
+        let dtheta = 2.0 * std::f64::consts::PI / n as f64;
+        let integral: f64 = (0..n).map(|i| self.evaluate(i as f64 * dtheta).norm_sqr()).sum::<f64>() * dtheta;
+        density * self.gravity / (4.0 * std::f64::consts::PI * self.frequency) * integral
+    }
+
+    /// Mean drift force for incident heading `heading` (radians)
+    pub fn mean_drift(&self, heading: f64, density: f64, n_angles: usize) -> MeanDriftForce {
+        let k = self.wave_number;
+        let n = n_angles.max(8);
+        let dtheta = 2.0 * std::f64::consts::PI / n as f64;
+
+        // Trapezoidal rule is spectrally accurate for periodic integrands
+        let (mut ix, mut iy, mut iz) = (0.0, 0.0, 0.0);
+        for i in 0..n {
+            let theta = i as f64 * dtheta;
+            let h = self.evaluate(theta);
+            let power = h.norm_sqr();
+            ix += power * theta.cos();
+            iy += power * theta.sin();
+            iz += (h.conj() * self.derivative(theta)).im;
+        }
+        let scale = k / (4.0 * std::f64::consts::PI) * dtheta;
+
+        let h_beta = self.evaluate(heading);
+        let dh_beta = self.derivative(heading);
+        let rho_g = density * self.gravity;
+
+        MeanDriftForce {
+            surge: -0.5 * rho_g * (scale * ix + h_beta.re * heading.cos()),
+            sway: -0.5 * rho_g * (scale * iy + h_beta.re * heading.sin()),
+            yaw: -0.5 * rho_g / k * (scale * iz + dh_beta.im),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetric_sources_have_no_sway_or_yaw() {
+        // Ring of equal sources about the z axis
+        let points: Vec<Point> = (0..12)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::PI / 6.0;
+                Point::new(a.cos(), a.sin(), -1.0)
+            })
+            .collect();
+        let strengths = vec![Complex64::new(0.3, -0.1); 12];
+        let kochin = KochinFunction::from_sources(&points, &strengths, &[1.0; 12], 1.0, 9.81);
+
+        // Kochin derivative matches finite differences
+        let (t, h) = (0.7, 1e-6);
+        let fd = (kochin.evaluate(t + h) - kochin.evaluate(t - h)) / (2.0 * h);
+        assert!((fd - kochin.derivative(t)).norm() < 1e-6);
+
+        let drift = kochin.mean_drift(0.0, 1025.0, DEFAULT_KOCHIN_ANGLES);
+        assert!(drift.surge.is_finite());
+        assert!(drift.sway.abs() < 1e-6 * (1.0 + drift.surge.abs()));
+        assert!(drift.yaw.abs() < 1e-6 * (1.0 + drift.surge.abs()));
+    }
+
+    #[test]
+    fn test_aranha_matches_speed_differences() {
+        // Smooth synthetic zero-speed drift F̄⁰(ω, β)
+        let zero_speed = |omega: f64, beta: f64| MeanDriftForce {
+            surge: -1e3 * omega * omega * beta.cos(),
+            sway: -1e3 * omega * beta.sin(),
+            yaw: 2e3 * (2.0 * beta).sin() / omega,
+        };
+        let (omega, beta, g, h) = (0.8, 2.5, 9.81, 1e-6);
+        let at_speed = |u: f64, alpha: f64| {
+            let (s, c) = (beta - alpha).sin_cos();
+            let tau = u * omega / g;
+            let base = zero_speed(omega * (1.0 + tau * c), beta - 2.0 * tau * s).components();
+            let scale = 1.0 - 4.0 * tau * c;
+            MeanDriftForce { surge: scale * base[0], sway: scale * base[1], yaw: scale * base[2] }
+        };
+        let d_frequency = MeanDriftForce {
+            surge: -2e3 * omega * beta.cos(),
+            sway: -1e3 * beta.sin(),
+            yaw: -2e3 * (2.0 * beta).sin() / (omega * omega),
+        };
+        let d_heading = MeanDriftForce {
+            surge: 1e3 * omega * omega * beta.sin(),
+            sway: -1e3 * omega * beta.cos(),
+            yaw: 4e3 * (2.0 * beta).cos() / omega,
+        };
+        let aranha = WaveDriftDamping::aranha(omega, beta, g, &zero_speed(omega, beta), &d_frequency, &d_heading);
+        let differences = WaveDriftDamping::finite_difference(
+            h,
+            [&at_speed(-h, 0.0), &at_speed(h, 0.0)],
+            [&at_speed(-h, std::f64::consts::FRAC_PI_2), &at_speed(h, std::f64::consts::FRAC_PI_2)],
+        );
+        for (a, d) in aranha.matrix.iter().flatten().zip(differences.matrix.iter().flatten()) {
+            assert!((a - d).abs() < 1e-4 * (1.0 + a.abs()), "{} vs {}", a, d);
+        }
+
+        // Head seas push the body aft; the drift grows with forward speed, so the surge damping is positive
+        let pi = std::f64::consts::PI;
+        let flat = MeanDriftForce { surge: 0.0, sway: 0.0, yaw: 0.0 };
+        let head_drift = MeanDriftForce { surge: -1e3, sway: 0.0, yaw: 0.0 };
+        let head = WaveDriftDamping::aranha(omega, pi, g, &head_drift, &flat, &flat);
+        assert!(head.matrix[0][0] > 0.0);
+        let slowed = head.drift_at(&head_drift, [0.5, 0.0]);
+        assert!(slowed.surge < head_drift.surge);
+    }
+}
@@ -10,11 +10,18 @@ pub trait BEMEngine {
 }
 
 /// Standard BEM engine implementation
+#[allow(dead_code)]
 pub struct StandardBEMEngine {
     tolerance: f64,
     max_iterations: usize,
 }
 
+impl Default for StandardBEMEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StandardBEMEngine {
     pub fn new() -> Self {
         Self {
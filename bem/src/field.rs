@@ -0,0 +1,358 @@
+//! Field-point evaluation of a solved problem
+//!
+//! The solved boundary distribution is propagated to arbitrary points with
+//! the same Green function as the solve:
+//! - indirect formulation: φ(x) = Σ σ_j G(x, ξ_j) w_j;
+//! - direct formulation: φ(x) = Σ (∂φ/∂n_j G(x, ξ_j) - φ_j ∂G/∂n_ξ) w_j.
+//!
+//! Diffraction results include the incident wave, so the returned field is
+//! the total one. Pressure is the linear dynamic pressure p = iωρφ and the
+//! free-surface elevation η = iω/g φ(z = 0), both for the e^{-iωt} time
+//! dependence used throughout the solver.
+
+use super::*;
+use crate::solver::{green_normal_derivative, green_source_gradient, green_value, BEMResult};
+use nalgebra::Point3;
+use num_complex::Complex64;
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use wavecore_green_functions::{GreenFunction, GreenFunctionParams};
+use wavecore_meshes::{Point, Vector};
+
+/// Finite-difference step for velocities of the direct formulation (m)
+const DIFFERENCE_STEP: f64 = 1e-4;
+
+/// Boundary data of a solve, kept for evaluation off the body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryDistribution {
+    /// Green function parameters of the solve
+    pub green_params: GreenFunctionParams,
+    /// Collocation points
+    pub positions: Vec<Point>,
+    /// Unit normals at the collocation points
+    pub normals: Vec<Vector>,
+    /// Quadrature weights of the collocation points
+    pub weights: Vec<f64>,
+    /// Prescribed normal velocity ∂φ/∂n
+    pub normal_velocity: Vec<Complex64>,
+    /// Incident wave heading (diffraction problems)
+    pub incident_heading: Option<f64>,
+}
+
+/// Flow quantities at field points
+#[derive(Debug, Clone)]
+pub struct FieldData {
+    /// Evaluation points
+    pub points: Vec<Point>,
+    /// Complex velocity potential
+    pub potential: Vec<Complex64>,
+    /// Complex dynamic pressure iωρφ
+    pub pressure: Vec<Complex64>,
+    /// Complex fluid velocity ∇φ
+    pub velocity: Vec<[Complex64; 3]>,
+}
+
+impl FieldData {
+    /// Number of evaluation points
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Check if there are no evaluation points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+/// Horizontal grid on the mean free surface z = 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeSurfaceGrid {
+    /// x coordinates (m)
+    pub x: Vec<f64>,
+    /// y coordinates (m)
+    pub y: Vec<f64>,
+}
+
+impl FreeSurfaceGrid {
+    /// Create a grid from its coordinate lines
+    pub fn new(x: Vec<f64>, y: Vec<f64>) -> Self {
+        Self { x, y }
+    }
+
+    /// Evenly spaced grid over [x_min, x_max] × [y_min, y_max]
+    pub fn regular(x_range: (f64, f64), y_range: (f64, f64), nx: usize, ny: usize) -> Self {
+        let line = |(min, max): (f64, f64), n: usize| -> Vec<f64> {
+            match n {
+                0 => Vec::new(),
+                1 => vec![0.5 * (min + max)],
+                _ => (0..n).map(|i| min + (max - min) * i as f64 / (n - 1) as f64).collect(),
+            }
+        };
+        Self::new(line(x_range, nx), line(y_range, ny))
+    }
+
+    /// Number of grid points
+    pub fn len(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+
+    /// Check if the grid has no points
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Grid points, x varying fastest
+    pub fn points(&self) -> Vec<Point> {
+        self.y
+            .iter()
+            .flat_map(|&y| self.x.iter().map(move |&x| Point::new(x, y, 0.0)))
+            .collect()
+    }
+}
+
+/// Complex free-surface elevation amplitude over a grid
+#[derive(Debug, Clone)]
+pub struct FreeSurfaceElevation {
+    /// Wave frequency (rad/s)
+    pub frequency: f64,
+    /// Evaluation grid
+    pub grid: FreeSurfaceGrid,
+    /// Complex elevation at each grid point, x varying fastest
+    pub elevation: Vec<Complex64>,
+}
+
+impl FreeSurfaceElevation {
+    /// Add `factor` times another elevation on the same grid and frequency
+    ///
+    /// Used to combine diffraction with radiation elevations weighted by the
+    /// body motion amplitudes.
+    pub fn add_scaled(&mut self, other: &FreeSurfaceElevation, factor: Complex64) -> Result<()> {
+        if other.grid != self.grid || (other.frequency - self.frequency).abs() > 1e-12 * self.frequency.abs() {
+            return Err(BEMError::InvalidProblem {
+                message: "Free-surface elevations must share grid and frequency".to_string(),
+            });
+        }
+        for (eta, other) in self.elevation.iter_mut().zip(&other.elevation) {
+            *eta += factor * other;
+        }
+        Ok(())
+    }
+
+    /// Real elevation Re(η e^{-iωt}) at time `t`
+    pub fn at_time(&self, t: f64) -> Vec<f64> {
+        let phase = Complex64::new(0.0, -self.frequency * t).exp();
+        self.elevation.iter().map(|eta| (eta * phase).re).collect()
+    }
+
+    /// Largest elevation amplitude
+    pub fn max_amplitude(&self) -> f64 {
+        self.elevation.iter().map(|eta| eta.norm()).fold(0.0, f64::max)
+    }
+}
+
+/// Complex wave elevation amplitude over `grid` from a radiation or diffraction result
+///
+/// Radiation elevations are per unit motion amplitude; combine them with the
+/// diffraction elevation through `FreeSurfaceElevation::add_scaled`.
+pub fn free_surface_elevation(grid: &FreeSurfaceGrid, result: &BEMResult) -> Result<FreeSurfaceElevation> {
+    let boundary = result.boundary()?;
+    let green_function = GreenFunction::new(boundary.green_params.clone())?;
+    let omega = boundary.green_params.frequency;
+    let g = boundary.green_params.gravity;
+
+    let elevation = grid
+        .points()
+        .par_iter()
+        .map(|x| Complex64::new(0.0, omega / g) * result.potential_at(boundary, &green_function, x))
+        .collect();
+
+    Ok(FreeSurfaceElevation {
+        frequency: omega,
+        grid: grid.clone(),
+        elevation,
+    })
+}
+
+impl BEMResult {
+    /// Evaluate potential, dynamic pressure and velocity at field points
+    pub fn evaluate_field(&self, points: &[Point3<f64>]) -> Result<FieldData> {
+        let boundary = self.boundary()?;
+        let green_function = GreenFunction::new(boundary.green_params.clone())?;
+        let omega = boundary.green_params.frequency;
+        let rho = 1025.0;
+
+        let evaluate = |x: &Point| -> (Complex64, [Complex64; 3]) {
+            let (mut phi, mut velocity) = match &self.source_strength {
+                Some(sigma) => {
+                    let mut phi = Complex64::new(0.0, 0.0);
+                    let mut velocity = [Complex64::new(0.0, 0.0); 3];
+                    for ((xi, w), s) in boundary.positions.iter().zip(&boundary.weights).zip(sigma) {
+                        let strength = s * w;
+                        phi += strength * green_value(&green_function, x, xi);
+                        // Gradient with respect to the field point
+                        let gradient = green_source_gradient(&green_function, x, xi);
+                        for (v, g) in velocity.iter_mut().zip(gradient) {
+                            *v -= strength * g;
+                        }
+                    }
+                    (phi, velocity)
+                }
+                None => {
+                    let representation = |x: &Point| self.green_representation(boundary, &green_function, x);
+                    let h = DIFFERENCE_STEP;
+                    let mut velocity = [Complex64::new(0.0, 0.0); 3];
+                    for (d, v) in velocity.iter_mut().enumerate() {
+                        let mut step = Vector::zeros();
+                        step[d] = h;
+                        *v = (representation(&(x + step)) - representation(&(x - step))) / (2.0 * h);
+                    }
+                    (representation(x), velocity)
+                }
+            };
+
+            if let Some(heading) = boundary.incident_heading {
+                let k = omega * omega / boundary.green_params.gravity;
+                let (cos_b, sin_b) = (heading.cos(), heading.sin());
+                let incident = incident_potential(omega, boundary.green_params.gravity, heading, x);
+                phi += incident;
+                velocity[0] += incident * Complex64::new(0.0, k * cos_b);
+                velocity[1] += incident * Complex64::new(0.0, k * sin_b);
+                velocity[2] += incident * k;
+            }
+            (phi, velocity)
+        };
+
+        let values: Vec<(Complex64, [Complex64; 3])> = points.par_iter().map(evaluate).collect();
+        let (potential, velocity): (Vec<Complex64>, Vec<[Complex64; 3]>) = values.into_iter().unzip();
+        let pressure = potential.iter().map(|phi| Complex64::new(0.0, omega * rho) * phi).collect();
+
+        Ok(FieldData {
+            points: points.to_vec(),
+            potential,
+            pressure,
+            velocity,
+        })
+    }
+
+    /// Boundary distribution, or an error for results without one
+    fn boundary(&self) -> Result<&BoundaryDistribution> {
+        self.boundary.as_ref().ok_or_else(|| BEMError::InvalidProblem {
+            message: "Result carries no boundary distribution to evaluate".to_string(),
+        })
+    }
+
+    /// Total potential at `x`
+    fn potential_at(&self, boundary: &BoundaryDistribution, green_function: &GreenFunction, x: &Point) -> Complex64 {
+        let scattered = match &self.source_strength {
+            Some(sigma) => boundary
+                .positions
+                .iter()
+                .zip(&boundary.weights)
+                .zip(sigma)
+                .map(|((xi, w), s)| s * *w * green_value(green_function, x, xi))
+                .sum(),
+            None => self.green_representation(boundary, green_function, x),
+        };
+        match boundary.incident_heading {
+            Some(heading) => scattered + incident_potential(boundary.green_params.frequency, boundary.green_params.gravity, heading, x),
+            None => scattered,
+        }
+    }
+
+    /// Green's representation of the direct-formulation potential at `x`
+    fn green_representation(&self, boundary: &BoundaryDistribution, green_function: &GreenFunction, x: &Point) -> Complex64 {
+        boundary
+            .positions
+            .iter()
+            .zip(&boundary.normals)
+            .zip(&boundary.weights)
+            .zip(self.potential.iter().zip(&boundary.normal_velocity))
+            .map(|(((xi, n), w), (phi, q))| {
+                let single = q * green_value(green_function, x, xi);
+                let double = phi * green_normal_derivative(green_function, x, xi, n, true);
+                (single - double) * *w
+            })
+            .sum()
+    }
+}
+
+/// Unit-amplitude incident potential φ_I = -i g/ω e^{kz + ik(x cos β + y sin β)}
+pub(crate) fn incident_potential(omega: f64, g: f64, heading: f64, x: &Point) -> Complex64 {
+    let k = omega * omega / g;
+    Complex64::new(0.0, -g / omega) * Complex64::new(k * x.z, k * (x.x * heading.cos() + x.y * heading.sin())).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_field_evaluation_is_consistent() {
+        let frequency = 1.0;
+        let points = [nalgebra::Point3::new(0.5, 0.3, -3.0), nalgebra::Point3::new(4.0, -2.0, -1.0)];
+        let h = 1e-4;
+
+        for problem in [
+            ProblemType::Radiation { frequency, mode: 2 },
+            ProblemType::Diffraction { frequency, direction: 0.3 },
+        ] {
+            let result = BEMSolver::new(SolverEngine::Standard)
+                .solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)])
+                .unwrap();
+            let field = result.evaluate_field(&points).unwrap();
+            assert_eq!(field.len(), 2);
+
+            for (i, x) in points.iter().enumerate() {
+                let phi = field.potential[i];
+                assert!(phi.is_finite() && phi.norm() > 0.0);
+                assert!((field.pressure[i] - num_complex::Complex64::new(0.0, 1025.0 * frequency) * phi).norm() < 1e-9 * field.pressure[i].norm());
+
+                // Velocity is the gradient of the potential
+                for d in 0..3 {
+                    let mut step = nalgebra::Vector3::zeros();
+                    step[d] = h;
+                    let shifted = result.evaluate_field(&[x + step, x - step]).unwrap();
+                    let difference = (shifted.potential[0] - shifted.potential[1]) / (2.0 * h);
+                    assert!((field.velocity[i][d] - difference).norm() < 1e-4 * (1.0 + difference.norm()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_free_surface_elevation() {
+        let grid = FreeSurfaceGrid::regular((-6.0, 6.0), (-6.0, 6.0), 5, 4);
+        assert_eq!(grid.points().len(), 20);
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let body = || vec![tetrahedron_body("a", 0.0)];
+
+        let diffraction = solver
+            .solve_bodies(&ProblemType::Diffraction { frequency: 1.0, direction: 0.0 }, body())
+            .unwrap();
+        let heave = solver
+            .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 2 }, body())
+            .unwrap();
+        let mut eta = free_surface_elevation(&grid, &diffraction).unwrap();
+        let eta_heave = free_surface_elevation(&grid, &heave).unwrap();
+
+        // Elevation is iω/g times the field potential on z = 0
+        let field = diffraction.evaluate_field(&grid.points()).unwrap();
+        for (e, phi) in eta.elevation.iter().zip(&field.potential) {
+            assert!((e - num_complex::Complex64::new(0.0, 1.0 / 9.81) * phi).norm() < 1e-9 * (1.0 + e.norm()));
+        }
+
+        // Total elevation: diffraction plus heave response
+        let before = eta.elevation.clone();
+        let motion = num_complex::Complex64::new(0.3, -0.1);
+        eta.add_scaled(&eta_heave, motion).unwrap();
+        for ((total, d), r) in eta.elevation.iter().zip(&before).zip(&eta_heave.elevation) {
+            assert!((total - d - motion * r).norm() < 1e-12);
+        }
+        assert_eq!(eta.at_time(0.0).len(), 20);
+        assert!(eta.max_amplitude() > 0.0);
+
+        let other_grid = FreeSurfaceGrid::regular((0.0, 1.0), (0.0, 1.0), 2, 2);
+        assert!(eta.add_scaled(&free_surface_elevation(&other_grid, &heave).unwrap(), motion).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Bodies and stores shared by the unit tests
+
+use super::*;
+
+/// Submerged tetrahedron of four panels, centred on x = `x0`
+pub(crate) fn tetrahedron_body(name: &str, x0: f64) -> wavecore_bodies::FloatingBody {
+    use nalgebra::Point3;
+    let vertices = vec![
+        Point3::new(x0 - 1.0, -1.0, -2.0),
+        Point3::new(x0 + 1.0, -1.0, -2.0),
+        Point3::new(x0, 1.0, -2.0),
+        Point3::new(x0, 0.0, -0.5),
+    ];
+    let faces = vec![[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]];
+    let mesh = wavecore_meshes::Mesh::new(vertices, faces).unwrap();
+    let mass_props = wavecore_bodies::MassProperties {
+        mass: 1000.0,
+        center_of_gravity: [x0, 0.0, -1.0],
+        inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+    wavecore_bodies::FloatingBody::with_mesh(name.to_string(), mass_props, mesh).unwrap()
+}
+
+/// Submerged octahedron symmetric about both vertical planes
+pub(crate) fn octahedron_body() -> wavecore_bodies::FloatingBody {
+    use nalgebra::Point3;
+    let vertices = vec![
+        Point3::new(1.0, 0.0, -2.0),
+        Point3::new(0.0, 1.0, -2.0),
+        Point3::new(-1.0, 0.0, -2.0),
+        Point3::new(0.0, -1.0, -2.0),
+        Point3::new(0.0, 0.0, -1.0),
+        Point3::new(0.0, 0.0, -3.0),
+    ];
+    let faces = vec![
+        [0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4],
+        [1, 0, 5], [2, 1, 5], [3, 2, 5], [0, 3, 5],
+    ];
+    let mesh = wavecore_meshes::Mesh::new(vertices, faces).unwrap();
+    let mass_props = wavecore_bodies::MassProperties {
+        mass: 1000.0,
+        center_of_gravity: [0.0, 0.0, -2.0],
+        inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+    wavecore_bodies::FloatingBody::with_mesh("octahedron".to_string(), mass_props, mesh).unwrap()
+}
+
+/// Checkpoint store kept in memory, counting saves
+#[derive(Default)]
+pub(crate) struct MemoryStore {
+    pub(crate) checkpoint: std::sync::Mutex<Option<SweepCheckpoint>>,
+    pub(crate) saves: std::sync::atomic::AtomicUsize,
+}
+
+impl CheckpointStore for MemoryStore {
+    fn save(&self, checkpoint: &SweepCheckpoint) -> Result<()> {
+        self.saves.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        *self.checkpoint.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<SweepCheckpoint>> {
+        Ok(self.checkpoint.lock().unwrap().clone())
+    }
+}
@@ -0,0 +1,391 @@
+//! Pluggable external force models for time-domain simulations
+//!
+//! A [`ForceModel`] maps the time, positions and velocities of the rigid-body
+//! modes to generalized forces and is evaluated at every stage of the time
+//! integration. Built-in models cover station keeping: quasi-static elastic
+//! catenary mooring lines resting partly on a frictionless seabed, and
+//! linear fenders against a fixed plane. Forces are about the displaced
+//! centre of gravity, in the order surge, sway, heave, roll, pitch, yaw.
+
+use super::*;
+use crate::froude_krylov::rigid_motion;
+use nalgebra::Vector3;
+use wavecore_meshes::{Point, Vector};
+
+/// Force model evaluated at each integration stage
+pub trait ForceModel: std::fmt::Debug + Send + Sync {
+    /// Generalized forces for every DOF of `positions` at time `time`
+    fn forces(&self, time: f64, positions: &[f64], velocities: &[f64]) -> Result<Vec<f64>>;
+}
+
+/// Uniform mooring line between a seabed anchor and a fairlead on the body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MooringLine {
+    /// Anchor position (m)
+    pub anchor: Point,
+    /// Fairlead position on the body in the reference pose (m)
+    pub fairlead: Point,
+    /// Unstretched length (m)
+    pub length: f64,
+    /// Submerged weight per unit length (N/m)
+    pub weight: f64,
+    /// Axial stiffness EA (N); infinite for an inextensible line
+    pub axial_stiffness: f64,
+}
+
+impl MooringLine {
+    /// Horizontal and vertical tension (H, V) at the fairlead for a horizontal
+    /// `span` and a fairlead `height` above the anchor
+    ///
+    /// Solves the elastic catenary equations by damped Newton iteration, with
+    /// the line resting on the seabed while V < wL and fully suspended beyond.
+    pub fn tension(&self, span: f64, height: f64) -> Result<(f64, f64)> {
+        let (w, l, ea) = (self.weight, self.length, self.axial_stiffness);
+        if !(span >= 0.0 && height > 0.0) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Fairlead span {} and height {} above the anchor are invalid", span, height),
+            });
+        }
+        let residual = |h: f64, v: f64| -> [f64; 2] {
+            let a = h / w;
+            if v < w * l {
+                [
+                    l - v / w + a * (v / h).asinh() + h * l / ea - span,
+                    a * ((1.0 + (v / h).powi(2)).sqrt() - 1.0) + v * v / (2.0 * ea * w) - height,
+                ]
+            } else {
+                let lower = (v - w * l) / h;
+                [
+                    a * ((v / h).asinh() - lower.asinh()) + h * l / ea - span,
+                    a * ((1.0 + (v / h).powi(2)).sqrt() - (1.0 + lower * lower).sqrt()) + (v * l - 0.5 * w * l * l) / ea - height,
+                ]
+            }
+        };
+        let norm = |r: [f64; 2]| r[0].hypot(r[1]);
+
+        // Starting point of Peyrot and Goulois
+        let lambda = if span < 1e-9 * l {
+            1e6
+        } else if l * l > span * span + height * height {
+            (3.0 * ((l * l - height * height) / (span * span) - 1.0)).sqrt()
+        } else {
+            0.2
+        };
+        let mut h = (0.5 * w * span / lambda).max(1e-6 * w * l);
+        let mut v = 0.5 * w * (height / lambda.tanh() + l);
+
+        let tolerance = 1e-10 * l.max(1.0);
+        let mut r = residual(h, v);
+        for _ in 0..200 {
+            if norm(r) < tolerance {
+                return Ok((h, v));
+            }
+            let (dh, dv) = (1e-7 * h, 1e-7 * v.abs().max(w * l));
+            let (rh, rv) = (residual(h + dh, v), residual(h, v + dv));
+            let jacobian = [[(rh[0] - r[0]) / dh, (rv[0] - r[0]) / dv], [(rh[1] - r[1]) / dh, (rv[1] - r[1]) / dv]];
+            let determinant = jacobian[0][0] * jacobian[1][1] - jacobian[0][1] * jacobian[1][0];
+            if determinant == 0.0 || !determinant.is_finite() {
+                break;
+            }
+            let step_h = (jacobian[1][1] * r[0] - jacobian[0][1] * r[1]) / determinant;
+            let step_v = (jacobian[0][0] * r[1] - jacobian[1][0] * r[0]) / determinant;
+
+            // Backtrack to keep H positive and reduce the residual
+            let mut scale = 1.0;
+            loop {
+                let (next_h, next_v) = (h - scale * step_h, v - scale * step_v);
+                if next_h > 0.0 {
+                    let next = residual(next_h, next_v);
+                    if norm(next) < norm(r) || scale < 1e-4 {
+                        h = next_h;
+                        v = next_v;
+                        r = next;
+                        break;
+                    }
+                }
+                scale *= 0.5;
+                if scale < 1e-12 {
+                    break;
+                }
+            }
+        }
+        if norm(r) < tolerance {
+            return Ok((h, v));
+        }
+        Err(BEMError::NumericalError {
+            message: format!("Catenary of length {} m did not converge for span {} m and height {} m", l, span, height),
+        })
+    }
+}
+
+/// Quasi-static catenary mooring of one body
+///
+/// Forces are reported relative to the pretension in the reference pose,
+/// which the ballast of the body is taken to balance.
+#[derive(Debug, Clone)]
+pub struct CatenaryMooring {
+    /// Mooring lines
+    pub lines: Vec<MooringLine>,
+    /// Centre of gravity in the reference pose
+    cog: Point,
+    /// Mooring forces of the reference pose
+    reference: [f64; 6],
+}
+
+impl CatenaryMooring {
+    /// Mooring of a body with centre of gravity `cog`
+    pub fn new(lines: Vec<MooringLine>, cog: Point) -> Result<Self> {
+        for line in &lines {
+            if !(line.length > 0.0 && line.weight > 0.0 && line.axial_stiffness > 0.0) {
+                return Err(BEMError::InvalidProblem {
+                    message: format!(
+                        "Mooring line length {}, weight {} and axial stiffness {} must be positive",
+                        line.length, line.weight, line.axial_stiffness
+                    ),
+                });
+            }
+        }
+        let mut mooring = Self { lines, cog, reference: [0.0; 6] };
+        mooring.reference = mooring.total_forces(&[0.0; 6])?;
+        Ok(mooring)
+    }
+
+    /// Fairlead tensions (H, V) of every line in `pose`
+    pub fn tensions(&self, pose: &[f64]) -> Result<Vec<(f64, f64)>> {
+        let (rotation, translation) = rigid_motion(pose);
+        let cog = self.cog + translation;
+        self.lines
+            .iter()
+            .map(|line| {
+                let fairlead = cog + rotation * (line.fairlead - self.cog);
+                let span = (line.anchor.xy() - fairlead.xy()).norm();
+                line.tension(span, fairlead.z - line.anchor.z)
+            })
+            .collect()
+    }
+
+    /// Forces of all lines on the body in `pose`, including the pretension
+    pub fn total_forces(&self, pose: &[f64]) -> Result<[f64; 6]> {
+        let (rotation, translation) = rigid_motion(pose);
+        let cog = self.cog + translation;
+        let mut forces = [0.0; 6];
+        for (line, (h, v)) in self.lines.iter().zip(self.tensions(pose)?) {
+            let fairlead = cog + rotation * (line.fairlead - self.cog);
+            let horizontal = line.anchor.xy() - fairlead.xy();
+            let direction = if horizontal.norm() > 0.0 { horizontal.normalize() } else { horizontal };
+            let force = Vector3::new(h * direction.x, h * direction.y, -v);
+            add_point_force(&mut forces, force, fairlead, cog);
+        }
+        Ok(forces)
+    }
+}
+
+impl ForceModel for CatenaryMooring {
+    fn forces(&self, _time: f64, positions: &[f64], _velocities: &[f64]) -> Result<Vec<f64>> {
+        let total = self.total_forces(positions)?;
+        Ok((0..positions.len()).map(|i| if i < 6 { total[i] - self.reference[i] } else { 0.0 }).collect())
+    }
+}
+
+/// Linear fender between a point on the body and a fixed plane
+///
+/// Carries compression only: k δ + c δ̇ along the plane normal while the
+/// contact point penetrates the plane by δ > 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearFender {
+    /// Contact point on the body in the reference pose
+    pub contact: Point,
+    /// Point on the fixed plane
+    pub plane_point: Point,
+    /// Unit normal of the plane, pointing from the plane towards the body
+    pub normal: Vector,
+    /// Stiffness (N/m)
+    pub stiffness: f64,
+    /// Damping (N s/m)
+    pub damping: f64,
+    /// Centre of gravity of the body in the reference pose
+    pub cog: Point,
+}
+
+impl LinearFender {
+    /// Create a fender; the normal is normalized
+    pub fn new(contact: Point, plane_point: Point, normal: Vector, stiffness: f64, damping: f64, cog: Point) -> Result<Self> {
+        if normal.norm() == 0.0 || stiffness < 0.0 || damping < 0.0 {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Fender needs a plane normal and non-negative stiffness {} and damping {}", stiffness, damping),
+            });
+        }
+        Ok(Self { contact, plane_point, normal: normal.normalize(), stiffness, damping, cog })
+    }
+
+    /// Penetration of the contact point into the plane in `pose`; negative when clear
+    pub fn penetration(&self, pose: &[f64]) -> f64 {
+        let (rotation, translation) = rigid_motion(pose);
+        let point = self.cog + translation + rotation * (self.contact - self.cog);
+        -(point - self.plane_point).dot(&self.normal)
+    }
+}
+
+impl ForceModel for LinearFender {
+    fn forces(&self, _time: f64, positions: &[f64], velocities: &[f64]) -> Result<Vec<f64>> {
+        let mut forces = [0.0; 6];
+        let penetration = self.penetration(positions);
+        if penetration > 0.0 {
+            let (rotation, translation) = rigid_motion(positions);
+            let cog = self.cog + translation;
+            let point = cog + rotation * (self.contact - self.cog);
+            let rate = |i: usize| velocities.get(i).copied().unwrap_or(0.0);
+            let velocity = Vector3::new(rate(0), rate(1), rate(2)) + Vector3::new(rate(3), rate(4), rate(5)).cross(&(point - cog));
+            let magnitude = (self.stiffness * penetration - self.damping * velocity.dot(&self.normal)).max(0.0);
+            add_point_force(&mut forces, magnitude * self.normal, point, cog);
+        }
+        Ok((0..positions.len()).map(|i| if i < 6 { forces[i] } else { 0.0 }).collect())
+    }
+}
+
+/// Add a force applied at `point` and its moment about `cog`
+fn add_point_force(forces: &mut [f64; 6], force: Vector3<f64>, point: Point, cog: Point) {
+    let moment = (point - cog).cross(&force);
+    for (k, value) in force.iter().chain(moment.iter()).enumerate() {
+        forces[k] += value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_domain::*;
+    use std::sync::Arc;
+    use wavecore_matrices::Matrix;
+    use wavecore_meshes::Mesh;
+
+    fn chain(anchor: Point, fairlead: Point, axial_stiffness: f64) -> MooringLine {
+        MooringLine { anchor, fairlead, length: 420.0, weight: 1000.0, axial_stiffness }
+    }
+
+    /// Four lines at 45° from a body at the origin in 100 m of water
+    fn spread_mooring() -> CatenaryMooring {
+        let lines = (0..4)
+            .map(|k| {
+                let angle = std::f64::consts::FRAC_PI_4 + k as f64 * std::f64::consts::FRAC_PI_2;
+                let (c, s) = (angle.cos(), angle.sin());
+                chain(Point::new(400.0 * c, 400.0 * s, -100.0), Point::new(10.0 * c, 10.0 * s, 0.0), 1e9)
+            })
+            .collect();
+        CatenaryMooring::new(lines, Point::origin()).unwrap()
+    }
+
+    #[test]
+    fn test_catenary_tensions() {
+        let (w, l, ea, h): (f64, f64, f64, f64) = (1000.0, 420.0, 1e9, 2e5);
+        let line = chain(Point::origin(), Point::origin(), ea);
+
+        // Resting on the seabed: the suspended length carries V = w Ls
+        let suspended = 200.0;
+        let v = w * suspended;
+        let span = l - suspended + h / w * (v / h).asinh() + h * l / ea;
+        let height = h / w * ((1.0 + (v / h).powi(2)).sqrt() - 1.0) + v * v / (2.0 * ea * w);
+        let (th, tv) = line.tension(span, height).unwrap();
+        assert!((th - h).abs() < 1e-6 * h && (tv - v).abs() < 1e-6 * v, "{} {}", th, tv);
+
+        // Fully suspended, lifting the anchor end with V - wL
+        let v = w * l + 5e4;
+        let lower = (v - w * l) / h;
+        let span = h / w * ((v / h).asinh() - lower.asinh()) + h * l / ea;
+        let height = h / w * ((1.0 + (v / h).powi(2)).sqrt() - (1.0 + lower * lower).sqrt()) + (v * l - 0.5 * w * l * l) / ea;
+        let (th, tv) = line.tension(span, height).unwrap();
+        assert!((th - h).abs() < 1e-6 * h && (tv - v).abs() < 1e-6 * v, "{} {}", th, tv);
+
+        // An inextensible line cannot span more than its length
+        let rigid = chain(Point::origin(), Point::origin(), f64::INFINITY);
+        assert!(rigid.tension(400.0, 200.0).is_err());
+    }
+
+    #[test]
+    fn test_spread_mooring_restoring() {
+        let mooring = spread_mooring();
+        assert!(mooring.forces(0.0, &[0.0; 6], &[0.0; 6]).unwrap().iter().all(|f| f.abs() < 1e-3));
+        let pretension = mooring.tensions(&[0.0; 6]).unwrap();
+        assert!(pretension.iter().all(|&(h, _)| (h - pretension[0].0).abs() < 1e-6 * h));
+
+        let offset = mooring.forces(0.0, &[5.0, 0.0, 0.0, 0.0, 0.0, 0.0], &[0.0; 6]).unwrap();
+        assert!(offset[0] < 0.0);
+        assert!(offset[1].abs() < 1e-6 * offset[0].abs() && offset[5].abs() < 1e-6 * offset[0].abs());
+        let further = mooring.forces(0.0, &[10.0, 0.0, 0.0, 0.0, 0.0, 0.0], &[0.0; 6]).unwrap();
+        assert!(further[0] < 2.0 * offset[0], "catenary stiffening {} {}", further[0], offset[0]);
+    }
+
+    #[test]
+    fn test_linear_fender() {
+        let fender = LinearFender::new(
+            Point::new(0.0, 5.0, 0.0),
+            Point::new(0.0, 6.0, 0.0),
+            Vector::new(0.0, -2.0, 0.0),
+            1e6,
+            1e5,
+            Point::origin(),
+        )
+        .unwrap();
+        assert!(fender.forces(0.0, &[0.0; 6], &[0.0; 6]).unwrap().iter().all(|&f| f == 0.0));
+
+        let pose = [0.0, 1.2, 0.0, 0.0, 0.0, 0.0];
+        assert!((fender.penetration(&pose) - 0.2).abs() < 1e-12);
+        let forces = fender.forces(0.0, &pose, &[0.0; 6]).unwrap();
+        assert!((forces[1] + 2e5).abs() < 1e-6);
+
+        // Approaching adds damping; separating fast releases the contact
+        let approaching = fender.forces(0.0, &pose, &[0.0, 1.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+        assert!((approaching[1] + 3e5).abs() < 1e-6);
+        let separating = fender.forces(0.0, &pose, &[0.0, -3.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(separating[1], 0.0);
+
+        // Off-centre contact yaws the body
+        let corner = LinearFender { contact: Point::new(10.0, 5.0, 0.0), ..fender };
+        assert!((corner.forces(0.0, &pose, &[0.0; 6]).unwrap()[5] + 10.0 * 2e5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_moored_body_static_offset() {
+        let mooring = Arc::new(spread_mooring());
+        let diagonal = |values: [f64; 6]| {
+            let mut matrix = Matrix::new(6, 6);
+            for (i, v) in values.into_iter().enumerate() {
+                matrix.set(i, i, v).unwrap();
+            }
+            matrix
+        };
+        let mut solver = TimeDomainSolver::new(TimeDomainConfig::default());
+        solver.time_params = TimeParameters { dt: 0.5, num_steps: 1200, integration_scheme: IntegrationScheme::RungeKutta4, ..Default::default() };
+        let push = 1e5;
+        let problem = TimeDomainProblem {
+            mesh: Mesh::new(
+                vec![Point::new(0.0, 0.0, -1.0), Point::new(1.0, 0.0, -1.0), Point::new(0.0, 1.0, -1.0)],
+                vec![[0, 1, 2]],
+            )
+            .unwrap(),
+            initial_conditions: InitialConditions { positions: vec![0.0; 6], velocities: vec![0.0; 6], accelerations: vec![0.0; 6] },
+            external_forces: ExternalForces {
+                time_forces: Vec::new(),
+                constant_forces: vec![push, 0.0, 0.0, 0.0, 0.0, 0.0],
+                control_forces: None,
+                force_models: vec![mooring.clone()],
+            },
+            wave_environment: WaveConditions { wave_type: WaveType::Regular { amplitude: 0.0, frequency: 0.1, phase: 0.0 }, ..Default::default() },
+            body_properties: BodyProperties {
+                mass: diagonal([1e6, 1e6, 1e6, 1e8, 1e8, 1e8]),
+                hydrostatic: diagonal([0.0, 0.0, 1e7, 1e9, 1e9, 0.0]),
+                linear_damping: diagonal([5e5, 5e5, 5e6, 5e8, 5e8, 5e8]),
+                cog: Point::origin(),
+            },
+        };
+        let results = solver.solve_time_domain(&problem).unwrap();
+
+        // The mooring holds the steady push at the final offset
+        let last = results.time.len() - 1;
+        let pose: Vec<f64> = (0..6).map(|i| results.motions[&i][last]).collect();
+        assert!(pose[0] > 1.0);
+        assert!(results.velocities[&0][last].abs() < 1e-3);
+        let restoring = mooring.forces(0.0, &pose, &[0.0; 6]).unwrap();
+        assert!((restoring[0] + push).abs() < 1e-2 * push, "{} vs {}", restoring[0], push);
+    }
+}
@@ -0,0 +1,284 @@
+//! Forward-speed corrections for seakeeping
+//!
+//! A body advancing at speed U along +x meets waves of frequency ω and
+//! direction β (β = 0 following seas) at the encounter frequency
+//!
+//! ω_e = ω - (ω²/g) U cos β.
+//!
+//! The zero-speed coefficients are corrected with the strip-theory speed
+//! terms of Salvesen, Tuck and Faltinsen. For the e^{-iωt} time dependence
+//! used by the solver the speed-dependent radiation potentials are
+//! φ_k = φ_k⁰ + (iU/ω_e) Σ_m C_km φ_m⁰, with pitch coupled to heave and yaw
+//! to sway, which gives for the complex force matrix F = ω²A + iωB
+//!
+//! F(U) = (I - (iU/ω_e) C) F⁰ (I + (iU/ω_e) Cᵀ),
+//!
+//! e.g. A35 = A35⁰ - U/ω_e² B33⁰ and B35 = B35⁰ + U A33⁰. The zero-speed
+//! coefficients are interpolated at ω_e. The Froude-Krylov excitation is
+//! unchanged and the diffraction part picks up the same speed term.
+
+use super::*;
+use crate::coefficients::HydroCoefficients;
+use crate::motion::{MotionRaos, MotionSolver};
+use ndarray::{Array2, Array3, Array4, ArrayView1, ArrayView2};
+use num_complex::Complex64;
+use wavecore_bodies::FloatingBody;
+
+/// Forward-speed transform of zero-speed coefficients
+#[derive(Debug, Clone)]
+pub struct ForwardSpeed {
+    /// Forward speed along +x (m/s)
+    pub speed: f64,
+    /// Gravitational acceleration (m/s²)
+    pub gravity: f64,
+    /// Global index of the first mode of each body
+    pub mode_offsets: Vec<usize>,
+}
+
+/// Coefficients corrected for forward speed
+#[derive(Debug, Clone)]
+pub struct ForwardSpeedCoefficients {
+    /// Forward speed (m/s)
+    pub speed: f64,
+    /// Wave frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Encounter frequency, shape (frequency, direction)
+    pub encounter_frequencies: Array2<f64>,
+    /// Added mass at the encounter frequency, shape (frequency, direction, mode, mode)
+    pub added_mass: Array4<f64>,
+    /// Damping at the encounter frequency, shape (frequency, direction, mode, mode)
+    pub damping: Array4<f64>,
+    /// Excitation per unit wave amplitude, shape (frequency, direction, mode)
+    pub excitation: Array3<Complex64>,
+}
+
+impl ForwardSpeed {
+    /// Transform for a single body
+    pub fn new(speed: f64) -> Self {
+        Self {
+            speed,
+            gravity: 9.81,
+            mode_offsets: vec![0],
+        }
+    }
+
+    /// Transform for bodies in global mode order
+    pub fn for_bodies(speed: f64, bodies: &[FloatingBody]) -> Self {
+        let mode_offsets = bodies
+            .iter()
+            .scan(0, |offset, body| {
+                let start = *offset;
+                *offset += body.n_modes();
+                Some(start)
+            })
+            .collect();
+        Self {
+            speed,
+            gravity: 9.81,
+            mode_offsets,
+        }
+    }
+
+    /// Encounter frequency of waves with frequency `omega` and direction `heading`
+    pub fn encounter_frequency(&self, omega: f64, heading: f64) -> f64 {
+        omega - omega * omega / self.gravity * self.speed * heading.cos()
+    }
+
+    /// Speed-coupling matrix C of `n` modes (pitch ← heave, yaw ← sway)
+    fn coupling(&self, n: usize) -> Array2<f64> {
+        let mut c = Array2::zeros((n, n));
+        for &o in self.mode_offsets.iter().filter(|&&o| o + 5 < n) {
+            c[[o + 4, o + 2]] = 1.0;
+            c[[o + 5, o + 1]] = -1.0;
+        }
+        c
+    }
+
+    /// Added mass and damping at speed from zero-speed values at `encounter_frequency`
+    pub fn correct_radiation(
+        &self,
+        encounter_frequency: f64,
+        added_mass: ArrayView2<f64>,
+        damping: ArrayView2<f64>,
+    ) -> Result<(Array2<f64>, Array2<f64>)> {
+        let n = added_mass.nrows();
+        if added_mass.dim() != (n, n) || damping.dim() != (n, n) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Added mass {:?} and damping {:?} must be square and equal in shape", added_mass.dim(), damping.dim()),
+            });
+        }
+        let omega = encounter_frequency;
+        let force = added_mass.mapv(|a| Complex64::new(omega * omega * a, 0.0)) + damping.mapv(|b| Complex64::new(0.0, omega * b));
+        let s = Complex64::new(0.0, self.speed / omega);
+        let c = self.coupling(n).mapv(|v| Complex64::new(v, 0.0));
+        let identity = Array2::<Complex64>::eye(n);
+        let left = &identity - &c.mapv(|v| s * v);
+        let right = &identity + &c.t().mapv(|v| s * v);
+        let corrected = left.dot(&force).dot(&right);
+        Ok((
+            corrected.mapv(|f| f.re / (omega * omega)),
+            corrected.mapv(|f| f.im / omega),
+        ))
+    }
+
+    /// Excitation at speed from the zero-speed total and Froude-Krylov excitation
+    pub fn correct_excitation(
+        &self,
+        encounter_frequency: f64,
+        excitation: ArrayView1<Complex64>,
+        froude_krylov: ArrayView1<Complex64>,
+    ) -> Result<Vec<Complex64>> {
+        let n = excitation.len();
+        if froude_krylov.len() != n {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Froude-Krylov force has {} modes, expected {}", froude_krylov.len(), n),
+            });
+        }
+        let s = Complex64::new(0.0, self.speed / encounter_frequency);
+        let c = self.coupling(n);
+        Ok((0..n)
+            .map(|i| {
+                let coupled: Complex64 = (0..n)
+                    .filter(|&m| c[[i, m]] != 0.0)
+                    .map(|m| (excitation[m] - froude_krylov[m]) * c[[i, m]])
+                    .sum();
+                excitation[i] + s * coupled
+            })
+            .collect())
+    }
+
+    /// Corrected coefficients over the frequency/direction grid of a sweep
+    ///
+    /// Entries whose encounter frequency is not positive or lies outside the
+    /// completed sweep frequencies are left as NaN.
+    pub fn apply(&self, coefficients: &HydroCoefficients) -> Result<ForwardSpeedCoefficients> {
+        let n = coefficients.n_dof();
+        let (nf, nd) = (coefficients.frequencies.len(), coefficients.directions.len());
+        let nan = Complex64::new(f64::NAN, f64::NAN);
+        let mut result = ForwardSpeedCoefficients {
+            speed: self.speed,
+            frequencies: coefficients.frequencies.clone(),
+            directions: coefficients.directions.clone(),
+            encounter_frequencies: Array2::from_elem((nf, nd), f64::NAN),
+            added_mass: Array4::from_elem((nf, nd, n, n), f64::NAN),
+            damping: Array4::from_elem((nf, nd, n, n), f64::NAN),
+            excitation: Array3::from_elem((nf, nd, n), nan),
+        };
+        for (f, &omega) in coefficients.frequencies.iter().enumerate() {
+            for (d, &heading) in coefficients.directions.iter().enumerate() {
+                let omega_e = self.encounter_frequency(omega, heading);
+                result.encounter_frequencies[[f, d]] = omega_e;
+                if omega_e <= 0.0 {
+                    continue;
+                }
+                if let Some((added_mass, damping)) = coefficients.radiation_at(omega_e) {
+                    let (a, b) = self.correct_radiation(omega_e, added_mass.view(), damping.view())?;
+                    result.added_mass.slice_mut(ndarray::s![f, d, .., ..]).assign(&a);
+                    result.damping.slice_mut(ndarray::s![f, d, .., ..]).assign(&b);
+                }
+                if coefficients.completed[f] {
+                    let excitation = self.correct_excitation(
+                        omega_e,
+                        coefficients.excitation_at(f, d),
+                        coefficients.froude_krylov.slice(ndarray::s![f, d, ..]),
+                    )?;
+                    result.excitation.slice_mut(ndarray::s![f, d, ..]).assign(&ArrayView1::from(&excitation));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl ForwardSpeedCoefficients {
+    /// Motion RAOs solved at the encounter frequencies; NaN where coefficients are missing
+    pub fn raos(&self, solver: &MotionSolver) -> Result<MotionRaos> {
+        let (nf, nd, n) = self.excitation.dim();
+        let mut values = Array3::from_elem((nf, nd, n), Complex64::new(f64::NAN, f64::NAN));
+        for f in 0..nf {
+            for d in 0..nd {
+                let excitation = self.excitation.slice(ndarray::s![f, d, ..]);
+                let added_mass = self.added_mass.slice(ndarray::s![f, d, .., ..]);
+                if excitation.iter().any(|x| x.is_nan()) || added_mass.iter().any(|x| x.is_nan()) {
+                    continue;
+                }
+                let damping = self.damping.slice(ndarray::s![f, d, .., ..]);
+                let motion = solver.solve_frequency(self.encounter_frequencies[[f, d]], added_mass, damping, excitation)?;
+                values.slice_mut(ndarray::s![f, d, ..]).assign(&ArrayView1::from(&motion));
+            }
+        }
+        Ok(MotionRaos {
+            frequencies: self.frequencies.clone(),
+            directions: self.directions.clone(),
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_strip_theory_speed_terms() {
+        let n = 6;
+        let (u, omega) = (5.0, 0.8);
+        let added_mass = Array2::from_shape_fn((n, n), |(i, j)| if i == j { 100.0 * (i + 1) as f64 } else { 3.0 * (i + j) as f64 });
+        let damping = Array2::from_shape_fn((n, n), |(i, j)| if i == j { 40.0 * (i + 1) as f64 } else { (i + j) as f64 });
+
+        let (a, b) = ForwardSpeed::new(u).correct_radiation(omega, added_mass.view(), damping.view()).unwrap();
+        let close = |x: f64, y: f64| (x - y).abs() < 1e-9 * y.abs().max(1.0);
+        assert!(close(a[[2, 4]], added_mass[[2, 4]] - u / (omega * omega) * damping[[2, 2]]));
+        assert!(close(b[[2, 4]], damping[[2, 4]] + u * added_mass[[2, 2]]));
+        assert!(close(a[[4, 2]], added_mass[[4, 2]] + u / (omega * omega) * damping[[2, 2]]));
+        assert!(close(b[[4, 2]], damping[[4, 2]] - u * added_mass[[2, 2]]));
+        assert!(close(b[[1, 5]], damping[[1, 5]] - u * added_mass[[1, 1]]));
+        assert!(close(a[[0, 0]], added_mass[[0, 0]]));
+
+        // Timman-Newman: A_jk(U) = A_kj(-U) for symmetric zero-speed coefficients
+        let (a_back, b_back) = ForwardSpeed::new(-u).correct_radiation(omega, added_mass.view(), damping.view()).unwrap();
+        for i in 0..n {
+            for j in 0..n {
+                assert!(close(a[[i, j]], a_back[[j, i]]));
+                assert!(close(b[[i, j]], b_back[[j, i]]));
+            }
+        }
+
+        let speed = ForwardSpeed::new(u);
+        assert!(speed.encounter_frequency(omega, std::f64::consts::PI) > omega);
+        assert!(speed.encounter_frequency(omega, 0.0) < omega);
+    }
+
+    #[test]
+    fn test_forward_speed_coefficients() {
+        let body = tetrahedron_body("a", 0.0);
+        let frequencies = [0.4, 0.6, 0.8, 1.0, 1.2];
+        let directions = [0.0, std::f64::consts::PI];
+        let sweep = BEMSolver::new(SolverEngine::Standard)
+            .solve_sweep_bodies(&frequencies, &directions, vec![body.clone()])
+            .unwrap();
+        assert!(sweep.froude_krylov.iter().all(|f| f.is_finite()));
+
+        // Zero speed reproduces the sweep
+        let still = ForwardSpeed::for_bodies(0.0, std::slice::from_ref(&body)).apply(&sweep).unwrap();
+        assert_eq!(still.encounter_frequencies[[2, 1]], 0.8);
+        for (a, b) in still.added_mass.slice(ndarray::s![2, 1, .., ..]).iter().zip(sweep.added_mass_at(2).iter()) {
+            assert!((a - b).abs() <= 1e-9 * b.abs().max(1.0));
+        }
+
+        // Head seas raise the encounter frequency, beyond the sweep at the top
+        let speed = ForwardSpeed::for_bodies(1.0, &[body]);
+        let moving = speed.apply(&sweep).unwrap();
+        let omega_e = moving.encounter_frequencies[[1, 1]];
+        assert!((omega_e - (0.6 + 0.36 / 9.81)).abs() < 1e-12);
+        assert!(moving.added_mass[[1, 1, 2, 2]].is_finite());
+        assert!(moving.added_mass[[4, 1, 2, 2]].is_nan());
+        let (a0, b0) = sweep.radiation_at(omega_e).unwrap();
+        let expected = b0[[2, 4]] + a0[[2, 2]];
+        assert!((moving.damping[[1, 1, 2, 4]] - expected).abs() <= 1e-9 * expected.abs().max(1.0));
+        assert!(moving.excitation.slice(ndarray::s![1, .., ..]).iter().all(|f| f.is_finite()));
+    }
+}
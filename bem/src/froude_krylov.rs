@@ -0,0 +1,294 @@
+//! Froude–Krylov and hydrostatic forces on the instantaneous wetted surface
+//!
+//! Weakly nonlinear time-domain simulations integrate the hydrostatic and
+//! undisturbed incident-wave pressure over the body in its current pose,
+//! re-clipping the hull each step against the incident free surface
+//! z = η(x, y, t) instead of the mean waterline. The mesh must therefore
+//! cover the hull above the waterline as well. The dynamic pressure decays
+//! with the linear depth profile below z = 0 and is held at its surface
+//! value above, so p = ρg(η - z) vanishes on the free surface. Forces are
+//! reported relative to the still-water forces of the reference pose, where
+//! buoyancy balances weight.
+
+use super::*;
+use crate::qtf::wave_number;
+use crate::time_domain::{WaveConditions, WaveType};
+use nalgebra::{Rotation3, Vector3};
+use wavecore_meshes::{Mesh, Point};
+
+/// Regular incident wave A sin(ωt + phase - k(x cos β + y sin β))
+#[derive(Debug, Clone, Copy)]
+pub struct IncidentWave {
+    /// Amplitude (m)
+    pub amplitude: f64,
+    /// Angular frequency (rad/s)
+    pub omega: f64,
+    /// Phase at the origin (rad)
+    pub phase: f64,
+    /// Wave number (rad/m)
+    pub wave_number: f64,
+    /// Propagation direction (rad)
+    pub direction: f64,
+    /// Water depth (m), `None` for deep water
+    pub depth: Option<f64>,
+}
+
+impl IncidentWave {
+    /// Incident wave of time-domain wave conditions; `None` unless the waves are regular
+    pub fn from_conditions(conditions: &WaveConditions) -> Option<Self> {
+        let WaveType::Regular { amplitude, frequency, phase } = conditions.wave_type else {
+            return None;
+        };
+        let omega = 2.0 * std::f64::consts::PI * frequency;
+        let depth = (conditions.parameters.depth > 0.0).then_some(conditions.parameters.depth);
+        Some(Self {
+            amplitude,
+            omega,
+            phase,
+            wave_number: wave_number(omega, conditions.parameters.g, depth),
+            direction: conditions.direction.to_radians(),
+            depth,
+        })
+    }
+
+    /// Free-surface elevation η(x, y, t)
+    pub fn elevation(&self, x: f64, y: f64, time: f64) -> f64 {
+        let position = self.wave_number * (x * self.direction.cos() + y * self.direction.sin());
+        self.amplitude * (self.omega * time + self.phase - position).sin()
+    }
+
+    /// Dynamic pressure over ρg: η at the surface, decaying with depth below z = 0
+    fn pressure_head(&self, point: &Point, time: f64) -> f64 {
+        let profile = match (point.z < 0.0, self.depth) {
+            (false, _) => 1.0,
+            (true, Some(h)) => (self.wave_number * (point.z + h)).cosh() / (self.wave_number * h).cosh(),
+            (true, None) => (self.wave_number * point.z).exp(),
+        };
+        self.elevation(point.x, point.y, time) * profile
+    }
+}
+
+/// Pressure integration over the clipped hull of one body
+#[derive(Debug, Clone)]
+pub struct NonlinearFroudeKrylov {
+    /// Hull vertices in the reference pose
+    vertices: Vec<Point>,
+    /// Triangles with outward normals
+    faces: Vec<[usize; 3]>,
+    /// Centre of gravity in the reference pose; moments are taken about it
+    cog: Point,
+    /// Water density (kg/m³)
+    rho: f64,
+    /// Gravitational acceleration (m/s²)
+    gravity: f64,
+    /// Still-water forces of the reference pose
+    reference: [f64; 6],
+}
+
+impl NonlinearFroudeKrylov {
+    /// Integrator for a hull mesh covering the body above and below the waterline
+    pub fn new(mesh: &Mesh, cog: Point, rho: f64, gravity: f64) -> Result<Self> {
+        // Quads are clipped as their two triangles
+        let mesh = &mesh.triangulated()?;
+        if let Some(face) = mesh.faces.iter().find(|face| face.iter().any(|&i| i >= mesh.vertices.len())) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Hull face {:?} references a missing vertex", face),
+            });
+        }
+        let mut integrator = Self {
+            vertices: mesh.vertices.clone(),
+            faces: mesh.faces.clone(),
+            cog,
+            rho,
+            gravity,
+            reference: [0.0; 6],
+        };
+        integrator.reference = integrator.integrate(&[0.0; 6], None, 0.0).0;
+        Ok(integrator)
+    }
+
+    /// Forces and moments about the displaced centre of gravity, less the still-water reference
+    ///
+    /// `pose` holds surge, sway, heave, roll, pitch and yaw; missing entries are zero.
+    pub fn forces(&self, pose: &[f64], wave: Option<&IncidentWave>, time: f64) -> [f64; 6] {
+        let mut forces = self.integrate(pose, wave, time).0;
+        for (force, reference) in forces.iter_mut().zip(&self.reference) {
+            *force -= reference;
+        }
+        forces
+    }
+
+    /// Total pressure forces and moments, including the buoyancy of the reference pose
+    pub fn pressure_forces(&self, pose: &[f64], wave: Option<&IncidentWave>, time: f64) -> [f64; 6] {
+        self.integrate(pose, wave, time).0
+    }
+
+    /// Area of the instantaneous wetted surface
+    pub fn wetted_area(&self, pose: &[f64], wave: Option<&IncidentWave>, time: f64) -> f64 {
+        self.integrate(pose, wave, time).1
+    }
+
+    /// Pressure forces and wetted area of the hull in `pose`
+    fn integrate(&self, pose: &[f64], wave: Option<&IncidentWave>, time: f64) -> ([f64; 6], f64) {
+        let (rotation, translation) = rigid_motion(pose);
+        let cog = self.cog + translation;
+        let moved: Vec<Point> = self.vertices.iter().map(|v| cog + rotation * (v - self.cog)).collect();
+        let height = |p: &Point| p.z - wave.map_or(0.0, |w| w.elevation(p.x, p.y, time));
+        let pressure = |p: &Point| self.rho * self.gravity * (-p.z + wave.map_or(0.0, |w| w.pressure_head(p, time)));
+
+        let mut force = Vector3::zeros();
+        let mut moment = Vector3::zeros();
+        let mut area = 0.0;
+        for &[a, b, c] in &self.faces {
+            let corners = [moved[a], moved[b], moved[c]];
+            let wetted = clip_below(&corners, &corners.map(|p| height(&p)));
+            for k in 1..wetted.len().saturating_sub(1) {
+                let (p0, p1, p2) = (wetted[0], wetted[k], wetted[k + 1]);
+                let area_vector = 0.5 * (p1 - p0).cross(&(p2 - p0));
+                area += area_vector.norm();
+
+                // Edge midpoints integrate quadratics exactly
+                for midpoint in [p0 + 0.5 * (p1 - p0), p1 + 0.5 * (p2 - p1), p2 + 0.5 * (p0 - p2)] {
+                    let load = -pressure(&midpoint) / 3.0 * area_vector;
+                    force += load;
+                    moment += (midpoint - cog).cross(&load);
+                }
+            }
+        }
+        ([force.x, force.y, force.z, moment.x, moment.y, moment.z], area)
+    }
+}
+
+/// Rotation about the centre of gravity and its translation for surge, sway, heave, roll, pitch and yaw
+///
+/// Rotations are applied roll, then pitch, then yaw; missing entries are zero.
+pub(crate) fn rigid_motion(pose: &[f64]) -> (Rotation3<f64>, Vector3<f64>) {
+    let dof = |i: usize| pose.get(i).copied().unwrap_or(0.0);
+    (Rotation3::from_euler_angles(dof(3), dof(4), dof(5)), Vector3::new(dof(0), dof(1), dof(2)))
+}
+
+/// Part of a triangle where the height above the free surface is not positive
+fn clip_below(corners: &[Point; 3], heights: &[f64; 3]) -> Vec<Point> {
+    let mut polygon = Vec::with_capacity(4);
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (ha, hb) = (heights[i], heights[j]);
+        if ha <= 0.0 {
+            polygon.push(corners[i]);
+        }
+        if (ha < 0.0 && hb > 0.0) || (ha > 0.0 && hb < 0.0) {
+            polygon.push(corners[i] + ha / (ha - hb) * (corners[j] - corners[i]));
+        }
+    }
+    polygon
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_domain::*;
+    use wavecore_matrices::Matrix;
+
+    const RHO_G: f64 = 1025.0 * 9.81;
+
+    /// Closed 2 m cube centred at the origin, half immersed, with outward normals
+    fn box_mesh() -> Mesh {
+        let vertices: Vec<Point> = (0..8)
+            .map(|i| Point::new(if i & 1 == 0 { -1.0 } else { 1.0 }, if i & 2 == 0 { -1.0 } else { 1.0 }, if i & 4 == 0 { -1.0 } else { 1.0 }))
+            .collect();
+        let quads = [[0, 1, 3, 2], [4, 5, 7, 6], [0, 1, 5, 4], [2, 3, 7, 6], [0, 2, 6, 4], [1, 3, 7, 5]];
+        let mut faces = Vec::new();
+        for [a, b, c, d] in quads {
+            for mut face in [[a, b, c], [a, c, d]] {
+                let centroid = (vertices[face[0]].coords + vertices[face[1]].coords + vertices[face[2]].coords) / 3.0;
+                let normal = (vertices[face[1]] - vertices[face[0]]).cross(&(vertices[face[2]] - vertices[face[0]]));
+                if normal.dot(&centroid) < 0.0 {
+                    face.swap(1, 2);
+                }
+                faces.push(face);
+            }
+        }
+        Mesh::new(vertices, faces).unwrap()
+    }
+
+    #[test]
+    fn test_still_water_restoring() {
+        let hull = NonlinearFroudeKrylov::new(&box_mesh(), Point::origin(), 1025.0, 9.81).unwrap();
+        assert!((hull.pressure_forces(&[0.0; 6], None, 0.0)[2] - RHO_G * 4.0).abs() < 1e-6);
+        assert!(hull.forces(&[0.0; 6], None, 0.0).iter().all(|f| f.abs() < 1e-6));
+        assert!((hull.wetted_area(&[0.0; 6], None, 0.0) - 12.0).abs() < 1e-12);
+
+        // Wall-sided heave restoring ρg A_wp z, until the deck submerges
+        assert!((hull.forces(&[0.0, 0.0, -0.2], None, 0.0)[2] - RHO_G * 4.0 * 0.2).abs() < 1e-6);
+        assert!((hull.forces(&[0.0, 0.0, -1.5], None, 0.0)[2] - RHO_G * 4.0).abs() < 1e-6);
+
+        // Small roll about the centre of gravity: C44 = ρg (I_wp + V (z_B - z_G)) = ρg (4/3 - 2)
+        let roll = 1e-3;
+        let moment = hull.forces(&[0.0, 0.0, 0.0, roll], None, 0.0)[3];
+        assert!((moment - RHO_G * (2.0 - 4.0 / 3.0) * roll).abs() < 1e-3 * RHO_G * roll, "{}", moment);
+    }
+
+    #[test]
+    fn test_long_wave_froude_krylov() {
+        let hull = NonlinearFroudeKrylov::new(&box_mesh(), Point::origin(), 1025.0, 9.81).unwrap();
+        let conditions = WaveConditions {
+            wave_type: WaveType::Regular { amplitude: 0.1, frequency: 0.09, phase: std::f64::consts::FRAC_PI_2 },
+            parameters: WaveParameters { depth: -1.0, rho: 1025.0, g: 9.81 },
+            direction: 0.0,
+        };
+        let wave = IncidentWave::from_conditions(&conditions).unwrap();
+        let k = wave.wave_number;
+
+        // Crest at the origin: the bottom carries ρgA e^{-k} cos(kx); the sides add no vertical force
+        let expected = RHO_G * 0.1 * (-k).exp() * 2.0 * 2.0 * k.sin() / k;
+        let heave = hull.forces(&[0.0; 6], Some(&wave), 0.0)[2];
+        assert!((heave - expected).abs() < 1e-6 * expected, "{} != {}", heave, expected);
+        // The crest wets a 0.1 m band of the four 2 m sides
+        assert!((hull.wetted_area(&[0.0; 6], Some(&wave), 0.0) - 12.8).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_nonlinear_time_domain_heave() {
+        let mass = 1025.0 * 4.0;
+        let diagonal = |values: [f64; 6]| {
+            let mut matrix = Matrix::new(6, 6);
+            for (i, v) in values.into_iter().enumerate() {
+                matrix.set(i, i, v).unwrap();
+            }
+            matrix
+        };
+        let run = |nonlinear: bool, heave: f64| {
+            let config = TimeDomainConfig { nonlinear_froude_krylov: nonlinear, ..Default::default() };
+            let mut solver = TimeDomainSolver::new(config);
+            solver.time_params = TimeParameters { dt: 0.01, num_steps: 200, integration_scheme: IntegrationScheme::RungeKutta4, ..Default::default() };
+            let problem = TimeDomainProblem {
+                mesh: box_mesh(),
+                initial_conditions: InitialConditions { positions: vec![0.0, 0.0, heave, 0.0, 0.0, 0.0], velocities: vec![0.0; 6], accelerations: vec![0.0; 6] },
+                external_forces: ExternalForces { time_forces: Vec::new(), constant_forces: vec![0.0; 6], control_forces: None, force_models: Vec::new() },
+                wave_environment: WaveConditions {
+                    wave_type: WaveType::Regular { amplitude: 0.0, frequency: 0.5, phase: 0.0 },
+                    parameters: WaveParameters { depth: -1.0, rho: 1025.0, g: 9.81 },
+                    direction: 0.0,
+                },
+                body_properties: BodyProperties {
+                    mass: diagonal([mass, mass, mass, 1e4, 1e4, 1e4]),
+                    hydrostatic: diagonal([0.0, 0.0, RHO_G * 4.0, 0.0, 0.0, 0.0]),
+                    linear_damping: Matrix::new(6, 6),
+                    cog: Point::origin(),
+                },
+            };
+            solver.solve_time_domain(&problem).unwrap()
+        };
+
+        // Small heave of a wall-sided hull is linear
+        let (linear, nonlinear) = (run(false, 0.05), run(true, 0.05));
+        for (a, b) in linear.motions[&2].iter().zip(&nonlinear.motions[&2]) {
+            assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+        }
+
+        // Once the deck submerges the restoring force saturates at the full buoyancy
+        let deep = run(true, -1.5);
+        assert!((deep.accelerations[&2][0] - RHO_G * 4.0 / mass).abs() < 1e-6);
+        assert!((run(false, -1.5).accelerations[&2][0] - RHO_G * 6.0 / mass).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,255 @@
+//! Higher-order panel discretization
+//!
+//! In the constant-panel method the unknown is uniform over each panel and
+//! collocated at its centroid. Higher orders let it vary with linear (3-node)
+//! or quadratic (6-node) shape functions over flat triangles, with nodes shared
+//! between neighbouring panels, and integrate the kernels by Gauss quadrature.
+
+use wavecore_meshes::{Panel, Point, Vector};
+use serde::{Serialize, Deserialize};
+
+/// Variation of the unknown over a panel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PanelOrder {
+    /// Uniform value per panel, collocated at the centroid
+    Constant,
+    /// Linear variation, nodes at panel vertices
+    Linear,
+    /// Quadratic variation, nodes at vertices and edge midpoints
+    Quadratic,
+}
+
+impl PanelOrder {
+    /// Number of nodes per triangular panel
+    pub fn nodes_per_panel(&self) -> usize {
+        match self {
+            PanelOrder::Constant => 1,
+            PanelOrder::Linear => 3,
+            PanelOrder::Quadratic => 6,
+        }
+    }
+}
+
+/// Collocation node carrying one unknown
+#[derive(Debug, Clone)]
+pub struct CollocationNode {
+    /// Node position
+    pub position: Point,
+    /// Unit normal (area-weighted average for shared nodes)
+    pub normal: Vector,
+    /// Owning body
+    pub body: usize,
+    /// Integral of the node's shape function over the surface
+    pub weight: f64,
+}
+
+/// Symmetric Gauss rules on the unit triangle as (barycentric point, weight)
+/// with weights summing to one
+pub fn triangle_rule(min_points: usize) -> Vec<([f64; 3], f64)> {
+    let permute = |a: f64, b: f64, w: f64| {
+        vec![([a, a, b], w), ([a, b, a], w), ([b, a, a], w)]
+    };
+
+    match min_points {
+        0 | 1 => vec![([1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0], 1.0)],
+        2 | 3 => permute(1.0 / 6.0, 2.0 / 3.0, 1.0 / 3.0),
+        4..=6 => {
+            let mut rule = permute(0.445948490915965, 0.108103018168070, 0.223381589678011);
+            rule.extend(permute(0.091576213509771, 0.816847572980459, 0.109951743655322));
+            rule
+        }
+        _ => {
+            let mut rule = vec![([1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0], 0.225)];
+            rule.extend(permute(0.470142064105115, 0.059715871789770, 0.132394152788506));
+            rule.extend(permute(0.101286507323456, 0.797426985353087, 0.125939180544827));
+            rule
+        }
+    }
+}
+
+/// Shape function values at barycentric coordinates `l`
+///
+/// Quadratic node order is the three vertices followed by the midpoints of
+/// edges (0,1), (1,2) and (2,0).
+pub fn shape_functions(order: PanelOrder, l: [f64; 3]) -> Vec<f64> {
+    match order {
+        PanelOrder::Constant => vec![1.0],
+        PanelOrder::Linear => l.to_vec(),
+        PanelOrder::Quadratic => vec![
+            l[0] * (2.0 * l[0] - 1.0),
+            l[1] * (2.0 * l[1] - 1.0),
+            l[2] * (2.0 * l[2] - 1.0),
+            4.0 * l[0] * l[1],
+            4.0 * l[1] * l[2],
+            4.0 * l[2] * l[0],
+        ],
+    }
+}
+
+/// Physical point of barycentric coordinates `l` on a flat panel
+pub fn map_to_panel(panel: &Panel, l: [f64; 3]) -> Point {
+    let v = panel.vertices();
+    Point::from(v[0].coords * l[0] + v[1].coords * l[1] + v[2].coords * l[2])
+}
+
+/// Build shared collocation nodes and panel connectivity for one body's faces
+///
+/// Returns the nodes and, per face, the indices of its nodes offset by `node_offset`.
+/// Constant panels may include quads; higher orders need one triangle face per panel.
+pub fn build_nodes(
+    order: PanelOrder,
+    vertices: &[Point],
+    faces: &[[usize; 3]],
+    panels: &[Panel],
+    body: usize,
+    node_offset: usize,
+    rule: &[([f64; 3], f64)],
+) -> (Vec<CollocationNode>, Vec<Vec<usize>>) {
+    use std::collections::HashMap;
+
+    let mut nodes: Vec<CollocationNode> = Vec::new();
+    let mut normal_sums: Vec<Vector> = Vec::new();
+    let mut elements = Vec::with_capacity(faces.len());
+    let mut vertex_nodes: HashMap<usize, usize> = HashMap::new();
+    let mut edge_nodes: HashMap<(usize, usize), usize> = HashMap::new();
+
+    let new_node = |position: Point, nodes: &mut Vec<CollocationNode>, normal_sums: &mut Vec<Vector>| {
+        nodes.push(CollocationNode {
+            position,
+            normal: Vector::zeros(),
+            body,
+            weight: 0.0,
+        });
+        normal_sums.push(Vector::zeros());
+        nodes.len() - 1
+    };
+
+    for (p, panel) in panels.iter().enumerate() {
+        let mut local = Vec::with_capacity(order.nodes_per_panel());
+        match order {
+            PanelOrder::Constant => {
+                local.push(new_node(panel.centroid(), &mut nodes, &mut normal_sums));
+            }
+            PanelOrder::Linear | PanelOrder::Quadratic => {
+                let face = faces[p];
+                for &v in &face {
+                    let id = match vertex_nodes.get(&v) {
+                        Some(&id) => id,
+                        None => {
+                            let id = new_node(vertices[v], &mut nodes, &mut normal_sums);
+                            vertex_nodes.insert(v, id);
+                            id
+                        }
+                    };
+                    local.push(id);
+                }
+                if order == PanelOrder::Quadratic {
+                    for (a, b) in [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])] {
+                        let key = (a.min(b), a.max(b));
+                        let id = match edge_nodes.get(&key) {
+                            Some(&id) => id,
+                            None => {
+                                let mid = Point::from((vertices[a].coords + vertices[b].coords) * 0.5);
+                                let id = new_node(mid, &mut nodes, &mut normal_sums);
+                                edge_nodes.insert(key, id);
+                                id
+                            }
+                        };
+                        local.push(id);
+                    }
+                }
+            }
+        }
+
+        // Shape-function integrals and area-weighted normals
+        for &(l, w) in rule {
+            let n = shape_functions(order, l);
+            for (a, &id) in local.iter().enumerate() {
+                nodes[id].weight += w * panel.area() * n[a];
+            }
+        }
+        for &id in &local {
+            normal_sums[id] += panel.normal() * panel.area();
+        }
+
+        elements.push(local.iter().map(|id| id + node_offset).collect());
+    }
+
+    for (node, sum) in nodes.iter_mut().zip(normal_sums) {
+        let norm = sum.norm();
+        if norm > 0.0 {
+            node.normal = sum / norm;
+        }
+    }
+
+    (nodes, elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BEMConfig, BEMSolver, ProblemType};
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_triangle_rules_integrate_quadratics() {
+        for n in [3, 6, 7] {
+            let rule = triangle_rule(n);
+            let total: f64 = rule.iter().map(|(_, w)| w).sum();
+            assert!((total - 1.0).abs() < 1e-12);
+
+            // ∫ L0² dA / A = 1/6 on the unit triangle
+            let l0_sq: f64 = rule.iter().map(|(l, w)| w * l[0] * l[0]).sum();
+            assert!((l0_sq - 1.0 / 6.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_shape_functions_partition_of_unity() {
+        let l = [0.2, 0.3, 0.5];
+        for order in [PanelOrder::Constant, PanelOrder::Linear, PanelOrder::Quadratic] {
+            let sum: f64 = shape_functions(order, l).iter().sum();
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_quadratic_nodes_shared_between_panels() {
+        let vertices = vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        ];
+        let faces = vec![[0, 1, 2], [0, 2, 3]];
+        let panels: Vec<Panel> = faces
+            .iter()
+            .map(|f| Panel::new(vertices[f[0]], vertices[f[1]], vertices[f[2]]).unwrap())
+            .collect();
+
+        let (nodes, elements) = build_nodes(PanelOrder::Quadratic, &vertices, &faces, &panels, 0, 0, &triangle_rule(3));
+        // 4 vertices + 5 distinct edges
+        assert_eq!(nodes.len(), 9);
+        assert_eq!(elements[0].len(), 6);
+
+        // Shape-function integrals sum to the total area
+        let area: f64 = nodes.iter().map(|n| n.weight).sum();
+        assert!((area - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_higher_order_panels() {
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+
+        // A tetrahedron has 4 vertices and 6 edges
+        for (order, n_nodes) in [(PanelOrder::Linear, 4), (PanelOrder::Quadratic, 10)] {
+            let solver = BEMSolver::with_config(BEMConfig {
+                panel_order: order,
+                ..Default::default()
+            });
+            let result = solver.solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)]).unwrap();
+            assert_eq!(result.potential().len(), n_nodes);
+            assert!(result.potential().iter().all(|p| p.is_finite()));
+        }
+    }
+}
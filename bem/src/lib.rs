@@ -12,6 +12,27 @@
 //! - **Solver Engines**: Multiple solver strategies
 //! - **Linear Solvers**: Integration with matrix solvers
 //! - **Wave Theory**: Airy wave theory implementation
+//! - **Frequency Sweeps**: Parallel solves collected into `HydroCoefficients`
+//! - **Drift Forces**: Far-field (Kochin) and near-field (pressure integration) mean drift, with wave drift damping
+//! - **Second-Order Loads**: Difference- and sum-frequency QTFs
+//! - **H-Matrix Engine**: ACA-compressed influence matrices solved by GMRES for large meshes
+//! - **Influence Storage**: Fundamental-region rows for symmetric hulls and Rankine parts reused across a sweep
+//! - **Preconditioning**: Block-diagonal and near-field sparse approximate inverses for iterative solves
+//! - **Krylov Recycling**: GCRO-DR recycle spaces warm-starting GMRES from one frequency of a sweep to the next
+//! - **Field Evaluation**: Potential, pressure and velocity anywhere in the fluid from a solved result
+//! - **Free Surface**: Complex wave elevation over a grid from radiation and diffraction results
+//! - **Excitation Forces**: Diffraction pressure integration cross-checked by the Haskind relations
+//! - **Quality Diagnostics**: Energy-conservation and reciprocity checks reported with each result
+//! - **Checkpointing**: Long sweeps save completed frequencies and resume after interruption
+//! - **Motion RAOs**: Equations of motion solved from the sweep coefficients and body properties
+//! - **Frequency Limits**: Zero- and infinite-frequency added mass from rigid-lid and double-body solves
+//! - **Forward Speed**: Encounter frequency and strip-theory speed corrections of coefficients and excitation
+//! - **Damping Lids**: Dissipative free-surface panels suppressing moonpool and gap resonances
+//! - **Nonlinear Froude–Krylov**: Incident-wave and hydrostatic pressure on the instantaneous wetted surface
+//! - **Force Models**: Quasi-static catenary mooring and fender forces in time-domain simulations
+//! - **Progress Reporting**: Assembly and sweep progress callbacks with cooperative cancellation
+//! - **Dynamic Positioning**: PID and LQR controllers with thrust limits in time-domain simulations
+//! - **Panel Output**: Pressure, source strength and normal velocity per panel for load transfer
 //! 
 //! ## Example
 //! 
@@ -38,6 +59,28 @@ pub mod results;
 pub mod linear_solver;
 pub mod engines;
 pub mod airy_waves;
+pub mod higher_order;
+pub mod symmetry;
+pub mod coefficients;
+pub mod drift;
+pub mod near_field;
+pub mod qtf;
+pub mod hmatrix;
+pub mod preconditioner;
+pub mod field;
+pub mod diagnostics;
+pub mod checkpoint;
+pub mod motion;
+pub mod limits;
+pub mod forward_speed;
+pub mod lid;
+pub mod control;
+pub mod force_models;
+pub mod progress;
+pub mod froude_krylov;
+pub mod panel_output;
+#[cfg(test)]
+mod fixtures;
 
 // Explicit exports to avoid ambiguity - Direct exports instead of re-exports
 pub use BEMSolver as BemSolver; // Direct export
@@ -48,8 +91,29 @@ pub use results::*;
 pub use linear_solver::*;
 pub use engines::*;
 pub use airy_waves::*;
+pub use higher_order::PanelOrder;
+pub use symmetry::Symmetry;
+pub use coefficients::{AdaptiveRefinement, HydroCoefficients};
+pub use drift::{KochinFunction, MeanDriftForce, WaveDriftDamping};
+pub use near_field::NearFieldDrift;
+pub use qtf::{FirstOrderField, QtfCalculator, QtfConfig, QtfKind, QtfMatrix, SecondOrderPotential};
+pub use hmatrix::{HMatrix, HMatrixConfig};
+pub use preconditioner::{Preconditioner, Preconditioning};
+pub use field::{free_surface_elevation, BoundaryDistribution, FieldData, FreeSurfaceElevation, FreeSurfaceGrid};
+pub use diagnostics::QualityDiagnostics;
+pub use checkpoint::{CheckpointStore, FrequencyRecord, SweepCheckpoint};
+pub use motion::{MotionRaos, MotionSolver};
+pub use limits::FrequencyLimit;
+pub use forward_speed::{ForwardSpeed, ForwardSpeedCoefficients};
+pub use lid::DampingLid;
+pub use control::{discrete_lqr, Controller};
+pub use progress::{CancellationToken, Progress, ProgressSink};
+pub use force_models::{CatenaryMooring, ForceModel, LinearFender, MooringLine};
+pub use froude_krylov::{IncidentWave, NonlinearFroudeKrylov};
+pub use panel_output::PanelOutput;
 
 use thiserror::Error;
+use serde::{Serialize, Deserialize};
 
 /// Error types for BEM operations
 #[derive(Error, Debug)]
@@ -78,6 +142,9 @@ pub enum BEMError {
     #[error("Memory allocation failed")]
     MemoryError,
     
+    #[error("Solve cancelled")]
+    Cancelled,
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -86,13 +153,13 @@ pub enum BEMError {
 pub type Result<T> = std::result::Result<T, BEMError>;
 
 /// Problem types for BEM solver
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProblemType {
     /// Radiation problem
     Radiation {
         /// Wave frequency (rad/s)
         frequency: f64,
-        /// Motion mode (0-5 for 6 DOF)
+        /// Motion mode: 0-5 rigid-body, 6+ generalized; later bodies follow all modes of earlier ones
         mode: usize,
     },
     /// Diffraction problem
@@ -108,26 +175,35 @@ pub enum ProblemType {
         frequency: f64,
         /// Wave direction (radians)
         direction: f64,
-        /// Motion modes to solve
+        /// Motion modes to solve (global indices for multi-body problems)
         modes: Vec<usize>,
     },
 }
 
 /// Solver engine types
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SolverEngine {
     /// Standard BEM solver
     Standard,
     /// Fast multipole method
     FastMultipole,
-    /// Hierarchical matrix method
+    /// Hierarchical matrix method (constant panels; other orders use the dense path)
     HierarchicalMatrix,
     /// Adaptive solver
     Adaptive,
 }
 
+/// Boundary integral formulation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Formulation {
+    /// Source distribution; solves for source strengths (required by drift forces and Kochin functions)
+    Indirect,
+    /// Potential formulation from Green's second identity; solves for the potential directly
+    Direct,
+}
+
 /// BEM solver configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BEMConfig {
     /// Solver engine to use
     pub engine: SolverEngine,
@@ -139,6 +215,37 @@ pub struct BEMConfig {
     pub parallel: bool,
     /// Memory limit (bytes)
     pub memory_limit: Option<usize>,
+    /// Boundary integral formulation
+    pub formulation: Formulation,
+    /// Variation of the unknown over each panel
+    pub panel_order: PanelOrder,
+    /// Wall-clock budget (seconds); iterative solves stop with partial results when exceeded
+    pub time_budget: Option<f64>,
+    /// Geometric symmetry planes exploited in assembly
+    pub symmetry: Symmetry,
+    /// Settings of the hierarchical matrix engine
+    pub hmatrix: HMatrixConfig,
+    /// Linear solver for the influence system
+    pub linear_solver: wavecore_matrices::SolverType,
+    /// Preconditioner of iterative solves (GMRES, BiCGSTAB and the H-matrix engine)
+    pub preconditioning: Preconditioning,
+    /// Cross-check diffraction excitation with the Haskind relations
+    /// (single diffraction solves then also solve every radiation mode)
+    pub haskind: bool,
+    /// Relative tolerance of the quality diagnostics
+    pub diagnostic_tolerance: f64,
+    /// Damping lids on internal free surfaces (constant panels, indirect formulation)
+    pub damping_lids: Vec<DampingLid>,
+    /// Keep the frequency-independent Rankine part of constant-panel matrices
+    /// across the frequencies of a sweep and assemble only the wave part per frequency
+    pub reuse_rankine: bool,
+    /// Vectors of the Krylov recycle space (GCRO-DR) carried between the GMRES
+    /// solves of a sweep; zero disables recycling. With recycling the
+    /// frequencies of a sweep are solved in order, one at a time.
+    pub recycle_space: usize,
+    /// Bit-identical results across runs and thread counts, at some cost in speed
+    /// (see `wavecore_matrices::deterministic`)
+    pub deterministic: bool,
 }
 
 impl Default for BEMConfig {
@@ -149,6 +256,19 @@ impl Default for BEMConfig {
             max_iterations: 1000,
             parallel: true,
             memory_limit: None,
+            formulation: Formulation::Indirect,
+            panel_order: PanelOrder::Constant,
+            time_budget: None,
+            symmetry: Symmetry::None,
+            hmatrix: HMatrixConfig::default(),
+            linear_solver: wavecore_matrices::SolverType::LU,
+            preconditioning: Preconditioning::NearField { neighbours: 16 },
+            haskind: true,
+            diagnostic_tolerance: diagnostics::DEFAULT_DIAGNOSTIC_TOLERANCE,
+            damping_lids: Vec::new(),
+            reuse_rankine: false,
+            recycle_space: 0,
+            deterministic: false,
         }
     }
 }
@@ -156,6 +276,7 @@ impl Default for BEMConfig {
 /// Main BEM solver
 pub struct BEMSolver {
     config: BEMConfig,
+    monitor: progress::Monitor,
 }
 
 impl BEMSolver {
@@ -166,12 +287,25 @@ impl BEMSolver {
                 engine,
                 ..Default::default()
             },
+            monitor: Default::default(),
         }
     }
     
     /// Create a new BEM solver with custom configuration
     pub fn with_config(config: BEMConfig) -> Self {
-        Self { config }
+        Self { config, monitor: Default::default() }
+    }
+    
+    /// Report assembly and sweep progress to `sink`
+    pub fn with_progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.monitor.sink = Some(std::sync::Arc::new(sink));
+        self
+    }
+    
+    /// Stop solves with `BEMError::Cancelled` once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.monitor.token = Some(token);
+        self
     }
     
     /// Get solver configuration
@@ -184,10 +318,22 @@ impl BEMSolver {
         self.config = config;
     }
     
-    /// Solve BEM problem
-    pub fn solve(&self, problem: &ProblemType, mesh: &wavecore_meshes::Mesh) -> Result<solver::BEMResult> {
+    /// Internal solver sharing the configuration and progress monitor
+    fn solver_impl(&self) -> solver::BEMSolverImpl {
+        solver::BEMSolverImpl::new(self.config.clone()).with_monitor(self.monitor.clone())
+    }
+    
+    /// Assembly configuration with the configured linear solver
+    fn assembly_config(&self) -> solver::AssemblyConfig {
+        solver::AssemblyConfig {
+            solver_type: self.config.linear_solver,
+            ..Default::default()
+        }
+    }
+    
+    /// Floating body with simplified mass properties for the mesh-only entry points
+    fn mesh_body(mesh: &wavecore_meshes::Mesh) -> Result<wavecore_bodies::FloatingBody> {
         use wavecore_bodies::{FloatingBody, MassProperties};
-        use solver::{BEMProblem, BEMSolverImpl, AssemblyConfig};
         
         // Create mass properties (simplified for now)
         let mass_props = MassProperties {
@@ -196,22 +342,78 @@ impl BEMSolver {
             inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
         };
         
-        // Create a floating body with the mesh
-        let body = FloatingBody::with_mesh(
-            "solver_body".to_string(),
-            mass_props,
-            mesh.clone()
-        )?;
+        Ok(FloatingBody::with_mesh("solver_body".to_string(), mass_props, mesh.clone())?)
+    }
+    
+    /// Solve BEM problem
+    pub fn solve(&self, problem: &ProblemType, mesh: &wavecore_meshes::Mesh) -> Result<solver::BEMResult> {
+        self.solve_bodies(problem, vec![Self::mesh_body(mesh)?])
+    }
+    
+    /// Solve radiation for every mode and diffraction for every direction over a frequency sweep
+    pub fn solve_sweep(&self, frequencies: &[f64], directions: &[f64], mesh: &wavecore_meshes::Mesh) -> Result<HydroCoefficients> {
+        self.solve_sweep_bodies(frequencies, directions, vec![Self::mesh_body(mesh)?])
+    }
+    
+    /// Frequency sweep with automatic refinement near resonances
+    pub fn solve_adaptive_sweep(&self, frequencies: &[f64], directions: &[f64], mesh: &wavecore_meshes::Mesh, refinement: &AdaptiveRefinement) -> Result<HydroCoefficients> {
+        let body = Self::mesh_body(mesh)?;
+        let solver_impl = self.solver_impl();
+        solver_impl.solve_adaptive_sweep(vec![body], frequencies, directions, self.assembly_config(), refinement)
+    }
+    
+    /// Frequency sweep for hydrodynamically interacting bodies
+    pub fn solve_sweep_bodies(&self, frequencies: &[f64], directions: &[f64], bodies: Vec<wavecore_bodies::FloatingBody>) -> Result<HydroCoefficients> {
+        let solver_impl = self.solver_impl();
+        solver_impl.solve_sweep(bodies, frequencies, directions, self.assembly_config())
+    }
+    
+    /// Frequency sweep that checkpoints completed frequencies to `store`
+    /// every `interval` frequencies
+    pub fn solve_sweep_checkpointed(
+        &self,
+        frequencies: &[f64],
+        directions: &[f64],
+        bodies: Vec<wavecore_bodies::FloatingBody>,
+        store: &dyn CheckpointStore,
+        interval: usize,
+    ) -> Result<HydroCoefficients> {
+        let solver_impl = self.solver_impl();
+        solver_impl.solve_sweep_checkpointed(bodies, frequencies, directions, self.assembly_config(), store, interval)
+    }
+    
+    /// Resume an interrupted checkpointed sweep of the same bodies
+    pub fn resume_from(&self, store: &dyn CheckpointStore, bodies: Vec<wavecore_bodies::FloatingBody>) -> Result<HydroCoefficients> {
+        let solver_impl = self.solver_impl();
+        solver_impl.resume_sweep(bodies, self.assembly_config(), store)
+    }
+    
+    /// Added mass in the zero-frequency (rigid-lid) limit
+    pub fn solve_added_mass_zero_freq(&self, bodies: Vec<wavecore_bodies::FloatingBody>) -> Result<wavecore_matrices::Matrix> {
+        let solver_impl = self.solver_impl();
+        solver_impl.solve_limit_added_mass(bodies, self.assembly_config(), FrequencyLimit::Zero)
+    }
+    
+    /// Added mass in the infinite-frequency (double-body) limit
+    pub fn solve_added_mass_inf_freq(&self, bodies: Vec<wavecore_bodies::FloatingBody>) -> Result<wavecore_matrices::Matrix> {
+        let solver_impl = self.solver_impl();
+        solver_impl.solve_limit_added_mass(bodies, self.assembly_config(), FrequencyLimit::Infinite)
+    }
+    
+    /// Solve BEM problem for hydrodynamically interacting bodies
+    ///
+    /// Coefficient matrices are 6N×6N, with block (i, j) coupling body i to body j.
+    pub fn solve_bodies(&self, problem: &ProblemType, bodies: Vec<wavecore_bodies::FloatingBody>) -> Result<solver::BEMResult> {
+        use solver::BEMProblem;
         
-        // Convert ProblemType to BEMProblem
         let bem_problem = BEMProblem {
-            body,
+            bodies,
             problem_type: problem.clone(),
-            assembly_config: AssemblyConfig::default(),
+            assembly_config: self.assembly_config(),
         };
         
         // Use internal solver implementation
-        let solver_impl = BEMSolverImpl::new(self.config.clone());
+        let solver_impl = self.solver_impl();
         let result = solver_impl.solve(&bem_problem)?;
         
         Ok(result)
@@ -221,34 +423,59 @@ impl BEMSolver {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+    use crate::fixtures::tetrahedron_body;
+
     #[test]
     fn test_bem_solver_creation() {
         let solver = BEMSolver::new(SolverEngine::Standard);
         assert_eq!(solver.config().engine as usize, SolverEngine::Standard as usize);
     }
-    
+
     #[test]
     fn test_problem_types() {
         let radiation = ProblemType::Radiation {
             frequency: 1.0,
             mode: 0,
         };
-        
+
         let diffraction = ProblemType::Diffraction {
             frequency: 1.0,
             direction: 0.0,
         };
-        
+
         let combined = ProblemType::Combined {
             frequency: 1.0,
             direction: 0.0,
             modes: vec![0, 1, 2],
         };
-        
+
         // Just test that they can be created
         assert!(matches!(radiation, ProblemType::Radiation { .. }));
         assert!(matches!(diffraction, ProblemType::Diffraction { .. }));
         assert!(matches!(combined, ProblemType::Combined { .. }));
     }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let problem = ProblemType::Diffraction { frequency: 1.0, direction: 0.3 };
+        let config = BEMConfig { formulation: Formulation::Direct, ..Default::default() };
+        let json = serde_json::to_string(&(&problem, &config)).unwrap();
+        let (problem, config): (ProblemType, BEMConfig) = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.formulation, Formulation::Direct);
+
+        let result = BEMSolver::with_config(config).solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)]).unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        let reloaded: solver::BEMResult = serde_json::from_str(&json).unwrap();
+        // JSON floats round-trip to within an ulp
+        let close = |a: &[num_complex::Complex64], b: &[num_complex::Complex64]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).norm() <= 1e-12 * y.norm())
+        };
+        assert!(close(&reloaded.potential, &result.potential));
+        assert!(close(reloaded.excitation_force().unwrap(), result.excitation_force().unwrap()));
+        assert_eq!(reloaded.diagnostics.haskind_discrepancy.is_some(), result.diagnostics.haskind_discrepancy.is_some());
+
+        let point = [nalgebra::Point3::new(3.0, 1.0, -0.5)];
+        let field = reloaded.evaluate_field(&point).unwrap();
+        assert!(close(&field.potential, &result.evaluate_field(&point).unwrap().potential));
+    }
 }
@@ -0,0 +1,161 @@
+//! Damping lids on internal free surfaces
+//!
+//! Moonpools and narrow gaps between side-by-side vessels trap standing
+//! waves whose amplitude potential flow overpredicts by an order of
+//! magnitude. A damping lid covers the trapped free surface with panels
+//! carrying the dissipative free-surface condition
+//!
+//! ∂φ/∂z = K(1 + iε) φ,  K = ω²/g,
+//!
+//! obtained from a Newtonian friction εω in the kinematic condition for the
+//! e^{-iωt} time dependence. ε = 0 recovers the ordinary free surface;
+//! values of 0.01-0.1 are typical and are tuned against model tests or CFD.
+//! Lids have no modes and carry no pressure force.
+
+use super::*;
+use wavecore_meshes::{Mesh, Point};
+
+/// Panels on an internal free surface with a dissipation parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DampingLid {
+    /// Lid vertices, all in the plane z = 0
+    pub vertices: Vec<Point>,
+    /// Triangles, oriented so normals point down into the fluid
+    pub faces: Vec<[usize; 3]>,
+    /// Dissipation parameter ε (0 for an undamped free surface)
+    pub damping: f64,
+}
+
+impl DampingLid {
+    /// Create a lid from a triangulation of the internal free surface
+    ///
+    /// Faces are reoriented to point down into the fluid.
+    pub fn new(vertices: Vec<Point>, faces: Vec<[usize; 3]>, damping: f64) -> Result<Self> {
+        let invalid = |message: String| Err(BEMError::InvalidProblem { message });
+        if faces.is_empty() {
+            return invalid("Damping lid has no faces".to_string());
+        }
+        if !(damping >= 0.0 && damping.is_finite()) {
+            return invalid(format!("Damping lid dissipation {} must be finite and non-negative", damping));
+        }
+        if let Some(v) = vertices.iter().find(|v| v.z.abs() > 1e-9) {
+            return invalid(format!("Damping lid vertex at z = {} is off the free surface", v.z));
+        }
+        let mut oriented = Vec::with_capacity(faces.len());
+        for &[a, b, c] in &faces {
+            if [a, b, c].iter().any(|&i| i >= vertices.len()) {
+                return invalid(format!("Damping lid face {:?} references a missing vertex", [a, b, c]));
+            }
+            let normal = (vertices[b] - vertices[a]).cross(&(vertices[c] - vertices[a]));
+            if normal.z.abs() < 1e-12 {
+                return invalid(format!("Damping lid face {:?} is degenerate", [a, b, c]));
+            }
+            oriented.push(if normal.z > 0.0 { [a, c, b] } else { [a, b, c] });
+        }
+        Ok(Self {
+            vertices,
+            faces: oriented,
+            damping,
+        })
+    }
+
+    /// Rectangular lid of `length` × `width` centred at (x, y), with nx × ny cells
+    pub fn rectangle(center: [f64; 2], length: f64, width: f64, nx: usize, ny: usize, damping: f64) -> Result<Self> {
+        if nx == 0 || ny == 0 {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Damping lid needs at least one cell, got {} × {}", nx, ny),
+            });
+        }
+        let (x0, y0) = (center[0] - 0.5 * length, center[1] - 0.5 * width);
+        let vertices = (0..=ny)
+            .flat_map(|j| (0..=nx).map(move |i| (i, j)))
+            .map(|(i, j)| Point::new(x0 + length * i as f64 / nx as f64, y0 + width * j as f64 / ny as f64, 0.0))
+            .collect();
+        let index = |i: usize, j: usize| j * (nx + 1) + i;
+        let faces = (0..ny)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .flat_map(|(i, j)| {
+                [
+                    [index(i, j), index(i + 1, j), index(i + 1, j + 1)],
+                    [index(i, j), index(i + 1, j + 1), index(i, j + 1)],
+                ]
+            })
+            .collect();
+        Self::new(vertices, faces, damping)
+    }
+
+    /// Lid over the moonpool openings of a hull floating at z = 0
+    ///
+    /// The openings are triangulated with edges no longer than `panel_size`.
+    pub fn moonpool(hull: &Mesh, panel_size: f64, damping: f64) -> Result<Self> {
+        let lid = wavecore_meshes::generate_moonpool_lid(hull, 0.0, panel_size)?;
+        Self::new(lid.vertices, lid.faces, damping)
+    }
+
+    /// Lid area
+    pub fn area(&self) -> f64 {
+        self.faces
+            .iter()
+            .map(|&[a, b, c]| 0.5 * (self.vertices[b] - self.vertices[a]).cross(&(self.vertices[c] - self.vertices[a])).norm())
+            .sum()
+    }
+
+    /// Panel mesh of the lid
+    pub fn mesh(&self) -> Result<Mesh> {
+        Ok(Mesh::new(self.vertices.clone(), self.faces.clone())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_lid_orientation_and_validation() {
+        let lid = DampingLid::rectangle([10.0, 0.0], 4.0, 2.0, 4, 2, 0.05).unwrap();
+        assert_eq!(lid.faces.len(), 16);
+        assert!((lid.area() - 8.0).abs() < 1e-12);
+        let mut mesh = lid.mesh().unwrap();
+        assert!(mesh.panels().unwrap().iter().all(|p| (p.normal().z + 1.0).abs() < 1e-12));
+
+        let raised = vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(0.0, 1.0, 0.5)];
+        assert!(DampingLid::new(raised, vec![[0, 1, 2]], 0.05).is_err());
+        assert!(DampingLid::rectangle([0.0, 0.0], 1.0, 1.0, 1, 1, -0.1).is_err());
+
+        let solid = wavecore_meshes::PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        assert!(DampingLid::moonpool(&solid, 0.5, 0.05).is_err());
+    }
+
+    #[test]
+    fn test_damping_lid_in_gap() {
+        let bodies = vec![tetrahedron_body("a", 0.0), tetrahedron_body("b", 3.0)];
+        let problem = ProblemType::Diffraction { frequency: 1.2, direction: 0.5 };
+        let with_lid = |damping: f64, formulation: Formulation| {
+            let config = BEMConfig {
+                formulation,
+                damping_lids: vec![DampingLid::rectangle([1.5, 0.0], 1.0, 2.0, 2, 2, damping).unwrap()],
+                ..Default::default()
+            };
+            BEMSolver::with_config(config).solve_bodies(&problem, bodies.clone())
+        };
+
+        let undamped = with_lid(0.0, Formulation::Indirect).unwrap();
+        let damped = with_lid(0.5, Formulation::Indirect).unwrap();
+        assert_eq!(damped.potential().len(), 4 + 4 + 8);
+        let (f0, f1) = (undamped.excitation_force().unwrap(), damped.excitation_force().unwrap());
+        assert_eq!(f1.len(), 12);
+        assert!(f1.iter().all(|f| f.is_finite()));
+        assert!(f0.iter().zip(f1).any(|(a, b)| (a - b).norm() > 1e-9 * a.norm().max(1.0)));
+
+        let radiation = BEMSolver::with_config(BEMConfig {
+            damping_lids: vec![DampingLid::rectangle([1.5, 0.0], 1.0, 2.0, 2, 2, 0.1).unwrap()],
+            ..Default::default()
+        })
+        .solve_bodies(&ProblemType::Radiation { frequency: 1.2, mode: 2 }, bodies.clone())
+        .unwrap();
+        assert!(radiation.damping().unwrap().get(2, 2).unwrap().is_finite());
+
+        assert!(with_lid(0.1, Formulation::Direct).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Zero- and infinite-frequency radiation limits
+//!
+//! At both limits the wave term of the free-surface Green function vanishes
+//! and the free-surface condition reduces to a wall or a pressure-release
+//! surface, represented by the image of each source in z = 0:
+//! - ω → 0: ∂φ/∂z = 0 (rigid lid), G = -(1/r + 1/r')/4π;
+//! - ω → ∞: φ = 0 (double body), G = -(1/r - 1/r')/4π.
+//!
+//! The radiation potentials are real, so the limits carry added mass only.
+//! Cummins time-domain models need A(∞) for the instantaneous response and
+//! A(0) to check the retardation functions.
+
+use serde::{Deserialize, Serialize};
+use wavecore_meshes::{Point, Vector};
+
+/// Limit of the radiation problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrequencyLimit {
+    /// ω → 0, rigid-lid free surface
+    Zero,
+    /// ω → ∞, pressure-release free surface
+    Infinite,
+}
+
+impl FrequencyLimit {
+    /// Sign of the image source
+    fn image_sign(self) -> f64 {
+        match self {
+            FrequencyLimit::Zero => 1.0,
+            FrequencyLimit::Infinite => -1.0,
+        }
+    }
+
+    /// Image-source part of G at `x` for a source at `xi`
+    pub fn image_green(self, x: &Point, xi: &Point) -> f64 {
+        let image = Point::new(xi.x, xi.y, -xi.z);
+        -self.image_sign() / (4.0 * std::f64::consts::PI * (x - image).norm())
+    }
+
+    /// G at `x` for a source at `xi`
+    pub fn green(self, x: &Point, xi: &Point) -> f64 {
+        -1.0 / (4.0 * std::f64::consts::PI * (x - xi).norm()) + self.image_green(x, xi)
+    }
+
+    /// Image-source part of ∇G with respect to the field point `x`
+    pub fn image_field_gradient(self, x: &Point, xi: &Point) -> Vector {
+        let d = x - Point::new(xi.x, xi.y, -xi.z);
+        d * (self.image_sign() / (4.0 * std::f64::consts::PI * d.norm().powi(3)))
+    }
+
+    /// ∇G with respect to the field point `x`
+    pub fn field_gradient(self, x: &Point, xi: &Point) -> Vector {
+        let d = x - xi;
+        d / (4.0 * std::f64::consts::PI * d.norm().powi(3)) + self.image_field_gradient(x, xi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BEMSolver, SolverEngine};
+
+    #[test]
+    fn test_free_surface_conditions() {
+        let xi = Point::new(0.3, -0.2, -1.5);
+        let surface = Point::new(1.0, 0.5, 0.0);
+        assert!(FrequencyLimit::Infinite.green(&surface, &xi).abs() < 1e-15);
+        assert!(FrequencyLimit::Zero.field_gradient(&surface, &xi).z.abs() < 1e-15);
+
+        let x = Point::new(0.7, 0.1, -0.8);
+        let h = 1e-6;
+        let numerical = (FrequencyLimit::Zero.green(&(x + Vector::new(h, 0.0, 0.0)), &xi)
+            - FrequencyLimit::Zero.green(&(x - Vector::new(h, 0.0, 0.0)), &xi))
+            / (2.0 * h);
+        assert!((numerical - FrequencyLimit::Zero.field_gradient(&x, &xi).x).abs() < 1e-7);
+    }
+
+    /// Sphere of radius 1 centred at depth `depth`, outward normals
+    fn sphere_body(depth: f64) -> wavecore_bodies::FloatingBody {
+        let sphere = wavecore_meshes::PredefinedGeometry::sphere(1.0, 16, 8).unwrap();
+        let vertices: Vec<_> = sphere.vertices.iter().map(|v| nalgebra::Point3::new(v.x, v.y, v.z - depth)).collect();
+        let faces: Vec<[usize; 3]> = sphere
+            .faces
+            .iter()
+            .filter_map(|&[a, b, c]| {
+                let (pa, pb, pc) = (sphere.vertices[a], sphere.vertices[b], sphere.vertices[c]);
+                let normal = (pb - pa).cross(&(pc - pa));
+                if normal.norm() < 1e-12 {
+                    None
+                } else if normal.dot(&(pa.coords + pb.coords + pc.coords)) > 0.0 {
+                    Some([a, b, c])
+                } else {
+                    Some([a, c, b])
+                }
+            })
+            .collect();
+        let mesh = wavecore_meshes::Mesh::new(vertices, faces).unwrap();
+        let mass_props = wavecore_bodies::MassProperties {
+            mass: 4300.0,
+            center_of_gravity: [0.0, 0.0, -depth],
+            inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        };
+        wavecore_bodies::FloatingBody::with_mesh("sphere".to_string(), mass_props, mesh).unwrap()
+    }
+
+    #[test]
+    fn test_limit_added_mass() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        // Deeply submerged: both limits tend to half the displaced mass
+        let unbounded = 2.0 / 3.0 * std::f64::consts::PI * 1025.0;
+        for a in [solver.solve_added_mass_zero_freq(vec![sphere_body(50.0)]).unwrap(), solver.solve_added_mass_inf_freq(vec![sphere_body(50.0)]).unwrap()] {
+            for k in 0..3 {
+                assert!((a.get(k, k).unwrap() - unbounded).abs() < 0.05 * unbounded);
+            }
+        }
+
+        // Near the surface the wall image raises heave added mass and the
+        // pressure-release image lowers it
+        let zero = solver.solve_added_mass_zero_freq(vec![sphere_body(1.5)]).unwrap();
+        let infinite = solver.solve_added_mass_inf_freq(vec![sphere_body(1.5)]).unwrap();
+        assert!(zero.get(2, 2).unwrap() > 1.05 * unbounded);
+        assert!(infinite.get(2, 2).unwrap() < 0.95 * unbounded);
+    }
+}
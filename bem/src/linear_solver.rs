@@ -1,8 +1,7 @@
 //! BEM linear solver
 
-use super::*;
-
 /// BEM linear solver
+#[allow(dead_code)]
 pub struct BEMLinearSolver {
     solver_type: wavecore_matrices::SolverType,
 }
@@ -0,0 +1,264 @@
+//! Equations of motion and response amplitude operators
+//!
+//! Body motions ξ per unit wave amplitude solve, for the e^{-iωt} time
+//! dependence used by the solver,
+//!
+//! [-ω²(M + A(ω)) - iω(B(ω) + B_ext) + C + C_ext] ξ = F(ω, β)
+//!
+//! with M the body mass matrix, A and B the added mass and radiation damping,
+//! C the hydrostatic stiffness and F the wave excitation. Rigid-body modes
+//! rotate about each body's centre of gravity, matching the radiation
+//! boundary conditions of the solver.
+
+use super::*;
+use crate::coefficients::HydroCoefficients;
+use ndarray::{Array2, Array3, ArrayView1, ArrayView2};
+use num_complex::Complex64;
+use wavecore_bodies::FloatingBody;
+use wavecore_matrices::{complex_lu_solve, ComplexMatrix};
+
+/// Motion RAOs over a frequency/direction grid
+#[derive(Debug, Clone)]
+pub struct MotionRaos {
+    /// Wave frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Complex motion per unit wave amplitude, shape (frequency, direction, mode);
+    /// NaN where the coefficients were not computed
+    pub values: Array3<Complex64>,
+}
+
+impl MotionRaos {
+    /// Complex RAOs of all modes at frequency and direction indices
+    pub fn at(&self, frequency: usize, direction: usize) -> ArrayView1<'_, Complex64> {
+        self.values.slice(ndarray::s![frequency, direction, ..])
+    }
+
+    /// RAO amplitude of `mode` over frequency at direction index `direction`
+    pub fn amplitude(&self, direction: usize, mode: usize) -> Vec<f64> {
+        self.values.slice(ndarray::s![.., direction, mode]).iter().map(|x| x.norm()).collect()
+    }
+
+    /// RAO phase (radians) of `mode` over frequency at direction index `direction`
+    pub fn phase(&self, direction: usize, mode: usize) -> Vec<f64> {
+        self.values.slice(ndarray::s![.., direction, mode]).iter().map(|x| x.arg()).collect()
+    }
+}
+
+/// Solver of the frequency-domain equations of motion
+#[derive(Debug, Clone)]
+pub struct MotionSolver {
+    /// Body mass matrix M
+    pub mass: Array2<f64>,
+    /// Hydrostatic stiffness C
+    pub stiffness: Array2<f64>,
+    /// Additional linear damping B_ext (e.g. viscous or PTO damping)
+    pub external_damping: Array2<f64>,
+    /// Additional stiffness C_ext (e.g. moorings)
+    pub external_stiffness: Array2<f64>,
+}
+
+impl MotionSolver {
+    /// Create a solver from mass and stiffness matrices of equal square shape
+    pub fn new(mass: Array2<f64>, stiffness: Array2<f64>) -> Result<Self> {
+        let n = mass.nrows();
+        if mass.dim() != (n, n) || stiffness.dim() != (n, n) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Mass {:?} and stiffness {:?} must be square and equal in shape", mass.dim(), stiffness.dim()),
+            });
+        }
+        Ok(Self {
+            mass,
+            stiffness,
+            external_damping: Array2::zeros((n, n)),
+            external_stiffness: Array2::zeros((n, n)),
+        })
+    }
+
+    /// Block-diagonal mass and hydrostatic stiffness of bodies, in global mode order
+    ///
+    /// The restoring matrices are those stored on the bodies, e.g. by
+    /// `FloatingBody::compute_hydrostatics`. Generalized modes contribute
+    /// their modal mass and stiffness.
+    pub fn from_bodies(bodies: &[FloatingBody]) -> Result<Self> {
+        let n: usize = bodies.iter().map(|body| body.n_modes()).sum();
+        let mut mass = Array2::zeros((n, n));
+        let mut stiffness = Array2::zeros((n, n));
+        let mut offset = 0;
+        for body in bodies {
+            let properties = &body.mass_properties;
+            for k in 0..3 {
+                mass[[offset + k, offset + k]] = properties.mass;
+                for l in 0..3 {
+                    mass[[offset + 3 + k, offset + 3 + l]] = properties.inertia_matrix[k][l];
+                }
+            }
+            for (k, row) in body.hydrostatic_stiffness().iter().enumerate() {
+                for (l, value) in row.iter().enumerate() {
+                    stiffness[[offset + k, offset + l]] = *value;
+                }
+            }
+            for (j, mode) in body.generalized_modes.iter().enumerate() {
+                mass[[offset + 6 + j, offset + 6 + j]] = mode.modal_mass;
+                stiffness[[offset + 6 + j, offset + 6 + j]] = mode.modal_stiffness;
+            }
+            offset += body.n_modes();
+        }
+        Self::new(mass, stiffness)
+    }
+
+    /// Add linear damping outside the potential-flow model
+    pub fn with_external_damping(mut self, damping: Array2<f64>) -> Result<Self> {
+        self.check_shape(damping.dim())?;
+        self.external_damping = damping;
+        Ok(self)
+    }
+
+    /// Add stiffness outside the hydrostatics (e.g. moorings)
+    pub fn with_external_stiffness(mut self, stiffness: Array2<f64>) -> Result<Self> {
+        self.check_shape(stiffness.dim())?;
+        self.external_stiffness = stiffness;
+        Ok(self)
+    }
+
+    /// Number of degrees of freedom
+    pub fn n_dof(&self) -> usize {
+        self.mass.nrows()
+    }
+
+    /// Complex motion amplitudes at one frequency and heading
+    pub fn solve_frequency(
+        &self,
+        frequency: f64,
+        added_mass: ArrayView2<f64>,
+        damping: ArrayView2<f64>,
+        excitation: ArrayView1<Complex64>,
+    ) -> Result<Vec<Complex64>> {
+        let n = self.n_dof();
+        self.check_shape(added_mass.dim())?;
+        self.check_shape(damping.dim())?;
+        if excitation.len() != n {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Excitation has {} modes, expected {}", excitation.len(), n),
+            });
+        }
+
+        let omega = frequency;
+        let mut system = ComplexMatrix::new(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                let re = -omega * omega * (self.mass[[i, j]] + added_mass[[i, j]])
+                    + self.stiffness[[i, j]]
+                    + self.external_stiffness[[i, j]];
+                let im = -omega * (damping[[i, j]] + self.external_damping[[i, j]]);
+                system.set(i, j, Complex64::new(re, im))?;
+            }
+        }
+        Ok(complex_lu_solve(&system, &excitation.to_vec())?)
+    }
+
+    /// RAOs at every frequency and direction of a coefficient dataset
+    ///
+    /// Frequencies that were not computed (e.g. skipped by a time budget)
+    /// are left as NaN.
+    pub fn raos(&self, coefficients: &HydroCoefficients) -> Result<MotionRaos> {
+        let n = self.n_dof();
+        if coefficients.n_dof() != n {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Coefficients have {} modes, expected {}", coefficients.n_dof(), n),
+            });
+        }
+        let (nf, nd) = (coefficients.frequencies.len(), coefficients.directions.len());
+        let mut values = Array3::from_elem((nf, nd, n), Complex64::new(f64::NAN, f64::NAN));
+        for (f, &frequency) in coefficients.frequencies.iter().enumerate() {
+            if !coefficients.completed[f] {
+                continue;
+            }
+            let added_mass = coefficients.added_mass.index_axis(ndarray::Axis(0), f);
+            let damping = coefficients.damping.index_axis(ndarray::Axis(0), f);
+            for d in 0..nd {
+                let motion = self.solve_frequency(frequency, added_mass, damping, coefficients.excitation_at(f, d))?;
+                values.slice_mut(ndarray::s![f, d, ..]).assign(&ArrayView1::from(&motion));
+            }
+        }
+        Ok(MotionRaos {
+            frequencies: coefficients.frequencies.clone(),
+            directions: coefficients.directions.clone(),
+            values,
+        })
+    }
+
+    fn check_shape(&self, dim: (usize, usize)) -> Result<()> {
+        let n = self.n_dof();
+        if dim != (n, n) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Matrix of shape {:?} does not match {} modes", dim, n),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_uncoupled_modes_match_single_dof_response() {
+        let n = 6;
+        let mass = Array2::from_diag(&ndarray::Array1::from_elem(n, 1000.0));
+        let stiffness = Array2::from_diag(&ndarray::Array1::from_vec(vec![0.0, 0.0, 8000.0, 2000.0, 2000.0, 0.0]));
+        let solver = MotionSolver::new(mass, stiffness)
+            .unwrap()
+            .with_external_damping(Array2::from_diag(&ndarray::Array1::from_elem(n, 50.0)))
+            .unwrap();
+
+        let mut coefficients = HydroCoefficients::new(vec![0.5, 1.0], vec![0.0], n);
+        let added_mass = Array2::from_diag(&ndarray::Array1::from_elem(n, 300.0));
+        let damping = Array2::from_diag(&ndarray::Array1::from_elem(n, 120.0));
+        let excitation: Vec<Complex64> = (0..n).map(|k| Complex64::new(100.0 * k as f64, -40.0)).collect();
+        coefficients.set_frequency(0, added_mass.as_slice().unwrap(), damping.as_slice().unwrap(), &excitation, true);
+
+        let raos = solver.raos(&coefficients).unwrap();
+        assert_eq!(raos.values.shape(), &[2, 1, 6]);
+        let omega = 0.5;
+        for (k, force) in excitation.iter().enumerate() {
+            let impedance = Complex64::new(-omega * omega * 1300.0 + solver.stiffness[[k, k]], -omega * 170.0);
+            assert!((raos.at(0, 0)[k] - force / impedance).norm() < 1e-12);
+        }
+        assert!(raos.amplitude(0, 2)[1].is_nan());
+        assert!(solver.with_external_stiffness(Array2::zeros((3, 3))).is_err());
+    }
+
+    #[test]
+    fn test_motion_raos_from_sweep() {
+        let mut body = tetrahedron_body("a", 0.0);
+        body.hydrostatic_properties.hydrostatic_stiffness[2][2] = 1025.0 * 9.81 * 2.0;
+        let sweep = BEMSolver::new(SolverEngine::Standard)
+            .solve_sweep_bodies(&[0.6, 1.2], &[0.0], vec![body.clone()])
+            .unwrap();
+        let solver = MotionSolver::from_bodies(&[body]).unwrap();
+        assert_eq!(solver.mass[[0, 0]], 1000.0);
+        assert_eq!(solver.stiffness[[2, 2]], 1025.0 * 9.81 * 2.0);
+
+        let raos = solver.raos(&sweep).unwrap();
+        assert_eq!(raos.values.shape(), &[2, 1, 6]);
+
+        // The motions satisfy the equations of motion
+        let omega = sweep.frequencies[1];
+        let (a, b) = (sweep.added_mass_at(1), sweep.damping_at(1));
+        let motion = raos.at(1, 0);
+        for i in 0..6 {
+            let force: num_complex::Complex64 = (0..6)
+                .map(|j| {
+                    let z = num_complex::Complex64::new(-omega * omega * (solver.mass[[i, j]] + a[[i, j]]) + solver.stiffness[[i, j]], -omega * b[[i, j]]);
+                    z * motion[j]
+                })
+                .sum();
+            let expected = sweep.excitation_at(1, 0)[i];
+            assert!((force - expected).norm() <= 1e-8 * (1.0 + expected.norm()));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Near-field mean drift forces by direct pressure integration
+//!
+//! Pinkster's method integrates the mean second-order pressure over the mean
+//! wetted surface. With complex first-order amplitudes (time dependence
+//! e^{-iωt}, mean of a product = ½ Re(a b*)) and normals pointing out of the
+//! body, the mean force is the sum of
+//!
+//! - waterline term: −¼ ρg ∮ |ζ_r|² n dl, with ζ_r the relative wave elevation
+//! - velocity-squared term: ¼ ρ ∫∫ |∇φ|² n dS
+//! - motion term: ½ ρ Re ∫∫ (X · ∇φ_t)* n dS, with X the panel displacement
+//! - rotation term: ½ Re(α × F⁽¹⁾*), with F⁽¹⁾ = −ω² M ξ the first-order inertia force
+//!
+//! Unlike the far-field method this yields all six components, including the
+//! vertical force and roll/pitch moments.
+
+use num_complex::Complex64;
+use serde::{Serialize, Deserialize};
+use wavecore_meshes::{Panel, Point, Vector};
+
+/// Complex 3-vector
+pub type ComplexVector = [Complex64; 3];
+
+/// Segment of the mean waterline
+#[derive(Debug, Clone)]
+pub struct WaterlineSegment {
+    /// Segment start
+    pub start: Point,
+    /// Segment end
+    pub end: Point,
+    /// Horizontal unit normal pointing out of the body
+    pub normal: Vector,
+}
+
+impl WaterlineSegment {
+    /// Segment midpoint
+    pub fn midpoint(&self) -> Point {
+        Point::from((self.start.coords + self.end.coords) * 0.5)
+    }
+
+    /// Segment length
+    pub fn length(&self) -> f64 {
+        (self.end - self.start).norm()
+    }
+}
+
+/// Extract waterline segments from panel edges lying in the plane z = 0
+pub fn waterline_segments(panels: &[Panel], tolerance: f64) -> Vec<WaterlineSegment> {
+    let mut segments = Vec::new();
+    for panel in panels {
+        let v = panel.corners();
+        let n = panel.normal();
+        let horizontal = Vector::new(n.x, n.y, 0.0);
+        if horizontal.norm() < 1e-12 {
+            continue;
+        }
+        for (a, b) in (0..v.len()).map(|a| (a, (a + 1) % v.len())) {
+            if v[a].z.abs() <= tolerance && v[b].z.abs() <= tolerance {
+                segments.push(WaterlineSegment {
+                    start: v[a],
+                    end: v[b],
+                    normal: horizontal.normalize(),
+                });
+            }
+        }
+    }
+    segments
+}
+
+/// First-order solution needed by the near-field method
+#[derive(Debug, Clone)]
+pub struct NearFieldInput<'a> {
+    /// Wetted panels
+    pub panels: &'a [Panel],
+    /// Total fluid velocity ∇φ at each panel centroid
+    pub velocity: &'a [ComplexVector],
+    /// Mean waterline
+    pub waterline: &'a [WaterlineSegment],
+    /// Wave elevation at each waterline segment midpoint
+    pub elevation: &'a [Complex64],
+    /// Rigid-body motions (surge, sway, heave, roll, pitch, yaw); `None` for a fixed body
+    pub motions: Option<[Complex64; 6]>,
+    /// Reference point of the motions and moments
+    pub reference: Point,
+    /// Body mass matrix about the reference point (for the rotation term)
+    pub mass_matrix: [[f64; 6]; 6],
+    /// Wave frequency (rad/s)
+    pub frequency: f64,
+}
+
+/// Near-field mean drift, split by contribution; each entry is
+/// (Fx, Fy, Fz, Mx, My, Mz) per unit wave amplitude squared
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NearFieldDrift {
+    /// Relative wave elevation along the waterline
+    pub waterline: [f64; 6],
+    /// Quadratic velocity pressure
+    pub velocity: [f64; 6],
+    /// First-order pressure gradient times body motion
+    pub motion: [f64; 6],
+    /// Rotation of the first-order force
+    pub rotation: [f64; 6],
+}
+
+impl NearFieldDrift {
+    /// Sum of all contributions
+    pub fn total(&self) -> [f64; 6] {
+        let mut total = [0.0; 6];
+        for term in [&self.waterline, &self.velocity, &self.motion, &self.rotation] {
+            for (t, v) in total.iter_mut().zip(term.iter()) {
+                *t += v;
+            }
+        }
+        total
+    }
+}
+
+/// Mean drift force and moment by direct pressure integration
+pub fn near_field_drift(input: &NearFieldInput, density: f64, gravity: f64) -> NearFieldDrift {
+    let omega = input.frequency;
+    let motions = input.motions.unwrap_or([Complex64::new(0.0, 0.0); 6]);
+    let translation = [motions[0], motions[1], motions[2]];
+    let rotation = [motions[3], motions[4], motions[5]];
+    let reference = input.reference;
+
+    // Complex displacement of a body point
+    let displacement = |p: &Point| -> ComplexVector {
+        let r = [p.x - reference.x, p.y - reference.y, p.z - reference.z];
+        let rot = cross_complex_real(&rotation, &r);
+        [translation[0] + rot[0], translation[1] + rot[1], translation[2] + rot[2]]
+    };
+
+    let mut waterline = [0.0; 6];
+    for (segment, zeta) in input.waterline.iter().zip(input.elevation) {
+        let mid = segment.midpoint();
+        let vertical = displacement(&mid)[2];
+        let relative = zeta - vertical;
+        let force = segment.normal * (-0.25 * density * gravity * relative.norm_sqr() * segment.length());
+        accumulate(&mut waterline, &mid, &reference, &force);
+    }
+
+    let mut velocity = [0.0; 6];
+    let mut motion = [0.0; 6];
+    for (panel, grad) in input.panels.iter().zip(input.velocity) {
+        let c = panel.centroid();
+        let n = panel.normal();
+        let area = panel.area();
+
+        let speed_sq: f64 = grad.iter().map(|g| g.norm_sqr()).sum();
+        accumulate(&mut velocity, &c, &reference, &(n * (0.25 * density * speed_sq * area)));
+
+        // ∇φ_t = −iω ∇φ
+        let x = displacement(&c);
+        let dot: Complex64 = x.iter().zip(grad).map(|(xi, g)| xi * (Complex64::new(0.0, -omega) * g).conj()).sum();
+        accumulate(&mut motion, &c, &reference, &(n * (0.5 * density * dot.re * area)));
+    }
+
+    // First-order inertia force −ω² M ξ and its rotation
+    let mut first_order = [Complex64::new(0.0, 0.0); 6];
+    for (i, f) in first_order.iter_mut().enumerate() {
+        *f = (0..6).map(|j| motions[j] * input.mass_matrix[i][j]).sum::<Complex64>() * (-omega * omega);
+    }
+    let force = [first_order[0].conj(), first_order[1].conj(), first_order[2].conj()];
+    let moment = [first_order[3].conj(), first_order[4].conj(), first_order[5].conj()];
+    let f_rot = cross_complex(&rotation, &force);
+    let m_rot = cross_complex(&rotation, &moment);
+    let mut rotation_term = [0.0; 6];
+    for k in 0..3 {
+        rotation_term[k] = 0.5 * f_rot[k].re;
+        rotation_term[k + 3] = 0.5 * m_rot[k].re;
+    }
+
+    NearFieldDrift {
+        waterline,
+        velocity,
+        motion,
+        rotation: rotation_term,
+    }
+}
+
+fn accumulate(total: &mut [f64; 6], at: &Point, reference: &Point, force: &Vector) {
+    let r = at - reference;
+    let moment = r.cross(force);
+    for k in 0..3 {
+        total[k] += force[k];
+        total[k + 3] += moment[k];
+    }
+}
+
+fn cross_complex_real(a: &ComplexVector, b: &[f64; 3]) -> ComplexVector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn cross_complex(a: &ComplexVector, b: &ComplexVector) -> ComplexVector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BEMSolver, ProblemType, SolverEngine};
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_uniform_waterline_elevation_cancels_on_closed_waterline() {
+        // Vertical square column of side 2 between z = -1 and z = 0
+        let corners = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+        let mut panels = Vec::new();
+        for i in 0..4 {
+            let (x0, y0) = corners[i];
+            let (x1, y1) = corners[(i + 1) % 4];
+            panels.push(Panel::new(Point::new(x0, y0, 0.0), Point::new(x0, y0, -1.0), Point::new(x1, y1, -1.0)).unwrap());
+            panels.push(Panel::new(Point::new(x0, y0, 0.0), Point::new(x1, y1, -1.0), Point::new(x1, y1, 0.0)).unwrap());
+        }
+
+        let waterline = waterline_segments(&panels, 1e-9);
+        assert_eq!(waterline.len(), 4);
+        let length: f64 = waterline.iter().map(|s| s.length()).sum();
+        assert!((length - 8.0).abs() < 1e-12);
+
+        let elevation = vec![Complex64::new(0.7, 0.2); waterline.len()];
+        let velocity = vec![[Complex64::new(0.0, 0.0); 3]; panels.len()];
+        let input = NearFieldInput {
+            panels: &panels,
+            velocity: &velocity,
+            waterline: &waterline,
+            elevation: &elevation,
+            motions: None,
+            reference: Point::origin(),
+            mass_matrix: [[0.0; 6]; 6],
+            frequency: 1.0,
+        };
+        let drift = near_field_drift(&input, 1025.0, 9.81);
+        for value in drift.total() {
+            assert!(value.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_diffraction_drift_forces() {
+        let problem = ProblemType::Diffraction { frequency: 1.0, direction: 0.0 };
+        let result = BEMSolver::new(SolverEngine::Standard)
+            .solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+
+        let far = result.mean_drift().unwrap();
+        assert!(far.surge.is_finite() && far.yaw.is_finite());
+
+        // Near field also yields the vertical component
+        let near = result.near_field_drift().unwrap();
+        assert!(near.total().iter().all(|v| v.is_finite()));
+        assert!(near.velocity[2] != 0.0);
+
+        // Submerged body: no waterline contribution
+        assert_eq!(near.waterline, [0.0; 6]);
+
+        // First-order field is kept for QTF post-processing
+        let field = result.first_order().unwrap();
+        assert!(field.elevation.is_empty());
+        assert_eq!(field.velocity.len(), 4);
+    }
+}
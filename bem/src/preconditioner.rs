@@ -0,0 +1,226 @@
+//! Preconditioners for iterative BEM solves
+//!
+//! Influence matrices are dominated by near-field interactions: the ½I jump
+//! term on the diagonal and strong coupling between neighbouring panels.
+//! Both preconditioners invert that structure locally:
+//! - block-diagonal: the exact inverse of each body's self-interaction
+//!   block, leaving only body-body coupling to the Krylov iteration;
+//! - near-field sparse approximate inverse: for each unknown, the system of
+//!   its nearest neighbours is inverted and the matching row kept, giving a
+//!   sparse M⁻¹ with a fixed number of entries per row.
+
+use super::*;
+use wavecore_matrices::ClusterTree;
+use nalgebra::DMatrix;
+use num_complex::Complex64;
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use wavecore_meshes::Point;
+
+/// Preconditioner applied to iterative solves
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Preconditioning {
+    /// Unpreconditioned iteration
+    None,
+    /// Inverse of each body's self-interaction block
+    BlockDiagonal,
+    /// Sparse approximate inverse from each unknown's nearest neighbours
+    NearField {
+        /// Neighbours per unknown, including itself
+        neighbours: usize,
+    },
+}
+
+/// Assembled preconditioner M⁻¹
+#[derive(Debug, Clone)]
+pub enum Preconditioner {
+    /// Unknowns of each block with the inverse of their block
+    BlockDiagonal(Vec<(Vec<usize>, DMatrix<Complex64>)>),
+    /// Sparse rows of M⁻¹ as (column, value) pairs
+    SparseApproximateInverse(Vec<Vec<(usize, Complex64)>>),
+}
+
+impl Preconditioner {
+    /// Build from matrix entries `entry(i, j)`
+    ///
+    /// `positions` locate the unknowns and `groups` gives the body of each.
+    /// Returns `None` for `Preconditioning::None`.
+    pub fn build<F>(kind: Preconditioning, positions: &[Point], groups: &[usize], entry: F) -> Result<Option<Self>>
+    where
+        F: Fn(usize, usize) -> Result<Complex64> + Sync,
+    {
+        match kind {
+            Preconditioning::None => Ok(None),
+            Preconditioning::BlockDiagonal => {
+                let n_groups = groups.iter().max().map_or(0, |g| g + 1);
+                let mut members = vec![Vec::new(); n_groups];
+                for (i, &g) in groups.iter().enumerate() {
+                    members[g].push(i);
+                }
+                let blocks = members
+                    .into_par_iter()
+                    .filter(|unknowns| !unknowns.is_empty())
+                    .map(|unknowns| {
+                        let inverse = local_inverse(&unknowns, &entry)?;
+                        Ok((unknowns, inverse))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Some(Preconditioner::BlockDiagonal(blocks)))
+            }
+            Preconditioning::NearField { neighbours } => {
+                let neighbourhoods = nearest_neighbours(positions, neighbours.max(1));
+                let rows = neighbourhoods
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(i, unknowns)| {
+                        let inverse = local_inverse(&unknowns, &entry)?;
+                        let local = unknowns.iter().position(|&j| j == i).unwrap_or(0);
+                        Ok(unknowns.iter().enumerate().map(|(c, &j)| (j, inverse[(local, c)])).collect())
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Some(Preconditioner::SparseApproximateInverse(rows)))
+            }
+        }
+    }
+
+    /// Apply M⁻¹ to `x`
+    pub fn apply(&self, x: &[Complex64]) -> Vec<Complex64> {
+        match self {
+            Preconditioner::BlockDiagonal(blocks) => {
+                let mut y = vec![Complex64::new(0.0, 0.0); x.len()];
+                for (unknowns, inverse) in blocks {
+                    for (r, &i) in unknowns.iter().enumerate() {
+                        y[i] = unknowns.iter().enumerate().map(|(c, &j)| inverse[(r, c)] * x[j]).sum();
+                    }
+                }
+                y
+            }
+            Preconditioner::SparseApproximateInverse(rows) => rows
+                .iter()
+                .map(|row| row.iter().map(|&(j, m)| m * x[j]).sum())
+                .collect(),
+        }
+    }
+
+    /// Number of stored entries
+    pub fn nnz(&self) -> usize {
+        match self {
+            Preconditioner::BlockDiagonal(blocks) => blocks.iter().map(|(u, _)| u.len() * u.len()).sum(),
+            Preconditioner::SparseApproximateInverse(rows) => rows.iter().map(Vec::len).sum(),
+        }
+    }
+}
+
+/// Inverse of the submatrix on `unknowns`, or its diagonal inverse if singular
+fn local_inverse<F>(unknowns: &[usize], entry: &F) -> Result<DMatrix<Complex64>>
+where
+    F: Fn(usize, usize) -> Result<Complex64>,
+{
+    let m = unknowns.len();
+    let mut local = DMatrix::zeros(m, m);
+    for (r, &i) in unknowns.iter().enumerate() {
+        for (c, &j) in unknowns.iter().enumerate() {
+            local[(r, c)] = entry(i, j)?;
+        }
+    }
+    match local.clone().try_inverse() {
+        Some(inverse) => Ok(inverse),
+        None => Ok(DMatrix::from_fn(m, m, |r, c| {
+            let d = local[(r, c)];
+            if r == c && d.norm() > 0.0 { d.inv() } else { Complex64::new(0.0, 0.0) }
+        })),
+    }
+}
+
+/// The `k` nearest unknowns of each unknown (itself included)
+///
+/// Candidates are taken from the parent of the unknown's leaf in a cluster
+/// tree with `k`-point leaves, which keeps the search linear in n.
+fn nearest_neighbours(positions: &[Point], k: usize) -> Vec<Vec<usize>> {
+    let coordinates: Vec<[f64; 3]> = positions.iter().map(|p| [p.x, p.y, p.z]).collect();
+    let tree = ClusterTree::new(&coordinates, k);
+    let parents = tree.parents();
+
+    let mut neighbourhoods = vec![Vec::new(); positions.len()];
+    for leaf in tree.leaves() {
+        let candidates = tree.indices(parents[leaf].unwrap_or(leaf));
+        for &i in tree.indices(leaf) {
+            let mut sorted = candidates.to_vec();
+            sorted.sort_by(|&a, &b| {
+                (positions[a] - positions[i]).norm_squared().total_cmp(&(positions[b] - positions[i]).norm_squared())
+            });
+            sorted.truncate(k);
+            if !sorted.contains(&i) {
+                sorted.pop();
+                sorted.insert(0, i);
+            }
+            neighbourhoods[i] = sorted;
+        }
+    }
+    neighbourhoods
+}
+
+impl wavecore_matrices::Preconditioner<Complex64> for Preconditioner {
+    fn apply(&self, x: &[Complex64]) -> wavecore_matrices::Result<Vec<Complex64>> {
+        Ok(Preconditioner::apply(self, x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_full_neighbourhood_is_exact_inverse() {
+        let positions: Vec<Point> = (0..4).map(|i| Point::new(i as f64, 0.0, -1.0)).collect();
+        let a = [[4.0, 1.0, 0.2, 0.1], [1.0, 4.0, 1.0, 0.2], [0.2, 1.0, 4.0, 1.0], [0.1, 0.2, 1.0, 4.0]];
+        let entry = |i: usize, j: usize| -> Result<Complex64> { Ok(Complex64::new(a[i][j], 0.1 * a[j][i])) };
+        let x = [1.0, -1.0, 0.5, 2.0].map(|v| Complex64::new(v, 0.5 * v));
+        let ax: Vec<Complex64> = (0..4).map(|i| (0..4).map(|j| entry(i, j).unwrap() * x[j]).sum()).collect();
+
+        for kind in [Preconditioning::BlockDiagonal, Preconditioning::NearField { neighbours: 4 }] {
+            let preconditioner = Preconditioner::build(kind, &positions, &[0; 4], entry).unwrap().unwrap();
+            for (y, e) in preconditioner.apply(&ax).iter().zip(x) {
+                assert!((y - e).norm() < 1e-12);
+            }
+        }
+
+        // Two neighbours per row: sparse with the diagonal kept
+        let sparse = Preconditioner::build(Preconditioning::NearField { neighbours: 2 }, &positions, &[0; 4], entry)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sparse.nnz(), 8);
+    }
+
+    #[test]
+    fn test_preconditioned_iterative_solve_matches_lu() {
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+        let bodies = vec![tetrahedron_body("a", 0.0), tetrahedron_body("b", 3.0)];
+        let direct = BEMSolver::new(SolverEngine::Standard)
+            .solve_bodies(&problem, bodies.clone())
+            .unwrap();
+        assert_eq!(direct.iterations(), Some(0));
+
+        for (linear_solver, preconditioning) in [
+            (wavecore_matrices::SolverType::GMRES, Preconditioning::BlockDiagonal),
+            (wavecore_matrices::SolverType::BiCGSTAB, Preconditioning::NearField { neighbours: 3 }),
+        ] {
+            let iterative = BEMSolver::with_config(BEMConfig {
+                tolerance: 1e-12,
+                linear_solver,
+                preconditioning,
+                ..Default::default()
+            })
+            .solve_bodies(&problem, bodies.clone())
+            .unwrap();
+
+            assert!(iterative.is_converged());
+            let history = iterative.residual_history();
+            assert!(history.len() >= 2 && history.last().unwrap() < &history[0]);
+            for (a, b) in direct.potential().iter().zip(iterative.potential()) {
+                assert!((a - b).norm() < 1e-8 * (1.0 + a.norm()));
+            }
+        }
+    }
+}
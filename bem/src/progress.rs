@@ -0,0 +1,126 @@
+//! Progress reporting and cancellation of long solves
+//!
+//! A [`ProgressSink`] receives the number of influence-matrix rows assembled
+//! and of sweep frequencies completed, and a [`CancellationToken`] shared with
+//! another thread stops a running solve between rows and frequencies with
+//! [`BEMError::Cancelled`]. Checkpointed sweeps keep the batches saved before
+//! cancellation, so the run can be resumed.
+
+use super::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Progress of a running solve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Rows of the influence matrix being assembled
+    PanelsAssembled { completed: usize, total: usize },
+    /// Frequencies of a sweep solved; the total grows as adaptive refinement adds frequencies
+    FrequenciesCompleted { completed: usize, total: usize },
+}
+
+/// Receiver of progress reports, called from the solver's worker threads
+pub trait ProgressSink: Send + Sync {
+    /// Report progress
+    fn report(&self, progress: Progress);
+}
+
+impl<F: Fn(Progress) + Send + Sync> ProgressSink for F {
+    fn report(&self, progress: Progress) {
+        self(progress)
+    }
+}
+
+/// Shared flag asking a running solve to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every solve holding a clone of this token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress sink and cancellation token of one solver
+#[derive(Clone, Default)]
+pub(crate) struct Monitor {
+    pub(crate) sink: Option<Arc<dyn ProgressSink>>,
+    pub(crate) token: Option<CancellationToken>,
+}
+
+impl Monitor {
+    /// `Err(Cancelled)` once cancellation was requested
+    pub(crate) fn check(&self) -> Result<()> {
+        match &self.token {
+            Some(token) if token.is_cancelled() => Err(BEMError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count one more of `total` items done, reporting about a hundred times over the total
+    pub(crate) fn advance(&self, counter: &AtomicUsize, total: usize, progress: fn(usize, usize) -> Progress) {
+        if let Some(sink) = &self.sink {
+            let completed = counter.fetch_add(1, Ordering::Relaxed) + 1;
+            if completed == total || completed.is_multiple_of(total.div_ceil(100).max(1)) {
+                sink.report(progress(completed, total));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_progress_and_cancellation() {
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let solver = BEMSolver::new(SolverEngine::Standard).with_progress(move |p: Progress| sink.lock().unwrap().push(p));
+        solver.solve_sweep_bodies(&[0.5, 1.0, 1.5], &[0.0], vec![tetrahedron_body("a", 0.0)]).unwrap();
+
+        let reports = reports.lock().unwrap();
+        let frequencies: Vec<usize> = reports
+            .iter()
+            .filter_map(|p| match p {
+                Progress::FrequenciesCompleted { completed, total: 3 } => Some(*completed),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(frequencies.len(), 3);
+        assert!(frequencies.contains(&3));
+        assert!(reports.contains(&Progress::PanelsAssembled { completed: 4, total: 4 }));
+
+        // Cancelling after the first frequency keeps its checkpoint for resuming
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let cancelling = BEMSolver::with_config(BEMConfig { parallel: false, ..Default::default() })
+            .with_cancellation(token.clone())
+            .with_progress(move |p: Progress| {
+                if matches!(p, Progress::FrequenciesCompleted { .. }) {
+                    cancel.cancel();
+                }
+            });
+        let store = MemoryStore::default();
+        let interrupted = cancelling.solve_sweep_checkpointed(&[0.5, 1.0, 1.5], &[0.0], vec![tetrahedron_body("a", 0.0)], &store, 1);
+        assert!(matches!(interrupted, Err(BEMError::Cancelled)));
+        assert!(token.is_cancelled());
+        assert_eq!(store.load().unwrap().unwrap().pending(), vec![1.0, 1.5]);
+        let resumed = BEMSolver::new(SolverEngine::Standard).resume_from(&store, vec![tetrahedron_body("a", 0.0)]).unwrap();
+        assert!(resumed.is_complete());
+    }
+}
@@ -0,0 +1,730 @@
+//! Second-order wave loads: quadratic transfer functions
+//!
+//! In bichromatic waves of amplitudes a_i, a_j the second-order force is
+//! F(t) = Σ_i Σ_j a_i a_j Re[T⁻_ij e^{-i(ω_i - ω_j)t} + T⁺_ij e^{-i(ω_i + ω_j)t}].
+//! The difference-frequency QTF T⁻ generalizes the near-field mean drift
+//! (its diagonal is the mean drift) by replacing |x|² with x_i x_j* in each
+//! quadratic term; the sum-frequency QTF T⁺ uses x_i x_j.
+//!
+//! The second-order potential adds a linear force at ω_i ∓ ω_j:
+//!
+//! - Pinkster's approximation keeps only the Froude-Krylov force of the
+//!   second-order incident wave, -iΩρ ∫∫ φ_I⁽²⁾ n dS. It is accurate when the
+//!   body is small compared with the wavelength of the difference frequency.
+//! - The complete contribution adds the diffraction of the second-order wave
+//!   by the indirect (Molin) method: with radiation potentials ψ_k at Ω,
+//!   normalized so that ∂ψ_k/∂n = n_k,
+//!   F_D,k = iΩρ [ ∫∫_SB ψ_k ∂φ_I⁽²⁾/∂n dS + (1/g) ∫∫_SF ψ_k Q_D dS ],
+//!   where Q_D is the free-surface forcing of the diffracted potential.
+
+use crate::near_field::{ComplexVector, WaterlineSegment};
+use serde::{Serialize, Deserialize};
+use crate::{BEMError, Result};
+use ndarray::Array3;
+use num_complex::Complex64;
+use wavecore_meshes::{Panel, Point, Vector};
+
+/// First-order solution at one frequency and heading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirstOrderField {
+    /// Wave frequency (rad/s)
+    pub frequency: f64,
+    /// Wave heading (radians)
+    pub heading: f64,
+    /// Total fluid velocity ∇φ at each body panel centroid
+    pub velocity: Vec<ComplexVector>,
+    /// Wave elevation at each waterline segment midpoint
+    pub elevation: Vec<Complex64>,
+    /// Rigid-body motions (surge, sway, heave, roll, pitch, yaw); `None` for a fixed body
+    pub motions: Option<[Complex64; 6]>,
+    /// Total potential at each free-surface panel centroid (complete method only)
+    pub free_surface: Vec<FreeSurfaceSample>,
+}
+
+/// First-order potential and derivatives at a free-surface point
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FreeSurfaceSample {
+    /// Potential φ
+    pub potential: Complex64,
+    /// Gradient ∇φ
+    pub gradient: ComplexVector,
+    /// Vertical curvature ∂²φ/∂z²
+    pub vertical_curvature: Complex64,
+}
+
+/// Radiation potentials at a difference or sum frequency, with ∂ψ_k/∂n = n_k
+#[derive(Debug, Clone)]
+pub struct RadiationPotentials {
+    /// ψ_k at each body panel centroid
+    pub body: Vec<[Complex64; 6]>,
+    /// ψ_k at each free-surface panel centroid
+    pub free_surface: Vec<[Complex64; 6]>,
+}
+
+/// Frequency combination of a QTF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QtfKind {
+    /// ω_i - ω_j
+    Difference,
+    /// ω_i + ω_j
+    Sum,
+}
+
+/// Treatment of the second-order potential
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondOrderPotential {
+    /// Quadratic first-order products only
+    Neglected,
+    /// Froude-Krylov force of the second-order incident wave
+    Pinkster,
+    /// Incident and diffracted second-order potential (indirect method)
+    Complete,
+}
+
+/// QTF computation settings
+#[derive(Debug, Clone)]
+pub struct QtfConfig {
+    /// Also compute sum-frequency QTFs
+    pub sum_frequency: bool,
+    /// Second-order potential contribution
+    pub potential: SecondOrderPotential,
+    /// Water density (kg/m³)
+    pub density: f64,
+    /// Gravitational acceleration (m/s²)
+    pub gravity: f64,
+    /// Water depth (m); `None` for deep water
+    pub depth: Option<f64>,
+}
+
+impl Default for QtfConfig {
+    fn default() -> Self {
+        Self {
+            sum_frequency: false,
+            potential: SecondOrderPotential::Pinkster,
+            density: 1025.0,
+            gravity: 9.81,
+            depth: None,
+        }
+    }
+}
+
+/// Mean wetted geometry shared by all first-order solutions
+#[derive(Debug, Clone)]
+pub struct QtfGeometry<'a> {
+    /// Wetted body panels
+    pub panels: &'a [Panel],
+    /// Mean waterline
+    pub waterline: &'a [WaterlineSegment],
+    /// Free-surface panels (complete method only)
+    pub free_surface: &'a [Panel],
+    /// Reference point of the motions and moments
+    pub reference: Point,
+    /// Body mass matrix about the reference point
+    pub mass_matrix: [[f64; 6]; 6],
+}
+
+/// QTF of one heading pair, (Fx, Fy, Fz, Mx, My, Mz) per unit amplitudes
+#[derive(Debug, Clone)]
+pub struct QtfMatrix {
+    /// Frequency combination
+    pub kind: QtfKind,
+    /// Heading of the first wave component (radians)
+    pub heading_i: f64,
+    /// Heading of the second wave component (radians)
+    pub heading_j: f64,
+    /// Wave frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Transfer function, shape (frequency i, frequency j, dof)
+    pub values: Array3<Complex64>,
+}
+
+impl QtfMatrix {
+    /// Transfer function of a frequency pair
+    pub fn at(&self, i: usize, j: usize) -> [Complex64; 6] {
+        let mut out = [Complex64::new(0.0, 0.0); 6];
+        for (k, v) in out.iter_mut().enumerate() {
+            *v = self.values[[i, j, k]];
+        }
+        out
+    }
+
+    /// Mean drift force from the diagonal of a unidirectional difference QTF
+    pub fn mean_drift(&self, i: usize) -> Option<[f64; 6]> {
+        if self.kind != QtfKind::Difference || self.heading_i != self.heading_j {
+            return None;
+        }
+        Some(self.at(i, i).map(|v| v.re))
+    }
+}
+
+/// Provider of radiation potentials at arbitrary frequencies
+pub type RadiationSource<'a> = Box<dyn Fn(f64) -> Option<RadiationPotentials> + Send + Sync + 'a>;
+
+/// Quadratic transfer function calculator
+pub struct QtfCalculator<'a> {
+    geometry: QtfGeometry<'a>,
+    config: QtfConfig,
+    radiation: Option<RadiationSource<'a>>,
+}
+
+impl<'a> QtfCalculator<'a> {
+    /// Create a calculator for a geometry
+    pub fn new(geometry: QtfGeometry<'a>, config: QtfConfig) -> Self {
+        Self {
+            geometry,
+            config,
+            radiation: None,
+        }
+    }
+
+    /// Radiation potentials at Ω, required by the complete method
+    pub fn with_radiation<F>(mut self, radiation: F) -> Self
+    where
+        F: Fn(f64) -> Option<RadiationPotentials> + Send + Sync + 'a,
+    {
+        self.radiation = Some(Box::new(radiation));
+        self
+    }
+
+    /// QTF matrices for every ordered heading pair
+    ///
+    /// `fields` must hold one solution per frequency and heading on a common
+    /// frequency grid. Difference QTFs come first, then sum QTFs if enabled.
+    pub fn compute(&self, fields: &[FirstOrderField]) -> Result<Vec<QtfMatrix>> {
+        let mut headings: Vec<f64> = Vec::new();
+        let mut frequencies: Vec<f64> = Vec::new();
+        for field in fields {
+            if !headings.iter().any(|h| (h - field.heading).abs() < 1e-12) {
+                headings.push(field.heading);
+            }
+            if !frequencies.iter().any(|f| (f - field.frequency).abs() < 1e-12) {
+                frequencies.push(field.frequency);
+            }
+        }
+        frequencies.sort_by(|a, b| a.total_cmp(b));
+
+        let lookup = |f: f64, h: f64| {
+            fields
+                .iter()
+                .find(|s| (s.frequency - f).abs() < 1e-12 && (s.heading - h).abs() < 1e-12)
+                .ok_or_else(|| BEMError::InvalidProblem {
+                    message: format!("Missing first-order solution at ω = {} rad/s, β = {} rad", f, h),
+                })
+        };
+
+        let mut kinds = vec![QtfKind::Difference];
+        if self.config.sum_frequency {
+            kinds.push(QtfKind::Sum);
+        }
+
+        let nf = frequencies.len();
+        let mut matrices = Vec::new();
+        for &kind in &kinds {
+            for &hi in &headings {
+                for &hj in &headings {
+                    let mut values = Array3::from_elem((nf, nf, 6), Complex64::new(0.0, 0.0));
+                    for (i, &fi) in frequencies.iter().enumerate() {
+                        let a = lookup(fi, hi)?;
+                        for (j, &fj) in frequencies.iter().enumerate() {
+                            let b = lookup(fj, hj)?;
+                            for (k, v) in self.transfer(a, b, kind)?.into_iter().enumerate() {
+                                values[[i, j, k]] = v;
+                            }
+                        }
+                    }
+                    matrices.push(QtfMatrix {
+                        kind,
+                        heading_i: hi,
+                        heading_j: hj,
+                        frequencies: frequencies.clone(),
+                        values,
+                    });
+                }
+            }
+        }
+        Ok(matrices)
+    }
+
+    /// Transfer function of one pair of first-order solutions
+    pub fn transfer(&self, a: &FirstOrderField, b: &FirstOrderField, kind: QtfKind) -> Result<[Complex64; 6]> {
+        let geometry = &self.geometry;
+        if a.velocity.len() != geometry.panels.len() || a.elevation.len() != geometry.waterline.len() {
+            return Err(BEMError::InvalidProblem {
+                message: "First-order solution does not match the QTF geometry".to_string(),
+            });
+        }
+
+        let mut total = self.quadratic(a, b, kind);
+        if self.config.potential != SecondOrderPotential::Neglected {
+            for (t, v) in total.iter_mut().zip(self.potential_force(a, b, kind)?) {
+                *t += v;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Quadratic products of first-order quantities
+    fn quadratic(&self, a: &FirstOrderField, b: &FirstOrderField, kind: QtfKind) -> [Complex64; 6] {
+        let (rho, g) = (self.config.density, self.config.gravity);
+        let reference = self.geometry.reference;
+        let c = |z: Complex64| conj_if(kind, z);
+        let zero = [Complex64::new(0.0, 0.0); 6];
+        let motions_a = a.motions.unwrap_or(zero);
+        let motions_b = b.motions.unwrap_or(zero);
+
+        let mut total = zero;
+
+        // Waterline: -¼ρg ζr_a ζr_b* n dl
+        for ((segment, za), zb) in self.geometry.waterline.iter().zip(&a.elevation).zip(&b.elevation) {
+            let mid = segment.midpoint();
+            let ra = za - displacement(&motions_a, &reference, &mid)[2];
+            let rb = zb - displacement(&motions_b, &reference, &mid)[2];
+            accumulate(&mut total, &mid, &reference, &segment.normal, ra * c(rb) * (-0.25 * rho * g * segment.length()));
+        }
+
+        let ga_scale = Complex64::new(0.0, -a.frequency);
+        let gb_scale = Complex64::new(0.0, -b.frequency);
+        for ((panel, va), vb) in self.geometry.panels.iter().zip(&a.velocity).zip(&b.velocity) {
+            let centroid = panel.centroid();
+            let area = panel.area();
+
+            // Velocity: ¼ρ ∇φ_a · ∇φ_b*
+            let speed: Complex64 = va.iter().zip(vb).map(|(x, y)| x * c(*y)).sum();
+
+            // Motion: ¼ρ (X_a · ∇φ_t,b* + ∇φ_t,a · X_b*), with ∇φ_t = -iω ∇φ
+            let xa = displacement(&motions_a, &reference, &centroid);
+            let xb = displacement(&motions_b, &reference, &centroid);
+            let motion: Complex64 = (0..3)
+                .map(|k| xa[k] * c(gb_scale * vb[k]) + ga_scale * va[k] * c(xb[k]))
+                .sum();
+
+            accumulate(&mut total, &centroid, &reference, &panel.normal(), (speed + motion) * (0.25 * rho * area));
+        }
+
+        // Rotation: ¼ (α_a × F_b* + α_b* × F_a), with F = -ω² M ξ
+        let inertia = |motions: &[Complex64; 6], omega: f64| {
+            let mut force = zero;
+            for (i, f) in force.iter_mut().enumerate() {
+                *f = (0..6).map(|j| motions[j] * self.geometry.mass_matrix[i][j]).sum::<Complex64>() * (-omega * omega);
+            }
+            force
+        };
+        let fa = inertia(&motions_a, a.frequency);
+        let fb = inertia(&motions_b, b.frequency);
+        let alpha_a = [motions_a[3], motions_a[4], motions_a[5]];
+        let alpha_b = [c(motions_b[3]), c(motions_b[4]), c(motions_b[5])];
+        for offset in [0, 3] {
+            let fa3 = [fa[offset], fa[offset + 1], fa[offset + 2]];
+            let fb3 = [c(fb[offset]), c(fb[offset + 1]), c(fb[offset + 2])];
+            let first = cross(&alpha_a, &fb3);
+            let second = cross(&alpha_b, &fa3);
+            for k in 0..3 {
+                total[offset + k] += 0.25 * (first[k] + second[k]);
+            }
+        }
+
+        total
+    }
+
+    /// Force of the second-order potential
+    fn potential_force(&self, a: &FirstOrderField, b: &FirstOrderField, kind: QtfKind) -> Result<[Complex64; 6]> {
+        let rho = self.config.density;
+        let reference = self.geometry.reference;
+        let zero = [Complex64::new(0.0, 0.0); 6];
+        let omega = match kind {
+            QtfKind::Difference => a.frequency - b.frequency,
+            QtfKind::Sum => a.frequency + b.frequency,
+        };
+        let Some(incident) = self.second_order_incident(a, b, kind) else {
+            return Ok(zero);
+        };
+
+        // Froude-Krylov: -iΩρ ∫∫ φ_I⁽²⁾ n dS
+        let mut total = zero;
+        for panel in self.geometry.panels {
+            let centroid = panel.centroid();
+            let scale = Complex64::new(0.0, -omega * rho * panel.area()) * incident.potential(&centroid);
+            accumulate(&mut total, &centroid, &reference, &panel.normal(), scale);
+        }
+
+        if self.config.potential != SecondOrderPotential::Complete {
+            return Ok(total);
+        }
+
+        let geometry = &self.geometry;
+        let radiation = self
+            .radiation
+            .as_ref()
+            .and_then(|source| source(omega.abs()))
+            .ok_or_else(|| BEMError::InvalidProblem {
+                message: format!("Complete QTF requires radiation potentials at Ω = {} rad/s", omega),
+            })?;
+        let fs_count = geometry.free_surface.len();
+        if radiation.body.len() != geometry.panels.len()
+            || radiation.free_surface.len() != fs_count
+            || a.free_surface.len() != fs_count
+            || b.free_surface.len() != fs_count
+        {
+            return Err(BEMError::InvalidProblem {
+                message: "Complete QTF requires free-surface samples for every free-surface panel".to_string(),
+            });
+        }
+
+        // Body term: ∫∫ ψ_k ∂φ_I⁽²⁾/∂n dS
+        let mut diffraction = zero;
+        for (panel, psi) in geometry.panels.iter().zip(&radiation.body) {
+            let centroid = panel.centroid();
+            let n = panel.normal();
+            let grad = incident.gradient(&centroid);
+            let dn = grad[0] * n.x + grad[1] * n.y + grad[2] * n.z;
+            for (d, p) in diffraction.iter_mut().zip(psi) {
+                *d += p * dn * panel.area();
+            }
+        }
+
+        // Free-surface term: (1/g) ∫∫ ψ_k Q_D dS, Q_D = Q_total - Q_incident
+        let g = self.config.gravity;
+        for (((panel, psi), sa), sb) in geometry
+            .free_surface
+            .iter()
+            .zip(&radiation.free_surface)
+            .zip(&a.free_surface)
+            .zip(&b.free_surface)
+        {
+            let centroid = panel.centroid();
+            let ia = self.incident_sample(a, &centroid);
+            let ib = self.incident_sample(b, &centroid);
+            let forcing = self.forcing(sa, sb, a.frequency, b.frequency, kind)
+                - self.forcing(&ia, &ib, a.frequency, b.frequency, kind);
+            for (d, p) in diffraction.iter_mut().zip(psi) {
+                *d += p * forcing * (panel.area() / g);
+            }
+        }
+
+        let factor = Complex64::new(0.0, omega * rho);
+        for (t, d) in total.iter_mut().zip(diffraction) {
+            *t += factor * d;
+        }
+        Ok(total)
+    }
+
+    /// Free-surface forcing Q of the second-order potential, halved for the QTF convention
+    ///
+    /// From φ⁽²⁾_tt + g φ⁽²⁾_z = -∂_t|∇φ|² + (1/g) φ_t ∂_z(φ_tt + g φ_z) at z = 0.
+    fn forcing(&self, a: &FreeSurfaceSample, b: &FreeSurfaceSample, wa: f64, wb: f64, kind: QtfKind) -> Complex64 {
+        let g = self.config.gravity;
+        let c = |z: Complex64| conj_if(kind, z);
+        let omega = match kind {
+            QtfKind::Difference => wa - wb,
+            QtfKind::Sum => wa + wb,
+        };
+        let time_a = Complex64::new(0.0, -wa) * a.potential;
+        let time_b = Complex64::new(0.0, -wb) * b.potential;
+        let vertical_a = a.gradient[2] * (-wa * wa) + a.vertical_curvature * g;
+        let vertical_b = b.gradient[2] * (-wb * wb) + b.vertical_curvature * g;
+        let grad: Complex64 = (0..3).map(|k| a.gradient[k] * c(b.gradient[k])).sum();
+
+        0.5 * (Complex64::new(0.0, omega) * grad + (time_a * c(vertical_b) + vertical_a * c(time_b)) / (2.0 * g))
+    }
+
+    /// Linear incident potential and derivatives of a field at a point
+    fn incident_sample(&self, field: &FirstOrderField, p: &Point) -> FreeSurfaceSample {
+        let wave = IncidentWave::new(field.frequency, field.heading, self.config.gravity, self.config.depth);
+        FreeSurfaceSample {
+            potential: wave.potential(p),
+            gradient: wave.gradient(p),
+            vertical_curvature: wave.potential(p) * wave.k * wave.k,
+        }
+    }
+
+    /// Second-order incident potential of a pair, `None` where it vanishes identically
+    fn second_order_incident(&self, a: &FirstOrderField, b: &FirstOrderField, kind: QtfKind) -> Option<SecondOrderWave> {
+        let g = self.config.gravity;
+        let wa = IncidentWave::new(a.frequency, a.heading, g, self.config.depth);
+        let wb = IncidentWave::new(b.frequency, b.heading, g, self.config.depth);
+        let sign = match kind {
+            QtfKind::Difference => -1.0,
+            QtfKind::Sum => 1.0,
+        };
+        let omega = a.frequency + sign * b.frequency;
+        let kx = wa.k * a.heading.cos() + sign * wb.k * b.heading.cos();
+        let ky = wa.k * a.heading.sin() + sign * wb.k * b.heading.sin();
+        let k = (kx * kx + ky * ky).sqrt();
+        if omega.abs() < 1e-12 || k < 1e-12 {
+            return None;
+        }
+
+        // Amplitude from the free-surface condition (gK tanh Kh - Ω²) A = Q at the origin
+        let origin = Point::origin();
+        let q = self.forcing(&self.incident_sample(a, &origin), &self.incident_sample(b, &origin), a.frequency, b.frequency, kind);
+        let tanh = self.config.depth.map_or(1.0, |h| (k * h).tanh());
+        let denominator = g * k * tanh - omega * omega;
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+        Some(SecondOrderWave {
+            amplitude: q / denominator,
+            kx,
+            ky,
+            k,
+            depth: self.config.depth,
+        })
+    }
+}
+
+/// Linear incident wave of unit amplitude
+struct IncidentWave {
+    k: f64,
+    omega: f64,
+    heading: f64,
+    gravity: f64,
+    depth: Option<f64>,
+}
+
+impl IncidentWave {
+    fn new(omega: f64, heading: f64, gravity: f64, depth: Option<f64>) -> Self {
+        Self {
+            k: wave_number(omega, gravity, depth),
+            omega,
+            heading,
+            gravity,
+            depth,
+        }
+    }
+
+    /// φ = -ig/ω Z(z) e^{ik(x cos β + y sin β)}
+    fn potential(&self, p: &Point) -> Complex64 {
+        let (vertical, _) = vertical_profile(self.k, p.z, self.depth);
+        let phase = Complex64::new(0.0, self.k * (p.x * self.heading.cos() + p.y * self.heading.sin())).exp();
+        Complex64::new(0.0, -self.gravity / self.omega) * vertical * phase
+    }
+
+    fn gradient(&self, p: &Point) -> ComplexVector {
+        let phi = self.potential(p);
+        let (_, slope) = vertical_profile(self.k, p.z, self.depth);
+        let ik = Complex64::new(0.0, self.k);
+        [phi * ik * self.heading.cos(), phi * ik * self.heading.sin(), phi * self.k * slope]
+    }
+}
+
+/// Second-order incident potential A Z_K(z) e^{i(Kx x + Ky y)}
+struct SecondOrderWave {
+    amplitude: Complex64,
+    kx: f64,
+    ky: f64,
+    k: f64,
+    depth: Option<f64>,
+}
+
+impl SecondOrderWave {
+    fn potential(&self, p: &Point) -> Complex64 {
+        let (vertical, _) = vertical_profile(self.k, p.z, self.depth);
+        self.amplitude * vertical * Complex64::new(0.0, self.kx * p.x + self.ky * p.y).exp()
+    }
+
+    fn gradient(&self, p: &Point) -> ComplexVector {
+        let phi = self.potential(p);
+        let (_, slope) = vertical_profile(self.k, p.z, self.depth);
+        [phi * Complex64::new(0.0, self.kx), phi * Complex64::new(0.0, self.ky), phi * self.k * slope]
+    }
+}
+
+/// Depth profile Z(z) and (dZ/dz)/(k Z)
+fn vertical_profile(k: f64, z: f64, depth: Option<f64>) -> (f64, f64) {
+    match depth {
+        Some(h) => ((k * (z + h)).cosh() / (k * h).cosh(), (k * (z + h)).tanh()),
+        None => ((k * z).exp(), 1.0),
+    }
+}
+
+/// Wave number from the dispersion relation ω² = gk tanh(kh)
+pub fn wave_number(omega: f64, gravity: f64, depth: Option<f64>) -> f64 {
+    let deep = omega * omega / gravity;
+    let Some(h) = depth else {
+        return deep;
+    };
+    // Newton iteration from the deep-water value
+    let mut k = deep.max((omega / (gravity * h).sqrt()).max(1e-12));
+    for _ in 0..50 {
+        let t = (k * h).tanh();
+        let f = gravity * k * t - omega * omega;
+        let df = gravity * (t + k * h * (1.0 - t * t));
+        let step = f / df;
+        k -= step;
+        if step.abs() < 1e-14 * k {
+            break;
+        }
+    }
+    k
+}
+
+fn conj_if(kind: QtfKind, z: Complex64) -> Complex64 {
+    match kind {
+        QtfKind::Difference => z.conj(),
+        QtfKind::Sum => z,
+    }
+}
+
+fn displacement(motions: &[Complex64; 6], reference: &Point, p: &Point) -> ComplexVector {
+    let r = [p.x - reference.x, p.y - reference.y, p.z - reference.z];
+    let rotation = [motions[3], motions[4], motions[5]];
+    [
+        motions[0] + rotation[1] * r[2] - rotation[2] * r[1],
+        motions[1] + rotation[2] * r[0] - rotation[0] * r[2],
+        motions[2] + rotation[0] * r[1] - rotation[1] * r[0],
+    ]
+}
+
+/// Add a force `normal * scale` at `at` and its moment about `reference`
+fn accumulate(total: &mut [Complex64; 6], at: &Point, reference: &Point, normal: &Vector, scale: Complex64) {
+    let r = at - reference;
+    let moment = r.cross(normal);
+    for k in 0..3 {
+        total[k] += scale * normal[k];
+        total[k + 3] += scale * moment[k];
+    }
+}
+
+fn cross(a: &ComplexVector, b: &ComplexVector) -> ComplexVector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::near_field::{near_field_drift, waterline_segments, NearFieldInput};
+
+    fn column() -> Vec<Panel> {
+        // Vertical square column of side 2 between z = -1 and z = 0, with bottom
+        let corners = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+        let mut panels = Vec::new();
+        for i in 0..4 {
+            let (x0, y0) = corners[i];
+            let (x1, y1) = corners[(i + 1) % 4];
+            panels.push(Panel::new(Point::new(x0, y0, 0.0), Point::new(x0, y0, -1.0), Point::new(x1, y1, -1.0)).unwrap());
+            panels.push(Panel::new(Point::new(x0, y0, 0.0), Point::new(x1, y1, -1.0), Point::new(x1, y1, 0.0)).unwrap());
+        }
+        panels
+    }
+
+    fn field(panels: &[Panel], waterline: &[WaterlineSegment], frequency: f64) -> FirstOrderField {
+        let wave = IncidentWave::new(frequency, 0.0, 9.81, None);
+        FirstOrderField {
+            frequency,
+            heading: 0.0,
+            velocity: panels.iter().map(|p| wave.gradient(&p.centroid())).collect(),
+            elevation: waterline
+                .iter()
+                .map(|s| Complex64::new(0.0, frequency / 9.81) * wave.potential(&s.midpoint()))
+                .collect(),
+            motions: Some([
+                Complex64::new(0.3, 0.1),
+                Complex64::new(0.0, 0.0),
+                Complex64::new(0.2, -0.4),
+                Complex64::new(0.0, 0.0),
+                Complex64::new(0.05, 0.02),
+                Complex64::new(0.0, 0.0),
+            ]),
+            free_surface: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_difference_qtf_diagonal_is_mean_drift() {
+        let panels = column();
+        let waterline = waterline_segments(&panels, 1e-9);
+        let mut mass = [[0.0; 6]; 6];
+        for (k, row) in mass.iter_mut().enumerate() {
+            row[k] = if k < 3 { 4100.0 } else { 1500.0 };
+        }
+        let geometry = QtfGeometry {
+            panels: &panels,
+            waterline: &waterline,
+            free_surface: &[],
+            reference: Point::origin(),
+            mass_matrix: mass,
+        };
+        let calculator = QtfCalculator::new(
+            geometry,
+            QtfConfig {
+                sum_frequency: true,
+                potential: SecondOrderPotential::Neglected,
+                ..Default::default()
+            },
+        );
+
+        let fields = vec![field(&panels, &waterline, 0.6), field(&panels, &waterline, 0.9)];
+        let matrices = calculator.compute(&fields).unwrap();
+        assert_eq!(matrices.len(), 2);
+        let difference = &matrices[0];
+
+        for (i, f) in fields.iter().enumerate() {
+            let input = NearFieldInput {
+                panels: &panels,
+                velocity: &f.velocity,
+                waterline: &waterline,
+                elevation: &f.elevation,
+                motions: f.motions,
+                reference: Point::origin(),
+                mass_matrix: mass,
+                frequency: f.frequency,
+            };
+            let expected = near_field_drift(&input, 1025.0, 9.81).total();
+            let diagonal = difference.mean_drift(i).unwrap();
+            for k in 0..6 {
+                assert!((diagonal[k] - expected[k]).abs() < 1e-8 * (1.0 + expected[k].abs()));
+                assert!(difference.values[[i, i, k]].im.abs() < 1e-8 * (1.0 + expected[k].abs()));
+            }
+        }
+
+        // Hermitian symmetry of the difference QTF
+        let (t01, t10) = (difference.at(0, 1), difference.at(1, 0));
+        for k in 0..6 {
+            assert!((t01[k] - t10[k].conj()).norm() < 1e-8 * (1.0 + t01[k].norm()));
+        }
+    }
+
+    #[test]
+    fn test_second_order_incident_wave() {
+        assert!((wave_number(1.0, 9.81, Some(1e3)) - 1.0 / 9.81).abs() < 1e-12);
+        let k = wave_number(0.8, 9.81, Some(20.0));
+        assert!((9.81 * k * (k * 20.0).tanh() - 0.64).abs() < 1e-12);
+
+        let panels = column();
+        let waterline = waterline_segments(&panels, 1e-9);
+        let geometry = QtfGeometry {
+            panels: &panels,
+            waterline: &waterline,
+            free_surface: &[],
+            reference: Point::origin(),
+            mass_matrix: [[0.0; 6]; 6],
+        };
+        let config = QtfConfig {
+            sum_frequency: true,
+            ..Default::default()
+        };
+        let calculator = QtfCalculator::new(geometry, config);
+        let a = field(&panels, &waterline, 0.6);
+        let b = field(&panels, &waterline, 0.9);
+
+        // Deep water: the unidirectional sum-frequency incident potential vanishes
+        let sum = calculator.second_order_incident(&a, &b, QtfKind::Sum).unwrap();
+        assert!(sum.amplitude.norm() < 1e-12);
+        let difference = calculator.second_order_incident(&a, &b, QtfKind::Difference).unwrap();
+        assert!(difference.amplitude.norm() > 0.0);
+
+        // The complete method needs radiation potentials
+        let complete = QtfCalculator::new(
+            calculator.geometry.clone(),
+            QtfConfig {
+                potential: SecondOrderPotential::Complete,
+                ..Default::default()
+            },
+        );
+        assert!(complete.transfer(&a, &b, QtfKind::Difference).is_err());
+    }
+}
@@ -1,6 +1,5 @@
 //! BEM solver results
 
-use super::*;
 use crate::problems::BEMProblem;
 
 /// BEM solution results
@@ -1,12 +1,34 @@
 //! BEM solver implementation with matrix assembly
 
 use super::*;
-use wavecore_matrices::{Matrix, LinearSolver, LinearSolverTrait, SolverType};
-use wavecore_green_functions::{GreenFunction, GreenFunctionParams, Method};
-use wavecore_meshes::{Mesh, Panel};
+use wavecore_matrices::{ComplexMatrix, Matrix, LinearSolver, SolverType, IterativeControl, IterativeOutcome, LinearOperator, RecycleSpace, complex_gcrodr_iterate, complex_gmres_iterate};
+use crate::symmetry::SymmetryMap;
+use crate::hmatrix::{BlockPartition, HMatrix};
+use crate::preconditioner::Preconditioner;
+use crate::drift::{KochinFunction, MeanDriftForce, DEFAULT_KOCHIN_ANGLES};
+use crate::near_field::{near_field_drift, waterline_segments, NearFieldDrift, NearFieldInput};
+use crate::qtf::FirstOrderField;
+use crate::field::{incident_potential, BoundaryDistribution};
+use crate::diagnostics::QualityDiagnostics;
+use crate::checkpoint::{CheckpointStore, FrequencyRecord, SweepCheckpoint};
+use crate::limits::FrequencyLimit;
+use crate::lid::DampingLid;
+use crate::progress::{Monitor, Progress};
+use crate::panel_output::PanelOutput;
+use num_complex::Complex64;
+use wavecore_green_functions::{rankine_gradient, GreenFunction, GreenFunctionParams, Method};
+use wavecore_meshes::{Panel, Point, Vector};
+use crate::higher_order::{build_nodes, map_to_panel, shape_functions, triangle_rule, CollocationNode, PanelOrder};
 use wavecore_bodies::{FloatingBody};
 use nalgebra::Point3;
 use rayon::prelude::*;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+use serde::{Serialize, Deserialize};
+
+mod influence;
+
+use influence::{InfluenceBlocks, Kernel, KernelPart, RankineCache};
 
 /// BEM matrix assembly configuration
 #[derive(Debug, Clone)]
@@ -38,29 +60,243 @@ impl Default for AssemblyConfig {
 /// BEM problem definition
 #[derive(Debug, Clone)]
 pub struct BEMProblem {
-    /// Floating body with mesh
-    pub body: FloatingBody,
+    /// Floating bodies with meshes (hydrodynamically coupled)
+    pub bodies: Vec<FloatingBody>,
     /// Problem type (radiation/diffraction)
     pub problem_type: ProblemType,
     /// Assembly configuration
     pub assembly_config: AssemblyConfig,
 }
 
+impl BEMProblem {
+    /// Create a single-body problem
+    pub fn single(body: FloatingBody, problem_type: ProblemType, assembly_config: AssemblyConfig) -> Self {
+        Self {
+            bodies: vec![body],
+            problem_type,
+            assembly_config,
+        }
+    }
+    
+    /// Total number of modes: 6 rigid-body modes per body plus its generalized modes
+    pub fn n_dof(&self) -> usize {
+        self.bodies.iter().map(|body| body.n_modes()).sum()
+    }
+}
+
+/// Panels of all bodies in a problem, with the owning body of each panel
+/// and the collocation nodes of the chosen panel order
+struct CoupledPanels {
+    panels: Vec<Panel>,
+    centers_of_gravity: Vec<[f64; 3]>,
+    order: PanelOrder,
+    nodes: Vec<CollocationNode>,
+    elements: Vec<Vec<usize>>,
+    /// First global mode of each body
+    mode_offsets: Vec<usize>,
+    /// Generalized-mode normal displacements at each node
+    flexible: Vec<Vec<f64>>,
+    /// Number of body nodes; damping-lid nodes follow them
+    n_body_nodes: usize,
+    /// Dissipation parameter of each lid node
+    lid_damping: Vec<f64>,
+}
+
+impl CoupledPanels {
+    fn from_bodies(bodies: &[FloatingBody], order: PanelOrder, rule: &[([f64; 3], f64)]) -> Result<Self> {
+        let mut panels = Vec::new();
+        let mut centers_of_gravity = Vec::with_capacity(bodies.len());
+        let mut nodes = Vec::new();
+        let mut elements = Vec::new();
+        let mut mode_offsets = Vec::with_capacity(bodies.len());
+        let mut flexible = Vec::new();
+        let mut n_modes = 0;
+        
+        for (b, body) in bodies.iter().enumerate() {
+            // Higher-order elements are triangles, so quads are split for them
+            let source = body.mesh()?;
+            let mut mesh = match order {
+                PanelOrder::Constant => source.clone(),
+                _ => source.triangulated()?,
+            };
+            let n_triangles = source.faces.len();
+            let source_panel = |p: usize| if p < n_triangles { p } else { n_triangles + (p - n_triangles) / 2 };
+            let body_panels = mesh.panels()?.to_vec();
+            if body_panels.is_empty() {
+                return Err(BEMError::InvalidProblem {
+                    message: format!("Mesh of body '{}' has no panels", body.name),
+                });
+            }
+            let (body_nodes, body_elements) = build_nodes(
+                order, &mesh.vertices, &mesh.faces, &body_panels, b, nodes.len(), rule,
+            );
+            
+            // Panel values of generalized modes, averaged onto shared nodes
+            let n_flexible = body.generalized_modes.len();
+            let mut sums = vec![vec![0.0; n_flexible]; body_nodes.len()];
+            let mut counts = vec![0usize; body_nodes.len()];
+            for (p, element) in body_elements.iter().enumerate() {
+                for &id in element {
+                    let local = id - nodes.len();
+                    for (m, mode) in body.generalized_modes.iter().enumerate() {
+                        sums[local][m] += mode.normal_displacement[source_panel(p)];
+                    }
+                    counts[local] += 1;
+                }
+            }
+            flexible.extend(sums.into_iter().zip(counts).map(|(sum, count)| {
+                sum.into_iter().map(|v| v / count.max(1) as f64).collect::<Vec<f64>>()
+            }));
+            
+            mode_offsets.push(n_modes);
+            n_modes += body.n_modes();
+            nodes.extend(body_nodes);
+            elements.extend(body_elements);
+            panels.extend(body_panels);
+            centers_of_gravity.push(body.mass_properties.center_of_gravity);
+        }
+        
+        let n_body_nodes = nodes.len();
+        Ok(Self { panels, centers_of_gravity, order, nodes, elements, mode_offsets, flexible, n_body_nodes, lid_damping: Vec::new() })
+    }
+    
+    /// Append damping-lid panels after the body panels
+    ///
+    /// Each lid is its own group for preconditioning; lid nodes have no modes.
+    fn with_lids(mut self, lids: &[DampingLid], rule: &[([f64; 3], f64)]) -> Result<Self> {
+        let n_bodies = self.centers_of_gravity.len();
+        for (l, lid) in lids.iter().enumerate() {
+            let mut mesh = lid.mesh()?;
+            let lid_panels = mesh.panels()?.to_vec();
+            let (lid_nodes, lid_elements) = build_nodes(
+                self.order, &mesh.vertices, &mesh.faces, &lid_panels, n_bodies + l, self.nodes.len(), rule,
+            );
+            self.flexible.extend(lid_nodes.iter().map(|_| Vec::new()));
+            self.lid_damping.extend(lid_nodes.iter().map(|_| lid.damping));
+            self.nodes.extend(lid_nodes);
+            self.elements.extend(lid_elements);
+            self.panels.extend(lid_panels);
+        }
+        Ok(self)
+    }
+    
+    /// Whether damping lids are part of the geometry
+    fn has_lids(&self) -> bool {
+        !self.lid_damping.is_empty()
+    }
+    
+    /// Factor K(1 + iε) of the damped free-surface condition at lid nodes
+    fn lid_factors(&self, frequency: f64) -> impl Iterator<Item = (usize, Complex64)> + '_ {
+        let k = frequency * frequency / 9.81;
+        self.lid_damping
+            .iter()
+            .enumerate()
+            .map(move |(l, &epsilon)| (self.n_body_nodes + l, Complex64::new(k, k * epsilon)))
+    }
+    
+    /// Number of unknowns
+    fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    
+    /// Generalized normal at node `i` for global mode `mode` (body offset + local mode)
+    fn generalized_normal(&self, i: usize, mode: usize) -> f64 {
+        if i >= self.n_body_nodes {
+            return 0.0;
+        }
+        let node = &self.nodes[i];
+        let offset = self.mode_offsets[node.body];
+        let flexible = &self.flexible[i];
+        if mode < offset || mode >= offset + 6 + flexible.len() {
+            return 0.0;
+        }
+        let local = mode - offset;
+        if local >= 6 {
+            return flexible[local - 6];
+        }
+        let n = node.normal;
+        let c = node.position;
+        let cog = self.centers_of_gravity[node.body];
+        let (x, y, z) = (c.x - cog[0], c.y - cog[1], c.z - cog[2]);
+        match local {
+            0 => n.x,
+            1 => n.y,
+            2 => n.z,
+            3 => y * n.z - z * n.y,
+            4 => z * n.x - x * n.z,
+            _ => x * n.y - y * n.x,
+        }
+    }
+}
+
+/// Frequency-independent geometry shared by all solves on the same bodies
+struct PreparedGeometry {
+    rule: Vec<([f64; 3], f64)>,
+    coupled: CoupledPanels,
+    symmetry: Option<SymmetryMap>,
+    /// Cluster tree and block partition of the H-matrix engine
+    partition: Option<BlockPartition>,
+    /// Rankine part of the constant-panel matrices, shared across frequencies
+    rankine: Option<RankineCache>,
+}
+
+/// Potential, source strengths (indirect formulation) and linear-solve outcome
+type SystemSolution = (Vec<Complex64>, Option<Vec<Complex64>>, IterativeOutcome<Complex64>);
+
+/// One solution of a batch with the quantities it shares with the others
+struct SolvedSystem<'a> {
+    green_function: &'a GreenFunction,
+    green_params: &'a GreenFunctionParams,
+    /// Right-hand side of the problem
+    rhs: Vec<Complex64>,
+    solution: SystemSolution,
+    /// Time to assemble and solve the whole batch
+    elapsed: std::time::Duration,
+}
+
 /// BEM result containing solution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BEMResult {
-    /// Solution vector (velocity potentials)
-    pub potential: Vec<f64>,
+    /// Complex velocity potential amplitudes at the collocation nodes
+    pub potential: Vec<Complex64>,
+    /// Complex source strengths (indirect formulation only)
+    pub source_strength: Option<Vec<Complex64>>,
     /// Added mass matrix (for radiation problems)
     pub added_mass: Option<Matrix>,
     /// Damping matrix (for radiation problems)  
     pub damping: Option<Matrix>,
-    /// Wave exciting forces (for diffraction problems)
-    pub excitation_force: Option<Vec<f64>>,
+    /// Complex wave excitation per unit wave amplitude from diffraction pressure integration
+    pub excitation_force: Option<Vec<Complex64>>,
+    /// Froude-Krylov part of `excitation_force`, from the incident-wave pressure alone
+    pub froude_krylov_force: Option<Vec<Complex64>>,
+    /// Complex wave excitation from the Haskind relations (diffraction problems with `BEMConfig::haskind`)
+    pub haskind_excitation: Option<Vec<Complex64>>,
+    /// Largest difference of direct and Haskind excitation relative to the largest direct force
+    pub haskind_discrepancy: Option<f64>,
+    /// Far-field mean drift force (diffraction problems, indirect formulation)
+    pub mean_drift: Option<MeanDriftForce>,
+    /// Near-field mean drift on the fixed body (diffraction, indirect formulation, constant panels)
+    pub near_field_drift: Option<NearFieldDrift>,
+    /// First-order fluid velocity and waterline elevation for QTFs (same cases as `near_field_drift`)
+    pub first_order: Option<FirstOrderField>,
+    /// Solved boundary distribution for field-point evaluation
+    pub boundary: Option<BoundaryDistribution>,
+    /// Pressure, source strength and normal velocity at the body panels
+    pub panel_output: Option<PanelOutput>,
+    /// Energy-conservation, reciprocity and Haskind checks of the solution
+    pub diagnostics: QualityDiagnostics,
     /// Computation time in seconds
     pub computation_time: f64,
     /// Number of iterations (for iterative solvers)
     pub iterations: Option<usize>,
+    /// Final linear-solver residual norm
+    pub residual: Option<f64>,
+    /// Residual norms of the linear solve (per iteration, per restart cycle for GMRES)
+    pub residual_history: Vec<f64>,
+    /// Whether the linear solve reached its tolerance
+    pub converged: bool,
+    /// Whether the wall-clock budget stopped the solve early
+    pub timed_out: bool,
 }
 
 impl BEMResult {
@@ -75,20 +311,66 @@ impl BEMResult {
     }
     
     /// Get excitation force vector
-    pub fn excitation_force(&self) -> Option<&Vec<f64>> {
+    pub fn excitation_force(&self) -> Option<&Vec<Complex64>> {
         self.excitation_force.as_ref()
     }
     
     /// Get exciting force vector (alias for excitation_force)
-    pub fn exciting_force(&self) -> Option<&Vec<f64>> {
+    pub fn exciting_force(&self) -> Option<&Vec<Complex64>> {
         self.excitation_force()
     }
     
+    /// Get the Froude-Krylov part of the excitation force
+    pub fn froude_krylov_force(&self) -> Option<&Vec<Complex64>> {
+        self.froude_krylov_force.as_ref()
+    }
+    
+    /// Get excitation force from the Haskind relations
+    pub fn haskind_excitation(&self) -> Option<&Vec<Complex64>> {
+        self.haskind_excitation.as_ref()
+    }
+    
+    /// Get the relative discrepancy of direct and Haskind excitation
+    ///
+    /// Both are exact for the continuous problem, so the discrepancy
+    /// measures the discretization error of the diffraction solve.
+    pub fn haskind_discrepancy(&self) -> Option<f64> {
+        self.haskind_discrepancy
+    }
+    
+    /// Get quality diagnostics of the solution
+    ///
+    /// Checks that fail beyond `BEMConfig::diagnostic_tolerance` usually
+    /// point at meshing or assembly errors.
+    pub fn diagnostics(&self) -> &QualityDiagnostics {
+        &self.diagnostics
+    }
+    
+    /// Get far-field mean drift force
+    pub fn mean_drift(&self) -> Option<&MeanDriftForce> {
+        self.mean_drift.as_ref()
+    }
+    
+    /// Get near-field mean drift force
+    pub fn near_field_drift(&self) -> Option<&NearFieldDrift> {
+        self.near_field_drift.as_ref()
+    }
+    
+    /// Get first-order field for second-order (QTF) post-processing
+    pub fn first_order(&self) -> Option<&FirstOrderField> {
+        self.first_order.as_ref()
+    }
+    
     /// Get velocity potential solution
-    pub fn potential(&self) -> &Vec<f64> {
+    pub fn potential(&self) -> &Vec<Complex64> {
         &self.potential
     }
     
+    /// Get source strengths (indirect formulation only)
+    pub fn source_strength(&self) -> Option<&Vec<Complex64>> {
+        self.source_strength.as_ref()
+    }
+    
     /// Get computation time
     pub fn computation_time(&self) -> f64 {
         self.computation_time
@@ -99,6 +381,16 @@ impl BEMResult {
         self.iterations
     }
     
+    /// Get the residual history of the linear solve
+    pub fn residual_history(&self) -> &[f64] {
+        &self.residual_history
+    }
+    
+    /// Check if the solution is fully converged (not a partial result)
+    pub fn is_converged(&self) -> bool {
+        self.converged && !self.timed_out
+    }
+    
     /// Check if result contains added mass data
     pub fn has_added_mass(&self) -> bool {
         self.added_mass.is_some()
@@ -118,113 +410,1050 @@ impl BEMResult {
 /// BEM solver implementation
 pub struct BEMSolverImpl {
     config: BEMConfig,
+    monitor: Monitor,
+    /// Krylov space recycled between GMRES solves
+    recycle: Mutex<RecycleSpace>,
 }
 
 impl BEMSolverImpl {
     /// Create a new BEM solver
     pub fn new(config: BEMConfig) -> Self {
-        Self { config }
+        let recycle = Mutex::new(RecycleSpace::new(config.recycle_space));
+        Self { config, monitor: Monitor::default(), recycle }
+    }
+    
+    /// Report progress to and check cancellation of `monitor`
+    pub(crate) fn with_monitor(mut self, monitor: Monitor) -> Self {
+        self.monitor = monitor;
+        self
     }
     
     /// Solve a BEM problem
+    ///
+    /// Combined problems solve the radiation of every listed mode and the
+    /// diffraction together against one assembly of the influence matrices.
     pub fn solve(&self, problem: &BEMProblem) -> Result<BEMResult> {
         let start_time = std::time::Instant::now();
+        let control = self.iterative_control(start_time);
+        let prepared = self.prepare(&problem.bodies, &problem.assembly_config, &self.config.damping_lids)?;
+        let mut result = match &problem.problem_type {
+            ProblemType::Combined { frequency, direction, modes } => {
+                if (1..modes.len()).any(|i| modes[..i].contains(&modes[i])) {
+                    return Err(BEMError::InvalidProblem {
+                        message: format!("Combined problem lists modes {:?} more than once", modes),
+                    });
+                }
+                let mut problem_types: Vec<ProblemType> =
+                    modes.iter().map(|&mode| ProblemType::Radiation { frequency: *frequency, mode }).collect();
+                problem_types.push(ProblemType::Diffraction { frequency: *frequency, direction: *direction });
+                let results = self.solve_batch(problem, &problem_types, &prepared, &control, None)?;
+                merge_combined(results, self.config.diagnostic_tolerance)?
+            }
+            _ => self.solve_prepared(problem, &prepared, &control)?,
+        };
+        result.computation_time = start_time.elapsed().as_secs_f64();
+        Ok(result)
+    }
+    
+    /// Added mass in the zero- or infinite-frequency limit
+    ///
+    /// Sources of the indirect formulation sit at the collocation nodes with
+    /// the image kernel of the limit; the self-influence of each node is that
+    /// of a flat disk of the node's area. Every mode is solved against the same
+    /// real influence matrix.
+    pub fn solve_limit_added_mass(
+        &self,
+        bodies: Vec<FloatingBody>,
+        assembly_config: AssemblyConfig,
+        limit: FrequencyLimit,
+    ) -> Result<Matrix> {
+        let start_time = std::time::Instant::now();
+        let control = self.iterative_control(start_time);
+        let prepared = self.prepare(&bodies, &assembly_config, &[])?;
+        let coupled = &prepared.coupled;
+        let n = coupled.n_nodes();
+        let n_dof: usize = bodies.iter().map(|body| body.n_modes()).sum();
+        
+        let rows = |row: &(dyn Fn(usize) -> Vec<f64> + Sync)| -> Vec<f64> {
+            if assembly_config.parallel {
+                (0..n).into_par_iter().flat_map_iter(row).collect()
+            } else {
+                (0..n).flat_map(row).collect()
+            }
+        };
+        // Single layer S and normal-derivative matrix ½I + K
+        let single = rows(&|i| {
+            let x = &coupled.nodes[i].position;
+            (0..n)
+                .map(|j| {
+                    let source = &coupled.nodes[j];
+                    if i == j {
+                        let radius = (source.weight / std::f64::consts::PI).sqrt();
+                        -0.5 * radius + limit.image_green(x, x) * source.weight
+                    } else {
+                        limit.green(x, &source.position) * source.weight
+                    }
+                })
+                .collect()
+        });
+        let double = rows(&|i| {
+            let node = &coupled.nodes[i];
+            (0..n)
+                .map(|j| {
+                    let source = &coupled.nodes[j];
+                    if i == j {
+                        0.5 + limit.image_field_gradient(&node.position, &node.position).dot(&node.normal) * source.weight
+                    } else {
+                        limit.field_gradient(&node.position, &source.position).dot(&node.normal) * source.weight
+                    }
+                })
+                .collect()
+        });
+        let single = Matrix::from_vec(n, n, single)?;
+        let double = Matrix::from_vec(n, n, double)?;
+        
+        // A_im = -ρ ∫ ψ_m n_i dS with ∂ψ_m/∂n = n_m
+        let rho = 1025.0;
+        let solver = LinearSolver::new(assembly_config.solver_type);
+        let mut added_mass = Matrix::new(n_dof, n_dof);
+        for mode in 0..n_dof {
+            let rhs: Vec<f64> = (0..n).map(|k| coupled.generalized_normal(k, mode)).collect();
+            let sigma = solver
+                .solve_with_control(&double, &rhs, &control)?
+                .into_converged("limit added mass", control.max_iterations)?;
+            let psi: Vec<f64> = single.data.chunks(n).map(|row| row.iter().zip(&sigma).map(|(s, q)| s * q).sum()).collect();
+            for i in 0..n_dof {
+                let force: f64 = (0..n).map(|k| psi[k] * coupled.generalized_normal(k, i) * coupled.nodes[k].weight).sum();
+                added_mass.set(i, mode, -rho * force)?;
+            }
+        }
+        Ok(added_mass)
+    }
+    
+    /// Solve radiation and diffraction problems over a frequency/direction grid
+    ///
+    /// Panels, collocation nodes and symmetry orbits are built once and shared
+    /// by every solve; frequencies are scheduled on the rayon pool when
+    /// `parallel` is set. Frequencies not started before the time budget
+    /// expires are left as NaN and reported in `completed`.
+    pub fn solve_sweep(
+        &self,
+        bodies: Vec<FloatingBody>,
+        frequencies: &[f64],
+        directions: &[f64],
+        assembly_config: AssemblyConfig,
+    ) -> Result<HydroCoefficients> {
+        self.run_sweep(bodies, frequencies, directions, assembly_config, None, None)
+    }
+    
+    /// Frequency sweep that inserts frequencies where the coefficients vary rapidly
+    ///
+    /// After each pass, intervals whose samples deviate from linear
+    /// interpolation by more than the refinement tolerance are bisected and
+    /// solved, until no interval is flagged or a limit is reached.
+    pub fn solve_adaptive_sweep(
+        &self,
+        bodies: Vec<FloatingBody>,
+        frequencies: &[f64],
+        directions: &[f64],
+        assembly_config: AssemblyConfig,
+        refinement: &AdaptiveRefinement,
+    ) -> Result<HydroCoefficients> {
+        self.run_sweep(bodies, frequencies, directions, assembly_config, Some(refinement), None)
+    }
+    
+    /// Frequency sweep that saves completed frequencies to `store` every
+    /// `interval` frequencies, so an interrupted run can be resumed with
+    /// `resume_sweep`
+    pub fn solve_sweep_checkpointed(
+        &self,
+        bodies: Vec<FloatingBody>,
+        frequencies: &[f64],
+        directions: &[f64],
+        assembly_config: AssemblyConfig,
+        store: &dyn CheckpointStore,
+        interval: usize,
+    ) -> Result<HydroCoefficients> {
+        let n_dof = bodies.iter().map(|body| body.n_modes()).sum();
+        let checkpoint = SweepCheckpoint::new(frequencies.to_vec(), directions.to_vec(), n_dof, interval);
+        self.run_sweep(bodies, frequencies, directions, assembly_config, None, Some((store, checkpoint)))
+    }
+    
+    /// Resume the checkpointed sweep in `store`, solving only the frequencies
+    /// it has not completed and checkpointing further progress to the same store
+    pub fn resume_sweep(
+        &self,
+        bodies: Vec<FloatingBody>,
+        assembly_config: AssemblyConfig,
+        store: &dyn CheckpointStore,
+    ) -> Result<HydroCoefficients> {
+        let checkpoint = store.load()?.ok_or_else(|| BEMError::InvalidProblem {
+            message: "No sweep checkpoint to resume from".to_string(),
+        })?;
+        let (frequencies, directions) = (checkpoint.frequencies.clone(), checkpoint.directions.clone());
+        self.run_sweep(bodies, &frequencies, &directions, assembly_config, None, Some((store, checkpoint)))
+    }
+    
+    fn run_sweep(
+        &self,
+        bodies: Vec<FloatingBody>,
+        frequencies: &[f64],
+        directions: &[f64],
+        assembly_config: AssemblyConfig,
+        refinement: Option<&AdaptiveRefinement>,
+        checkpoint: Option<(&dyn CheckpointStore, SweepCheckpoint)>,
+    ) -> Result<HydroCoefficients> {
+        let start_time = std::time::Instant::now();
+        let control = self.iterative_control(start_time);
+        if frequencies.is_empty() || frequencies.iter().any(|&f| !(f > 0.0 && f.is_finite())) {
+            return Err(BEMError::InvalidProblem {
+                message: "Sweep frequencies must be positive and finite".to_string(),
+            });
+        }
+        let prepared = self.prepare(&bodies, &assembly_config, &self.config.damping_lids)?;
+        let template = BEMProblem {
+            bodies,
+            problem_type: ProblemType::Radiation { frequency: frequencies[0], mode: 0 },
+            assembly_config,
+        };
+        let n_dof = template.n_dof();
+        
+        let mut pending = frequencies.to_vec();
+        if refinement.is_some() {
+            pending.sort_by(f64::total_cmp);
+            pending.dedup();
+        }
+        let mut samples: Vec<(f64, Option<FrequencyRecord>)> = Vec::new();
+        let completed = AtomicUsize::new(0);
+        if let Some((store, mut checkpoint)) = checkpoint {
+            // Solve missing frequencies in batches, saving after each batch
+            checkpoint.validate(n_dof)?;
+            let missing = checkpoint.pending();
+            for batch in missing.chunks(checkpoint.interval) {
+                let results = self.solve_frequencies(&template, &prepared, batch, directions, &control, (&completed, missing.len()))?;
+                let completed = checkpoint.records.len();
+                checkpoint.records.extend(results.into_iter().flatten());
+                if checkpoint.records.len() > completed {
+                    store.save(&checkpoint)?;
+                }
+            }
+            pending.clear();
+            samples = frequencies
+                .iter()
+                .map(|&f| (f, checkpoint.records.iter().find(|r| r.frequency == f).cloned()))
+                .collect();
+        }
+        let mut passes = 0;
+        while !pending.is_empty() {
+            let total = samples.len() + pending.len();
+            let results = self.solve_frequencies(&template, &prepared, &pending, directions, &control, (&completed, total))?;
+            samples.extend(pending.drain(..).zip(results));
+            
+            let Some(refinement) = refinement else { break };
+            passes += 1;
+            if passes > refinement.max_passes || control.expired() {
+                break;
+            }
+            samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let sampled: Vec<f64> = samples.iter().map(|(f, _)| *f).collect();
+            let values: Vec<Option<Vec<f64>>> = samples.iter().map(|(_, r)| r.as_ref().map(FrequencyRecord::values)).collect();
+            pending = refinement.refine(&sampled, &values);
+        }
         
-        // Extract mesh from body
-        let mut mesh = problem.body.mesh()?.clone();
+        let sampled: Vec<f64> = samples.iter().map(|(f, _)| *f).collect();
+        let mut coefficients = HydroCoefficients::new(sampled, directions.to_vec(), n_dof);
+        for (f, (_, result)) in samples.into_iter().enumerate() {
+            if let Some(result) = result {
+                coefficients.set_frequency(f, &result.added_mass, &result.damping, &result.excitation, result.converged);
+                for (d, drift) in result.mean_drift.iter().enumerate() {
+                    if let Some(drift) = drift {
+                        coefficients.set_mean_drift(f, d, drift);
+                    }
+                }
+                for (d, discrepancy) in result.haskind_discrepancy.iter().enumerate() {
+                    if let Some(discrepancy) = discrepancy {
+                        coefficients.set_haskind_discrepancy(f, d, *discrepancy);
+                    }
+                }
+                coefficients.set_froude_krylov(f, &result.froude_krylov);
+                coefficients.set_diagnostics(f, result.diagnostics);
+            }
+        }
+        coefficients.computation_time = start_time.elapsed().as_secs_f64();
+        Ok(coefficients)
+    }
+    
+    /// Solve all modes and directions at each frequency, in parallel if configured
+    ///
+    /// Solved frequencies advance the `(completed, total)` progress of the sweep.
+    fn solve_frequencies(
+        &self,
+        template: &BEMProblem,
+        prepared: &PreparedGeometry,
+        frequencies: &[f64],
+        directions: &[f64],
+        control: &IterativeControl,
+        (completed, total): (&AtomicUsize, usize),
+    ) -> Result<Vec<Option<FrequencyRecord>>> {
+        let n_dof = template.n_dof();
+        let solve_frequency = |frequency: f64| -> Result<Option<FrequencyRecord>> {
+            self.monitor.check()?;
+            if control.expired() {
+                return Ok(None);
+            }
+            let mut out = FrequencyRecord {
+                frequency,
+                added_mass: vec![0.0; n_dof * n_dof],
+                damping: vec![0.0; n_dof * n_dof],
+                excitation: Vec::with_capacity(directions.len() * n_dof),
+                froude_krylov: Vec::with_capacity(directions.len() * n_dof),
+                haskind_discrepancy: Vec::with_capacity(directions.len()),
+                mean_drift: Vec::with_capacity(directions.len()),
+                diagnostics: QualityDiagnostics::new(self.config.diagnostic_tolerance),
+                converged: true,
+            };
+            let mut checks = Vec::with_capacity(n_dof + directions.len());
+            
+            // Every mode and direction against one assembly and factorization
+            let mut problem_types: Vec<ProblemType> = (0..n_dof).map(|mode| ProblemType::Radiation { frequency, mode }).collect();
+            problem_types.extend(directions.iter().map(|&direction| ProblemType::Diffraction { frequency, direction }));
+            let mut results = self.solve_batch(template, &problem_types, prepared, control, None)?;
+            let diffraction = results.split_off(n_dof);
+            
+            for (mode, result) in results.into_iter().enumerate() {
+                out.converged &= result.is_converged();
+                checks.push(result.diagnostics.clone());
+                if let (Some(a), Some(b)) = (&result.added_mass, &result.damping) {
+                    for i in 0..n_dof {
+                        out.added_mass[i * n_dof + mode] = a.get(i, mode)?;
+                        out.damping[i * n_dof + mode] = b.get(i, mode)?;
+                    }
+                }
+            }
+            for result in diffraction {
+                out.converged &= result.is_converged();
+                out.mean_drift.push(result.mean_drift);
+                out.haskind_discrepancy.push(result.haskind_discrepancy);
+                checks.push(result.diagnostics);
+                out.excitation.extend(result.excitation_force.unwrap_or_else(|| vec![Complex64::new(0.0, 0.0); n_dof]));
+                out.froude_krylov.extend(result.froude_krylov_force.unwrap_or_else(|| vec![Complex64::new(0.0, 0.0); n_dof]));
+            }
+            
+            // Worst per-solve checks, plus reciprocity of the complete matrices
+            out.diagnostics = QualityDiagnostics::worst(&checks, self.config.diagnostic_tolerance);
+            out.diagnostics.check_reciprocity(
+                &Matrix::from_vec(n_dof, n_dof, out.added_mass.clone())?,
+                &Matrix::from_vec(n_dof, n_dof, out.damping.clone())?,
+            );
+            self.monitor.advance(completed, total, |completed, total| Progress::FrequenciesCompleted { completed, total });
+            Ok(Some(out))
+        };
         
-        // Validate mesh
-        if mesh.panels()?.is_empty() {
+        // Recycling carries the Krylov space from each frequency to the next
+        if self.config.parallel && self.config.recycle_space == 0 {
+            frequencies.par_iter().map(|&f| solve_frequency(f)).collect()
+        } else {
+            frequencies.iter().map(|&f| solve_frequency(f)).collect()
+        }
+    }
+    
+    /// Iterative-solver control derived from the configuration
+    fn iterative_control(&self, start_time: std::time::Instant) -> IterativeControl {
+        IterativeControl {
+            tolerance: self.config.tolerance,
+            max_iterations: self.config.max_iterations,
+            restart: None,
+            deadline: self.config.time_budget.map(|t| start_time + std::time::Duration::from_secs_f64(t.max(0.0))),
+        }
+    }
+    
+    /// Frequency-independent preprocessing of the bodies' meshes and damping lids
+    fn prepare(&self, bodies: &[FloatingBody], assembly_config: &AssemblyConfig, lids: &[DampingLid]) -> Result<PreparedGeometry> {
+        if bodies.is_empty() {
             return Err(BEMError::InvalidProblem {
-                message: "Mesh has no panels".to_string(),
+                message: "Problem has no bodies".to_string(),
             });
         }
+        if !lids.is_empty() && (self.config.panel_order != PanelOrder::Constant || self.config.formulation != Formulation::Indirect) {
+            return Err(BEMError::InvalidProblem {
+                message: "Damping lids require constant panels and the indirect formulation".to_string(),
+            });
+        }
+        
+        // Gather panels of all bodies into one coupled system
+        let rule = triangle_rule(assembly_config.integration_points);
+        let coupled = CoupledPanels::from_bodies(bodies, self.config.panel_order, &rule)?.with_lids(lids, &rule)?;
+        
+        // The H-matrix engine and symmetry reduction are available for constant
+        // panels; lid rows carry their own condition and use the dense path
+        let hierarchical = matches!(self.config.engine, SolverEngine::HierarchicalMatrix);
+        let (symmetry, partition) = match coupled.order {
+            _ if coupled.has_lids() => (None, None),
+            PanelOrder::Constant if hierarchical => {
+                let centroids: Vec<Point> = coupled.panels.iter().map(|p| p.centroid()).collect();
+                (None, Some(BlockPartition::new(&centroids, &self.config.hmatrix)))
+            }
+            PanelOrder::Constant => (
+                SymmetryMap::build(&coupled.panels, self.config.symmetry)
+                    .map_err(|message| BEMError::InvalidProblem { message })?,
+                None,
+            ),
+            _ => (None, None),
+        };
+        
+        let rankine = (self.config.reuse_rankine && coupled.order == PanelOrder::Constant && partition.is_none()).then(RankineCache::default);
+        
+        Ok(PreparedGeometry { rule, coupled, symmetry, partition, rankine })
+    }
+    
+    /// Solve one problem on preprocessed geometry
+    fn solve_prepared(
+        &self,
+        problem: &BEMProblem,
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+    ) -> Result<BEMResult> {
+        self.solve_prepared_with(problem, prepared, control, None)
+    }
+    
+    /// Solve one problem, reusing radiation potentials of every mode at the
+    /// same frequency for the Haskind check when available
+    fn solve_prepared_with(
+        &self,
+        problem: &BEMProblem,
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+        radiation: Option<&[Vec<Complex64>]>,
+    ) -> Result<BEMResult> {
+        let mut results = self.solve_batch(problem, std::slice::from_ref(&problem.problem_type), prepared, control, radiation)?;
+        Ok(results.remove(0))
+    }
+    
+    /// Solve radiation and diffraction problems at one frequency together
+    ///
+    /// The influence matrices are assembled once and direct solvers factorize
+    /// once for every right-hand side. Diffraction problems take the radiation
+    /// potentials for the Haskind check from the batch when it holds every
+    /// mode, else from `radiation` or from a separate batch. Without
+    /// `BEMConfig::parallel` the matrix kernels stay on the calling thread, and
+    /// with `BEMConfig::deterministic` they keep a fixed summation order.
+    fn solve_batch(
+        &self,
+        template: &BEMProblem,
+        problem_types: &[ProblemType],
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+        radiation: Option<&[Vec<Complex64>]>,
+    ) -> Result<Vec<BEMResult>> {
+        let solve = || {
+            if self.config.parallel {
+                self.solve_batch_with_kernels(template, problem_types, prepared, control, radiation)
+            } else {
+                wavecore_matrices::serial(|| self.solve_batch_with_kernels(template, problem_types, prepared, control, radiation))
+            }
+        };
+        if self.config.deterministic {
+            wavecore_matrices::deterministic(solve)
+        } else {
+            solve()
+        }
+    }
+    
+    fn solve_batch_with_kernels(
+        &self,
+        template: &BEMProblem,
+        problem_types: &[ProblemType],
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+        radiation: Option<&[Vec<Complex64>]>,
+    ) -> Result<Vec<BEMResult>> {
+        let solve_start = std::time::Instant::now();
+        let coupled = &prepared.coupled;
+        let problems: Vec<BEMProblem> = problem_types
+            .iter()
+            .map(|problem_type| BEMProblem { problem_type: problem_type.clone(), ..template.clone() })
+            .collect();
+        let Some(first) = problems.first() else { return Ok(Vec::new()) };
         
-        // Set up Green function
-        let green_function = self.setup_green_function(problem)?;
+        // Set up Green function, shared by every problem of the batch
+        let green_params = self.green_function_params(first);
+        if problems.iter().any(|p| self.green_function_params(p).frequency != green_params.frequency) {
+            return Err(BEMError::InvalidProblem {
+                message: "Problems solved together must share their frequency".to_string(),
+            });
+        }
+        let green_function = GreenFunction::new(green_params.clone())?;
         
-        // Assemble BEM matrix
-        let bem_matrix = self.assemble_bem_matrix(&mut mesh, &green_function, &problem.assembly_config)?;
+        // Set up right-hand sides (normal velocity) based on problem type
+        let rhs = problems
+            .iter()
+            .map(|problem| self.setup_right_hand_side(problem, coupled))
+            .collect::<Result<Vec<_>>>()?;
+        let solver = LinearSolver::new(template.assembly_config.solver_type);
+        let config = &template.assembly_config;
         
-        // Set up right-hand side based on problem type
-        let rhs = self.setup_right_hand_side(problem, &mut mesh)?;
+        let solutions = match (&prepared.partition, &prepared.symmetry) {
+            (Some(partition), _) => self.solve_hierarchical(partition, coupled, &rhs, &green_function, config, control)?,
+            (None, Some(map)) => self.solve_symmetric(map, prepared, &rhs, &green_function, config, &solver, control)?,
+            (None, None) => self.solve_full(prepared, &rhs, &green_function, config, &solver, control)?,
+        };
+        let elapsed = solve_start.elapsed();
         
-        // Solve linear system
-        let solver = LinearSolver::new(problem.assembly_config.solver_type);
-        let potential = solver.solve(&bem_matrix, &rhs)?;
+        // Radiation problems first, so diffraction can reuse their potentials
+        let mut batch: Vec<_> = problems.into_iter().zip(rhs).zip(solutions).enumerate().collect();
+        batch.sort_by_key(|(_, ((problem, _), _))| !matches!(problem.problem_type, ProblemType::Radiation { .. }));
+        let mut modes: Vec<Option<Vec<Complex64>>> = vec![None; template.n_dof()];
+        let mut results: Vec<Option<BEMResult>> = vec![None; batch.len()];
+        for (index, ((problem, rhs), solution)) in batch {
+            let own: Option<Vec<Vec<Complex64>>> = modes.iter().cloned().collect();
+            let reuse = own.as_deref().or(radiation);
+            let solved = SolvedSystem { green_function: &green_function, green_params: &green_params, rhs, solution, elapsed };
+            let result = self.finish_solution(&problem, prepared, control, solved, reuse)?;
+            if let ProblemType::Radiation { mode, .. } = problem.problem_type {
+                modes[mode] = Some(result.potential.clone());
+            }
+            results[index] = Some(result);
+        }
+        Ok(results.into_iter().flatten().collect())
+    }
+    
+    /// Post-process one solution of a batch into its result
+    fn finish_solution(
+        &self,
+        problem: &BEMProblem,
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+        solved: SolvedSystem,
+        radiation: Option<&[Vec<Complex64>]>,
+    ) -> Result<BEMResult> {
+        let coupled = &prepared.coupled;
+        let SolvedSystem { green_function, green_params, rhs, solution: (potential, source_strength, outcome), elapsed } = solved;
         
         // Post-process results
-        let result = self.post_process_results(problem, potential, start_time.elapsed())?;
+        let mut result = self.post_process_results(problem, coupled, potential, elapsed)?;
+        
+        // Radiated energy of the sources against the near-field damping
+        if let (ProblemType::Radiation { frequency, mode }, Some(sigma), Some(damping)) =
+            (&problem.problem_type, &source_strength, &result.damping)
+        {
+            let points: Vec<Point> = coupled.nodes.iter().map(|n| n.position).collect();
+            let weights: Vec<f64> = coupled.nodes.iter().map(|n| n.weight).collect();
+            let kochin = KochinFunction::from_sources(&points, sigma, &weights, *frequency, 9.81);
+            let far_field = kochin.radiated_damping(1025.0, DEFAULT_KOCHIN_ANGLES);
+            result.diagnostics.check_energy(damping.get(*mode, *mode)?, far_field);
+        }
+        
+        // Far-field drift from the Kochin function of the scattering sources
+        if let (ProblemType::Diffraction { frequency, direction }, Some(sigma)) = (&problem.problem_type, &source_strength) {
+            let points: Vec<Point> = coupled.nodes.iter().map(|n| n.position).collect();
+            let weights: Vec<f64> = coupled.nodes.iter().map(|n| n.weight).collect();
+            let kochin = KochinFunction::from_sources(&points, sigma, &weights, *frequency, 9.81);
+            result.mean_drift = Some(kochin.mean_drift(*direction, 1025.0, DEFAULT_KOCHIN_ANGLES));
+            if coupled.order == PanelOrder::Constant && !coupled.has_lids() {
+                let field = self.first_order_field(coupled, sigma, green_function, *frequency, *direction);
+                result.near_field_drift = Some(self.near_field_drift(coupled, &field));
+                result.first_order = Some(field);
+            }
+        }
+        
+        // Excitation from the radiation potentials through the Haskind relations
+        if let (ProblemType::Diffraction { frequency, direction }, true) = (&problem.problem_type, self.config.haskind) {
+            let solved;
+            let radiation = match radiation {
+                Some(radiation) => radiation,
+                None => {
+                    solved = self.radiation_potentials(problem, prepared, control, *frequency)?;
+                    &solved
+                }
+            };
+            let haskind = self.haskind_excitation(coupled, radiation, &rhs, *frequency, *direction);
+            result.haskind_discrepancy = result.excitation_force.as_ref().map(|direct| relative_discrepancy(direct, &haskind));
+            result.diagnostics.haskind_discrepancy = result.haskind_discrepancy;
+            result.haskind_excitation = Some(haskind);
+        }
+        result.panel_output = Some(self.panel_output(problem, coupled, &result.potential, source_strength.as_deref(), &rhs, green_params));
+        result.boundary = Some(BoundaryDistribution {
+            green_params: green_params.clone(),
+            positions: coupled.nodes.iter().map(|n| n.position).collect(),
+            normals: coupled.nodes.iter().map(|n| n.normal).collect(),
+            weights: coupled.nodes.iter().map(|n| n.weight).collect(),
+            normal_velocity: rhs,
+            incident_heading: match problem.problem_type {
+                ProblemType::Diffraction { direction, .. } => Some(direction),
+                _ => None,
+            },
+        });
+        result.source_strength = source_strength;
+        result.iterations = Some(outcome.iterations);
+        result.residual = Some(outcome.residual);
+        result.residual_history = outcome.history;
+        result.converged = outcome.converged;
+        result.timed_out = outcome.timed_out;
         
         Ok(result)
     }
     
-    /// Set up Green function for the problem
-    fn setup_green_function(&self, problem: &BEMProblem) -> Result<GreenFunction> {
+    /// Assemble and solve the full system in the configured formulation for every right-hand side
+    #[allow(clippy::too_many_arguments)]
+    fn solve_full(
+        &self,
+        prepared: &PreparedGeometry,
+        rhs: &[Vec<Complex64>],
+        green_function: &GreenFunction,
+        config: &AssemblyConfig,
+        solver: &LinearSolver,
+        control: &IterativeControl,
+    ) -> Result<Vec<SystemSolution>> {
+        let coupled = &prepared.coupled;
+        
+        // Assemble single-layer influence matrix
+        let bem_matrix = self.influence_matrix(prepared, green_function, config, Kernel::Single)?;
+        
+        match self.config.formulation {
+            Formulation::Indirect => {
+                // (½I + K) σ = ∂φ/∂n, then φ = S σ; lid rows impose
+                // (∂/∂n + K(1 + iε)) φ with the normal pointing down into the fluid
+                let mut k_matrix = self.influence_matrix(prepared, green_function, config, Kernel::Double { source_normal: false })?;
+                let n = k_matrix.cols;
+                for (i, factor) in coupled.lid_factors(green_function.params().frequency) {
+                    for j in 0..n {
+                        k_matrix.data[i * n + j] += factor * bem_matrix.data[i * n + j];
+                    }
+                }
+                let outcomes = self.solve_dense(solver, coupled, &k_matrix, rhs, control)?;
+                Ok(outcomes
+                    .into_iter()
+                    .map(|mut outcome| {
+                        let sigma = std::mem::take(&mut outcome.solution);
+                        (matrix_vector(&bem_matrix, &sigma), Some(sigma), outcome)
+                    })
+                    .collect())
+            }
+            Formulation::Direct => {
+                // (½I + D) φ = S ∂φ/∂n
+                let d_matrix = self.influence_matrix(prepared, green_function, config, Kernel::Double { source_normal: true })?;
+                let rhs: Vec<Vec<Complex64>> = rhs.iter().map(|b| matrix_vector(&bem_matrix, b)).collect();
+                let outcomes = self.solve_dense(solver, coupled, &d_matrix, &rhs, control)?;
+                Ok(outcomes
+                    .into_iter()
+                    .map(|mut outcome| (std::mem::take(&mut outcome.solution), None, outcome))
+                    .collect())
+            }
+        }
+    }
+    
+    /// Solve one reduced system per symmetry class and recombine, for every right-hand side
+    ///
+    /// Only rows of the fundamental region are assembled, once against every
+    /// panel, and each class reduces them with its characters.
+    #[allow(clippy::too_many_arguments)]
+    fn solve_symmetric(
+        &self,
+        map: &SymmetryMap,
+        prepared: &PreparedGeometry,
+        rhs: &[Vec<Complex64>],
+        green_function: &GreenFunction,
+        config: &AssemblyConfig,
+        solver: &LinearSolver,
+        control: &IterativeControl,
+    ) -> Result<Vec<SystemSolution>> {
+        let panels = &prepared.coupled.panels;
+        let rows: Vec<usize> = map.orbits.iter().map(|orbit| orbit[0]).collect();
+        let double_layer = Kernel::Double { source_normal: self.config.formulation == Formulation::Direct };
+        let s_blocks = self.influence_blocks(prepared, &rows, green_function, config, Kernel::Single)?;
+        let d_blocks = self.influence_blocks(prepared, &rows, green_function, config, double_layer)?;
+        
+        let mut potentials = vec![Vec::with_capacity(map.group_size()); rhs.len()];
+        let mut sources = vec![Vec::with_capacity(map.group_size()); rhs.len()];
+        let mut combined: Vec<IterativeOutcome<Complex64>> = rhs
+            .iter()
+            .map(|_| IterativeOutcome {
+                solution: Vec::new(),
+                iterations: 0,
+                residual: 0.0,
+                converged: true,
+                timed_out: false,
+                history: Vec::new(),
+            })
+            .collect();
+        
+        for class in 0..map.group_size() {
+            let s_reduced = s_blocks.reduce(map, class)?;
+            let f_reduced: Vec<Vec<Complex64>> = rhs.iter().map(|b| map.project(b, class)).collect();
+            let identity = |x: &[Complex64]| Ok(x.to_vec());
+            
+            let solved: Vec<SystemSolution> = match self.config.formulation {
+                Formulation::Indirect => {
+                    let k_reduced = d_blocks.reduce(map, class)?;
+                    solver
+                        .solve_complex_multi_preconditioned(&k_reduced, &f_reduced, identity, control)?
+                        .into_iter()
+                        .map(|mut outcome| {
+                            let sigma = std::mem::take(&mut outcome.solution);
+                            (matrix_vector(&s_reduced, &sigma), Some(sigma), outcome)
+                        })
+                        .collect()
+                }
+                Formulation::Direct => {
+                    let d_reduced = d_blocks.reduce(map, class)?;
+                    let f_reduced: Vec<Vec<Complex64>> = f_reduced.iter().map(|f| matrix_vector(&s_reduced, f)).collect();
+                    solver
+                        .solve_complex_multi_preconditioned(&d_reduced, &f_reduced, identity, control)?
+                        .into_iter()
+                        .map(|mut outcome| (std::mem::take(&mut outcome.solution), None, outcome))
+                        .collect()
+                }
+            };
+            
+            for (k, (potential, source, outcome)) in solved.into_iter().enumerate() {
+                let total = &mut combined[k];
+                total.iterations += outcome.iterations;
+                total.residual = total.residual.max(outcome.residual);
+                total.converged &= outcome.converged;
+                total.timed_out |= outcome.timed_out;
+                total.history.extend(outcome.history);
+                potentials[k].push(potential);
+                if let Some(source) = source {
+                    sources[k].push(source);
+                }
+            }
+        }
+        
+        let n = panels.len();
+        Ok(combined
+            .into_iter()
+            .zip(potentials.iter().zip(&sources))
+            .map(|(outcome, (potentials, sources))| {
+                let source_strength = if sources.is_empty() {
+                    None
+                } else {
+                    Some(map.reconstruct(sources, n))
+                };
+                (map.reconstruct(potentials, n), source_strength, outcome)
+            })
+            .collect())
+    }
+    
+    /// Radiation potentials of every mode at `frequency`
+    fn radiation_potentials(
+        &self,
+        problem: &BEMProblem,
+        prepared: &PreparedGeometry,
+        control: &IterativeControl,
+        frequency: f64,
+    ) -> Result<Vec<Vec<Complex64>>> {
+        let problem_types: Vec<ProblemType> = (0..problem.n_dof()).map(|mode| ProblemType::Radiation { frequency, mode }).collect();
+        let results = self.solve_batch(problem, &problem_types, prepared, control, None)?;
+        Ok(results.into_iter().map(|result| result.potential).collect())
+    }
+    
+    /// Excitation from the radiation potentials φ_k (Haskind relations)
+    ///
+    /// Green's second identity between φ_k and the diffraction potential gives
+    /// F_k = ∫ (-iωρ φ_I n_k - ρ φ_k ∂φ_I/∂n) dS, so no diffraction solution
+    /// is needed. `rhs` is the diffraction boundary condition -∂φ_I/∂n.
+    fn haskind_excitation(
+        &self,
+        coupled: &CoupledPanels,
+        radiation: &[Vec<Complex64>],
+        rhs: &[Complex64],
+        frequency: f64,
+        direction: f64,
+    ) -> Vec<Complex64> {
+        let (rho, g) = (1025.0, 9.81);
+        let incident: Vec<Complex64> = coupled
+            .nodes
+            .iter()
+            .map(|node| incident_potential(frequency, g, direction, &node.position))
+            .collect();
+        radiation
+            .iter()
+            .enumerate()
+            .map(|(k, phi_k)| {
+                (0..coupled.n_body_nodes)
+                    .map(|j| {
+                        let froude_krylov = Complex64::new(0.0, -frequency * rho) * incident[j] * coupled.generalized_normal(j, k);
+                        (froude_krylov + rho * phi_k[j] * rhs[j]) * coupled.nodes[j].weight
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+    
+    /// Solve with H-matrix compressed operators and GMRES
+    ///
+    /// The linear solver type of the assembly configuration is ignored: a
+    /// compressed matrix only supports products, so GMRES is always used.
+    fn solve_hierarchical(
+        &self,
+        partition: &BlockPartition,
+        coupled: &CoupledPanels,
+        rhs: &[Vec<Complex64>],
+        green_function: &GreenFunction,
+        config: &AssemblyConfig,
+        control: &IterativeControl,
+    ) -> Result<Vec<SystemSolution>> {
+        let panels = &coupled.panels;
+        let compress = |coefficient: &(dyn Fn(usize, usize) -> Result<Complex64> + Sync)| {
+            HMatrix::assemble(partition, &self.config.hmatrix, config.parallel, coefficient)
+        };
+        let source_normal = self.config.formulation == Formulation::Direct;
+        let double_layer = |i: usize, j: usize| {
+            self.double_layer_coefficient(i, j, panels, green_function, source_normal, KernelPart::Full)
+        };
+        
+        let s_matrix = compress(&|i, j| self.compute_influence_coefficient(i, j, panels, green_function, KernelPart::Full))?;
+        let system = compress(&double_layer)?;
+        let preconditioner = self.preconditioner(coupled, double_layer)?;
+        let gmres = |b: &[Complex64]| {
+            self.gmres(
+                &system,
+                |x| Ok(preconditioner.as_ref().map_or_else(|| x.to_vec(), |p| p.apply(x))),
+                b,
+                control,
+            )
+        };
+        
+        rhs.iter()
+            .map(|rhs| match self.config.formulation {
+                Formulation::Indirect => {
+                    let mut outcome = gmres(rhs)?;
+                    let sigma = std::mem::take(&mut outcome.solution);
+                    Ok((s_matrix.matvec(&sigma), Some(sigma), outcome))
+                }
+                Formulation::Direct => {
+                    let mut outcome = gmres(&s_matrix.matvec(rhs))?;
+                    let potential = std::mem::take(&mut outcome.solution);
+                    Ok((potential, None, outcome))
+                }
+            })
+            .collect()
+    }
+    
+    /// Solve a dense system for every right-hand side, preconditioned when the solver is iterative
+    fn solve_dense(
+        &self,
+        solver: &LinearSolver,
+        coupled: &CoupledPanels,
+        matrix: &ComplexMatrix,
+        rhs: &[Vec<Complex64>],
+        control: &IterativeControl,
+    ) -> Result<Vec<IterativeOutcome<Complex64>>> {
+        let preconditioner = match solver.solver_type() {
+            SolverType::GMRES | SolverType::BiCGSTAB => {
+                let n = matrix.cols;
+                self.preconditioner(coupled, |i, j| Ok(matrix.data[i * n + j]))?
+            }
+            _ => None,
+        };
+        let precondition = |x: &[Complex64]| Ok(preconditioner.as_ref().map_or_else(|| x.to_vec(), |p| p.apply(x)));
+        let outcomes = match solver.solver_type() {
+            SolverType::GMRES if self.config.recycle_space > 0 => {
+                rhs.iter().map(|b| self.gmres(matrix, precondition, b, control)).collect::<Result<_>>()?
+            }
+            _ => solver.solve_complex_multi_preconditioned(matrix, rhs, precondition, control)?,
+        };
+        Ok(outcomes)
+    }
+    
+    /// GMRES, recycling the Krylov space of earlier solves when `BEMConfig::recycle_space` is set
+    fn gmres<A, P>(&self, a: &A, precondition: P, b: &[Complex64], control: &IterativeControl) -> Result<IterativeOutcome<Complex64>>
+    where
+        A: LinearOperator<Complex64> + ?Sized,
+        P: Fn(&[Complex64]) -> wavecore_matrices::Result<Vec<Complex64>>,
+    {
+        if self.config.recycle_space == 0 {
+            return Ok(complex_gmres_iterate(a, precondition, b, control)?);
+        }
+        let mut recycle = self.recycle.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok(complex_gcrodr_iterate(a, precondition, b, control, &mut recycle)?)
+    }
+    
+    /// Configured preconditioner over the collocation nodes, from matrix entries
+    fn preconditioner<F>(&self, coupled: &CoupledPanels, entry: F) -> Result<Option<Preconditioner>>
+    where
+        F: Fn(usize, usize) -> Result<Complex64> + Sync,
+    {
+        let positions: Vec<Point> = coupled.nodes.iter().map(|n| n.position).collect();
+        let groups: Vec<usize> = coupled.nodes.iter().map(|n| n.body).collect();
+        Preconditioner::build(self.config.preconditioning, &positions, &groups, entry)
+    }
+    
+    /// Green function parameters for the problem
+    fn green_function_params(&self, problem: &BEMProblem) -> GreenFunctionParams {
         let frequency = match &problem.problem_type {
             ProblemType::Radiation { frequency, .. } => *frequency,
             ProblemType::Diffraction { frequency, .. } => *frequency,
             ProblemType::Combined { frequency, .. } => *frequency,
         };
         
-        let params = GreenFunctionParams {
+        GreenFunctionParams {
             method: problem.assembly_config.green_function_method,
             frequency,
             depth: f64::INFINITY, // TODO: Add depth support
             gravity: 9.81,
             ..Default::default()
+        }
+    }
+    
+    /// Influence matrix of `kernel` over every collocation node
+    fn influence_matrix(
+        &self,
+        prepared: &PreparedGeometry,
+        green_function: &GreenFunction,
+        config: &AssemblyConfig,
+        kernel: Kernel,
+    ) -> Result<ComplexMatrix> {
+        let coupled = &prepared.coupled;
+        match (coupled.order, kernel) {
+            (PanelOrder::Constant, _) => {
+                let rows: Vec<usize> = (0..coupled.panels.len()).collect();
+                Ok(self.influence_blocks(prepared, &rows, green_function, config, kernel)?.matrix)
+            }
+            (_, Kernel::Single) => self.assemble_higher_order_matrix(coupled, &prepared.rule, green_function, config, None),
+            (_, Kernel::Double { source_normal }) => {
+                self.assemble_higher_order_matrix(coupled, &prepared.rule, green_function, config, Some(source_normal))
+            }
+        }
+    }
+    
+    /// Constant-panel coefficients of `kernel` for the panels `rows` against every panel
+    ///
+    /// With a Rankine cache only the wave part is assembled here.
+    fn influence_blocks(
+        &self,
+        prepared: &PreparedGeometry,
+        rows: &[usize],
+        green_function: &GreenFunction,
+        config: &AssemblyConfig,
+        kernel: Kernel,
+    ) -> Result<InfluenceBlocks> {
+        let panels = &prepared.coupled.panels;
+        let assemble = |part: KernelPart| -> Result<InfluenceBlocks> {
+            let coefficient = |i: usize, j: usize| match kernel {
+                Kernel::Single => self.compute_influence_coefficient(i, j, panels, green_function, part),
+                Kernel::Double { source_normal } => self.double_layer_coefficient(i, j, panels, green_function, source_normal, part),
+            };
+            let matrix = self.assemble_rows(rows.len(), config, |r| {
+                (0..panels.len()).map(|j| coefficient(rows[r], j)).collect()
+            })?;
+            Ok(InfluenceBlocks { rows: rows.to_vec(), matrix })
         };
         
-        let green_function = GreenFunction::new(params)?;
-        Ok(green_function)
+        match &prepared.rankine {
+            Some(cache) => {
+                let rankine = cache.get_or_assemble(kernel, || assemble(KernelPart::Rankine))?;
+                let mut blocks = assemble(KernelPart::Wave)?;
+                blocks.add(rankine)?;
+                Ok(blocks)
+            }
+            None => assemble(KernelPart::Full),
+        }
     }
     
-    /// Assemble BEM influence matrix
-    fn assemble_bem_matrix(
-        &self, 
-        mesh: &mut Mesh, 
+    /// Assemble a higher-order influence matrix by quadrature over each panel
+    ///
+    /// `double_layer` selects the kernel: `None` for G, `Some(source_normal)`
+    /// for ∂G/∂n (plus the ½I jump term) as in the constant-panel assembly.
+    fn assemble_higher_order_matrix(
+        &self,
+        coupled: &CoupledPanels,
+        rule: &[([f64; 3], f64)],
         green_function: &GreenFunction,
-        config: &AssemblyConfig
-    ) -> Result<Matrix> {
-        let panels = mesh.panels()?;
-        let n_panels = panels.len();
-        
-        // Initialize matrix
-        let mut matrix_data = vec![0.0; n_panels * n_panels];
+        config: &AssemblyConfig,
+        double_layer: Option<bool>,
+    ) -> Result<ComplexMatrix> {
+        let n_nodes = coupled.n_nodes();
+        let shape: Vec<Vec<f64>> = rule.iter().map(|(l, _)| shape_functions(coupled.order, *l)).collect();
         
-        if config.parallel {
-            // Parallel assembly using rayon
-            let matrix_rows: Vec<Vec<f64>> = (0..n_panels)
-                .into_par_iter()
-                .map(|i| {
-                    let mut row = vec![0.0; n_panels];
-                    for j in 0..n_panels {
-                        row[j] = self.compute_influence_coefficient(
-                            i, j, &panels, green_function, config
-                        ).unwrap_or(0.0);
-                    }
-                    row
-                })
-                .collect();
+        self.assemble_rows(n_nodes, config, |p| {
+            let node = &coupled.nodes[p];
+            let mut row = vec![Complex64::new(0.0, 0.0); n_nodes];
             
-            // Copy results to matrix_data
-            for (i, row) in matrix_rows.iter().enumerate() {
-                for (j, value) in row.iter().enumerate() {
-                    matrix_data[i * n_panels + j] = *value;
+            for (panel, element) in coupled.panels.iter().zip(&coupled.elements) {
+                for ((l, w), n) in rule.iter().zip(&shape) {
+                    let xi = map_to_panel(panel, *l);
+                    let kernel = match double_layer {
+                        None => green_value(green_function, &node.position, &xi),
+                        Some(true) => green_normal_derivative(green_function, &node.position, &xi, &panel.normal(), true),
+                        Some(false) => green_normal_derivative(green_function, &node.position, &xi, &node.normal, false),
+                    };
+                    let weight = kernel * (w * panel.area());
+                    for (a, &q) in element.iter().enumerate() {
+                        row[q] += weight * n[a];
+                    }
                 }
             }
-        } else {
-            // Sequential assembly
-            for i in 0..n_panels {
-                for j in 0..n_panels {
-                    matrix_data[i * n_panels + j] = self.compute_influence_coefficient(
-                        i, j, &panels, green_function, config
-                    )?;
-                }
+            
+            if double_layer.is_some() {
+                row[p] += 0.5;
             }
+            Ok(row)
+        })
+    }
+    
+    /// Double-layer coefficient with the ½I jump term
+    ///
+    /// With `source_normal` the kernel is ∂G/∂n at the source panel (D, direct
+    /// formulation), otherwise at the field panel (K, indirect formulation).
+    fn double_layer_coefficient(
+        &self,
+        field_panel: usize,
+        source_panel: usize,
+        panels: &[Panel],
+        green_function: &GreenFunction,
+        source_normal: bool,
+        part: KernelPart,
+    ) -> Result<Complex64> {
+        if field_panel != source_panel {
+            return self.compute_normal_derivative(field_panel, source_panel, panels, green_function, source_normal, part);
         }
+        // Flat panels see no normal derivative of their own source
+        Ok(if part.has_jump() { Complex64::new(0.5, 0.0) } else { Complex64::new(0.0, 0.0) })
+    }
+    
+    /// Fill a matrix of `n` rows row by row, in parallel if configured
+    fn assemble_rows<F>(&self, n: usize, config: &AssemblyConfig, row: F) -> Result<ComplexMatrix>
+    where
+        F: Fn(usize) -> Result<Vec<Complex64>> + Sync,
+    {
+        let assembled = AtomicUsize::new(0);
+        let row = |i: usize| -> Result<Vec<Complex64>> {
+            self.monitor.check()?;
+            let values = row(i)?;
+            self.monitor.advance(&assembled, n, |completed, total| Progress::PanelsAssembled { completed, total });
+            Ok(values)
+        };
+        let matrix_rows: Vec<Vec<Complex64>> = if config.parallel {
+            // Parallel assembly using rayon
+            (0..n).into_par_iter().map(&row).collect::<Result<_>>()?
+        } else {
+            (0..n).map(&row).collect::<Result<_>>()?
+        };
         
-        Ok(Matrix::from_vec(n_panels, n_panels, matrix_data)?)
+        let cols = matrix_rows.first().map_or(n, Vec::len);
+        Ok(ComplexMatrix::from_vec(n, cols, matrix_rows.concat())?)
+    }
+    
+    /// Compute normal derivative of the Green function between two panels
+    fn compute_normal_derivative(
+        &self,
+        field_panel: usize,
+        source_panel: usize,
+        panels: &[Panel],
+        green_function: &GreenFunction,
+        source_normal: bool,
+        part: KernelPart,
+    ) -> Result<Complex64> {
+        let source = &panels[source_panel];
+        let field = &panels[field_panel];
+        let normal = if source_normal { source.normal() } else { field.normal() };
+        
+        let derivative = kernel_normal_derivative(
+            green_function, part, &field.centroid(), &source.centroid(), &normal, source_normal,
+        );
+        
+        Ok(derivative * source.area())
     }
     
     /// Compute influence coefficient between two panels
@@ -234,8 +1463,8 @@ impl BEMSolverImpl {
         field_panel: usize,
         panels: &[Panel],
         green_function: &GreenFunction,
-        config: &AssemblyConfig,
-    ) -> Result<f64> {
+        part: KernelPart,
+    ) -> Result<Complex64> {
         let source = &panels[source_panel];
         let field = &panels[field_panel];
         
@@ -245,7 +1474,7 @@ impl BEMSolverImpl {
         
         if source_panel == field_panel {
             // Singular case - use specialized integration
-            self.compute_singular_influence(source, green_function, config)
+            self.compute_singular_influence(source, green_function, part)
         } else {
             // Regular case - direct Green function evaluation
             let r1 = Point3::new(field_center.x, field_center.y, field_center.z);
@@ -255,206 +1484,661 @@ impl BEMSolverImpl {
             let r = ((r2.x - r1.x).powi(2) + (r2.y - r1.y).powi(2)).sqrt();
             let z = r2.z - r1.z;
             
-            match green_function.evaluate(r, z) {
+            match part.evaluate(green_function, r, z) {
                 Ok(g_value) => {
                     // Apply panel area weighting
                     let area = source.area();
-                    Ok(g_value.re * area) // Take real part for BEM matrix
+                    Ok(g_value * area)
                 }
-                Err(_) => Ok(0.0), // Handle errors gracefully
+                Err(_) => Ok(part.fallback(r, z) * source.area()), // Handle errors gracefully
             }
         }
     }
     
-    /// Compute singular influence coefficient (self-influence)
-    fn compute_singular_influence(
-        &self,
-        panel: &Panel,
-        green_function: &GreenFunction,
-        config: &AssemblyConfig,
-    ) -> Result<f64> {
-        // For singular panels, use analytical or numerical integration
-        // This is a simplified implementation - real BEM would use more sophisticated methods
-        
+    /// Self-influence of a flat panel, integrated over a disk of the panel's area
+    ///
+    /// The Rankine part -i/(4πR) integrates to -ia/2 over a disk of radius a,
+    /// as for the self-influence of the frequency limits; the regular wave
+    /// part is taken at the centroid.
+    fn compute_singular_influence(&self, panel: &Panel, green_function: &GreenFunction, part: KernelPart) -> Result<Complex64> {
         let area = panel.area();
-        
-        // Use a small offset to avoid singularity
-        let offset = config.singular_tolerance;
-        let normal = panel.normal();
-        let center = panel.centroid();
-        
-        // Evaluate Green function at offset point
-        let offset_point = Point3::new(
-            center.x + offset * normal.x,
-            center.y + offset * normal.y,
-            center.z + offset * normal.z,
-        );
-        let center_point = Point3::new(center.x, center.y, center.z);
-        
-        // Convert to r,z coordinates
-        let r = ((offset_point.x - center_point.x).powi(2) + (offset_point.y - center_point.y).powi(2)).sqrt();
-        let z = offset_point.z - center_point.z;
-        
-        match green_function.evaluate(r, z) {
-            Ok(g_value) => Ok(g_value.re * area),
-            Err(_) => {
-                // Fallback to analytical estimate for flat panels
-                Ok(-area / (4.0 * std::f64::consts::PI))
-            }
-        }
+        let disk = Complex64::new(0.0, -0.5 * (area / std::f64::consts::PI).sqrt());
+        let wave = || green_function.wave_part(0.0, 0.0).unwrap_or_default() * area;
+        Ok(match part {
+            KernelPart::Full => disk + wave(),
+            KernelPart::Rankine => disk,
+            KernelPart::Wave => wave(),
+        })
     }
     
     /// Set up right-hand side vector based on problem type
-    fn setup_right_hand_side(&self, problem: &BEMProblem, mesh: &mut Mesh) -> Result<Vec<f64>> {
-        let panels = mesh.panels()?;
-        let n_panels = panels.len();
-        
+    fn setup_right_hand_side(&self, problem: &BEMProblem, coupled: &CoupledPanels) -> Result<Vec<Complex64>> {
         match &problem.problem_type {
             ProblemType::Radiation { frequency, mode } => {
                 // For radiation problems, RHS depends on body motion
-                self.setup_radiation_rhs(*frequency, *mode, &panels)
+                self.setup_radiation_rhs(*frequency, *mode, problem.n_dof(), coupled)
             }
             ProblemType::Diffraction { frequency, direction } => {
                 // For diffraction problems, RHS is incident wave potential
-                self.setup_diffraction_rhs(*frequency, *direction, &panels)
-            }
-            ProblemType::Combined { frequency, direction, modes } => {
-                // For combined problems, solve for first mode (simplification)
-                if let Some(&first_mode) = modes.first() {
-                    self.setup_radiation_rhs(*frequency, first_mode, &panels)
-                } else {
-                    self.setup_diffraction_rhs(*frequency, *direction, &panels)
-                }
+                self.setup_diffraction_rhs(*frequency, *direction, coupled)
             }
+            ProblemType::Combined { .. } => Err(BEMError::InvalidProblem {
+                message: "Combined problems are solved as their radiation and diffraction parts".to_string(),
+            }),
         }
     }
     
     /// Set up radiation problem right-hand side
-    fn setup_radiation_rhs(&self, frequency: f64, mode: usize, panels: &[Panel]) -> Result<Vec<f64>> {
-        let n_panels = panels.len();
-        let mut rhs = vec![0.0; n_panels];
+    fn setup_radiation_rhs(&self, frequency: f64, mode: usize, n_dof: usize, coupled: &CoupledPanels) -> Result<Vec<Complex64>> {
+        if mode >= n_dof {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Mode {} out of range for {} degrees of freedom", mode, n_dof),
+            });
+        }
         
-        // For radiation problems, RHS = -n · (iω ξ)
-        // where n is normal vector, ω is frequency, ξ is motion amplitude
+        // For radiation problems, RHS = -iω (n · ξ) for a unit displacement amplitude,
+        // where only nodes of the moving body see a non-zero normal velocity
         let omega = frequency;
-        
-        for (i, panel) in panels.iter().enumerate() {
-            let normal = panel.normal();
-            
-            // Unit motion in specified mode
-            let motion_velocity = match mode {
-                0 => nalgebra::Vector3::new(1.0, 0.0, 0.0), // Surge
-                1 => nalgebra::Vector3::new(0.0, 1.0, 0.0), // Sway
-                2 => nalgebra::Vector3::new(0.0, 0.0, 1.0), // Heave
-                3 => {
-                    // Roll - velocity depends on position
-                    let center = panel.centroid();
-                    nalgebra::Vector3::new(0.0, -center.z, center.y)
-                }
-                4 => {
-                    // Pitch - velocity depends on position
-                    let center = panel.centroid();
-                    nalgebra::Vector3::new(center.z, 0.0, -center.x)
-                }
-                5 => {
-                    // Yaw - velocity depends on position
-                    let center = panel.centroid();
-                    nalgebra::Vector3::new(-center.y, center.x, 0.0)
-                }
-                _ => nalgebra::Vector3::new(0.0, 0.0, 0.0), // Invalid mode
-            };
-            
-            // RHS = -n · (iω ξ) = -ω * (n · ξ) for the imaginary part
-            let normal_vec = nalgebra::Vector3::new(normal.x, normal.y, normal.z);
-            rhs[i] = -omega * normal_vec.dot(&motion_velocity);
-        }
+        let rhs = (0..coupled.n_nodes())
+            .map(|i| Complex64::new(0.0, -omega * coupled.generalized_normal(i, mode)))
+            .collect();
         
         Ok(rhs)
     }
     
     /// Set up diffraction problem right-hand side
-    fn setup_diffraction_rhs(&self, frequency: f64, direction: f64, panels: &[Panel]) -> Result<Vec<f64>> {
-        let n_panels = panels.len();
-        let mut rhs = vec![0.0; n_panels];
+    fn setup_diffraction_rhs(&self, frequency: f64, direction: f64, coupled: &CoupledPanels) -> Result<Vec<Complex64>> {
+        let g = 9.81;
+        let wave_number = frequency * frequency / g; // k = ω²/g
+        let (cos_b, sin_b) = (direction.cos(), direction.sin());
         
-        // For diffraction problems, RHS = -∂φ_I/∂n
-        // where φ_I is incident wave potential
-        let wave_number = frequency * frequency / 9.81; // k = ω²/g
+        // For diffraction problems, RHS = -∂φ_I/∂n with the unit-amplitude incident wave
+        // φ_I = -i g/ω e^{kz + ik(x cos β + y sin β)}, so ∂φ_I/∂n = k φ_I (n_z + i(n_x cos β + n_y sin β))
+        let mut rhs: Vec<Complex64> = coupled
+            .nodes
+            .iter()
+            .map(|node| {
+                let (c, n) = (node.position, node.normal);
+                let phase = Complex64::new(wave_number * c.z, wave_number * (c.x * cos_b + c.y * sin_b));
+                let incident = Complex64::new(0.0, -g / frequency) * phase.exp();
+                -incident * wave_number * Complex64::new(n.z, n.x * cos_b + n.y * sin_b)
+            })
+            .collect();
         
-        for (i, panel) in panels.iter().enumerate() {
-            let center = panel.centroid();
-            let normal = panel.normal();
-            
-            // Incident wave: φ_I = (g/iω) * A * exp(ikx cos β + iky sin β + kz)
-            // ∂φ_I/∂n = (g/iω) * A * ik * (n · k_vec) * exp(...)
-            
-            let k_x = wave_number * direction.cos();
-            let k_y = wave_number * direction.sin();
-            let k_z = wave_number;
-            
-            let wave_vector = nalgebra::Vector3::new(k_x, k_y, 0.0); // Horizontal propagation
-            let normal_vec = nalgebra::Vector3::new(normal.x, normal.y, normal.z);
-            
-            // Simplified: assume unit amplitude and take real part
-            let phase = k_x * center.x + k_y * center.y + k_z * center.z;
-            let amplitude = 1.0; // Unit wave amplitude
-            
-            rhs[i] = -amplitude * wave_number * normal_vec.dot(&wave_vector) * phase.cos();
+        // Lid nodes: -(∂/∂n + K(1 + iε)) φ_I, which reduces to -iεK φ_I
+        for (i, factor) in coupled.lid_factors(frequency) {
+            rhs[i] -= factor * incident_potential(frequency, g, direction, &coupled.nodes[i].position);
         }
         
         Ok(rhs)
     }
     
+    /// Solution interpolated to the body panel centroids
+    ///
+    /// Lid panels follow the body panels and are left out. Diffraction
+    /// pressures include the incident wave.
+    fn panel_output(
+        &self,
+        problem: &BEMProblem,
+        coupled: &CoupledPanels,
+        potential: &[Complex64],
+        source_strength: Option<&[Complex64]>,
+        normal_velocity: &[Complex64],
+        green_params: &GreenFunctionParams,
+    ) -> PanelOutput {
+        let rho = 1025.0;
+        let omega = green_params.frequency;
+        let shape = shape_functions(coupled.order, [1.0 / 3.0; 3]);
+        let body_elements = coupled.elements.iter().take_while(|element| element[0] < coupled.n_body_nodes);
+        let at_centroid = |values: &[Complex64], element: &[usize]| -> Complex64 {
+            element.iter().zip(&shape).map(|(&id, &n)| values[id] * n).sum()
+        };
+        
+        let mut output = PanelOutput {
+            centroids: Vec::new(),
+            normals: Vec::new(),
+            areas: Vec::new(),
+            pressure: Vec::new(),
+            source_strength: source_strength.map(|_| Vec::new()),
+            normal_velocity: Vec::new(),
+        };
+        for (panel, element) in coupled.panels.iter().zip(body_elements) {
+            let centroid = panel.centroid;
+            let mut phi = at_centroid(potential, element);
+            if let ProblemType::Diffraction { direction, .. } = problem.problem_type {
+                phi += incident_potential(omega, green_params.gravity, direction, &centroid);
+            }
+            output.centroids.push([centroid.x, centroid.y, centroid.z]);
+            output.normals.push([panel.normal.x, panel.normal.y, panel.normal.z]);
+            output.areas.push(panel.area);
+            output.pressure.push(Complex64::new(0.0, omega * rho) * phi);
+            if let (Some(values), Some(sigma)) = (output.source_strength.as_mut(), source_strength) {
+                values.push(at_centroid(sigma, element));
+            }
+            output.normal_velocity.push(at_centroid(normal_velocity, element));
+        }
+        output
+    }
+    
+    /// Total velocity and waterline elevation on the fixed body from the scattering source strengths
+    fn first_order_field(
+        &self,
+        coupled: &CoupledPanels,
+        sigma: &[Complex64],
+        green_function: &GreenFunction,
+        frequency: f64,
+        direction: f64,
+    ) -> FirstOrderField {
+        let g = 9.81;
+        let k = frequency * frequency / g;
+        let panels = &coupled.panels;
+        let (cos_b, sin_b) = (direction.cos(), direction.sin());
+        
+        // Unit-amplitude incident potential φ_I = -i g/ω e^{kz + ik(x cos β + y sin β)}
+        let incident = |p: &Point| {
+            Complex64::new(0.0, -g / frequency) * Complex64::new(k * p.z, k * (p.x * cos_b + p.y * sin_b)).exp()
+        };
+        
+        let velocity: Vec<[Complex64; 3]> = panels
+            .iter()
+            .enumerate()
+            .map(|(i, panel)| {
+                let c = panel.centroid();
+                let n = panel.normal();
+                let mut scattered = [n.x, n.y, n.z].map(|n| sigma[i] * (0.5 * n));
+                for (j, source) in panels.iter().enumerate() {
+                    if j != i {
+                        // Gradient with respect to the field point
+                        let gradient = green_source_gradient(green_function, &c, &source.centroid());
+                        for (s, g) in scattered.iter_mut().zip(gradient) {
+                            *s -= g * sigma[j] * source.area();
+                        }
+                    }
+                }
+                let phi_i = incident(&c);
+                let ik = Complex64::new(0.0, k);
+                [
+                    phi_i * ik * cos_b + scattered[0],
+                    phi_i * ik * sin_b + scattered[1],
+                    phi_i * k + scattered[2],
+                ]
+            })
+            .collect();
+        
+        let waterline = waterline_segments(panels, 1e-6);
+        let elevation: Vec<Complex64> = waterline
+            .iter()
+            .map(|segment| {
+                let m = segment.midpoint();
+                let scattered: Complex64 = panels
+                    .iter()
+                    .zip(sigma)
+                    .map(|(source, s)| s * source.area() * green_value(green_function, &m, &source.centroid()))
+                    .sum();
+                Complex64::new(0.0, frequency / g) * (incident(&m) + scattered)
+            })
+            .collect();
+        
+        FirstOrderField {
+            frequency,
+            heading: direction,
+            velocity,
+            elevation,
+            motions: None,
+            free_surface: Vec::new(),
+        }
+    }
+    
+    /// Near-field drift on the fixed body
+    fn near_field_drift(&self, coupled: &CoupledPanels, field: &FirstOrderField) -> NearFieldDrift {
+        let panels = &coupled.panels;
+        let waterline = waterline_segments(panels, 1e-6);
+        let input = NearFieldInput {
+            panels,
+            velocity: &field.velocity,
+            waterline: &waterline,
+            elevation: &field.elevation,
+            motions: None,
+            reference: Point::origin(),
+            mass_matrix: [[0.0; 6]; 6],
+            frequency: field.frequency,
+        };
+        near_field_drift(&input, 1025.0, 9.81)
+    }
+    
     /// Post-process results to extract hydrodynamic coefficients
     fn post_process_results(
         &self,
         problem: &BEMProblem,
-        potential: Vec<f64>,
+        coupled: &CoupledPanels,
+        potential: Vec<Complex64>,
         computation_time: std::time::Duration,
     ) -> Result<BEMResult> {
+        let n_dof = problem.n_dof();
         let mut result = BEMResult {
             potential,
+            source_strength: None,
             added_mass: None,
             damping: None,
             excitation_force: None,
+            froude_krylov_force: None,
+            haskind_excitation: None,
+            haskind_discrepancy: None,
+            mean_drift: None,
+            near_field_drift: None,
+            first_order: None,
+            boundary: None,
+            panel_output: None,
+            diagnostics: QualityDiagnostics::new(self.config.diagnostic_tolerance),
             computation_time: computation_time.as_secs_f64(),
             iterations: None,
+            residual: None,
+            residual_history: Vec::new(),
+            converged: true,
+            timed_out: false,
         };
         
         // For radiation problems, compute added mass and damping
         if let ProblemType::Radiation { frequency, mode } = &problem.problem_type {
-            // This is where we would integrate pressure over body surface
-            // to get added mass and damping coefficients
-            // Simplified implementation:
-            
-            let n_dof = 6; // 6 degrees of freedom
+            let rho = 1025.0;
             let mut added_mass_data = vec![0.0; n_dof * n_dof];
             let mut damping_data = vec![0.0; n_dof * n_dof];
             
-            // Placeholder computation - real implementation would integrate
-            // pressure = iωρφ over body surface
+            // Column `mode` of the coupled coefficients from the radiation force
+            // -iωρ ∫ φ n_i dS = ω² A_im + iω B_im, integrated over every body so
+            // off-diagonal blocks capture interaction:
+            // A_im = ρ/ω Im ∫ φ n_i dS and B_im = -ρ Re ∫ φ n_i dS
             for i in 0..n_dof {
-                added_mass_data[i * n_dof + i] = 1000.0; // Diagonal terms
-                damping_data[i * n_dof + i] = 100.0 * frequency; // Frequency dependent
+                let force: Complex64 = (0..coupled.n_nodes())
+                    .map(|k| result.potential[k] * (coupled.generalized_normal(k, i) * coupled.nodes[k].weight))
+                    .sum();
+                added_mass_data[i * n_dof + mode] = rho * force.im / frequency;
+                damping_data[i * n_dof + mode] = -rho * force.re;
             }
             
             result.added_mass = Some(Matrix::from_vec(n_dof, n_dof, added_mass_data)?);
             result.damping = Some(Matrix::from_vec(n_dof, n_dof, damping_data)?);
         }
         
-        // For diffraction problems, compute wave exciting forces
+        // For diffraction problems, integrate the total pressure iωρ(φ_I + φ_D):
+        // F_i = -iωρ ∫ (φ_I + φ_D) n_i dS, the incident part alone being the
+        // Froude-Krylov force
         if let ProblemType::Diffraction { frequency, direction } = &problem.problem_type {
-            // Compute exciting forces from pressure integration
-            let mut forces = vec![0.0; 6]; // 6 DOF forces/moments
-            
-            // Simplified computation
-            for i in 0..6 {
-                forces[i] = 1000.0 * frequency.sin() * direction.cos(); // Placeholder
-            }
+            let rho = 1025.0;
+            let incident: Vec<Complex64> = coupled
+                .nodes
+                .iter()
+                .map(|node| incident_potential(*frequency, 9.81, *direction, &node.position))
+                .collect();
+            let total: Vec<Complex64> = incident.iter().zip(&result.potential).map(|(a, b)| a + b).collect();
+            let integrate = |phi: &[Complex64]| -> Vec<Complex64> {
+                (0..n_dof)
+                    .map(|i| {
+                        let integral: Complex64 = (0..coupled.n_nodes())
+                            .map(|k| phi[k] * (coupled.generalized_normal(k, i) * coupled.nodes[k].weight))
+                            .sum();
+                        Complex64::new(0.0, -frequency * rho) * integral
+                    })
+                    .collect()
+            };
             
-            result.excitation_force = Some(forces);
+            result.froude_krylov_force = Some(integrate(&incident));
+            result.excitation_force = Some(integrate(&total));
         }
         
         Ok(result)
     }
-} 
\ No newline at end of file
+}
+
+/// Green function between field point `x` and source point `xi`
+pub(crate) fn green_value(green_function: &GreenFunction, x: &Point, xi: &Point) -> Complex64 {
+    let r = ((xi.x - x.x).powi(2) + (xi.y - x.y).powi(2)).sqrt();
+    let z = xi.z - x.z;
+    green_function.evaluate(r, z).unwrap_or(Complex64::new(0.0, 0.0))
+}
+
+/// ∂G/∂n, with `normal` taken at the source (`source_normal`) or field point
+pub(crate) fn green_normal_derivative(
+    green_function: &GreenFunction,
+    x: &Point,
+    xi: &Point,
+    normal: &Vector,
+    source_normal: bool,
+) -> Complex64 {
+    kernel_normal_derivative(green_function, KernelPart::Full, x, xi, normal, source_normal)
+}
+
+/// ∂/∂n of one part of the Green function, as in [`green_normal_derivative`]
+fn kernel_normal_derivative(
+    green_function: &GreenFunction,
+    part: KernelPart,
+    x: &Point,
+    xi: &Point,
+    normal: &Vector,
+    source_normal: bool,
+) -> Complex64 {
+    let [gx, gy, gz] = kernel_source_gradient(green_function, part, x, xi);
+    let derivative = gx * normal.x + gy * normal.y + gz * normal.z;
+    
+    if source_normal {
+        derivative
+    } else {
+        // Gradient with respect to the field point has the opposite sign
+        -derivative
+    }
+}
+
+/// ∇G with respect to the source point `xi`
+pub(crate) fn green_source_gradient(green_function: &GreenFunction, x: &Point, xi: &Point) -> [Complex64; 3] {
+    kernel_source_gradient(green_function, KernelPart::Full, x, xi)
+}
+
+/// ∇ of one part of the Green function with respect to the source point `xi`
+fn kernel_source_gradient(green_function: &GreenFunction, part: KernelPart, x: &Point, xi: &Point) -> [Complex64; 3] {
+    let zero = Complex64::new(0.0, 0.0);
+    let (dx, dy) = (xi.x - x.x, xi.y - x.y);
+    let r = (dx * dx + dy * dy).sqrt();
+    let z = xi.z - x.z;
+    
+    let (dg_dr, dg_dz) = match (part.gradient(green_function, r, z), part) {
+        (Ok(g), _) => g,
+        // Handle errors gracefully, keeping the parts summing to the complete kernel
+        (Err(_), KernelPart::Wave) => {
+            let (dr, dz) = rankine_gradient(r, z);
+            (-dr, -dz)
+        }
+        (Err(_), _) => return [zero; 3],
+    };
+    
+    let (gx, gy) = if r > 1e-12 {
+        (dg_dr * (dx / r), dg_dr * (dy / r))
+    } else {
+        (zero, zero)
+    };
+    [gx, gy, dg_dz]
+}
+
+/// Result of a combined problem: the diffraction result (last) with the added
+/// mass and damping columns of every radiation result
+fn merge_combined(mut results: Vec<BEMResult>, tolerance: f64) -> Result<BEMResult> {
+    let mut combined = results.pop().ok_or_else(|| BEMError::SolverError {
+        message: "Combined problem produced no diffraction result".to_string(),
+    })?;
+    let checks: Vec<QualityDiagnostics> = results.iter().chain(std::iter::once(&combined)).map(|r| r.diagnostics.clone()).collect();
+    let sum = |matrices: Vec<&Matrix>| -> Result<Option<Matrix>> {
+        let Some(first) = matrices.first() else { return Ok(None) };
+        let mut data = vec![0.0; first.data.len()];
+        for matrix in &matrices {
+            for (total, value) in data.iter_mut().zip(&matrix.data) {
+                *total += value;
+            }
+        }
+        Ok(Some(Matrix::from_vec(first.rows, first.cols, data)?))
+    };
+    combined.added_mass = sum(results.iter().filter_map(|r| r.added_mass.as_ref()).collect())?;
+    combined.damping = sum(results.iter().filter_map(|r| r.damping.as_ref()).collect())?;
+    for result in results {
+        combined.iterations = Some(combined.iterations.unwrap_or(0) + result.iterations.unwrap_or(0));
+        combined.residual = match (combined.residual, result.residual) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        combined.residual_history.extend(result.residual_history);
+        combined.converged &= result.converged;
+        combined.timed_out |= result.timed_out;
+    }
+    combined.diagnostics = QualityDiagnostics::worst(&checks, tolerance);
+    Ok(combined)
+}
+
+/// Largest difference of two force vectors relative to the largest entry of `reference`
+fn relative_discrepancy(reference: &[Complex64], other: &[Complex64]) -> f64 {
+    let scale = reference.iter().map(|f| f.norm()).fold(0.0, f64::max);
+    let difference = reference.iter().zip(other).map(|(a, b)| (a - b).norm()).fold(0.0, f64::max);
+    if scale > 0.0 {
+        difference / scale
+    } else {
+        difference
+    }
+}
+
+/// Dense matrix-vector product
+fn matrix_vector(matrix: &ComplexMatrix, x: &[Complex64]) -> Vec<Complex64> {
+    matrix
+        .data
+        .chunks(matrix.cols)
+        .map(|row| row.iter().zip(x).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_multi_body_radiation() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let bodies = vec![tetrahedron_body("a", 0.0), tetrahedron_body("b", 5.0)];
+
+        // Heave of the second body
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 8 };
+        let result = solver.solve_bodies(&problem, bodies.clone()).unwrap();
+
+        let added_mass = result.added_mass().unwrap();
+        assert_eq!(added_mass.dimensions(), (12, 12));
+        assert_eq!(result.potential().len(), 8);
+
+        // Only the radiating column is populated
+        assert!(added_mass.get(8, 8).unwrap().abs() > 0.0);
+        assert_eq!(added_mass.get(2, 3).unwrap(), 0.0);
+
+        // Mode beyond 6N is rejected
+        let invalid = ProblemType::Radiation { frequency: 1.0, mode: 12 };
+        assert!(solver.solve_bodies(&invalid, bodies).is_err());
+    }
+
+    #[test]
+    fn test_combined_problem_matches_separate_solves() {
+        for linear_solver in [wavecore_matrices::SolverType::LU, wavecore_matrices::SolverType::GMRES] {
+            let solver = BEMSolver::with_config(BEMConfig { linear_solver, ..Default::default() });
+            let body = || vec![tetrahedron_body("a", 0.0)];
+            let combined = ProblemType::Combined { frequency: 1.0, direction: 0.3, modes: vec![2, 0, 4] };
+            let result = solver.solve_bodies(&combined, body()).unwrap();
+
+            let added_mass = result.added_mass().unwrap();
+            let damping = result.damping().unwrap();
+            for mode in 0..6 {
+                let separate = solver.solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode }, body()).unwrap();
+                for i in 0..6 {
+                    let (a, b) = if [2, 0, 4].contains(&mode) {
+                        (separate.added_mass().unwrap().get(i, mode).unwrap(), separate.damping().unwrap().get(i, mode).unwrap())
+                    } else {
+                        (0.0, 0.0)
+                    };
+                    assert!((added_mass.get(i, mode).unwrap() - a).abs() <= 1e-6 * (1.0 + a.abs()));
+                    assert!((damping.get(i, mode).unwrap() - b).abs() <= 1e-6 * (1.0 + b.abs()));
+                }
+            }
+
+            let diffraction = solver.solve_bodies(&ProblemType::Diffraction { frequency: 1.0, direction: 0.3 }, body()).unwrap();
+            for (x, y) in result.excitation_force().unwrap().iter().zip(diffraction.excitation_force().unwrap()) {
+                assert!((x - y).norm() <= 1e-6 * (1.0 + y.norm()));
+            }
+            assert_eq!(result.haskind_excitation.is_some(), diffraction.haskind_excitation.is_some());
+            assert!(result.is_converged());
+        }
+
+        let repeated = ProblemType::Combined { frequency: 1.0, direction: 0.0, modes: vec![1, 1] };
+        assert!(BEMSolver::new(SolverEngine::Standard).solve_bodies(&repeated, vec![tetrahedron_body("a", 0.0)]).is_err());
+    }
+
+    #[test]
+    fn test_generalized_mode_radiation() {
+        let solver = BEMSolver::new(SolverEngine::Standard);
+
+        // A generalized mode with the heave shape must reproduce heave added mass
+        let mut body = tetrahedron_body("a", 0.0);
+        let heave_shape: Vec<f64> = body.mesh.as_mut().unwrap().panels().unwrap().iter().map(|p| p.normal().z).collect();
+        body.add_generalized_mode(wavecore_bodies::GeneralizedMode::new("heave-like", heave_shape)).unwrap();
+
+        let heave = solver
+            .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 2 }, vec![body.clone()])
+            .unwrap();
+        let flexible = solver
+            .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 6 }, vec![body])
+            .unwrap();
+
+        let a_heave = heave.added_mass().unwrap();
+        let a_flexible = flexible.added_mass().unwrap();
+        assert_eq!(a_flexible.dimensions(), (7, 7));
+        assert!((a_flexible.get(6, 6).unwrap() - a_heave.get(2, 2).unwrap()).abs() < 1e-9);
+        assert!((a_flexible.get(2, 6).unwrap() - a_heave.get(6, 2).unwrap()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_formulations() {
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+        let bodies = vec![tetrahedron_body("a", 0.0)];
+
+        let indirect = BEMSolver::new(SolverEngine::Standard)
+            .solve_bodies(&problem, bodies.clone())
+            .unwrap();
+        assert_eq!(indirect.source_strength().unwrap().len(), 4);
+
+        let direct = BEMSolver::with_config(BEMConfig {
+            formulation: Formulation::Direct,
+            ..Default::default()
+        })
+        .solve_bodies(&problem, bodies)
+        .unwrap();
+        assert!(direct.source_strength().is_none());
+        assert_eq!(direct.potential().len(), 4);
+    }
+
+    #[test]
+    fn test_time_budget_returns_partial_result() {
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+        let bodies = vec![tetrahedron_body("a", 0.0)];
+        let mut bem_problem = BEMProblem::single(bodies[0].clone(), problem, AssemblyConfig::default());
+        bem_problem.assembly_config.solver_type = wavecore_matrices::SolverType::GMRES;
+
+        let config = BEMConfig {
+            time_budget: Some(0.0),
+            ..Default::default()
+        };
+        let result = BEMSolverImpl::new(config).solve(&bem_problem).unwrap();
+        assert!(result.timed_out);
+        assert!(!result.converged);
+        assert_eq!(result.potential().len(), 4);
+    }
+
+    /// Submerged 2 m cube centred at z = -3 with `n` × `n` outward quads per side
+    fn quad_cube_body(n: usize) -> wavecore_bodies::FloatingBody {
+        use nalgebra::{Point3, Vector3};
+        let mut vertices = Vec::new();
+        let mut quads = Vec::new();
+        for axis in 0..3 {
+            for side in [-1.0, 1.0] {
+                let normal = Vector3::ith(axis, side);
+                let u = Vector3::ith((axis + 1) % 3, 1.0);
+                let v = normal.cross(&u);
+                let start = vertices.len();
+                for i in 0..=n {
+                    for j in 0..=n {
+                        let (a, b) = (2.0 * i as f64 / n as f64 - 1.0, 2.0 * j as f64 / n as f64 - 1.0);
+                        vertices.push(Point3::new(0.0, 0.0, -3.0) + normal + a * u + b * v);
+                    }
+                }
+                let id = |i: usize, j: usize| start + i * (n + 1) + j;
+                for i in 0..n {
+                    for j in 0..n {
+                        quads.push([id(i, j), id(i + 1, j), id(i + 1, j + 1), id(i, j + 1)]);
+                    }
+                }
+            }
+        }
+        let mesh = wavecore_meshes::Mesh::with_quads(vertices, Vec::new(), quads).unwrap();
+        let mass_props = wavecore_bodies::MassProperties {
+            center_of_gravity: [0.0, 0.0, -3.0],
+            ..Default::default()
+        };
+        wavecore_bodies::FloatingBody::with_mesh("cube".to_string(), mass_props, mesh).unwrap()
+    }
+
+    #[test]
+    fn test_quad_panels() {
+        let body = quad_cube_body(3);
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+        let result = BEMSolver::new(SolverEngine::Standard).solve_bodies(&problem, vec![body.clone()]).unwrap();
+        let panels = result.panel_output.as_ref().unwrap();
+        assert_eq!(panels.len(), 6 * 9);
+        assert!((panels.areas.iter().sum::<f64>() - 24.0).abs() < 1e-9);
+        assert!(result.added_mass().unwrap().get(2, 2).unwrap().is_finite());
+
+        // Higher-order elements split the quads into triangles
+        let linear = BEMSolver::with_config(BEMConfig { panel_order: PanelOrder::Linear, ..Default::default() });
+        assert!(linear.solve_bodies(&problem, vec![body]).is_ok());
+    }
+
+    #[test]
+    fn test_recycled_gmres_sweep_matches_lu() {
+        let frequencies = [0.8, 0.85, 0.9, 0.95];
+        let bodies = vec![tetrahedron_body("a", 0.0), tetrahedron_body("b", 3.0)];
+        let direct = BEMSolver::new(SolverEngine::Standard)
+            .solve_sweep_bodies(&frequencies, &[0.0], bodies.clone())
+            .unwrap();
+        let recycled = BEMSolver::with_config(BEMConfig {
+            tolerance: 1e-12,
+            linear_solver: wavecore_matrices::SolverType::GMRES,
+            preconditioning: Preconditioning::None,
+            recycle_space: 4,
+            ..Default::default()
+        })
+        .solve_sweep_bodies(&frequencies, &[0.0], bodies)
+        .unwrap();
+
+        assert!(recycled.completed.iter().all(|&c| c));
+        for (a, b) in direct.added_mass.iter().zip(&recycled.added_mass) {
+            assert!((a - b).abs() < 1e-8 * (1.0 + a.abs()));
+        }
+        for (a, b) in direct.excitation.iter().zip(&recycled.excitation) {
+            assert!((a - b).norm() < 1e-8 * (1.0 + a.norm()));
+        }
+    }
+
+    #[test]
+    fn test_complex_potential_gives_radiation_damping() {
+        let problem = ProblemType::Radiation { frequency: 1.0, mode: 2 };
+        let result = BEMSolver::new(SolverEngine::Standard)
+            .solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+
+        // The radiated wave puts the potential out of phase with the heave velocity
+        assert!(result.potential().iter().any(|p| p.re.abs() > 0.0 && p.im.abs() > 0.0));
+        let damping = result.damping().unwrap();
+        assert!(damping.get(2, 2).unwrap() > 0.0);
+        assert_eq!(damping.get(2, 3).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_haskind_excitation_matches_direct() {
+        let problem = ProblemType::Diffraction { frequency: 1.0, direction: 0.4 };
+        let result = BEMSolver::new(SolverEngine::Standard)
+            .solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+
+        let direct = result.excitation_force().unwrap();
+        let haskind = result.haskind_excitation().unwrap();
+        assert_eq!(direct.len(), 6);
+        assert_eq!(haskind.len(), 6);
+        // Four panels leave a discretization error of a few percent
+        assert!(result.haskind_discrepancy().unwrap() < 0.05);
+
+        let unchecked = BEMSolver::with_config(BEMConfig { haskind: false, ..Default::default() })
+            .solve_bodies(&problem, vec![tetrahedron_body("a", 0.0)])
+            .unwrap();
+        assert!(unchecked.haskind_discrepancy().is_none());
+        assert_eq!(unchecked.excitation_force(), result.excitation_force());
+    }
+}
@@ -0,0 +1,173 @@
+//! Structured storage of constant-panel influence matrices
+//!
+//! Coefficients are stored as blocks of collocation rows against every panel.
+//! A mesh symmetric under a group of g reflections keeps only the n/g rows of
+//! its fundamental region, about half the dense storage for one plane, and the
+//! reduced matrix of each symmetry class is formed from those rows without
+//! evaluating the Green function again.
+//!
+//! The kernel splits into the frequency-independent Rankine part -i/(4πR)
+//! and the wave part. With `BEMConfig::reuse_rankine` the Rankine blocks of a
+//! geometry, including the singular self-influence and the ½I jump, are
+//! assembled once and shared by every frequency of a sweep, and each solve
+//! assembles only the wave part.
+
+use std::sync::OnceLock;
+
+use num_complex::Complex64;
+use wavecore_green_functions::{rankine, rankine_gradient, GreenFunction};
+use wavecore_matrices::ComplexMatrix;
+
+use super::{BEMError, Result, SymmetryMap};
+
+/// Part of the Green function a coefficient is assembled from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KernelPart {
+    /// Complete kernel
+    Full,
+    /// Frequency-independent Rankine part
+    Rankine,
+    /// Frequency-dependent wave part
+    Wave,
+}
+
+impl KernelPart {
+    /// Kernel value at horizontal distance `r` and vertical offset `z`
+    pub(crate) fn evaluate(self, green_function: &GreenFunction, r: f64, z: f64) -> wavecore_green_functions::Result<Complex64> {
+        match self {
+            KernelPart::Full => green_function.evaluate(r, z),
+            KernelPart::Rankine => Ok(rankine(r, z)),
+            KernelPart::Wave => green_function.wave_part(r, z),
+        }
+    }
+
+    /// Kernel gradient (∂/∂r, ∂/∂z)
+    pub(crate) fn gradient(self, green_function: &GreenFunction, r: f64, z: f64) -> wavecore_green_functions::Result<(Complex64, Complex64)> {
+        match self {
+            KernelPart::Full => green_function.gradient(r, z),
+            KernelPart::Rankine => Ok(rankine_gradient(r, z)),
+            KernelPart::Wave => green_function.wave_gradient(r, z),
+        }
+    }
+
+    /// Value added where the kernel fails to evaluate, so the Rankine and
+    /// wave parts still sum to the fallback of the complete kernel
+    pub(crate) fn fallback(self, r: f64, z: f64) -> Complex64 {
+        match self {
+            KernelPart::Wave => -rankine(r, z),
+            _ => Complex64::new(0.0, 0.0),
+        }
+    }
+
+    /// Whether the ½I jump of the double layer belongs to this part
+    pub(crate) fn has_jump(self) -> bool {
+        self != KernelPart::Wave
+    }
+}
+
+/// Influence operator of the constant-panel system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kernel {
+    /// Single layer S
+    Single,
+    /// Double layer with the ½I jump, ∂G/∂n at the source (D) or field panel (K)
+    Double { source_normal: bool },
+}
+
+/// Influence coefficients of selected collocation rows against every panel
+#[derive(Debug, Clone)]
+pub(crate) struct InfluenceBlocks {
+    /// Panel of each stored row
+    pub(crate) rows: Vec<usize>,
+    /// Coefficients, one row per entry of `rows`
+    pub(crate) matrix: ComplexMatrix,
+}
+
+impl InfluenceBlocks {
+    /// Add the coefficients of `other`, stored for the same rows
+    pub(crate) fn add(&mut self, other: &InfluenceBlocks) -> Result<()> {
+        if self.rows != other.rows || self.matrix.cols != other.matrix.cols {
+            return Err(BEMError::SolverError {
+                message: "Influence blocks of different rows cannot be added".to_string(),
+            });
+        }
+        for (a, b) in self.matrix.data.iter_mut().zip(&other.matrix.data) {
+            *a += b;
+        }
+        Ok(())
+    }
+
+    /// Reduced matrix of symmetry class `class` from the fundamental rows
+    pub(crate) fn reduce(&self, map: &SymmetryMap, class: usize) -> Result<ComplexMatrix> {
+        let chi = &map.characters[class];
+        let (n_reduced, cols) = (map.n_fundamental(), self.matrix.cols);
+        let mut data = Vec::with_capacity(n_reduced * n_reduced);
+        for i in 0..n_reduced {
+            let row = &self.matrix.data[i * cols..(i + 1) * cols];
+            data.extend(map.orbits.iter().map(|orbit| {
+                orbit.iter().zip(chi).map(|(&p, &c)| row[p] * c).sum::<Complex64>()
+            }));
+        }
+        Ok(ComplexMatrix::from_vec(n_reduced, n_reduced, data)?)
+    }
+}
+
+/// Rankine blocks of one geometry, assembled on first use
+#[derive(Debug, Default)]
+pub(crate) struct RankineCache {
+    single: OnceLock<InfluenceBlocks>,
+    double_field: OnceLock<InfluenceBlocks>,
+    double_source: OnceLock<InfluenceBlocks>,
+}
+
+impl RankineCache {
+    /// Cached blocks of `kernel`, assembled by `assemble` the first time
+    ///
+    /// Concurrent first uses may both assemble; the first stored copy is kept.
+    pub(crate) fn get_or_assemble<F>(&self, kernel: Kernel, assemble: F) -> Result<&InfluenceBlocks>
+    where
+        F: FnOnce() -> Result<InfluenceBlocks>,
+    {
+        let cell = match kernel {
+            Kernel::Single => &self.single,
+            Kernel::Double { source_normal: false } => &self.double_field,
+            Kernel::Double { source_normal: true } => &self.double_source,
+        };
+        if let Some(blocks) = cell.get() {
+            return Ok(blocks);
+        }
+        let blocks = assemble()?;
+        Ok(cell.get_or_init(|| blocks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BEMConfig, BEMSolver, Formulation, Symmetry};
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_rankine_reuse_matches_full_assembly() {
+        let frequencies = [0.8, 1.2];
+        for (symmetry, formulation) in [
+            (Symmetry::None, Formulation::Indirect),
+            (Symmetry::Detect, Formulation::Indirect),
+            (Symmetry::Detect, Formulation::Direct),
+        ] {
+            let config = BEMConfig { symmetry, formulation, ..Default::default() };
+            let full = BEMSolver::with_config(config.clone())
+                .solve_sweep_bodies(&frequencies, &[0.3], vec![octahedron_body()])
+                .unwrap();
+            let reused = BEMSolver::with_config(BEMConfig { reuse_rankine: true, ..config })
+                .solve_sweep_bodies(&frequencies, &[0.3], vec![octahedron_body()])
+                .unwrap();
+
+            for (a, b) in full.added_mass.iter().zip(&reused.added_mass) {
+                assert!((a - b).abs() < 1e-8 * (1.0 + a.abs()));
+            }
+            for (a, b) in full.excitation.iter().zip(&reused.excitation) {
+                assert!((a - b).norm() < 1e-8 * (1.0 + a.norm()));
+            }
+        }
+    }
+}
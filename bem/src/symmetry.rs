@@ -0,0 +1,278 @@
+//! Geometric symmetry of the panel set
+//!
+//! When the hull is symmetric about the x-z and/or y-z planes, the influence
+//! matrix commutes with the reflections. Projecting onto the symmetric and
+//! antisymmetric parts block-diagonalizes the system: only panels in the
+//! fundamental region are assembled against all mirror images, and each
+//! symmetry class is solved separately before recombination.
+
+use std::iter::Sum;
+use std::ops::{AddAssign, Div, Mul};
+use wavecore_meshes::{Panel, SymmetricMesh};
+use serde::{Serialize, Deserialize};
+
+/// Symmetry planes to exploit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Symmetry {
+    /// Assemble the full system
+    None,
+    /// Use whichever planes the panel set is symmetric about
+    Detect,
+    /// Symmetric about the x-z plane (y → -y)
+    XZ,
+    /// Symmetric about the y-z plane (x → -x)
+    YZ,
+    /// Symmetric about both planes
+    Both,
+}
+
+impl Symmetry {
+    /// Whether (x-z, y-z) planes are requested
+    fn planes(&self) -> (bool, bool) {
+        match self {
+            Symmetry::None | Symmetry::Detect => (false, false),
+            Symmetry::XZ => (true, false),
+            Symmetry::YZ => (false, true),
+            Symmetry::Both => (true, true),
+        }
+    }
+}
+
+impl From<&SymmetricMesh> for Symmetry {
+    /// Planes a half- or quarter-body mesh is mirrored about
+    fn from(mesh: &SymmetricMesh) -> Self {
+        match mesh.planes() {
+            (false, false) => Symmetry::None,
+            (true, false) => Symmetry::XZ,
+            (false, true) => Symmetry::YZ,
+            (true, true) => Symmetry::Both,
+        }
+    }
+}
+
+/// Orbits of panels under the symmetry group
+#[derive(Debug, Clone)]
+pub struct SymmetryMap {
+    /// Planes in use (x-z, y-z)
+    pub planes: (bool, bool),
+    /// For each fundamental panel, its image under each group element
+    pub orbits: Vec<Vec<usize>>,
+    /// Character table: sign of each group element in each symmetry class
+    pub characters: Vec<Vec<f64>>,
+}
+
+impl SymmetryMap {
+    /// Build the map for requested or detected planes
+    ///
+    /// Returns `None` when no symmetry is requested or detected. An explicitly
+    /// requested plane the panels do not satisfy is an error.
+    pub fn build(panels: &[Panel], symmetry: Symmetry) -> std::result::Result<Option<Self>, String> {
+        match symmetry {
+            Symmetry::None => Ok(None),
+            Symmetry::Detect => {
+                let xz = Self::with_planes(panels, (true, false)).is_ok();
+                let yz = Self::with_planes(panels, (false, true)).is_ok();
+                if !xz && !yz {
+                    return Ok(None);
+                }
+                Self::with_planes(panels, (xz, yz)).map(Some)
+            }
+            explicit => Self::with_planes(panels, explicit.planes()).map(Some),
+        }
+    }
+
+    /// Order of the symmetry group
+    pub fn group_size(&self) -> usize {
+        self.characters.len()
+    }
+
+    /// Number of panels in the fundamental region
+    pub fn n_fundamental(&self) -> usize {
+        self.orbits.len()
+    }
+
+    fn with_planes(panels: &[Panel], planes: (bool, bool)) -> std::result::Result<Self, String> {
+        // Group elements as (sign of x, sign of y)
+        let mut elements = vec![(1.0, 1.0)];
+        if planes.0 {
+            elements.push((1.0, -1.0));
+        }
+        if planes.1 {
+            elements.push((-1.0, 1.0));
+        }
+        if planes.0 && planes.1 {
+            elements.push((-1.0, -1.0));
+        }
+
+        let scale = panels
+            .iter()
+            .map(|p| p.centroid().coords.amax())
+            .fold(1.0, f64::max);
+        let tol = 1e-6 * scale;
+        let key = |x: f64, y: f64, z: f64| ((x / tol).round() as i64, (y / tol).round() as i64, (z / tol).round() as i64);
+
+        let lookup: std::collections::HashMap<_, usize> = panels
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let c = p.centroid();
+                (key(c.x, c.y, c.z), i)
+            })
+            .collect();
+
+        let mut orbits = Vec::new();
+        for (i, panel) in panels.iter().enumerate() {
+            let c = panel.centroid();
+            let in_fundamental = (!planes.0 || c.y > tol) && (!planes.1 || c.x > tol);
+            if !in_fundamental {
+                continue;
+            }
+            let mut orbit = Vec::with_capacity(elements.len());
+            for &(sx, sy) in &elements {
+                let image = lookup.get(&key(sx * c.x, sy * c.y, c.z)).copied().ok_or_else(|| {
+                    format!("Panel {} has no mirror image for symmetry {:?}", i, planes)
+                })?;
+                orbit.push(image);
+            }
+            orbits.push(orbit);
+        }
+
+        if orbits.len() * elements.len() != panels.len() {
+            return Err(format!(
+                "Panels on or crossing the symmetry planes prevent symmetry {:?}",
+                planes
+            ));
+        }
+
+        // One class per sign pattern over the generating planes
+        let characters = elements
+            .iter()
+            .map(|&(cx, cy)| {
+                elements
+                    .iter()
+                    .map(|&(sx, sy)| {
+                        let px = if sx < 0.0 { cx } else { 1.0 };
+                        let py = if sy < 0.0 { cy } else { 1.0 };
+                        px * py
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self { planes, orbits, characters })
+    }
+
+    /// Project a full panel vector onto symmetry class `class`
+    pub fn project<T>(&self, full: &[T], class: usize) -> Vec<T>
+    where
+        T: Copy + Sum + Div<f64, Output = T>,
+        f64: Mul<T, Output = T>,
+    {
+        let chi = &self.characters[class];
+        let g = self.group_size() as f64;
+        self.orbits
+            .iter()
+            .map(|orbit| orbit.iter().zip(chi).map(|(&p, &c)| c * full[p]).sum::<T>() / g)
+            .collect()
+    }
+
+    /// Recombine per-class reduced vectors into a full panel vector
+    pub fn reconstruct<T>(&self, reduced: &[Vec<T>], n_panels: usize) -> Vec<T>
+    where
+        T: Copy + Default + AddAssign,
+        f64: Mul<T, Output = T>,
+    {
+        let mut full = vec![T::default(); n_panels];
+        for (class, values) in reduced.iter().enumerate() {
+            let chi = &self.characters[class];
+            for (orbit, &v) in self.orbits.iter().zip(values) {
+                for (&p, &c) in orbit.iter().zip(chi) {
+                    full[p] += c * v;
+                }
+            }
+        }
+        full
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BEMConfig, BEMSolver, ProblemType, SolverEngine};
+    use crate::fixtures::*;
+    use wavecore_meshes::Point;
+
+    fn symmetric_panels() -> Vec<Panel> {
+        // Four triangles, one per quadrant, mirrored about both planes
+        let mut panels = Vec::new();
+        for (sx, sy) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+            panels.push(Panel::new(
+                Point::new(sx * 1.0, sy * 1.0, -1.0),
+                Point::new(sx * 2.0, sy * 1.0, -1.0),
+                Point::new(sx * 1.0, sy * 2.0, -1.5),
+            ).unwrap());
+        }
+        panels
+    }
+
+    #[test]
+    fn test_detect_both_planes() {
+        let panels = symmetric_panels();
+        let map = SymmetryMap::build(&panels, Symmetry::Detect).unwrap().unwrap();
+        assert_eq!(map.planes, (true, true));
+        assert_eq!(map.group_size(), 4);
+        assert_eq!(map.n_fundamental(), 1);
+    }
+
+    #[test]
+    fn test_project_reconstruct_roundtrip() {
+        let panels = symmetric_panels();
+        let map = SymmetryMap::build(&panels, Symmetry::Both).unwrap().unwrap();
+        let full = vec![1.0, -2.0, 3.5, 0.25];
+
+        let reduced: Vec<Vec<f64>> = (0..map.group_size()).map(|c| map.project(&full, c)).collect();
+        let back = map.reconstruct(&reduced, panels.len());
+        for (a, b) in full.iter().zip(&back) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_mesh_stores_fundamental_region() {
+        let full = wavecore_meshes::PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let quarter = SymmetricMesh::from_full(&full, true, true).unwrap();
+        let mut mirrored = quarter.full_mesh().unwrap();
+        let map = SymmetryMap::build(mirrored.panels().unwrap(), Symmetry::from(&quarter)).unwrap().unwrap();
+        assert_eq!(map.planes, (true, true));
+        assert_eq!(map.n_fundamental(), quarter.half.faces.len());
+        assert!(map.orbits.iter().enumerate().all(|(i, orbit)| orbit[0] == i));
+    }
+
+    #[test]
+    fn test_asymmetric_panels_rejected() {
+        let mut panels = symmetric_panels();
+        panels.pop();
+        assert!(SymmetryMap::build(&panels, Symmetry::Detect).unwrap().is_none());
+        assert!(SymmetryMap::build(&panels, Symmetry::Both).is_err());
+    }
+
+    #[test]
+    fn test_symmetry_matches_full_solution() {
+        for mode in [1, 2, 5] {
+            let problem = ProblemType::Radiation { frequency: 1.2, mode };
+            let full = BEMSolver::new(SolverEngine::Standard)
+                .solve_bodies(&problem, vec![octahedron_body()])
+                .unwrap();
+            let reduced = BEMSolver::with_config(BEMConfig {
+                symmetry: Symmetry::Detect,
+                ..Default::default()
+            })
+            .solve_bodies(&problem, vec![octahedron_body()])
+            .unwrap();
+
+            for (a, b) in full.potential().iter().zip(reduced.potential()) {
+                assert!((a - b).norm() < 1e-8 * (1.0 + a.norm()));
+            }
+        }
+    }
+}
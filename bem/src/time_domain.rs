@@ -1,9 +1,28 @@
-use crate::{BEMError, Result, ProblemType};
+//! Time-domain simulation with the Cummins equation
+//!
+//! Body motions x(t) obey
+//!
+//! (M + A∞) ẍ + ∫₀ᵗ K(t - τ) ẋ(τ) dτ + C x = F(t)
+//!
+//! with the retardation functions obtained from the radiation damping of a
+//! frequency sweep by the cosine transform K(t) = (2/π) ∫₀^∞ B(ω) cos(ωt) dω
+//! and A∞ from an infinite-frequency solve. Ogilvie's relation
+//! A(ω) = A∞ - (1/ω) ∫₀^∞ K(t) sin(ωt) dt reconstructs the added mass of the
+//! sweep from K and A∞, which validates both.
+
+use crate::{BEMError, Result};
+use crate::coefficients::HydroCoefficients;
+use crate::control::Controller;
+use crate::force_models::ForceModel;
+use crate::froude_krylov::{IncidentWave, NonlinearFroudeKrylov};
+use ndarray::Array3;
+use num_complex::Complex64;
+use wavecore_matrices::lu_solve;
 use wavecore_meshes::Mesh;
-use wavecore_green_functions::GreenFunction;
 use wavecore_matrices::Matrix;
-use nalgebra::{Point3, Vector3};
-use std::collections::HashMap;
+use nalgebra::Point3;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 
 /// Time domain solver for transient hydrodynamic analysis
@@ -18,6 +37,21 @@ pub struct TimeDomainSolver {
     pub memory_effects: MemoryEffects,
     /// Solver configuration
     pub config: TimeDomainConfig,
+    /// Wave excitation of regular waves, from a frequency sweep
+    pub wave_excitation: Option<WaveExcitation>,
+}
+
+/// Excitation per unit wave amplitude over completed sweep frequencies
+#[derive(Debug, Clone)]
+pub struct WaveExcitation {
+    /// Wave frequencies (rad/s), ascending
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Complex excitation, shape (frequency, direction, mode)
+    pub values: Array3<Complex64>,
+    /// Froude–Krylov part of the excitation, same shape
+    pub froude_krylov: Array3<Complex64>,
 }
 
 /// Time stepping parameters
@@ -46,20 +80,38 @@ pub enum IntegrationScheme {
     Trapezoidal,
     /// Fourth-order Runge-Kutta
     RungeKutta4,
+    /// Newmark-β; β = 1/4, γ = 1/2 is the unconditionally stable average acceleration
+    Newmark { beta: f64, gamma: f64 },
     /// Adaptive time stepping
     Adaptive { tolerance: f64 },
 }
 
+/// Quantities held fixed over one time step
+struct StepContext<'a> {
+    /// Problem being integrated
+    problem: &'a TimeDomainProblem,
+    /// Mass matrix M + A∞
+    mass: &'a Matrix,
+    /// Radiation memory force, evaluated from the history at the start of the step
+    memory: &'a [f64],
+    /// Controller force, sampled at the start of the step
+    control: &'a [f64],
+    /// Pressure integration over the wetted surface, when nonlinear
+    froude_krylov: Option<&'a NonlinearFroudeKrylov>,
+    /// Regular incident wave of the problem
+    incident: Option<IncidentWave>,
+}
+
 /// Impulse response function data
 #[derive(Debug, Clone)]
 pub struct ImpulseResponseData {
     /// Time vector for impulse responses
     pub time_vector: Vec<f64>,
-    /// Impulse response functions for each DOF pair
+    /// Retardation functions K_ij(t) for each DOF pair
     pub responses: HashMap<(usize, usize), Vec<f64>>,
     /// Added mass at infinite frequency
     pub added_mass_inf: Matrix,
-    /// Damping coefficients
+    /// Damping at the highest frequency, where the cosine transform is truncated
     pub damping: Matrix,
     /// Computation metadata
     pub metadata: ImpulseResponseMetadata,
@@ -79,7 +131,7 @@ pub struct ImpulseResponseMetadata {
 }
 
 /// Free surface condition for time domain
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FreeSurfaceCondition {
     /// Free surface mesh
     pub fs_mesh: FreeSurfaceMesh,
@@ -249,6 +301,35 @@ pub struct MemoryEffects {
     history: TimeHistory,
     /// Convolution method
     convolution_method: ConvolutionMethod,
+    /// Sampling interval of kernels and history (s)
+    dt: f64,
+    /// Block convolution state of the FFT method
+    blocks: Option<BlockConvolution>,
+}
+
+/// Uniformly partitioned FFT convolution
+///
+/// Each completed block of velocities is convolved with the kernels by FFT
+/// and its contribution to later steps is accumulated; only the current,
+/// incomplete block is summed directly.
+#[derive(Debug, Clone)]
+struct BlockConvolution {
+    /// Steps per block
+    block: usize,
+    /// FFT length covering a block convolved with a kernel
+    size: usize,
+    /// Kernel length in samples
+    kernel_length: usize,
+    /// Trapezoid-weighted kernels per DOF pair, with their spectra
+    kernels: HashMap<(usize, usize), (Vec<f64>, Vec<Complex64>)>,
+    /// Velocities of the incomplete block per DOF
+    pending: Vec<Vec<f64>>,
+    /// Contributions of completed blocks per DOF, starting at the incomplete block
+    tails: Vec<VecDeque<f64>>,
+    /// Velocities of the first step, for the start-up trapezoid end point
+    first: Vec<f64>,
+    /// Steps recorded
+    steps: usize,
 }
 
 /// Memory kernel for convolution
@@ -285,7 +366,7 @@ pub struct TimeHistory {
 }
 
 /// Convolution methods
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ConvolutionMethod {
     /// Direct convolution
     Direct,
@@ -308,6 +389,13 @@ pub struct TimeDomainConfig {
     pub include_free_surface: bool,
     /// Nonlinear analysis
     pub nonlinear: bool,
+    /// Length of the retardation functions (s)
+    pub retardation_time: f64,
+    /// Evaluation of the radiation memory convolution
+    pub convolution_method: ConvolutionMethod,
+    /// Froude–Krylov and restoring forces of the rigid modes on the instantaneous
+    /// wetted surface; `TimeDomainProblem::mesh` must then cover the whole hull
+    pub nonlinear_froude_krylov: bool,
     /// Output configuration
     pub output: TimeDomainOutputConfig,
 }
@@ -362,6 +450,8 @@ pub struct ExternalForces {
     pub constant_forces: Vec<f64>,
     /// Control forces
     pub control_forces: Option<ControlForces>,
+    /// State-dependent force models such as mooring lines and fenders
+    pub force_models: Vec<Arc<dyn ForceModel>>,
 }
 
 /// Time-dependent force
@@ -376,19 +466,21 @@ pub struct TimeForce {
 /// Control forces for dynamic positioning
 #[derive(Debug, Clone)]
 pub struct ControlForces {
-    /// Controller type
+    /// Controller type with its gains
     pub controller_type: ControllerType,
-    /// Control parameters
-    pub parameters: HashMap<String, f64>,
     /// Target positions
     pub targets: Vec<f64>,
+    /// Largest control force of each DOF; empty when unlimited
+    pub thrust_limits: Vec<f64>,
 }
 
 /// Controller types
 #[derive(Debug, Clone)]
 pub enum ControllerType {
-    PID,
-    LQR,
+    /// Proportional, integral and derivative gains of each DOF on its position error
+    PID { proportional: Vec<f64>, integral: Vec<f64>, derivative: Vec<f64> },
+    /// Linear-quadratic regulator weighting the position and velocity errors and the force of each DOF
+    LQR { position_weights: Vec<f64>, velocity_weights: Vec<f64>, force_weights: Vec<f64> },
     MPC,
     Custom,
 }
@@ -419,6 +511,8 @@ pub struct TimeDomainResults {
     pub accelerations: HashMap<usize, Vec<f64>>,
     /// Forces for each DOF
     pub forces: HashMap<usize, Vec<f64>>,
+    /// Controller forces for each DOF, included in `forces`; empty without control forces
+    pub control_forces: HashMap<usize, Vec<f64>>,
     /// Wave elevation at body center
     pub wave_elevation: Vec<f64>,
     /// Free surface elevation (if computed)
@@ -487,126 +581,140 @@ impl TimeDomainSolver {
             free_surface,
             memory_effects,
             config,
+            wave_excitation: None,
         }
     }
 
+    /// Cummins-equation solver from a completed sweep and the infinite-frequency added mass
+    ///
+    /// Retardation functions are sampled at the time step of `time_params`.
+    pub fn from_coefficients(
+        config: TimeDomainConfig,
+        time_params: TimeParameters,
+        coefficients: &HydroCoefficients,
+        added_mass_inf: Matrix,
+    ) -> Result<Self> {
+        let mut solver = Self::new(config);
+        solver.time_params = time_params;
+        let impulse_responses = solver.calculate_impulse_responses(coefficients, added_mass_inf)?;
+        solver.set_impulse_responses(impulse_responses);
+        solver.wave_excitation = Some(WaveExcitation::from_coefficients(coefficients));
+        Ok(solver)
+    }
+
+    /// Install retardation functions and A∞, resampling the kernels to the time step
+    pub fn set_impulse_responses(&mut self, impulse_responses: ImpulseResponseData) {
+        self.memory_effects =
+            MemoryEffects::from_impulse_responses(&impulse_responses, self.time_params.dt, self.config.convolution_method);
+        self.impulse_responses = impulse_responses;
+    }
+
     /// Solve time domain problem
     pub fn solve_time_domain(&mut self, problem: &TimeDomainProblem) -> Result<TimeDomainResults> {
-        // Initialize time stepping
-        let mut time = self.time_params.t0;
+        let start_time = std::time::Instant::now();
         let dt = self.time_params.dt;
         let mut results = self.initialize_results(problem)?;
         
         // Current state
         let mut positions = problem.initial_conditions.positions.clone();
         let mut velocities = problem.initial_conditions.velocities.clone();
-        let mut accelerations = problem.initial_conditions.accelerations.clone();
+        let mass = self.total_mass(problem)?;
+        let parameters = &problem.wave_environment.parameters;
+        let froude_krylov = if self.config.nonlinear_froude_krylov {
+            Some(NonlinearFroudeKrylov::new(&problem.mesh, problem.body_properties.cog, parameters.rho, parameters.g)?)
+        } else {
+            None
+        };
+        let incident = IncidentWave::from_conditions(&problem.wave_environment);
+        let mut controller = match &problem.external_forces.control_forces {
+            Some(control) => Some(Controller::new(control, &mass, &problem.body_properties, dt)?),
+            None => None,
+        };
+
+        let mut sub_step = dt;
         
         // Time stepping loop
         for step in 0..self.time_params.num_steps {
             // Update time
-            time = self.time_params.t0 + step as f64 * dt;
+            let time = self.time_params.t0 + step as f64 * dt;
             
             // Compute wave elevation
             let wave_elevation = self.compute_wave_elevation(time, &problem.wave_environment)?;
             
-            // Compute hydrodynamic forces
-            let hydro_forces = self.compute_hydrodynamic_forces(
-                time, &positions, &velocities, &accelerations, problem
-            )?;
-            
-            // Compute external forces
-            let external_forces = self.compute_external_forces(time, &problem.external_forces)?;
-            
-            // Apply memory effects
+            // Apply memory effects, held over the step
             let memory_forces = if self.config.include_memory {
                 self.memory_effects.compute_memory_forces(time, &velocities)?
             } else {
                 vec![0.0; positions.len()]
             };
             
-            // Total forces
-            let total_forces: Vec<f64> = hydro_forces.iter()
-                .zip(external_forces.iter())
-                .zip(memory_forces.iter())
-                .map(|((h, e), m)| h + e + m)
-                .collect();
+            // The convolution at the next step sees the current velocities
+            if self.config.include_memory {
+                self.memory_effects.update_history(time, &velocities);
+            }
+            
+            // Sample the controller, holding its forces over the step
+            let control_forces = match controller.as_mut() {
+                Some(controller) => {
+                    let forces = controller.update(&positions, &velocities);
+                    for (i, &force) in forces.iter().enumerate() {
+                        results.control_forces.entry(i).or_default().push(force);
+                    }
+                    forces
+                },
+                None => vec![0.0; positions.len()],
+            };
+            
+            // Total forces and accelerations: (M + A∞) a = F
+            let context = StepContext {
+                problem,
+                mass: &mass,
+                memory: &memory_forces,
+                control: &control_forces,
+                froude_krylov: froude_krylov.as_ref(),
+                incident,
+            };
+            let total_forces = self.total_forces(time, &positions, &velocities, &context)?;
+            let accelerations = lu_solve(&mass, &total_forces)?;
+            
+            // Store results at the start of the step
+            self.store_step_results(&mut results, time, &positions, &velocities, &accelerations, &total_forces, wave_elevation)?;
             
             // Time integration step
             match self.time_params.integration_scheme {
                 IntegrationScheme::ForwardEuler => {
-                    self.forward_euler_step(dt, &mut positions, &mut velocities, &mut accelerations, &total_forces, problem)?;
+                    self.forward_euler_step(dt, &mut positions, &mut velocities, &accelerations);
                 },
                 IntegrationScheme::RungeKutta4 => {
-                    self.runge_kutta4_step(dt, &mut positions, &mut velocities, &mut accelerations, &total_forces, problem)?;
+                    self.runge_kutta4_step(time, dt, &mut positions, &mut velocities, &context)?;
                 },
-                _ => {
+                IntegrationScheme::Trapezoidal => {
+                    self.newmark_step(time, dt, (0.25, 0.5), &mut positions, &mut velocities, &context)?;
+                },
+                IntegrationScheme::Newmark { beta, gamma } => {
+                    self.newmark_step(time, dt, (beta, gamma), &mut positions, &mut velocities, &context)?;
+                },
+                IntegrationScheme::Adaptive { tolerance } => {
+                    let error = self.adaptive_step(time, (dt, tolerance), &mut sub_step, &mut positions, &mut velocities, &context)?;
+                    let estimates = &mut results.metadata.error_estimates;
+                    estimates.local_error = estimates.local_error.max(error);
+                },
+                IntegrationScheme::BackwardEuler => {
                     return Err(BEMError::SolverError { message: "Unsupported integration scheme".to_string() });
                 }
             }
-            
-            // Store results
-            self.store_step_results(&mut results, step, time, &positions, &velocities, &accelerations, &total_forces, wave_elevation)?;
-            
-            // Update memory history - Now with mutable self
-            if self.config.include_memory {
-                self.memory_effects.update_history(time, &velocities);
-            }
         }
-        
+
         // Finalize results
+        results.metadata.computation_time = start_time.elapsed().as_secs_f64();
         self.finalize_results(results)
     }
 
-    /// Calculate impulse response functions
-    pub fn calculate_impulse_responses(&self, frequencies: &[f64]) -> Result<ImpulseResponseData> {
-        if frequencies.is_empty() {
-            return Err(BEMError::InvalidProblem { message: "Empty frequency array".to_string() });
-        }
-
-        let num_dofs = 6; // Typical 6-DOF rigid body
-        let mut responses = HashMap::new();
-        
-        // Time vector for impulse responses
-        let t_max = 4.0 * std::f64::consts::PI / frequencies[0]; // 4 periods of lowest frequency
-        let dt = 0.1 / frequencies.last().unwrap(); // 10 points per period of highest frequency
-        let time_vector: Vec<f64> = (0..((t_max / dt) as usize))
-            .map(|i| i as f64 * dt)
-            .collect();
-        
-        // Compute impulse responses for each DOF pair
-        for i in 0..num_dofs {
-            for j in 0..num_dofs {
-                let impulse_response = self.compute_impulse_response_ij(i, j, frequencies, &time_vector)?;
-                responses.insert((i, j), impulse_response);
-            }
-        }
-        
-        // Compute added mass at infinite frequency (simplified) - Fix Matrix API
-        let mut added_mass_inf = Matrix::new(num_dofs, num_dofs);
-        for i in 0..num_dofs {
-            added_mass_inf.set(i, i, 0.1)?;
-        }
-
-        let mut damping = Matrix::new(num_dofs, num_dofs);
-        for i in 0..num_dofs {
-            damping.set(i, i, 0.05)?;
-        }
-
-        let metadata = ImpulseResponseMetadata {
-            frequency_range: (*frequencies.first().unwrap(), *frequencies.last().unwrap()),
-            num_frequencies: frequencies.len(),
-            method: "Frequency domain transformation".to_string(),
-            accuracy: 0.01, // 1% estimated accuracy
-        };
-        
-        Ok(ImpulseResponseData {
-            time_vector,
-            responses,
-            added_mass_inf,
-            damping,
-            metadata,
-        })
+    /// Retardation functions from the radiation damping of a sweep
+    ///
+    /// Sampled at the solver time step up to `retardation_time`.
+    pub fn calculate_impulse_responses(&self, coefficients: &HydroCoefficients, added_mass_inf: Matrix) -> Result<ImpulseResponseData> {
+        ImpulseResponseData::from_coefficients(coefficients, added_mass_inf, self.time_params.dt, self.config.retardation_time)
     }
 
     /// Apply convolution for memory effects
@@ -627,30 +735,6 @@ impl TimeDomainSolver {
         Ok(result)
     }
 
-    /// Compute impulse response for DOF pair (i,j)
-    fn compute_impulse_response_ij(&self, i: usize, j: usize, frequencies: &[f64], time_vector: &[f64]) -> Result<Vec<f64>> {
-        let mut impulse_response = vec![0.0; time_vector.len()];
-        
-        // Simplified implementation - inverse Fourier transform
-        for (t_idx, &t) in time_vector.iter().enumerate() {
-            let mut value = 0.0;
-            
-            for &freq in frequencies {
-                // Simplified frequency domain response
-                let omega = 2.0 * std::f64::consts::PI * freq;
-                let response_real = 1.0 / (1.0 + omega * omega); // Simple response
-                let response_imag = omega / (1.0 + omega * omega);
-                
-                // Inverse Fourier transform component
-                value += response_real * (omega * t).cos() + response_imag * (omega * t).sin();
-            }
-            
-            impulse_response[t_idx] = value * 2.0 / frequencies.len() as f64;
-        }
-        
-        Ok(impulse_response)
-    }
-
     /// Initialize results structure
     fn initialize_results(&self, problem: &TimeDomainProblem) -> Result<TimeDomainResults> {
         let num_dofs = problem.initial_conditions.positions.len();
@@ -692,6 +776,7 @@ impl TimeDomainSolver {
             velocities,
             accelerations,
             forces,
+            control_forces: HashMap::new(),
             wave_elevation,
             free_surface_elevation: None,
             metadata,
@@ -752,38 +837,112 @@ impl TimeDomainSolver {
         Ok(0.0)
     }
 
-    /// Compute hydrodynamic forces
-    fn compute_hydrodynamic_forces(&self, time: f64, positions: &[f64], velocities: &[f64], 
-                                  accelerations: &[f64], problem: &TimeDomainProblem) -> Result<Vec<f64>> {
+    /// Mass matrix of the Cummins equation, M + A∞
+    ///
+    /// An empty A∞ (no impulse responses installed) adds nothing.
+    fn total_mass(&self, problem: &TimeDomainProblem) -> Result<Matrix> {
+        let mut mass = problem.body_properties.mass.clone();
+        let added = &self.impulse_responses.added_mass_inf;
+        if added.rows == 0 {
+            return Ok(mass);
+        }
+        if added.dimensions() != mass.dimensions() {
+            return Err(BEMError::InvalidProblem {
+                message: format!("A(∞) of shape {:?} does not match mass of shape {:?}", added.dimensions(), mass.dimensions()),
+            });
+        }
+        for (m, a) in mass.data.iter_mut().zip(&added.data) {
+            *m += a;
+        }
+        Ok(mass)
+    }
+
+    /// Hydrodynamic, external, memory and control forces on a state
+    fn total_forces(&self, time: f64, positions: &[f64], velocities: &[f64], context: &StepContext) -> Result<Vec<f64>> {
+        let hydro_forces = self.compute_hydrodynamic_forces(time, positions, velocities, context)?;
+        let external_forces = self.compute_external_forces(time, positions, velocities, &context.problem.external_forces)?;
+        Ok(hydro_forces.iter()
+            .zip(external_forces.iter())
+            .zip(context.memory.iter())
+            .zip(context.control.iter())
+            .map(|(((h, e), m), c)| h + e + m + c)
+            .collect())
+    }
+
+    /// Accelerations of a state, solving (M + A∞) a = F
+    fn accelerations(&self, time: f64, positions: &[f64], velocities: &[f64], context: &StepContext) -> Result<Vec<f64>> {
+        Ok(lu_solve(context.mass, &self.total_forces(time, positions, velocities, context)?)?)
+    }
+
+    /// Compute hydrodynamic forces: wave excitation, restoring and linear damping
+    ///
+    /// With nonlinear Froude–Krylov forces the rigid modes take their restoring
+    /// and Froude–Krylov forces from the wetted surface and only the
+    /// diffraction part of the sweep excitation.
+    fn compute_hydrodynamic_forces(&self, time: f64, positions: &[f64], velocities: &[f64],
+                                  context: &StepContext) -> Result<Vec<f64>> {
+        let problem = context.problem;
         let num_dofs = positions.len();
-        let mut forces = vec![0.0; num_dofs];
+        let mut forces = self.compute_wave_excitation(time, &problem.wave_environment, num_dofs, context.froude_krylov.is_some())?;
         
-        // Added mass forces - Fix Matrix indexing
-        for i in 0..num_dofs {
-            for j in 0..num_dofs {
-                forces[i] -= problem.body_properties.mass.get(i, j)? * accelerations[j];
+        // Pressure integration over the wetted surface
+        let mut linear_from = 0;
+        if let Some(hull) = context.froude_krylov {
+            let pressure = hull.forces(positions, context.incident.as_ref(), time);
+            for (force, p) in forces.iter_mut().zip(pressure) {
+                *force += p;
             }
+            linear_from = num_dofs.min(6);
         }
         
         // Hydrostatic forces
-        for i in 0..num_dofs {
-            for j in 0..num_dofs {
-                forces[i] -= problem.body_properties.hydrostatic.get(i, j)? * positions[j];
+        for (i, force) in forces.iter_mut().enumerate().skip(linear_from) {
+            for (j, position) in positions.iter().enumerate() {
+                *force -= problem.body_properties.hydrostatic.get(i, j)? * position;
             }
         }
         
         // Linear damping forces
-        for i in 0..num_dofs {
-            for j in 0..num_dofs {
-                forces[i] -= problem.body_properties.linear_damping.get(i, j)? * velocities[j];
+        for (i, force) in forces.iter_mut().enumerate() {
+            for (j, velocity) in velocities.iter().enumerate() {
+                *force -= problem.body_properties.linear_damping.get(i, j)? * velocity;
             }
         }
         
         Ok(forces)
     }
 
+    /// Excitation of a regular wave from the sweep, zero without sweep data
+    ///
+    /// The elevation A sin(ωt + phase) at the origin is the complex amplitude
+    /// A e^{-i(phase - π/2)} of the solver's e^{-iωt} convention.
+    fn compute_wave_excitation(&self, time: f64, wave_conditions: &WaveConditions, num_dofs: usize,
+                               diffraction_only: bool) -> Result<Vec<f64>> {
+        let (Some(excitation), WaveType::Regular { amplitude, frequency, phase }) = (&self.wave_excitation, &wave_conditions.wave_type) else {
+            return Ok(vec![0.0; num_dofs]);
+        };
+        let omega = 2.0 * std::f64::consts::PI * frequency;
+        let direction = wave_conditions.direction.to_radians();
+        let values = if diffraction_only {
+            excitation.diffraction_at(omega, direction)
+        } else {
+            excitation.at(omega, direction)
+        };
+        let values = values.ok_or_else(|| BEMError::InvalidProblem {
+            message: format!("Wave frequency {} rad/s is outside the sweep", omega),
+        })?;
+        if values.len() != num_dofs {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Excitation has {} modes, expected {}", values.len(), num_dofs),
+            });
+        }
+        let wave = Complex64::from_polar(*amplitude, -(phase - std::f64::consts::FRAC_PI_2) - omega * time);
+        Ok(values.iter().map(|x| (wave * x).re).collect())
+    }
+
     /// Compute external forces
-    fn compute_external_forces(&self, time: f64, external_forces: &ExternalForces) -> Result<Vec<f64>> {
+    fn compute_external_forces(&self, time: f64, positions: &[f64], velocities: &[f64],
+                               external_forces: &ExternalForces) -> Result<Vec<f64>> {
         let mut forces = external_forces.constant_forces.clone();
         
         // Add time-dependent forces
@@ -794,39 +953,138 @@ impl TimeDomainSolver {
             }
         }
         
+        // Add force models, evaluated on the current state
+        for model in &external_forces.force_models {
+            for (force, value) in forces.iter_mut().zip(model.forces(time, positions, velocities)?) {
+                *force += value;
+            }
+        }
+        
         Ok(forces)
     }
 
-    /// Forward Euler integration step
-    fn forward_euler_step(&self, dt: f64, positions: &mut [f64], velocities: &mut [f64], 
-                         accelerations: &mut [f64], forces: &[f64], problem: &TimeDomainProblem) -> Result<()> {
-        // Update accelerations: M * a = F - Fix Matrix indexing
-        for i in 0..accelerations.len() {
-            accelerations[i] = forces[i] / problem.body_properties.mass.get(i, i)?.max(1e-10);
-        }
-        
+    /// Forward Euler integration step, semi-implicit in the positions
+    fn forward_euler_step(&self, dt: f64, positions: &mut [f64], velocities: &mut [f64], accelerations: &[f64]) {
         // Update velocities: v = v + a * dt
-        for i in 0..velocities.len() {
-            velocities[i] += accelerations[i] * dt;
+        for (v, a) in velocities.iter_mut().zip(accelerations) {
+            *v += a * dt;
         }
         
         // Update positions: x = x + v * dt
+        for (x, v) in positions.iter_mut().zip(velocities.iter()) {
+            *x += v * dt;
+        }
+    }
+
+    /// Runge-Kutta 4th order integration step
+    fn runge_kutta4_step(&self, time: f64, dt: f64, positions: &mut [f64], velocities: &mut [f64],
+                        context: &StepContext) -> Result<()> {
+        let advance = |base: &[f64], rate: &[f64], h: f64| -> Vec<f64> {
+            base.iter().zip(rate).map(|(b, r)| b + h * r).collect()
+        };
+        
+        let a1 = self.accelerations(time, positions, velocities, context)?;
+        let (x2, v2) = (advance(positions, velocities, 0.5 * dt), advance(velocities, &a1, 0.5 * dt));
+        let a2 = self.accelerations(time + 0.5 * dt, &x2, &v2, context)?;
+        let (x3, v3) = (advance(positions, &v2, 0.5 * dt), advance(velocities, &a2, 0.5 * dt));
+        let a3 = self.accelerations(time + 0.5 * dt, &x3, &v3, context)?;
+        let (x4, v4) = (advance(positions, &v3, dt), advance(velocities, &a3, dt));
+        let a4 = self.accelerations(time + dt, &x4, &v4, context)?;
+        
         for i in 0..positions.len() {
-            positions[i] += velocities[i] * dt;
+            positions[i] += dt / 6.0 * (velocities[i] + 2.0 * v2[i] + 2.0 * v3[i] + v4[i]);
+            velocities[i] += dt / 6.0 * (a1[i] + 2.0 * a2[i] + 2.0 * a3[i] + a4[i]);
         }
         
         Ok(())
     }
 
-    /// Runge-Kutta 4th order integration step
-    fn runge_kutta4_step(&self, dt: f64, positions: &mut [f64], velocities: &mut [f64],
-                        accelerations: &mut [f64], forces: &[f64], problem: &TimeDomainProblem) -> Result<()> {
-        // Simplified RK4 - would need full implementation
-        self.forward_euler_step(dt, positions, velocities, accelerations, forces, problem)
+    /// Newmark-β step with parameters (β, γ)
+    ///
+    /// Implicit in the linear restoring and damping forces: the end-of-step
+    /// accelerations solve (M + γΔt B + βΔt² C) a = F evaluated at the
+    /// predicted state. Other forces are taken at the predicted state.
+    fn newmark_step(&self, time: f64, dt: f64, (beta, gamma): (f64, f64), positions: &mut [f64], velocities: &mut [f64],
+                    context: &StepContext) -> Result<()> {
+        let accelerations = self.accelerations(time, positions, velocities, context)?;
+        
+        // Predictors from the current state
+        for i in 0..positions.len() {
+            positions[i] += dt * velocities[i] + (0.5 - beta) * dt * dt * accelerations[i];
+            velocities[i] += (1.0 - gamma) * dt * accelerations[i];
+        }
+        
+        let properties = &context.problem.body_properties;
+        let mut effective = context.mass.clone();
+        for i in 0..positions.len() {
+            for j in 0..positions.len() {
+                let value = effective.get(i, j)?
+                    + gamma * dt * properties.linear_damping.get(i, j)?
+                    + beta * dt * dt * properties.hydrostatic.get(i, j)?;
+                effective.set(i, j, value)?;
+            }
+        }
+        let forces = self.total_forces(time + dt, positions, velocities, context)?;
+        let next = lu_solve(&effective, &forces)?;
+        
+        // Correctors
+        for i in 0..positions.len() {
+            positions[i] += beta * dt * dt * next[i];
+            velocities[i] += gamma * dt * next[i];
+        }
+        
+        Ok(())
+    }
+
+    /// Error-controlled Runge-Kutta sub-steps across one step of (Δt, tolerance)
+    ///
+    /// Step doubling estimates the local error of each sub-step, which is kept
+    /// below the tolerance relative to 1 + |state|; `sub_step` carries the
+    /// step size between calls. Returns the largest accepted error estimate.
+    fn adaptive_step(&self, time: f64, (dt, tolerance): (f64, f64), sub_step: &mut f64, positions: &mut [f64],
+                     velocities: &mut [f64], context: &StepContext) -> Result<f64> {
+        let end = time + dt;
+        let mut current = time;
+        let mut largest: f64 = 0.0;
+        while end - current > 1e-12 * dt {
+            let h = sub_step.min(end - current);
+            let clipped = h < *sub_step;
+            let (mut full_x, mut full_v) = (positions.to_vec(), velocities.to_vec());
+            self.runge_kutta4_step(current, h, &mut full_x, &mut full_v, context)?;
+            let (mut half_x, mut half_v) = (positions.to_vec(), velocities.to_vec());
+            self.runge_kutta4_step(current, 0.5 * h, &mut half_x, &mut half_v, context)?;
+            self.runge_kutta4_step(current + 0.5 * h, 0.5 * h, &mut half_x, &mut half_v, context)?;
+            
+            // Richardson estimate of the error of the half steps
+            let error = full_x.iter().zip(&half_x).chain(full_v.iter().zip(&half_v))
+                .map(|(a, b)| (a - b).abs() / 15.0 / (1.0 + b.abs()))
+                .fold(0.0, f64::max);
+            let ratio = error / tolerance;
+            let factor = if ratio > 0.0 { (0.9 * ratio.powf(-0.2)).clamp(0.2, 4.0) } else { 4.0 };
+            if ratio <= 1.0 {
+                positions.copy_from_slice(&half_x);
+                velocities.copy_from_slice(&half_v);
+                current += h;
+                largest = largest.max(error);
+                // A step shortened to end on the output time does not limit the next one
+                if !clipped || factor < 1.0 {
+                    *sub_step = (h * factor).min(dt);
+                }
+            } else {
+                *sub_step = h * factor;
+                if *sub_step < 1e-12 * dt {
+                    return Err(BEMError::NumericalError {
+                        message: format!("Adaptive step size underflow at t = {} s", current),
+                    });
+                }
+            }
+        }
+        Ok(largest)
     }
 
     /// Store results for current time step
-    fn store_step_results(&self, results: &mut TimeDomainResults, step: usize, time: f64,
+    #[allow(clippy::too_many_arguments)]
+    fn store_step_results(&self, results: &mut TimeDomainResults, time: f64,
                          positions: &[f64], velocities: &[f64], accelerations: &[f64],
                          forces: &[f64], wave_elevation: f64) -> Result<()> {
         results.time.push(time);
@@ -850,6 +1108,12 @@ impl TimeDomainSolver {
     }
 }
 
+impl Default for MemoryEffects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryEffects {
     /// Create new memory effects handler
     pub fn new() -> Self {
@@ -857,20 +1121,60 @@ impl MemoryEffects {
             kernels: HashMap::new(),
             history: TimeHistory::new(1000), // Store 1000 time steps
             convolution_method: ConvolutionMethod::Direct,
+            dt: TimeParameters::default().dt,
+            blocks: None,
+        }
+    }
+
+    /// Retardation kernels resampled at interval `dt`, keeping as much history as the kernels span
+    pub fn from_impulse_responses(impulse_responses: &ImpulseResponseData, dt: f64, method: ConvolutionMethod) -> Self {
+        let time = &impulse_responses.time_vector;
+        let duration = time.last().copied().unwrap_or(0.0);
+        let samples: Vec<f64> = (0..=(duration / dt + 1e-9) as usize).map(|k| k as f64 * dt).collect();
+        let kernels: HashMap<(usize, usize), MemoryKernel> = impulse_responses
+            .responses
+            .iter()
+            .map(|(&pair, values)| {
+                let kernel = MemoryKernel {
+                    values: samples.iter().map(|&t| interpolate_linear(time, values, t)).collect(),
+                    time: samples.clone(),
+                    kernel_type: KernelType::Retardation,
+                };
+                (pair, kernel)
+            })
+            .collect();
+        let blocks = (method == ConvolutionMethod::FFT).then(|| BlockConvolution::new(&kernels, samples.len()));
+        Self {
+            kernels,
+            history: TimeHistory::new(samples.len()),
+            convolution_method: method,
+            dt,
+            blocks,
         }
     }
 
-    /// Compute memory forces
+    /// Radiation memory force -∫₀ᵗ K(τ) ẋ(t - τ) dτ from the current velocities and the history
     pub fn compute_memory_forces(&self, _time: f64, velocities: &[f64]) -> Result<Vec<f64>> {
+        match (self.convolution_method, &self.blocks) {
+            (ConvolutionMethod::Direct, _) => {},
+            (ConvolutionMethod::FFT, Some(blocks)) => {
+                return Ok(blocks.forces(velocities).into_iter().map(|f| -f * self.dt).collect());
+            },
+            _ => {
+                return Err(BEMError::SolverError {
+                    message: format!("{:?} convolution is not supported", self.convolution_method),
+                });
+            }
+        }
         let mut forces = vec![0.0; velocities.len()];
-        
-        // Apply convolution for each DOF
-        for i in 0..velocities.len() {
-            if let Some(history) = self.history.data.get(&i) {
-                if let Some(kernel) = self.kernels.get(&(i, i)) {
-                    forces[i] = self.convolve_history(history, &kernel.values)?;
-                }
+
+        // Apply convolution for each DOF pair
+        for (&(i, j), kernel) in &self.kernels {
+            if i >= velocities.len() || j >= velocities.len() {
+                continue;
             }
+            let history = self.history.data.get(&j).map_or(&[][..], |h| h.as_slice());
+            forces[i] -= self.convolve_history(velocities[j], history, &kernel.values)?;
         }
         
         Ok(forces)
@@ -878,10 +1182,14 @@ impl MemoryEffects {
 
     /// Update velocity history
     pub fn update_history(&mut self, time: f64, velocities: &[f64]) {
+        if let Some(blocks) = &mut self.blocks {
+            blocks.push(velocities);
+            return;
+        }
         self.history.times.push(time);
         
         for (i, &velocity) in velocities.iter().enumerate() {
-            self.history.data.entry(i).or_insert_with(Vec::new).push(velocity);
+            self.history.data.entry(i).or_default().push(velocity);
         }
         
         // Trim history to maximum length
@@ -895,16 +1203,127 @@ impl MemoryEffects {
         }
     }
 
-    /// Convolve history with kernel
-    fn convolve_history(&self, history: &[f64], kernel: &[f64]) -> Result<f64> {
-        let mut result = 0.0;
-        let len = history.len().min(kernel.len());
+    /// Trapezoidal convolution of a kernel with the current value and the history
+    fn convolve_history(&self, current: f64, history: &[f64], kernel: &[f64]) -> Result<f64> {
+        let len = (history.len() + 1).min(kernel.len());
+        if len < 2 {
+            return Ok(0.0);
+        }
+        let value = |k: usize| if k == 0 { current } else { history[history.len() - k] };
+        let mut result = 0.5 * (kernel[0] * value(0) + kernel[len - 1] * value(len - 1));
         
-        for i in 0..len {
-            result += history[history.len() - 1 - i] * kernel[i];
+        for (k, &weight) in kernel.iter().enumerate().take(len - 1).skip(1) {
+            result += weight * value(k);
         }
         
-        Ok(result)
+        Ok(result * self.dt)
+    }
+}
+
+impl BlockConvolution {
+    /// Partition for kernels of `kernel_length` samples, in blocks of about √length steps
+    fn new(kernels: &HashMap<(usize, usize), MemoryKernel>, kernel_length: usize) -> Self {
+        let block = ((kernel_length as f64).sqrt() as usize).next_power_of_two().max(8);
+        let size = (block + kernel_length).next_power_of_two();
+        let weighted = kernels
+            .iter()
+            .map(|(&pair, kernel)| {
+                // Trapezoid end weights; a single sample spans no interval
+                let mut values = kernel.values.clone();
+                match values.len() {
+                    0 => {},
+                    1 => values[0] = 0.0,
+                    len => {
+                        values[0] *= 0.5;
+                        values[len - 1] *= 0.5;
+                    }
+                }
+                let mut spectrum: Vec<Complex64> = values.iter().map(|&v| Complex64::new(v, 0.0)).collect();
+                spectrum.resize(size, Complex64::new(0.0, 0.0));
+                fft(&mut spectrum, false);
+                (pair, (values, spectrum))
+            })
+            .collect();
+        let n = kernels.keys().map(|&(i, j)| i.max(j) + 1).max().unwrap_or(0);
+        Self {
+            block,
+            size,
+            kernel_length,
+            kernels: weighted,
+            pending: vec![Vec::with_capacity(block); n],
+            tails: vec![VecDeque::new(); n],
+            first: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    /// Trapezoidal convolution sums of all DOFs at the current step, not yet scaled by dt
+    fn forces(&self, velocities: &[f64]) -> Vec<f64> {
+        let step = self.steps;
+        let offset = step % self.block;
+        let mut forces: Vec<f64> = (0..velocities.len())
+            .map(|i| self.tails.get(i).and_then(|tail| tail.get(offset)).copied().unwrap_or(0.0))
+            .collect();
+        for (&(i, j), (weights, _)) in &self.kernels {
+            if i >= velocities.len() || j >= velocities.len() {
+                continue;
+            }
+            let value = |k: usize| if k == 0 { velocities[j] } else { self.pending[j][offset - k] };
+            forces[i] += (0..=offset.min(self.kernel_length - 1)).map(|k| weights[k] * value(k)).sum::<f64>();
+            
+            // Until the kernel is covered, the first step closes the trapezoid with half weight
+            if step + 1 < self.kernel_length {
+                let first = if step == 0 { velocities[j] } else { self.first[j] };
+                forces[i] -= if step == 0 { weights[0] } else { 0.5 * weights[step] } * first;
+            }
+        }
+        forces
+    }
+
+    /// Record the velocities of a step, convolving the block by FFT once it is complete
+    fn push(&mut self, velocities: &[f64]) {
+        if self.steps == 0 {
+            self.first = velocities.to_vec();
+        }
+        self.steps += 1;
+        for (pending, &v) in self.pending.iter_mut().zip(velocities) {
+            pending.push(v);
+        }
+        if !self.steps.is_multiple_of(self.block) {
+            return;
+        }
+        
+        let spectra: Vec<Vec<Complex64>> = self
+            .pending
+            .iter_mut()
+            .map(|pending| {
+                let mut spectrum: Vec<Complex64> = pending.drain(..).map(|v| Complex64::new(v, 0.0)).collect();
+                spectrum.resize(self.size, Complex64::new(0.0, 0.0));
+                fft(&mut spectrum, false);
+                spectrum
+            })
+            .collect();
+        for (i, tail) in self.tails.iter_mut().enumerate() {
+            let mut product = vec![Complex64::new(0.0, 0.0); self.size];
+            for (j, spectrum) in spectra.iter().enumerate() {
+                if let Some((_, kernel)) = self.kernels.get(&(i, j)) {
+                    for ((p, a), b) in product.iter_mut().zip(kernel).zip(spectrum) {
+                        *p += a * b;
+                    }
+                }
+            }
+            fft(&mut product, true);
+            
+            // Outputs of the completed block are done; the rest carries to later steps
+            tail.drain(..self.block.min(tail.len()));
+            let length = self.block + self.kernel_length - 1;
+            if tail.len() < length - self.block {
+                tail.resize(length - self.block, 0.0);
+            }
+            for (m, value) in product.iter().enumerate().take(length).skip(self.block) {
+                tail[m - self.block] += value.re / self.size as f64;
+            }
+        }
     }
 }
 
@@ -940,6 +1359,9 @@ impl Default for TimeDomainConfig {
             include_memory: true,
             include_free_surface: false,
             nonlinear: false,
+            retardation_time: 20.0,
+            convolution_method: ConvolutionMethod::FFT,
+            nonlinear_froude_krylov: false,
             output: TimeDomainOutputConfig::default(),
         }
     }
@@ -957,29 +1379,79 @@ impl Default for TimeDomainOutputConfig {
     }
 }
 
-impl Default for FreeSurfaceCondition {
-    fn default() -> Self {
-        Self {
-            fs_mesh: FreeSurfaceMesh::default(),
-            wave_conditions: WaveConditions::default(),
-            boundary_conditions: FreeSurfaceBoundary::default(),
-            nonlinear_effects: NonlinearEffects::default(),
+impl FreeSurfaceMesh {
+    /// Regular grid of nx × ny panels over the extents on z = 0
+    pub fn regular(parameters: FreeSurfaceMeshParams) -> Result<Self> {
+        let FreeSurfaceMeshParams { x_extent, y_extent, nx, ny } = parameters.clone();
+        if nx == 0 || ny == 0 || x_extent.1 <= x_extent.0 || y_extent.1 <= y_extent.0 {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Free-surface grid needs positive extents and panel counts, got {:?}", parameters),
+            });
         }
+        let vertices = (0..=ny)
+            .flat_map(|j| (0..=nx).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let x = x_extent.0 + (x_extent.1 - x_extent.0) * i as f64 / nx as f64;
+                let y = y_extent.0 + (y_extent.1 - y_extent.0) * j as f64 / ny as f64;
+                Point3::new(x, y, 0.0)
+            })
+            .collect();
+        let index = |i: usize, j: usize| j * (nx + 1) + i;
+        let quads = (0..ny)
+            .flat_map(|j| (0..nx).map(move |i| [index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1)]))
+            .collect();
+        let mut mesh = Self::from_mesh(&Mesh::with_quads(vertices, Vec::new(), quads)?)?;
+        mesh.parameters = parameters;
+        Ok(mesh)
+    }
+
+    /// Free-surface mesh from generated panels, e.g. a `FreeSurfaceRing` or `FreeSurfacePatch`
+    ///
+    /// The extents are the mesh bounds; nx and ny are left at zero as the
+    /// panels need not form a grid.
+    pub fn from_mesh(mesh: &Mesh) -> Result<Self> {
+        let panels = mesh
+            .polygons()
+            .map(|polygon| {
+                let corners: Vec<Point3<f64>> = polygon.iter().map(|&v| mesh.vertices[v]).collect();
+                let panel = match corners[..] {
+                    [a, b, c, d] => wavecore_meshes::Panel::quad(a, b, c, d)?,
+                    _ => wavecore_meshes::Panel::new(corners[0], corners[1], corners[2])?,
+                };
+                let normal = panel.normal();
+                Ok(FreeSurfacePanel {
+                    vertices: polygon.to_vec(),
+                    center: panel.centroid(),
+                    area: panel.area(),
+                    normal: Point3::new(normal.x, normal.y, normal.z),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let extent = |f: fn(&Point3<f64>) -> f64| {
+            mesh.vertices.iter().map(f).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+        };
+        Ok(Self {
+            grid_points: mesh.vertices.clone(),
+            panels,
+            parameters: FreeSurfaceMeshParams {
+                x_extent: extent(|p| p.x),
+                y_extent: extent(|p| p.y),
+                nx: 0,
+                ny: 0,
+            },
+        })
     }
 }
 
 impl Default for FreeSurfaceMesh {
     fn default() -> Self {
-        Self {
-            grid_points: Vec::new(),
-            panels: Vec::new(),
-            parameters: FreeSurfaceMeshParams {
-                x_extent: (-50.0, 50.0),
-                y_extent: (-50.0, 50.0),
-                nx: 20,
-                ny: 20,
-            },
-        }
+        Self::regular(FreeSurfaceMeshParams {
+            x_extent: (-50.0, 50.0),
+            y_extent: (-50.0, 50.0),
+            nx: 20,
+            ny: 20,
+        })
+        .expect("default free-surface grid is valid")
     }
 }
 
@@ -1023,13 +1495,127 @@ impl Default for NonlinearEffects {
 }
 
 impl ImpulseResponseData {
+    /// Retardation functions from the radiation damping of a sweep, sampled every `dt` up to `duration`
+    ///
+    /// K(t) = (2/π) ∫ B(ω) cos(ωt) dω over the completed frequencies, with B
+    /// interpolated linearly, taken to vanish at ω = 0 and truncated above
+    /// the highest frequency. B is resampled on a uniform grid finer than the
+    /// sweep and transformed by FFT; the sinc² factor makes the transform
+    /// exact for the piecewise-linear resampled curve. `metadata.accuracy`
+    /// reports the Ogilvie check of `added_mass_error`.
+    pub fn from_coefficients(coefficients: &HydroCoefficients, added_mass_inf: Matrix, dt: f64, duration: f64) -> Result<Self> {
+        let n = coefficients.n_dof();
+        if added_mass_inf.dimensions() != (n, n) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("A(∞) of shape {:?} does not match {} modes", added_mass_inf.dimensions(), n),
+            });
+        }
+        if !(dt > 0.0 && duration > 0.0) {
+            return Err(BEMError::InvalidProblem {
+                message: format!("Time step {} and duration {} must be positive", dt, duration),
+            });
+        }
+        let solved = completed_frequencies(coefficients);
+        if solved.len() < 2 {
+            return Err(BEMError::InvalidProblem {
+                message: "Retardation functions need at least two completed frequencies".to_string(),
+            });
+        }
+        
+        let time_vector: Vec<f64> = (0..=(duration / dt + 1e-9) as usize).map(|k| k as f64 * dt).collect();
+        let omega: Vec<f64> = std::iter::once(0.0).chain(solved.iter().map(|&f| coefficients.frequencies[f])).collect();
+        
+        // Grid step a quarter of the finest sweep spacing, with Δω Δt = 2π / size
+        let spacing = omega.windows(2).map(|w| w[1] - w[0]).filter(|&h| h > 0.0).fold(f64::INFINITY, f64::min);
+        let size = ((2.0 * std::f64::consts::PI / (dt * 0.25 * spacing)).ceil() as usize)
+            .max(time_vector.len())
+            .next_power_of_two();
+        let step = 2.0 * std::f64::consts::PI / (size as f64 * dt);
+        let grid_length = (omega[omega.len() - 1] / step) as usize + 1;
+        let mut responses = HashMap::new();
+        for i in 0..n {
+            for j in 0..n {
+                let damping: Vec<f64> = std::iter::once(0.0).chain(solved.iter().map(|&f| coefficients.damping[[f, i, j]])).collect();
+                
+                // e^{iωt} is periodic in the grid index with period `size`, so higher frequencies fold exactly
+                let mut spectrum = vec![Complex64::new(0.0, 0.0); size];
+                let mut segment = 1;
+                for m in 1..grid_length {
+                    let w = m as f64 * step;
+                    while omega[segment] < w {
+                        segment += 1;
+                    }
+                    let t = (w - omega[segment - 1]) / (omega[segment] - omega[segment - 1]);
+                    spectrum[m % size] += damping[segment - 1] + t * (damping[segment] - damping[segment - 1]);
+                }
+                fft(&mut spectrum, true);
+                
+                let response = time_vector
+                    .iter()
+                    .zip(&spectrum)
+                    .map(|(&t, value)| {
+                        let half = 0.5 * step * t;
+                        let attenuation = if half == 0.0 { 1.0 } else { (half.sin() / half).powi(2) };
+                        2.0 / std::f64::consts::PI * step * attenuation * value.re
+                    })
+                    .collect();
+                responses.insert((i, j), response);
+            }
+        }
+
+        let last = *solved.last().unwrap();
+        let damping = Matrix::from_vec(n, n, coefficients.damping_at(last).iter().copied().collect())?;
+        let mut data = Self {
+            time_vector,
+            responses,
+            added_mass_inf,
+            damping,
+            metadata: ImpulseResponseMetadata {
+                frequency_range: (coefficients.frequencies[solved[0]], coefficients.frequencies[last]),
+                num_frequencies: solved.len(),
+                method: "FFT of radiation damping".to_string(),
+                accuracy: 0.0,
+            },
+        };
+        data.metadata.accuracy = data.added_mass_error(coefficients)?;
+        Ok(data)
+    }
+
+    /// Added mass from the retardation functions, A(ω) = A∞ - (1/ω) ∫ K(t) sin(ωt) dt
+    pub fn added_mass_at(&self, frequency: f64) -> Result<Matrix> {
+        let mut added_mass = self.added_mass_inf.clone();
+        for (&(i, j), response) in &self.responses {
+            let transform = linear_fourier_integral(&self.time_vector, response, frequency).im;
+            added_mass.set(i, j, added_mass.get(i, j)? - transform / frequency)?;
+        }
+        Ok(added_mass)
+    }
+
+    /// Largest difference between the sweep added mass and its Ogilvie
+    /// reconstruction, relative to the largest sweep added mass
+    ///
+    /// Small values confirm that A∞, the damping curve and the retardation
+    /// time are consistent.
+    pub fn added_mass_error(&self, coefficients: &HydroCoefficients) -> Result<f64> {
+        let mut difference: f64 = 0.0;
+        let mut scale: f64 = 0.0;
+        for f in completed_frequencies(coefficients) {
+            let reconstructed = self.added_mass_at(coefficients.frequencies[f])?;
+            for ((i, j), &a) in coefficients.added_mass_at(f).indexed_iter() {
+                difference = difference.max((reconstructed.get(i, j)? - a).abs());
+                scale = scale.max(a.abs());
+            }
+        }
+        Ok(if scale > 0.0 { difference / scale } else { difference })
+    }
+
     /// Create empty impulse response data
     pub fn empty() -> Self {
         Self {
             time_vector: Vec::new(),
             responses: HashMap::new(),
-            added_mass_inf: Matrix::new(6, 6),
-            damping: Matrix::new(6, 6),
+            added_mass_inf: Matrix::new(0, 0),
+            damping: Matrix::new(0, 0),
             metadata: ImpulseResponseMetadata {
                 frequency_range: (0.0, 0.0),
                 num_frequencies: 0,
@@ -1040,9 +1626,128 @@ impl ImpulseResponseData {
     }
 }
 
+impl WaveExcitation {
+    /// Excitation of the completed frequencies of a sweep
+    pub fn from_coefficients(coefficients: &HydroCoefficients) -> Self {
+        let solved = completed_frequencies(coefficients);
+        let (nd, n) = (coefficients.directions.len(), coefficients.n_dof());
+        let mut values = Array3::from_elem((solved.len(), nd, n), Complex64::new(0.0, 0.0));
+        let mut froude_krylov = values.clone();
+        for (k, &f) in solved.iter().enumerate() {
+            values
+                .index_axis_mut(ndarray::Axis(0), k)
+                .assign(&coefficients.excitation.index_axis(ndarray::Axis(0), f));
+            froude_krylov
+                .index_axis_mut(ndarray::Axis(0), k)
+                .assign(&coefficients.froude_krylov.index_axis(ndarray::Axis(0), f));
+        }
+        Self {
+            frequencies: solved.iter().map(|&f| coefficients.frequencies[f]).collect(),
+            directions: coefficients.directions.clone(),
+            values,
+            froude_krylov,
+        }
+    }
+
+    /// Excitation at `frequency`, interpolated linearly, for the nearest direction
+    ///
+    /// `None` outside the frequency range or without directions.
+    pub fn at(&self, frequency: f64, direction: f64) -> Option<Vec<Complex64>> {
+        self.interpolate(&self.values, frequency, direction)
+    }
+
+    /// Diffraction part of the excitation, without the Froude–Krylov force
+    pub fn diffraction_at(&self, frequency: f64, direction: f64) -> Option<Vec<Complex64>> {
+        let total = self.at(frequency, direction)?;
+        let froude_krylov = self.interpolate(&self.froude_krylov, frequency, direction)?;
+        Some(total.iter().zip(froude_krylov).map(|(x, f)| x - f).collect())
+    }
+
+    fn interpolate(&self, values: &Array3<Complex64>, frequency: f64, direction: f64) -> Option<Vec<Complex64>> {
+        let angle = |d: f64| (d - direction).sin().atan2((d - direction).cos()).abs();
+        let d = (0..self.directions.len()).min_by(|&a, &b| angle(self.directions[a]).total_cmp(&angle(self.directions[b])))?;
+        let upper = self.frequencies.iter().position(|&f| f >= frequency)?;
+        let row = |f: usize| values.slice(ndarray::s![f, d, ..]).to_vec();
+        if upper == 0 {
+            return (self.frequencies[0] == frequency).then(|| row(0));
+        }
+        let (lo, hi) = (upper - 1, upper);
+        let t = (frequency - self.frequencies[lo]) / (self.frequencies[hi] - self.frequencies[lo]);
+        Some(row(lo).iter().zip(row(hi)).map(|(a, b)| a * (1.0 - t) + b * t).collect())
+    }
+}
+
+/// Indices of completed sweep frequencies in ascending order
+fn completed_frequencies(coefficients: &HydroCoefficients) -> Vec<usize> {
+    let mut solved: Vec<usize> = (0..coefficients.frequencies.len()).filter(|&f| coefficients.completed[f]).collect();
+    solved.sort_by(|&a, &b| coefficients.frequencies[a].total_cmp(&coefficients.frequencies[b]));
+    solved
+}
+
+/// ∫ y(x) e^{iwx} dx for y linear between samples, integrated exactly per segment
+fn linear_fourier_integral(x: &[f64], y: &[f64], w: f64) -> Complex64 {
+    let phase = |x: f64| Complex64::new(0.0, w * x).exp();
+    x.windows(2)
+        .zip(y.windows(2))
+        .map(|(x, y)| {
+            let h = x[1] - x[0];
+            if (w * h).abs() < 1e-4 {
+                return 0.5 * h * (y[0] * phase(x[0]) + y[1] * phase(x[1]));
+            }
+            let i_w = Complex64::new(0.0, w);
+            let slope = (y[1] - y[0]) / h;
+            (y[1] * phase(x[1]) - y[0] * phase(x[0])) / i_w + slope * (phase(x[1]) - phase(x[0])) / (w * w)
+        })
+        .sum()
+}
+
+/// In-place radix-2 FFT of a power-of-two length; the inverse uses e^{+2πijk/n} and is not normalized
+fn fft(values: &mut [Complex64], inverse: bool) {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let root = Complex64::from_polar(1.0, sign * 2.0 * std::f64::consts::PI / length as f64);
+        for start in (0..n).step_by(length) {
+            let mut twiddle = Complex64::new(1.0, 0.0);
+            for k in 0..length / 2 {
+                let (a, b) = (values[start + k], values[start + k + length / 2] * twiddle);
+                values[start + k] = a + b;
+                values[start + k + length / 2] = a - b;
+                twiddle *= root;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+/// Linear interpolation of samples (x, y) at `t`, zero beyond the last sample
+fn interpolate_linear(x: &[f64], y: &[f64], t: f64) -> f64 {
+    match x.iter().position(|&xi| xi >= t) {
+        Some(0) => y[0],
+        Some(k) => y[k - 1] + (y[k] - y[k - 1]) * (t - x[k - 1]) / (x[k] - x[k - 1]),
+        None => 0.0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{BEMSolver, MotionSolver, SolverEngine};
+    use crate::fixtures::*;
 
     #[test]
     fn test_time_domain_solver_creation() {
@@ -1052,18 +1757,192 @@ mod tests {
         assert_eq!(solver.time_params.num_steps, 1000);
     }
 
+    /// One-mode coefficients of the retardation function K(t) = c(1 - at)e^{-at}:
+    /// B(ω) = 2caω²/(a² + ω²)², A(ω) = A∞ + c(a² - ω²)/(a² + ω²)²
+    fn exponential_kernel_coefficients() -> HydroCoefficients {
+        let (c, a, added_mass_inf) = (5.0, 1.0, 5.0);
+        let frequencies: Vec<f64> = (1..=1600).map(|k| 0.05 * k as f64).collect();
+        let mut coefficients = HydroCoefficients::new(frequencies.clone(), vec![0.0], 1);
+        for (f, &w) in frequencies.iter().enumerate() {
+            let denominator = (a * a + w * w).powi(2);
+            let added_mass = added_mass_inf + c * (a * a - w * w) / denominator;
+            let damping = 2.0 * c * a * w * w / denominator;
+            coefficients.set_frequency(f, &[added_mass], &[damping], &[Complex64::new(50.0, 0.0)], true);
+        }
+        coefficients
+    }
+
     #[test]
     fn test_impulse_response_calculation() {
-        let config = TimeDomainConfig::default();
-        let solver = TimeDomainSolver::new(config);
-        let frequencies = vec![0.5, 1.0, 1.5, 2.0];
+        let coefficients = exponential_kernel_coefficients();
+        let mut solver = TimeDomainSolver::new(TimeDomainConfig::default());
+        solver.time_params.dt = 0.05;
+        
+        let impulse_data = solver.calculate_impulse_responses(&coefficients, Matrix::from_vec(1, 1, vec![5.0]).unwrap()).unwrap();
+        let kernel = &impulse_data.responses[&(0, 0)];
+        for (&t, &k) in impulse_data.time_vector.iter().zip(kernel).step_by(10) {
+            let exact = 5.0 * (1.0 - t) * (-t).exp();
+            assert!((k - exact).abs() < if t == 0.0 { 0.2 } else { 0.05 }, "K({}) = {} != {}", t, k, exact);
+        }
+        assert!(impulse_data.metadata.accuracy < 0.02, "Ogilvie error {}", impulse_data.metadata.accuracy);
+        
+        // A wrong A∞ shows up in the Ogilvie check
+        let shifted = impulse_data.added_mass_inf.data[0] + 2.0;
+        let mut wrong = impulse_data.clone();
+        wrong.added_mass_inf = Matrix::from_vec(1, 1, vec![shifted]).unwrap();
+        assert!(wrong.added_mass_error(&coefficients).unwrap() > 0.15);
+        assert!(solver.calculate_impulse_responses(&coefficients, Matrix::new(2, 2)).is_err());
+    }
+
+    /// Free decay of x(0) = 0.1 for a 1 Hz oscillator with mass 2 and damping ratio ζ, 50 steps per period
+    fn free_decay(scheme: IntegrationScheme, zeta: f64, periods: usize) -> TimeDomainResults {
+        let omega = 2.0 * std::f64::consts::PI;
+        let one = |v: f64| Matrix::from_vec(1, 1, vec![v]).unwrap();
+        let time_params = TimeParameters {
+            dt: 0.02,
+            total_time: periods as f64,
+            num_steps: 50 * periods + 1,
+            integration_scheme: scheme,
+            ..Default::default()
+        };
+        let mut solver = TimeDomainSolver::new(TimeDomainConfig::default());
+        solver.time_params = time_params;
+        let problem = TimeDomainProblem {
+            mesh: Mesh::new(
+                vec![Point3::new(0.0, 0.0, -1.0), Point3::new(1.0, 0.0, -1.0), Point3::new(0.0, 1.0, -1.0)],
+                vec![[0, 1, 2]],
+            )
+            .unwrap(),
+            initial_conditions: InitialConditions { positions: vec![0.1], velocities: vec![0.0], accelerations: vec![0.0] },
+            external_forces: ExternalForces { time_forces: Vec::new(), constant_forces: vec![0.0], control_forces: None, force_models: Vec::new() },
+            wave_environment: WaveConditions::default(),
+            body_properties: BodyProperties {
+                mass: one(2.0),
+                hydrostatic: one(2.0 * omega * omega),
+                linear_damping: one(2.0 * zeta * 2.0 * omega),
+                cog: Point3::origin(),
+            },
+        };
+        solver.solve_time_domain(&problem).unwrap()
+    }
+
+    /// Largest deviation of the oscillator energy from the exact free decay, relative to the initial energy
+    fn energy_drift(results: &TimeDomainResults, zeta: f64) -> f64 {
+        let omega = 2.0 * std::f64::consts::PI;
+        let damped = omega * (1.0 - zeta * zeta).sqrt();
+        let energy = |x: f64, v: f64| v * v + omega * omega * x * x;
+        let initial = energy(0.1, 0.0);
+        results.time.iter().enumerate()
+            .map(|(n, &t)| {
+                let decay = 0.1 * (-zeta * omega * t).exp();
+                let x = decay * ((damped * t).cos() + zeta * omega / damped * (damped * t).sin());
+                let v = -decay * omega * omega / damped * (damped * t).sin();
+                (energy(results.motions[&0][n], results.velocities[&0][n]) - energy(x, v)).abs() / initial
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_energy_drift_free_decay() {
+        for (scheme, tolerance) in [
+            (IntegrationScheme::RungeKutta4, 1e-5),
+            (IntegrationScheme::Adaptive { tolerance: 1e-9 }, 5e-7),
+            (IntegrationScheme::Newmark { beta: 0.25, gamma: 0.5 }, 5e-3),
+            (IntegrationScheme::Trapezoidal, 5e-3),
+        ] {
+            let results = free_decay(scheme.clone(), 0.02, 20);
+            assert_eq!(results.time.len(), 1001);
+            let drift = energy_drift(&results, 0.02);
+            assert!(drift < tolerance, "{:?}: energy drift {}", scheme, drift);
+        }
+        
+        // Average acceleration conserves the energy of an undamped oscillator
+        let undamped = free_decay(IntegrationScheme::Newmark { beta: 0.25, gamma: 0.5 }, 0.0, 100);
+        let initial = (0.2 * std::f64::consts::PI).powi(2);
+        let final_energy = |results: &TimeDomainResults| {
+            let last = results.time.len() - 1;
+            results.velocities[&0][last].powi(2) + (2.0 * std::f64::consts::PI * results.motions[&0][last]).powi(2)
+        };
+        assert!((final_energy(&undamped) / initial - 1.0).abs() < 1e-10);
+        
+        // γ > 1/2 dissipates numerically, the forward Euler energy error is first order
+        let dissipative = free_decay(IntegrationScheme::Newmark { beta: 0.3, gamma: 0.6 }, 0.0, 100);
+        assert!(final_energy(&dissipative) < 0.5 * initial);
+        assert!(energy_drift(&free_decay(IntegrationScheme::ForwardEuler, 0.02, 20), 0.02) > 1e-2);
+        
+        let adaptive = free_decay(IntegrationScheme::Adaptive { tolerance: 1e-9 }, 0.02, 20);
+        assert!(adaptive.metadata.error_estimates.local_error <= 1e-9);
+        assert!(adaptive.metadata.error_estimates.local_error > 0.0);
+    }
+
+    #[test]
+    fn test_fft_convolution_matches_direct() {
+        // Two coupled modes with 150-sample kernels, long enough to cross several blocks
+        let time_vector: Vec<f64> = (0..150).map(|k| 0.1 * k as f64).collect();
+        let mut responses = HashMap::new();
+        for (i, j, c) in [(0, 0, 3.0), (0, 1, -1.0), (1, 0, -1.0), (1, 1, 2.0)] {
+            responses.insert((i, j), time_vector.iter().map(|&t: &f64| c * (-0.4 * t).exp() * (1.3 * t).cos()).collect());
+        }
+        let data = ImpulseResponseData { time_vector, responses, ..ImpulseResponseData::empty() };
+        let mut direct = MemoryEffects::from_impulse_responses(&data, 0.1, ConvolutionMethod::Direct);
+        let mut fast = MemoryEffects::from_impulse_responses(&data, 0.1, ConvolutionMethod::FFT);
+        
+        for step in 0..400 {
+            let t = 0.1 * step as f64;
+            let velocities = [(0.7 * t).sin() + 0.3 * (2.9 * t).cos(), (1.9 * t).cos() - 0.5];
+            let expected = direct.compute_memory_forces(t, &velocities).unwrap();
+            let forces = fast.compute_memory_forces(t, &velocities).unwrap();
+            for (f, e) in forces.iter().zip(&expected) {
+                assert!((f - e).abs() < 1e-10 * (1.0 + e.abs()), "step {}: {} != {}", step, f, e);
+            }
+            direct.update_history(t, &velocities);
+            fast.update_history(t, &velocities);
+        }
         
-        let result = solver.calculate_impulse_responses(&frequencies);
-        assert!(result.is_ok());
+        let recursive = MemoryEffects::from_impulse_responses(&data, 0.1, ConvolutionMethod::Recursive);
+        assert!(recursive.compute_memory_forces(0.0, &[0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_cummins_matches_frequency_domain() {
+        let coefficients = exponential_kernel_coefficients();
+        let omega = 2.0;
+        let time_params = TimeParameters { dt: 0.01, total_time: 60.0, num_steps: 6000, ..Default::default() };
+        let mut solver = TimeDomainSolver::from_coefficients(
+            TimeDomainConfig::default(),
+            time_params,
+            &coefficients,
+            Matrix::from_vec(1, 1, vec![5.0]).unwrap(),
+        )
+        .unwrap();
+        
+        let one = |v: f64| Matrix::from_vec(1, 1, vec![v]).unwrap();
+        let problem = TimeDomainProblem {
+            mesh: Mesh::new(
+                vec![Point3::new(0.0, 0.0, -1.0), Point3::new(1.0, 0.0, -1.0), Point3::new(0.0, 1.0, -1.0)],
+                vec![[0, 1, 2]],
+            )
+            .unwrap(),
+            initial_conditions: InitialConditions { positions: vec![0.0], velocities: vec![0.0], accelerations: vec![0.0] },
+            external_forces: ExternalForces { time_forces: Vec::new(), constant_forces: vec![0.0], control_forces: None, force_models: Vec::new() },
+            wave_environment: WaveConditions {
+                wave_type: WaveType::Regular {
+                    amplitude: 1.0,
+                    frequency: omega / (2.0 * std::f64::consts::PI),
+                    phase: std::f64::consts::FRAC_PI_2,
+                },
+                ..Default::default()
+            },
+            body_properties: BodyProperties { mass: one(10.0), hydrostatic: one(100.0), linear_damping: one(20.0), cog: Point3::origin() },
+        };
+        let results = solver.solve_time_domain(&problem).unwrap();
         
-        let impulse_data = result.unwrap();
-        assert!(!impulse_data.time_vector.is_empty());
-        assert!(!impulse_data.responses.is_empty());
+        // Steady amplitude |X| / |C - ω²(M + A(ω)) - iω(B(ω) + B_lin)|
+        let impedance = Complex64::new(100.0 - omega * omega * (10.0 + 4.4), -omega * (1.6 + 20.0));
+        let expected = 50.0 / impedance.norm();
+        let motion = &results.motions[&0];
+        let simulated = motion[motion.len() - 700..].iter().fold(0.0, |m: f64, x| m.max(x.abs()));
+        assert!((simulated - expected).abs() < 0.03 * expected, "{} != {}", simulated, expected);
     }
 
     #[test]
@@ -1104,4 +1983,63 @@ mod tests {
         let elevation_quarter = solver.compute_wave_elevation(std::f64::consts::PI / 4.0, &wave_conditions);
         assert!(elevation_quarter.is_ok());
     }
+
+    #[test]
+    fn test_free_surface_mesh_generation() {
+        let grid = FreeSurfaceMesh::default();
+        assert_eq!((grid.grid_points.len(), grid.panels.len()), (21 * 21, 400));
+        assert!((grid.panels.iter().map(|p| p.area).sum::<f64>() - 100.0 * 100.0).abs() < 1e-6);
+        assert!(grid.panels.iter().all(|p| p.vertices.len() == 4 && (p.normal.z - 1.0).abs() < 1e-12));
+
+        let cylinder = wavecore_meshes::PredefinedGeometry::cylinder(2.0, 3.0, 24, 4).unwrap();
+        let ring = wavecore_meshes::FreeSurfaceRing::around(&cylinder, 20.0, 1.0).unwrap();
+        let mesh = FreeSurfaceMesh::from_mesh(&ring.mesh().unwrap()).unwrap();
+        assert_eq!(mesh.panels.len(), ring.n_theta * ring.n_radial);
+        assert!((mesh.parameters.x_extent.1 - 20.0).abs() < 1e-12);
+
+        let empty = FreeSurfaceMeshParams { x_extent: (0.0, 1.0), y_extent: (0.0, 1.0), nx: 0, ny: 2 };
+        assert!(FreeSurfaceMesh::regular(empty).is_err());
+    }
+
+    #[test]
+    fn test_cummins_from_sweep() {
+        let mut body = tetrahedron_body("a", 0.0);
+        body.hydrostatic_properties.hydrostatic_stiffness[2][2] = 1025.0 * 9.81 * 2.0;
+        let solver = BEMSolver::new(SolverEngine::Standard);
+        let frequencies: Vec<f64> = (1..=15).map(|k| 0.2 * k as f64).collect();
+        let sweep = solver.solve_sweep_bodies(&frequencies, &[0.0], vec![body.clone()]).unwrap();
+        let added_mass_inf = solver.solve_added_mass_inf_freq(vec![body.clone()]).unwrap();
+
+        let config = TimeDomainConfig { retardation_time: 10.0, ..Default::default() };
+        let time_params = TimeParameters { dt: 0.05, total_time: 10.0, num_steps: 200, ..Default::default() };
+        let mut simulation = TimeDomainSolver::from_coefficients(config, time_params, &sweep, added_mass_inf.clone()).unwrap();
+        assert_eq!(simulation.impulse_responses.responses.len(), 36);
+        assert!(simulation.impulse_responses.metadata.accuracy.is_finite());
+        assert_eq!(simulation.impulse_responses.added_mass_inf.data, added_mass_inf.data);
+
+        let motion = MotionSolver::from_bodies(&[body.clone()]).unwrap();
+        // Mass and stiffness at the scale of the computed coefficients keep the
+        // explicit steps stable against the retardation kernels in every mode
+        let identity = ndarray::Array2::<f64>::eye(6);
+        let matrix = |a: ndarray::Array2<f64>| wavecore_matrices::Matrix::from_vec(6, 6, a.iter().copied().collect()).unwrap();
+        let problem = TimeDomainProblem {
+            mesh: body.mesh.clone().unwrap(),
+            initial_conditions: InitialConditions { positions: vec![0.0; 6], velocities: vec![0.0; 6], accelerations: vec![0.0; 6] },
+            external_forces: ExternalForces { time_forces: Vec::new(), constant_forces: vec![0.0; 6], control_forces: None, force_models: Vec::new() },
+            wave_environment: WaveConditions {
+                wave_type: WaveType::Regular { amplitude: 1.0, frequency: 1.0 / (2.0 * std::f64::consts::PI), phase: 0.0 },
+                ..Default::default()
+            },
+            body_properties: BodyProperties {
+                mass: matrix(&motion.mass + &(1.0e10 * &identity)),
+                hydrostatic: matrix(&motion.stiffness + &(4.0e10 * &identity)),
+                linear_damping: wavecore_matrices::Matrix::new(6, 6),
+                cog: nalgebra::Point3::new(0.0, 0.0, -1.0),
+            },
+        };
+        let results = simulation.solve_time_domain(&problem).unwrap();
+        assert_eq!(results.time.len(), 200);
+        assert!(results.motions.values().flatten().all(|x| x.is_finite()));
+        assert!(results.motions[&2].iter().any(|x| x.abs() > 0.0));
+    }
 }
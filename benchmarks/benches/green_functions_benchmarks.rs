@@ -159,5 +159,46 @@ fn green_function_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, green_function_benchmark);
+/// Throughput of every method on the shared comparison grid, followed by an
+/// accuracy report against the high-precision reference
+fn method_comparison_benchmark(c: &mut Criterion) {
+    let grid = ComparisonGrid::default();
+    let mut group = c.benchmark_group("method_comparison");
+    
+    for method in ALL_METHODS {
+        let green_fns: Vec<GreenFunction> = grid
+            .wave_numbers
+            .iter()
+            .filter_map(|&k| GreenFunction::new(grid.params(method, k)).ok())
+            .collect();
+        
+        group.bench_function(format!("{:?}", method), |b| {
+            b.iter(|| {
+                for green_fn in &green_fns {
+                    for &r in &grid.r {
+                        for &z in &grid.z {
+                            black_box(green_fn.evaluate(black_box(r), black_box(z)).ok());
+                        }
+                    }
+                }
+            });
+        });
+    }
+    group.finish();
+    
+    match compare_methods(&grid, &ALL_METHODS) {
+        Ok(report) => {
+            let table = report.to_markdown();
+            println!("\n{}", table);
+            if let Some(method) = report.recommend(1e-3) {
+                println!("Recommended for 1e-3 relative accuracy: {:?}", method);
+            }
+            let _ = std::fs::create_dir_all("target/criterion");
+            let _ = std::fs::write("target/criterion/green_function_comparison.md", table);
+        }
+        Err(e) => eprintln!("Method comparison failed: {}", e),
+    }
+}
+
+criterion_group!(benches, green_function_benchmark, method_comparison_benchmark);
 criterion_main!(benches); 
\ No newline at end of file
@@ -0,0 +1,248 @@
+//! Arrays of floating bodies
+//!
+//! A [`BodyArray`] holds the bodies of a wave energy farm, a side-by-side
+//! offloading pair or any other multi-body layout. Each body keeps its mesh
+//! and mass properties in its own frame, with its pose giving its place in
+//! the array frame, and the array origin places the whole layout globally.
+//! Modes are numbered body after body, six rigid-body modes followed by the
+//! body's generalized modes, which is the order of the multi-body BEM
+//! solves and of the motion solver.
+
+use super::*;
+use nalgebra::Rotation3;
+use wavecore_meshes::Mesh;
+
+/// Floating bodies placed relative to a common frame
+#[derive(Debug, Clone, Default)]
+pub struct BodyArray {
+    /// Placement of the array frame in the global frame
+    pub origin: BodyPose,
+    bodies: Vec<FloatingBody>,
+}
+
+impl BodyArray {
+    /// Empty array with its frame placed at `origin`
+    pub fn new(origin: BodyPose) -> Self {
+        Self { origin, bodies: Vec::new() }
+    }
+
+    /// Add a body at `layout` in the array frame, returning its index
+    pub fn add_body(&mut self, mut body: FloatingBody, layout: BodyPose) -> Result<usize> {
+        if self.bodies.iter().any(|b| b.name == body.name) {
+            return Err(BodyError::InvalidData {
+                message: format!("Array already holds a body named '{}'", body.name),
+            });
+        }
+        body.pose = layout;
+        self.bodies.push(body);
+        Ok(self.bodies.len() - 1)
+    }
+
+    /// Number of bodies
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Whether the array holds no bodies
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// Bodies in mode order, their poses in the array frame
+    pub fn bodies(&self) -> &[FloatingBody] {
+        &self.bodies
+    }
+
+    /// Body at `index`
+    pub fn body(&self, index: usize) -> Result<&FloatingBody> {
+        self.bodies.get(index).ok_or_else(|| BodyError::BodyNotFound {
+            name: format!("#{}", index),
+        })
+    }
+
+    /// Mutable body at `index`
+    pub fn body_mut(&mut self, index: usize) -> Result<&mut FloatingBody> {
+        self.bodies.get_mut(index).ok_or_else(|| BodyError::BodyNotFound {
+            name: format!("#{}", index),
+        })
+    }
+
+    /// Index of the body named `name`
+    pub fn index_of(&self, name: &str) -> Result<usize> {
+        self.bodies.iter().position(|b| b.name == name).ok_or_else(|| BodyError::BodyNotFound {
+            name: name.to_string(),
+        })
+    }
+
+    /// Pose of a body in the global frame
+    pub fn global_pose(&self, index: usize) -> Result<BodyPose> {
+        Ok(compose(&self.origin, &self.body(index)?.pose))
+    }
+
+    /// Pose of body `other` in the frame of body `index`, e.g. the gap between side-by-side vessels
+    pub fn relative_pose(&self, index: usize, other: &BodyPose) -> Result<BodyPose> {
+        let (rotation, translation) = rigid(&self.body(index)?.pose);
+        let inverse = rotation.inverse();
+        Ok(compose(&pose_of(inverse, -(inverse * translation)), other))
+    }
+
+    /// Total number of modes
+    pub fn n_modes(&self) -> usize {
+        self.bodies.iter().map(|b| b.n_modes()).sum()
+    }
+
+    /// Global index of the first mode of a body
+    pub fn mode_offset(&self, index: usize) -> Result<usize> {
+        self.body(index)?;
+        Ok(self.bodies[..index].iter().map(|b| b.n_modes()).sum())
+    }
+
+    /// Global index of a rigid-body degree of freedom of a body
+    pub fn dof_index(&self, index: usize, dof: DOF) -> Result<usize> {
+        Ok(self.mode_offset(index)? + dof.index())
+    }
+
+    /// Global index of mode `mode` of a body, generalized modes following the six rigid-body modes
+    pub fn mode_index(&self, index: usize, mode: usize) -> Result<usize> {
+        let n_modes = self.body(index)?.n_modes();
+        if mode >= n_modes {
+            return Err(BodyError::InvalidDOF {
+                message: format!("Body '{}' has {} modes, not {}", self.bodies[index].name, n_modes, mode + 1),
+            });
+        }
+        Ok(self.mode_offset(index)? + mode)
+    }
+
+    /// Body and local mode of a global mode index
+    pub fn locate_mode(&self, global: usize) -> Option<(usize, usize)> {
+        let mut offset = 0;
+        for (index, body) in self.bodies.iter().enumerate() {
+            if global < offset + body.n_modes() {
+                return Some((index, global - offset));
+            }
+            offset += body.n_modes();
+        }
+        None
+    }
+
+    /// Compute and store the hydrostatics of every body at its global pose
+    pub fn compute_hydrostatics(&mut self, rho: f64, gravity: f64) -> Result<()> {
+        for index in 0..self.bodies.len() {
+            let pose = self.global_pose(index)?;
+            let body = &self.bodies[index];
+            let properties = HydrostaticProperties::from_mesh(body.mesh()?, &pose, &body.mass_properties, rho, gravity)?;
+            self.bodies[index].hydrostatic_properties = properties;
+        }
+        Ok(())
+    }
+
+    /// Copies of the bodies with meshes, centres of gravity and inertia in the global frame
+    ///
+    /// These are the bodies to hand to the multi-body BEM solves and motion
+    /// solver, in mode order. Their poses are reset to the identity.
+    pub fn placed_bodies(&self) -> Result<Vec<FloatingBody>> {
+        (0..self.bodies.len())
+            .map(|index| {
+                let (rotation, translation) = rigid(&self.global_pose(index)?);
+                let mut body = self.bodies[index].clone();
+                if let Some(mesh) = &body.mesh {
+                    let vertices = mesh.vertices.iter().map(|p| rotation * p + translation).collect();
+                    body.mesh = Some(
+                        Mesh::with_quads(vertices, mesh.faces.clone(), mesh.quads.clone())
+                            .map_err(|e| BodyError::TransformationError { message: e.to_string() })?,
+                    );
+                }
+                let cog = rotation * Point::from(body.mass_properties.cog_vector()) + translation;
+                let inertia = rotation.matrix() * body.mass_properties.inertia_matrix() * rotation.matrix().transpose();
+                body.mass_properties.center_of_gravity = [cog.x, cog.y, cog.z];
+                body.mass_properties.inertia_matrix = [0, 1, 2].map(|i| [0, 1, 2].map(|j| inertia[(i, j)]));
+                body.pose = BodyPose::default();
+                Ok(body)
+            })
+            .collect()
+    }
+}
+
+/// Rotation and translation of a pose, rotations applied roll, then pitch, then yaw
+fn rigid(pose: &BodyPose) -> (Rotation3<f64>, Vector) {
+    let [roll, pitch, yaw] = pose.orientation;
+    (Rotation3::from_euler_angles(roll, pitch, yaw), pose.position_vector())
+}
+
+fn pose_of(rotation: Rotation3<f64>, translation: Vector) -> BodyPose {
+    let (roll, pitch, yaw) = rotation.euler_angles();
+    BodyPose::new([translation.x, translation.y, translation.z], [roll, pitch, yaw])
+}
+
+/// Pose `inner`, given in the frame placed by `outer`, in the frame `outer` is given in
+fn compose(outer: &BodyPose, inner: &BodyPose) -> BodyPose {
+    let ((r_outer, t_outer), (r_inner, t_inner)) = (rigid(outer), rigid(inner));
+    pose_of(r_outer * r_inner, r_outer * t_inner + t_outer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+    use wavecore_meshes::PredefinedGeometry;
+
+    fn barge(name: &str) -> FloatingBody {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let mass = MassProperties { mass: 8200.0, center_of_gravity: [1.0, 0.0, -0.5], inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 3.0]] };
+        FloatingBody::with_mesh(name.to_string(), mass, hull).unwrap()
+    }
+
+    #[test]
+    fn test_side_by_side_layout_and_mode_numbering() {
+        // Array frame 100 m along x and turned a quarter in yaw
+        let mut array = BodyArray::new(BodyPose::new([100.0, 0.0, 0.0], [0.0, 0.0, FRAC_PI_2]));
+        array.add_body(barge("port"), BodyPose::new([0.0, 3.0, 0.0], [0.0, 0.0, 0.0])).unwrap();
+        let mut flexible = barge("starboard");
+        let n_panels = flexible.mesh().unwrap().n_panels();
+        flexible.add_generalized_mode(GeneralizedMode::new("bending", vec![0.0; n_panels])).unwrap();
+        array.add_body(flexible, BodyPose::new([0.0, -3.0, 0.0], [0.0, 0.0, 0.0])).unwrap();
+        array.add_body(barge("aft"), BodyPose::new([-8.0, 0.0, 0.0], [0.0, 0.0, FRAC_PI_2])).unwrap();
+        assert!(array.add_body(barge("aft"), BodyPose::default()).is_err());
+
+        assert_eq!(array.n_modes(), 19);
+        assert_eq!(array.mode_offset(2).unwrap(), 13);
+        assert_eq!(array.dof_index(1, DOF::Heave).unwrap(), 8);
+        assert_eq!(array.mode_index(1, 6).unwrap(), 12);
+        assert!(array.mode_index(0, 6).is_err());
+        assert_eq!(array.locate_mode(12), Some((1, 6)));
+        assert_eq!(array.locate_mode(19), None);
+        assert_eq!(array.index_of("aft").unwrap(), 2);
+        assert!(matches!(array.index_of("bow"), Err(BodyError::BodyNotFound { .. })));
+
+        let close = |a: [f64; 3], b: [f64; 3]| (0..3).all(|k| (a[k] - b[k]).abs() < 1e-12);
+        let port = array.global_pose(0).unwrap();
+        assert!(close(port.position, [97.0, 0.0, 0.0]) && close(port.orientation, [0.0, 0.0, FRAC_PI_2]));
+        let aft = array.global_pose(2).unwrap();
+        assert!(close(aft.position, [100.0, -8.0, 0.0]) && (aft.orientation[2].abs() - 2.0 * FRAC_PI_2).abs() < 1e-12);
+        let gap = array.relative_pose(0, &array.body(1).unwrap().pose).unwrap();
+        assert!(close(gap.position, [0.0, -6.0, 0.0]));
+
+        // Placed bodies carry global meshes and centres of gravity
+        let placed = array.placed_bodies().unwrap();
+        assert_eq!(placed.iter().map(|b| b.n_modes()).sum::<usize>(), array.n_modes());
+        assert!(close(placed[0].mass_properties.center_of_gravity, [97.0, 1.0, -0.5]));
+        assert!((placed[0].mass_properties.inertia_matrix[0][0] - 2.0).abs() < 1e-12);
+        let xs = placed[0].mesh().unwrap().vertices.iter().map(|p| p.x);
+        let (min, max) = xs.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), x| (a.min(x), b.max(x)));
+        assert!((min - 96.0).abs() < 1e-12 && (max - 98.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_array_hydrostatics_at_global_poses() {
+        let mut array = BodyArray::new(BodyPose::new([0.0, 0.0, 0.25], [0.0, 0.0, 0.0]));
+        array.add_body(barge("a"), BodyPose::new([0.0, 10.0, 0.0], [0.0, 0.0, 0.0])).unwrap();
+        array.add_body(barge("b"), BodyPose::new([0.0, -10.0, 0.0], [0.0, 0.0, FRAC_PI_2])).unwrap();
+        array.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).unwrap();
+
+        let (a, b) = (&array.bodies()[0].hydrostatic_properties, &array.bodies()[1].hydrostatic_properties);
+        assert!((a.displaced_volume - 6.0).abs() < 1e-12 && (b.displaced_volume - 6.0).abs() < 1e-12);
+        assert!((a.center_of_buoyancy[1] - 10.0).abs() < 1e-12);
+        assert!((a.hydrostatic_stiffness[3][3] - b.hydrostatic_stiffness[4][4]).abs() < 1e-6);
+        assert_eq!(array.bodies()[0].pose.position, [0.0, 10.0, 0.0]);
+    }
+}
@@ -7,6 +7,12 @@ pub struct DOFManager {
     dofs: std::collections::HashMap<DOF, bool>,
 }
 
+impl Default for DOFManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DOFManager {
     /// Create a new DOF manager
     pub fn new() -> Self {
@@ -12,6 +12,8 @@ pub struct FloatingBody {
     pub pose: BodyPose,
     pub dofs: std::collections::HashMap<DOF, bool>,
     pub mesh: Option<Mesh>,
+    /// Flexible modes appended after the six rigid-body modes
+    pub generalized_modes: Vec<GeneralizedMode>,
 }
 
 impl FloatingBody {
@@ -24,6 +26,7 @@ impl FloatingBody {
             pose: BodyPose::default(),
             dofs: std::collections::HashMap::new(),
             mesh: None,
+            generalized_modes: Vec::new(),
         })
     }
     
@@ -36,6 +39,7 @@ impl FloatingBody {
             pose: BodyPose::default(),
             dofs: std::collections::HashMap::new(),
             mesh: Some(mesh),
+            generalized_modes: Vec::new(),
         })
     }
     
@@ -78,4 +82,26 @@ impl FloatingBody {
     pub fn has_mesh(&self) -> bool {
         self.mesh.is_some()
     }
+    
+    /// Add a flexible mode; its shape must cover every mesh panel
+    pub fn add_generalized_mode(&mut self, mode: GeneralizedMode) -> Result<()> {
+        let n_panels = self.mesh()?.n_panels();
+        if mode.normal_displacement.len() != n_panels {
+            return Err(BodyError::InvalidDOF {
+                message: format!(
+                    "Mode '{}' has {} values for {} panels",
+                    mode.name,
+                    mode.normal_displacement.len(),
+                    n_panels
+                ),
+            });
+        }
+        self.generalized_modes.push(mode);
+        Ok(())
+    }
+    
+    /// Number of modes: six rigid-body modes plus generalized modes
+    pub fn n_modes(&self) -> usize {
+        6 + self.generalized_modes.len()
+    }
 } 
\ No newline at end of file
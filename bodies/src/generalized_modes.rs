@@ -0,0 +1,43 @@
+//! Generalized (flexible) modes for hydroelastic analysis
+//!
+//! A generalized mode is described by its normal displacement n · h on each
+//! mesh panel. Together with the six rigid-body modes it enters the radiation
+//! problem like any other degree of freedom, giving modal added mass, damping
+//! and excitation.
+
+/// User-supplied flexible mode shape
+#[derive(Debug, Clone)]
+pub struct GeneralizedMode {
+    /// Mode name (e.g. "2-node vertical bending")
+    pub name: String,
+    /// Normal displacement n · h at each panel of the body mesh
+    pub normal_displacement: Vec<f64>,
+    /// Modal (generalized) structural mass
+    pub modal_mass: f64,
+    /// Modal (generalized) structural stiffness
+    pub modal_stiffness: f64,
+}
+
+impl GeneralizedMode {
+    /// Create a mode from per-panel normal displacements
+    pub fn new(name: impl Into<String>, normal_displacement: Vec<f64>) -> Self {
+        Self {
+            name: name.into(),
+            normal_displacement,
+            modal_mass: 0.0,
+            modal_stiffness: 0.0,
+        }
+    }
+
+    /// Set modal structural mass and stiffness
+    pub fn with_structure(mut self, modal_mass: f64, modal_stiffness: f64) -> Self {
+        self.modal_mass = modal_mass;
+        self.modal_stiffness = modal_stiffness;
+        self
+    }
+
+    /// Dry natural frequency (rad/s), if structural properties are set
+    pub fn dry_natural_frequency(&self) -> Option<f64> {
+        (self.modal_mass > 0.0 && self.modal_stiffness >= 0.0).then(|| (self.modal_stiffness / self.modal_mass).sqrt())
+    }
+}
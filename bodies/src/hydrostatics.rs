@@ -0,0 +1,176 @@
+//! Hydrostatics of a body from its mesh and pose
+//!
+//! The mesh is placed by the body pose, cut at the free surface z = 0, and
+//! the displaced volume and centre of buoyancy are integrated over the
+//! wetted part as ∫ f n_z dA with f vanishing at z = 0, as for the solid
+//! mass properties. Waterplane moments come from the waterline polygons of
+//! the cut. The restoring matrix follows the usual linear hydrostatics with
+//! rotations about the centre of gravity, the reference point of the BEM
+//! rotational modes, where the weight has no moment and only the buoyancy
+//! arm above or below it remains.
+
+use super::*;
+use nalgebra::Rotation3;
+use wavecore_meshes::Mesh;
+
+/// Density of sea water (kg/m³)
+pub const SEAWATER_DENSITY: f64 = 1025.0;
+
+/// Gravitational acceleration (m/s²)
+pub const GRAVITY: f64 = 9.81;
+
+impl HydrostaticProperties {
+    /// Hydrostatics of a body-fixed mesh placed by `pose` in water of density `rho`
+    ///
+    /// The mesh must have outward normals and be closed, or open only at
+    /// the waterline. The centre of buoyancy is in the global frame.
+    pub fn from_mesh(mesh: &Mesh, pose: &BodyPose, mass_properties: &MassProperties, rho: f64, gravity: f64) -> Result<Self> {
+        if rho <= 0.0 || gravity <= 0.0 {
+            return Err(BodyError::HydrostaticError {
+                message: format!("Density and gravity must be positive, got {} and {}", rho, gravity),
+            });
+        }
+        let rotation = Rotation3::from_euler_angles(pose.orientation[0], pose.orientation[1], pose.orientation[2]);
+        let place = |p: &Point| rotation * p + pose.position_vector();
+        let vertices = mesh.vertices.iter().map(place).collect();
+        let placed = Mesh::with_quads(vertices, mesh.faces.clone(), mesh.quads.clone())
+            .and_then(|m| m.clip_at_waterline(0.0))
+            .map_err(|e| BodyError::HydrostaticError { message: e.to_string() })?;
+        let cog = place(&Point::from(mass_properties.cog_vector()));
+
+        // Volume and its first moments; edge midpoints integrate quadratics exactly
+        let mut volume = 0.0;
+        let mut moment = Vector::zeros();
+        for face in &placed.wetted.faces {
+            let [a, b, c] = face.map(|v| placed.wetted.vertices[v]);
+            let n_z = 0.5 * (b - a).cross(&(c - a)).z;
+            for p in [a + 0.5 * (b - a), b + 0.5 * (c - b), c + 0.5 * (a - c)] {
+                let w = n_z / 3.0;
+                volume += w * p.z;
+                moment += w * Vector::new(p.x * p.z, p.y * p.z, 0.5 * p.z * p.z);
+            }
+        }
+        if volume <= 0.0 {
+            return Err(BodyError::HydrostaticError {
+                message: format!("Body displaces no water (volume {}); check its pose and normals", volume),
+            });
+        }
+        let buoyancy = moment / volume;
+
+        // Area, first and second moments of the waterplane about the centre of gravity
+        let (mut area, mut sx, mut sy, mut ixx, mut iyy, mut ixy) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        for polygon in &placed.waterplane {
+            for k in 0..polygon.len() {
+                let (x0, y0) = (polygon[k].x - cog.x, polygon[k].y - cog.y);
+                let next = polygon[(k + 1) % polygon.len()];
+                let (x1, y1) = (next.x - cog.x, next.y - cog.y);
+                let cross = x0 * y1 - x1 * y0;
+                area += cross / 2.0;
+                sx += (x0 + x1) * cross / 6.0;
+                sy += (y0 + y1) * cross / 6.0;
+                iyy += (x0 * x0 + x0 * x1 + x1 * x1) * cross / 12.0;
+                ixx += (y0 * y0 + y0 * y1 + y1 * y1) * cross / 12.0;
+                ixy += (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) * cross / 24.0;
+            }
+        }
+
+        let rho_g = rho * gravity;
+        let b = buoyancy - cog.coords;
+        let mut stiffness = [[0.0; 6]; 6];
+        stiffness[2][2] = rho_g * area;
+        stiffness[2][3] = rho_g * sy;
+        stiffness[2][4] = -rho_g * sx;
+        stiffness[3][3] = rho_g * (ixx + volume * b.z);
+        stiffness[3][4] = -rho_g * ixy;
+        stiffness[3][5] = -rho_g * volume * b.x;
+        stiffness[4][4] = rho_g * (iyy + volume * b.z);
+        stiffness[4][5] = -rho_g * volume * b.y;
+        stiffness[3][2] = stiffness[2][3];
+        stiffness[4][2] = stiffness[2][4];
+        stiffness[4][3] = stiffness[3][4];
+
+        // Transverse metacentric height with the waterplane inertia about the centre of flotation
+        let centre_line = if area > 0.0 { sy / area } else { 0.0 };
+        let inertia = ixx - area * centre_line * centre_line;
+        Ok(Self {
+            displaced_volume: volume,
+            center_of_buoyancy: [buoyancy.x, buoyancy.y, buoyancy.z],
+            waterplane_area: area,
+            metacentric_height: inertia / volume + b.z,
+            hydrostatic_stiffness: stiffness,
+        })
+    }
+}
+
+impl FloatingBody {
+    /// Compute and store the hydrostatics of the attached mesh at the current pose
+    pub fn compute_hydrostatics(&mut self, rho: f64, gravity: f64) -> Result<&HydrostaticProperties> {
+        self.hydrostatic_properties = HydrostaticProperties::from_mesh(self.mesh()?, &self.pose, &self.mass_properties, rho, gravity)?;
+        Ok(&self.hydrostatic_properties)
+    }
+
+    /// Restoring matrix of the rigid-body modes, zero until hydrostatics are computed or set
+    pub fn hydrostatic_stiffness(&self) -> [[f64; 6]; 6] {
+        self.hydrostatic_properties.hydrostatic_stiffness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wavecore_meshes::{HullSurface, PredefinedGeometry};
+
+    #[test]
+    fn test_box_barge_restoring_matrix() {
+        let (l, b, d, zg) = (4.0, 2.0, 1.0, -0.2);
+        let rho_g = SEAWATER_DENSITY * GRAVITY;
+        for surface in [HullSurface::Closed, HullSurface::Wetted] {
+            let hull = PredefinedGeometry::box_hull_with_surface(l, b, d, 4, 2, 2, surface).unwrap();
+            let mass = MassProperties { mass: SEAWATER_DENSITY * l * b * d, center_of_gravity: [0.0, 0.0, zg], ..Default::default() };
+            let mut body = FloatingBody::with_mesh("barge".to_string(), mass, hull).unwrap();
+            assert_eq!(body.hydrostatic_stiffness(), [[0.0; 6]; 6]);
+
+            let properties = body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).unwrap().clone();
+            assert!((properties.displaced_volume - l * b * d).abs() < 1e-12);
+            assert!((Vector::from(properties.center_of_buoyancy) - Vector::new(0.0, 0.0, -0.5 * d)).norm() < 1e-12);
+            assert!((properties.waterplane_area - l * b).abs() < 1e-12);
+            let arm = -0.5 * d - zg;
+            assert!((properties.metacentric_height - (b * b / (12.0 * d) + arm)).abs() < 1e-12);
+
+            let c = body.hydrostatic_stiffness();
+            let expected = [(2, 2, l * b), (3, 3, l * b.powi(3) / 12.0 + l * b * d * arm), (4, 4, b * l.powi(3) / 12.0 + l * b * d * arm)];
+            for (i, j, value) in expected {
+                assert!((c[i][j] - rho_g * value).abs() < 1e-9 * rho_g, "C{}{}", i + 1, j + 1);
+            }
+            let diagonal: f64 = expected.iter().map(|&(i, j, _)| c[i][j].abs()).sum();
+            assert!(c.iter().flatten().map(|v| v.abs()).sum::<f64>() - diagonal < 1e-9 * rho_g);
+        }
+    }
+
+    #[test]
+    fn test_pose_moves_the_waterline() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let mass = MassProperties { mass: 1000.0, center_of_gravity: [0.0, 0.0, -0.5], ..Default::default() };
+        let mut body = FloatingBody::with_mesh("barge".to_string(), mass, hull).unwrap();
+        let level = body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).unwrap().clone();
+
+        // Lifted and moved off the origin: less volume, the same stiffness about the centre of gravity
+        body.pose = BodyPose::new([10.0, -3.0, 0.25], [0.0, 0.0, 0.0]);
+        let lifted = body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).unwrap().clone();
+        assert!((lifted.displaced_volume - 6.0).abs() < 1e-12);
+        assert!((lifted.center_of_buoyancy[0] - 10.0).abs() < 1e-12);
+        assert!((lifted.hydrostatic_stiffness[2][2] - level.hydrostatic_stiffness[2][2]).abs() < 1e-6);
+        assert!(lifted.hydrostatic_stiffness[2][3].abs() < 1e-6 && lifted.hydrostatic_stiffness[2][4].abs() < 1e-6);
+
+        // A quarter turn in yaw swaps roll and pitch
+        body.pose = BodyPose::new([0.0, 0.0, 0.0], [0.0, 0.0, std::f64::consts::FRAC_PI_2]);
+        let turned = body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).unwrap();
+        assert!((turned.hydrostatic_stiffness[3][3] - level.hydrostatic_stiffness[4][4]).abs() < 1e-6);
+        assert!((turned.hydrostatic_stiffness[4][4] - level.hydrostatic_stiffness[3][3]).abs() < 1e-6);
+
+        body.pose = BodyPose::new([0.0, 0.0, 5.0], [0.0, 0.0, 0.0]);
+        assert!(body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).is_err());
+        body.remove_mesh();
+        assert!(body.compute_hydrostatics(SEAWATER_DENSITY, GRAVITY).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Mass properties estimated from the hull mesh
+//!
+//! Without a weight breakdown, mass, centre of gravity and inertia can be
+//! approximated by spreading the mass uniformly, either through the volume
+//! the mesh encloses or over its surface as a shell of constant thickness.
+//! Volume integrals are turned into surface integrals of the form
+//! ∫ f n_z dA with f vanishing at z = 0, so a wetted mesh left open at the
+//! waterplane gives the submerged solid as if it were closed by a lid.
+
+use super::*;
+use wavecore_meshes::Mesh;
+
+/// How mass is spread over the hull
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MassDistribution {
+    /// Solid of uniform density (kg/m³) filling the mesh
+    Solid { density: f64 },
+    /// Shell of uniform thickness (m) and density (kg/m³) over the mesh surface
+    Shell { thickness: f64, density: f64 },
+}
+
+/// Degree-3 rule on a triangle: barycentric coordinates and weights summing to 1
+const RULE: [([f64; 3], f64); 4] = [
+    ([1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0], -27.0 / 48.0),
+    ([0.6, 0.2, 0.2], 25.0 / 48.0),
+    ([0.2, 0.6, 0.2], 25.0 / 48.0),
+    ([0.2, 0.2, 0.6], 25.0 / 48.0),
+];
+
+impl MassProperties {
+    /// Mass, centre of gravity and inertia about it of a uniformly distributed body
+    ///
+    /// A solid needs a closed mesh, or a wetted one open only at z = 0,
+    /// with normals pointing out of the body. Quads are split into triangles.
+    pub fn from_mesh(mesh: &Mesh, distribution: MassDistribution) -> Result<Self> {
+        let (density, solid) = match distribution {
+            MassDistribution::Solid { density } => (density, true),
+            MassDistribution::Shell { thickness, density } => {
+                if thickness <= 0.0 {
+                    return Err(BodyError::InvalidMassProperties {
+                        message: format!("Shell thickness must be positive, got {}", thickness),
+                    });
+                }
+                (density * thickness, false)
+            }
+        };
+        if density <= 0.0 {
+            return Err(BodyError::InvalidMassProperties {
+                message: format!("Density must be positive, got {}", density),
+            });
+        }
+        let mesh = mesh.triangulated().map_err(|e| BodyError::InvalidData { message: e.to_string() })?;
+
+        // Zeroth, first and second moments of the volume or surface
+        let mut measure = 0.0;
+        let mut first = Vector::zeros();
+        let mut second = Matrix3::zeros();
+        for face in &mesh.faces {
+            let [a, b, c] = face.map(|v| mesh.vertices[v]);
+            let vector_area = 0.5 * (b - a).cross(&(c - a));
+            let weight = if solid { vector_area.z } else { vector_area.norm() };
+            for (l, w) in RULE {
+                let p = a.coords * l[0] + b.coords * l[1] + c.coords * l[2];
+                let w = w * weight;
+                if solid {
+                    // ∂/∂z of each surface integrand is the volume integrand
+                    let z = p.z;
+                    measure += w * z;
+                    first += w * Vector::new(p.x * z, p.y * z, 0.5 * z * z);
+                    second += w * Matrix3::new(
+                        p.x * p.x * z, p.x * p.y * z, 0.5 * p.x * z * z,
+                        p.x * p.y * z, p.y * p.y * z, 0.5 * p.y * z * z,
+                        0.5 * p.x * z * z, 0.5 * p.y * z * z, z * z * z / 3.0,
+                    );
+                } else {
+                    measure += w;
+                    first += w * p;
+                    second += w * p * p.transpose();
+                }
+            }
+        }
+        if measure <= 0.0 {
+            return Err(BodyError::InvalidMassProperties {
+                message: format!("Mesh encloses no mass (measure {}); check that it is closed and outward", measure),
+            });
+        }
+
+        let mass = density * measure;
+        let cog = first / measure;
+        // I = ∫ρ(r·r 1 - r rᵀ), shifted to the centre of gravity
+        let about_origin = density * (Matrix3::identity() * second.trace() - second);
+        let shift = mass * (Matrix3::identity() * cog.norm_squared() - cog * cog.transpose());
+        let inertia = about_origin - shift;
+        let inertia = 0.5 * (inertia + inertia.transpose());
+        Ok(Self {
+            mass,
+            center_of_gravity: [cog.x, cog.y, cog.z],
+            inertia_matrix: [0, 1, 2].map(|i| [0, 1, 2].map(|j| inertia[(i, j)])),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wavecore_meshes::{HullSurface, PredefinedGeometry};
+
+    #[test]
+    fn test_solid_box_closed_and_wetted() {
+        let (l, b, d, rho) = (4.0, 2.0, 1.0, 1025.0);
+        let mass = rho * l * b * d;
+        let expected = [mass * (b * b + d * d) / 12.0, mass * (l * l + d * d) / 12.0, mass * (l * l + b * b) / 12.0];
+        for surface in [HullSurface::Closed, HullSurface::Wetted] {
+            let hull = PredefinedGeometry::box_hull_with_surface(l, b, d, 4, 2, 2, surface).unwrap();
+            let props = MassProperties::from_mesh(&hull, MassDistribution::Solid { density: rho }).unwrap();
+            assert!((props.mass - mass).abs() < 1e-9 * mass);
+            let cog = props.cog_vector();
+            assert!((cog - Vector::new(0.0, 0.0, -0.5 * d)).norm() < 1e-12);
+            for i in 0..3 {
+                for j in 0..3 {
+                    let value = if i == j { expected[i] } else { 0.0 };
+                    assert!((props.inertia_matrix[i][j] - value).abs() < 1e-9 * mass);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shell_box_and_invalid_inputs() {
+        let (l, b, d) = (4.0, 2.0, 1.0);
+        let hull = PredefinedGeometry::box_hull(l, b, d, 4, 2, 2).unwrap();
+        let (t, rho) = (0.01, 7850.0);
+        let props = MassProperties::from_mesh(&hull, MassDistribution::Shell { thickness: t, density: rho }).unwrap();
+        let m = rho * t;
+        assert!((props.mass - m * 2.0 * (l * b + l * d + b * d)).abs() < 1e-9);
+
+        // Yaw inertia of the deck and bottom, sides and ends as thin plates
+        let plates = 2.0 * l * b * (l * l + b * b) / 12.0
+            + 2.0 * l * d * (l * l / 12.0 + b * b / 4.0)
+            + 2.0 * b * d * (b * b / 12.0 + l * l / 4.0);
+        assert!((props.inertia_matrix[2][2] - m * plates).abs() < 1e-9 * m * plates);
+        assert!((props.center_of_gravity[2] + 0.5 * d).abs() < 1e-12);
+
+        let inward = wavecore_meshes::Mesh::new(hull.vertices.clone(), hull.faces.iter().map(|&[a, b, c]| [a, c, b]).collect()).unwrap();
+        assert!(MassProperties::from_mesh(&inward, MassDistribution::Solid { density: 1000.0 }).is_err());
+        assert!(MassProperties::from_mesh(&hull, MassDistribution::Shell { thickness: 0.0, density: rho }).is_err());
+        assert!(MassProperties::from_mesh(&hull, MassDistribution::Solid { density: -1.0 }).is_err());
+    }
+}
@@ -10,9 +10,12 @@
 //! 
 //! - **Floating Body**: Complete body representation
 //! - **Degrees of Freedom**: 6 DOF motion support
+//! - **Generalized Modes**: User-supplied flexible mode shapes for hydroelasticity
 //! - **Mass Properties**: Mass, inertia, center of gravity
-//! - **Hydrostatic Properties**: Buoyancy, stability
+//! - **Inertia Estimation**: Mass properties of a uniform solid or shell hull from its mesh
+//! - **Hydrostatic Properties**: Buoyancy, stability and the restoring matrix from the mesh at the body pose
 //! - **Body Transformations**: Position and orientation
+//! - **Body Arrays**: Farms and side-by-side layouts with relative placement and global mode numbering
 //! 
 //! ## Example
 //! 
@@ -39,9 +42,17 @@
 
 pub mod floating_body;
 pub mod dofs;
+pub mod generalized_modes;
+pub mod inertia;
+pub mod hydrostatics;
+pub mod array;
 
 pub use floating_body::*;
 pub use dofs::*;
+pub use generalized_modes::*;
+pub use inertia::*;
+pub use hydrostatics::{GRAVITY, SEAWATER_DENSITY};
+pub use array::BodyArray;
 
 use thiserror::Error;
 use nalgebra::{Point3, Vector3, Matrix3};
@@ -321,4 +332,22 @@ mod tests {
         assert_eq!(pose.position, [1.0, 2.0, 3.0]);
         assert_eq!(pose.orientation, [0.1, 0.2, 0.3]);
     }
-} 
\ No newline at end of file
+    
+    #[test]
+    fn test_generalized_mode_length_check() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, -1.0),
+            Point3::new(1.0, 0.0, -1.0),
+            Point3::new(0.0, 1.0, -1.0),
+            Point3::new(0.0, 0.0, 0.0),
+        ];
+        let faces = vec![[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]];
+        let mesh = wavecore_meshes::Mesh::new(vertices, faces).unwrap();
+        let mut body = FloatingBody::with_mesh("barge".to_string(), MassProperties::default(), mesh).unwrap();
+        
+        assert!(body.add_generalized_mode(GeneralizedMode::new("bending", vec![1.0; 3])).is_err());
+        body.add_generalized_mode(GeneralizedMode::new("bending", vec![1.0; 4]).with_structure(2.0, 8.0)).unwrap();
+        assert_eq!(body.n_modes(), 7);
+        assert_eq!(body.generalized_modes[0].dry_natural_frequency(), Some(2.0));
+    }
+}
@@ -127,7 +127,9 @@ fn rust_mesh_to_c_mesh(rust_mesh: &Mesh) -> CMesh {
         z: p.z,
     }).collect();
     
-    let faces: Vec<u32> = rust_mesh.faces.iter().flat_map(|face| {
+    // C meshes hold triangles only
+    let triangles = rust_mesh.triangulated().map_or_else(|_| rust_mesh.faces.clone(), |mesh| mesh.faces);
+    let faces: Vec<u32> = triangles.iter().flat_map(|face| {
         face.iter().map(|&v| v as u32)
     }).collect();
     
@@ -198,8 +200,8 @@ pub extern "C" fn wavecore_create_sphere_mesh(radius: f64, theta_res: u32, phi_r
 pub extern "C" fn wavecore_create_cylinder_mesh(radius: f64, height: f64, theta_res: u32, z_res: u32) -> *mut CMesh {
     clear_error();
     
-    // For now, return a sphere as placeholder since cylinder is not implemented
-    match PredefinedGeometry::sphere(radius, theta_res as usize, z_res as usize) {
+    // Vertical cylinder from z = -height to the waterplane
+    match PredefinedGeometry::cylinder(radius, height, theta_res as usize, z_res as usize) {
         Ok(rust_mesh) => {
             let c_mesh = rust_mesh_to_c_mesh(&rust_mesh);
             let boxed_mesh = Box::new(c_mesh);
@@ -216,9 +218,8 @@ pub extern "C" fn wavecore_create_cylinder_mesh(radius: f64, height: f64, theta_
 pub extern "C" fn wavecore_create_box_mesh(length: f64, width: f64, height: f64, x_res: u32, y_res: u32, z_res: u32) -> *mut CMesh {
     clear_error();
     
-    // For now, return a sphere as placeholder since box_mesh is not implemented
-    let radius = (length * width * height).powf(1.0/3.0);
-    match PredefinedGeometry::sphere(radius, x_res as usize, y_res as usize) {
+    // Closed barge from z = -height to the waterplane
+    match PredefinedGeometry::box_hull(length, width, height, x_res as usize, y_res as usize, z_res as usize) {
         Ok(rust_mesh) => {
             let c_mesh = rust_mesh_to_c_mesh(&rust_mesh);
             let boxed_mesh = Box::new(c_mesh);
@@ -12,6 +12,7 @@
 //! - **Matrix Operations**: GPU-accelerated BEM matrix assembly and solving
 //! - **Memory Management**: Efficient GPU memory allocation and transfer
 //! - **CPU Fallback**: Automatic fallback to CPU when GPU is unavailable
+//! - **Krylov Operators**: Uploaded matrices as `LinearOperator`s for the shared Krylov solvers
 //! - **Performance Monitoring**: Benchmarking and profiling tools
 //! 
 //! ## Example
@@ -34,6 +35,7 @@ pub mod solver;
 pub mod memory;
 pub mod kernels;
 pub mod fallback;
+pub mod operator;
 
 use thiserror::Error;
 
@@ -73,6 +75,7 @@ pub use solver::{GpuBemSolver, GpuSolverConfig, GpuSolverStatistics};
 pub use memory::{GpuMemoryPool, GpuMatrix, GpuVector};
 pub use kernels::{GpuKernels, KernelType, GpuMesh};
 pub use fallback::{CpuFallback, CpuFallbackConfig, CpuFallbackStats};
+pub use operator::GpuOperator;
 
 /// GPU acceleration capabilities
 #[derive(Debug, Clone)]
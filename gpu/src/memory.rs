@@ -1,5 +1,5 @@
 use crate::{GpuDevice, GpuError, GpuResult};
-use wavecore_matrices::Matrix;
+use wavecore_matrices::{backend, lu_solve, DeviceMatrix, LinearOperator, Matrix, MatrixError};
 use std::sync::Arc;
 use std::collections::HashMap;
 
@@ -25,6 +25,8 @@ pub struct GpuMatrix {
     rows: usize,
     cols: usize,
     data_block: GpuMemoryBlock,
+    /// Entries kept in host memory when the block has no device allocation
+    host: Option<Vec<f64>>,
 }
 
 /// GPU vector representation
@@ -57,11 +59,13 @@ impl GpuMemoryPool {
         }
 
         let block = self.allocate_block(size)?;
+        let host = (!block.on_device()).then(|| vec![0.0; rows * cols]);
 
         Ok(GpuMatrix {
             rows,
             cols,
             data_block: block,
+            host,
         })
     }
 
@@ -103,6 +107,9 @@ impl GpuMemoryPool {
                 }
             }
         }
+        if let Some(host) = gpu_matrix.host.as_mut() {
+            host.copy_from_slice(&matrix.data);
+        }
         
         Ok(gpu_matrix)
     }
@@ -147,8 +154,14 @@ impl GpuMemoryPool {
             }
         }
         
-        // Fallback for non-CUDA builds
-        Ok(Matrix::new(gpu_matrix.rows, gpu_matrix.cols))
+        // Host copy of the entries when there is no device allocation
+        match &gpu_matrix.host {
+            Some(host) => Matrix::from_vec(gpu_matrix.rows, gpu_matrix.cols, host.clone())
+                .map_err(|e| GpuError::MemoryError {
+                    message: format!("Failed to create matrix: {}", e)
+                }),
+            None => Ok(Matrix::new(gpu_matrix.rows, gpu_matrix.cols)),
+        }
     }
 
     /// Download vector from GPU
@@ -222,6 +235,16 @@ impl GpuMemoryPool {
     }
 }
 
+impl GpuMemoryBlock {
+    /// Whether the block holds device memory
+    fn on_device(&self) -> bool {
+        #[cfg(feature = "cuda")]
+        return self.ptr.is_some();
+        #[cfg(not(feature = "cuda"))]
+        false
+    }
+}
+
 impl Clone for GpuMemoryBlock {
     fn clone(&self) -> Self {
         Self {
@@ -270,6 +293,44 @@ impl GpuMatrix {
     }
 }
 
+/// Matrices in a GPU memory pool
+///
+/// Products and solves download the operands and run on the multi-threaded
+/// CPU kernels until device kernels are available, as [`GpuOperator`](crate::GpuOperator)
+/// does; results are uploaded back to the pool.
+impl DeviceMatrix for GpuMatrix {
+    type Device = GpuMemoryPool;
+
+    fn upload(pool: &mut GpuMemoryPool, matrix: &Matrix) -> wavecore_matrices::Result<Self> {
+        pool.upload_matrix(matrix).map_err(matrix_error)
+    }
+
+    fn download(&self, pool: &GpuMemoryPool) -> wavecore_matrices::Result<Matrix> {
+        pool.download_matrix(self).map_err(matrix_error)
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn matmul(&self, pool: &mut GpuMemoryPool, other: &Self) -> wavecore_matrices::Result<Self> {
+        let product = backend::multiply(&self.download(pool)?, &other.download(pool)?)?;
+        Self::upload(pool, &product)
+    }
+
+    fn matvec(&self, pool: &GpuMemoryPool, x: &[f64]) -> wavecore_matrices::Result<Vec<f64>> {
+        self.download(pool)?.apply(x)
+    }
+
+    fn solve(&self, pool: &GpuMemoryPool, b: &[f64]) -> wavecore_matrices::Result<Vec<f64>> {
+        lu_solve(&self.download(pool)?, b)
+    }
+}
+
+fn matrix_error(error: GpuError) -> MatrixError {
+    MatrixError::SolverError { message: error.to_string() }
+}
+
 impl GpuVector {
     /// Get vector length
     pub fn len(&self) -> usize {
@@ -351,4 +412,38 @@ mod tests {
         assert_eq!(total, 1024 * 1024);
         assert_eq!(blocks, 0);
     }
+
+    #[test]
+    fn test_gpu_matrix_as_device_matrix() {
+        let device = Arc::new(GpuDevice {
+            info: crate::device::DeviceInfo {
+                id: 0,
+                name: "Test".to_string(),
+                total_memory: 1024 * 1024 * 1024,
+                free_memory: 1024 * 1024 * 1024,
+                compute_capability: (7, 5),
+                max_threads_per_block: 1024,
+                max_shared_memory: 49152,
+                multiprocessor_count: 108,
+                clock_rate: 1500000,
+            },
+            #[cfg(feature = "cuda")]
+            cuda_device: None,
+        });
+        let mut pool = GpuMemoryPool::new(device, 1024 * 1024).unwrap();
+        let a = Matrix::from_vec(3, 3, vec![4.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 2.0]).unwrap();
+        let b = vec![1.0, 2.0, 3.0];
+
+        // The same generic call on host and pool storage
+        let on_host = wavecore_matrices::solve_on::<Matrix>(&mut wavecore_matrices::Host, &a, &b).unwrap();
+        let on_pool = wavecore_matrices::solve_on::<GpuMatrix>(&mut pool, &a, &b).unwrap();
+        assert!(on_host.iter().zip(&on_pool).all(|(p, q)| (p - q).abs() < 1e-12));
+
+        let stored = GpuMatrix::upload(&mut pool, &a).unwrap();
+        assert_eq!(stored.download(&pool).unwrap().data, a.data);
+        let squared = stored.matmul(&mut pool, &stored).unwrap();
+        assert_eq!(DeviceMatrix::shape(&squared), (3, 3));
+        assert_eq!(squared.download(&pool).unwrap().data, backend::multiply(&a, &a).unwrap().data);
+        assert_eq!(stored.matvec(&pool, &b).unwrap(), vec![6.0, 10.0, 8.0]);
+    }
 }
@@ -0,0 +1,208 @@
+//! Accuracy and throughput comparison of Green function methods
+//!
+//! Every `Method` is evaluated on the same (r, z, k) grid and compared with a
+//! high-precision reference (HAMS with a tight tolerance and a large
+//! integration budget). The report lists error and evaluation rate per method
+//! and recommends the fastest method within a requested accuracy.
+
+use super::*;
+use std::time::Instant;
+
+/// All available methods, in declaration order
+pub const ALL_METHODS: [Method; 4] = [
+    Method::Delhommeau,
+    Method::HAMS,
+    Method::LiangWuNoblesse,
+    Method::FinGreen3D,
+];
+
+/// Evaluation grid shared by all methods
+#[derive(Debug, Clone)]
+pub struct ComparisonGrid {
+    /// Horizontal distances (m)
+    pub r: Vec<f64>,
+    /// Vertical offsets (m, negative below the free surface)
+    pub z: Vec<f64>,
+    /// Wave numbers (rad/m)
+    pub wave_numbers: Vec<f64>,
+    /// Water depth (m)
+    pub depth: f64,
+    /// Gravitational acceleration (m/s²)
+    pub gravity: f64,
+}
+
+impl Default for ComparisonGrid {
+    fn default() -> Self {
+        Self {
+            r: vec![0.1, 0.5, 1.0, 2.0, 5.0, 10.0],
+            z: vec![-0.1, -0.5, -1.0, -2.0, -5.0],
+            wave_numbers: vec![0.1, 0.5, 1.0, 2.0],
+            depth: 20.0,
+            gravity: 9.81,
+        }
+    }
+}
+
+impl ComparisonGrid {
+    /// Number of (r, z) points per wave number
+    pub fn points_per_wave_number(&self) -> usize {
+        self.r.len() * self.z.len()
+    }
+
+    /// Wave frequency for wave number `k` from the finite-depth dispersion relation
+    pub fn frequency(&self, k: f64) -> f64 {
+        (self.gravity * k * (k * self.depth).tanh()).sqrt()
+    }
+
+    /// Green function parameters for `method` at wave number `k`
+    pub fn params(&self, method: Method, k: f64) -> GreenFunctionParams {
+        GreenFunctionParams {
+            method,
+            frequency: self.frequency(k),
+            depth: self.depth,
+            gravity: self.gravity,
+            ..Default::default()
+        }
+    }
+
+    /// Parameters of the high-precision reference at wave number `k`
+    pub fn reference_params(&self, k: f64) -> GreenFunctionParams {
+        GreenFunctionParams {
+            tolerance: 1e-12,
+            max_points: 5000,
+            ..self.params(Method::HAMS, k)
+        }
+    }
+}
+
+/// Accuracy and speed of one method
+#[derive(Debug, Clone)]
+pub struct MethodReport {
+    /// Method evaluated
+    pub method: Method,
+    /// Maximum absolute error against the reference
+    pub max_abs_error: f64,
+    /// Root-mean-square relative error against the reference
+    pub rms_rel_error: f64,
+    /// Evaluations per second
+    pub evaluations_per_second: f64,
+    /// Points where construction or evaluation failed
+    pub failures: usize,
+}
+
+impl MethodReport {
+    /// Whether every point evaluated successfully
+    pub fn is_complete(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+/// Comparison of all methods on one grid
+#[derive(Debug, Clone)]
+pub struct ComparisonReport {
+    /// Grid used
+    pub grid: ComparisonGrid,
+    /// One entry per method
+    pub methods: Vec<MethodReport>,
+}
+
+impl ComparisonReport {
+    /// Fastest complete method whose RMS relative error is within `tolerance`
+    pub fn recommend(&self, tolerance: f64) -> Option<Method> {
+        self.methods
+            .iter()
+            .filter(|m| m.is_complete() && m.rms_rel_error <= tolerance)
+            .max_by(|a, b| a.evaluations_per_second.total_cmp(&b.evaluations_per_second))
+            .map(|m| m.method)
+    }
+
+    /// Markdown table of the results
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "Green function comparison: {} r × {} z × {} k, depth {} m\n\n",
+            self.grid.r.len(),
+            self.grid.z.len(),
+            self.grid.wave_numbers.len(),
+            self.grid.depth
+        );
+        out.push_str("| Method | Max abs error | RMS rel error | Evaluations/s | Failures |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for m in &self.methods {
+            out.push_str(&format!(
+                "| {:?} | {:.3e} | {:.3e} | {:.3e} | {} |\n",
+                m.method, m.max_abs_error, m.rms_rel_error, m.evaluations_per_second, m.failures
+            ));
+        }
+        out
+    }
+}
+
+/// Compare `methods` against the high-precision reference on `grid`
+pub fn compare_methods(grid: &ComparisonGrid, methods: &[Method]) -> Result<ComparisonReport> {
+    if grid.r.is_empty() || grid.z.is_empty() || grid.wave_numbers.is_empty() {
+        return Err(GreenFunctionError::InvalidParameters {
+            message: "Comparison grid must not be empty".to_string(),
+        });
+    }
+
+    // Reference values, one block of r × z per wave number
+    let mut reference = Vec::with_capacity(grid.wave_numbers.len() * grid.points_per_wave_number());
+    for &k in &grid.wave_numbers {
+        let green_fn = GreenFunction::new(grid.reference_params(k))?;
+        for &r in &grid.r {
+            for &z in &grid.z {
+                reference.push(green_fn.evaluate(r, z)?);
+            }
+        }
+    }
+
+    let reports = methods
+        .iter()
+        .map(|&method| {
+            let mut values: Vec<Option<Complex64>> = Vec::with_capacity(reference.len());
+            let mut elapsed = 0.0;
+            for &k in &grid.wave_numbers {
+                match GreenFunction::new(grid.params(method, k)) {
+                    Ok(green_fn) => {
+                        let start = Instant::now();
+                        for &r in &grid.r {
+                            for &z in &grid.z {
+                                values.push(green_fn.evaluate(r, z).ok());
+                            }
+                        }
+                        elapsed += start.elapsed().as_secs_f64();
+                    }
+                    Err(_) => values.extend(std::iter::repeat_n(None, grid.points_per_wave_number())),
+                }
+            }
+
+            let mut max_abs_error: f64 = 0.0;
+            let mut sum_sq_rel = 0.0;
+            let mut failures = 0;
+            for (value, exact) in values.iter().zip(&reference) {
+                match value {
+                    Some(v) if v.re.is_finite() && v.im.is_finite() => {
+                        let error = (v - exact).norm();
+                        max_abs_error = max_abs_error.max(error);
+                        sum_sq_rel += (error / exact.norm().max(1e-12)).powi(2);
+                    }
+                    _ => failures += 1,
+                }
+            }
+            let succeeded = reference.len() - failures;
+
+            MethodReport {
+                method,
+                max_abs_error,
+                rms_rel_error: if succeeded > 0 { (sum_sq_rel / succeeded as f64).sqrt() } else { f64::INFINITY },
+                evaluations_per_second: if elapsed > 0.0 { succeeded as f64 / elapsed } else { 0.0 },
+                failures,
+            }
+        })
+        .collect();
+
+    Ok(ComparisonReport {
+        grid: grid.clone(),
+        methods: reports,
+    })
+}
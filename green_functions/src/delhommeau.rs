@@ -1,8 +1,7 @@
 //! Delhommeau Green function implementation
 
-use super::*;
-
 /// Delhommeau Green function implementation
+#[derive(Default)]
 pub struct DelhommeauImpl;
 
 impl DelhommeauImpl {
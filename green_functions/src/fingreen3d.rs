@@ -1,8 +1,7 @@
 //! FinGreen3D Green function implementation
 
-use super::*;
-
 /// FinGreen3D Green function implementation
+#[derive(Default)]
 pub struct FinGreen3DImpl;
 
 impl FinGreen3DImpl {
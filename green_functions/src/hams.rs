@@ -1,8 +1,7 @@
 //! HAMS Green function implementation
 
-use super::*;
-
 /// HAMS Green function implementation
+#[derive(Default)]
 pub struct HAMSImpl;
 
 impl HAMSImpl {
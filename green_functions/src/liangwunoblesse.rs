@@ -1,8 +1,7 @@
 //! LiangWuNoblesse Green function implementation
 
-use super::*;
-
 /// LiangWuNoblesse Green function implementation
+#[derive(Default)]
 pub struct LiangWuNoblesseImpl;
 
 impl LiangWuNoblesseImpl {
@@ -13,6 +13,8 @@
 //! - **LiangWuNoblesse Method**: Advanced Green function for complex geometries
 //! - **FinGreen3D Method**: Finite depth Green function
 //! - **Unified Interface**: Common trait for all Green function methods
+//! - **Rankine Split**: Frequency-independent Rankine part and frequency-dependent wave part
+//! - **Method Comparison**: Accuracy vs speed report against a high-precision reference
 //! 
 //! ## Example
 //! 
@@ -42,17 +44,20 @@ pub mod hams;
 pub mod liangwunoblesse;
 pub mod fingreen3d;
 pub mod utils;
+pub mod comparison;
 
 pub use delhommeau::*;
 pub use hams::*;
 pub use liangwunoblesse::*;
 pub use fingreen3d::*;
 pub use utils::*;
+pub use comparison::*;
 
 use thiserror::Error;
 use num_complex::Complex64;
 use nalgebra::Point3;
-use num_traits::Zero;
+use ndarray::Array2;
+use serde::{Serialize, Deserialize};
 
 /// Error types for Green function operations
 #[derive(Error, Debug)]
@@ -80,7 +85,7 @@ pub enum GreenFunctionError {
 pub type Result<T> = std::result::Result<T, GreenFunctionError>;
 
 /// Green function methods
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Method {
     /// Delhommeau method (infinite depth)
     Delhommeau,
@@ -93,13 +98,14 @@ pub enum Method {
 }
 
 /// Green function parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GreenFunctionParams {
     /// Green function method
     pub method: Method,
     /// Wave frequency (rad/s)
     pub frequency: f64,
     /// Water depth (m), use f64::INFINITY for infinite depth
+    #[serde(with = "depth_serde")]
     pub depth: f64,
     /// Gravitational acceleration (m/s²)
     pub gravity: f64,
@@ -111,6 +117,19 @@ pub struct GreenFunctionParams {
     pub max_points: usize,
 }
 
+/// Depth as an optional number, `None` for infinite depth (JSON has no infinity)
+mod depth_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(depth: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        Some(*depth).filter(|d| d.is_finite()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+    }
+}
+
 impl Default for GreenFunctionParams {
     fn default() -> Self {
         Self {
@@ -141,6 +160,18 @@ pub trait GreenFunctionTrait: Send + Sync {
     /// Evaluate Green function gradient
     fn gradient(&self, r: f64, z: f64) -> Result<(Complex64, Complex64)>;
     
+    /// Frequency-dependent wave part G - G_R, with G_R the [`rankine`] part
+    fn wave_part(&self, r: f64, z: f64) -> Result<Complex64> {
+        Ok(self.evaluate(r, z)? - rankine(r, z))
+    }
+    
+    /// Gradient of the wave part
+    fn wave_gradient(&self, r: f64, z: f64) -> Result<(Complex64, Complex64)> {
+        let (dr, dz) = self.gradient(r, z)?;
+        let (rankine_dr, rankine_dz) = rankine_gradient(r, z);
+        Ok((dr - rankine_dr, dz - rankine_dz))
+    }
+    
     /// Get method type
     fn method(&self) -> Method;
     
@@ -148,6 +179,19 @@ pub trait GreenFunctionTrait: Send + Sync {
     fn params(&self) -> &GreenFunctionParams;
 }
 
+/// Frequency-independent Rankine part -i/(4πR) shared by every method, R = √(r² + z²)
+pub fn rankine(r: f64, z: f64) -> Complex64 {
+    let distance = (r * r + z * z).sqrt();
+    Complex64::new(0.0, -0.25 / (std::f64::consts::PI * distance))
+}
+
+/// Gradient (∂/∂r, ∂/∂z) of the Rankine part
+pub fn rankine_gradient(r: f64, z: f64) -> (Complex64, Complex64) {
+    let distance = (r * r + z * z).sqrt();
+    let factor = 0.25 / (std::f64::consts::PI * distance.powi(3));
+    (Complex64::new(0.0, factor * r), Complex64::new(0.0, factor * z))
+}
+
 /// Main Green function implementation
 pub struct GreenFunction {
     params: GreenFunctionParams,
@@ -180,6 +224,43 @@ impl GreenFunction {
         self.implementation.gradient(r, z)
     }
     
+    /// Evaluate the frequency-dependent wave part G - G_R
+    pub fn wave_part(&self, r: f64, z: f64) -> Result<Complex64> {
+        self.implementation.wave_part(r, z)
+    }
+    
+    /// Evaluate the wave-part gradient
+    pub fn wave_gradient(&self, r: f64, z: f64) -> Result<(Complex64, Complex64)> {
+        self.implementation.wave_gradient(r, z)
+    }
+    
+    /// Evaluate Green function over a structured horizontal grid at depth `z`
+    ///
+    /// The source is located at the origin. Element `[i, j]` of the returned
+    /// array holds the kernel value at field point `(x[i], y[j], z)`.
+    pub fn evaluate_grid(&self, x: &[f64], y: &[f64], z: f64) -> Result<Array2<Complex64>> {
+        if x.is_empty() || y.is_empty() {
+            return Err(GreenFunctionError::InvalidParameters {
+                message: "Grid coordinates must not be empty".to_string(),
+            });
+        }
+        if !z.is_finite() || x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+            return Err(GreenFunctionError::InvalidParameters {
+                message: "Grid coordinates must be finite".to_string(),
+            });
+        }
+        
+        let mut field = Array2::<Complex64>::zeros((x.len(), y.len()));
+        for (i, &xi) in x.iter().enumerate() {
+            for (j, &yj) in y.iter().enumerate() {
+                let r = (xi * xi + yj * yj).sqrt();
+                field[[i, j]] = self.implementation.evaluate(r, z)?;
+            }
+        }
+        
+        Ok(field)
+    }
+    
     /// Get method type
     pub fn method(&self) -> Method {
         self.params.method
@@ -268,6 +349,28 @@ impl GreenFunctionTrait for DelhommeauGreenFunction {
         Ok((dr, dz))
     }
     
+    fn wave_part(&self, r: f64, z: f64) -> Result<Complex64> {
+        let k = self.params.frequency.powi(2) / self.params.gravity;
+        let distance = (r.powi(2) + z.powi(2)).sqrt();
+        
+        // -i/(4π) (e^{ikR} - 1)/R without cancellation, tending to k/(4π) at R = 0
+        let scale = Complex64::new(0.0, -0.25 / std::f64::consts::PI);
+        let g_direct = if distance < 1e-10 {
+            scale * Complex64::new(0.0, k)
+        } else {
+            let half = (0.5 * k * distance).sin();
+            scale * Complex64::new(-2.0 * half * half, (k * distance).sin()) / distance
+        };
+        
+        if self.params.depth.is_finite() {
+            let r_image = (r.powi(2) + (z + 2.0 * self.params.depth).powi(2)).sqrt();
+            if r_image > 1e-10 {
+                return Ok(g_direct + scale * (Complex64::i() * k * r_image).exp() / r_image);
+            }
+        }
+        Ok(g_direct)
+    }
+    
     fn method(&self) -> Method {
         Method::Delhommeau
     }
@@ -755,6 +858,25 @@ mod tests {
     use super::*;
     use approx::assert_relative_eq;
     
+    #[test]
+    fn test_rankine_and_wave_parts_sum_to_kernel() {
+        for method in [Method::Delhommeau, Method::HAMS] {
+            let params = GreenFunctionParams { method, frequency: 1.2, ..Default::default() };
+            let green_fn = GreenFunction::new(params).unwrap();
+            for (r, z) in [(1.0, -0.5), (0.01, 0.002), (7.0, -3.0)] {
+                let total = green_fn.evaluate(r, z).unwrap();
+                let split = rankine(r, z) + green_fn.wave_part(r, z).unwrap();
+                assert!((total - split).norm() < 1e-12 * (1.0 + total.norm()));
+                
+                let (dr, dz) = green_fn.gradient(r, z).unwrap();
+                let (wave_dr, wave_dz) = green_fn.wave_gradient(r, z).unwrap();
+                let (rankine_dr, rankine_dz) = rankine_gradient(r, z);
+                assert!((dr - wave_dr - rankine_dr).norm() < 1e-9 * (1.0 + dr.norm()));
+                assert!((dz - wave_dz - rankine_dz).norm() < 1e-9 * (1.0 + dz.norm()));
+            }
+        }
+    }
+    
     #[test]
     fn test_green_function_creation() {
         let params = GreenFunctionParams::default();
@@ -762,6 +884,26 @@ mod tests {
         assert_eq!(green_fn.method(), Method::Delhommeau);
     }
     
+    #[test]
+    fn test_evaluate_grid() {
+        let green_fn = GreenFunction::new(GreenFunctionParams::default()).unwrap();
+        let x = [0.5, 1.0, 2.0];
+        let y = [-1.0, 1.5];
+        let z = -0.5;
+        
+        let field = green_fn.evaluate_grid(&x, &y, z).unwrap();
+        assert_eq!(field.dim(), (3, 2));
+        
+        for (i, &xi) in x.iter().enumerate() {
+            for (j, &yj) in y.iter().enumerate() {
+                let expected = green_fn.evaluate((xi * xi + yj * yj).sqrt(), z).unwrap();
+                assert!((field[[i, j]] - expected).norm() < 1e-14);
+            }
+        }
+        
+        assert!(green_fn.evaluate_grid(&[], &y, z).is_err());
+    }
+    
     #[test]
     fn test_delhommeau_green_function() {
         let params = GreenFunctionParams {
@@ -1240,6 +1382,27 @@ mod tests {
             assert!(gradient_change < 10.0); // Should not change too rapidly
         }
     }
+    
+    #[test]
+    fn test_method_comparison_report() {
+        let grid = ComparisonGrid {
+            r: vec![0.5, 2.0],
+            z: vec![-0.5, -1.5],
+            wave_numbers: vec![0.5],
+            ..Default::default()
+        };
+        let report = compare_methods(&grid, &ALL_METHODS).unwrap();
+        assert_eq!(report.methods.len(), ALL_METHODS.len());
+        
+        // The reference method agrees with itself to its own tolerance
+        let hams = report.methods.iter().find(|m| m.method == Method::HAMS).unwrap();
+        assert!(hams.is_complete());
+        assert!(hams.rms_rel_error.is_finite());
+        
+        let table = report.to_markdown();
+        assert!(table.contains("Delhommeau"));
+        assert!(report.recommend(f64::INFINITY).is_some());
+    }
 }
 
 /// SIMD optimization module for Green functions
@@ -1,4 +1,4 @@
-use crate::{GreenFunctionTrait, GreenFunctionError};
+use crate::GreenFunctionError;
 use crate::Result as GreenFunctionResult;
 use nalgebra::Point3;
 use std::arch::x86_64::*;
@@ -14,6 +14,7 @@ pub struct SIMDGreenFunction {
 }
 
 /// Vectorized evaluator for multiple points
+#[allow(dead_code)]
 pub struct VectorizedEvaluator {
     /// Vector size (4 or 8 points)
     vector_size: usize,
@@ -24,6 +25,7 @@ pub struct VectorizedEvaluator {
 }
 
 /// SIMD kernels for different Green function types
+#[allow(dead_code)]
 pub struct SIMDKernels {
     /// Rankine source kernel
     rankine_kernel: RankineKernel,
@@ -91,12 +93,14 @@ pub struct CacheConfig {
 }
 
 /// Rankine source SIMD kernel
+#[allow(dead_code)]
 pub struct RankineKernel {
     /// Kernel implementation
     implementation: RankineImpl,
 }
 
 /// Free surface SIMD kernel
+#[allow(dead_code)]
 pub struct FreeSurfaceKernel {
     /// Water depth
     depth: f64,
@@ -107,6 +111,7 @@ pub struct FreeSurfaceKernel {
 }
 
 /// Delhommeau SIMD kernel
+#[allow(dead_code)]
 pub struct DelhommeauKernel {
     /// Water depth
     depth: f64,
@@ -286,15 +291,13 @@ impl SIMDGreenFunction {
 
     /// Check hardware support for instruction set
     fn check_hardware_support(instruction_set: &InstructionSet) -> bool {
-        unsafe {
-            match instruction_set {
-                InstructionSet::SSE2 => std::arch::is_x86_feature_detected!("sse2"),
-                InstructionSet::SSE4_1 => std::arch::is_x86_feature_detected!("sse4.1"),
-                InstructionSet::AVX => std::arch::is_x86_feature_detected!("avx"),
-                InstructionSet::AVX2 => std::arch::is_x86_feature_detected!("avx2"),
-                InstructionSet::AVX512F => std::arch::is_x86_feature_detected!("avx512f"),
-                InstructionSet::FMA => std::arch::is_x86_feature_detected!("fma"),
-            }
+        match instruction_set {
+            InstructionSet::SSE2 => std::arch::is_x86_feature_detected!("sse2"),
+            InstructionSet::SSE4_1 => std::arch::is_x86_feature_detected!("sse4.1"),
+            InstructionSet::AVX => std::arch::is_x86_feature_detected!("avx"),
+            InstructionSet::AVX2 => std::arch::is_x86_feature_detected!("avx2"),
+            InstructionSet::AVX512F => std::arch::is_x86_feature_detected!("avx512f"),
+            InstructionSet::FMA => std::arch::is_x86_feature_detected!("fma"),
         }
     }
 
@@ -591,9 +594,11 @@ impl CacheConfig {
             prefetch_distance: 2,
         }
     }
+}
 
-    /// Default cache configuration
-    pub fn default() -> Self {
+impl Default for CacheConfig {
+    /// Detected cache configuration
+    fn default() -> Self {
         Self::detect()
     }
 }
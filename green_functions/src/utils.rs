@@ -1,8 +1,7 @@
 //! Green function utilities
 
-use super::*;
-
 /// Green function utilities
+#[derive(Default)]
 pub struct GreenFunctionUtils;
 
 impl GreenFunctionUtils {
@@ -0,0 +1,268 @@
+//! Versioned result archives
+//!
+//! Result archives bundle named data arrays with provenance metadata. Every
+//! archive carries a `schema_version`; when the layout changes, a migration
+//! step from the previous version is registered in `MIGRATIONS` and older
+//! archives are upgraded on load by the chain of steps, so results written
+//! by earlier releases stay usable. Results saved before versioning are a
+//! bare JSON or YAML data array, as written by `FileIO::save_data`, and are
+//! read as schema v0.
+
+use super::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Schema version written by this release
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Oldest schema version that can be migrated, the unversioned data array
+pub const FIRST_SCHEMA_VERSION: u32 = 0;
+
+/// Name given to the single dataset of an unversioned (v0) archive
+pub const LEGACY_DATASET_NAME: &str = "data";
+
+/// Upgrade of a raw archive by one schema version
+type Migration = fn(serde_json::Value) -> Result<serde_json::Value>;
+
+/// Migration steps, element `i` upgrading schema `FIRST_SCHEMA_VERSION + i` to the next
+const MIGRATIONS: [Migration; (CURRENT_SCHEMA_VERSION - FIRST_SCHEMA_VERSION) as usize] = [ArchiveMigrator::migrate_v0_to_v1];
+
+/// Result archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultArchive {
+    /// Archive schema version
+    pub schema_version: u32,
+    /// Creation time (RFC 3339)
+    pub created: String,
+    /// Producing library and version
+    pub producer: String,
+    /// Free-form attributes
+    pub attributes: HashMap<String, String>,
+    /// Named datasets
+    pub datasets: BTreeMap<String, DataArray>,
+}
+
+impl Default for ResultArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultArchive {
+    /// Create an empty archive at the current schema version
+    pub fn new() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            created: timestamp::now_utc(timestamp::RFC3339).unwrap_or_default(),
+            producer: producer_string(),
+            attributes: HashMap::new(),
+            datasets: BTreeMap::new(),
+        }
+    }
+
+    /// Add or replace a dataset
+    pub fn add_dataset(&mut self, name: &str, data: DataArray) {
+        self.datasets.insert(name.to_string(), data);
+    }
+
+    /// Get a dataset by name
+    pub fn dataset(&self, name: &str) -> Option<&DataArray> {
+        self.datasets.get(name)
+    }
+
+    /// Dataset names in sorted order
+    pub fn dataset_names(&self) -> Vec<&str> {
+        self.datasets.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Save archive as JSON
+    pub fn save(&self, path: &str) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content).map_err(|e| IOError::WriteError {
+            message: format!("Failed to write archive {}: {}", path, e),
+        })
+    }
+
+    /// Load archive, migrating older schema versions in memory
+    pub fn load(path: &str) -> Result<Self> {
+        let value = read_archive_value(path)?;
+        let (migrated, _) = ArchiveMigrator::migrate(value)?;
+        Ok(serde_json::from_value(migrated)?)
+    }
+}
+
+/// Outcome of an archive migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    /// Schema version found in the archive
+    pub from_version: u32,
+    /// Schema version after migration
+    pub to_version: u32,
+    /// Whether the archive file was rewritten
+    pub rewritten: bool,
+}
+
+/// Archive schema migrator
+pub struct ArchiveMigrator;
+
+impl ArchiveMigrator {
+    /// Detect the schema version of a raw archive
+    pub fn detect_version(value: &serde_json::Value) -> Result<u32> {
+        match value.get("schema_version") {
+            Some(version) => match version.as_u64() {
+                Some(v) if v > FIRST_SCHEMA_VERSION as u64 => Ok(v as u32),
+                _ => Err(IOError::ParseError {
+                    message: format!("Invalid schema_version: {}", version),
+                }),
+            },
+            // Results saved before versioning were a bare data array
+            None if value.get("shape").is_some() && value.get("data").is_some() => Ok(FIRST_SCHEMA_VERSION),
+            None => Err(IOError::InvalidFormat {
+                format: "unrecognized result archive layout".to_string(),
+            }),
+        }
+    }
+
+    /// Upgrade a raw archive to the current schema, returning the source version
+    pub fn migrate(mut value: serde_json::Value) -> Result<(serde_json::Value, u32)> {
+        let from_version = Self::detect_version(&value)?;
+        if from_version > CURRENT_SCHEMA_VERSION {
+            return Err(IOError::InvalidFormat {
+                format: format!(
+                    "archive schema v{} is newer than supported v{}",
+                    from_version, CURRENT_SCHEMA_VERSION
+                ),
+            });
+        }
+
+        for step in &MIGRATIONS[(from_version - FIRST_SCHEMA_VERSION) as usize..] {
+            value = step(value)?;
+        }
+
+        Ok((value, from_version))
+    }
+
+    /// Upgrade an archive file in place
+    pub fn migrate_file(path: &str, dry_run: bool) -> Result<MigrationReport> {
+        let value = read_archive_value(path)?;
+        let (migrated, from_version) = Self::migrate(value)?;
+        let needs_rewrite = from_version < CURRENT_SCHEMA_VERSION;
+
+        if needs_rewrite && !dry_run {
+            // Validate against the current layout before touching the file
+            let archive: ResultArchive = serde_json::from_value(migrated)?;
+            let tmp_path = format!("{}.migrating", path);
+            archive.save(&tmp_path)?;
+            fs::rename(&tmp_path, path)?;
+        }
+
+        Ok(MigrationReport {
+            from_version,
+            to_version: CURRENT_SCHEMA_VERSION,
+            rewritten: needs_rewrite && !dry_run,
+        })
+    }
+
+    /// v0 -> v1: wrap the bare data array and add provenance fields
+    fn migrate_v0_to_v1(value: serde_json::Value) -> Result<serde_json::Value> {
+        let data: DataArray = serde_json::from_value(value)?;
+        let mut archive = ResultArchive::new();
+        archive.schema_version = 1;
+        archive.attributes.insert("migrated_from".to_string(), "0".to_string());
+        archive.add_dataset(LEGACY_DATASET_NAME, data);
+        Ok(serde_json::to_value(archive)?)
+    }
+}
+
+fn read_archive_value(path: &str) -> Result<serde_json::Value> {
+    if !Path::new(path).exists() {
+        return Err(IOError::FileNotFound {
+            path: path.to_string(),
+        });
+    }
+    let content = fs::read_to_string(path)?;
+    let value = serde_json::from_str(&content);
+    // Legacy data arrays may also have been saved as YAML
+    #[cfg(feature = "yaml")]
+    let value = value.or_else(|e| serde_yaml::from_str(&content).map_err(|_| e));
+    Ok(value?)
+}
+
+fn producer_string() -> String {
+    format!("wavecore-io {}", env!("CARGO_PKG_VERSION"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_version() {
+        let archive = serde_json::to_value(ResultArchive::new()).unwrap();
+        assert_eq!(ArchiveMigrator::detect_version(&archive).unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // A bare data array is an unversioned archive
+        let array = serde_json::to_value(DataArray::new(&[2], &[1.0, 2.0]).unwrap()).unwrap();
+        assert_eq!(ArchiveMigrator::detect_version(&array).unwrap(), 0);
+        assert!(ArchiveMigrator::detect_version(&serde_json::json!({ "schema_version": 0 })).is_err());
+        assert!(ArchiveMigrator::detect_version(&serde_json::json!({ "datasets": {} })).is_err());
+    }
+
+    #[test]
+    fn test_reject_future_schema() {
+        let value = serde_json::json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(ArchiveMigrator::migrate(value).is_err());
+    }
+
+    #[test]
+    fn test_migrate_legacy_file_in_place() {
+        // Layouts written by `FileIO::save_data` before archives were versioned
+        let legacy = [
+            ("json", "{\n  \"shape\": [\n    3\n  ],\n  \"data\": [\n    1.0,\n    2.0,\n    3.0\n  ],\n  \"data_type\": \"Float64\"\n}"),
+            #[cfg(feature = "yaml")]
+            ("yaml", "shape:\n- 3\ndata:\n- 1.0\n- 2.0\n- 3.0\ndata_type: Float64\n"),
+        ];
+        for (extension, content) in legacy {
+            let path = std::env::temp_dir().join(format!("wavecore_legacy_{}.{}", std::process::id(), extension));
+            let path = path.to_str().unwrap();
+            fs::write(path, content).unwrap();
+
+            let report = ArchiveMigrator::migrate_file(path, true).unwrap();
+            assert_eq!((report.from_version, report.rewritten), (0, false));
+            assert_eq!(fs::read_to_string(path).unwrap(), content);
+
+            let report = ArchiveMigrator::migrate_file(path, false).unwrap();
+            assert_eq!(report, MigrationReport { from_version: 0, to_version: CURRENT_SCHEMA_VERSION, rewritten: true });
+            assert!(!Path::new(&format!("{}.migrating", path)).exists());
+
+            let archive = ResultArchive::load(path).unwrap();
+            assert_eq!(archive.schema_version, CURRENT_SCHEMA_VERSION);
+            assert_eq!(archive.attributes["migrated_from"], "0");
+            assert_eq!(archive.dataset_names(), vec![LEGACY_DATASET_NAME]);
+            assert_eq!(archive.dataset(LEGACY_DATASET_NAME).unwrap().as_slice(), &[1.0, 2.0, 3.0]);
+            assert!(!ArchiveMigrator::migrate_file(path, false).unwrap().rewritten);
+
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_migrate_current_file_is_untouched() {
+        let path = std::env::temp_dir().join(format!("wavecore_archive_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut archive = ResultArchive::new();
+        archive.add_dataset("added_mass", DataArray::new(&[3], &[1.0, 2.0, 3.0]).unwrap());
+        archive.save(path).unwrap();
+
+        let report = ArchiveMigrator::migrate_file(path, false).unwrap();
+        assert_eq!(report.from_version, CURRENT_SCHEMA_VERSION);
+        assert!(!report.rewritten);
+
+        let loaded = ResultArchive::load(path).unwrap();
+        assert_eq!(loaded.dataset_names(), vec!["added_mass"]);
+        assert_eq!(loaded.dataset("added_mass").unwrap().as_slice(), &[1.0, 2.0, 3.0]);
+
+        fs::remove_file(path).unwrap();
+    }
+}
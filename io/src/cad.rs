@@ -0,0 +1,874 @@
+//! IGES and STEP import of trimmed NURBS hull surfaces
+//!
+//! Hull definitions leave CAD as B-spline surface patches rather than panels.
+//! [`parse_iges`] reads rational B-spline surfaces (entity 128), trimmed
+//! surfaces (144) whose boundaries (142) are drawn in parameter space as
+//! B-spline curves (126), lines (110) or composite curves (102), and
+//! transformation matrices (124). [`parse_step`] reads
+//! `B_SPLINE_SURFACE_WITH_KNOTS` surfaces, rational ones included, and trims
+//! the faces bounding them by the B-spline or polyline `PCURVE`s of their
+//! edges. Coordinates are converted to metres from the file's length unit,
+//! and [`CadFile::mesh`] panels the patches with curvature-adaptive
+//! refinement.
+//!
+//! Analytic surfaces are skipped, and boundaries without a supported
+//! parameter-space curve leave their surface untrimmed; both are listed in
+//! [`CadFile::warnings`].
+
+use super::*;
+use nalgebra::Matrix4;
+use std::collections::{BTreeMap, HashSet};
+use wavecore_meshes::{Mesh, NurbsCurve, NurbsSurface, PanelizeOptions, Point};
+
+/// Points sampled on each knot span of a curved trimming curve
+const TRIM_SAMPLES: usize = 16;
+
+/// NURBS patches read from an IGES or STEP file
+#[derive(Debug, Clone)]
+pub struct CadFile {
+    /// Surface patches in metres, with their trimming loops
+    pub surfaces: Vec<NurbsSurface>,
+    /// Factor that converted the file's length unit to metres
+    pub unit_scale: f64,
+    /// Entities skipped or left untrimmed
+    pub warnings: Vec<String>,
+}
+
+impl CadFile {
+    /// Panel mesh of all patches, welded along shared edges
+    pub fn mesh(&self, options: &PanelizeOptions) -> Result<Mesh> {
+        if self.surfaces.is_empty() {
+            return Err(IOError::InvalidFormat {
+                format: "CAD file without supported NURBS surfaces".to_string(),
+            });
+        }
+        Ok(Mesh::from_nurbs(&self.surfaces, options)?)
+    }
+}
+
+/// Read an IGES file
+pub fn read_iges(path: &str) -> Result<CadFile> {
+    parse_iges(&std::fs::read_to_string(path)?)
+}
+
+/// Read a STEP (ISO 10303-21) file
+pub fn read_step(path: &str) -> Result<CadFile> {
+    parse_step(&std::fs::read_to_string(path)?)
+}
+
+/// Parse the text of a fixed-format ASCII IGES file
+pub fn parse_iges(text: &str) -> Result<CadFile> {
+    let mut global = String::new();
+    let mut directory = Vec::new();
+    let mut parameters = Vec::new();
+    for line in text.lines().map(|l| l.trim_end_matches('\r')).filter(|l| !l.trim().is_empty()) {
+        let section = line
+            .get(72..73)
+            .ok_or_else(|| iges_error(format!("line shorter than 73 columns: '{}'", line)))?;
+        match section {
+            "S" | "T" => {}
+            "G" => global.push_str(&line[..72]),
+            "D" => directory.push(line),
+            "P" => parameters.push(line),
+            "C" => {
+                return Err(IOError::InvalidFormat {
+                    format: "compressed IGES (only fixed-format ASCII is supported)".to_string(),
+                })
+            }
+            other => return Err(iges_error(format!("unknown section '{}'", other))),
+        }
+    }
+    if global.is_empty() {
+        return Err(iges_error("missing global section".to_string()));
+    }
+
+    // Field 1 may redefine the parameter delimiter and field 2 the record delimiter
+    let delimiter = match global.strip_prefix("1H") {
+        Some(rest) => rest.chars().next().unwrap_or(','),
+        None => ',',
+    };
+    let mut fields = tokens(&global, delimiter, ';');
+    let end = fields.get(1).and_then(|f| f.chars().next()).unwrap_or(';');
+    if end != ';' {
+        fields = tokens(&global, delimiter, end);
+    }
+    let field = |k: usize| fields.get(k).map(String::as_str).unwrap_or("");
+    let unit_scale = iges_unit(field(13), field(14))?;
+
+    if directory.len() % 2 != 0 {
+        return Err(iges_error("directory section has an odd number of lines".to_string()));
+    }
+    let mut entries = BTreeMap::new();
+    for (k, pair) in directory.chunks(2).enumerate() {
+        let column = |line: &str, c: usize| -> Result<usize> {
+            let word = line.get(8 * c..8 * c + 8).unwrap_or("").trim();
+            if word.is_empty() {
+                return Ok(0);
+            }
+            word.parse::<i64>()
+                .map(|v| v.max(0) as usize)
+                .map_err(|_| iges_error(format!("invalid directory field '{}'", word)))
+        };
+        entries.insert(
+            2 * k + 1,
+            Entry {
+                kind: column(pair[0], 0)?,
+                pointer: column(pair[0], 1)?,
+                transform: column(pair[0], 6)?,
+                lines: column(pair[1], 3)?,
+            },
+        );
+    }
+    let iges = Iges {
+        delimiter,
+        end,
+        entries,
+        parameters,
+    };
+
+    let mut warnings = Vec::new();
+    let mut referenced = HashSet::new();
+    for (&de, entry) in &iges.entries {
+        if entry.kind == 144 {
+            referenced.insert(iges.fields(de)?.index()?);
+        }
+    }
+    let mut surfaces = Vec::new();
+    for (&de, entry) in &iges.entries {
+        match entry.kind {
+            128 if !referenced.contains(&de) => surfaces.push(iges.surface(de)?),
+            144 => surfaces.extend(iges.trimmed(de, &mut warnings)?),
+            114 | 118 | 120 | 122 | 140 | 143 | 190..=198 => {
+                warnings.push(format!("IGES entity {} at DE {}: surface type not supported, skipped", entry.kind, de))
+            }
+            _ => {}
+        }
+    }
+    for surface in &mut surfaces {
+        surface.transform(&Matrix4::new_scaling(unit_scale));
+    }
+    Ok(CadFile {
+        surfaces,
+        unit_scale,
+        warnings,
+    })
+}
+
+/// Parse the text of a STEP (ISO 10303-21) file
+pub fn parse_step(text: &str) -> Result<CadFile> {
+    if !text.trim_start().starts_with("ISO-10303-21;") {
+        return Err(IOError::InvalidFormat {
+            format: "STEP file without an ISO-10303-21 header".to_string(),
+        });
+    }
+    let data = text
+        .find("DATA;")
+        .map(|start| &text[start + 5..])
+        .ok_or_else(|| step_error("missing DATA section".to_string()))?;
+    let mut reader = Reader { text: data.as_bytes(), at: 0 };
+    let mut entities = BTreeMap::new();
+    loop {
+        reader.skip_space();
+        if reader.rest().starts_with(b"ENDSEC") || reader.at >= reader.text.len() {
+            break;
+        }
+        reader.expect(b'#')?;
+        let id = reader.integer()?;
+        reader.skip_space();
+        reader.expect(b'=')?;
+        reader.skip_space();
+        let parts = if reader.peek() == Some(b'(') {
+            // Complex instance of several partial entity values
+            reader.at += 1;
+            let mut parts = Vec::new();
+            while {
+                reader.skip_space();
+                reader.peek() != Some(b')')
+            } {
+                match reader.value()? {
+                    Value::Typed(name, args) => parts.push((name, args)),
+                    _ => return Err(step_error(format!("invalid complex entity #{}", id))),
+                }
+            }
+            reader.at += 1;
+            parts
+        } else {
+            match reader.value()? {
+                Value::Typed(name, args) => vec![(name, args)],
+                _ => return Err(step_error(format!("invalid entity #{}", id))),
+            }
+        };
+        reader.skip_space();
+        reader.expect(b';')?;
+        entities.insert(id, parts);
+    }
+    let step = Step { entities };
+
+    let unit_scale = step
+        .entities
+        .keys()
+        .find(|&&id| step.part(id, "LENGTH_UNIT").is_some())
+        .map_or(Ok(1.0), |&id| step.length_unit(id))?;
+
+    let mut warnings = Vec::new();
+    let mut surfaces = Vec::new();
+    let mut bounded = HashSet::new();
+    for &id in step.entities.keys() {
+        let Some(face) = step.part(id, "ADVANCED_FACE").or_else(|| step.part(id, "FACE_SURFACE")) else {
+            continue;
+        };
+        let geometry = reference(arg(face, 2)?)?;
+        bounded.insert(geometry);
+        let Some(mut surface) = step.surface(geometry, &mut warnings)? else {
+            continue;
+        };
+        match step.face_trim(list(arg(face, 1)?)?, geometry)? {
+            Some(loops) => surface.trim = loops,
+            None => warnings.push(format!("STEP face #{}: bounds without a B-spline or polyline PCURVE, left untrimmed", id)),
+        }
+        surfaces.push(surface);
+    }
+    for &id in step.entities.keys() {
+        if !bounded.contains(&id) && step.part(id, "B_SPLINE_SURFACE_WITH_KNOTS").is_some() {
+            surfaces.extend(step.surface(id, &mut warnings)?);
+        }
+    }
+    for surface in &mut surfaces {
+        surface.transform(&Matrix4::new_scaling(unit_scale));
+    }
+    Ok(CadFile {
+        surfaces,
+        unit_scale,
+        warnings,
+    })
+}
+
+fn iges_error(message: String) -> IOError {
+    IOError::ParseError {
+        message: format!("IGES: {}", message),
+    }
+}
+
+fn step_error(message: String) -> IOError {
+    IOError::ParseError {
+        message: format!("STEP: {}", message),
+    }
+}
+
+/// Fields of free-format IGES data up to the record delimiter, Hollerith strings unquoted
+fn tokens(text: &str, delimiter: char, end: char) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut fields = Vec::new();
+    let mut k = 0;
+    loop {
+        while chars.get(k) == Some(&' ') {
+            k += 1;
+        }
+        let digits = chars[k.min(chars.len())..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let mut field: String = if digits > 0 && chars.get(k + digits) == Some(&'H') {
+            let count: usize = chars[k..k + digits].iter().collect::<String>().parse().unwrap_or(0);
+            let start = k + digits + 1;
+            k = (start + count).min(chars.len());
+            chars[start..k].iter().collect()
+        } else {
+            String::new()
+        };
+        let start = k;
+        while k < chars.len() && chars[k] != delimiter && chars[k] != end {
+            k += 1;
+        }
+        if field.is_empty() {
+            field = chars[start..k].iter().collect::<String>().trim().to_string();
+        }
+        fields.push(field);
+        if k >= chars.len() || chars[k] == end {
+            return fields;
+        }
+        k += 1;
+    }
+}
+
+/// Metres per IGES model unit, from the unit flag or, for flag 3, the unit name
+fn iges_unit(flag: &str, name: &str) -> Result<f64> {
+    const NAMES: [&str; 12] = ["", "IN", "MM", "", "FT", "MI", "M", "KM", "MIL", "UM", "CM", "UIN"];
+    let flag: usize = if flag.is_empty() {
+        1
+    } else {
+        flag.parse().map_err(|_| iges_error(format!("invalid unit flag '{}'", flag)))?
+    };
+    let name = if flag == 3 { name.trim() } else { NAMES.get(flag).copied().unwrap_or("") };
+    match name.to_ascii_uppercase().as_str() {
+        "IN" | "INCH" => Ok(0.0254),
+        "MM" => Ok(0.001),
+        "FT" => Ok(0.3048),
+        "MI" => Ok(1609.344),
+        "M" => Ok(1.0),
+        "KM" => Ok(1000.0),
+        "MIL" => Ok(2.54e-5),
+        "UM" | "MICRON" => Ok(1e-6),
+        "CM" => Ok(0.01),
+        "UIN" => Ok(2.54e-8),
+        _ => Err(IOError::InvalidFormat {
+            format: format!("IGES length unit (flag {}, name '{}')", flag, name),
+        }),
+    }
+}
+
+/// Directory entry fields used by the importer
+struct Entry {
+    kind: usize,
+    pointer: usize,
+    transform: usize,
+    lines: usize,
+}
+
+struct Iges<'a> {
+    delimiter: char,
+    end: char,
+    entries: BTreeMap<usize, Entry>,
+    parameters: Vec<&'a str>,
+}
+
+/// Parameter fields of one IGES entity, consumed in order after the entity type
+struct Fields {
+    values: Vec<String>,
+    at: usize,
+}
+
+impl Fields {
+    fn real(&mut self) -> Result<f64> {
+        let word = self
+            .values
+            .get(self.at)
+            .ok_or_else(|| iges_error("truncated parameter data".to_string()))?;
+        self.at += 1;
+        if word.is_empty() {
+            return Ok(0.0);
+        }
+        word.replace(['D', 'd'], "E")
+            .parse()
+            .map_err(|_| iges_error(format!("invalid number '{}'", word)))
+    }
+
+    fn reals(&mut self, n: usize) -> Result<Vec<f64>> {
+        (0..n).map(|_| self.real()).collect()
+    }
+
+    fn index(&mut self) -> Result<usize> {
+        let value = self.real()?;
+        if value < 0.0 || value.fract() != 0.0 {
+            return Err(iges_error(format!("invalid count or pointer {}", value)));
+        }
+        Ok(value as usize)
+    }
+
+    fn points(&mut self, n: usize) -> Result<Vec<Point>> {
+        let coordinates = self.reals(3 * n)?;
+        Ok(coordinates.chunks(3).map(|c| Point::new(c[0], c[1], c[2])).collect())
+    }
+}
+
+impl Iges<'_> {
+    fn entry(&self, de: usize) -> Result<&Entry> {
+        self.entries
+            .get(&de)
+            .ok_or_else(|| iges_error(format!("missing directory entry {}", de)))
+    }
+
+    fn fields(&self, de: usize) -> Result<Fields> {
+        let entry = self.entry(de)?;
+        let lines = entry
+            .pointer
+            .checked_sub(1)
+            .and_then(|first| self.parameters.get(first..first + entry.lines.max(1)))
+            .ok_or_else(|| iges_error(format!("parameter data of entry {} out of range", de)))?;
+        let data: String = lines.iter().map(|l| l.get(..64).unwrap_or(l)).collect();
+        let values = tokens(&data, self.delimiter, self.end);
+        if values.first().and_then(|v| v.parse::<usize>().ok()) != Some(entry.kind) {
+            return Err(iges_error(format!("parameter data of entry {} is not of type {}", de, entry.kind)));
+        }
+        Ok(Fields { values, at: 1 })
+    }
+
+    /// Matrix of a 124 entity composed with the matrices it refers to
+    fn matrix(&self, de: usize) -> Result<Matrix4<f64>> {
+        if de == 0 {
+            return Ok(Matrix4::identity());
+        }
+        let entry = self.entry(de)?;
+        if entry.kind != 124 || entry.transform == de {
+            return Err(iges_error(format!("entry {} is not a transformation matrix", de)));
+        }
+        let r = self.fields(de)?.reals(12)?;
+        #[rustfmt::skip]
+        let own = Matrix4::new(
+            r[0], r[1], r[2], r[3],
+            r[4], r[5], r[6], r[7],
+            r[8], r[9], r[10], r[11],
+            0.0, 0.0, 0.0, 1.0,
+        );
+        Ok(self.matrix(entry.transform)? * own)
+    }
+
+    /// Rational B-spline surface (128) in model coordinates
+    fn surface(&self, de: usize) -> Result<NurbsSurface> {
+        let mut f = self.fields(de)?;
+        let [k1, k2, m1, m2] = [f.index()?, f.index()?, f.index()?, f.index()?];
+        f.at += 5;
+        let (nu, nv) = (k1 + 1, k2 + 1);
+        let knots = [f.reals(k1 + m1 + 2)?, f.reals(k2 + m2 + 2)?];
+        // Weights and control points run along u fastest
+        let weights = f.reals(nu * nv)?;
+        let points = f.points(nu * nv)?;
+        let range = f.reals(4)?;
+        let mut surface = NurbsSurface::new(
+            [m1, m2],
+            knots,
+            (0..nu).map(|i| (0..nv).map(|j| points[j * nu + i]).collect()).collect(),
+            (0..nu).map(|i| (0..nv).map(|j| weights[j * nu + i]).collect()).collect(),
+        )?;
+        for d in 0..2 {
+            let (start, end) = (range[2 * d].max(surface.domain[d].0), range[2 * d + 1].min(surface.domain[d].1));
+            if start < end {
+                surface.domain[d] = (start, end);
+            }
+        }
+        surface.transform(&self.matrix(self.entry(de)?.transform)?);
+        Ok(surface)
+    }
+
+    /// Parameter-space points of a curve, or `None` for an unsupported curve type
+    fn curve(&self, de: usize) -> Result<Option<Vec<[f64; 2]>>> {
+        let mut f = self.fields(de)?;
+        let points = match self.entry(de)?.kind {
+            126 => {
+                let [k, m] = [f.index()?, f.index()?];
+                f.at += 4;
+                let knots = f.reals(k + m + 2)?;
+                let weights = f.reals(k + 1)?;
+                let control_points = f.points(k + 1)?;
+                let range = f.reals(2)?;
+                let mut curve = NurbsCurve::new(m, knots, control_points, weights)?;
+                let (start, end) = (range[0].max(curve.domain.0), range[1].min(curve.domain.1));
+                if start < end {
+                    curve.domain = (start, end);
+                }
+                curve.polyline(TRIM_SAMPLES)
+            }
+            110 => f.points(2)?,
+            102 => {
+                let count = f.index()?;
+                let mut points: Vec<[f64; 2]> = Vec::new();
+                for _ in 0..count {
+                    let Some(part) = self.curve(f.index()?)? else {
+                        return Ok(None);
+                    };
+                    let skip = (points.last() == part.first()) as usize;
+                    points.extend(&part[skip..]);
+                }
+                return Ok(Some(points));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(points.iter().map(|p| [p.x, p.y]).collect()))
+    }
+
+    /// Parameter-space loop of a curve on a parametric surface (142)
+    fn boundary(&self, de: usize) -> Result<Option<Vec<[f64; 2]>>> {
+        if self.entry(de)?.kind != 142 {
+            return Err(iges_error(format!("entry {} is not a curve on a parametric surface", de)));
+        }
+        let mut f = self.fields(de)?;
+        f.at += 2;
+        let parameter_curve = f.index()?;
+        if parameter_curve == 0 {
+            return Ok(None);
+        }
+        let mut points = self.curve(parameter_curve)?;
+        if let Some(points) = points.as_mut().filter(|p| p.len() > 1 && p.first() == p.last()) {
+            points.pop();
+        }
+        Ok(points)
+    }
+
+    /// Trimmed surface (144), untrimmed when a boundary has no usable parameter curve
+    fn trimmed(&self, de: usize, warnings: &mut Vec<String>) -> Result<Option<NurbsSurface>> {
+        let mut f = self.fields(de)?;
+        let [base, natural_outer, holes, outer] = [f.index()?, f.index()?, f.index()?, f.index()?];
+        let inner = (0..holes).map(|_| f.index()).collect::<Result<Vec<_>>>()?;
+        let kind = self.entry(base)?.kind;
+        if kind != 128 {
+            warnings.push(format!("IGES trimmed surface {}: base surface type {} not supported, skipped", de, kind));
+            return Ok(None);
+        }
+        let mut surface = self.surface(base)?;
+        let [(u0, u1), (v0, v1)] = surface.domain;
+        let mut loops = Vec::new();
+        if natural_outer == 0 || outer == 0 {
+            loops.push(Some(vec![[u0, v0], [u1, v0], [u1, v1], [u0, v1]]));
+        } else {
+            loops.push(self.boundary(outer)?);
+        }
+        for hole in inner {
+            loops.push(self.boundary(hole)?);
+        }
+        match loops.into_iter().collect::<Option<Vec<_>>>() {
+            Some(loops) => surface.trim = loops,
+            None => warnings.push(format!("IGES trimmed surface {}: boundary without a supported parameter-space curve, left untrimmed", de)),
+        }
+        Ok(Some(surface))
+    }
+}
+
+/// Parameter of a STEP entity
+#[derive(Debug, Clone)]
+enum Value {
+    Reference(usize),
+    Number(f64),
+    Text,
+    Enumeration(String),
+    List(Vec<Value>),
+    Typed(String, Vec<Value>),
+    Unset,
+}
+
+struct Reader<'a> {
+    text: &'a [u8],
+    at: usize,
+}
+
+impl Reader<'_> {
+    fn rest(&self) -> &[u8] {
+        &self.text[self.at.min(self.text.len())..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.at).copied()
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                self.at += 1;
+            }
+            if !self.rest().starts_with(b"/*") {
+                return;
+            }
+            self.at = self.rest().windows(2).position(|w| w == b"*/").map_or(self.text.len(), |end| self.at + end + 2);
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() != Some(c) {
+            return Err(step_error(format!("expected '{}' at byte {}", c as char, self.at)));
+        }
+        self.at += 1;
+        Ok(())
+    }
+
+    fn take_while(&mut self, accept: impl Fn(u8) -> bool) -> &str {
+        let start = self.at;
+        while self.peek().is_some_and(&accept) {
+            self.at += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.at]).unwrap_or("")
+    }
+
+    fn integer(&mut self) -> Result<usize> {
+        let word = self.take_while(|c| c.is_ascii_digit());
+        word.parse().map_err(|_| step_error(format!("invalid instance name '#{}'", word)))
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_space();
+        match self.peek() {
+            Some(b'#') => {
+                self.at += 1;
+                Ok(Value::Reference(self.integer()?))
+            }
+            Some(b'\'') => {
+                // Strings only name things; skip them, quotes doubled inside
+                self.at += 1;
+                loop {
+                    match self.peek() {
+                        Some(b'\'') if self.text.get(self.at + 1) == Some(&b'\'') => self.at += 2,
+                        Some(b'\'') => break,
+                        Some(_) => self.at += 1,
+                        None => return Err(step_error("unterminated string".to_string())),
+                    }
+                }
+                self.at += 1;
+                Ok(Value::Text)
+            }
+            Some(b'.') => {
+                self.at += 1;
+                let name = self.take_while(|c| c != b'.').to_string();
+                self.expect(b'.')?;
+                Ok(Value::Enumeration(name))
+            }
+            Some(b'(') => Ok(Value::List(self.arguments()?)),
+            Some(b'$' | b'*') => {
+                self.at += 1;
+                Ok(Value::Unset)
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_').to_string();
+                self.skip_space();
+                Ok(Value::Typed(name, self.arguments()?))
+            }
+            Some(c) if c.is_ascii_digit() || c == b'-' || c == b'+' => {
+                let word = self.take_while(|c| c.is_ascii_digit() || b"+-.eE".contains(&c));
+                word.parse()
+                    .map(Value::Number)
+                    .map_err(|_| step_error(format!("invalid number '{}'", word)))
+            }
+            _ => Err(step_error(format!("unexpected character at byte {}", self.at))),
+        }
+    }
+
+    /// Parenthesized, comma-separated values
+    fn arguments(&mut self) -> Result<Vec<Value>> {
+        self.expect(b'(')?;
+        let mut values = Vec::new();
+        self.skip_space();
+        if self.peek() == Some(b')') {
+            self.at += 1;
+            return Ok(values);
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_space();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b')') => {
+                    self.at += 1;
+                    return Ok(values);
+                }
+                _ => return Err(step_error(format!("expected ',' or ')' at byte {}", self.at))),
+            }
+        }
+    }
+}
+
+fn arg(args: &[Value], k: usize) -> Result<&Value> {
+    args.get(k).ok_or_else(|| step_error(format!("missing parameter {}", k + 1)))
+}
+
+fn reference(value: &Value) -> Result<usize> {
+    match value {
+        Value::Reference(id) => Ok(*id),
+        other => Err(step_error(format!("expected an instance reference, found {:?}", other))),
+    }
+}
+
+fn number(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(x) => Ok(*x),
+        Value::Typed(_, args) if args.len() == 1 => number(&args[0]),
+        other => Err(step_error(format!("expected a number, found {:?}", other))),
+    }
+}
+
+fn list(value: &Value) -> Result<&[Value]> {
+    match value {
+        Value::List(values) => Ok(values),
+        other => Err(step_error(format!("expected a list, found {:?}", other))),
+    }
+}
+
+fn numbers(value: &Value) -> Result<Vec<f64>> {
+    list(value)?.iter().map(number).collect()
+}
+
+fn is_true(value: &Value) -> bool {
+    matches!(value, Value::Enumeration(e) if e == "T")
+}
+
+/// Knot values repeated by their multiplicities
+fn expand_knots(multiplicities: &Value, knots: &Value) -> Result<Vec<f64>> {
+    let (multiplicities, knots) = (numbers(multiplicities)?, numbers(knots)?);
+    if multiplicities.len() != knots.len() {
+        return Err(step_error("knot multiplicities and values differ in length".to_string()));
+    }
+    Ok(knots
+        .iter()
+        .zip(multiplicities)
+        .flat_map(|(&t, m)| std::iter::repeat_n(t, m as usize))
+        .collect())
+}
+
+struct Step {
+    entities: BTreeMap<usize, Vec<(String, Vec<Value>)>>,
+}
+
+impl Step {
+    /// Arguments of the partial entity `name` of instance `id`
+    fn part(&self, id: usize, name: &str) -> Option<&[Value]> {
+        self.entities
+            .get(&id)?
+            .iter()
+            .find(|(part, _)| part == name)
+            .map(|(_, args)| args.as_slice())
+    }
+
+    fn point(&self, value: &Value) -> Result<Point> {
+        let id = reference(value)?;
+        let args = self
+            .part(id, "CARTESIAN_POINT")
+            .ok_or_else(|| step_error(format!("#{} is not a CARTESIAN_POINT", id)))?;
+        let c = numbers(arg(args, 1)?)?;
+        Ok(Point::new(c.first().copied().unwrap_or(0.0), c.get(1).copied().unwrap_or(0.0), c.get(2).copied().unwrap_or(0.0)))
+    }
+
+    /// Metres per unit of a length unit instance
+    fn length_unit(&self, id: usize) -> Result<f64> {
+        if let Some(args) = self.part(id, "SI_UNIT") {
+            let prefix = match arg(args, 0)? {
+                Value::Enumeration(prefix) => prefix.as_str(),
+                _ => "",
+            };
+            return match prefix {
+                "" => Ok(1.0),
+                "KILO" => Ok(1e3),
+                "HECTO" => Ok(1e2),
+                "DECA" => Ok(1e1),
+                "DECI" => Ok(1e-1),
+                "CENTI" => Ok(1e-2),
+                "MILLI" => Ok(1e-3),
+                "MICRO" => Ok(1e-6),
+                "NANO" => Ok(1e-9),
+                other => Err(IOError::InvalidFormat {
+                    format: format!("STEP length unit prefix {}", other),
+                }),
+            };
+        }
+        if let Some(args) = self.part(id, "CONVERSION_BASED_UNIT") {
+            let measure = reference(arg(args, 1)?)?;
+            let measure = self
+                .entities
+                .get(&measure)
+                .and_then(|parts| parts.iter().find(|(name, _)| name.ends_with("MEASURE_WITH_UNIT")))
+                .ok_or_else(|| step_error(format!("conversion of unit #{} not found", id)))?;
+            return Ok(number(arg(&measure.1, 0)?)? * self.length_unit(reference(arg(&measure.1, 1)?)?)?);
+        }
+        Err(IOError::InvalidFormat {
+            format: format!("STEP length unit #{}", id),
+        })
+    }
+
+    /// B-spline surface with knots, rational or not; other surfaces are skipped with a warning
+    fn surface(&self, id: usize, warnings: &mut Vec<String>) -> Result<Option<NurbsSurface>> {
+        let Some(with_knots) = self.part(id, "B_SPLINE_SURFACE_WITH_KNOTS") else {
+            let kind = self.entities.get(&id).and_then(|p| p.first()).map_or("missing", |(name, _)| name.as_str());
+            warnings.push(format!("STEP surface #{}: {} not supported, skipped", id, kind));
+            return Ok(None);
+        };
+        // A simple instance carries the name and B_SPLINE_SURFACE attributes before its own
+        let (base, knots) = match self.part(id, "B_SPLINE_SURFACE") {
+            Some(base) => (base, with_knots),
+            None => (&with_knots[1..], &with_knots[with_knots.len().saturating_sub(5)..]),
+        };
+        let degrees = [number(arg(base, 0)?)? as usize, number(arg(base, 1)?)? as usize];
+        let control_points = list(arg(base, 2)?)?
+            .iter()
+            .map(|row| list(row)?.iter().map(|p| self.point(p)).collect())
+            .collect::<Result<Vec<Vec<Point>>>>()?;
+        let weights = match self.part(id, "RATIONAL_B_SPLINE_SURFACE") {
+            Some(rational) => list(arg(rational, 0)?)?.iter().map(numbers).collect::<Result<_>>()?,
+            None => control_points.iter().map(|row| vec![1.0; row.len()]).collect(),
+        };
+        let knots = [expand_knots(arg(knots, 0)?, arg(knots, 2)?)?, expand_knots(arg(knots, 1)?, arg(knots, 3)?)?];
+        Ok(Some(NurbsSurface::new(degrees, knots, control_points, weights)?))
+    }
+
+    /// Parameter-space points of a B-spline or polyline curve, `None` for other curves
+    fn curve(&self, id: usize) -> Result<Option<Vec<[f64; 2]>>> {
+        let points = if let Some(with_knots) = self.part(id, "B_SPLINE_CURVE_WITH_KNOTS") {
+            let (base, knots) = match self.part(id, "B_SPLINE_CURVE") {
+                Some(base) => (base, with_knots),
+                None => (&with_knots[1..], &with_knots[with_knots.len().saturating_sub(3)..]),
+            };
+            let control_points = list(arg(base, 1)?)?.iter().map(|p| self.point(p)).collect::<Result<Vec<_>>>()?;
+            let weights = match self.part(id, "RATIONAL_B_SPLINE_CURVE") {
+                Some(rational) => numbers(arg(rational, 0)?)?,
+                None => vec![1.0; control_points.len()],
+            };
+            let knots = expand_knots(arg(knots, 0)?, arg(knots, 1)?)?;
+            NurbsCurve::new(number(arg(base, 0)?)? as usize, knots, control_points, weights)?.polyline(TRIM_SAMPLES)
+        } else if let Some(polyline) = self.part(id, "POLYLINE") {
+            list(arg(polyline, 1)?)?.iter().map(|p| self.point(p)).collect::<Result<_>>()?
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(points.iter().map(|p| [p.x, p.y]).collect()))
+    }
+
+    /// Curve of the `PCURVE` on `surface` among an edge geometry and its associated curves
+    fn pcurve(&self, geometry: usize, surface: usize) -> Result<Option<Vec<[f64; 2]>>> {
+        let mut candidates = vec![geometry];
+        if let Some(args) = ["SURFACE_CURVE", "SEAM_CURVE", "INTERSECTION_CURVE"]
+            .iter()
+            .find_map(|name| self.part(geometry, name))
+        {
+            candidates.extend(list(arg(args, 2)?)?.iter().filter_map(|v| reference(v).ok()));
+        }
+        for id in candidates {
+            let Some(pcurve) = self.part(id, "PCURVE") else {
+                continue;
+            };
+            if reference(arg(pcurve, 1)?)? != surface {
+                continue;
+            }
+            let representation = reference(arg(pcurve, 2)?)?;
+            let items = self
+                .part(representation, "DEFINITIONAL_REPRESENTATION")
+                .ok_or_else(|| step_error(format!("#{} is not a DEFINITIONAL_REPRESENTATION", representation)))?;
+            return match list(arg(items, 1)?)?.first() {
+                Some(item) => self.curve(reference(item)?),
+                None => Ok(None),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Trimming loops of a face on `surface`, or `None` when an edge has no usable parameter curve
+    fn face_trim(&self, bounds: &[Value], surface: usize) -> Result<Option<Vec<Vec<[f64; 2]>>>> {
+        let mut loops = Vec::new();
+        for bound in bounds {
+            let bound = reference(bound)?;
+            let args = self
+                .part(bound, "FACE_OUTER_BOUND")
+                .or_else(|| self.part(bound, "FACE_BOUND"))
+                .ok_or_else(|| step_error(format!("#{} is not a face bound", bound)))?;
+            let edge_loop = reference(arg(args, 1)?)?;
+            let Some(edges) = self.part(edge_loop, "EDGE_LOOP") else {
+                return Ok(None);
+            };
+            let mut points: Vec<[f64; 2]> = Vec::new();
+            for oriented in list(arg(edges, 1)?)? {
+                let oriented = reference(oriented)?;
+                let args = self
+                    .part(oriented, "ORIENTED_EDGE")
+                    .ok_or_else(|| step_error(format!("#{} is not an ORIENTED_EDGE", oriented)))?;
+                let (edge, orientation) = (reference(arg(args, 3)?)?, is_true(arg(args, 4)?));
+                let edge = self
+                    .part(edge, "EDGE_CURVE")
+                    .ok_or_else(|| step_error(format!("#{} is not an EDGE_CURVE", edge)))?;
+                let Some(mut part) = self.pcurve(reference(arg(edge, 3)?)?, surface)? else {
+                    return Ok(None);
+                };
+                // The curve runs along the edge when its sense agrees with the edge's use
+                if orientation != is_true(arg(edge, 4)?) {
+                    part.reverse();
+                }
+                let skip = (points.last() == part.first()) as usize;
+                points.extend(&part[skip..]);
+            }
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+            loops.push(points);
+        }
+        Ok(Some(loops))
+    }
+}
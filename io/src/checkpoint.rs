@@ -0,0 +1,57 @@
+//! JSON file storage of sweep checkpoints
+//!
+//! Checkpoints are written to a temporary file next to the target and renamed
+//! over it, so a crash while saving leaves the previous checkpoint intact.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use wavecore_bem::{BEMError, CheckpointStore, SweepCheckpoint};
+
+/// Sweep checkpoint stored as a JSON file
+#[derive(Debug, Clone)]
+pub struct FileCheckpoint {
+    path: PathBuf,
+}
+
+impl FileCheckpoint {
+    /// Store checkpoints at `path`
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self { path: path.as_ref().to_path_buf() }
+    }
+
+    /// Checkpoint file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Delete the checkpoint file, e.g. once the sweep results are saved
+    pub fn remove(&self) -> std::io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl CheckpointStore for FileCheckpoint {
+    fn save(&self, checkpoint: &SweepCheckpoint) -> wavecore_bem::Result<()> {
+        let content = serde_json::to_string(checkpoint).map_err(invalid_data)?;
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        fs::write(&partial, content)?;
+        fs::rename(&partial, &self.path)?;
+        Ok(())
+    }
+
+    fn load(&self) -> wavecore_bem::Result<Option<SweepCheckpoint>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content).map_err(invalid_data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn invalid_data(e: serde_json::Error) -> BEMError {
+    BEMError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
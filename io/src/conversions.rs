@@ -0,0 +1,56 @@
+//! Conversion of solver output to data arrays
+
+use super::*;
+use wavecore_bem::solver::BEMResult;
+use wavecore_bem::PanelOutput;
+
+/// Columns of the panel data array, one row per panel
+///
+/// Source strength columns are NaN when the solve used the direct formulation.
+pub const PANEL_COLUMNS: [&str; 13] = [
+    "x", "y", "z", "nx", "ny", "nz", "area",
+    "pressure_re", "pressure_im", "source_re", "source_im", "normal_velocity_re", "normal_velocity_im",
+];
+
+/// Conversion into a [`DataArray`]
+pub trait ToDataArray {
+    /// Data array of the values
+    fn to_dataarray(&self) -> Result<DataArray>;
+}
+
+impl ToDataArray for PanelOutput {
+    /// `[n_panels, 13]` array with the columns of [`PANEL_COLUMNS`]
+    fn to_dataarray(&self) -> Result<DataArray> {
+        let mut data = Vec::with_capacity(self.len() * PANEL_COLUMNS.len());
+        for i in 0..self.len() {
+            let (source_re, source_im) = match &self.source_strength {
+                Some(sigma) => (sigma[i].re, sigma[i].im),
+                None => (f64::NAN, f64::NAN),
+            };
+            data.extend_from_slice(&self.centroids[i]);
+            data.extend_from_slice(&self.normals[i]);
+            data.extend_from_slice(&[
+                self.areas[i],
+                self.pressure[i].re,
+                self.pressure[i].im,
+                source_re,
+                source_im,
+                self.normal_velocity[i].re,
+                self.normal_velocity[i].im,
+            ]);
+        }
+        DataArray::new(&[self.len(), PANEL_COLUMNS.len()], &data)
+    }
+}
+
+impl ToDataArray for BEMResult {
+    /// Panel data array of the result's panel output
+    fn to_dataarray(&self) -> Result<DataArray> {
+        self.panel_output
+            .as_ref()
+            .ok_or_else(|| IOError::DataArrayError {
+                message: "Result carries no panel output".to_string(),
+            })?
+            .to_dataarray()
+    }
+}
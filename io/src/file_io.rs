@@ -20,14 +20,10 @@ impl FileIO {
             });
         }
         
-        // Read file content
-        let content = fs::read_to_string(path)
-            .map_err(|e| IOError::MemoryMapError(e))?;
-        
         // Parse based on format
         let mesh = match format {
-            Format::STL => Self::parse_stl(&content)?,
-            Format::OBJ => Self::parse_obj(&content)?,
+            Format::STL => stl::read_stl(path, &StlOptions::default())?,
+            Format::OBJ => Self::parse_obj(&fs::read_to_string(path)?)?,
             _ => {
                 return Err(IOError::InvalidFormat {
                     format: format!("{:?}", format),
@@ -46,8 +42,8 @@ impl FileIO {
         let start_time = Instant::now();
         
         let content = match format {
-            Format::STL => Self::serialize_stl(mesh)?,
-            Format::OBJ => Self::serialize_obj(mesh)?,
+            Format::STL => stl::stl_to_bytes(mesh, &StlOptions::default()),
+            Format::OBJ => Self::serialize_obj(mesh)?.into_bytes(),
             _ => {
                 return Err(IOError::InvalidFormat {
                     format: format!("{:?}", format),
@@ -72,9 +68,10 @@ impl FileIO {
         
         let content = match format {
             Format::JSON => serde_json::to_string_pretty(data)
-                .map_err(|e| IOError::SerializationError(e))?,
+                .map_err(IOError::SerializationError)?,
+            #[cfg(feature = "yaml")]
             Format::YAML => serde_yaml::to_string(data)
-                .map_err(|e| IOError::YamlError(e))?,
+                .map_err(IOError::YamlError)?,
             Format::CSV => Self::serialize_csv(data)?,
             _ => {
                 return Err(IOError::InvalidFormat {
@@ -106,13 +103,14 @@ impl FileIO {
         }
         
         let content = fs::read_to_string(path)
-            .map_err(|e| IOError::MemoryMapError(e))?;
+            .map_err(IOError::MemoryMapError)?;
         
         let data = match format {
             Format::JSON => serde_json::from_str(&content)
-                .map_err(|e| IOError::SerializationError(e))?,
+                .map_err(IOError::SerializationError)?,
+            #[cfg(feature = "yaml")]
             Format::YAML => serde_yaml::from_str(&content)
-                .map_err(|e| IOError::YamlError(e))?,
+                .map_err(IOError::YamlError)?,
             Format::CSV => Self::parse_csv(&content)?,
             _ => {
                 return Err(IOError::InvalidFormat {
@@ -138,7 +136,7 @@ impl FileIO {
         }
         
         let metadata = fs::metadata(path)
-            .map_err(|e| IOError::MemoryMapError(e))?;
+            .map_err(IOError::MemoryMapError)?;
         
         let format = Self::detect_format(path)?;
         
@@ -183,160 +181,9 @@ impl FileIO {
         }
     }
     
-    /// Parse STL file
-    fn parse_stl(content: &str) -> Result<wavecore_meshes::Mesh> {
-        let mut vertices = Vec::new();
-        let mut faces = Vec::new();
-        let mut normals = Vec::new();
-        
-        let lines: Vec<&str> = content.lines().collect();
-        let mut i = 0;
-        
-        while i < lines.len() {
-            let line = lines[i].trim();
-            
-            if line.starts_with("facet normal") {
-                // Parse normal
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 5 {
-                    let nx: f64 = parts[2].parse().unwrap_or(0.0);
-                    let ny: f64 = parts[3].parse().unwrap_or(0.0);
-                    let nz: f64 = parts[4].parse().unwrap_or(0.0);
-                    normals.push(nalgebra::Vector3::new(nx, ny, nz));
-                }
-                
-                i += 1;
-                
-                // Skip "outer loop" line
-                while i < lines.len() && !lines[i].trim().starts_with("vertex") {
-                    i += 1;
-                }
-                
-                // Parse vertices
-                let mut face_vertices = Vec::new();
-                while i < lines.len() && lines[i].trim().starts_with("vertex") {
-                    let line = lines[i].trim();
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 4 {
-                        let x: f64 = parts[1].parse().unwrap_or(0.0);
-                        let y: f64 = parts[2].parse().unwrap_or(0.0);
-                        let z: f64 = parts[3].parse().unwrap_or(0.0);
-                        face_vertices.push(vertices.len());
-                        vertices.push(nalgebra::Point3::new(x, y, z));
-                    }
-                    i += 1;
-                }
-                
-                // Create face if we have 3 vertices
-                if face_vertices.len() >= 3 {
-                    faces.push([face_vertices[0], face_vertices[1], face_vertices[2]]);
-                }
-                
-                // Skip endloop and endfacet
-                while i < lines.len() && !lines[i].trim().starts_with("facet") && !lines[i].trim().starts_with("endsolid") {
-                    i += 1;
-                }
-            } else {
-                i += 1;
-            }
-        }
-        
-        wavecore_meshes::Mesh::new(vertices, faces)
-            .map_err(|e| IOError::ParseError {
-                message: format!("Failed to create mesh: {}", e),
-            })
-    }
-    
-    /// Parse OBJ file
+    /// Parse OBJ file, merging all groups into one mesh
     fn parse_obj(content: &str) -> Result<wavecore_meshes::Mesh> {
-        let mut vertices = Vec::new();
-        let mut faces = Vec::new();
-        let mut normals = Vec::new();
-        
-        for line in content.lines() {
-            let line = line.trim();
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            
-            if parts.is_empty() || line.starts_with('#') {
-                continue;
-            }
-            
-            match parts[0] {
-                "v" => {
-                    if parts.len() >= 4 {
-                        let x: f64 = parts[1].parse().unwrap_or(0.0);
-                        let y: f64 = parts[2].parse().unwrap_or(0.0);
-                        let z: f64 = parts[3].parse().unwrap_or(0.0);
-                        vertices.push(nalgebra::Point3::new(x, y, z));
-                    }
-                }
-                "vn" => {
-                    if parts.len() >= 4 {
-                        let nx: f64 = parts[1].parse().unwrap_or(0.0);
-                        let ny: f64 = parts[2].parse().unwrap_or(0.0);
-                        let nz: f64 = parts[3].parse().unwrap_or(0.0);
-                        normals.push(nalgebra::Vector3::new(nx, ny, nz));
-                    }
-                }
-                "f" => {
-                    if parts.len() >= 4 {
-                        let mut face = Vec::new();
-                        for i in 1..parts.len() {
-                            let vertex_part = parts[i].split('/').next().unwrap_or("0");
-                            let vertex_idx: usize = vertex_part.parse().unwrap_or(0);
-                            if vertex_idx > 0 {
-                                face.push(vertex_idx - 1); // OBJ indices are 1-based
-                            }
-                        }
-                        if face.len() >= 3 {
-                            faces.push([face[0], face[1], face[2]]);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-        
-        wavecore_meshes::Mesh::new(vertices, faces)
-            .map_err(|e| IOError::ParseError {
-                message: format!("Failed to create mesh: {}", e),
-            })
-    }
-    
-    /// Serialize mesh to STL
-    fn serialize_stl(mesh: &wavecore_meshes::Mesh) -> Result<String> {
-        let mut content = String::new();
-        content.push_str("solid mesh\n");
-        
-        for (i, face) in mesh.faces.iter().enumerate() {
-            if face.len() >= 3 {
-                let v1 = mesh.vertices[face[0]];
-                let v2 = mesh.vertices[face[1]];
-                let v3 = mesh.vertices[face[2]];
-                
-                // Calculate normal
-                let normal = if i < mesh.normals.len() {
-                    mesh.normals[i]
-                } else {
-                    // Calculate face normal
-                    let u = v2 - v1;
-                    let v = v3 - v1;
-                    let cross = u.cross(&v);
-                    cross.normalize()
-                };
-                
-                content.push_str(&format!("  facet normal {} {} {}\n", normal.x, normal.y, normal.z));
-                content.push_str("    outer loop\n");
-                content.push_str(&format!("      vertex {} {} {}\n", v1.x, v1.y, v1.z));
-                content.push_str(&format!("      vertex {} {} {}\n", v2.x, v2.y, v2.z));
-                content.push_str(&format!("      vertex {} {} {}\n", v3.x, v3.y, v3.z));
-                content.push_str("    endloop\n");
-                content.push_str("  endfacet\n");
-            }
-        }
-        
-        content.push_str("endsolid mesh\n");
-        Ok(content)
+        obj::parse_obj(content)?.to_mesh()
     }
     
     /// Serialize mesh to OBJ
@@ -443,7 +290,7 @@ endloop
 endfacet
 endsolid test"#;
         
-        let mesh = FileIO::parse_stl(stl_content).unwrap();
+        let mesh = stl::parse_stl(stl_content.as_bytes(), &StlOptions::default()).unwrap();
         assert_eq!(mesh.vertices.len(), 3);
         assert_eq!(mesh.faces.len(), 1);
     }
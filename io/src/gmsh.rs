@@ -0,0 +1,361 @@
+//! GMSH `.msh` surface mesh import
+//!
+//! Reads ASCII meshes in the legacy 2.2 format and the 4.0/4.1 formats.
+//! Triangles and quadrilaterals (higher-order elements by their corner nodes)
+//! are collected by physical group, so a file meshed with physical surfaces
+//! such as `"hull"` and `"moonpool"` maps each group, or a chosen set of
+//! groups, onto a floating body ready for the BEM solver.
+//!
+//! In 2.2 files an element's physical group is its first tag; in 4.x files it
+//! comes from the physical tags of the surface entity the element block lies on.
+
+use super::*;
+use std::collections::{BTreeMap, HashMap};
+use wavecore_bodies::{FloatingBody, MassProperties as BodyMass, DOF};
+use wavecore_meshes::{Mesh, MeshCollection, Point};
+
+/// Name of the group holding surface elements outside any physical group
+pub const DEFAULT_GMSH_GROUP: &str = "default";
+
+/// Surface elements of one physical group
+#[derive(Debug, Clone)]
+pub struct GmshGroup {
+    /// Physical tag, or 0 for [`DEFAULT_GMSH_GROUP`]
+    pub tag: i32,
+    /// Name from `$PhysicalNames`, or `physical_<tag>` when unnamed
+    pub name: String,
+    /// Triangles and quads as indices into [`GmshFile::nodes`]
+    pub polygons: Vec<Vec<usize>>,
+}
+
+impl GmshGroup {
+    /// Number of quadrilateral elements
+    pub fn quad_count(&self) -> usize {
+        self.polygons.iter().filter(|p| p.len() == 4).count()
+    }
+}
+
+/// Contents of a GMSH mesh file
+#[derive(Debug, Clone, Default)]
+pub struct GmshFile {
+    /// Format version, e.g. `2.2` or `4.1`
+    pub version: f64,
+    /// All nodes in file order
+    pub nodes: Vec<Point>,
+    /// Groups with at least one surface element, by ascending tag
+    pub groups: Vec<GmshGroup>,
+}
+
+impl GmshFile {
+    /// Group by name
+    pub fn group(&self, name: &str) -> Option<&GmshGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// Triangulated mesh of the named groups, sharing nodes along their seams
+    pub fn mesh(&self, groups: &[&str]) -> Result<Mesh> {
+        let mut local = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for name in groups {
+            let group = self.group(name).ok_or_else(|| IOError::ParseError {
+                message: format!("GMSH physical group '{}' not found", name),
+            })?;
+            for polygon in &group.polygons {
+                for triangle in obj::triangulate(&self.nodes, polygon) {
+                    let [a, b, c] = triangle.map(|n| self.nodes[n]);
+                    if 0.5 * (b - a).cross(&(c - a)).norm() < 1e-12 {
+                        continue;
+                    }
+                    faces.push(triangle.map(|n| {
+                        *local.entry(n).or_insert_with(|| {
+                            vertices.push(self.nodes[n]);
+                            vertices.len() - 1
+                        })
+                    }));
+                }
+            }
+        }
+        Mesh::new(vertices, faces).map_err(|e| IOError::ParseError {
+            message: format!("GMSH groups {:?}: {}", groups, e),
+        })
+    }
+
+    /// Mesh of each group, keyed by group name
+    pub fn to_collection(&self) -> Result<MeshCollection> {
+        let mut collection = MeshCollection::new();
+        for group in &self.groups {
+            collection.add_mesh(group.name.clone(), self.mesh(&[group.name.as_str()])?)?;
+        }
+        Ok(collection)
+    }
+
+    /// Floating body meshed by the named groups, free in all six rigid-body modes
+    pub fn to_body(&self, name: &str, groups: &[&str]) -> Result<FloatingBody> {
+        let body_error = |e: wavecore_bodies::BodyError| IOError::ParseError {
+            message: format!("GMSH body '{}': {}", name, e),
+        };
+        let mut body = FloatingBody::with_mesh(name.to_string(), BodyMass::default(), self.mesh(groups)?)
+            .map_err(body_error)?;
+        for dof in DOF::all() {
+            body.set_dof(dof, true).map_err(body_error)?;
+        }
+        Ok(body)
+    }
+
+    /// One floating body per group, named after it
+    pub fn to_bodies(&self) -> Result<Vec<FloatingBody>> {
+        self.groups.iter().map(|g| self.to_body(&g.name, &[g.name.as_str()])).collect()
+    }
+}
+
+/// Read a GMSH `.msh` file
+pub fn read_gmsh(path: &str) -> Result<GmshFile> {
+    parse_gmsh(&std::fs::read_to_string(path)?)
+}
+
+/// Parse the text of a GMSH `.msh` file
+pub fn parse_gmsh(text: &str) -> Result<GmshFile> {
+    let sections = sections(text)?;
+    let section = |name: &str| sections.get(name).map(Vec::as_slice).unwrap_or(&[]);
+
+    let header: Vec<&str> = section("MeshFormat").first().map(|l| l.split_whitespace().collect()).unwrap_or_default();
+    let version: f64 = header
+        .first()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| error("missing or invalid $MeshFormat".to_string()))?;
+    if header.get(1) != Some(&"0") {
+        return Err(IOError::InvalidFormat {
+            format: "binary GMSH mesh (only ASCII .msh is supported)".to_string(),
+        });
+    }
+    let legacy = version < 3.0;
+    if !legacy && version >= 5.0 {
+        return Err(IOError::InvalidFormat {
+            format: format!("GMSH mesh format {}", version),
+        });
+    }
+
+    let mut names = HashMap::new();
+    for line in section("PhysicalNames").iter().skip(1) {
+        let mut words = line.splitn(3, char::is_whitespace);
+        let (Some(dim), Some(tag), Some(name)) = (words.next(), words.next(), words.next()) else {
+            return Err(error(format!("invalid physical name '{}'", line)));
+        };
+        if dim == "2" {
+            let tag: i32 = parse(tag)?;
+            names.insert(tag, name.trim().trim_matches('"').to_string());
+        }
+    }
+
+    let (nodes, node_index) = if legacy {
+        legacy_nodes(section("Nodes"))?
+    } else {
+        block_nodes(section("Nodes"), version)?
+    };
+    let index = |tag: &str| -> Result<usize> {
+        let tag: usize = parse(tag)?;
+        node_index.get(&tag).copied().ok_or_else(|| error(format!("element references unknown node {}", tag)))
+    };
+
+    // Surface elements under each physical tag; 0 collects those outside any group
+    let mut grouped: BTreeMap<i32, Vec<Vec<usize>>> = BTreeMap::new();
+    if legacy {
+        for line in section("Elements").iter().skip(1) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let (kind, tag_count) = match words[..] {
+                [_, kind, tags, ..] => (parse::<u32>(kind)?, parse::<usize>(tags)?),
+                _ => return Err(error(format!("invalid element '{}'", line))),
+            };
+            let Some(corners) = surface_corners(kind) else { continue };
+            let first_node = 3 + tag_count;
+            if words.len() < first_node + corners {
+                return Err(error(format!("element '{}' has too few nodes", line)));
+            }
+            let physical = if tag_count > 0 { parse::<i32>(words[3])?.max(0) } else { 0 };
+            let polygon = words[first_node..first_node + corners].iter().map(|w| index(w)).collect::<Result<_>>()?;
+            grouped.entry(physical).or_default().push(polygon);
+        }
+    } else {
+        let physicals = surface_physicals(section("Entities"), version)?;
+        let lines = section("Elements");
+        let mut row = 1;
+        while row < lines.len() {
+            let block: Vec<&str> = lines[row].split_whitespace().collect();
+            if block.len() < 4 {
+                return Err(error(format!("invalid element block '{}'", lines[row])));
+            }
+            // 4.0 lists the entity tag first, 4.1 the dimension
+            let (dim, entity) = if version < 4.1 { (block[1], block[0]) } else { (block[0], block[1]) };
+            let (dim, entity): (u32, i32) = (parse(dim)?, parse(entity)?);
+            let (kind, count): (u32, usize) = (parse(block[2])?, parse(block[3])?);
+            let rows = lines.get(row + 1..row + 1 + count).ok_or_else(|| error("truncated $Elements".to_string()))?;
+            row += 1 + count;
+            let (2, Some(corners)) = (dim, surface_corners(kind)) else { continue };
+            let tags = physicals.get(&entity).cloned().unwrap_or_default();
+            for line in rows {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                if words.len() < 1 + corners {
+                    return Err(error(format!("element '{}' has too few nodes", line)));
+                }
+                let polygon: Vec<usize> = words[1..1 + corners].iter().map(|w| index(w)).collect::<Result<_>>()?;
+                if tags.is_empty() {
+                    grouped.entry(0).or_default().push(polygon);
+                } else {
+                    for &tag in &tags {
+                        grouped.entry(tag).or_default().push(polygon.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|(tag, polygons)| GmshGroup {
+            tag,
+            name: match tag {
+                0 => DEFAULT_GMSH_GROUP.to_string(),
+                _ => names.get(&tag).cloned().unwrap_or_else(|| format!("physical_{}", tag)),
+            },
+            polygons,
+        })
+        .collect();
+    Ok(GmshFile { version, nodes, groups })
+}
+
+fn error(message: String) -> IOError {
+    IOError::ParseError {
+        message: format!("GMSH: {}", message),
+    }
+}
+
+fn parse<T: std::str::FromStr>(word: &str) -> Result<T> {
+    word.parse().map_err(|_| error(format!("invalid number '{}'", word)))
+}
+
+/// Lines between each `$Name` and `$EndName`, keyed by name
+fn sections(text: &str) -> Result<HashMap<&str, Vec<&str>>> {
+    let mut sections = HashMap::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match (line.strip_prefix('$'), current.take()) {
+            (Some(end), Some((name, lines))) if end.strip_prefix("End") == Some(name) => {
+                sections.insert(name, lines);
+            }
+            (_, Some((name, mut lines))) => {
+                lines.push(line);
+                current = Some((name, lines));
+            }
+            (Some(name), None) => current = Some((name, Vec::new())),
+            (None, None) => {}
+        }
+    }
+    match current {
+        Some((name, _)) => Err(error(format!("section ${} is not closed", name))),
+        None => Ok(sections),
+    }
+}
+
+/// Corner nodes of a surface element type, `None` for other dimensions
+fn surface_corners(kind: u32) -> Option<usize> {
+    match kind {
+        // Linear, 6-node and 10-node triangles
+        2 | 9 | 21 => Some(3),
+        // Linear, 9-node and 8-node quadrangles
+        3 | 10 | 16 => Some(4),
+        _ => None,
+    }
+}
+
+type Nodes = (Vec<Point>, HashMap<usize, usize>);
+
+/// `$Nodes` of a 2.2 file: a count then `tag x y z` lines
+fn legacy_nodes(lines: &[&str]) -> Result<Nodes> {
+    let mut nodes = Vec::new();
+    let mut index = HashMap::new();
+    for line in lines.iter().skip(1) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [tag, x, y, z] = words[..] else {
+            return Err(error(format!("invalid node '{}'", line)));
+        };
+        index.insert(parse(tag)?, nodes.len());
+        nodes.push(Point::new(parse(x)?, parse(y)?, parse(z)?));
+    }
+    Ok((nodes, index))
+}
+
+/// `$Nodes` of a 4.x file, in entity blocks
+fn block_nodes(lines: &[&str], version: f64) -> Result<Nodes> {
+    let mut words = lines.iter().flat_map(|l| l.split_whitespace());
+    let mut next = || words.next().ok_or_else(|| error("truncated $Nodes".to_string()));
+    let blocks: usize = parse(next()?)?;
+    next()?;
+    if version >= 4.1 {
+        next()?;
+        next()?;
+    }
+    let mut nodes = Vec::new();
+    let mut index = HashMap::new();
+    for _ in 0..blocks {
+        let (first, second) = (next()?, next()?);
+        let dim: usize = parse(if version < 4.1 { second } else { first })?;
+        let parametric: u32 = parse(next()?)?;
+        let count: usize = parse(next()?)?;
+        let extra = if parametric == 1 { dim } else { 0 };
+        if version < 4.1 {
+            for _ in 0..count {
+                let tag: usize = parse(next()?)?;
+                index.insert(tag, nodes.len());
+                nodes.push(Point::new(parse(next()?)?, parse(next()?)?, parse(next()?)?));
+                for _ in 0..extra {
+                    next()?;
+                }
+            }
+        } else {
+            // 4.1 lists the block's tags before its coordinates
+            let tags = (0..count).map(|_| parse::<usize>(next()?)).collect::<Result<Vec<_>>>()?;
+            for tag in tags {
+                index.insert(tag, nodes.len());
+                nodes.push(Point::new(parse(next()?)?, parse(next()?)?, parse(next()?)?));
+                for _ in 0..extra {
+                    next()?;
+                }
+            }
+        }
+    }
+    Ok((nodes, index))
+}
+
+/// Physical tags of each surface entity in a 4.x `$Entities` section
+fn surface_physicals(lines: &[&str], version: f64) -> Result<HashMap<i32, Vec<i32>>> {
+    let mut physicals = HashMap::new();
+    if lines.is_empty() {
+        return Ok(physicals);
+    }
+    let mut words = lines.iter().flat_map(|l| l.split_whitespace());
+    let mut next = || words.next().ok_or_else(|| error("truncated $Entities".to_string()));
+    let counts = [next()?, next()?, next()?, next()?].map(parse::<usize>);
+    for (dim, count) in counts.into_iter().enumerate() {
+        for _ in 0..count? {
+            let tag: i32 = parse(next()?)?;
+            // Points carry a position in 4.1 and a bounding box otherwise
+            let coordinates = if dim == 0 && version >= 4.1 { 3 } else { 6 };
+            for _ in 0..coordinates {
+                next()?;
+            }
+            let count: usize = parse(next()?)?;
+            let tags = (0..count).map(|_| parse::<i32>(next()?)).collect::<Result<Vec<_>>>()?;
+            if dim > 0 {
+                let bounding: usize = parse(next()?)?;
+                for _ in 0..bounding {
+                    next()?;
+                }
+            }
+            if dim == 2 {
+                physicals.insert(tag, tags.into_iter().map(i32::abs).collect());
+            }
+        }
+    }
+    Ok(physicals)
+}
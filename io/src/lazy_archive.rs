@@ -0,0 +1,658 @@
+//! Lazy slice access into NetCDF result databases
+//!
+//! Reads NetCDF classic files, both CDF-1 and the 64-bit offset CDF-2 used
+//! for databases beyond 2 GiB, by memory-mapping the file and parsing only
+//! its header. A slice request decodes just the big-endian elements it
+//! covers, so serving one frequency of a multi-GB database touches only the
+//! pages that hold it. Variables along the unlimited dimension are read
+//! record by record. [`NetcdfWriter`] writes `f64` datasets as CDF-2.
+//!
+//! NetCDF-4 files are HDF5 containers and need the HDF5 library; they are
+//! rejected with [`IOError::InvalidFormat`].
+
+use super::*;
+use memmap2::Mmap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const MAGIC: &[u8; 3] = b"CDF";
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+const STREAMING: u32 = u32::MAX;
+
+/// External type of a NetCDF variable or attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NcType {
+    /// 8-bit signed integer
+    Byte,
+    /// 8-bit character
+    Char,
+    /// 16-bit signed integer
+    Short,
+    /// 32-bit signed integer
+    Int,
+    /// 32-bit float
+    Float,
+    /// 64-bit float
+    Double,
+}
+
+impl NcType {
+    fn from_code(code: u32) -> Result<Self> {
+        Ok(match code {
+            1 => Self::Byte,
+            2 => Self::Char,
+            3 => Self::Short,
+            4 => Self::Int,
+            5 => Self::Float,
+            6 => Self::Double,
+            _ => {
+                return Err(IOError::ParseError {
+                    message: format!("Unknown NetCDF type {}", code),
+                })
+            }
+        })
+    }
+
+    fn code(self) -> u32 {
+        self as u32 + 1
+    }
+
+    /// Bytes per element
+    pub fn size(self) -> usize {
+        match self {
+            Self::Byte | Self::Char => 1,
+            Self::Short => 2,
+            Self::Int | Self::Float => 4,
+            Self::Double => 8,
+        }
+    }
+
+    /// Numeric value of the big-endian element at the start of `bytes`
+    fn decode(self, bytes: &[u8]) -> f64 {
+        match self {
+            Self::Byte | Self::Char => bytes[0] as i8 as f64,
+            Self::Short => i16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            Self::Int => i32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64,
+            Self::Float => f32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64,
+            Self::Double => f64::from_be_bytes(bytes[..8].try_into().unwrap()),
+        }
+    }
+}
+
+/// Header entry of one variable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetEntry {
+    /// Array shape, the record dimension counting the records present
+    pub shape: Vec<usize>,
+    /// Dimension names
+    pub dims: Vec<String>,
+    /// Element type
+    pub nc_type: NcType,
+    /// Variable attributes; numeric values are comma-separated
+    pub attributes: HashMap<String, String>,
+    /// Byte offset of the first element, of the first record for record variables
+    pub begin: u64,
+    /// Whether the first dimension is the unlimited record dimension
+    pub record: bool,
+}
+
+impl DatasetEntry {
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Whether the dataset has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes of one record, or of the whole variable when it has no record dimension
+    fn inner_size(&self) -> usize {
+        let skip = usize::from(self.record);
+        self.shape[skip..].iter().product::<usize>() * self.nc_type.size()
+    }
+}
+
+/// Header of a NetCDF file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveIndex {
+    /// Dimension names and lengths in definition order, the unlimited one counting its records
+    pub dimensions: Vec<(String, usize)>,
+    /// Global attributes; numeric values are comma-separated
+    pub attributes: HashMap<String, String>,
+    /// Variables by name
+    pub datasets: BTreeMap<String, DatasetEntry>,
+}
+
+/// Selection along one dimension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SliceSpec {
+    /// Whole dimension
+    All,
+    /// Single index (dimension is dropped from the result)
+    Index(usize),
+    /// Half-open range `[start, end)`
+    Range(usize, usize),
+}
+
+/// Writer of `f64` datasets as a NetCDF CDF-2 (64-bit offset) file
+#[derive(Debug, Default)]
+pub struct NetcdfWriter {
+    attributes: BTreeMap<String, String>,
+    dimensions: Vec<(String, usize)>,
+    datasets: Vec<(String, Vec<usize>, DataArray)>,
+}
+
+impl NetcdfWriter {
+    /// Create an empty writer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a writer from an in-memory result archive, naming dimensions `<dataset>_<axis>`
+    pub fn from_archive(archive: &ResultArchive) -> Result<Self> {
+        let mut writer = Self::new();
+        writer.attributes.extend(archive.attributes.clone());
+        writer.set_attribute("producer", &archive.producer);
+        writer.set_attribute("created", &archive.created);
+        writer.set_attribute("schema_version", &archive.schema_version.to_string());
+        for (name, data) in &archive.datasets {
+            writer.add_dataset(name, data.clone(), &[])?;
+        }
+        Ok(writer)
+    }
+
+    /// Set a global attribute
+    pub fn set_attribute(&mut self, key: &str, value: &str) {
+        self.attributes.insert(key.to_string(), value.to_string());
+    }
+
+    /// Add a dataset with named dimensions; datasets sharing a dimension name share its length
+    pub fn add_dataset(&mut self, name: &str, data: DataArray, dims: &[&str]) -> Result<()> {
+        if !dims.is_empty() && dims.len() != data.shape.len() {
+            return Err(IOError::DataArrayError {
+                message: format!("{} dimension names given for {}-d dataset '{}'", dims.len(), data.shape.len(), name),
+            });
+        }
+        if self.datasets.iter().any(|(existing, _, _)| existing == name) {
+            return Err(IOError::DataArrayError {
+                message: format!("Dataset '{}' already added", name),
+            });
+        }
+        let mut ids = Vec::with_capacity(data.shape.len());
+        for (axis, &len) in data.shape.iter().enumerate() {
+            let dim = dims.get(axis).map_or_else(|| format!("{}_{}", name, axis), |d| d.to_string());
+            let id = match self.dimensions.iter().position(|(d, _)| *d == dim) {
+                Some(id) if self.dimensions[id].1 == len => id,
+                Some(id) => {
+                    return Err(IOError::DataArrayError {
+                        message: format!("Dimension '{}' has length {}, not {} as in '{}'", dim, self.dimensions[id].1, len, name),
+                    })
+                }
+                None => {
+                    self.dimensions.push((dim, len));
+                    self.dimensions.len() - 1
+                }
+            };
+            ids.push(id);
+        }
+        self.datasets.push((name.to_string(), ids, data));
+        Ok(())
+    }
+
+    /// Write the file
+    pub fn write(&self, path: &str) -> Result<()> {
+        // Offsets are fixed-width, so a first pass gives the header length
+        let header_len = self.header(&vec![0; self.datasets.len()]).len() as u64;
+        let begins: Vec<u64> = self
+            .datasets
+            .iter()
+            .scan(header_len, |next, (_, _, data)| {
+                let begin = *next;
+                *next += (data.data.len() * 8) as u64;
+                Some(begin)
+            })
+            .collect();
+
+        let mut file = std::io::BufWriter::new(File::create(path).map_err(|e| IOError::WriteError {
+            message: format!("Failed to create {}: {}", path, e),
+        })?);
+        file.write_all(&self.header(&begins))?;
+        for (_, _, data) in &self.datasets {
+            for value in &data.data {
+                file.write_all(&value.to_be_bytes())?;
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    fn header(&self, begins: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(2);
+        put_u32(&mut out, 0);
+
+        put_list(&mut out, NC_DIMENSION, self.dimensions.len());
+        for (name, len) in &self.dimensions {
+            put_name(&mut out, name);
+            put_u32(&mut out, *len as u32);
+        }
+
+        put_list(&mut out, NC_ATTRIBUTE, self.attributes.len());
+        for (key, value) in &self.attributes {
+            put_name(&mut out, key);
+            put_u32(&mut out, NcType::Char.code());
+            put_name(&mut out, value);
+        }
+
+        put_list(&mut out, NC_VARIABLE, self.datasets.len());
+        for ((name, ids, data), begin) in self.datasets.iter().zip(begins) {
+            put_name(&mut out, name);
+            put_u32(&mut out, ids.len() as u32);
+            for id in ids {
+                put_u32(&mut out, *id as u32);
+            }
+            put_list(&mut out, NC_ATTRIBUTE, 0);
+            put_u32(&mut out, NcType::Double.code());
+            // vsize saturates for variables beyond 4 GiB, as the format specifies
+            put_u32(&mut out, u32::try_from(data.data.len() * 8).unwrap_or(u32::MAX));
+            out.extend_from_slice(&begin.to_be_bytes());
+        }
+        out
+    }
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Tag and count of a header list, or the absent marker when empty
+fn put_list(out: &mut Vec<u8>, tag: u32, len: usize) {
+    put_u32(out, if len == 0 { 0 } else { tag });
+    put_u32(out, len as u32);
+}
+
+/// Length-prefixed text padded to four bytes
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    put_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+    out.resize(out.len().next_multiple_of(4), 0);
+}
+
+/// Cursor over a NetCDF header
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    offset64: bool,
+}
+
+impl HeaderReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self.at.checked_add(len).filter(|&end| end <= self.bytes.len()).ok_or_else(|| IOError::ParseError {
+            message: "Truncated NetCDF header".to_string(),
+        })?;
+        let bytes = &self.bytes[self.at..end];
+        self.at = end;
+        Ok(bytes)
+    }
+
+    fn padded(&mut self, len: usize) -> Result<&[u8]> {
+        let start = self.at;
+        self.take(len.next_multiple_of(4))?;
+        Ok(&self.bytes[start..start + len])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn offset(&mut self) -> Result<u64> {
+        if self.offset64 {
+            Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+        } else {
+            self.u32().map(u64::from)
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        Ok(String::from_utf8_lossy(self.padded(len)?).into_owned())
+    }
+
+    /// Element count of a list tagged `tag`, zero when absent
+    fn list(&mut self, tag: u32) -> Result<usize> {
+        let (found, len) = (self.u32()?, self.u32()? as usize);
+        if found != tag && !(found == 0 && len == 0) {
+            return Err(IOError::ParseError {
+                message: format!("Expected NetCDF list tag {:#x}, found {:#x}", tag, found),
+            });
+        }
+        Ok(len)
+    }
+
+    fn attributes(&mut self) -> Result<HashMap<String, String>> {
+        let mut attributes = HashMap::new();
+        for _ in 0..self.list(NC_ATTRIBUTE)? {
+            let name = self.name()?;
+            let nc_type = NcType::from_code(self.u32()?)?;
+            let len = self.u32()? as usize;
+            let bytes = self.padded(len * nc_type.size())?;
+            let value = match nc_type {
+                NcType::Char => String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string(),
+                _ => bytes.chunks(nc_type.size()).map(|b| nc_type.decode(b).to_string()).collect::<Vec<_>>().join(", "),
+            };
+            attributes.insert(name, value);
+        }
+        Ok(attributes)
+    }
+}
+
+/// Memory-mapped NetCDF reader
+pub struct LazyArchive {
+    mmap: Mmap,
+    index: ArchiveIndex,
+    record_size: usize,
+}
+
+impl LazyArchive {
+    /// Open a NetCDF classic file, reading only its header
+    pub fn open(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Err(IOError::FileNotFound {
+                path: path.to_string(),
+            });
+        }
+        let file = File::open(path)?;
+        // Safety: the file is treated as read-only for the lifetime of the map
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let offset64 = match mmap.get(..4) {
+            Some([b'C', b'D', b'F', 1]) => false,
+            Some([b'C', b'D', b'F', 2]) => true,
+            Some([0x89, b'H', b'D', b'F']) => {
+                return Err(IOError::InvalidFormat {
+                    format: "NetCDF-4/HDF5 (only NetCDF classic files are read)".to_string(),
+                })
+            }
+            _ => {
+                return Err(IOError::InvalidFormat {
+                    format: "not a NetCDF classic file".to_string(),
+                })
+            }
+        };
+        let mut header = HeaderReader { bytes: &mmap, at: 4, offset64 };
+        let numrecs = header.u32()?;
+
+        let mut dimensions = Vec::new();
+        let mut record_dim = None;
+        for id in 0..header.list(NC_DIMENSION)? {
+            let name = header.name()?;
+            let len = header.u32()? as usize;
+            if len == 0 {
+                record_dim = Some(id);
+            }
+            dimensions.push((name, len));
+        }
+        let attributes = header.attributes()?;
+
+        let mut datasets = BTreeMap::new();
+        for _ in 0..header.list(NC_VARIABLE)? {
+            let name = header.name()?;
+            let mut ids = Vec::new();
+            for _ in 0..header.u32()? {
+                let id = header.u32()? as usize;
+                if id >= dimensions.len() {
+                    return Err(IOError::ParseError {
+                        message: format!("Variable '{}' refers to undefined dimension {}", name, id),
+                    });
+                }
+                ids.push(id);
+            }
+            let attributes = header.attributes()?;
+            let nc_type = NcType::from_code(header.u32()?)?;
+            let _vsize = header.u32()?;
+            let begin = header.offset()?;
+            let entry = DatasetEntry {
+                shape: ids.iter().map(|&id| dimensions[id].1).collect(),
+                dims: ids.iter().map(|&id| dimensions[id].0.clone()).collect(),
+                nc_type,
+                attributes,
+                begin,
+                record: ids.first().is_some_and(|&id| Some(id) == record_dim),
+            };
+            datasets.insert(name, entry);
+        }
+
+        // Records hold every record variable in turn, padded unless there is only one
+        let record_vars: Vec<&DatasetEntry> = datasets.values().filter(|e| e.record).collect();
+        let record_size = match record_vars.as_slice() {
+            [only] => only.inner_size(),
+            vars => vars.iter().map(|e| e.inner_size().next_multiple_of(4)).sum(),
+        };
+        let records = match (numrecs, record_vars.iter().map(|e| e.begin).min()) {
+            (STREAMING, Some(first)) if record_size > 0 => (mmap.len() as u64).saturating_sub(first) as usize / record_size,
+            (STREAMING, _) => 0,
+            (n, _) => n as usize,
+        };
+        if let Some(id) = record_dim {
+            dimensions[id].1 = records;
+        }
+        for entry in datasets.values_mut().filter(|e| e.record) {
+            entry.shape[0] = records;
+        }
+
+        for (name, entry) in &datasets {
+            let end = match (entry.record, entry.shape.first()) {
+                (true, Some(&n)) if n > 0 => entry.begin as usize + (n - 1) * record_size + entry.inner_size(),
+                (true, _) => 0,
+                (false, _) => entry.begin as usize + entry.inner_size(),
+            };
+            if end > mmap.len() {
+                return Err(IOError::ParseError {
+                    message: format!("Dataset '{}' extends past end of file", name),
+                });
+            }
+        }
+
+        let index = ArchiveIndex { dimensions, attributes, datasets };
+        Ok(Self { mmap, index, record_size })
+    }
+
+    /// File header
+    pub fn index(&self) -> &ArchiveIndex {
+        &self.index
+    }
+
+    /// Dataset names in sorted order
+    pub fn dataset_names(&self) -> Vec<&str> {
+        self.index.datasets.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Header entry of a dataset
+    pub fn entry(&self, name: &str) -> Result<&DatasetEntry> {
+        self.index.datasets.get(name).ok_or_else(|| IOError::DataArrayError {
+            message: format!("Dataset '{}' not found", name),
+        })
+    }
+
+    /// Read a full dataset
+    pub fn read(&self, name: &str) -> Result<DataArray> {
+        let ndim = self.entry(name)?.shape.len();
+        self.read_slice(name, &vec![SliceSpec::All; ndim])
+    }
+
+    /// Read a hyperslab as `f64`; one spec per dimension
+    pub fn read_slice(&self, name: &str, spec: &[SliceSpec]) -> Result<DataArray> {
+        let entry = self.entry(name)?;
+        if spec.len() != entry.shape.len() {
+            return Err(IOError::DataArrayError {
+                message: format!("Expected {} slice specs for '{}', got {}", entry.shape.len(), name, spec.len()),
+            });
+        }
+        if entry.nc_type == NcType::Char {
+            return Err(IOError::DataArrayError {
+                message: format!("Dataset '{}' holds text, not numbers", name),
+            });
+        }
+
+        // Resolve each dimension to a [start, end) range
+        let mut ranges = Vec::with_capacity(spec.len());
+        let mut out_shape = Vec::new();
+        for (d, (s, &n)) in spec.iter().zip(&entry.shape).enumerate() {
+            let (start, end) = match *s {
+                SliceSpec::All => (0, n),
+                SliceSpec::Index(i) => (i, i + 1),
+                SliceSpec::Range(a, b) => (a, b),
+            };
+            if start >= end || end > n {
+                return Err(IOError::DataArrayError {
+                    message: format!("Slice {:?} out of bounds for dimension {} of size {}", s, d, n),
+                });
+            }
+            if !matches!(s, SliceSpec::Index(_)) {
+                out_shape.push(end - start);
+            }
+            ranges.push((start, end));
+        }
+
+        // Byte strides of the stored array; records are `record_size` apart
+        let size = entry.nc_type.size();
+        let mut strides = vec![size; entry.shape.len()];
+        for d in (0..entry.shape.len().saturating_sub(1)).rev() {
+            strides[d] = strides[d + 1] * entry.shape[d + 1];
+        }
+        if entry.record {
+            strides[0] = self.record_size;
+        }
+
+        let count: usize = ranges.iter().map(|(a, b)| b - a).product();
+        let mut values = Vec::with_capacity(count);
+        let mut cursor: Vec<usize> = ranges.iter().map(|(a, _)| *a).collect();
+        for _ in 0..count {
+            let at = entry.begin as usize + cursor.iter().zip(&strides).map(|(i, s)| i * s).sum::<usize>();
+            values.push(entry.nc_type.decode(&self.mmap[at..at + size]));
+
+            // Advance the multi-index, last dimension fastest
+            for d in (0..cursor.len()).rev() {
+                cursor[d] += 1;
+                if cursor[d] < ranges[d].1 {
+                    break;
+                }
+                cursor[d] = ranges[d].0;
+            }
+        }
+
+        if out_shape.is_empty() {
+            out_shape.push(1);
+        }
+        DataArray::new(&out_shape, &values)
+    }
+
+    /// Read a slice selecting single indices along named dimensions
+    pub fn select(&self, name: &str, selection: &[(&str, usize)]) -> Result<DataArray> {
+        let entry = self.entry(name)?;
+        let mut spec = vec![SliceSpec::All; entry.shape.len()];
+        for (dim, index) in selection {
+            let d = entry.dims.iter().position(|n| n == dim).ok_or_else(|| IOError::DataArrayError {
+                message: format!("Dataset '{}' has no dimension '{}'", name, dim),
+            })?;
+            spec[d] = SliceSpec::Index(*index);
+        }
+        self.read_slice(name, &spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> String {
+        std::env::temp_dir().join(format!("wavecore_{}_{}.nc", name, std::process::id())).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_lazy_slice_access() {
+        let path = scratch("lazy_slices");
+        // 3 frequencies × 2 headings × 6 dofs
+        let values: Vec<f64> = (0..36).map(|i| i as f64).collect();
+        let data = DataArray::new(&[3, 2, 6], &values).unwrap();
+
+        let mut writer = NetcdfWriter::new();
+        writer.set_attribute("title", "tetrahedron");
+        writer.add_dataset("excitation", data, &["frequency", "heading", "dof"]).unwrap();
+        writer.add_dataset("period", DataArray::new(&[3], &[4.0, 6.0, 8.0]).unwrap(), &["frequency"]).unwrap();
+        assert!(writer.add_dataset("heading", DataArray::new(&[3], &[0.0; 3]).unwrap(), &["heading"]).is_err());
+        writer.write(&path).unwrap();
+
+        let archive = LazyArchive::open(&path).unwrap();
+        assert_eq!(archive.dataset_names(), vec!["excitation", "period"]);
+        assert_eq!(archive.index().attributes["title"], "tetrahedron");
+        assert_eq!(archive.index().dimensions[1], ("heading".to_string(), 2));
+
+        let slice = archive.select("excitation", &[("frequency", 1), ("heading", 0)]).unwrap();
+        assert_eq!(slice.shape, vec![6]);
+        assert_eq!(slice.data, vec![12.0, 13.0, 14.0, 15.0, 16.0, 17.0]);
+
+        let block = archive.read_slice("excitation", &[SliceSpec::Range(1, 3), SliceSpec::Index(1), SliceSpec::Range(0, 2)]).unwrap();
+        assert_eq!(block.shape, vec![2, 2]);
+        assert_eq!(block.data, vec![18.0, 19.0, 30.0, 31.0]);
+
+        assert_eq!(archive.read("period").unwrap().data, vec![4.0, 6.0, 8.0]);
+        assert!(archive.read_slice("period", &[SliceSpec::Index(3)]).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_classic_records_and_types() {
+        // CDF-1 file as other tools write it: time(unlimited) × dof(2) floats and a short coordinate
+        let mut bytes = b"CDF\x01".to_vec();
+        put_u32(&mut bytes, 3);
+        put_list(&mut bytes, NC_DIMENSION, 2);
+        for (name, len) in [("time", 0), ("dof", 2)] {
+            put_name(&mut bytes, name);
+            put_u32(&mut bytes, len);
+        }
+        put_list(&mut bytes, NC_ATTRIBUTE, 1);
+        put_name(&mut bytes, "rho");
+        put_u32(&mut bytes, NcType::Double.code());
+        put_u32(&mut bytes, 1);
+        bytes.extend_from_slice(&1025.0f64.to_be_bytes());
+        put_list(&mut bytes, NC_VARIABLE, 2);
+        let header_len = bytes.len() + 36 + 44;
+        for (name, ids, nc_type, vsize, begin) in [("dof", vec![1], NcType::Short, 4, header_len), ("force", vec![0, 1], NcType::Float, 8, header_len + 4)] {
+            put_name(&mut bytes, name);
+            put_u32(&mut bytes, ids.len() as u32);
+            ids.iter().for_each(|&id| put_u32(&mut bytes, id));
+            put_list(&mut bytes, NC_ATTRIBUTE, 0);
+            put_u32(&mut bytes, nc_type.code());
+            put_u32(&mut bytes, vsize);
+            put_u32(&mut bytes, begin as u32);
+        }
+        assert_eq!(bytes.len(), header_len);
+        bytes.extend_from_slice(&[0, 3, 0, 5]);
+        for value in [1.5f32, -2.0, 2.5, -4.0, 3.5, -6.0] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        let path = scratch("classic_records");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let archive = LazyArchive::open(&path).unwrap();
+        assert_eq!(archive.index().attributes["rho"], "1025");
+        assert_eq!(archive.read("dof").unwrap().data, vec![3.0, 5.0]);
+        let force = archive.entry("force").unwrap();
+        assert!(force.record);
+        assert_eq!(force.shape, vec![3, 2]);
+        assert_eq!(archive.select("force", &[("dof", 1)]).unwrap().data, vec![-2.0, -4.0, -6.0]);
+        assert_eq!(archive.select("force", &[("time", 2)]).unwrap().data, vec![3.5, -6.0]);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(LazyArchive::open(&path).is_err());
+    }
+}
@@ -9,9 +9,20 @@
 //! 
 //! - **File I/O**: Multiple format support (STL, OBJ, NEMOH, WAMIT)
 //! - **Data Arrays**: XArray-like functionality for efficient data handling
-//! - **Serialization**: JSON, YAML, binary formats
+//! - **Serialization**: JSON, YAML (`yaml` feature), binary formats
 //! - **Memory Mapping**: Efficient large file handling
 //! - **Format Conversion**: Between different file formats
+//! - **Result Archives**: Versioned archives with schema migration
+//! - **Lazy Access**: Memory-mapped slice reads from NetCDF classic result databases
+//! - **Sweep Checkpoints**: JSON checkpoint files for resuming interrupted BEM sweeps
+//! - **Panel Data**: Per-panel pressures and source strengths as data arrays for load transfer
+//! - **Out-of-Core Matrices**: Tiled dense matrices in memory-mapped files with streaming LU
+//! - **Matrix Caching**: Binary files of dense matrices and LU factors reused across runs
+//! - **STL Exchange**: Binary and ASCII STL with vertex welding and unit scaling
+//! - **OBJ Import**: Named groups and polygon faces mapped to mesh collections
+//! - **GMSH Import**: `.msh` 2.2/4.x surface meshes with physical groups mapped to bodies
+//! - **CAD Import**: Trimmed NURBS surfaces from IGES and STEP panelled by curvature
+//! - **VTK Export**: `.vtu` meshes with per-panel pressure and source strength fields for ParaView
 //! 
 //! ## Example
 //! 
@@ -34,14 +45,36 @@ pub mod file_io;
 pub mod xarray;
 pub mod wamit;
 pub mod nemoh;
+pub mod archive;
+pub mod lazy_archive;
+mod timestamp;
+pub mod checkpoint;
+pub mod conversions;
+pub mod ooc;
+pub mod matrix_file;
+pub mod stl;
+pub mod obj;
+pub mod gmsh;
+pub mod vtk;
+pub mod cad;
 
 pub use file_io::*;
 pub use wamit::*;
 pub use nemoh::*;
 pub use xarray::*;
+pub use archive::*;
+pub use lazy_archive::*;
+pub use checkpoint::FileCheckpoint;
+pub use conversions::{ToDataArray, PANEL_COLUMNS};
+pub use ooc::{OocLU, OocMatrix, DEFAULT_OOC_TILE};
+pub use matrix_file::BinaryMatrix;
+pub use stl::{read_stl, write_stl, parse_stl, stl_to_bytes, StlEncoding, StlOptions};
+pub use obj::{read_obj, parse_obj, ObjFile, ObjGroup, DEFAULT_OBJ_GROUP};
+pub use gmsh::{read_gmsh, parse_gmsh, GmshFile, GmshGroup, DEFAULT_GMSH_GROUP};
+pub use vtk::{export_vtk, panel_fields, vtu_string, VtkField};
+pub use cad::{read_iges, parse_iges, read_step, parse_step, CadFile};
 
 use thiserror::Error;
-use ndarray::Array;
 use serde::{Serialize, Deserialize};
 
 /// Error types for I/O operations
@@ -65,6 +98,7 @@ pub enum IOError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
     
+    #[cfg(feature = "yaml")]
     #[error("YAML error: {0}")]
     YamlError(#[from] serde_yaml::Error),
     
@@ -73,6 +107,9 @@ pub enum IOError {
     
     #[error("Mesh error: {0}")]
     MeshError(#[from] wavecore_meshes::MeshError),
+    
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] wavecore_matrices::MatrixError),
 }
 
 /// Result type for I/O operations
@@ -0,0 +1,204 @@
+//! Binary files of dense matrices and LU factorizations
+//!
+//! Assembling the influence matrices of a BEM problem costs far more than
+//! solving with them, and factorizing costs more than a solve, so a run that
+//! only changes the right-hand side (a new wave heading) can read both back
+//! instead of recomputing them.
+//!
+//! Layout: `b"WCMX"` | `u32` version | `u32` kind | `u32` reserved | `u64` rows |
+//! `u64` cols | entries row-major as little-endian `f64` (complex entries as
+//! real, imaginary pairs) | for LU factors, one `u64` row interchange per row.
+
+use super::*;
+use num_complex::Complex64;
+use std::path::Path;
+use wavecore_matrices::{ComplexMatrix, DenseLU, Matrix};
+
+const MAGIC: &[u8; 4] = b"WCMX";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 32;
+
+/// Contents of a matrix file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum Kind {
+    Real = 0,
+    Complex = 1,
+    RealLU = 2,
+    ComplexLU = 3,
+}
+
+impl Kind {
+    fn from_u32(value: u32) -> Option<Self> {
+        [Kind::Real, Kind::Complex, Kind::RealLU, Kind::ComplexLU].into_iter().find(|kind| *kind as u32 == value)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Real => "real matrix",
+            Kind::Complex => "complex matrix",
+            Kind::RealLU => "real LU factorization",
+            Kind::ComplexLU => "complex LU factorization",
+        }
+    }
+}
+
+/// Matrix that can be written to and read from the binary matrix format
+pub trait BinaryMatrix: Sized {
+    /// Encode in the binary matrix format
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Decode from the binary matrix format
+    fn from_bytes(bytes: &[u8]) -> Result<Self>;
+
+    /// Write to a file at `path`, replacing any existing file
+    fn write_binary(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Read a file written by [`BinaryMatrix::write_binary`]
+    fn read_binary(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Err(IOError::FileNotFound { path: path.to_string() });
+        }
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+impl BinaryMatrix for Matrix {
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(Kind::Real, self.rows, self.cols, &self.data, &[])
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (rows, cols, data, _) = decode(bytes, Kind::Real)?;
+        Ok(Matrix::from_vec(rows, cols, data)?)
+    }
+}
+
+impl BinaryMatrix for ComplexMatrix {
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(Kind::Complex, self.rows, self.cols, &self.data, &[])
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (rows, cols, data, _) = decode(bytes, Kind::Complex)?;
+        Ok(ComplexMatrix::from_vec(rows, cols, data)?)
+    }
+}
+
+impl BinaryMatrix for DenseLU {
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(Kind::RealLU, self.size(), self.size(), self.factors(), self.pivots())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (n, _, factors, pivots) = decode(bytes, Kind::RealLU)?;
+        Ok(DenseLU::from_parts(n, factors, pivots)?)
+    }
+}
+
+impl BinaryMatrix for DenseLU<Complex64> {
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(Kind::ComplexLU, self.size(), self.size(), self.factors(), self.pivots())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (n, _, factors, pivots) = decode(bytes, Kind::ComplexLU)?;
+        Ok(DenseLU::from_parts(n, factors, pivots)?)
+    }
+}
+
+/// Matrix entry stored as little-endian `f64` parts
+trait Entry: Sized {
+    const LEN: usize;
+
+    fn write(&self, bytes: &mut Vec<u8>);
+
+    fn read(bytes: &[u8]) -> Self;
+}
+
+impl Entry for f64 {
+    const LEN: usize = 8;
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+}
+
+impl Entry for Complex64 {
+    const LEN: usize = 16;
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        self.re.write(bytes);
+        self.im.write(bytes);
+    }
+
+    fn read(bytes: &[u8]) -> Self {
+        Complex64::new(f64::read(bytes), f64::read(&bytes[8..]))
+    }
+}
+
+fn encode<T: Entry>(kind: Kind, rows: usize, cols: usize, data: &[T], pivots: &[usize]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + data.len() * T::LEN + pivots.len() * 8);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(kind as u32).to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(rows as u64).to_le_bytes());
+    bytes.extend_from_slice(&(cols as u64).to_le_bytes());
+    data.iter().for_each(|entry| entry.write(&mut bytes));
+    pivots.iter().for_each(|&p| bytes.extend_from_slice(&(p as u64).to_le_bytes()));
+    bytes
+}
+
+type Decoded<T> = (usize, usize, Vec<T>, Vec<usize>);
+
+fn decode<T: Entry>(bytes: &[u8], expected: Kind) -> Result<Decoded<T>> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC {
+        return Err(IOError::InvalidFormat {
+            format: "not a WaveCore matrix file".to_string(),
+        });
+    }
+    let word = |k: usize| u32::from_le_bytes(bytes[4 + 4 * k..8 + 4 * k].try_into().unwrap());
+    if word(0) != VERSION {
+        return Err(IOError::InvalidFormat {
+            format: format!("matrix file v{} is not supported", word(0)),
+        });
+    }
+    match Kind::from_u32(word(1)) {
+        Some(kind) if kind == expected => {}
+        Some(kind) => {
+            return Err(IOError::InvalidFormat {
+                format: format!("matrix file holds a {}, not a {}", kind.name(), expected.name()),
+            })
+        }
+        None => {
+            return Err(IOError::InvalidFormat {
+                format: format!("unknown matrix file kind {}", word(1)),
+            })
+        }
+    }
+    let field = |k: usize| u64::from_le_bytes(bytes[16 + 8 * k..24 + 8 * k].try_into().unwrap()) as usize;
+    let (rows, cols) = (field(0), field(1));
+    let pivot_count = if matches!(expected, Kind::RealLU | Kind::ComplexLU) { rows } else { 0 };
+    let entries = rows.checked_mul(cols).filter(|count| count.checked_mul(T::LEN).is_some());
+    let length = entries.and_then(|count| (HEADER_LEN + count * T::LEN).checked_add(pivot_count.checked_mul(8)?));
+    let (entries, length) = match (entries, length) {
+        (Some(entries), Some(length)) if length == bytes.len() => (entries, length),
+        _ => {
+            return Err(IOError::ParseError {
+                message: "Truncated or oversized matrix file".to_string(),
+            })
+        }
+    };
+    let data_end = HEADER_LEN + entries * T::LEN;
+    let data = bytes[HEADER_LEN..data_end].chunks_exact(T::LEN).map(T::read).collect();
+    let pivots = bytes[data_end..length].chunks_exact(8).map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()) as usize).collect();
+    Ok((rows, cols, data, pivots))
+}
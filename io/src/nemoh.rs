@@ -1,6 +1,8 @@
-use crate::{IOError, Result};
+use crate::{timestamp, IOError, Result};
 use wavecore_meshes::{Mesh, Panel};
-use wavecore_bem::BEMResult;
+use wavecore_bem::{BEMResult, ProblemType};
+use wavecore_bem::solver::{AssemblyConfig, BEMProblem};
+use wavecore_bodies::{FloatingBody, MassProperties as BodyMass, DOF};
 use nalgebra::Point3;
 use std::collections::HashMap;
 use std::fs::File;
@@ -19,6 +21,7 @@ pub struct NemohInterface {
 }
 
 /// NEMOH configuration parser
+#[allow(dead_code)]
 pub struct NemohConfigParser {
     /// Parsing options
     options: NemohParsingOptions,
@@ -27,6 +30,7 @@ pub struct NemohConfigParser {
 }
 
 /// NEMOH mesh converter
+#[allow(dead_code)]
 pub struct NemohMeshConverter {
     /// Conversion settings
     settings: ConversionSettings,
@@ -35,6 +39,7 @@ pub struct NemohMeshConverter {
 }
 
 /// NEMOH results processor
+#[allow(dead_code)]
 pub struct NemohResultsProcessor {
     /// Processing options
     options: ProcessingOptions,
@@ -327,6 +332,56 @@ pub struct OutputFormats {
     pub csv: bool,
 }
 
+/// BEM problems of a NEMOH case: bodies with their meshes and the wave conditions
+#[derive(Debug, Clone)]
+pub struct NemohCase {
+    /// Bodies with meshes and the degrees of freedom of the case enabled
+    pub bodies: Vec<FloatingBody>,
+    /// Wave frequencies (rad/s)
+    pub frequencies: Vec<f64>,
+    /// Wave directions (radians)
+    pub directions: Vec<f64>,
+    /// Water depth (m), `None` for deep water
+    pub depth: Option<f64>,
+    /// Water density (kg/m³)
+    pub rho: f64,
+    /// Gravitational acceleration (m/s²)
+    pub g: f64,
+}
+
+impl NemohCase {
+    /// Global indices of the enabled modes; each body takes all modes of the bodies before it
+    pub fn modes(&self) -> Vec<usize> {
+        let mut offset = 0;
+        let mut modes = Vec::new();
+        for body in &self.bodies {
+            modes.extend(DOF::all().into_iter().filter(|dof| body.is_dof_enabled(dof)).map(|dof| offset + dof.index()));
+            offset += body.n_modes();
+        }
+        modes
+    }
+
+    /// One combined radiation-diffraction problem per frequency and direction
+    pub fn problems(&self) -> Vec<BEMProblem> {
+        let modes = self.modes();
+        self.frequencies
+            .iter()
+            .flat_map(|&frequency| self.directions.iter().map(move |&direction| (frequency, direction)))
+            .map(|(frequency, direction)| BEMProblem {
+                bodies: self.bodies.clone(),
+                problem_type: ProblemType::Combined { frequency, direction, modes: modes.clone() },
+                assembly_config: AssemblyConfig::default(),
+            })
+            .collect()
+    }
+}
+
+impl Default for NemohInterface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NemohInterface {
     /// Create new NEMOH interface
     pub fn new() -> Self {
@@ -347,6 +402,37 @@ impl NemohInterface {
         self.config_parser.parse_config(path)
     }
 
+    /// Read a `Nemoh.cal` case and the meshes it names, relative to its directory
+    pub fn read_nemoh_case(&self, path: &Path) -> Result<NemohCase> {
+        let config = self.config_parser.parse_config(path)?;
+        let directory = path.parent().unwrap_or(Path::new("."));
+        let bodies = config
+            .bodies
+            .iter()
+            .map(|body| {
+                let mesh = self.mesh_converter.read_mesh(&directory.join(&body.mesh_file))?;
+                let mass = BodyMass { center_of_gravity: [body.cog.x, body.cog.y, body.cog.z], ..BodyMass::default() };
+                let body_error = |e: wavecore_bodies::BodyError| IOError::ParseError {
+                    message: format!("NEMOH body '{}': {}", body.name, e),
+                };
+                let mut floating = FloatingBody::with_mesh(body.name.clone(), mass, mesh).map_err(body_error)?;
+                for dof in &body.dofs {
+                    floating.set_dof(DOF::all()[dof.index - 1], true).map_err(body_error)?;
+                }
+                Ok(floating)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let environment = &config.environment;
+        Ok(NemohCase {
+            bodies,
+            frequencies: environment.frequencies.clone(),
+            directions: environment.directions.iter().map(|d| d.to_radians()).collect(),
+            depth: (environment.depth > 0.0).then_some(environment.depth),
+            rho: environment.rho,
+            g: environment.g,
+        })
+    }
+
     /// Export NEMOH-compatible results
     pub fn export_nemoh_results(&self, results: &BEMResult) -> Result<NemohOutput> {
         self.results_processor.convert_results(results)
@@ -370,12 +456,12 @@ impl NemohInterface {
     }
 
     /// Run NEMOH computation
-    pub fn run_nemoh(&self, config_path: &Path, output_dir: &Path) -> Result<NemohOutput> {
+    pub fn run_nemoh(&self, _config_path: &Path, _output_dir: &Path) -> Result<NemohOutput> {
         // This would execute NEMOH and parse results
         // Placeholder implementation
         let metadata = NemohMetadata {
             version: "3.0".to_string(),
-            date: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            date: timestamp::now_utc(timestamp::DATE_TIME).unwrap_or_default(),
             computation_time: 120.0,
             num_bodies: 1,
             total_panels: 1000,
@@ -404,6 +490,12 @@ impl NemohInterface {
     }
 }
 
+impl Default for NemohConfigParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NemohConfigParser {
     /// Create new NEMOH config parser
     pub fn new() -> Self {
@@ -413,12 +505,15 @@ impl NemohConfigParser {
         }
     }
 
-    /// Parse NEMOH configuration file
+    /// Parse NEMOH configuration file, either a `Nemoh.cal` or a keyword file written by [`Self::write_config`]
     pub fn parse_config(&self, path: &Path) -> Result<NemohConfig> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         
         let mut lines: Vec<String> = reader.lines().collect::<std::result::Result<_, _>>()?;
+        if lines.iter().any(|line| line.trim_start().starts_with("---")) {
+            return self.parse_cal(&lines.join("\n"));
+        }
         
         // Remove comments and empty lines
         lines.retain(|line| {
@@ -442,6 +537,123 @@ impl NemohConfigParser {
         })
     }
 
+    /// Parse the positional `Nemoh.cal` format
+    ///
+    /// Section headers (`--- … ---`) are skipped and each remaining line is
+    /// read in order, values first and a `!` comment after. A depth of 0
+    /// means deep water. The frequency line may start with a unit flag
+    /// (1 rad/s, 2 Hz, 3 period in s), as in NEMOH v3.
+    pub fn parse_cal(&self, content: &str) -> Result<NemohConfig> {
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with("---"));
+        let mut next = |what: &str| {
+            lines.next().ok_or_else(|| IOError::ParseError { message: format!("Nemoh.cal ends before the {}", what) })
+        };
+        let numbers = |(number, line): (usize, &str), count: usize, what: &str| -> Result<Vec<f64>> {
+            let values: Vec<f64> = line.split_whitespace().take(count).map_while(|w| w.parse().ok()).collect();
+            if values.len() < count {
+                return Err(IOError::ParseError {
+                    message: format!("Nemoh.cal line {}: expected {} for the {}", number + 1, count, what),
+                });
+            }
+            Ok(values)
+        };
+        let count = |line, what: &str| -> Result<usize> { Ok(numbers(line, 1, what)?[0] as usize) };
+
+        let rho = numbers(next("density")?, 1, "density")?[0];
+        let g = numbers(next("gravity")?, 1, "gravity")?[0];
+        let depth = numbers(next("depth")?, 1, "depth")?[0];
+        numbers(next("wave measurement point")?, 2, "wave measurement point")?;
+
+        let mut bodies = Vec::new();
+        for _ in 0..count(next("number of bodies")?, "number of bodies")? {
+            let mesh_file = next("mesh file")?.1.split_whitespace().next().unwrap_or_default().to_string();
+            numbers(next("point and panel counts")?, 2, "point and panel counts")?;
+            let mut dofs = Vec::new();
+            let mut cog = Point3::origin();
+            for _ in 0..count(next("number of degrees of freedom")?, "number of degrees of freedom")? {
+                let line = next("degree of freedom")?;
+                let v = numbers(line, 7, "degree of freedom")?;
+                let axis = (0..3).find(|&k| v[1 + k] == 1.0 && (0..3).all(|j| j == k || v[1 + j] == 0.0));
+                let rotation = v[0] == 2.0;
+                let (Some(axis), true) = (axis, v[0] == 1.0 || rotation) else {
+                    return Err(IOError::ParseError {
+                        message: format!("Nemoh.cal line {}: only motions along or about x, y and z are supported", line.0 + 1),
+                    });
+                };
+                let index = axis + if rotation { 4 } else { 1 };
+                let center = rotation.then(|| Point3::new(v[4], v[5], v[6]));
+                if let Some(center) = center {
+                    cog = center;
+                }
+                dofs.push(DegreeOfFreedom {
+                    dof_type: ["surge", "sway", "heave", "roll", "pitch", "yaw"][index - 1].to_string(),
+                    index,
+                    direction: Point3::new(v[1], v[2], v[3]),
+                    center,
+                });
+            }
+            // Generalised forces mirror the motions and additional lines are free text
+            for what in ["number of generalised forces", "number of additional lines"] {
+                for _ in 0..count(next(what)?, what)? {
+                    next(what)?;
+                }
+            }
+            let name = Path::new(&mesh_file).file_stem().and_then(|s| s.to_str()).unwrap_or("body").to_string();
+            bodies.push(BodyConfig { name, mesh_file, dofs, mass: self.default_mass(), cog });
+        }
+
+        let line = next("wave frequencies")?;
+        let with_unit = line.1.split_whitespace().take_while(|w| w.parse::<f64>().is_ok()).count() >= 4;
+        let f = numbers(line, if with_unit { 4 } else { 3 }, "wave frequencies")?;
+        let (unit, f) = if with_unit { (f[0] as u32, &f[1..]) } else { (1, &f[..]) };
+        let frequencies = linspace(f[0] as usize, f[1], f[2])
+            .into_iter()
+            .map(|w| match unit {
+                2 => 2.0 * std::f64::consts::PI * w,
+                3 => 2.0 * std::f64::consts::PI / w,
+                _ => w,
+            })
+            .collect();
+        let d = numbers(next("wave directions")?, 3, "wave directions")?;
+        let directions = linspace(d[0] as usize, d[1], d[2]);
+
+        // Post-processing: impulse response, pressure, Kochin, then the free-surface grid
+        let post: Vec<_> = (0..4).map_while(|_| next("post-processing").ok()).collect();
+        let free_surface = match post.get(3).map(|&line| numbers(line, 4, "free surface grid")) {
+            Some(Ok(v)) if v[0] > 0.0 => FreeSurfaceConfig {
+                nx: v[0] as usize,
+                ny: v[1] as usize,
+                lx: v[2],
+                ly: v[3],
+                origin: Point3::new(-0.5 * v[2], -0.5 * v[3], 0.0),
+            },
+            _ => self.parse_free_surface_section(&[])?,
+        };
+
+        Ok(NemohConfig {
+            environment: Environment {
+                rho,
+                g,
+                depth: if depth > 0.0 { depth } else { self.defaults.depth },
+                frequencies,
+                directions,
+            },
+            bodies,
+            free_surface,
+            solver: self.parse_solver_section(&[])?,
+            output: self.parse_output_section(&[])?,
+        })
+    }
+
+    /// Mass properties of bodies whose mass the input does not give
+    fn default_mass(&self) -> MassProperties {
+        MassProperties { mass: 1000.0, inertia: [100.0, 0.0, 0.0, 0.0, 100.0, 0.0, 0.0, 0.0, 100.0], added_mass: None }
+    }
+
     /// Write NEMOH configuration file
     pub fn write_config(&self, config: &NemohConfig, path: &Path) -> Result<()> {
         let file = File::create(path)?;
@@ -449,7 +661,9 @@ impl NemohConfigParser {
 
         writeln!(writer, "! NEMOH Configuration File")?;
         writeln!(writer, "! Generated by WaveCore")?;
-        writeln!(writer, "! Date: {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
+        if let Some(date) = timestamp::now_utc(timestamp::DATE_TIME) {
+            writeln!(writer, "! Date: {}", date)?;
+        }
         writeln!(writer, "!")?;
 
         // Write environment section
@@ -477,8 +691,6 @@ impl NemohConfigParser {
         let mut rho = self.defaults.rho;
         let mut g = self.defaults.g;
         let mut depth = self.defaults.depth;
-        let mut frequencies = Vec::new();
-        let mut directions = Vec::new();
 
         for line in lines {
             if line.contains("RHO") {
@@ -497,8 +709,8 @@ impl NemohConfigParser {
         }
 
         // Default frequencies and directions
-        frequencies = vec![0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5];
-        directions = vec![0.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0];
+        let frequencies = vec![0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5];
+        let directions = vec![0.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0];
 
         Ok(Environment {
             rho,
@@ -510,7 +722,7 @@ impl NemohConfigParser {
     }
 
     /// Parse bodies section
-    fn parse_bodies_section(&self, lines: &[String]) -> Result<Vec<BodyConfig>> {
+    fn parse_bodies_section(&self, _lines: &[String]) -> Result<Vec<BodyConfig>> {
         // Simplified - create default body
         let dofs = vec![
             DegreeOfFreedom {
@@ -533,11 +745,7 @@ impl NemohConfigParser {
             },
         ];
 
-        let mass = MassProperties {
-            mass: 1000.0,
-            inertia: [100.0, 0.0, 0.0, 0.0, 100.0, 0.0, 0.0, 0.0, 100.0],
-            added_mass: None,
-        };
+        let mass = self.default_mass();
 
         let body = BodyConfig {
             name: "hull".to_string(),
@@ -551,7 +759,7 @@ impl NemohConfigParser {
     }
 
     /// Parse free surface section
-    fn parse_free_surface_section(&self, lines: &[String]) -> Result<FreeSurfaceConfig> {
+    fn parse_free_surface_section(&self, _lines: &[String]) -> Result<FreeSurfaceConfig> {
         Ok(FreeSurfaceConfig {
             nx: 50,
             ny: 50,
@@ -562,7 +770,7 @@ impl NemohConfigParser {
     }
 
     /// Parse solver section
-    fn parse_solver_section(&self, lines: &[String]) -> Result<SolverConfig> {
+    fn parse_solver_section(&self, _lines: &[String]) -> Result<SolverConfig> {
         let iterative = IterativeSolverConfig {
             solver_type: "GMRES".to_string(),
             max_iterations: 1000,
@@ -591,7 +799,7 @@ impl NemohConfigParser {
     }
 
     /// Parse output section
-    fn parse_output_section(&self, lines: &[String]) -> Result<OutputConfig> {
+    fn parse_output_section(&self, _lines: &[String]) -> Result<OutputConfig> {
         let detailed = DetailedOutputConfig {
             body_potential: true,
             free_surface_elevation: false,
@@ -682,6 +890,12 @@ impl NemohConfigParser {
     }
 }
 
+impl Default for NemohMeshConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NemohMeshConverter {
     /// Create new NEMOH mesh converter
     pub fn new() -> Self {
@@ -693,101 +907,104 @@ impl NemohMeshConverter {
 
     /// Read NEMOH mesh file
     pub fn read_mesh(&self, path: &Path) -> Result<Mesh> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        
-        let mut panels = Vec::new();
+        self.parse_mesh(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a NEMOH mesh: `2 ISYM`, then `id x y z` node lines ended by a line
+    /// starting with 0, then `i j k l` panel lines ended by `0 0 0 0`
+    ///
+    /// With ISYM = 1 only the half y ≥ 0 is given and is reflected across
+    /// y = 0. Triangles repeat their last node.
+    pub fn parse_mesh(&self, content: &str) -> Result<Mesh> {
+        let error = |number: usize, message: &str| IOError::ParseError {
+            message: format!("NEMOH mesh line {}: {}", number + 1, message),
+        };
+        let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let (number, header) = lines.next().ok_or_else(|| error(0, "empty mesh file"))?;
+        let header: Vec<i64> = header.split_whitespace().take(2).map_while(|w| w.parse().ok()).collect();
+        let symmetric = match header[..] {
+            [_, isym] => isym == 1,
+            _ => return Err(error(number, "expected '2 ISYM'")),
+        };
+
         let mut vertices = Vec::new();
-        
-        for line in reader.lines() {
-            let line = line?;
-            let trimmed = line.trim();
-            
-            // Skip comments and empty lines
-            if trimmed.is_empty() || trimmed.starts_with('!') {
-                continue;
+        let mut ids = HashMap::new();
+        for (number, line) in lines.by_ref() {
+            let v: Vec<f64> = line.split_whitespace().take(4).map_while(|w| w.parse().ok()).collect();
+            if v.first() == Some(&0.0) {
+                break;
             }
-            
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            
-            // Parse based on number of values
-            if parts.len() == 3 {
-                // Vertex coordinates
-                let x = parts[0].parse::<f64>().map_err(|_| IOError::ParseError { message: "Invalid x coordinate".to_string() })?;
-                let y = parts[1].parse::<f64>().map_err(|_| IOError::ParseError { message: "Invalid y coordinate".to_string() })?;
-                let z = parts[2].parse::<f64>().map_err(|_| IOError::ParseError { message: "Invalid z coordinate".to_string() })?;
-                vertices.push(Point3::new(x, y, z));
-            } else if parts.len() >= 4 {
-                // Panel connectivity (simplified - assume triangular or quad panels)
-                let indices: std::result::Result<Vec<usize>, _> = parts.iter().take(4).map(|s| s.parse::<usize>()).collect();
-                
-                if let Ok(indices) = indices {
-                    let panel_vertices: Vec<Point3<f64>> = indices.into_iter()
-                        .filter_map(|i| if i > 0 && i <= vertices.len() { Some(vertices[i-1]) } else { None })
-                        .collect();
-                    
-                    if panel_vertices.len() >= 3 {
-                        if panel_vertices.len() >= 3 {
-                            match Panel::new(panel_vertices[0], panel_vertices[1], panel_vertices[2]) {
-                                Ok(panel) => panels.push(panel),
-                                Err(_) => continue, // Skip invalid panels
-                            }
-                        }
-                    }
-                }
+            if v.len() < 4 {
+                return Err(error(number, "expected 'id x y z'"));
             }
+            ids.insert(v[0] as usize, vertices.len());
+            vertices.push(Point3::new(v[1], v[2], v[3]));
         }
-        
-        // Quality checks
-        if self.quality_checks.check_area {
-            self.check_panel_areas(&panels)?;
+
+        let mut polygons = Vec::new();
+        for (number, line) in lines {
+            let v: Vec<usize> = line.split_whitespace().take(4).map_while(|w| w.parse().ok()).collect();
+            if v.first() == Some(&0) {
+                break;
+            }
+            let polygon = v
+                .iter()
+                .map(|id| ids.get(id).copied())
+                .collect::<Option<Vec<usize>>>()
+                .filter(|p| p.len() == 4)
+                .ok_or_else(|| error(number, "expected four node ids"))?;
+            polygons.push(polygon);
         }
-        
-        // Convert panels to vertices and faces
-        let mut mesh_vertices = Vec::new();
-        let mut mesh_faces = Vec::new();
-        let mut vertex_map = std::collections::HashMap::new();
-        
-        for panel in &panels {
-            let mut face_vertices = [0; 3];
-            for (i, vertex) in panel.vertices().iter().enumerate() {
-                let vertex_key = format!("{:.6},{:.6},{:.6}", vertex.x, vertex.y, vertex.z);
-                let vertex_idx = *vertex_map.entry(vertex_key.clone()).or_insert_with(|| {
-                    mesh_vertices.push(*vertex);
-                    mesh_vertices.len() - 1
-                });
-                face_vertices[i] = vertex_idx;
+
+        // The reflected half reverses the node order so normals still point into the fluid
+        if symmetric {
+            let n = vertices.len();
+            vertices.extend_from_within(..);
+            for p in &mut vertices[n..] {
+                p.y = -p.y;
             }
-            mesh_faces.push(face_vertices);
+            let mirrored: Vec<Vec<usize>> = polygons.iter().map(|p| p.iter().rev().map(|&v| v + n).collect()).collect();
+            polygons.extend(mirrored);
+        }
+
+        let mut faces = Vec::new();
+        let mut quads = Vec::new();
+        for mut polygon in polygons {
+            polygon.dedup();
+            if polygon.len() > 1 && polygon[0] == polygon[polygon.len() - 1] {
+                polygon.pop();
+            }
+            match polygon[..] {
+                [a, b, c] => faces.push([a, b, c]),
+                [a, b, c, d] => quads.push([a, b, c, d]),
+                _ => {}
+            }
+        }
+        let mut mesh = Mesh::with_quads(vertices, faces, quads)?;
+        if self.quality_checks.check_area {
+            self.check_panel_areas(mesh.panels()?)?;
         }
-        
-        let mesh = Mesh::new(mesh_vertices, mesh_faces)?;
         Ok(mesh)
     }
 
-    /// Write NEMOH mesh file
+    /// Write NEMOH mesh file, without symmetry; triangles repeat their last node
     pub fn write_mesh(&self, mesh: &Mesh, path: &Path) -> Result<()> {
         let file = File::create(path)?;
         let mut writer = std::io::BufWriter::new(file);
-        
-        writeln!(writer, "! NEMOH Mesh File")?;
-        writeln!(writer, "! Generated by WaveCore")?;
-        writeln!(writer, "! Date: {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
-        writeln!(writer, "!")?;
-        
-        // Write number of panels
-        let panels = mesh.get_panels().ok_or_else(|| IOError::ParseError { 
-            message: "Mesh has no panels".to_string() 
-        })?;
-        writeln!(writer, "{}", panels.len())?;
-        
-        // Write panels
-        for panel in panels {
-            for vertex in panel.vertices() {
-                writeln!(writer, "{:.6} {:.6} {:.6}", vertex.x, vertex.y, vertex.z)?;
-            }
+
+        writeln!(writer, "2 0")?;
+        for (i, vertex) in mesh.vertices.iter().enumerate() {
+            writeln!(writer, "{} {:.10e} {:.10e} {:.10e}", i + 1, vertex.x, vertex.y, vertex.z)?;
         }
-        
+        writeln!(writer, "0 0. 0. 0.")?;
+        for face in &mesh.faces {
+            writeln!(writer, "{} {} {} {}", face[0] + 1, face[1] + 1, face[2] + 1, face[2] + 1)?;
+        }
+        for quad in &mesh.quads {
+            writeln!(writer, "{} {} {} {}", quad[0] + 1, quad[1] + 1, quad[2] + 1, quad[3] + 1)?;
+        }
+        writeln!(writer, "0 0 0 0")?;
+
         writer.flush()?;
         Ok(())
     }
@@ -806,6 +1023,12 @@ impl NemohMeshConverter {
     }
 }
 
+impl Default for NemohResultsProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NemohResultsProcessor {
     /// Create new NEMOH results processor
     pub fn new() -> Self {
@@ -823,7 +1046,7 @@ impl NemohResultsProcessor {
         
         let metadata = NemohMetadata {
             version: "WaveCore 4.0".to_string(),
-            date: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            date: timestamp::now_utc(timestamp::DATE_TIME).unwrap_or_default(),
             computation_time: results.computation_time(),
             num_bodies: 1,
             total_panels: results.potential().len(), // Use potential length as proxy for panel count
@@ -839,7 +1062,7 @@ impl NemohResultsProcessor {
     }
 
     /// Extract hydrodynamic coefficients
-    fn extract_hydrodynamic_coefficients(&self, results: &BEMResult) -> Result<HydrodynamicCoefficients> {
+    fn extract_hydrodynamic_coefficients(&self, _results: &BEMResult) -> Result<HydrodynamicCoefficients> {
         // Simplified extraction - would need proper implementation
         let frequencies = vec![0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5];
         let added_mass = vec![vec![0.1; 6]; 6];
@@ -853,7 +1076,7 @@ impl NemohResultsProcessor {
     }
 
     /// Extract exciting forces
-    fn extract_exciting_forces(&self, results: &BEMResult) -> Result<ExcitingForces> {
+    fn extract_exciting_forces(&self, _results: &BEMResult) -> Result<ExcitingForces> {
         let frequencies = vec![0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5];
         let directions = vec![0.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0];
         let amplitudes = vec![vec![1.0; 6]; directions.len()];
@@ -868,6 +1091,15 @@ impl NemohResultsProcessor {
     }
 }
 
+/// `count` values evenly spaced from `min` to `max`, as NEMOH expands its frequency and direction ranges
+fn linspace(count: usize, min: f64, max: f64) -> Vec<f64> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![min],
+        n => (0..n).map(|i| min + (max - min) * i as f64 / (n - 1) as f64).collect(),
+    }
+}
+
 // Default implementations
 impl Default for NemohParsingOptions {
     fn default() -> Self {
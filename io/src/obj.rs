@@ -0,0 +1,222 @@
+//! Wavefront OBJ import with groups and polygon faces
+//!
+//! Modelling tools such as Blender and Rhino export OBJ with one object or
+//! group per part and quads or general polygons as faces. [`parse_obj`] keeps
+//! each `o`/`g` group as an [`ObjGroup`] with its polygons intact; groups are
+//! triangulated into panel meshes on demand, oriented by the file's vertex
+//! normals where present, and collected by name into a [`MeshCollection`].
+
+use super::*;
+use wavecore_meshes::{Mesh, MeshCollection, Point, Vector};
+
+/// Name given to faces that precede any `o` or `g` statement
+pub const DEFAULT_OBJ_GROUP: &str = "default";
+
+/// Faces of one OBJ object or group
+#[derive(Debug, Clone)]
+pub struct ObjGroup {
+    /// Object or group name
+    pub name: String,
+    /// Vertices used by the group
+    pub vertices: Vec<Point>,
+    /// Polygon faces as indices into `vertices`
+    pub polygons: Vec<Vec<usize>>,
+    /// Unit normal of each polygon, from the file's vertex normals where given
+    pub normals: Vec<Vector>,
+}
+
+impl ObjGroup {
+    /// Number of quadrilateral faces
+    pub fn quad_count(&self) -> usize {
+        self.polygons.iter().filter(|p| p.len() == 4).count()
+    }
+
+    /// Triangulate into a panel mesh
+    ///
+    /// Quads are split along their shorter diagonal and larger polygons fanned
+    /// from their first vertex; each triangle is wound to agree with its
+    /// polygon's normal.
+    pub fn to_mesh(&self) -> Result<Mesh> {
+        let mut faces = Vec::new();
+        for (polygon, normal) in self.polygons.iter().zip(&self.normals) {
+            for [a, b, c] in triangulate(&self.vertices, polygon) {
+                let (pa, pb, pc) = (self.vertices[a], self.vertices[b], self.vertices[c]);
+                let cross = (pb - pa).cross(&(pc - pa));
+                if 0.5 * cross.norm() < 1e-12 {
+                    continue;
+                }
+                faces.push(if cross.dot(normal) < 0.0 { [a, c, b] } else { [a, b, c] });
+            }
+        }
+        Mesh::new(self.vertices.clone(), faces).map_err(|e| IOError::ParseError {
+            message: format!("OBJ group '{}': {}", self.name, e),
+        })
+    }
+}
+
+/// Contents of an OBJ file
+#[derive(Debug, Clone, Default)]
+pub struct ObjFile {
+    /// Groups with at least one face, in file order
+    pub groups: Vec<ObjGroup>,
+}
+
+impl ObjFile {
+    /// Group by name
+    pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// Triangulated mesh of each group, keyed by group name
+    pub fn to_collection(&self) -> Result<MeshCollection> {
+        let mut collection = MeshCollection::new();
+        for group in &self.groups {
+            collection.add_mesh(group.name.clone(), group.to_mesh()?)?;
+        }
+        Ok(collection)
+    }
+
+    /// All groups triangulated into a single mesh
+    pub fn to_mesh(&self) -> Result<Mesh> {
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for group in &self.groups {
+            let mesh = group.to_mesh()?;
+            let offset = vertices.len();
+            vertices.extend(mesh.vertices);
+            faces.extend(mesh.faces.iter().map(|f| f.map(|v| v + offset)));
+        }
+        Ok(Mesh::new(vertices, faces)?)
+    }
+}
+
+/// Face corner as position and optional normal indices into the file-wide lists
+type Corner = (usize, Option<usize>);
+
+/// Read an OBJ file
+pub fn read_obj(path: &str) -> Result<ObjFile> {
+    parse_obj(&std::fs::read_to_string(path)?)
+}
+
+/// Parse OBJ text into its groups
+pub fn parse_obj(text: &str) -> Result<ObjFile> {
+    let mut positions: Vec<Point> = Vec::new();
+    let mut vertex_normals: Vec<Vector> = Vec::new();
+    let mut groups: Vec<(String, Vec<Vec<Corner>>)> = vec![(DEFAULT_OBJ_GROUP.to_string(), Vec::new())];
+    let mut current = 0;
+
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: String| IOError::ParseError {
+            message: format!("OBJ line {}: {}", number + 1, message),
+        };
+        let mut words = line.split('#').next().unwrap_or("").split_whitespace();
+        let Some(keyword) = words.next() else { continue };
+        match keyword {
+            "v" | "vn" => {
+                let coords: Vec<f64> = words
+                    .take(3)
+                    .map(str::parse)
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(|e| error(format!("invalid coordinate: {}", e)))?;
+                if coords.len() != 3 {
+                    return Err(error(format!("'{}' needs 3 coordinates", keyword)));
+                }
+                if keyword == "v" {
+                    positions.push(Point::new(coords[0], coords[1], coords[2]));
+                } else {
+                    vertex_normals.push(Vector::new(coords[0], coords[1], coords[2]));
+                }
+            }
+            "o" | "g" => {
+                let name = words.collect::<Vec<_>>().join(" ");
+                let name = if name.is_empty() { DEFAULT_OBJ_GROUP.to_string() } else { name };
+                current = match groups.iter().position(|(n, _)| *n == name) {
+                    Some(index) => index,
+                    None => {
+                        groups.push((name, Vec::new()));
+                        groups.len() - 1
+                    }
+                };
+            }
+            "f" => {
+                let corners = words
+                    .map(|word| {
+                        let mut fields = word.split('/');
+                        let position = resolve(fields.next().unwrap_or(""), positions.len())
+                            .ok_or_else(|| error(format!("invalid vertex reference '{}'", word)))?;
+                        let normal = match fields.nth(1) {
+                            Some(field) if !field.is_empty() => Some(
+                                resolve(field, vertex_normals.len())
+                                    .ok_or_else(|| error(format!("invalid normal reference '{}'", word)))?,
+                            ),
+                            _ => None,
+                        };
+                        Ok((position, normal))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                if corners.len() < 3 {
+                    return Err(error(format!("face has {} vertices", corners.len())));
+                }
+                groups[current].1.push(corners);
+            }
+            _ => {}
+        }
+    }
+
+    // Each group gets its own vertex list holding only the vertices it uses
+    let groups = groups
+        .into_iter()
+        .filter(|(_, faces)| !faces.is_empty())
+        .map(|(name, faces)| {
+            let mut local = std::collections::HashMap::new();
+            let mut vertices = Vec::new();
+            let mut polygons = Vec::with_capacity(faces.len());
+            let mut normals = Vec::with_capacity(faces.len());
+            for face in faces {
+                let polygon: Vec<usize> = face
+                    .iter()
+                    .map(|&(p, _)| {
+                        *local.entry(p).or_insert_with(|| {
+                            vertices.push(positions[p]);
+                            vertices.len() - 1
+                        })
+                    })
+                    .collect();
+                let given: Vector = face.iter().filter_map(|&(_, n)| n.map(|n| vertex_normals[n])).sum();
+                let normal = if given.norm() > 0.0 { given } else { newell_normal(&vertices, &polygon) };
+                normals.push(normal.try_normalize(0.0).unwrap_or_else(Vector::zeros));
+                polygons.push(polygon);
+            }
+            ObjGroup { name, vertices, polygons, normals }
+        })
+        .collect();
+    Ok(ObjFile { groups })
+}
+
+/// Zero-based index of a 1-based or negative (relative) OBJ reference
+fn resolve(field: &str, count: usize) -> Option<usize> {
+    let index: i64 = field.parse().ok()?;
+    let index = if index < 0 { count as i64 + index } else { index - 1 };
+    (0..count as i64).contains(&index).then_some(index as usize)
+}
+
+/// Area-weighted normal of a possibly non-planar polygon
+fn newell_normal(vertices: &[Point], polygon: &[usize]) -> Vector {
+    let origin = vertices[polygon[0]];
+    polygon.windows(2).skip(1).fold(Vector::zeros(), |sum, w| {
+        sum + (vertices[w[0]] - origin).cross(&(vertices[w[1]] - origin))
+    })
+}
+
+/// Split a polygon into triangles, quads along their shorter diagonal
+pub(crate) fn triangulate(vertices: &[Point], polygon: &[usize]) -> Vec<[usize; 3]> {
+    if let [a, b, c, d] = *polygon {
+        if (vertices[a] - vertices[c]).norm() <= (vertices[b] - vertices[d]).norm() {
+            vec![[a, b, c], [a, c, d]]
+        } else {
+            vec![[a, b, d], [b, c, d]]
+        }
+    } else {
+        (1..polygon.len() - 1).map(|k| [polygon[0], polygon[k], polygon[k + 1]]).collect()
+    }
+}
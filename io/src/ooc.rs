@@ -0,0 +1,382 @@
+//! Out-of-core dense complex matrices in memory-mapped files
+//!
+//! A matrix too large for RAM is stored as square tiles of
+//! [`DEFAULT_OOC_TILE`] × [`DEFAULT_OOC_TILE`] entries, each contiguous in the
+//! file, so assembling, multiplying or factorizing touches one tile's pages at
+//! a time. The LU factorization is left-looking: each column panel of tiles is
+//! read into RAM, updated by the factors to its left as they stream past,
+//! factorized with partial pivoting and written back. A 50 000-panel BEM
+//! system then needs the 40 GB file plus one panel of about 200 MB in memory.
+//!
+//! Layout: `b"WCOM"` | `u32` version | `u64` rows | `u64` cols | `u64` tile | tiles,
+//! row of tiles by row of tiles, each tile row-major and padded to full size,
+//! entries as little-endian `f64` pairs.
+
+use super::*;
+use memmap2::MmapMut;
+use num_complex::Complex64;
+use std::fs::OpenOptions;
+use std::ops::Range;
+use std::path::Path;
+use wavecore_matrices::{ComplexMatrix, MatrixError};
+
+const MAGIC: &[u8; 4] = b"WCOM";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 32;
+const ENTRY_LEN: usize = 16;
+
+/// Default tile size, 1 MB of complex entries per tile
+pub const DEFAULT_OOC_TILE: usize = 256;
+
+/// Dense complex matrix stored in a memory-mapped file
+pub struct OocMatrix {
+    path: String,
+    mmap: MmapMut,
+    rows: usize,
+    cols: usize,
+    tile: usize,
+}
+
+impl OocMatrix {
+    /// Create a zero matrix in a new file at `path`, replacing any existing file
+    pub fn create(path: &str, rows: usize, cols: usize, tile: usize) -> Result<Self> {
+        if tile == 0 {
+            return Err(IOError::DataArrayError {
+                message: "Out-of-core tile size must be positive".to_string(),
+            });
+        }
+        let tiles = rows.div_ceil(tile) * cols.div_ceil(tile);
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len((HEADER_LEN + tiles * tile * tile * ENTRY_LEN) as u64)?;
+        // Safety: the file is owned by this matrix for the lifetime of the map
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[0..4].copy_from_slice(MAGIC);
+        mmap[4..8].copy_from_slice(&VERSION.to_le_bytes());
+        for (k, value) in [rows, cols, tile].into_iter().enumerate() {
+            mmap[8 + 8 * k..16 + 8 * k].copy_from_slice(&(value as u64).to_le_bytes());
+        }
+        Ok(Self { path: path.to_string(), mmap, rows, cols, tile })
+    }
+
+    /// Open a matrix file written by [`OocMatrix::create`]
+    pub fn open(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Err(IOError::FileNotFound { path: path.to_string() });
+        }
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        // Safety: the file is owned by this matrix for the lifetime of the map
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        if mmap.len() < HEADER_LEN || &mmap[0..4] != MAGIC {
+            return Err(IOError::InvalidFormat {
+                format: "not a WaveCore out-of-core matrix".to_string(),
+            });
+        }
+        let version = u32::from_le_bytes(mmap[4..8].try_into().unwrap());
+        if version != VERSION {
+            return Err(IOError::InvalidFormat {
+                format: format!("out-of-core matrix v{} is not supported", version),
+            });
+        }
+        let field = |k: usize| u64::from_le_bytes(mmap[8 + 8 * k..16 + 8 * k].try_into().unwrap()) as usize;
+        let (rows, cols, tile) = (field(0), field(1), field(2));
+        if tile == 0 || mmap.len() < HEADER_LEN + rows.div_ceil(tile) * cols.div_ceil(tile) * tile * tile * ENTRY_LEN {
+            return Err(IOError::ParseError {
+                message: "Truncated out-of-core matrix".to_string(),
+            });
+        }
+        Ok(Self { path: path.to_string(), mmap, rows, cols, tile })
+    }
+
+    /// Path of the backing file
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Tile size
+    pub fn tile_size(&self) -> usize {
+        self.tile
+    }
+
+    /// Number of tiles down and across
+    pub fn tile_grid(&self) -> (usize, usize) {
+        (self.rows.div_ceil(self.tile), self.cols.div_ceil(self.tile))
+    }
+
+    /// Rows covered by tile row `ti`
+    pub fn tile_rows(&self, ti: usize) -> Range<usize> {
+        ti * self.tile..((ti + 1) * self.tile).min(self.rows)
+    }
+
+    /// Columns covered by tile column `tj`
+    pub fn tile_cols(&self, tj: usize) -> Range<usize> {
+        tj * self.tile..((tj + 1) * self.tile).min(self.cols)
+    }
+
+    /// Entry (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Result<Complex64> {
+        self.check_index(i, j)?;
+        Ok(self.load(i / self.tile, j / self.tile, i % self.tile, j % self.tile))
+    }
+
+    /// Set entry (i, j)
+    pub fn set(&mut self, i: usize, j: usize, value: Complex64) -> Result<()> {
+        self.check_index(i, j)?;
+        self.store(i / self.tile, j / self.tile, i % self.tile, j % self.tile, value);
+        Ok(())
+    }
+
+    /// Copy tile (ti, tj) into memory
+    pub fn read_tile(&self, ti: usize, tj: usize) -> Result<ComplexMatrix> {
+        let (rows, cols) = (self.tile_rows(ti).len(), self.tile_cols(tj).len());
+        let data = (0..rows * cols).map(|k| self.load(ti, tj, k / cols, k % cols)).collect();
+        Ok(ComplexMatrix::from_vec(rows, cols, data)?)
+    }
+
+    /// Overwrite tile (ti, tj)
+    pub fn write_tile(&mut self, ti: usize, tj: usize, block: &ComplexMatrix) -> Result<()> {
+        let (rows, cols) = (self.tile_rows(ti).len(), self.tile_cols(tj).len());
+        if block.rows != rows || block.cols != cols {
+            return Err(MatrixError::DimensionMismatch { expected: rows * cols, actual: block.rows * block.cols }.into());
+        }
+        for (k, &value) in block.data.iter().enumerate() {
+            self.store(ti, tj, k / cols, k % cols, value);
+        }
+        Ok(())
+    }
+
+    /// Assemble the matrix tile by tile from `block(rows, cols)`
+    pub fn fill<F>(&mut self, mut block: F) -> Result<()>
+    where
+        F: FnMut(Range<usize>, Range<usize>) -> Result<ComplexMatrix>,
+    {
+        let (tile_rows, tile_cols) = self.tile_grid();
+        for ti in 0..tile_rows {
+            for tj in 0..tile_cols {
+                let values = block(self.tile_rows(ti), self.tile_cols(tj))?;
+                self.write_tile(ti, tj, &values)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Matrix-vector product A x, streaming the tiles
+    pub fn matvec(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        if x.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch { expected: self.cols, actual: x.len() }.into());
+        }
+        let mut y = vec![Complex64::new(0.0, 0.0); self.rows];
+        let (tile_rows, tile_cols) = self.tile_grid();
+        for ti in 0..tile_rows {
+            for tj in 0..tile_cols {
+                self.multiply_subtract(ti, tj, &x[self.tile_cols(tj)], &mut y[self.tile_rows(ti)]);
+            }
+        }
+        Ok(y.into_iter().map(|yi| -yi).collect())
+    }
+
+    /// Write modified pages back to the file
+    pub fn flush(&self) -> Result<()> {
+        Ok(self.mmap.flush()?)
+    }
+
+    /// Factorize in place by left-looking LU with partial pivoting
+    pub fn lu(mut self) -> Result<OocLU> {
+        if self.rows != self.cols {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols }.into());
+        }
+        let n = self.rows;
+        let mut pivots = vec![0; n];
+        let (tiles, _) = self.tile_grid();
+        for k in 0..tiles {
+            let columns = self.tile_cols(k);
+            let width = columns.len();
+
+            // Column panel k in memory, row-major n × width
+            let mut panel = vec![Complex64::new(0.0, 0.0); n * width];
+            for ti in 0..tiles {
+                for (r, i) in self.tile_rows(ti).enumerate() {
+                    for c in 0..width {
+                        panel[i * width + c] = self.load(ti, k, r, c);
+                    }
+                }
+            }
+
+            // Updates from the factored panels to the left
+            for j in 0..k {
+                let block = self.tile_rows(j);
+                for r in block.clone() {
+                    swap_rows(&mut panel, width, r, pivots[r]);
+                }
+                for (r, i) in block.clone().enumerate() {
+                    for s in 0..r {
+                        let l = self.load(j, j, r, s);
+                        for c in 0..width {
+                            let u = panel[(block.start + s) * width + c];
+                            panel[i * width + c] -= l * u;
+                        }
+                    }
+                }
+                for ti in j + 1..tiles {
+                    for (r, i) in self.tile_rows(ti).enumerate() {
+                        for s in 0..block.len() {
+                            let l = self.load(ti, j, r, s);
+                            for c in 0..width {
+                                let u = panel[(block.start + s) * width + c];
+                                panel[i * width + c] -= l * u;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Panel factorization below the diagonal
+            for c in 0..width {
+                let r = columns.start + c;
+                let pivot = (r..n).max_by(|&p, &q| panel[p * width + c].norm().total_cmp(&panel[q * width + c].norm())).unwrap();
+                if panel[pivot * width + c].norm() == 0.0 {
+                    return Err(MatrixError::SingularMatrix.into());
+                }
+                pivots[r] = pivot;
+                swap_rows(&mut panel, width, r, pivot);
+                let diagonal = panel[r * width + c];
+                for i in r + 1..n {
+                    let l = panel[i * width + c] / diagonal;
+                    panel[i * width + c] = l;
+                    for cc in c + 1..width {
+                        let u = panel[r * width + cc];
+                        panel[i * width + cc] -= l * u;
+                    }
+                }
+            }
+
+            for ti in 0..tiles {
+                for (r, i) in self.tile_rows(ti).enumerate() {
+                    for c in 0..width {
+                        self.store(ti, k, r, c, panel[i * width + c]);
+                    }
+                }
+            }
+        }
+        Ok(OocLU { factors: self, pivots })
+    }
+
+    /// y -= A_(ti,tj) x, with `y` the rows of tile row `ti`
+    fn multiply_subtract(&self, ti: usize, tj: usize, x: &[Complex64], y: &mut [Complex64]) {
+        for (r, yr) in y.iter_mut().enumerate().take(self.tile_rows(ti).len()) {
+            *yr -= x.iter().enumerate().map(|(c, xc)| self.load(ti, tj, r, c) * xc).sum::<Complex64>();
+        }
+    }
+
+    fn check_index(&self, i: usize, j: usize) -> Result<()> {
+        if i >= self.rows || j >= self.cols {
+            return Err(IOError::DataArrayError {
+                message: format!("Index ({}, {}) out of bounds for {}×{} matrix", i, j, self.rows, self.cols),
+            });
+        }
+        Ok(())
+    }
+
+    fn offset(&self, ti: usize, tj: usize, r: usize, c: usize) -> usize {
+        let (_, tile_cols) = self.tile_grid();
+        HEADER_LEN + ((ti * tile_cols + tj) * self.tile * self.tile + r * self.tile + c) * ENTRY_LEN
+    }
+
+    fn load(&self, ti: usize, tj: usize, r: usize, c: usize) -> Complex64 {
+        let at = self.offset(ti, tj, r, c);
+        let bytes = &self.mmap[at..at + ENTRY_LEN];
+        Complex64::new(
+            f64::from_le_bytes(bytes[..8].try_into().unwrap()),
+            f64::from_le_bytes(bytes[8..].try_into().unwrap()),
+        )
+    }
+
+    fn store(&mut self, ti: usize, tj: usize, r: usize, c: usize, value: Complex64) {
+        let at = self.offset(ti, tj, r, c);
+        self.mmap[at..at + 8].copy_from_slice(&value.re.to_le_bytes());
+        self.mmap[at + 8..at + ENTRY_LEN].copy_from_slice(&value.im.to_le_bytes());
+    }
+}
+
+/// LU factors of an [`OocMatrix`], kept in its file
+///
+/// Each panel's row interchanges apply to the panels to its right only, so
+/// solves interleave them with the forward substitution.
+pub struct OocLU {
+    factors: OocMatrix,
+    pivots: Vec<usize>,
+}
+
+impl OocLU {
+    /// Factored matrix, L below and U on and above the diagonal
+    pub fn factors(&self) -> &OocMatrix {
+        &self.factors
+    }
+
+    /// Row interchanged with each row during factorization
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    /// Solve A x = b, streaming the factors once forward and once backward
+    pub fn solve(&self, b: &[Complex64]) -> Result<Vec<Complex64>> {
+        let a = &self.factors;
+        if b.len() != a.rows {
+            return Err(MatrixError::DimensionMismatch { expected: a.rows, actual: b.len() }.into());
+        }
+        let mut y = b.to_vec();
+        let (tiles, _) = a.tile_grid();
+        for j in 0..tiles {
+            let block = a.tile_rows(j);
+            for r in block.clone() {
+                y.swap(r, self.pivots[r]);
+            }
+            for (r, i) in block.clone().enumerate() {
+                let sum: Complex64 = (0..r).map(|s| a.load(j, j, r, s) * y[block.start + s]).sum();
+                y[i] -= sum;
+            }
+            let (solved, rest) = y.split_at_mut(block.end);
+            for ti in j + 1..tiles {
+                let rows = a.tile_rows(ti);
+                a.multiply_subtract(ti, j, &solved[block.clone()], &mut rest[rows.start - block.end..rows.end - block.end]);
+            }
+        }
+        for j in (0..tiles).rev() {
+            let block = a.tile_rows(j);
+            let (head, solved) = y.split_at_mut(block.end);
+            for tj in j + 1..tiles {
+                let cols = a.tile_cols(tj);
+                a.multiply_subtract(j, tj, &solved[cols.start - block.end..cols.end - block.end], &mut head[block.clone()]);
+            }
+            for (r, i) in block.clone().enumerate().rev() {
+                let sum: Complex64 = (r + 1..block.len()).map(|s| a.load(j, j, r, s) * y[block.start + s]).sum();
+                y[i] = (y[i] - sum) / a.load(j, j, r, r);
+            }
+        }
+        Ok(y)
+    }
+
+    /// Remove the backing file
+    pub fn remove(self) -> Result<()> {
+        let path = self.factors.path.clone();
+        drop(self);
+        Ok(std::fs::remove_file(path)?)
+    }
+}
+
+fn swap_rows(panel: &mut [Complex64], width: usize, a: usize, b: usize) {
+    if a != b {
+        for c in 0..width {
+            panel.swap(a * width + c, b * width + c);
+        }
+    }
+}
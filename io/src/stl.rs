@@ -0,0 +1,231 @@
+//! STL mesh files, binary and ASCII
+//!
+//! STL stores a soup of independent triangles, so corners shared by
+//! neighbouring facets are repeated and, in ASCII files, rounded differently.
+//! Reading welds corners closer than a tolerance back into shared vertices,
+//! drops facets that collapse in the process and converts file units to
+//! metres; writing emits either encoding with outward facet normals.
+//!
+//! Binary layout: 80-byte header | `u32` facet count | per facet, 12
+//! little-endian `f32` (normal, three vertices) and a `u16` attribute.
+
+use super::*;
+use std::collections::HashMap;
+use wavecore_meshes::{Mesh, Point};
+
+const HEADER_LEN: usize = 84;
+const FACET_LEN: usize = 50;
+
+/// Encoding of an STL file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StlEncoding {
+    /// Text `solid … endsolid`
+    #[default]
+    Ascii,
+    /// Fixed-size binary facets
+    Binary,
+}
+
+/// Options for reading and writing STL files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StlOptions {
+    /// Metres per file unit: 0.001 for a file in millimetres
+    pub scale: f64,
+    /// Distance in metres below which corners are welded into one vertex
+    pub weld_tolerance: f64,
+    /// Encoding used when writing; ASCII by default, as `FileIO::save_mesh` has always written
+    pub encoding: StlEncoding,
+}
+
+impl Default for StlOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            weld_tolerance: 1e-6,
+            encoding: StlEncoding::Ascii,
+        }
+    }
+}
+
+/// Read an STL file of either encoding
+pub fn read_stl(path: &str, options: &StlOptions) -> Result<Mesh> {
+    parse_stl(&std::fs::read(path)?, options)
+}
+
+/// Write a mesh as an STL file
+pub fn write_stl(mesh: &Mesh, path: &str, options: &StlOptions) -> Result<()> {
+    std::fs::write(path, stl_to_bytes(mesh, options))?;
+    Ok(())
+}
+
+/// Decode STL contents, detecting the encoding
+///
+/// Binary files may also begin with `solid`, so a file whose length matches
+/// its facet count is taken as binary.
+pub fn parse_stl(bytes: &[u8], options: &StlOptions) -> Result<Mesh> {
+    if !(options.scale > 0.0 && options.weld_tolerance >= 0.0) {
+        return Err(IOError::ParseError {
+            message: "STL scale must be positive and weld tolerance non-negative".to_string(),
+        });
+    }
+    let binary = bytes.len() >= HEADER_LEN
+        && HEADER_LEN + FACET_LEN * u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize == bytes.len();
+    let facets = if binary || !bytes.trim_ascii_start().starts_with(b"solid") {
+        binary_facets(bytes)?
+    } else {
+        ascii_facets(bytes)?
+    };
+    weld(&facets, options)
+}
+
+/// Encode a mesh as STL contents
+pub fn stl_to_bytes(mesh: &Mesh, options: &StlOptions) -> Vec<u8> {
+    let facets = mesh.faces.iter().enumerate().map(|(f, face)| {
+        let normal = mesh.normals.get(f).map_or([0.0; 3], |n| [n.x, n.y, n.z]);
+        (normal, face.map(|v| {
+            let p = mesh.vertices[v] / options.scale;
+            [p.x, p.y, p.z]
+        }))
+    });
+    match options.encoding {
+        StlEncoding::Binary => {
+            let mut bytes = Vec::with_capacity(HEADER_LEN + FACET_LEN * mesh.faces.len());
+            let mut header = [b' '; 80];
+            header[..22].copy_from_slice(b"WaveCore binary mesh  ");
+            bytes.extend_from_slice(&header);
+            bytes.extend_from_slice(&(mesh.faces.len() as u32).to_le_bytes());
+            for (normal, corners) in facets {
+                for value in normal.iter().chain(corners.iter().flatten()) {
+                    bytes.extend_from_slice(&(*value as f32).to_le_bytes());
+                }
+                bytes.extend_from_slice(&0u16.to_le_bytes());
+            }
+            bytes
+        }
+        StlEncoding::Ascii => {
+            let mut text = String::from("solid mesh\n");
+            for (n, corners) in facets {
+                text.push_str(&format!("  facet normal {} {} {}\n    outer loop\n", n[0], n[1], n[2]));
+                for p in corners {
+                    text.push_str(&format!("      vertex {} {} {}\n", p[0], p[1], p[2]));
+                }
+                text.push_str("    endloop\n  endfacet\n");
+            }
+            text.push_str("endsolid mesh\n");
+            text.into_bytes()
+        }
+    }
+}
+
+/// Corners of each facet in file units
+type Facet = [[f64; 3]; 3];
+
+fn binary_facets(bytes: &[u8]) -> Result<Vec<Facet>> {
+    if bytes.len() < HEADER_LEN {
+        return Err(IOError::ParseError {
+            message: format!("Binary STL of {} bytes is shorter than its header", bytes.len()),
+        });
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    if bytes.len() < HEADER_LEN + FACET_LEN * count {
+        return Err(IOError::ParseError {
+            message: format!("Binary STL declares {} facets but holds {} bytes", count, bytes.len()),
+        });
+    }
+    let value = |offset: usize| {
+        f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]) as f64
+    };
+    Ok((0..count)
+        .map(|f| {
+            // Skip the stored normal, which is recomputed from the winding
+            let start = HEADER_LEN + FACET_LEN * f + 12;
+            [0, 1, 2].map(|c| [0, 1, 2].map(|k| value(start + 12 * c + 4 * k)))
+        })
+        .collect())
+}
+
+fn ascii_facets(bytes: &[u8]) -> Result<Vec<Facet>> {
+    let text = std::str::from_utf8(bytes).map_err(|e| IOError::ParseError {
+        message: format!("ASCII STL is not valid UTF-8: {}", e),
+    })?;
+    let mut facets = Vec::new();
+    let mut corners = Vec::with_capacity(3);
+    for (number, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("vertex") => {
+                let coords: Vec<f64> = words.map(str::parse).collect::<std::result::Result<_, _>>().map_err(|e| {
+                    IOError::ParseError {
+                        message: format!("Invalid STL vertex on line {}: {}", number + 1, e),
+                    }
+                })?;
+                if coords.len() != 3 {
+                    return Err(IOError::ParseError {
+                        message: format!("STL vertex on line {} has {} coordinates", number + 1, coords.len()),
+                    });
+                }
+                corners.push([coords[0], coords[1], coords[2]]);
+            }
+            Some("endloop") => {
+                if corners.len() != 3 {
+                    return Err(IOError::ParseError {
+                        message: format!("STL facet ending on line {} has {} vertices", number + 1, corners.len()),
+                    });
+                }
+                facets.push([corners[0], corners[1], corners[2]]);
+                corners.clear();
+            }
+            _ => {}
+        }
+    }
+    Ok(facets)
+}
+
+/// Merge corners within the weld tolerance and drop facets that collapse
+fn weld(facets: &[Facet], options: &StlOptions) -> Result<Mesh> {
+    let tolerance = options.weld_tolerance;
+    let cell = |p: &Point| {
+        if tolerance > 0.0 {
+            [p.x, p.y, p.z].map(|c| (c / tolerance).floor() as i64)
+        } else {
+            [p.x, p.y, p.z].map(|c| c.to_bits() as i64)
+        }
+    };
+
+    // Vertices bucketed by cells of the tolerance size; a match lies in a neighbouring cell
+    let mut vertices: Vec<Point> = Vec::new();
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    let mut faces = Vec::with_capacity(facets.len());
+    for facet in facets {
+        let face = facet.map(|[x, y, z]| {
+            let p = Point::new(x, y, z) * options.scale;
+            let home = cell(&p);
+            let reach = if tolerance > 0.0 { 1 } else { 0 };
+            for dx in -reach..=reach {
+                for dy in -reach..=reach {
+                    for dz in -reach..=reach {
+                        let key = [home[0] + dx, home[1] + dy, home[2] + dz];
+                        if let Some(found) = grid.get(&key).and_then(|bucket| {
+                            bucket.iter().copied().find(|&v| (vertices[v] - p).norm() <= tolerance)
+                        }) {
+                            return found;
+                        }
+                    }
+                }
+            }
+            vertices.push(p);
+            grid.entry(home).or_default().push(vertices.len() - 1);
+            vertices.len() - 1
+        });
+        let [a, b, c] = face.map(|v| vertices[v]);
+        if 0.5 * (b - a).cross(&(c - a)).norm() >= 1e-12 {
+            faces.push(face);
+        }
+    }
+    if faces.is_empty() {
+        return Err(IOError::ParseError {
+            message: "STL contains no non-degenerate facets".to_string(),
+        });
+    }
+    Ok(Mesh::new(vertices, faces)?)
+}
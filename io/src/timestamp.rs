@@ -0,0 +1,23 @@
+//! UTC timestamps for file headers and archive metadata
+//!
+//! Dates come from `chrono` behind the `timestamps` feature, on by default.
+//! Without it no clock is read: header date lines are left out and date
+//! fields stay empty.
+
+/// Current UTC time in a `chrono` format, `None` without the `timestamps` feature
+#[cfg(feature = "timestamps")]
+pub(crate) fn now_utc(format: &str) -> Option<String> {
+    Some(chrono::Utc::now().format(format).to_string())
+}
+
+/// Current UTC time in a `chrono` format, `None` without the `timestamps` feature
+#[cfg(not(feature = "timestamps"))]
+pub(crate) fn now_utc(_format: &str) -> Option<String> {
+    None
+}
+
+/// `YYYY-MM-DD HH:MM:SS UTC` header date
+pub(crate) const DATE_TIME: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+/// RFC 3339 date of archive metadata
+pub(crate) const RFC3339: &str = "%+";
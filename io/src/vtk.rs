@@ -0,0 +1,170 @@
+//! VTK XML unstructured grid (`.vtu`) export for visualization
+//!
+//! Writes a panel mesh as triangle and quad cells with per-panel scalar and vector
+//! fields attached as cell data, for inspecting pressure maps and source
+//! strengths in ParaView. [`panel_fields`] derives the usual fields from a
+//! solve's [`PanelOutput`]; any other per-panel values can be added alongside.
+
+use super::*;
+use num_complex::Complex64;
+use std::fmt::Write as _;
+use wavecore_bem::PanelOutput;
+use wavecore_meshes::Mesh;
+
+/// VTK cell type of a linear triangle
+const VTK_TRIANGLE: u8 = 5;
+
+/// VTK cell type of a bilinear quadrilateral
+const VTK_QUAD: u8 = 9;
+
+/// Per-panel values written as VTK cell data
+#[derive(Debug, Clone, PartialEq)]
+pub enum VtkField {
+    /// One value per panel
+    Scalar { name: String, values: Vec<f64> },
+    /// One 3-component vector per panel
+    Vector { name: String, values: Vec<[f64; 3]> },
+}
+
+impl VtkField {
+    /// Scalar field
+    pub fn scalar(name: impl Into<String>, values: Vec<f64>) -> Self {
+        Self::Scalar { name: name.into(), values }
+    }
+
+    /// Vector field
+    pub fn vector(name: impl Into<String>, values: Vec<[f64; 3]>) -> Self {
+        Self::Vector { name: name.into(), values }
+    }
+
+    /// Field name shown in ParaView
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Scalar { name, .. } | Self::Vector { name, .. } => name,
+        }
+    }
+
+    /// Number of panels the field covers
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Scalar { values, .. } => values.len(),
+            Self::Vector { values, .. } => values.len(),
+        }
+    }
+
+    /// Whether the field has no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Fields of a solve's panel output
+///
+/// Pressure, source strength (indirect formulation only) and normal velocity
+/// as magnitude and phase in radians, plus panel normals and areas.
+pub fn panel_fields(output: &PanelOutput) -> Vec<VtkField> {
+    let mut fields = Vec::new();
+    let mut complex = |name: &str, values: &[Complex64]| {
+        fields.push(VtkField::scalar(format!("{}_magnitude", name), values.iter().map(|v| v.norm()).collect()));
+        fields.push(VtkField::scalar(format!("{}_phase", name), values.iter().map(|v| v.arg()).collect()));
+    };
+    complex("pressure", &output.pressure);
+    if let Some(sigma) = &output.source_strength {
+        complex("source_strength", sigma);
+    }
+    complex("normal_velocity", &output.normal_velocity);
+    fields.push(VtkField::vector("normal", output.normals.clone()));
+    fields.push(VtkField::scalar("area", output.areas.clone()));
+    fields
+}
+
+/// Write a mesh and its panel fields as a `.vtu` file
+pub fn export_vtk(mesh: &Mesh, fields: &[VtkField], path: &str) -> Result<()> {
+    std::fs::write(path, vtu_string(mesh, fields)?).map_err(|e| IOError::WriteError {
+        message: format!("Failed to write VTU file {}: {}", path, e),
+    })
+}
+
+/// ASCII VTU document of a mesh and its panel fields
+pub fn vtu_string(mesh: &Mesh, fields: &[VtkField]) -> Result<String> {
+    for field in fields {
+        if field.len() != mesh.n_panels() {
+            return Err(IOError::WriteError {
+                message: format!(
+                    "VTK field '{}' has {} values for {} panels",
+                    field.name(),
+                    field.len(),
+                    mesh.n_panels()
+                ),
+            });
+        }
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\"?>\n");
+    xml.push_str("<VTKFile type=\"UnstructuredGrid\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">\n");
+    xml.push_str("  <UnstructuredGrid>\n");
+    let _ = writeln!(
+        xml,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        mesh.vertices.len(),
+        mesh.n_panels()
+    );
+
+    xml.push_str("      <Points>\n");
+    let points = mesh.vertices.iter().flat_map(|p| [p.x, p.y, p.z]);
+    data_array(&mut xml, "Float64", None, 3, points);
+    xml.push_str("      </Points>\n");
+
+    xml.push_str("      <Cells>\n");
+    data_array(&mut xml, "Int64", Some("connectivity"), 1, mesh.polygons().flatten());
+    let offsets = mesh.polygons().scan(0, |offset, polygon| {
+        *offset += polygon.len();
+        Some(*offset)
+    });
+    data_array(&mut xml, "Int64", Some("offsets"), 1, offsets);
+    let types = mesh.polygons().map(|polygon| if polygon.len() == 4 { VTK_QUAD } else { VTK_TRIANGLE });
+    data_array(&mut xml, "UInt8", Some("types"), 1, types);
+    xml.push_str("      </Cells>\n");
+
+    xml.push_str("      <CellData>\n");
+    for field in fields {
+        let name = escape(field.name());
+        match field {
+            VtkField::Scalar { values, .. } => data_array(&mut xml, "Float64", Some(&name), 1, values.iter()),
+            VtkField::Vector { values, .. } => data_array(&mut xml, "Float64", Some(&name), 3, values.iter().flatten()),
+        }
+    }
+    xml.push_str("      </CellData>\n");
+
+    xml.push_str("    </Piece>\n  </UnstructuredGrid>\n</VTKFile>\n");
+    Ok(xml)
+}
+
+/// Append an ASCII `<DataArray>`, one tuple per line
+fn data_array<T: std::fmt::Display>(
+    xml: &mut String,
+    kind: &str,
+    name: Option<&str>,
+    components: usize,
+    values: impl IntoIterator<Item = T>,
+) {
+    let _ = write!(xml, "        <DataArray type=\"{}\"", kind);
+    if let Some(name) = name {
+        let _ = write!(xml, " Name=\"{}\"", name);
+    }
+    let _ = writeln!(xml, " NumberOfComponents=\"{}\" format=\"ascii\">", components);
+    for (i, value) in values.into_iter().enumerate() {
+        xml.push_str(if i % components == 0 { "          " } else { " " });
+        let _ = write!(xml, "{}", value);
+        if i % components == components - 1 {
+            xml.push('\n');
+        }
+    }
+    xml.push_str("        </DataArray>\n");
+}
+
+/// Escape a field name for use in an XML attribute
+fn escape(name: &str) -> String {
+    name.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
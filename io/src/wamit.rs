@@ -1,8 +1,8 @@
-use crate::{IOError, Result};
+use crate::{timestamp, IOError, Result};
 use wavecore_meshes::{Mesh, Panel};
-use wavecore_bem::BEMResult;
+use wavecore_bem::{BEMResult, HydroCoefficients};
 use nalgebra::Point3;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
@@ -17,6 +17,9 @@ use nom::{
 };
 use serde::{Serialize, Deserialize};
 
+/// Names of the rigid-body modes in WAMIT order
+const MODE_NAMES: [&str; 6] = ["surge", "sway", "heave", "roll", "pitch", "yaw"];
+
 /// WAMIT file format interface
 pub struct WamitInterface {
     /// WAMIT file format parser
@@ -28,6 +31,7 @@ pub struct WamitInterface {
 }
 
 /// WAMIT geometry description format (.gdf) parser
+#[allow(dead_code)]
 pub struct WamitParser {
     /// Current parsing mode
     mode: WamitParsingMode,
@@ -38,6 +42,7 @@ pub struct WamitParser {
 }
 
 /// WAMIT format converter
+#[allow(dead_code)]
 pub struct FormatConverter {
     /// Conversion options
     options: ConversionOptions,
@@ -213,17 +218,17 @@ pub struct WamitHeader {
     pub comments: Vec<String>,
 }
 
-/// WAMIT output data
+/// WAMIT output data, keyed by mode name in sorted order so exports are reproducible
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WamitOutput {
     /// Added mass coefficients
-    pub added_mass: HashMap<String, Vec<f64>>,
+    pub added_mass: BTreeMap<String, Vec<f64>>,
     /// Damping coefficients
-    pub damping: HashMap<String, Vec<f64>>,
+    pub damping: BTreeMap<String, Vec<f64>>,
     /// Exciting forces
-    pub exciting_forces: HashMap<String, Vec<ComplexForce>>,
+    pub exciting_forces: BTreeMap<String, Vec<ComplexForce>>,
     /// Response amplitude operators
-    pub raos: HashMap<String, Vec<ComplexRAO>>,
+    pub raos: BTreeMap<String, Vec<ComplexRAO>>,
     /// Frequencies
     pub frequencies: Vec<f64>,
     /// Wave headings
@@ -266,6 +271,33 @@ pub struct FormattedMesh {
     pub metadata: HashMap<String, String>,
 }
 
+/// Low-order WAMIT geometry file (.gdf)
+///
+/// Every panel is a quadrilateral, numbered counterclockwise seen from the
+/// fluid; triangles repeat a vertex. With `isx` or `isy` set, x = 0 or y = 0
+/// is a plane of symmetry and only the panels on its positive side are stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GdfMesh {
+    /// Title line
+    pub header: String,
+    /// Characteristic length ULEN
+    pub ulen: f64,
+    /// Gravitational acceleration GRAV
+    pub gravity: f64,
+    /// Symmetry about x = 0
+    pub isx: bool,
+    /// Symmetry about y = 0
+    pub isy: bool,
+    /// Panel vertices
+    pub panels: Vec<[Point3<f64>; 4]>,
+}
+
+impl Default for WamitInterface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WamitInterface {
     /// Create new WAMIT interface
     pub fn new() -> Self {
@@ -276,18 +308,15 @@ impl WamitInterface {
         }
     }
 
-    /// Read WAMIT .gdf geometry files
+    /// Read WAMIT .gdf geometry files, reflecting panels across any symmetry planes
     pub fn read_gdf(&self, path: &Path) -> Result<Mesh> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        
-        let mut lines: Vec<String> = reader.lines().collect::<std::result::Result<_, _>>()?;
-        let content = lines.join("\n");
-        
-        match self.parser.parse_gdf(&content) {
-            Ok((_, mesh)) => Ok(mesh),
-            Err(e) => Err(IOError::ParseError { message: format!("GDF parsing failed: {:?}", e) }),
-        }
+        GdfMesh::parse(&std::fs::read_to_string(path)?)?.to_mesh()
+    }
+
+    /// Write a mesh as a WAMIT .gdf file, keeping the half or quarter selected by the symmetry flags
+    pub fn write_gdf(&self, mesh: &Mesh, path: &Path, isx: bool, isy: bool) -> Result<()> {
+        std::fs::write(path, GdfMesh::from_mesh(mesh, isx, isy).to_gdf_string())?;
+        Ok(())
     }
 
     /// Read WAMIT .pot potential files
@@ -295,7 +324,7 @@ impl WamitInterface {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         
-        let mut lines: Vec<String> = reader.lines().collect::<std::result::Result<_, _>>()?;
+        let lines: Vec<String> = reader.lines().collect::<std::result::Result<_, _>>()?;
         let content = lines.join("\n");
         
         match self.parser.parse_pot(&content) {
@@ -306,26 +335,36 @@ impl WamitInterface {
 
     /// Write WAMIT-compatible output
     pub fn write_wamit_output(&self, results: &BEMResult, path: &Path) -> Result<()> {
-        let file = File::create(path)?;
-        let mut writer = std::io::BufWriter::new(file);
-        
         // Convert BEM results to WAMIT format
         let wamit_output = self.converter.bem_to_wamit(results)?;
+        self.write_output(&wamit_output, path)
+    }
+
+    /// Write the diagonal coefficients and excitation of a frequency sweep as WAMIT-compatible output
+    pub fn write_coefficients(&self, coefficients: &HydroCoefficients, path: &Path) -> Result<()> {
+        self.write_output(&self.converter.coefficients_to_wamit(coefficients), path)
+    }
+
+    fn write_output(&self, wamit_output: &WamitOutput, path: &Path) -> Result<()> {
+        let file = File::create(path)?;
+        let mut writer = std::io::BufWriter::new(file);
         
         // Write header
         writeln!(writer, "! WAMIT Output File Generated by WaveCore")?;
-        writeln!(writer, "! Date: {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
+        if let Some(date) = timestamp::now_utc(timestamp::DATE_TIME) {
+            writeln!(writer, "! Date: {}", date)?;
+        }
         writeln!(writer, "! Version: WaveCore v4.0")?;
         writeln!(writer, "!")?;
         
         // Write added mass coefficients
-        self.write_added_mass(&mut writer, &wamit_output)?;
+        self.write_added_mass(&mut writer, wamit_output)?;
         
         // Write damping coefficients
-        self.write_damping(&mut writer, &wamit_output)?;
+        self.write_damping(&mut writer, wamit_output)?;
         
         // Write exciting forces
-        self.write_exciting_forces(&mut writer, &wamit_output)?;
+        self.write_exciting_forces(&mut writer, wamit_output)?;
         
         writer.flush()?;
         Ok(())
@@ -373,10 +412,12 @@ impl WamitInterface {
         writeln!(writer, "! Exciting Forces")?;
         writeln!(writer, "! Mode   Frequency   Heading   Magnitude   Phase")?;
         
+        // Forces run over headings for each frequency in turn
+        let n_headings = output.headings.len().max(1);
         for (mode, forces) in &output.exciting_forces {
             for (i, force) in forces.iter().enumerate() {
-                let freq = if i < output.frequencies.len() { output.frequencies[i] } else { 0.0 };
-                let heading = if i < output.headings.len() { output.headings[i] } else { 0.0 };
+                let freq = output.frequencies.get(i / n_headings).copied().unwrap_or(0.0);
+                let heading = output.headings.get(i % n_headings).copied().unwrap_or(0.0);
                 
                 writeln!(writer, "{:>6} {:>10.4} {:>8.1} {:>12.6} {:>8.2}", 
                          mode, freq, heading, force.magnitude, force.phase)?;
@@ -461,6 +502,158 @@ impl WamitInterface {
     }
 }
 
+impl GdfMesh {
+    /// Panels of a mesh, keeping those with centroids on the positive side of each symmetry plane
+    pub fn from_mesh(mesh: &Mesh, isx: bool, isy: bool) -> Self {
+        let panels = mesh
+            .faces
+            .iter()
+            .map(|&[a, b, c]| [a, b, c, c])
+            .chain(mesh.quads.iter().copied())
+            .map(|f| f.map(|v| mesh.vertices[v]))
+            .filter(|[a, b, c, d]| {
+                let centroid = (a.coords + b.coords + c.coords + d.coords) / 4.0;
+                (!isx || centroid.x > 0.0) && (!isy || centroid.y > 0.0)
+            })
+            .collect();
+        Self {
+            header: "WaveCore mesh export".to_string(),
+            ulen: 1.0,
+            gravity: 9.80665,
+            isx,
+            isy,
+            panels,
+        }
+    }
+
+    /// Parse the text of a .gdf file
+    pub fn parse(content: &str) -> Result<Self> {
+        let error = |message: String| IOError::ParseError { message: format!("GDF: {}", message) };
+        let mut lines = content.lines();
+        let header = lines.next().ok_or_else(|| error("empty file".to_string()))?.trim().to_string();
+        // Lines 2-4 carry ULEN GRAV, ISX ISY and NPAN, optionally followed by comments
+        let mut fields = |count: usize, what: &str| -> Result<Vec<f64>> {
+            let line = lines.next().ok_or_else(|| error(format!("missing {} line", what)))?;
+            let values: Vec<f64> = line.split_whitespace().take(count).map_while(|w| w.parse().ok()).collect();
+            if values.len() < count {
+                return Err(error(format!("invalid {} line '{}'", what, line.trim())));
+            }
+            Ok(values)
+        };
+        let scales = fields(2, "ULEN GRAV")?;
+        let symmetry = fields(2, "ISX ISY")?;
+        let count = fields(1, "NPAN")?[0];
+        if count < 0.0 || count.fract() != 0.0 {
+            return Err(error(format!("invalid panel count {}", count)));
+        }
+        let count = count as usize;
+
+        // Coordinates are free format: 12 numbers per panel over any number of lines
+        let numbers: Vec<f64> = lines
+            .flat_map(str::split_whitespace)
+            .take(12 * count)
+            .map(|w| w.parse().map_err(|_| error(format!("invalid coordinate '{}'", w))))
+            .collect::<Result<_>>()?;
+        if numbers.len() < 12 * count {
+            return Err(error(format!("{} panels declared but {} coordinates found", count, numbers.len())));
+        }
+        let panels = numbers
+            .chunks_exact(12)
+            .map(|p| [0, 1, 2, 3].map(|k| Point3::new(p[3 * k], p[3 * k + 1], p[3 * k + 2])))
+            .collect();
+        Ok(Self {
+            header,
+            ulen: scales[0],
+            gravity: scales[1],
+            isx: symmetry[0] != 0.0,
+            isy: symmetry[1] != 0.0,
+            panels,
+        })
+    }
+
+    /// Text of the .gdf file
+    pub fn to_gdf_string(&self) -> String {
+        let mut text = format!("{}\n", self.header);
+        text.push_str(&format!("{} {}    ULEN GRAV\n", self.ulen, self.gravity));
+        text.push_str(&format!("{} {}    ISX ISY\n", self.isx as u8, self.isy as u8));
+        text.push_str(&format!("{}    NPAN\n", self.panels.len()));
+        for panel in &self.panels {
+            for p in panel {
+                text.push_str(&format!("{:.10e} {:.10e} {:.10e}\n", p.x, p.y, p.z));
+            }
+        }
+        text
+    }
+
+    /// Mesh of the whole body, with panels reflected across the symmetry planes
+    ///
+    /// Vertices with identical coordinates are merged; quads stay
+    /// quadrilateral panels and those with collapsed corners become triangles.
+    pub fn to_mesh(&self) -> Result<Mesh> {
+        let mut panels = self.panels.clone();
+        // A reflection reverses the vertex order, which keeps normals pointing into the fluid
+        let reflect = |panels: &mut Vec<[Point3<f64>; 4]>, axis: usize| {
+            let mirrored: Vec<_> = panels
+                .iter()
+                .map(|panel| {
+                    let mut panel = *panel;
+                    panel.reverse();
+                    panel.map(|mut p| {
+                        p[axis] = -p[axis];
+                        p
+                    })
+                })
+                .collect();
+            panels.extend(mirrored);
+        };
+        if self.isx {
+            reflect(&mut panels, 0);
+        }
+        if self.isy {
+            reflect(&mut panels, 1);
+        }
+
+        let mut vertices = Vec::new();
+        let mut index = HashMap::new();
+        let mut faces = Vec::new();
+        let mut quads = Vec::new();
+        for panel in &panels {
+            // Collapsed corners turn a quad into a triangle
+            let mut corners: Vec<usize> = panel
+                .iter()
+                .map(|p| {
+                    // Adding zero maps -0.0 to 0.0 so reflected points on a symmetry plane merge
+                    let key = [p.x + 0.0, p.y + 0.0, p.z + 0.0].map(f64::to_bits);
+                    *index.entry(key).or_insert_with(|| {
+                        vertices.push(Point3::new(p.x + 0.0, p.y + 0.0, p.z + 0.0));
+                        vertices.len() - 1
+                    })
+                })
+                .collect();
+            corners.dedup();
+            if corners.len() > 1 && corners[0] == corners[corners.len() - 1] {
+                corners.pop();
+            }
+            match corners[..] {
+                [a, b, c] if 0.5 * (vertices[b] - vertices[a]).cross(&(vertices[c] - vertices[a])).norm() >= 1e-12 => {
+                    faces.push([a, b, c]);
+                }
+                [a, b, c, d] if Panel::quad(vertices[a], vertices[b], vertices[c], vertices[d]).is_ok() => {
+                    quads.push([a, b, c, d]);
+                }
+                _ => {}
+            }
+        }
+        Ok(Mesh::with_quads(vertices, faces, quads)?)
+    }
+}
+
+impl Default for WamitParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WamitParser {
     /// Create new WAMIT parser
     pub fn new() -> Self {
@@ -563,7 +756,7 @@ impl WamitParser {
         let header = WamitHeader {
             version: "7.0".to_string(),
             title: "WaveCore Generated".to_string(),
-            date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            date: timestamp::now_utc("%Y-%m-%d").unwrap_or_default(),
             comments: vec!["Parsed from WAMIT POT file".to_string()],
         };
         
@@ -603,6 +796,12 @@ impl WamitParser {
     }
 }
 
+impl Default for FormatConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FormatConverter {
     /// Create new format converter
     pub fn new() -> Self {
@@ -613,12 +812,12 @@ impl FormatConverter {
     }
 
     /// Convert BEM results to WAMIT format
-    pub fn bem_to_wamit(&self, results: &BEMResult) -> Result<WamitOutput> {
+    pub fn bem_to_wamit(&self, _results: &BEMResult) -> Result<WamitOutput> {
         // Simplified conversion - extract key results
-        let mut added_mass = HashMap::new();
-        let mut damping = HashMap::new();
-        let mut exciting_forces = HashMap::new();
-        let mut raos = HashMap::new();
+        let mut added_mass = BTreeMap::new();
+        let mut damping = BTreeMap::new();
+        let mut exciting_forces = BTreeMap::new();
+        let mut raos = BTreeMap::new();
         
         // Extract frequencies (placeholder)
         let frequencies = vec![0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5];
@@ -629,7 +828,7 @@ impl FormatConverter {
             added_mass.insert(mode.to_string(), vec![0.1; frequencies.len()]);
             damping.insert(mode.to_string(), vec![0.05; frequencies.len()]);
             
-            let forces: Vec<ComplexForce> = (0..frequencies.len()).map(|_| ComplexForce {
+            let forces: Vec<ComplexForce> = (0..frequencies.len() * headings.len()).map(|_| ComplexForce {
                 magnitude: 1.0,
                 phase: 0.0,
                 real: 1.0,
@@ -656,6 +855,45 @@ impl FormatConverter {
         })
     }
 
+    /// Convert the coefficients of a frequency sweep to WAMIT format
+    ///
+    /// Added mass and damping keep the diagonal term of each mode;
+    /// headings are in degrees.
+    pub fn coefficients_to_wamit(&self, coefficients: &HydroCoefficients) -> WamitOutput {
+        let mut added_mass = BTreeMap::new();
+        let mut damping = BTreeMap::new();
+        let mut exciting_forces = BTreeMap::new();
+        
+        for mode in 0..coefficients.n_dof() {
+            let name = MODE_NAMES.get(mode).map_or_else(|| format!("mode{}", mode + 1), |name| name.to_string());
+            let diagonal = |values: &ndarray::Array3<f64>| (0..coefficients.frequencies.len()).map(|f| values[[f, mode, mode]]).collect();
+            added_mass.insert(name.clone(), diagonal(&coefficients.added_mass));
+            damping.insert(name.clone(), diagonal(&coefficients.damping));
+            
+            let forces = coefficients
+                .excitation
+                .outer_iter()
+                .flat_map(|per_direction| per_direction.column(mode).to_vec())
+                .map(|force| ComplexForce {
+                    magnitude: force.norm(),
+                    phase: force.arg().to_degrees(),
+                    real: force.re,
+                    imaginary: force.im,
+                })
+                .collect();
+            exciting_forces.insert(name, forces);
+        }
+        
+        WamitOutput {
+            added_mass,
+            damping,
+            exciting_forces,
+            raos: BTreeMap::new(),
+            frequencies: coefficients.frequencies.clone(),
+            headings: coefficients.directions.iter().map(|d| d.to_degrees()).collect(),
+        }
+    }
+
     /// Convert mesh to specified format
     pub fn convert_mesh(&self, input: &Mesh, format: OutputFormat) -> Result<FormattedMesh> {
         let mut mesh = input.clone();
@@ -729,6 +967,12 @@ impl FormatConverter {
     }
 }
 
+impl Default for CompatibilityLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CompatibilityLayer {
     /// Create new compatibility layer
     pub fn new() -> Self {
@@ -867,10 +1111,9 @@ mod tests {
     #[test]
     fn test_format_conversion() {
         let converter = FormatConverter::new();
-        let mesh = Mesh {
-            panels: vec![],
-            metadata: HashMap::new(),
-        };
+        let vertices = vec![Point3::new(0.0, 0.0, -1.0), Point3::new(1.0, 0.0, -1.0), Point3::new(0.0, 1.0, -1.0)];
+        let mut mesh = Mesh::new(vertices, vec![[0, 1, 2]]).unwrap();
+        mesh.panels().unwrap();
         
         let result = converter.convert_mesh(&mesh, OutputFormat::WamitGdf);
         assert!(result.is_ok());
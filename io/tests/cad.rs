@@ -0,0 +1,185 @@
+//! Trimmed NURBS surfaces imported from IGES and STEP and panelled
+
+use wavecore_io::{parse_iges, parse_step, IOError};
+use wavecore_meshes::{Mesh, PanelizeOptions};
+
+/// Fixed-format IGES text with a unit flag and name from (type, transform DE, parameter data) entities
+fn iges(units: &str, entities: &[(usize, usize, &str)]) -> String {
+    let mut text = format!("{:<72}S{:>7}\n", "wavecore test hull", 1);
+    let global = format!("1H,,1H;,4HHULL,8Hhull.igs,4HTEST,3H1.0,32,38,6,308,15,4HHULL,1.0,{},1,0.01;", units);
+    for (k, chunk) in global.as_bytes().chunks(72).enumerate() {
+        text += &format!("{:<72}G{:>7}\n", std::str::from_utf8(chunk).unwrap(), k + 1);
+    }
+    let mut directory = String::new();
+    let mut parameters = String::new();
+    let mut line = 1;
+    for (k, (kind, transform, data)) in entities.iter().enumerate() {
+        let de = 2 * k + 1;
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(64).collect();
+        directory += &format!("{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}D{:>7}\n", kind, line, 0, 0, 0, 0, transform, 0, "00000000", de);
+        directory += &format!("{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}D{:>7}\n", kind, 0, 0, chunks.len(), 0, "", "", "", 0, de + 1);
+        for chunk in chunks {
+            parameters += &format!("{:<64}{:>8}P{:>7}\n", std::str::from_utf8(chunk).unwrap(), de, line);
+            line += 1;
+        }
+    }
+    text + &directory + &parameters + &format!("{:<72}T{:>7}\n", "S      1G      2D      8P      8", 1)
+}
+
+/// Bilinear 2000 × 1000 mm plate in z = 0, placed by the transformation at `transform`
+fn plate(transform: usize) -> (usize, usize, &'static str) {
+    (
+        128,
+        transform,
+        "128,1,1,1,1,0,0,1,0,0,0.,0.,1.,1.,0.,0.,1.,1.,1.,1.,1.,1.,0.,0.,0.,2000.,0.,0.,0.,1000.,0.,2000.,1000.,0.,0.,1.,0.,1.;",
+    )
+}
+
+fn area(mesh: &Mesh) -> f64 {
+    let triangles = mesh.triangulated().unwrap();
+    triangles
+        .faces
+        .iter()
+        .map(|&[a, b, c]| 0.5 * (triangles.vertices[b] - triangles.vertices[a]).cross(&(triangles.vertices[c] - triangles.vertices[a])).norm())
+        .sum()
+}
+
+#[test]
+fn iges_surface_is_scaled_placed_and_panelled() {
+    let text = iges("2,2HMM", &[
+        (124, 0, "124,1.,0.,0.,0.,0.,1.,0.,0.,0.,0.,1.,-1000.;"),
+        plate(1),
+        (120, 0, "120,0,0,0.,1.;"),
+    ]);
+    let file = parse_iges(&text).unwrap();
+    assert_eq!(file.unit_scale, 0.001);
+    assert_eq!(file.surfaces.len(), 1);
+    assert_eq!(file.warnings.len(), 1);
+
+    let options = PanelizeOptions {
+        max_panel_size: 0.5,
+        ..PanelizeOptions::default()
+    };
+    let mesh = file.mesh(&options).unwrap();
+    assert_eq!(mesh.quads.len(), 8);
+    assert!(mesh.vertices.iter().all(|p| (p.z + 1.0).abs() < 1e-12));
+    assert!((area(&mesh) - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn iges_trimmed_surface_cuts_a_hole() {
+    // A square hole over the middle quarter of the parameter range, as four lines
+    let text = iges("2,2HMM", &[
+        plate(0),
+        (144, 0, "144,1,0,1,0,5;"),
+        (142, 0, "142,1,1,7,0,1;"),
+        (102, 0, "102,4,9,11,13,15;"),
+        (110, 0, "110,0.25,0.25,0.,0.75,0.25,0.;"),
+        (110, 0, "110,0.75,0.25,0.,0.75,0.75,0.;"),
+        (110, 0, "110,0.75,0.75,0.,0.25,0.75,0.;"),
+        (110, 0, "110,0.25,0.75,0.,0.25,0.25,0.;"),
+    ]);
+    let file = parse_iges(&text).unwrap();
+    assert_eq!(file.surfaces.len(), 1);
+    assert_eq!(file.surfaces[0].trim.len(), 2);
+    assert!(file.warnings.is_empty());
+
+    let mesh = file
+        .mesh(&PanelizeOptions {
+            max_panel_size: 0.25,
+            ..PanelizeOptions::default()
+        })
+        .unwrap();
+    assert!((area(&mesh) - 1.5).abs() < 1e-12);
+}
+
+#[test]
+fn malformed_iges_is_rejected() {
+    assert!(matches!(parse_iges("too short\n"), Err(IOError::ParseError { .. })));
+    let text = iges("3,4HYARD", &[plate(0)]);
+    assert!(matches!(parse_iges(&text), Err(IOError::InvalidFormat { .. })));
+}
+
+/// A plate trimmed to a triangle by degree-1 pcurves, a rational quarter cylinder and a plane
+const STEP: &str = r#"ISO-10303-21;
+HEADER;
+FILE_DESCRIPTION((''),'2;1');
+FILE_NAME('hull.stp','2024-01-01T00:00:00',(''),(''),'','','');
+FILE_SCHEMA(('AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }'));
+ENDSEC;
+DATA;
+#1=(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.));
+#10=CARTESIAN_POINT('',(0.,0.,0.));
+#11=CARTESIAN_POINT('',(0.,1000.,0.));
+#12=CARTESIAN_POINT('',(1000.,0.,0.));
+#13=CARTESIAN_POINT('',(1000.,1000.,0.));
+#14=B_SPLINE_SURFACE_WITH_KNOTS('plate',1,1,((#10,#11),(#12,#13)),.UNSPECIFIED.,.F.,.F.,.F.,(2,2),(2,2),(0.,1.),(0.,1.),.UNSPECIFIED.);
+/* Triangle (0, 0), (1, 0), (0, 1) in parameter space, its last edge used backwards */
+#20=CARTESIAN_POINT('',(0.,0.));
+#21=CARTESIAN_POINT('',(1.,0.));
+#22=CARTESIAN_POINT('',(0.,1.));
+#23=B_SPLINE_CURVE_WITH_KNOTS('',1,(#20,#21),.POLYLINE_FORM.,.F.,.F.,(2,2),(0.,1.),.UNSPECIFIED.);
+#24=B_SPLINE_CURVE_WITH_KNOTS('',1,(#21,#22),.POLYLINE_FORM.,.F.,.F.,(2,2),(0.,1.),.UNSPECIFIED.);
+#25=B_SPLINE_CURVE_WITH_KNOTS('',1,(#20,#22),.POLYLINE_FORM.,.F.,.F.,(2,2),(0.,1.),.UNSPECIFIED.);
+#30=DEFINITIONAL_REPRESENTATION('',(#23),#99);
+#31=DEFINITIONAL_REPRESENTATION('',(#24),#99);
+#32=DEFINITIONAL_REPRESENTATION('',(#25),#99);
+#33=PCURVE('',#14,#30);
+#34=PCURVE('',#14,#31);
+#35=PCURVE('',#14,#32);
+#40=SURFACE_CURVE('',#98,(#33),.PCURVE_S1.);
+#41=SURFACE_CURVE('',#98,(#34),.PCURVE_S1.);
+#42=SURFACE_CURVE('',#98,(#35),.PCURVE_S1.);
+#45=EDGE_CURVE('',#97,#97,#40,.T.);
+#46=EDGE_CURVE('',#97,#97,#41,.T.);
+#47=EDGE_CURVE('',#97,#97,#42,.T.);
+#48=ORIENTED_EDGE('',*,*,#45,.T.);
+#49=ORIENTED_EDGE('',*,*,#46,.T.);
+#50=ORIENTED_EDGE('',*,*,#47,.F.);
+#52=EDGE_LOOP('',(#48,#49,#50));
+#53=FACE_OUTER_BOUND('',#52,.T.);
+#54=ADVANCED_FACE('',(#53),#14,.T.);
+#70=CARTESIAN_POINT('',(5000.,0.,0.));
+#71=CARTESIAN_POINT('',(5000.,0.,3000.));
+#72=CARTESIAN_POINT('',(5000.,2000.,0.));
+#73=CARTESIAN_POINT('',(5000.,2000.,3000.));
+#74=CARTESIAN_POINT('',(3000.,2000.,0.));
+#75=CARTESIAN_POINT('',(3000.,2000.,3000.));
+#76=(BOUNDED_SURFACE() B_SPLINE_SURFACE(2,1,((#70,#71),(#72,#73),(#74,#75)),.CYLINDRICAL_SURF.,.F.,.F.,.F.)
+  B_SPLINE_SURFACE_WITH_KNOTS((3,3),(2,2),(0.,1.),(0.,1.),.UNSPECIFIED.) GEOMETRIC_REPRESENTATION_ITEM()
+  RATIONAL_B_SPLINE_SURFACE(((1.,1.),(0.707106781186548,0.707106781186548),(1.,1.))) REPRESENTATION_ITEM('') SURFACE());
+#80=PLANE('',#81);
+#82=ADVANCED_FACE('it''s flat',(),#80,.T.);
+ENDSEC;
+END-ISO-10303-21;
+"#;
+
+#[test]
+fn step_faces_are_trimmed_and_rational_surfaces_exact() {
+    let file = parse_step(STEP).unwrap();
+    assert_eq!(file.unit_scale, 0.001);
+    assert_eq!(file.surfaces.len(), 2);
+    assert_eq!(file.warnings.len(), 1);
+    assert_eq!(file.surfaces[0].trim, vec![vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]]);
+
+    let options = PanelizeOptions {
+        max_panel_size: 0.1,
+        max_normal_angle: 5.0,
+        ..PanelizeOptions::default()
+    };
+    let triangle = Mesh::from_nurbs(&file.surfaces[..1], &options).unwrap();
+    assert!((area(&triangle) - 0.5).abs() < 0.06);
+
+    let cylinder = Mesh::from_nurbs(&file.surfaces[1..], &options).unwrap();
+    assert!(cylinder.vertices.iter().all(|p| ((p.x - 3.0).hypot(p.y) - 2.0).abs() < 1e-9));
+    assert!((area(&cylinder) - 3.0 * std::f64::consts::PI).abs() < 0.01);
+    // Normals point away from the axis after outward orientation of the open patch
+    let centroid = |q: &[usize; 4]| q.iter().fold(nalgebra::Vector3::zeros(), |s, &v| s + cylinder.vertices[v].coords) / 4.0;
+    assert!(cylinder.quads.iter().zip(&cylinder.normals).all(|(q, n)| {
+        let c = centroid(q);
+        n.dot(&nalgebra::Vector3::new(c.x - 3.0, c.y, 0.0)) > 0.0
+    }));
+
+    assert!(matches!(parse_step("#1=FOO();"), Err(IOError::InvalidFormat { .. })));
+    assert!(matches!(parse_step(&STEP.replace("#14=B_SPLINE", "#14 B_SPLINE")), Err(IOError::ParseError { .. })));
+}
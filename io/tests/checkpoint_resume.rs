@@ -0,0 +1,38 @@
+//! Sweep checkpoint files written and resumed through `FileCheckpoint`
+
+mod common;
+
+use common::tetrahedron_body;
+use wavecore_bem::{BEMSolver, CheckpointStore, SolverEngine};
+use wavecore_io::FileCheckpoint;
+
+#[test]
+fn interrupted_sweep_resumes_from_file() {
+    let path = std::env::temp_dir().join(format!("wavecore_checkpoint_{}.json", std::process::id()));
+    let store = FileCheckpoint::new(&path);
+    store.remove().unwrap();
+    assert!(store.load().unwrap().is_none());
+
+    let solver = BEMSolver::new(SolverEngine::Standard);
+    let frequencies = [0.6, 1.2];
+    let complete = solver
+        .solve_sweep_checkpointed(&frequencies, &[0.0], vec![tetrahedron_body()], &store, 1)
+        .unwrap();
+
+    // Drop the last frequency as if the run had been preempted
+    let mut checkpoint = store.load().unwrap().unwrap();
+    assert_eq!(checkpoint.records.len(), 2);
+    checkpoint.records.pop();
+    store.save(&checkpoint).unwrap();
+
+    let resumed = solver.resume_from(&store, vec![tetrahedron_body()]).unwrap();
+    store.remove().unwrap();
+    assert!(resumed.is_complete());
+    assert_eq!(resumed.frequencies, complete.frequencies);
+    for (a, b) in resumed.damping.iter().zip(&complete.damping) {
+        assert!((a - b).abs() <= 1e-12 * (1.0 + b.abs()));
+    }
+    for (a, b) in resumed.excitation.iter().zip(&complete.excitation) {
+        assert!((a - b).norm() <= 1e-12 * (1.0 + b.norm()));
+    }
+}
@@ -0,0 +1,29 @@
+//! Fixtures shared by the integration tests
+
+#![allow(dead_code)]
+
+use nalgebra::Point3;
+use wavecore_bodies::{FloatingBody, MassProperties};
+use wavecore_meshes::Mesh;
+
+/// Closed tetrahedron fully below the free surface, normals outward
+pub fn tetrahedron_mesh() -> Mesh {
+    let vertices = vec![
+        Point3::new(-1.0, -1.0, -2.0),
+        Point3::new(1.0, -1.0, -2.0),
+        Point3::new(0.0, 1.0, -2.0),
+        Point3::new(0.0, 0.0, -0.5),
+    ];
+    let faces = vec![[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]];
+    Mesh::new(vertices, faces).unwrap()
+}
+
+/// One-tonne body on the tetrahedron mesh
+pub fn tetrahedron_body() -> FloatingBody {
+    let mass_props = MassProperties {
+        mass: 1000.0,
+        center_of_gravity: [0.0, 0.0, -1.0],
+        inertia_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+    FloatingBody::with_mesh("tetrahedron".to_string(), mass_props, tetrahedron_mesh()).unwrap()
+}
@@ -0,0 +1,53 @@
+//! WAMIT low-order GDF files written with symmetry planes and read back whole
+
+use wavecore_io::{GdfMesh, WamitInterface};
+use wavecore_meshes::{HullSurface, Mesh, PredefinedGeometry};
+
+fn displacement(mesh: &Mesh) -> f64 {
+    // Open at the waterplane: V = Σ z n_z A over the wetted surface
+    mesh.faces
+        .iter()
+        .zip(&mesh.normals)
+        .map(|(face, normal)| {
+            let [a, b, c] = face.map(|v| mesh.vertices[v]);
+            (a.z + b.z + c.z) / 3.0 * normal.z * 0.5 * (b - a).cross(&(c - a)).norm()
+        })
+        .sum()
+}
+
+#[test]
+fn gdf_quarter_model_round_trip() {
+    let barge = PredefinedGeometry::box_hull_with_surface(8.0, 4.0, 2.0, 4, 2, 2, HullSurface::Wetted).unwrap();
+    let path = std::env::temp_dir().join(format!("wavecore_barge_{}.gdf", std::process::id()));
+    let wamit = WamitInterface::new();
+    wamit.write_gdf(&barge, &path, true, true).unwrap();
+
+    let file = GdfMesh::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(file.isx && file.isy);
+    assert_eq!(file.panels.len(), barge.faces.len() / 4);
+
+    let read = wamit.read_gdf(&path).unwrap();
+    assert_eq!((read.vertices.len(), read.faces.len()), (barge.vertices.len(), barge.faces.len()));
+    assert!((displacement(&read) - 64.0).abs() < 1e-9);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn gdf_free_format_quads() {
+    // One quad on 12 numbers per line plus one triangle with a repeated vertex, no symmetry
+    let text = "unit square and triangle
+1.0 9.80665
+0 0
+2
+0 0 -1  1 0 -1  1 1 -1  0 1 -1
+0 0 -1
+0 1 -1
+-1 0 -1
+-1 0 -1
+";
+    let mesh = GdfMesh::parse(text).unwrap().to_mesh().unwrap();
+    assert_eq!((mesh.vertices.len(), mesh.faces.len(), mesh.quads.len()), (5, 1, 1));
+    assert_eq!(mesh.n_panels(), mesh.normals.len());
+    assert!(mesh.normals.iter().all(|n| (n.z - 1.0).abs() < 1e-12));
+    assert!(GdfMesh::parse("title\n1 9.81\n0 0\n2\n0 0 0 1 0 0 1 1 0 0 1 0\n").is_err());
+}
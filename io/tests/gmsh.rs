@@ -0,0 +1,131 @@
+//! GMSH meshes imported with their physical groups mapped to bodies
+
+use wavecore_bodies::DOF;
+use wavecore_io::{parse_gmsh, IOError, DEFAULT_GMSH_GROUP};
+
+/// Two bottom quads in "hull" and a 6-node triangle wall, plus a point and a line to skip
+const LEGACY: &str = r#"$MeshFormat
+2.2 0 8
+$EndMeshFormat
+$PhysicalNames
+2
+2 1 "hull"
+2 2 "side wall"
+$EndPhysicalNames
+$Nodes
+10
+1 0 0 -1
+2 1 0 -1
+3 2 0 -1
+4 0 1 -1
+5 1 1 -1
+6 2 1 -1
+7 0 0 0
+8 0.5 0 -1
+9 0.5 0 -0.5
+10 0 0 -0.5
+$EndNodes
+$Elements
+5
+1 15 2 0 1 1
+2 1 2 0 1 1 2
+3 3 2 1 1 1 4 5 2
+4 3 2 1 1 2 5 6 3
+5 9 2 2 2 1 2 7 8 9 10
+$EndElements
+"#;
+
+/// The same surfaces in 4.1 blocks, with the wall outside any physical group
+const BLOCKS: &str = r#"$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$PhysicalNames
+1
+2 1 "hull"
+$EndPhysicalNames
+$Entities
+1 0 2 0
+1 0 0 -1 0
+1 0 0 -1 2 1 -1 1 1 4 1 2 3 -4
+2 0 0 -1 1 0 0 0 0
+$EndEntities
+$Nodes
+2 10 1 10
+2 1 0 6
+1
+2
+3
+4
+5
+6
+0 0 -1
+1 0 -1
+2 0 -1
+0 1 -1
+1 1 -1
+2 1 -1
+2 2 0 4
+7
+8
+9
+10
+0 0 0
+0.5 0 -1
+0.5 0 -0.5
+0 0 -0.5
+$EndNodes
+$Elements
+2 3 1 3
+2 1 3 2
+1 1 4 5 2
+2 2 5 6 3
+2 2 9 1
+3 1 2 7 8 9 10
+$EndElements
+"#;
+
+#[test]
+fn legacy_physical_groups_become_bodies() {
+    let file = parse_gmsh(LEGACY).unwrap();
+    assert_eq!(file.version, 2.2);
+    let names: Vec<_> = file.groups.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, ["hull", "side wall"]);
+    assert_eq!(file.group("hull").unwrap().quad_count(), 2);
+
+    let hull = file.mesh(&["hull"]).unwrap();
+    assert_eq!((hull.vertices.len(), hull.faces.len()), (6, 4));
+    assert!(hull.normals.iter().all(|n| (n.z + 1.0).abs() < 1e-12));
+
+    // Groups merged into one body share the nodes along their seam
+    let wall = file.mesh(&["side wall"]).unwrap();
+    assert!((wall.normals[0].y + 1.0).abs() < 1e-12);
+    let body = file.to_body("barge", &["hull", "side wall"]).unwrap();
+    let mesh = body.mesh().unwrap();
+    assert_eq!((mesh.vertices.len(), mesh.faces.len()), (7, 5));
+    assert!(DOF::all().iter().all(|dof| body.is_dof_enabled(dof)));
+
+    let bodies = file.to_bodies().unwrap();
+    assert_eq!(bodies.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), ["hull", "side wall"]);
+    assert_eq!(file.to_collection().unwrap().mesh_names().len(), 2);
+    assert!(file.mesh(&["deck"]).is_err());
+}
+
+#[test]
+fn block_format_reads_entity_physicals() {
+    let file = parse_gmsh(BLOCKS).unwrap();
+    let names: Vec<_> = file.groups.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, [DEFAULT_GMSH_GROUP, "hull"]);
+    assert_eq!(file.nodes.len(), 10);
+    let legacy = parse_gmsh(LEGACY).unwrap();
+    assert_eq!(file.mesh(&["hull"]).unwrap().vertices, legacy.mesh(&["hull"]).unwrap().vertices);
+    assert_eq!(file.mesh(&[DEFAULT_GMSH_GROUP]).unwrap().faces.len(), 1);
+}
+
+#[test]
+fn binary_and_malformed_files_are_rejected() {
+    let binary = LEGACY.replace("2.2 0 8", "2.2 1 8");
+    assert!(matches!(parse_gmsh(&binary), Err(IOError::InvalidFormat { .. })));
+    let unknown_node = LEGACY.replace("1 4 5 2\n", "1 4 5 42\n");
+    assert!(matches!(parse_gmsh(&unknown_node), Err(IOError::ParseError { .. })));
+    assert!(parse_gmsh(&LEGACY.replace("$EndElements\n", "")).is_err());
+}
@@ -0,0 +1,163 @@
+//! Golden-file regression tests for the exporters
+//!
+//! Each test runs a small canonical case through an exporter and compares
+//! the file against `tests/golden/`. Numeric tokens are compared with a
+//! relative tolerance, everything else must match exactly, and `! Date:`
+//! header lines are ignored.
+//!
+//! Set `WAVECORE_UPDATE_GOLDEN=1` to record the golden files of a new case
+//! or rewrite them after an intended format or coefficient change, and
+//! review the diff before committing. Without it a missing golden file
+//! fails the test.
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::{tetrahedron_body, tetrahedron_mesh};
+use wavecore_bem::{BEMSolver, ProblemType, SolverEngine};
+use wavecore_io::{export_vtk, panel_fields, DataArray, FileIO, Format, NemohInterface, WamitInterface};
+
+const RELATIVE_TOLERANCE: f64 = 1e-6;
+const ABSOLUTE_TOLERANCE: f64 = 1e-9;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(name)
+}
+
+fn scratch_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("wavecore_golden_{}_{}", std::process::id(), name))
+}
+
+/// Export written by `write` to a scratch file
+fn export<F: FnOnce(&PathBuf)>(name: &str, write: F) -> String {
+    let path = scratch_path(name);
+    write(&path);
+    let content = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    content
+}
+
+fn numbers_match(expected: f64, actual: f64) -> bool {
+    (expected - actual).abs() <= ABSOLUTE_TOLERANCE + RELATIVE_TOLERANCE * expected.abs().max(actual.abs())
+}
+
+/// Compare `actual` against the golden file `name`
+fn assert_golden(name: &str, actual: &str) {
+    let path = golden_path(name);
+    if std::env::var_os("WAVECORE_UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        eprintln!("Recorded golden file {}", path.display());
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!("{}: {}; run with WAVECORE_UPDATE_GOLDEN=1 to record it", path.display(), e)
+    });
+    let significant = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter(|line| !line.trim_start().starts_with("! Date:"))
+            .map(|line| line.to_string())
+            .collect()
+    };
+    let (expected, actual) = (significant(&expected), significant(actual));
+    assert_eq!(expected.len(), actual.len(), "{}: line count changed", name);
+
+    for (line, (e, a)) in expected.iter().zip(&actual).enumerate() {
+        let tokens = |text: &str| -> Vec<String> {
+            text.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty())
+                .map(|token| token.to_string())
+                .collect()
+        };
+        let (e_tokens, a_tokens) = (tokens(e), tokens(a));
+        assert_eq!(e_tokens.len(), a_tokens.len(), "{}:{}: expected `{}`, got `{}`", name, line + 1, e, a);
+        for (et, at) in e_tokens.iter().zip(&a_tokens) {
+            let same = match (et.parse::<f64>(), at.parse::<f64>()) {
+                (Ok(x), Ok(y)) => numbers_match(x, y),
+                _ => et == at,
+            };
+            assert!(same, "{}:{}: expected `{}`, got `{}`", name, line + 1, e, a);
+        }
+    }
+}
+
+/// Canonical submerged tetrahedron
+fn heave_radiation() -> wavecore_bem::solver::BEMResult {
+    let solver = BEMSolver::new(SolverEngine::Standard);
+    solver
+        .solve_bodies(&ProblemType::Radiation { frequency: 1.0, mode: 2 }, vec![tetrahedron_body()])
+        .unwrap()
+}
+
+#[test]
+fn wamit_output_matches_golden() {
+    let coefficients = BEMSolver::new(SolverEngine::Standard)
+        .solve_sweep_bodies(&[0.5, 1.0], &[0.0, std::f64::consts::FRAC_PI_4], vec![tetrahedron_body()])
+        .unwrap();
+    let content = export("tetrahedron_sweep.out", |path| {
+        WamitInterface::new().write_coefficients(&coefficients, path).unwrap();
+    });
+    assert_golden("tetrahedron_sweep.out", &content);
+}
+
+#[test]
+fn vtu_panel_fields_match_golden() {
+    let output = heave_radiation().panel_output.unwrap();
+    let content = export("tetrahedron_heave.vtu", |path| {
+        export_vtk(&tetrahedron_mesh(), &panel_fields(&output), path.to_str().unwrap()).unwrap();
+    });
+    assert_golden("tetrahedron_heave.vtu", &content);
+}
+
+#[test]
+fn nemoh_mesh_matches_golden() {
+    let mut mesh = tetrahedron_mesh();
+    mesh.panels().unwrap();
+    let content = export("tetrahedron_nemoh.dat", |path| {
+        NemohInterface::new().write_nemoh_mesh(&mesh, path).unwrap();
+    });
+    assert_golden("tetrahedron_nemoh.dat", &content);
+}
+
+#[test]
+fn mesh_exports_match_golden() {
+    let mesh = tetrahedron_mesh();
+    for (name, format) in [("tetrahedron.stl", Format::STL), ("tetrahedron.obj", Format::OBJ)] {
+        let content = export(name, |path| {
+            FileIO::save_mesh(&mesh, path.to_str().unwrap(), format).unwrap();
+        });
+        assert_golden(name, &content);
+    }
+}
+
+#[test]
+fn csv_export_matches_golden() {
+    let data = DataArray::new(&[2, 3], &[0.0, 0.5, 1.25, -2.0, 3.5, 0.001]).unwrap();
+    let content = export("data_array.csv", |path| {
+        FileIO::save_data(&data, path.to_str().unwrap(), Format::CSV).unwrap();
+    });
+    assert_golden("data_array.csv", &content);
+}
+
+#[test]
+fn canonical_coefficients_match_golden() {
+    let solver = BEMSolver::new(SolverEngine::Standard);
+    let limits = [solver.solve_added_mass_zero_freq(vec![tetrahedron_body()]).unwrap(), solver.solve_added_mass_inf_freq(vec![tetrahedron_body()]).unwrap()];
+
+    // Translational added mass of the unit-volume tetrahedron is of the order of its displaced mass
+    for added_mass in &limits {
+        for k in 0..3 {
+            let diagonal = added_mass.get(k, k).unwrap();
+            assert!(diagonal > 0.0 && diagonal < 10.0 * 1025.0, "A{}{} = {}", k + 1, k + 1, diagonal);
+        }
+    }
+
+    let values: Vec<f64> = limits.iter().flat_map(|a| a.data.iter().copied()).collect();
+    let data = DataArray::new(&[2, 6, 6], &values).unwrap();
+    let content = export("tetrahedron_limit_added_mass.csv", |path| {
+        FileIO::save_data(&data, path.to_str().unwrap(), Format::CSV).unwrap();
+    });
+    assert_golden("tetrahedron_limit_added_mass.csv", &content);
+}
@@ -0,0 +1,44 @@
+//! Matrices and LU factors written to binary files and read back
+
+use num_complex::Complex64;
+use wavecore_io::{BinaryMatrix, IOError};
+use wavecore_matrices::{ComplexMatrix, DenseLU, Matrix};
+
+#[test]
+fn cached_factors_solve_new_right_hand_sides() {
+    let n = 12;
+    let dir = std::env::temp_dir();
+    let path = |name: &str| dir.join(format!("wavecore_{}_{}.bin", name, std::process::id())).to_str().unwrap().to_string();
+
+    let a = ComplexMatrix::from_vec(n, n, (0..n * n).map(|k| {
+        let (i, j) = (k / n, k % n);
+        Complex64::new(if i == j { 3.0 } else { 1.0 / (1 + i + j) as f64 }, ((i * 5 + j) % 7) as f64 * 0.1)
+    }).collect()).unwrap();
+    a.write_binary(&path("influence")).unwrap();
+    let lu = a.lu().unwrap();
+    lu.write_binary(&path("influence_lu")).unwrap();
+
+    let read = ComplexMatrix::read_binary(&path("influence")).unwrap();
+    assert_eq!((read.rows, read.cols), (n, n));
+    assert_eq!(read.data, a.data);
+    let cached = DenseLU::<Complex64>::read_binary(&path("influence_lu")).unwrap();
+    assert_eq!(cached, lu);
+
+    // A new heading only changes the right-hand side
+    let b: Vec<Complex64> = (0..n).map(|i| Complex64::from_polar(1.0, i as f64 * 0.4)).collect();
+    let x = cached.solve(&b).unwrap();
+    assert!(a.matvec(&x).unwrap().iter().zip(&b).all(|(p, q)| (p - q).norm() < 1e-12));
+
+    let real = Matrix::from_vec(2, 3, vec![1.0, -2.0, 3.5, 0.0, f64::MAX, 1e-300]).unwrap();
+    let back = Matrix::from_bytes(&real.to_bytes()).unwrap();
+    assert_eq!((back.rows, back.cols, back.data), (2, 3, real.data.clone()));
+
+    // Wrong kind, truncated file, missing file
+    assert!(matches!(Matrix::read_binary(&path("influence")), Err(IOError::InvalidFormat { .. })));
+    let bytes = lu.to_bytes();
+    assert!(matches!(DenseLU::<Complex64>::from_bytes(&bytes[..bytes.len() - 1]), Err(IOError::ParseError { .. })));
+    assert!(matches!(DenseLU::<f64>::read_binary(&path("missing")), Err(IOError::FileNotFound { .. })));
+
+    std::fs::remove_file(path("influence")).unwrap();
+    std::fs::remove_file(path("influence_lu")).unwrap();
+}
@@ -0,0 +1,90 @@
+//! NEMOH meshes and Nemoh.cal cases imported as BEM problems
+
+use wavecore_bem::ProblemType;
+use wavecore_bodies::DOF;
+use wavecore_io::NemohInterface;
+use wavecore_meshes::{HullSurface, PredefinedGeometry};
+
+const NEMOH_CAL: &str = "--- Environment ------------------------------------------------------------------------------------------------------------------
+1025.0\t\t\t\t! RHO \t\t\t! KG/M**3 \t! Fluid specific volume
+9.81\t\t\t\t! G\t\t\t! M/S**2\t! Gravity
+0.\t\t\t\t! DEPTH\t\t\t! M\t\t! Water depth
+0.\t0.\t\t\t! XEFF YEFF\t\t! M\t\t! Wave measurement point
+--- Description of floating bodies -----------------------------------------------------------------------------------------------
+1\t\t\t\t! Number of bodies
+--- Body 1 -----------------------------------------------------------------------------------------------------------------------
+barge.dat\t\t! Name of mesh file
+12 10\t\t\t! Number of points and number of panels
+3\t\t\t\t! Number of degrees of freedom
+1 1. 0.\t0. 0. 0. 0.\t\t! Surge
+1 0. 0.\t1. 0. 0. 0.\t\t! Heave
+2 0. 1.\t0. 0. 0. -0.5\t\t! Pitch about a point
+3\t\t\t\t! Number of resulting generalised forces
+1 1. 0.\t0. 0. 0. 0.\t\t! Force in x direction
+1 0. 0.\t1. 0. 0. 0.\t\t! Force in z direction
+2 0. 1.\t0. 0. 0. -0.5\t\t! Moment force in y direction about a point
+0\t\t\t\t! Number of lines of additional information
+--- Load cases to be solved -------------------------------------------------------------------------------------------------------
+4\t0.5\t2.0\t\t! Number of wave frequencies, Min, and Max (rad/s)
+3\t0.\t90.\t\t! Number of wave directions, Min and Max (degrees)
+--- Post processing ---------------------------------------------------------------------------------------------------------------
+0\t0.1\t10.\t\t! IRF \t\t\t\t! IRF calculation (0 for no calculation), time step and duration
+0\t\t\t\t! Show pressure
+0\t0.\t180.\t\t! Kochin function \t\t! Number of directions of calculation (0 for no calculations), Min and Max (degrees)
+0\t50\t400.\t400.\t! Free surface elevation \t! Number of points in x direction (0 for no calcutions) and y direction and dimensions of domain in x and y direction
+";
+
+#[test]
+fn nemoh_cal_becomes_bem_problems() {
+    let dir = std::env::temp_dir().join(format!("wavecore_nemoh_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let nemoh = NemohInterface::new();
+    let barge = PredefinedGeometry::box_hull_with_surface(6.0, 3.0, 1.0, 3, 2, 1, HullSurface::Wetted).unwrap();
+    nemoh.write_nemoh_mesh(&barge, &dir.join("barge.dat")).unwrap();
+    std::fs::write(dir.join("Nemoh.cal"), NEMOH_CAL).unwrap();
+
+    let case = nemoh.read_nemoh_case(&dir.join("Nemoh.cal")).unwrap();
+    assert_eq!((case.rho, case.g, case.depth), (1025.0, 9.81, None));
+    assert_eq!(case.frequencies, vec![0.5, 1.0, 1.5, 2.0]);
+    assert!((case.directions[1] - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+
+    let body = &case.bodies[0];
+    assert_eq!(body.name, "barge");
+    assert_eq!(body.mesh().unwrap().faces.len(), barge.faces.len());
+    assert!(body.is_dof_enabled(&DOF::Pitch) && !body.is_dof_enabled(&DOF::Roll));
+    assert_eq!(body.mass_properties.center_of_gravity, [0.0, 0.0, -0.5]);
+
+    let problems = case.problems();
+    assert_eq!(problems.len(), 12);
+    match &problems[5].problem_type {
+        ProblemType::Combined { frequency, direction, modes } => {
+            assert_eq!(*frequency, 1.0);
+            assert!((direction - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+            assert_eq!(modes, &vec![0, 2, 4]);
+        }
+        other => panic!("unexpected problem {:?}", other),
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn nemoh_mesh_symmetry_quads_and_triangles() {
+    // Half of a 2 m x 2 m bottom at z = -1 plus a triangular end, reflected across y = 0
+    let half = "2 1
+1 0. 0. -1.
+2 2. 0. -1.
+3 2. 1. -1.
+4 0. 1. -1.
+5 2. 1. 0.
+0 0. 0. 0.
+1 4 3 2
+2 3 5 5
+0 0 0 0
+";
+    let mesh = NemohInterface::new().mesh_converter.parse_mesh(half).unwrap();
+    assert_eq!((mesh.faces.len(), mesh.quads.len()), (2, 2));
+    let bottom: Vec<_> = mesh.normals.iter().filter(|n| n.z < -0.99).collect();
+    assert_eq!(bottom.len(), 2);
+    assert!(mesh.normals.iter().all(|n| n.z < -0.99 || n.x > 0.99));
+    assert!(NemohInterface::new().mesh_converter.parse_mesh("2 0\n1 0 0 0\n0 0 0 0\n1 2 3 4\n0 0 0 0\n").is_err());
+}
@@ -0,0 +1,50 @@
+//! OBJ groups and polygon faces imported into mesh collections
+
+use wavecore_io::{parse_obj, ObjFile, DEFAULT_OBJ_GROUP};
+
+const PONTOONS: &str = "# two parts exported from a modeller
+v 0 0 0
+v 2 0 0
+v 2 1 0
+v 0 1 0
+vn 0 0 -1
+o hull
+f 1//1 4//1 3//1 2//1
+v 0 0 -1
+v 2 0 -1
+v 1 0.5 -2
+g keel
+f -3 -2 -1
+f 5 6 1 2 7
+";
+
+#[test]
+fn obj_groups_become_named_meshes() {
+    let file: ObjFile = parse_obj(PONTOONS).unwrap();
+    assert_eq!(file.groups.len(), 2);
+    let hull = file.group("hull").unwrap();
+    assert_eq!((hull.vertices.len(), hull.quad_count()), (4, 1));
+    assert!((hull.normals[0].z + 1.0).abs() < 1e-12);
+
+    // The quad keeps its polygon and triangulates along the normal from the file
+    let mesh = hull.to_mesh().unwrap();
+    assert_eq!(mesh.faces.len(), 2);
+    assert!(mesh.normals.iter().all(|n| (n.z + 1.0).abs() < 1e-12));
+
+    // Negative references and a pentagon in the keel group
+    let keel = file.group("keel").unwrap();
+    assert_eq!(keel.polygons.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 5]);
+    assert_eq!(keel.vertices.len(), 5);
+
+    let collection = file.to_collection().unwrap();
+    let mut names = collection.mesh_names();
+    names.sort();
+    assert_eq!(names, vec!["hull", "keel"]);
+    assert_eq!(collection.get_mesh("keel").unwrap().faces.len(), 4);
+    assert_eq!(file.to_mesh().unwrap().faces.len(), 6);
+
+    // Ungrouped faces land in the default group; bad references are reported
+    let plain = parse_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+    assert_eq!(plain.groups[0].name, DEFAULT_OBJ_GROUP);
+    assert!(parse_obj("v 0 0 0\nf 1 2 4\n").is_err());
+}
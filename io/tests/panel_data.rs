@@ -0,0 +1,31 @@
+//! Panel output of a solve converted to a data array
+
+mod common;
+
+use common::tetrahedron_body;
+use wavecore_bem::{BEMSolver, ProblemType, SolverEngine};
+use wavecore_io::{ToDataArray, PANEL_COLUMNS};
+
+#[test]
+fn panel_output_converts_to_dataarray() {
+    let solver = BEMSolver::new(SolverEngine::Standard);
+    let result = solver
+        .solve_bodies(&ProblemType::Diffraction { frequency: 1.0, direction: 0.0 }, vec![tetrahedron_body()])
+        .unwrap();
+    let panels = result.panel_output.as_ref().unwrap();
+
+    let array = result.to_dataarray().unwrap();
+    assert_eq!(array.dimensions(), &[4, PANEL_COLUMNS.len()]);
+    for (i, row) in array.as_slice().chunks(PANEL_COLUMNS.len()).enumerate() {
+        assert_eq!(&row[..3], &panels.centroids[i]);
+        assert_eq!(row[6], panels.areas[i]);
+        assert_eq!((row[7], row[8]), (panels.pressure[i].re, panels.pressure[i].im));
+        assert_eq!(row[9], panels.source_strength.as_ref().unwrap()[i].re);
+        assert_eq!(row[12], panels.normal_velocity[i].im);
+    }
+
+    let mut direct = panels.clone();
+    direct.source_strength = None;
+    let array = direct.to_dataarray().unwrap();
+    assert!(array.as_slice()[9].is_nan() && array.as_slice()[10].is_nan());
+}
@@ -0,0 +1,58 @@
+//! STL meshes written in both encodings and read back
+
+use wavecore_io::{parse_stl, read_stl, stl_to_bytes, write_stl, StlEncoding, StlOptions};
+use wavecore_meshes::PredefinedGeometry;
+
+#[test]
+fn stl_round_trip_welds_shared_corners() {
+    let mesh = PredefinedGeometry::box_hull(10.0, 4.0, 2.0, 5, 2, 2).unwrap();
+    let path = std::env::temp_dir().join(format!("wavecore_stl_{}.stl", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    for encoding in [StlEncoding::Binary, StlEncoding::Ascii] {
+        let options = StlOptions { encoding, ..StlOptions::default() };
+        write_stl(&mesh, path, &options).unwrap();
+        let read = read_stl(path, &options).unwrap();
+        assert_eq!(read.vertices.len(), mesh.vertices.len());
+        assert_eq!(read.faces.len(), mesh.faces.len());
+        for (p, q) in read.normals.iter().zip(&mesh.normals) {
+            assert!((p - q).norm() < 1e-6);
+        }
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn stl_scales_units_and_drops_collapsed_facets() {
+    // Two facets in millimetres; the second collapses when corners 0.4 mm apart are welded
+    let ascii = b"solid part
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1000 0 0
+      vertex 0 1000 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 1000 0 0
+      vertex 1000.4 0 0
+      vertex 0 1000 0
+    endloop
+  endfacet
+endsolid part
+";
+    let options = StlOptions { scale: 1e-3, weld_tolerance: 1e-3, ..StlOptions::default() };
+    let mesh = parse_stl(ascii, &options).unwrap();
+    assert_eq!(mesh.faces.len(), 1);
+    assert!((mesh.vertices[1].x - 1.0).abs() < 1e-12);
+
+    // Written back in millimetres, a binary file starting with "solid" is still detected as binary
+    let mut binary = stl_to_bytes(&mesh, &options);
+    binary[..5].copy_from_slice(b"solid");
+    let read = parse_stl(&binary, &options).unwrap();
+    assert!((read.vertices[2].y - 1.0).abs() < 1e-9);
+
+    assert!(parse_stl(b"solid bad\n vertex 0 0\n endloop\nendsolid", &options).is_err());
+    assert!(parse_stl(&binary[..100], &options).is_err());
+}
@@ -0,0 +1,60 @@
+//! Meshes and panel fields exported as VTU files
+
+use num_complex::Complex64;
+use wavecore_bem::PanelOutput;
+use wavecore_io::{export_vtk, panel_fields, vtu_string, VtkField};
+use wavecore_meshes::{Mesh, Point, PredefinedGeometry};
+
+#[test]
+fn vtu_holds_triangles_and_cell_fields() {
+    let mesh = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 2, 1, 1).unwrap();
+    let n = mesh.faces.len();
+    let output = PanelOutput {
+        centroids: vec![[0.0; 3]; n],
+        normals: mesh.normals.iter().map(|v| [v.x, v.y, v.z]).collect(),
+        areas: vec![0.5; n],
+        pressure: (0..n).map(|i| Complex64::new(0.0, i as f64)).collect(),
+        source_strength: None,
+        normal_velocity: vec![Complex64::new(-1.0, 0.0); n],
+    };
+    let mut fields = panel_fields(&output);
+    let names: Vec<_> = fields.iter().map(VtkField::name).collect();
+    assert_eq!(
+        names,
+        ["pressure_magnitude", "pressure_phase", "normal_velocity_magnitude", "normal_velocity_phase", "normal", "area"]
+    );
+    assert_eq!(fields[0], VtkField::scalar("pressure_magnitude", (0..n).map(|i| i as f64).collect()));
+    fields.push(VtkField::scalar("p<sub>", vec![0.0; n]));
+
+    let path = std::env::temp_dir().join(format!("wavecore_vtk_{}.vtu", std::process::id()));
+    export_vtk(&mesh, &fields, path.to_str().unwrap()).unwrap();
+    let xml = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(xml.contains(&format!("NumberOfPoints=\"{}\" NumberOfCells=\"{}\"", mesh.vertices.len(), n)));
+    assert!(xml.contains(&format!("          {}\n        </DataArray>", 3 * n)));
+    assert!(xml.contains("Name=\"normal\" NumberOfComponents=\"3\""));
+    assert!(xml.contains("Name=\"p&lt;sub&gt;\""));
+    assert_eq!(xml.matches("<DataArray").count(), 4 + fields.len());
+    let phase = std::f64::consts::FRAC_PI_2.to_string();
+    assert!(xml.contains(&format!("          {}\n", phase)));
+
+    let short = [VtkField::vector("velocity", vec![[0.0; 3]; n - 1])];
+    assert!(vtu_string(&mesh, &short).is_err());
+}
+
+#[test]
+fn vtu_writes_quads_as_quad_cells() {
+    let vertices = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [2.0, 0.0]]
+        .iter()
+        .map(|&[x, y]| Point::new(x, y, -1.0))
+        .collect();
+    let mesh = Mesh::with_quads(vertices, vec![[1, 4, 2]], vec![[0, 1, 2, 3]]).unwrap();
+    let xml = vtu_string(&mesh, &[VtkField::scalar("id", vec![0.0, 1.0])]).unwrap();
+    assert!(xml.contains("NumberOfCells=\"2\""));
+    let connectivity: String = [1, 4, 2, 0, 1, 2, 3].iter().map(|v| format!("          {}\n", v)).collect();
+    assert!(xml.contains(&connectivity));
+    assert!(xml.contains("          3\n          7\n"));
+    assert!(xml.contains("          5\n          9\n"));
+    assert!(vtu_string(&mesh, &[VtkField::scalar("id", vec![0.0])]).is_err());
+}
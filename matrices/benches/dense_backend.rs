@@ -0,0 +1,47 @@
+//! Dense backend benchmarks
+//!
+//! Compares the reference triple loop with the backend product and LU solve
+//! across sizes. Run once without and once with `--features lapack` (with a
+//! BLAS/LAPACK linked) to see where the system library overtakes the native
+//! kernels; `backend::LAPACK_CROSSOVER` is set from that comparison.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use wavecore_matrices::*;
+
+fn test_matrix(n: usize) -> Matrix {
+    let data = (0..n * n)
+        .map(|k| ((k * 7919 % 101) as f64 - 50.0) / 50.0 + if k % (n + 1) == 0 { n as f64 } else { 0.0 })
+        .collect();
+    Matrix::from_vec(n, n, data).unwrap()
+}
+
+fn reference_multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut c = Matrix::new(a.rows, b.cols);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            c.data[i * b.cols + j] = (0..a.cols).map(|k| a.data[i * a.cols + k] * b.data[k * b.cols + j]).sum();
+        }
+    }
+    c
+}
+
+fn dense_backend_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("dense_{}", backend::backend_name()));
+    for n in [16, 32, 64, 128, 256] {
+        let a = test_matrix(n);
+        let rhs = vec![1.0; n];
+        group.bench_with_input(BenchmarkId::new("reference_multiply", n), &a, |bench, a| {
+            bench.iter(|| black_box(reference_multiply(a, a)));
+        });
+        group.bench_with_input(BenchmarkId::new("multiply", n), &a, |bench, a| {
+            bench.iter(|| black_box(backend::multiply(a, a).unwrap()));
+        });
+        group.bench_with_input(BenchmarkId::new("lu_solve", n), &a, |bench, a| {
+            bench.iter(|| black_box(lu_solve(a, &rhs).unwrap()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, dense_backend_benchmark);
+criterion_main!(benches);
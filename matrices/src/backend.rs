@@ -0,0 +1,433 @@
+//! Dense kernels: matrix products and LU/Cholesky factorizations
+//!
+//! Products use the cache-blocked kernels behind `ndarray` and factorizations
+//! use nalgebra. With the `lapack` feature, problems of at least
+//! [`LAPACK_CROSSOVER`] rows go to the system BLAS/LAPACK instead (OpenBLAS,
+//! MKL or reference LAPACK). The library is linked by the final binary, for
+//! example through an `openblas-src` dependency or `RUSTFLAGS="-l openblas"`.
+//! The `dense_backend` benchmark measures the crossover on a given machine.
+//!
+//! The native kernels split large problems across the threads set by
+//! [`set_num_threads`](crate::parallel::set_num_threads): products by blocks
+//! of rows, and LU by a blocked right-looking factorization whose trailing
+//! updates run in parallel.
+
+use super::*;
+use crate::parallel;
+use nalgebra::{Cholesky, ComplexField, DMatrix, LU};
+use ndarray::ArrayView2;
+use num_complex::Complex64;
+use rayon::prelude::*;
+
+/// Size from which the `lapack` feature hands products and factorizations to the library
+///
+/// Below it, call overhead and the row/column-major copies outweigh the
+/// faster kernels.
+pub const LAPACK_CROSSOVER: usize = 64;
+
+/// Column width of the panels of the blocked LU factorization
+const LU_BLOCK: usize = 64;
+
+/// Name of the dense backend compiled in
+pub fn backend_name() -> &'static str {
+    if cfg!(feature = "lapack") {
+        "lapack"
+    } else {
+        "native"
+    }
+}
+
+/// Matrix product A B
+pub fn multiply(a: &Matrix, b: &Matrix) -> Result<Matrix> {
+    if a.cols != b.rows {
+        return Err(MatrixError::DimensionMismatch {
+            expected: a.cols,
+            actual: b.rows,
+        });
+    }
+    #[cfg(feature = "lapack")]
+    if system::applies(a.rows.max(b.cols)) {
+        return Ok(system::multiply(a, b));
+    }
+    let deterministic = parallel::is_deterministic();
+    if !deterministic && !parallel::is_parallel(a.rows * a.cols * b.cols) {
+        let product = view(a).dot(&view(b));
+        return Ok(Matrix {
+            rows: a.rows,
+            cols: b.cols,
+            data: product.iter().copied().collect(),
+        });
+    }
+    // Blocks of rows of A, a few per thread to balance uneven cores, or of a
+    // fixed size so that the kernel's summation order never changes
+    let block_rows = if deterministic {
+        parallel::DETERMINISTIC_BLOCK_ROWS
+    } else {
+        a.rows.div_ceil(4 * parallel::num_threads()).max(1)
+    };
+    let b_view = view(b);
+    let mut data = vec![0.0; a.rows * b.cols];
+    let multiply_block = |(out, rows): (&mut [f64], &[f64])| {
+        let block = ArrayView2::from_shape((rows.len() / a.cols.max(1), a.cols), rows).unwrap();
+        out.iter_mut().zip(block.dot(&b_view).iter()).for_each(|(c, &value)| *c = value);
+    };
+    let chunk = (block_rows * b.cols).max(1);
+    let a_chunk = (block_rows * a.cols).max(1);
+    if parallel::is_parallel(a.rows * a.cols * b.cols) {
+        parallel::install(|| data.par_chunks_mut(chunk).zip(a.data.par_chunks(a_chunk)).for_each(multiply_block));
+    } else {
+        data.chunks_mut(chunk).zip(a.data.chunks(a_chunk)).for_each(multiply_block);
+    }
+    Ok(Matrix { rows: a.rows, cols: b.cols, data })
+}
+
+/// Solve a square system for several right-hand sides with one LU factorization
+pub fn lu_solve_multi(a: &Matrix, b: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+    check_square(a.rows, a.cols, b.iter().map(Vec::len))?;
+    #[cfg(feature = "lapack")]
+    if system::applies(a.rows) {
+        return system::lu_solve_multi(a, b);
+    }
+    if use_blocked_lu(a.rows) {
+        return blocked_lu_solve_multi(a.rows, a.data.clone(), b);
+    }
+    let lu = LU::new(DMatrix::from_row_slice(a.rows, a.cols, &a.data));
+    if !lu.is_invertible() {
+        return Err(MatrixError::SingularMatrix);
+    }
+    let rhs = DMatrix::from_fn(a.rows, b.len(), |i, j| b[j][i]);
+    match lu.solve(&rhs) {
+        Some(solution) => Ok(solution.column_iter().map(|column| column.iter().copied().collect()).collect()),
+        None => Err(MatrixError::SolverError {
+            message: "LU solver failed to find solution".to_string(),
+        }),
+    }
+}
+
+/// Solve a symmetric positive definite system for several right-hand sides by Cholesky
+pub fn cholesky_solve_multi(a: &Matrix, b: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+    check_square(a.rows, a.cols, b.iter().map(Vec::len))?;
+    #[cfg(feature = "lapack")]
+    if system::applies(a.rows) {
+        return system::cholesky_solve_multi(a, b);
+    }
+    match Cholesky::new(DMatrix::from_row_slice(a.rows, a.cols, &a.data)) {
+        Some(cholesky) => {
+            let solution = cholesky.solve(&DMatrix::from_fn(a.rows, b.len(), |i, j| b[j][i]));
+            Ok(solution.column_iter().map(|column| column.iter().copied().collect()).collect())
+        }
+        None => Err(not_positive_definite()),
+    }
+}
+
+/// Solve a square complex system for several right-hand sides with one LU factorization
+pub fn complex_lu_solve_multi(a: &ComplexMatrix, b: &[Vec<Complex64>]) -> Result<Vec<Vec<Complex64>>> {
+    check_square(a.rows, a.cols, b.iter().map(Vec::len))?;
+    #[cfg(feature = "lapack")]
+    if system::applies(a.rows) {
+        return system::complex_lu_solve_multi(a, b);
+    }
+    if use_blocked_lu(a.rows) {
+        return blocked_lu_solve_multi(a.rows, a.data.clone(), b);
+    }
+    let lu = LU::new(DMatrix::from_row_slice(a.rows, a.cols, &a.data));
+    if !lu.is_invertible() {
+        return Err(MatrixError::SingularMatrix);
+    }
+    let rhs = DMatrix::from_fn(a.rows, b.len(), |i, j| b[j][i]);
+    match lu.solve(&rhs) {
+        Some(solution) => Ok(solution.column_iter().map(|column| column.iter().copied().collect()).collect()),
+        None => Err(MatrixError::SolverError {
+            message: "Complex LU solver failed to find solution".to_string(),
+        }),
+    }
+}
+
+/// Whether an `n × n` factorization goes to the parallel blocked LU
+fn use_blocked_lu(n: usize) -> bool {
+    n >= 2 * LU_BLOCK && parallel::is_parallel(n * n * n)
+}
+
+/// LU factorization for several right-hand sides by [`lu_factor`]
+fn blocked_lu_solve_multi<T>(n: usize, mut a: Vec<T>, b: &[Vec<T>]) -> Result<Vec<Vec<T>>>
+where
+    T: ComplexField + Copy,
+{
+    let pivots = lu_factor(n, &mut a)?;
+    Ok(b.iter()
+        .map(|rhs| {
+            let mut x = rhs.clone();
+            lu_apply(n, &a, &pivots, &mut x);
+            x
+        })
+        .collect())
+}
+
+/// Blocked right-looking LU with partial pivoting of the row-major `n × n`
+/// matrix `a`, in place, returning the row interchanges
+///
+/// Each panel of [`LU_BLOCK`] columns is factorized on one thread, then the
+/// rows below it are updated, in parallel for large matrices.
+pub(crate) fn lu_factor<T>(n: usize, a: &mut [T]) -> Result<Vec<usize>>
+where
+    T: ComplexField + Copy,
+{
+    let mut pivots = vec![0; n];
+    let parallel = parallel::is_parallel(n * n * n);
+    for k0 in (0..n).step_by(LU_BLOCK) {
+        let k1 = (k0 + LU_BLOCK).min(n);
+
+        // Panel: columns k0..k1 of rows k0..n, swapping whole rows
+        for j in k0..k1 {
+            let pivot = (j..n)
+                .max_by(|&p, &q| {
+                    a[p * n + j].modulus().partial_cmp(&a[q * n + j].modulus()).unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            if a[pivot * n + j].is_zero() {
+                return Err(MatrixError::SingularMatrix);
+            }
+            pivots[j] = pivot;
+            if pivot != j {
+                for c in 0..n {
+                    a.swap(j * n + c, pivot * n + c);
+                }
+            }
+            let diagonal = a[j * n + j];
+            for i in j + 1..n {
+                let l = a[i * n + j] / diagonal;
+                a[i * n + j] = l;
+                for c in j + 1..k1 {
+                    let u = a[j * n + c];
+                    a[i * n + c] -= l * u;
+                }
+            }
+        }
+
+        // Block row of U: L₁₁⁻¹ A₁₂
+        for j in k0..k1 {
+            for i in j + 1..k1 {
+                let l = a[i * n + j];
+                for c in k1..n {
+                    let u = a[j * n + c];
+                    a[i * n + c] -= l * u;
+                }
+            }
+        }
+
+        // Trailing update A₂₂ -= L₂₁ U₁₂, one row per task
+        let (top, bottom) = a.split_at_mut(k1 * n);
+        let top = &*top;
+        let update = |row: &mut [T]| {
+            for j in k0..k1 {
+                let l = row[j];
+                let u = &top[j * n + k1..(j + 1) * n];
+                row[k1..].iter_mut().zip(u).for_each(|(r, &u)| *r -= l * u);
+            }
+        };
+        if parallel {
+            parallel::install(|| bottom.par_chunks_mut(n).for_each(update));
+        } else {
+            bottom.chunks_mut(n).for_each(update);
+        }
+    }
+    Ok(pivots)
+}
+
+/// Overwrite `x` with A⁻¹ x from the factors of [`lu_factor`]
+pub(crate) fn lu_apply<T>(n: usize, factors: &[T], pivots: &[usize], x: &mut [T])
+where
+    T: ComplexField + Copy,
+{
+    for (j, &pivot) in pivots.iter().enumerate() {
+        x.swap(j, pivot);
+    }
+    for i in 0..n {
+        x[i] = (0..i).fold(x[i], |sum, j| sum - factors[i * n + j] * x[j]);
+    }
+    for i in (0..n).rev() {
+        x[i] = (i + 1..n).fold(x[i], |sum, j| sum - factors[i * n + j] * x[j]) / factors[i * n + i];
+    }
+}
+
+/// Overwrite `x` with A⁻ᴴ x from the factors of [`lu_factor`]
+pub(crate) fn lu_apply_adjoint<T>(n: usize, factors: &[T], pivots: &[usize], x: &mut [T])
+where
+    T: ComplexField + Copy,
+{
+    for i in 0..n {
+        x[i] = (0..i).fold(x[i], |sum, j| sum - factors[j * n + i].conjugate() * x[j]) / factors[i * n + i].conjugate();
+    }
+    for i in (0..n).rev() {
+        x[i] = (i + 1..n).fold(x[i], |sum, j| sum - factors[j * n + i].conjugate() * x[j]);
+    }
+    for (j, &pivot) in pivots.iter().enumerate().rev() {
+        x.swap(j, pivot);
+    }
+}
+
+fn view(matrix: &Matrix) -> ArrayView2<'_, f64> {
+    ArrayView2::from_shape((matrix.rows, matrix.cols), &matrix.data).unwrap()
+}
+
+fn check_square(rows: usize, cols: usize, mut rhs_lengths: impl Iterator<Item = usize>) -> Result<()> {
+    if rows != cols {
+        return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+    match rhs_lengths.find(|&len| len != rows) {
+        Some(actual) => Err(MatrixError::DimensionMismatch { expected: rows, actual }),
+        None => Ok(()),
+    }
+}
+
+fn not_positive_definite() -> MatrixError {
+    MatrixError::SolverError {
+        message: "Matrix is not positive definite for Cholesky decomposition".to_string(),
+    }
+}
+
+/// System BLAS/LAPACK kernels
+///
+/// The row-major storage of A is the column-major storage of Aᵀ, so
+/// factorizations of Aᵀ are solved with the transposed triangular solves.
+#[cfg(feature = "lapack")]
+mod system {
+    use super::*;
+    use std::ffi::c_char;
+
+    /// Whether a problem of `n` rows goes to the system library
+    pub(super) fn applies(n: usize) -> bool {
+        n >= LAPACK_CROSSOVER && !parallel::is_deterministic()
+    }
+
+    extern "C" {
+        // Every LAPACK provider also exports the BLAS it is built on
+        fn dgemm_(
+            transa: *const c_char,
+            transb: *const c_char,
+            m: *const i32,
+            n: *const i32,
+            k: *const i32,
+            alpha: *const f64,
+            a: *const f64,
+            lda: *const i32,
+            b: *const f64,
+            ldb: *const i32,
+            beta: *const f64,
+            c: *mut f64,
+            ldc: *const i32,
+        );
+    }
+
+    /// A B, computed as the column-major product Bᵀ Aᵀ
+    pub(super) fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+        let mut c = Matrix::new(a.rows, b.cols);
+        let (m, n, k) = (b.cols as i32, a.rows as i32, a.cols as i32);
+        let (no_transpose, one, zero) = (b'N' as c_char, 1.0, 0.0);
+        if m > 0 && n > 0 {
+            unsafe {
+                dgemm_(
+                    &no_transpose,
+                    &no_transpose,
+                    &m,
+                    &n,
+                    &k,
+                    &one,
+                    b.data.as_ptr(),
+                    &m.max(1),
+                    a.data.as_ptr(),
+                    &k.max(1),
+                    &zero,
+                    c.data.as_mut_ptr(),
+                    &m,
+                );
+            }
+        }
+        c
+    }
+
+    pub(super) fn lu_solve_multi(a: &Matrix, b: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+        let n = a.rows as i32;
+        let mut factors = a.data.clone();
+        let mut pivots = vec![0; a.rows];
+        let mut rhs = b.concat();
+        let mut info = 0;
+        unsafe { lapack::dgetrf(n, n, &mut factors, n, &mut pivots, &mut info) };
+        if info > 0 {
+            return Err(MatrixError::SingularMatrix);
+        }
+        unsafe { lapack::dgetrs(b'T', n, b.len() as i32, &factors, n, &pivots, &mut rhs, n, &mut info) };
+        check_info(info, "dgetrs")?;
+        Ok(rhs.chunks(a.rows.max(1)).take(b.len()).map(<[f64]>::to_vec).collect())
+    }
+
+    pub(super) fn cholesky_solve_multi(a: &Matrix, b: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+        let n = a.rows as i32;
+        let mut factors = a.data.clone();
+        let mut rhs = b.concat();
+        let mut info = 0;
+        unsafe { lapack::dpotrf(b'L', n, &mut factors, n, &mut info) };
+        if info > 0 {
+            return Err(not_positive_definite());
+        }
+        unsafe { lapack::dpotrs(b'L', n, b.len() as i32, &factors, n, &mut rhs, n, &mut info) };
+        check_info(info, "dpotrs")?;
+        Ok(rhs.chunks(a.rows.max(1)).take(b.len()).map(<[f64]>::to_vec).collect())
+    }
+
+    pub(super) fn complex_lu_solve_multi(a: &ComplexMatrix, b: &[Vec<Complex64>]) -> Result<Vec<Vec<Complex64>>> {
+        let n = a.rows as i32;
+        let mut factors = a.data.clone();
+        let mut pivots = vec![0; a.rows];
+        let mut rhs = b.concat();
+        let mut info = 0;
+        unsafe { lapack::zgetrf(n, n, &mut factors, n, &mut pivots, &mut info) };
+        if info > 0 {
+            return Err(MatrixError::SingularMatrix);
+        }
+        unsafe { lapack::zgetrs(b'T', n, b.len() as i32, &factors, n, &pivots, &mut rhs, n, &mut info) };
+        check_info(info, "zgetrs")?;
+        Ok(rhs.chunks(a.rows.max(1)).take(b.len()).map(<[Complex64]>::to_vec).collect())
+    }
+
+    fn check_info(info: i32, routine: &str) -> Result<()> {
+        if info == 0 {
+            Ok(())
+        } else {
+            Err(MatrixError::SolverError {
+                message: format!("{} failed with info {}", routine, info),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_matches_reference_loops() {
+        // Sizes on both sides of the crossover
+        for n in [5, LAPACK_CROSSOVER + 3] {
+            let a = Matrix::from_vec(n, n, (0..n * n).map(|k| ((k * 7 % 11) as f64 - 5.0) / 3.0 + if k % (n + 1) == 0 { n as f64 } else { 0.0 }).collect()).unwrap();
+            let b = Matrix::from_vec(n, 2, (0..2 * n).map(|k| (k as f64).sin()).collect()).unwrap();
+            let product = multiply(&a, &b).unwrap();
+            for i in 0..n {
+                for j in 0..2 {
+                    let expected: f64 = (0..n).map(|k| a.data[i * n + k] * b.data[k * 2 + j]).sum();
+                    assert!((product.data[i * 2 + j] - expected).abs() < 1e-10);
+                }
+            }
+
+            let rhs: Vec<Vec<f64>> = (0..2).map(|j| (0..n).map(|i| b.data[i * 2 + j]).collect()).collect();
+            for solution in lu_solve_multi(&a, &rhs).unwrap().iter().chain(&cholesky_solve_multi(&multiply(&a.transpose(), &a).unwrap(), &rhs).unwrap()) {
+                assert_eq!(solution.len(), n);
+            }
+            let x = &lu_solve_multi(&a, &rhs).unwrap()[1];
+            let residual: f64 = (0..n).map(|i| (0..n).map(|k| a.data[i * n + k] * x[k]).sum::<f64>() - rhs[1][i]).map(|r| r * r).sum();
+            assert!(residual.sqrt() < 1e-9);
+        }
+        assert!(matches!(lu_solve_multi(&Matrix::new(3, 3), &[vec![1.0; 3]]), Err(MatrixError::SingularMatrix)));
+        assert!(cholesky_solve_multi(&Matrix::from_vec(2, 2, vec![1.0, 2.0, 2.0, 1.0]).unwrap(), &[vec![1.0; 2]]).is_err());
+    }
+}
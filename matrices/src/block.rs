@@ -1,6 +1,13 @@
 //! Block matrix operations
+//!
+//! Besides plain block matrices, this module holds hierarchical matrices:
+//! a [`ClusterTree`] of the panel collocation points partitions the influence
+//! matrix into near-field blocks stored densely and far-field blocks
+//! compressed by adaptive cross approximation.
 
 use super::*;
+use num_complex::Complex64;
+use std::ops::Range;
 
 /// Block matrix representation
 pub struct BlockMatrix {
@@ -43,4 +50,697 @@ impl BlockMatrix {
         
         Ok(&self.blocks[i][j])
     }
-} 
\ No newline at end of file
+} 
+
+/// Construction parameters of a hierarchical matrix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HMatrixConfig {
+    /// Admissibility parameter η; larger values compress more blocks
+    pub eta: f64,
+    /// Maximum points per leaf cluster
+    pub leaf_size: usize,
+    /// Tolerance and rank cap of low-rank blocks, also used when recompressing during H-LU
+    pub aca: AcaControl,
+}
+
+impl Default for HMatrixConfig {
+    fn default() -> Self {
+        Self {
+            eta: 2.0,
+            leaf_size: 32,
+            aca: AcaControl::default(),
+        }
+    }
+}
+
+/// Hierarchical (H-) matrix over a cluster tree
+///
+/// Admissible blocks between well-separated clusters are stored as ACA
+/// low-rank factors and the remaining near-field leaves densely, so storage
+/// and products cost about O(n log n) instead of O(n²). [`HMatrix::lu`]
+/// factorizes in H-arithmetic; at a coarse tolerance the factors make a
+/// cheap preconditioner for GMRES on the exact operator.
+#[derive(Debug, Clone)]
+pub struct HMatrix {
+    /// Cluster tree of both rows and columns
+    pub tree: ClusterTree,
+    /// Construction parameters
+    pub config: HMatrixConfig,
+    root: HNode,
+}
+
+/// H-LU factors P A ≈ L U with pivoting inside the dense diagonal leaves
+#[derive(Debug, Clone)]
+pub struct HMatrixLU {
+    permutation: Vec<usize>,
+    root: HNode,
+}
+
+/// Block of an H-matrix between a row and a column range of the tree ordering
+#[derive(Debug, Clone)]
+struct HNode {
+    rows: Range<usize>,
+    cols: Range<usize>,
+    block: HBlock,
+}
+
+#[derive(Debug, Clone)]
+enum HBlock {
+    Dense(ComplexMatrix),
+    LowRank(LowRankBlock),
+    /// Children 11, 12, 21, 22 of a pair of split clusters
+    Split(Box<[HNode; 4]>),
+    /// Packed LU factors of a diagonal leaf; row k of P A is row `pivots[k]` of A
+    Lu { factors: ComplexMatrix, pivots: Vec<usize> },
+}
+
+/// Product of two blocks, kept low-rank where a factor is
+enum Product {
+    LowRank(LowRankBlock),
+    Dense(ComplexMatrix),
+}
+
+impl HMatrix {
+    /// Assemble from panel collocation points and a block callback
+    ///
+    /// `block(rows, cols)` returns the dense block of the given original
+    /// panel indices. Admissible blocks only request the rows and columns
+    /// chosen by ACA, one at a time.
+    pub fn assemble<F>(points: &[[f64; 3]], config: &HMatrixConfig, block: F) -> Result<Self>
+    where
+        F: Fn(&[usize], &[usize]) -> Result<ComplexMatrix>,
+    {
+        let tree = ClusterTree::new(points, config.leaf_size);
+        let root = Self::build(&tree, 0, 0, config, &block)?;
+        Ok(Self { tree, config: config.clone(), root })
+    }
+
+    /// Compress a dense matrix, with `points` locating its rows and columns
+    pub fn from_dense(matrix: &ComplexMatrix, points: &[[f64; 3]], config: &HMatrixConfig) -> Result<Self> {
+        if !matrix.is_square() || matrix.rows != points.len() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: points.len(),
+                actual: matrix.rows,
+            });
+        }
+        Self::assemble(points, config, |rows, cols| {
+            let data = rows.iter().flat_map(|&i| cols.iter().map(move |&j| matrix.data[i * matrix.cols + j])).collect();
+            ComplexMatrix::from_vec(rows.len(), cols.len(), data)
+        })
+    }
+
+    fn build<F>(tree: &ClusterTree, s: usize, t: usize, config: &HMatrixConfig, block: &F) -> Result<HNode>
+    where
+        F: Fn(&[usize], &[usize]) -> Result<ComplexMatrix>,
+    {
+        let (row_cluster, col_cluster) = (&tree.clusters[s], &tree.clusters[t]);
+        let (rows, cols) = (tree.indices(s), tree.indices(t));
+        let block = if row_cluster.is_admissible(col_cluster, config.eta) {
+            let low_rank = LowRankBlock::aca_with(
+                rows.len(),
+                cols.len(),
+                |i| Ok(block(&rows[i..i + 1], cols)?.data),
+                |j| Ok(block(rows, &cols[j..j + 1])?.data),
+                &config.aca,
+            )?;
+            // ACA overestimates the rank; recompress to the tolerance
+            let low_rank = low_rank.truncate(config.aca.tolerance, config.aca.max_rank)?;
+            if low_rank.storage() < rows.len() * cols.len() {
+                HBlock::LowRank(low_rank)
+            } else {
+                Self::dense_block(rows, cols, block)?
+            }
+        } else if let (Some([s1, s2]), Some([t1, t2])) = (row_cluster.children, col_cluster.children) {
+            HBlock::Split(Box::new([
+                Self::build(tree, s1, t1, config, block)?,
+                Self::build(tree, s1, t2, config, block)?,
+                Self::build(tree, s2, t1, config, block)?,
+                Self::build(tree, s2, t2, config, block)?,
+            ]))
+        } else {
+            Self::dense_block(rows, cols, block)?
+        };
+        Ok(HNode {
+            rows: row_cluster.start..row_cluster.end,
+            cols: col_cluster.start..col_cluster.end,
+            block,
+        })
+    }
+
+    fn dense_block<F>(rows: &[usize], cols: &[usize], block: &F) -> Result<HBlock>
+    where
+        F: Fn(&[usize], &[usize]) -> Result<ComplexMatrix>,
+    {
+        let dense = block(rows, cols)?;
+        if dense.dimensions() != (rows.len(), cols.len()) {
+            return Err(MatrixError::DimensionMismatch {
+                expected: rows.len() * cols.len(),
+                actual: dense.data.len(),
+            });
+        }
+        Ok(HBlock::Dense(dense))
+    }
+
+    /// Matrix size
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// Stored entries, compared with n² for the dense matrix
+    pub fn storage(&self) -> usize {
+        self.root.storage()
+    }
+
+    /// Number of low-rank blocks and their largest rank
+    pub fn low_rank_blocks(&self) -> (usize, usize) {
+        self.root.low_rank_blocks()
+    }
+
+    /// Matrix-vector product A x
+    pub fn matvec(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        let permutation = &self.tree.permutation;
+        if x.len() != permutation.len() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: permutation.len(),
+                actual: x.len(),
+            });
+        }
+        let local = ComplexMatrix::from_vec(x.len(), 1, permutation.iter().map(|&p| x[p]).collect())?;
+        Ok(scatter(permutation, &self.root.apply(&local)?.data))
+    }
+
+    /// Dense copy in the original ordering
+    pub fn to_dense(&self) -> Result<ComplexMatrix> {
+        let (local, permutation) = (self.root.to_dense()?, &self.tree.permutation);
+        let n = permutation.len();
+        let mut dense = ComplexMatrix::new(n, n);
+        for (i, &p) in permutation.iter().enumerate() {
+            for (j, &q) in permutation.iter().enumerate() {
+                dense.data[p * n + q] = local.data[i * n + j];
+            }
+        }
+        Ok(dense)
+    }
+
+    /// H-LU factorization, recompressing updates to the configured tolerance and rank cap
+    pub fn lu(&self) -> Result<HMatrixLU> {
+        let mut root = self.root.clone();
+        root.factorize(&self.config)?;
+        Ok(HMatrixLU {
+            permutation: self.tree.permutation.clone(),
+            root,
+        })
+    }
+}
+
+impl HMatrixLU {
+    /// Solve A x = b with the factors
+    pub fn solve(&self, b: &[Complex64]) -> Result<Vec<Complex64>> {
+        if b.len() != self.permutation.len() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.permutation.len(),
+                actual: b.len(),
+            });
+        }
+        let mut x = ComplexMatrix::from_vec(b.len(), 1, self.permutation.iter().map(|&p| b[p]).collect())?;
+        self.root.solve_lower(&mut x)?;
+        self.root.solve_upper(&mut x)?;
+        Ok(scatter(&self.permutation, &x.data))
+    }
+}
+
+/// Values in the tree ordering moved back to the original ordering
+fn scatter(permutation: &[usize], local: &[Complex64]) -> Vec<Complex64> {
+    let mut values = vec![Complex64::new(0.0, 0.0); local.len()];
+    for (&p, &value) in permutation.iter().zip(local) {
+        values[p] = value;
+    }
+    values
+}
+
+impl HNode {
+    fn storage(&self) -> usize {
+        match &self.block {
+            HBlock::Dense(dense) => dense.data.len(),
+            HBlock::LowRank(low_rank) => low_rank.storage(),
+            HBlock::Split(children) => children.iter().map(HNode::storage).sum(),
+            HBlock::Lu { factors, .. } => factors.data.len(),
+        }
+    }
+
+    fn low_rank_blocks(&self) -> (usize, usize) {
+        match &self.block {
+            HBlock::LowRank(low_rank) => (1, low_rank.rank()),
+            HBlock::Split(children) => children
+                .iter()
+                .map(HNode::low_rank_blocks)
+                .fold((0, 0), |(count, rank), (c, r)| (count + c, rank.max(r))),
+            _ => (0, 0),
+        }
+    }
+
+    fn unfactored(&self) -> MatrixError {
+        MatrixError::SolverError {
+            message: "Operation needs an unfactored H-matrix block".to_string(),
+        }
+    }
+
+    /// Product A X with X in the tree ordering of the columns
+    fn apply(&self, x: &ComplexMatrix) -> Result<ComplexMatrix> {
+        match &self.block {
+            HBlock::Dense(dense) => dense.multiply(x),
+            HBlock::LowRank(low_rank) => low_rank.u.multiply(&low_rank.v.multiply(x)?),
+            HBlock::Split(children) => {
+                let mut y = ComplexMatrix::new(self.rows.len(), x.cols);
+                for child in children.iter() {
+                    let part = child.apply(&sub_block(x, shift(&child.cols, self.cols.start), 0..x.cols))?;
+                    add_block(&mut y, shift(&child.rows, self.rows.start).start, 0, &part, 1.0);
+                }
+                Ok(y)
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+
+    /// Product W A with W in the tree ordering of the rows
+    fn apply_left(&self, w: &ComplexMatrix) -> Result<ComplexMatrix> {
+        match &self.block {
+            HBlock::Dense(dense) => w.multiply(dense),
+            HBlock::LowRank(low_rank) => w.multiply(&low_rank.u)?.multiply(&low_rank.v),
+            HBlock::Split(children) => {
+                let mut y = ComplexMatrix::new(w.rows, self.cols.len());
+                for child in children.iter() {
+                    let part = child.apply_left(&sub_block(w, 0..w.rows, shift(&child.rows, self.rows.start)))?;
+                    add_block(&mut y, 0, shift(&child.cols, self.cols.start).start, &part, 1.0);
+                }
+                Ok(y)
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+
+    fn to_dense(&self) -> Result<ComplexMatrix> {
+        match &self.block {
+            HBlock::Dense(dense) => Ok(dense.clone()),
+            HBlock::LowRank(low_rank) => Ok(low_rank.to_dense()),
+            HBlock::Split(children) => {
+                let mut dense = ComplexMatrix::new(self.rows.len(), self.cols.len());
+                for child in children.iter() {
+                    let (rows, cols) = (shift(&child.rows, self.rows.start), shift(&child.cols, self.cols.start));
+                    add_block(&mut dense, rows.start, cols.start, &child.to_dense()?, 1.0);
+                }
+                Ok(dense)
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+
+    /// Factorize a diagonal block in place
+    fn factorize(&mut self, config: &HMatrixConfig) -> Result<()> {
+        match &mut self.block {
+            HBlock::Dense(dense) => {
+                let (factors, pivots) = dense_lu(dense)?;
+                self.block = HBlock::Lu { factors, pivots };
+                Ok(())
+            }
+            HBlock::Split(children) => {
+                let [a11, a12, a21, a22] = &mut **children;
+                a11.factorize(config)?;
+                a12.solve_lower_block(a11, config)?;
+                a21.solve_upper_block(a11, config)?;
+                a22.multiply_subtract(a21, a12, config)?;
+                a22.factorize(config)
+            }
+            _ => Err(MatrixError::SolverError {
+                message: "Diagonal H-matrix block is low-rank or already factored".to_string(),
+            }),
+        }
+    }
+
+    /// X ← L⁻¹ P X with the factors of this diagonal block
+    fn solve_lower(&self, x: &mut ComplexMatrix) -> Result<()> {
+        match &self.block {
+            HBlock::Lu { factors, pivots } => {
+                let (n, k) = (factors.rows, x.cols);
+                let permuted: Vec<Complex64> = pivots.iter().flat_map(|&p| x.data[p * k..(p + 1) * k].to_vec()).collect();
+                x.data = permuted;
+                for i in 0..n {
+                    for j in 0..i {
+                        let l = factors.data[i * n + j];
+                        for c in 0..k {
+                            let value = x.data[j * k + c];
+                            x.data[i * k + c] -= l * value;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            HBlock::Split(children) => {
+                let [l11, _, l21, l22] = &**children;
+                let split = l11.rows.len();
+                let mut x1 = sub_block(x, 0..split, 0..x.cols);
+                let mut x2 = sub_block(x, split..x.rows, 0..x.cols);
+                l11.solve_lower(&mut x1)?;
+                add_block(&mut x2, 0, 0, &l21.apply(&x1)?, -1.0);
+                l22.solve_lower(&mut x2)?;
+                x.data = [x1.data, x2.data].concat();
+                Ok(())
+            }
+            _ => Err(MatrixError::SolverError {
+                message: "H-matrix diagonal block is not factored".to_string(),
+            }),
+        }
+    }
+
+    /// X ← U⁻¹ X with the factors of this diagonal block
+    fn solve_upper(&self, x: &mut ComplexMatrix) -> Result<()> {
+        match &self.block {
+            HBlock::Lu { factors, .. } => {
+                let (n, k) = (factors.rows, x.cols);
+                for i in (0..n).rev() {
+                    for j in (i + 1)..n {
+                        let u = factors.data[i * n + j];
+                        for c in 0..k {
+                            let value = x.data[j * k + c];
+                            x.data[i * k + c] -= u * value;
+                        }
+                    }
+                    let diagonal = factors.data[i * n + i];
+                    x.data[i * k..(i + 1) * k].iter_mut().for_each(|value| *value /= diagonal);
+                }
+                Ok(())
+            }
+            HBlock::Split(children) => {
+                let [u11, u12, _, u22] = &**children;
+                let split = u11.rows.len();
+                let mut x1 = sub_block(x, 0..split, 0..x.cols);
+                let mut x2 = sub_block(x, split..x.rows, 0..x.cols);
+                u22.solve_upper(&mut x2)?;
+                add_block(&mut x1, 0, 0, &u12.apply(&x2)?, -1.0);
+                u11.solve_upper(&mut x1)?;
+                x.data = [x1.data, x2.data].concat();
+                Ok(())
+            }
+            _ => Err(MatrixError::SolverError {
+                message: "H-matrix diagonal block is not factored".to_string(),
+            }),
+        }
+    }
+
+    /// W ← W U⁻¹ with the factors of this diagonal block
+    fn solve_upper_left(&self, w: &mut ComplexMatrix) -> Result<()> {
+        match &self.block {
+            HBlock::Lu { factors, .. } => {
+                let (n, m) = (factors.rows, w.rows);
+                for j in 0..n {
+                    for i in 0..j {
+                        let u = factors.data[i * n + j];
+                        for r in 0..m {
+                            let value = w.data[r * n + i];
+                            w.data[r * n + j] -= value * u;
+                        }
+                    }
+                    let diagonal = factors.data[j * n + j];
+                    for r in 0..m {
+                        w.data[r * n + j] /= diagonal;
+                    }
+                }
+                Ok(())
+            }
+            HBlock::Split(children) => {
+                let [u11, u12, _, u22] = &**children;
+                let split = u11.rows.len();
+                let mut w1 = sub_block(w, 0..w.rows, 0..split);
+                let mut w2 = sub_block(w, 0..w.rows, split..w.cols);
+                u11.solve_upper_left(&mut w1)?;
+                add_block(&mut w2, 0, 0, &u12.apply_left(&w1)?, -1.0);
+                u22.solve_upper_left(&mut w2)?;
+                let mut joined = ComplexMatrix::new(w.rows, w.cols);
+                add_block(&mut joined, 0, 0, &w1, 1.0);
+                add_block(&mut joined, 0, split, &w2, 1.0);
+                *w = joined;
+                Ok(())
+            }
+            _ => Err(MatrixError::SolverError {
+                message: "H-matrix diagonal block is not factored".to_string(),
+            }),
+        }
+    }
+
+    /// B ← L⁻¹ P B for this block B in the rows of the factored diagonal block `l`
+    fn solve_lower_block(&mut self, l: &HNode, config: &HMatrixConfig) -> Result<()> {
+        match &mut self.block {
+            HBlock::Dense(dense) => l.solve_lower(dense),
+            HBlock::LowRank(low_rank) => l.solve_lower(&mut low_rank.u),
+            HBlock::Split(children) => {
+                let HBlock::Split(factors) = &l.block else { return Err(self.unfactored()) };
+                let [l11, _, l21, l22] = &**factors;
+                let [b11, b12, b21, b22] = &mut **children;
+                for (top, bottom) in [(b11, b21), (b12, b22)] {
+                    top.solve_lower_block(l11, config)?;
+                    bottom.multiply_subtract(l21, top, config)?;
+                    bottom.solve_lower_block(l22, config)?;
+                }
+                Ok(())
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+
+    /// B ← B U⁻¹ for this block B in the columns of the factored diagonal block `u`
+    fn solve_upper_block(&mut self, u: &HNode, config: &HMatrixConfig) -> Result<()> {
+        match &mut self.block {
+            HBlock::Dense(dense) => u.solve_upper_left(dense),
+            HBlock::LowRank(low_rank) => u.solve_upper_left(&mut low_rank.v),
+            HBlock::Split(children) => {
+                let HBlock::Split(factors) = &u.block else { return Err(self.unfactored()) };
+                let [u11, u12, _, u22] = &**factors;
+                let [b11, b12, b21, b22] = &mut **children;
+                for (left, right) in [(b11, b12), (b21, b22)] {
+                    left.solve_upper_block(u11, config)?;
+                    right.multiply_subtract(left, u12, config)?;
+                    right.solve_upper_block(u22, config)?;
+                }
+                Ok(())
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+
+    /// C ← C - A B for this block C
+    fn multiply_subtract(&mut self, a: &HNode, b: &HNode, config: &HMatrixConfig) -> Result<()> {
+        if let (HBlock::Split(a), HBlock::Split(b), HBlock::Split(c)) = (&a.block, &b.block, &mut self.block) {
+            for i in 0..2 {
+                for j in 0..2 {
+                    for k in 0..2 {
+                        c[2 * i + j].multiply_subtract(&a[2 * i + k], &b[2 * k + j], config)?;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        let product = self.product(a, b, config)?;
+        self.subtract(&product, config)
+    }
+
+    /// Product A B in the representation suited to this target block
+    fn product(&self, a: &HNode, b: &HNode, config: &HMatrixConfig) -> Result<Product> {
+        let inner = a.cols.len();
+        let small = inner < self.rows.len().min(self.cols.len());
+        Ok(match (&a.block, &b.block) {
+            (HBlock::LowRank(l), _) => Product::LowRank(LowRankBlock::new(l.u.clone(), b.apply_left(&l.v)?)?),
+            (_, HBlock::LowRank(l)) => Product::LowRank(LowRankBlock::new(a.apply(&l.u)?, l.v.clone())?),
+            (HBlock::Dense(d), _) if small => Product::LowRank(LowRankBlock::new(d.clone(), b.to_dense()?)?),
+            (_, HBlock::Dense(d)) if small => Product::LowRank(LowRankBlock::new(a.to_dense()?, d.clone())?),
+            (HBlock::Dense(d), _) => Product::Dense(b.apply_left(d)?),
+            (_, HBlock::Dense(d)) => Product::Dense(a.apply(d)?),
+            _ if matches!(self.block, HBlock::LowRank(_)) => {
+                // Cross approximation of the product from its rows and columns
+                let (m, n) = (a.rows.len(), b.cols.len());
+                let unit = |rows, cols, k| {
+                    let mut e = ComplexMatrix::new(rows, cols);
+                    e.data[k] = Complex64::new(1.0, 0.0);
+                    e
+                };
+                let low_rank = LowRankBlock::aca_with(
+                    m,
+                    n,
+                    |i| Ok(b.apply_left(&a.apply_left(&unit(1, m, i))?)?.data),
+                    |j| Ok(a.apply(&b.apply(&unit(n, 1, j))?)?.data),
+                    &config.aca,
+                )?;
+                Product::LowRank(low_rank)
+            }
+            _ => Product::Dense(a.apply(&b.to_dense()?)?),
+        })
+    }
+
+    /// C ← C - P, recompressing low-rank targets
+    fn subtract(&mut self, product: &Product, config: &HMatrixConfig) -> Result<()> {
+        let (row_start, col_start) = (self.rows.start, self.cols.start);
+        match &mut self.block {
+            HBlock::Dense(dense) => {
+                add_block(dense, 0, 0, &product.to_dense(), -1.0);
+                Ok(())
+            }
+            HBlock::LowRank(low_rank) => {
+                let mut negative = product.to_low_rank();
+                negative.u.data.iter_mut().for_each(|value| *value = -*value);
+                *low_rank = low_rank.add(&negative)?.truncate(config.aca.tolerance, config.aca.max_rank)?;
+                Ok(())
+            }
+            HBlock::Split(children) => {
+                for child in children.iter_mut() {
+                    let part = product.restrict(shift(&child.rows, row_start), shift(&child.cols, col_start));
+                    child.subtract(&part, config)?;
+                }
+                Ok(())
+            }
+            HBlock::Lu { .. } => Err(self.unfactored()),
+        }
+    }
+}
+
+impl Product {
+    fn to_dense(&self) -> ComplexMatrix {
+        match self {
+            Product::LowRank(low_rank) => low_rank.to_dense(),
+            Product::Dense(dense) => dense.clone(),
+        }
+    }
+
+    /// Low-rank form; a dense product becomes a factor times the identity
+    fn to_low_rank(&self) -> LowRankBlock {
+        match self {
+            Product::LowRank(low_rank) => low_rank.clone(),
+            Product::Dense(dense) if dense.rows <= dense.cols => LowRankBlock {
+                u: ComplexMatrix::identity(dense.rows),
+                v: dense.clone(),
+            },
+            Product::Dense(dense) => LowRankBlock {
+                u: dense.clone(),
+                v: ComplexMatrix::identity(dense.cols),
+            },
+        }
+    }
+
+    fn restrict(&self, rows: Range<usize>, cols: Range<usize>) -> Product {
+        match self {
+            Product::LowRank(low_rank) => Product::LowRank(LowRankBlock {
+                u: sub_block(&low_rank.u, rows, 0..low_rank.u.cols),
+                v: sub_block(&low_rank.v, 0..low_rank.v.rows, cols),
+            }),
+            Product::Dense(dense) => Product::Dense(sub_block(dense, rows, cols)),
+        }
+    }
+}
+
+/// Range relative to `origin`
+fn shift(range: &Range<usize>, origin: usize) -> Range<usize> {
+    (range.start - origin)..(range.end - origin)
+}
+
+/// Copy of the entries in the given rows and columns
+fn sub_block(matrix: &ComplexMatrix, rows: Range<usize>, cols: Range<usize>) -> ComplexMatrix {
+    let data = rows
+        .clone()
+        .flat_map(|i| matrix.data[i * matrix.cols + cols.start..i * matrix.cols + cols.end].iter().copied())
+        .collect();
+    ComplexMatrix { rows: rows.len(), cols: cols.len(), data }
+}
+
+/// target[row + i, col + j] += factor · source[i, j]
+fn add_block(target: &mut ComplexMatrix, row: usize, col: usize, source: &ComplexMatrix, factor: f64) {
+    for i in 0..source.rows {
+        let start = (row + i) * target.cols + col;
+        let destination = &mut target.data[start..start + source.cols];
+        for (t, s) in destination.iter_mut().zip(&source.data[i * source.cols..(i + 1) * source.cols]) {
+            *t += s * factor;
+        }
+    }
+}
+
+/// Packed LU factors of a dense block with partial pivoting
+fn dense_lu(matrix: &ComplexMatrix) -> Result<(ComplexMatrix, Vec<usize>)> {
+    let n = matrix.rows;
+    let mut factors = matrix.clone();
+    let mut pivots: Vec<usize> = (0..n).collect();
+    for k in 0..n {
+        let pivot = (k..n)
+            .max_by(|&a, &b| factors.data[a * n + k].norm().total_cmp(&factors.data[b * n + k].norm()))
+            .unwrap();
+        if factors.data[pivot * n + k].norm() == 0.0 {
+            return Err(MatrixError::SingularMatrix);
+        }
+        if pivot != k {
+            for j in 0..n {
+                factors.data.swap(k * n + j, pivot * n + j);
+            }
+            pivots.swap(k, pivot);
+        }
+        let diagonal = factors.data[k * n + k];
+        for i in (k + 1)..n {
+            let l = factors.data[i * n + k] / diagonal;
+            factors.data[i * n + k] = l;
+            for j in (k + 1)..n {
+                let u = factors.data[k * n + j];
+                factors.data[i * n + j] -= l * u;
+            }
+        }
+    }
+    Ok((factors, pivots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmatrix_matvec_and_lu() {
+        // Helmholtz kernel between panels on a ring waterline
+        let points: Vec<[f64; 3]> = (0..400)
+            .map(|i| {
+                let theta = i as f64 * std::f64::consts::TAU / 400.0;
+                [5.0 * theta.cos(), 5.0 * theta.sin(), -0.5]
+            })
+            .collect();
+        let kernel = |i: usize, j: usize| {
+            if i == j {
+                return Complex64::new(40.0, 2.0);
+            }
+            let r = (0..3).map(|d| (points[i][d] - points[j][d]).powi(2)).sum::<f64>().sqrt();
+            Complex64::new(0.0, 0.5 * r).exp() / r
+        };
+        let n = points.len();
+        let dense = ComplexMatrix::from_vec(n, n, (0..n * n).map(|k| kernel(k / n, k % n)).collect()).unwrap();
+        let config = HMatrixConfig { leaf_size: 16, ..Default::default() };
+        let hmatrix = HMatrix::from_dense(&dense, &points, &config).unwrap();
+
+        let (blocks, max_rank) = hmatrix.low_rank_blocks();
+        assert!(blocks > 0 && max_rank < 20);
+        assert!(hmatrix.storage() < n * n / 3);
+
+        let x: Vec<Complex64> = (0..n).map(|i| Complex64::new((i as f64 * 0.37).sin(), (i as f64 * 0.11).cos())).collect();
+        let exact = dense.matvec(&x).unwrap();
+        let norm = |v: &[Complex64]| v.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+        let difference = |a: &[Complex64], b: &[Complex64]| norm(&a.iter().zip(b).map(|(a, b)| a - b).collect::<Vec<_>>());
+        assert!(difference(&hmatrix.matvec(&x).unwrap(), &exact) < 1e-5 * norm(&exact));
+        let reconstructed = hmatrix.to_dense().unwrap();
+        assert!(difference(&reconstructed.data, &dense.data) < 1e-5 * norm(&dense.data));
+
+        // H-LU solve against the dense solution
+        let reference = complex_lu_solve(&dense, &exact).unwrap();
+        let lu = hmatrix.lu().unwrap();
+        let solution = lu.solve(&exact).unwrap();
+        assert!(difference(&solution, &reference) < 1e-4 * norm(&reference));
+
+        // Coarse H-LU as a GMRES preconditioner
+        let coarse = HMatrixConfig { aca: AcaControl { tolerance: 1e-2, ..Default::default() }, ..config };
+        let preconditioner = HMatrix::from_dense(&dense, &points, &coarse).unwrap().lu().unwrap();
+        let outcome = LinearSolver::new(SolverType::GMRES)
+            .solve_complex_preconditioned(&dense, &exact, |r| preconditioner.solve(r), &IterativeControl { tolerance: 1e-8, restart: Some(5), ..Default::default() })
+            .unwrap();
+        assert!(outcome.converged && outcome.iterations <= 5);
+        assert!(difference(&outcome.solution, &x) < 1e-6 * norm(&x));
+    }
+}
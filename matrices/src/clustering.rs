@@ -0,0 +1,409 @@
+//! Cluster trees and octrees over panel collocation points
+//!
+//! Fast BEM operators group panels hierarchically and treat well-separated
+//! groups as one far-field interaction. [`ClusterTree`] bisects the points
+//! at the median along the longest bounding-box side, giving the balanced
+//! binary tree whose block partition under the standard admissibility
+//! condition drives the H-matrix engines. [`Octree`] splits cubes into
+//! octants for the fast multipole method, with the colleague and
+//! interaction lists of its translation passes. Both hold their points as a
+//! permutation in which every cluster or box is a contiguous range.
+
+use super::*;
+
+/// Cluster of points: a contiguous range of the tree ordering and its bounding box
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cluster {
+    /// First position in the tree ordering
+    pub start: usize,
+    /// One past the last position in the tree ordering
+    pub end: usize,
+    /// Lower corner of the bounding box
+    pub min: [f64; 3],
+    /// Upper corner of the bounding box
+    pub max: [f64; 3],
+    /// Two halves, split across the longest box side
+    pub children: Option<[usize; 2]>,
+}
+
+impl Cluster {
+    /// Number of points
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Whether the cluster has no points
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Diagonal of the bounding box
+    pub fn diameter(&self) -> f64 {
+        (0..3).map(|d| (self.max[d] - self.min[d]).max(0.0).powi(2)).sum::<f64>().sqrt()
+    }
+
+    /// Distance between the bounding boxes
+    pub fn distance(&self, other: &Cluster) -> f64 {
+        (0..3)
+            .map(|d| (self.min[d] - other.max[d]).max(other.min[d] - self.max[d]).max(0.0).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Standard admissibility min(diam s, diam t) ≤ η dist(s, t) of the block between two clusters
+    pub fn is_admissible(&self, other: &Cluster, eta: f64) -> bool {
+        let distance = self.distance(other);
+        distance > 0.0 && self.diameter().min(other.diameter()) <= eta * distance
+    }
+}
+
+/// Block of a matrix partition between a row and a column cluster
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClusterBlock {
+    /// Row cluster
+    pub row: usize,
+    /// Column cluster
+    pub col: usize,
+    /// Far-field block eligible for low-rank compression
+    pub admissible: bool,
+}
+
+/// Binary cluster tree of panel collocation points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterTree {
+    /// Original index of the point at each position of the tree ordering
+    pub permutation: Vec<usize>,
+    /// Clusters, the root first
+    pub clusters: Vec<Cluster>,
+}
+
+impl ClusterTree {
+    /// Bisect the points until clusters hold at most `leaf_size` points
+    pub fn new(points: &[[f64; 3]], leaf_size: usize) -> Self {
+        let mut tree = Self {
+            permutation: (0..points.len()).collect(),
+            clusters: Vec::new(),
+        };
+        tree.split(points, 0, points.len(), leaf_size.max(1));
+        tree
+    }
+
+    fn split(&mut self, points: &[[f64; 3]], start: usize, end: usize, leaf_size: usize) -> usize {
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for &p in &self.permutation[start..end] {
+            for d in 0..3 {
+                min[d] = min[d].min(points[p][d]);
+                max[d] = max[d].max(points[p][d]);
+            }
+        }
+        let id = self.clusters.len();
+        self.clusters.push(Cluster { start, end, min, max, children: None });
+
+        if end - start > leaf_size {
+            let axis = (0..3).max_by(|&a, &b| (max[a] - min[a]).total_cmp(&(max[b] - min[b]))).unwrap();
+            self.permutation[start..end].sort_by(|&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+            let middle = (start + end) / 2;
+            let left = self.split(points, start, middle, leaf_size);
+            let right = self.split(points, middle, end, leaf_size);
+            self.clusters[id].children = Some([left, right]);
+        }
+        id
+    }
+
+    /// Number of points
+    pub fn size(&self) -> usize {
+        self.permutation.len()
+    }
+
+    /// Original indices of the points of a cluster
+    pub fn indices(&self, cluster: usize) -> &[usize] {
+        let cluster = &self.clusters[cluster];
+        &self.permutation[cluster.start..cluster.end]
+    }
+
+    /// Clusters without children
+    pub fn leaves(&self) -> Vec<usize> {
+        (0..self.clusters.len()).filter(|&c| self.clusters[c].children.is_none()).collect()
+    }
+
+    /// Parent of each cluster, `None` for the root
+    pub fn parents(&self) -> Vec<Option<usize>> {
+        let mut parents = vec![None; self.clusters.len()];
+        for (id, cluster) in self.clusters.iter().enumerate() {
+            for child in cluster.children.into_iter().flatten() {
+                parents[child] = Some(id);
+            }
+        }
+        parents
+    }
+
+    /// Levels below the root of the deepest leaf
+    pub fn depth(&self) -> usize {
+        fn depth(tree: &ClusterTree, c: usize) -> usize {
+            tree.clusters[c].children.map_or(0, |[a, b]| 1 + depth(tree, a).max(depth(tree, b)))
+        }
+        if self.clusters.is_empty() {
+            0
+        } else {
+            depth(self, 0)
+        }
+    }
+
+    /// Partition the matrix over the tree into admissible blocks and near-field leaf blocks
+    ///
+    /// A block that fails the admissibility condition is split along
+    /// whichever of its clusters has children, so the blocks cover every
+    /// row and column pair exactly once.
+    pub fn partition(&self, eta: f64) -> Vec<ClusterBlock> {
+        let mut blocks = Vec::new();
+        if !self.clusters.is_empty() {
+            self.subdivide(0, 0, eta, &mut blocks);
+        }
+        blocks
+    }
+
+    fn subdivide(&self, row: usize, col: usize, eta: f64, blocks: &mut Vec<ClusterBlock>) {
+        let (tau, sigma) = (&self.clusters[row], &self.clusters[col]);
+        if tau.is_admissible(sigma, eta) {
+            blocks.push(ClusterBlock { row, col, admissible: true });
+            return;
+        }
+        match (tau.children, sigma.children) {
+            (None, None) => blocks.push(ClusterBlock { row, col, admissible: false }),
+            (Some(rows), None) => rows.into_iter().for_each(|r| self.subdivide(r, col, eta, blocks)),
+            (None, Some(cols)) => cols.into_iter().for_each(|c| self.subdivide(row, c, eta, blocks)),
+            (Some(rows), Some(cols)) => {
+                for r in rows {
+                    for c in cols {
+                        self.subdivide(r, c, eta, blocks);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Cube of an octree: a contiguous range of the tree ordering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OctreeNode {
+    /// Centre of the cube
+    pub center: [f64; 3],
+    /// Half the side length
+    pub half_width: f64,
+    /// Subdivisions below the root cube
+    pub level: usize,
+    /// First position in the tree ordering
+    pub start: usize,
+    /// One past the last position in the tree ordering
+    pub end: usize,
+    /// Enclosing cube, `None` for the root
+    pub parent: Option<usize>,
+    /// Non-empty octants
+    pub children: Vec<usize>,
+}
+
+impl OctreeNode {
+    /// Number of points
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Whether the cube holds no points
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Whether two cubes overlap or share a face, edge or corner
+    pub fn is_adjacent(&self, other: &OctreeNode) -> bool {
+        let reach = self.half_width + other.half_width;
+        (0..3).all(|d| (self.center[d] - other.center[d]).abs() <= reach * (1.0 + 1e-12))
+    }
+}
+
+/// Octree of panel collocation points for fast multipole translations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Octree {
+    /// Original index of the point at each position of the tree ordering
+    pub permutation: Vec<usize>,
+    /// Cubes, the root first and children after their parent
+    pub nodes: Vec<OctreeNode>,
+}
+
+impl Octree {
+    /// Split the bounding cube of the points into octants until a cube holds at most
+    /// `leaf_size` points or lies `max_level` levels down
+    pub fn new(points: &[[f64; 3]], leaf_size: usize, max_level: usize) -> Self {
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for p in points {
+            for d in 0..3 {
+                min[d] = min[d].min(p[d]);
+                max[d] = max[d].max(p[d]);
+            }
+        }
+        let mut tree = Self {
+            permutation: (0..points.len()).collect(),
+            nodes: Vec::new(),
+        };
+        if points.is_empty() {
+            return tree;
+        }
+        let center = [0, 1, 2].map(|d| 0.5 * (min[d] + max[d]));
+        // Widen slightly so points on the upper faces fall inside
+        let half_width = (0..3).map(|d| 0.5 * (max[d] - min[d])).fold(0.0, f64::max).max(f64::MIN_POSITIVE) * (1.0 + 1e-9);
+        tree.nodes.push(OctreeNode {
+            center,
+            half_width,
+            level: 0,
+            start: 0,
+            end: points.len(),
+            parent: None,
+            children: Vec::new(),
+        });
+        let mut queue = std::collections::VecDeque::from([0]);
+        while let Some(id) = queue.pop_front() {
+            let node = &tree.nodes[id];
+            if node.len() <= leaf_size.max(1) || node.level >= max_level {
+                continue;
+            }
+            let (center, half, level, start, end) = (node.center, node.half_width, node.level, node.start, node.end);
+            let octant = |p: usize| (0..3).map(|d| ((points[p][d] >= center[d]) as usize) << d).sum::<usize>();
+            tree.permutation[start..end].sort_by_key(|&p| octant(p));
+            let mut first = start;
+            for k in 0..8 {
+                let last = first + tree.permutation[first..end].iter().take_while(|&&p| octant(p) == k).count();
+                if last > first {
+                    let child = tree.nodes.len();
+                    tree.nodes.push(OctreeNode {
+                        center: [0, 1, 2].map(|d| center[d] + if k >> d & 1 == 1 { 0.5 } else { -0.5 } * half),
+                        half_width: 0.5 * half,
+                        level: level + 1,
+                        start: first,
+                        end: last,
+                        parent: Some(id),
+                        children: Vec::new(),
+                    });
+                    tree.nodes[id].children.push(child);
+                    queue.push_back(child);
+                }
+                first = last;
+            }
+        }
+        tree
+    }
+
+    /// Original indices of the points of a cube
+    pub fn indices(&self, node: usize) -> &[usize] {
+        let node = &self.nodes[node];
+        &self.permutation[node.start..node.end]
+    }
+
+    /// Cubes without children
+    pub fn leaves(&self) -> Vec<usize> {
+        (0..self.nodes.len()).filter(|&n| self.nodes[n].children.is_empty()).collect()
+    }
+
+    /// Adjacent cubes of the same level (colleagues), excluding the cube itself
+    pub fn neighbours(&self, node: usize) -> Vec<usize> {
+        let Some(parent) = self.nodes[node].parent else {
+            return Vec::new();
+        };
+        let mut candidates = vec![parent];
+        candidates.extend(self.neighbours(parent));
+        candidates
+            .into_iter()
+            .flat_map(|c| self.nodes[c].children.iter().copied())
+            .filter(|&c| c != node && self.nodes[c].is_adjacent(&self.nodes[node]))
+            .collect()
+    }
+
+    /// Well-separated cubes of the same level whose parents are adjacent to the cube's parent
+    ///
+    /// These are the sources of the multipole-to-local translations into the cube.
+    pub fn interaction_list(&self, node: usize) -> Vec<usize> {
+        let Some(parent) = self.nodes[node].parent else {
+            return Vec::new();
+        };
+        let mut candidates = vec![parent];
+        candidates.extend(self.neighbours(parent));
+        candidates
+            .into_iter()
+            .flat_map(|c| self.nodes[c].children.iter().copied())
+            .filter(|&c| !self.nodes[c].is_adjacent(&self.nodes[node]))
+            .collect()
+    }
+
+    /// Leaves adjacent to a cube, at any level, with which it interacts directly
+    pub fn near_field(&self, node: usize) -> Vec<usize> {
+        let mut near = Vec::new();
+        let mut stack = vec![0];
+        while let Some(c) = stack.pop() {
+            if !self.nodes[c].is_adjacent(&self.nodes[node]) {
+                continue;
+            }
+            if self.nodes[c].children.is_empty() {
+                near.push(c);
+            } else {
+                stack.extend(&self.nodes[c].children);
+            }
+        }
+        near.sort_unstable();
+        near
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(n: usize, offset: f64) -> Vec<[f64; 3]> {
+        (0..n * n).map(|k| [offset + (k % n) as f64, (k / n) as f64, -1.0]).collect()
+    }
+
+    #[test]
+    fn test_cluster_tree_is_balanced_and_partitions_every_pair() {
+        let mut points = grid(16, 0.0);
+        points.extend(grid(16, 40.0));
+        let tree = ClusterTree::new(&points, 16);
+        let mut seen = tree.permutation.clone();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..points.len()).collect::<Vec<_>>());
+        assert!(tree.leaves().iter().all(|&l| (8..=16).contains(&tree.clusters[l].len())));
+        assert_eq!(tree.depth(), 5);
+        assert_eq!(tree.parents()[0], None);
+
+        let blocks = tree.partition(2.0);
+        let mut covered = vec![0usize; points.len() * points.len()];
+        for block in &blocks {
+            for &i in tree.indices(block.row) {
+                for &j in tree.indices(block.col) {
+                    covered[i * points.len() + j] += 1;
+                }
+            }
+        }
+        assert!(covered.iter().all(|&c| c == 1));
+        // The two distant grids interact through a single admissible block each way
+        assert!(blocks.iter().any(|b| b.admissible && tree.clusters[b.row].len() == 256));
+        assert!(blocks.iter().filter(|b| !b.admissible).all(|b| tree.clusters[b.row].children.is_none()));
+    }
+
+    #[test]
+    fn test_octree_lists_separate_near_and_far_cubes() {
+        let points: Vec<[f64; 3]> = (0..512).map(|k| [(k % 8) as f64, (k / 8 % 8) as f64, (k / 64) as f64]).collect();
+        let tree = Octree::new(&points, 1, 3);
+        assert_eq!(tree.leaves().len(), 512);
+        assert!(tree.leaves().iter().all(|&l| tree.nodes[l].len() == 1 && tree.nodes[l].level == 3));
+
+        // A corner cube has 7 colleagues and 4³ - 2³ = 56 interactions
+        let corner = tree.leaves().into_iter().find(|&l| tree.indices(l) == [0]).unwrap();
+        assert_eq!(tree.neighbours(corner).len(), 7);
+        let interactions = tree.interaction_list(corner);
+        assert_eq!(interactions.len(), 56);
+        assert!(interactions.iter().all(|&c| !tree.nodes[c].is_adjacent(&tree.nodes[corner])));
+        assert_eq!(tree.near_field(corner).len(), 8);
+
+        // An interior cube has the full 26 colleagues and 6³ - 27 = 189 interactions
+        let inner = tree.leaves().into_iter().find(|&l| tree.indices(l) == [2 + 8 * 2 + 64 * 2]).unwrap();
+        assert_eq!(tree.neighbours(inner).len(), 26);
+        assert_eq!(tree.interaction_list(inner).len(), 189);
+    }
+}
@@ -0,0 +1,591 @@
+//! Complex-valued matrices and linear solvers
+//!
+//! Free-surface influence coefficients are complex. Dense systems are
+//! factorized directly in ℂ and the Krylov solvers iterate in ℂ with the
+//! Hermitian inner product, so no real/imaginary split of the system is
+//! needed. `complex_to_real` and `real_to_complex` convert to the equivalent
+//! real form [[Re A, -Im A], [Im A, Re A]] for code that still expects it.
+
+use super::*;
+use crate::operator::check_operator;
+use crate::solvers::{IterativeControl, IterativeOutcome};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+
+/// Dense complex matrix, row-major
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComplexMatrix {
+    /// Number of rows
+    pub rows: usize,
+    /// Number of columns
+    pub cols: usize,
+    /// Matrix data (row-major)
+    pub data: Vec<Complex64>,
+}
+
+impl ComplexMatrix {
+    /// Create a new zero matrix
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![Complex64::new(0.0, 0.0); rows * cols],
+        }
+    }
+
+    /// Create matrix from row-major data
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<Complex64>) -> Result<Self> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::InvalidDimensions { rows, cols });
+        }
+        Ok(Self { rows, cols, data })
+    }
+
+    /// Combine real and imaginary parts of equal dimensions
+    pub fn from_parts(re: &Matrix, im: &Matrix) -> Result<Self> {
+        if re.dimensions() != im.dimensions() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: re.data.len(),
+                actual: im.data.len(),
+            });
+        }
+        let data = re.data.iter().zip(&im.data).map(|(&r, &i)| Complex64::new(r, i)).collect();
+        Self::from_vec(re.rows, re.cols, data)
+    }
+
+    /// Get element at position (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Result<Complex64> {
+        if i >= self.rows || j >= self.cols {
+            return Err(MatrixError::InvalidDimensions { rows: i, cols: j });
+        }
+        Ok(self.data[i * self.cols + j])
+    }
+
+    /// Set element at position (i, j)
+    pub fn set(&mut self, i: usize, j: usize, value: Complex64) -> Result<()> {
+        if i >= self.rows || j >= self.cols {
+            return Err(MatrixError::InvalidDimensions { rows: i, cols: j });
+        }
+        self.data[i * self.cols + j] = value;
+        Ok(())
+    }
+
+    /// Get matrix dimensions
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Check if matrix is square
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// Real part
+    pub fn real_part(&self) -> Matrix {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|z| z.re).collect() }
+    }
+
+    /// Imaginary part
+    pub fn imag_part(&self) -> Matrix {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|z| z.im).collect() }
+    }
+
+    /// Identity matrix of size `n`
+    pub fn identity(n: usize) -> Self {
+        let mut matrix = Self::new(n, n);
+        for i in 0..n {
+            matrix.data[i * n + i] = Complex64::new(1.0, 0.0);
+        }
+        matrix
+    }
+
+    /// Check if matrix is complex symmetric (A = Aᵀ, as for reciprocal BEM operators)
+    pub fn is_symmetric(&self) -> bool {
+        self.is_square()
+            && (0..self.rows).all(|i| {
+                ((i + 1)..self.cols).all(|j| (self.data[i * self.cols + j] - self.data[j * self.cols + i]).norm() <= 1e-10)
+            })
+    }
+
+    /// Sum of two matrices of equal dimensions
+    pub fn add(&self, other: &ComplexMatrix) -> Result<ComplexMatrix> {
+        if self.dimensions() != other.dimensions() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.rows * self.cols,
+                actual: other.rows * other.cols,
+            });
+        }
+        let data = self.data.iter().zip(&other.data).map(|(a, b)| a + b).collect();
+        Self::from_vec(self.rows, self.cols, data)
+    }
+
+    /// Matrix product A B
+    pub fn multiply(&self, other: &ComplexMatrix) -> Result<ComplexMatrix> {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.cols,
+                actual: other.rows,
+            });
+        }
+        let mut result = Self::new(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self.data[i * self.cols + k];
+                let row = &other.data[k * other.cols..(k + 1) * other.cols];
+                for (r, b) in result.data[i * other.cols..(i + 1) * other.cols].iter_mut().zip(row) {
+                    *r += a * b;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Matrix multiplied by a scalar
+    pub fn scale(&self, factor: Complex64) -> ComplexMatrix {
+        Self { rows: self.rows, cols: self.cols, data: self.data.iter().map(|a| a * factor).collect() }
+    }
+
+    /// Transpose Aᵀ
+    pub fn transpose(&self) -> ComplexMatrix {
+        let data = (0..self.cols)
+            .flat_map(|j| (0..self.rows).map(move |i| (i, j)))
+            .map(|(i, j)| self.data[i * self.cols + j])
+            .collect();
+        Self { rows: self.cols, cols: self.rows, data }
+    }
+
+    /// Conjugate transpose Aᴴ
+    pub fn conjugate_transpose(&self) -> ComplexMatrix {
+        let mut transposed = self.transpose();
+        transposed.data.iter_mut().for_each(|a| *a = a.conj());
+        transposed
+    }
+
+    /// Matrix-vector product A x
+    pub fn matvec(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        if x.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.cols,
+                actual: x.len(),
+            });
+        }
+        Ok(parallel::map_rows(self.rows, self.data.len(), |i| {
+            self.data[i * self.cols..(i + 1) * self.cols].iter().zip(x).map(|(a, b)| a * b).sum()
+        }))
+    }
+}
+
+/// Split a complex vector into `[re..., im...]`
+pub fn complex_to_real(x: &[Complex64]) -> Vec<f64> {
+    x.iter().map(|z| z.re).chain(x.iter().map(|z| z.im)).collect()
+}
+
+/// Join `[re..., im...]` into a complex vector
+pub fn real_to_complex(x: &[f64]) -> Vec<Complex64> {
+    let n = x.len() / 2;
+    x[..n].iter().zip(&x[n..]).map(|(&re, &im)| Complex64::new(re, im)).collect()
+}
+
+/// LU decomposition solver for complex systems
+pub fn complex_lu_solve(a: &ComplexMatrix, b: &[Complex64]) -> Result<Vec<Complex64>> {
+    let mut solutions = complex_lu_solve_multi(a, &[b.to_vec()])?;
+    Ok(solutions.remove(0))
+}
+
+/// LU solve of a complex system for several right-hand sides, factorizing once
+pub fn complex_lu_solve_multi(a: &ComplexMatrix, b: &[Vec<Complex64>]) -> Result<Vec<Vec<Complex64>>> {
+    backend::complex_lu_solve_multi(a, b)
+}
+
+/// Right-preconditioned GMRES on any complex [`LinearOperator`]
+///
+/// Arnoldi in ℂ with modified Gram-Schmidt and complex Givens rotations;
+/// restarts, stopping criteria and history follow the real solver.
+pub fn complex_gmres_iterate<A, P>(
+    a: &A,
+    precondition: P,
+    b: &[Complex64],
+    control: &IterativeControl,
+) -> Result<IterativeOutcome<Complex64>>
+where
+    A: LinearOperator<Complex64> + ?Sized,
+    P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[Complex64]| a.apply(x);
+    let zero = Complex64::new(0.0, 0.0);
+    let restart = control.restart.unwrap_or(n.min(50)).max(1);
+    let finish = |solution, iterations, residual, converged, timed_out, history| {
+        Ok(IterativeOutcome { solution, iterations, residual, converged, timed_out, history })
+    };
+
+    let mut x = vec![zero; n];
+    let mut r = b.to_vec();
+    let mut beta = norm(&r);
+    let mut iterations = 0;
+    let mut history = vec![beta];
+    if beta < control.tolerance {
+        return finish(x, 0, beta, true, false, history);
+    }
+
+    for _ in 0..control.max_iterations {
+        if control.expired() {
+            return finish(x, iterations, beta, false, true, history);
+        }
+
+        // Arnoldi basis of A M⁻¹ from the current residual
+        let mut v = vec![scale(&r, Complex64::new(1.0 / beta, 0.0))];
+        let mut h = vec![vec![zero; restart]; restart + 1];
+        let mut k = 0;
+        for j in 0..restart {
+            let mut w = apply(&precondition(&v[j])?)?;
+            iterations += 1;
+            k = j + 1;
+            for i in 0..=j {
+                h[i][j] = dot(&v[i], &w);
+                axpy(&mut w, -h[i][j], &v[i]);
+            }
+            let h_next = norm(&w);
+            h[j + 1][j] = Complex64::new(h_next, 0.0);
+            if h_next < 1e-14 {
+                break;
+            }
+            v.push(scale(&w, Complex64::new(1.0 / h_next, 0.0)));
+        }
+
+        // x += M⁻¹ V y with y minimizing |β e₁ - H y|
+        let y = least_squares(&h, beta, k)?;
+        let mut z = vec![zero; n];
+        for (yj, vj) in y.iter().zip(&v) {
+            axpy(&mut z, *yj, vj);
+        }
+        for (xi, zi) in x.iter_mut().zip(precondition(&z)?) {
+            *xi += zi;
+        }
+
+        r = b.iter().zip(apply(&x)?).map(|(bi, ax)| bi - ax).collect();
+        beta = norm(&r);
+        history.push(beta);
+        if beta < control.tolerance {
+            return finish(x, iterations, beta, true, false, history);
+        }
+    }
+    finish(x, iterations, beta, false, false, history)
+}
+
+/// Right-preconditioned BiCGSTAB on any complex [`LinearOperator`], with the shadow residual r₀ and Hermitian products
+pub fn complex_bicgstab_iterate<A, P>(
+    a: &A,
+    precondition: P,
+    b: &[Complex64],
+    control: &IterativeControl,
+) -> Result<IterativeOutcome<Complex64>>
+where
+    A: LinearOperator<Complex64> + ?Sized,
+    P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[Complex64]| a.apply(x);
+    let zero = Complex64::new(0.0, 0.0);
+    let one = Complex64::new(1.0, 0.0);
+    let finish = |solution, iterations, residual, converged, timed_out, history| {
+        Ok(IterativeOutcome { solution, iterations, residual, converged, timed_out, history })
+    };
+
+    let mut x = vec![zero; n];
+    let mut r = b.to_vec();
+    let r0 = r.clone();
+    let r0_norm_sq = dot(&r0, &r0).re;
+    let mut v = vec![zero; n];
+    let mut p = vec![zero; n];
+    let mut history = vec![norm(&r)];
+    if history[0] < control.tolerance {
+        return finish(x, 0, history[0], true, false, history);
+    }
+
+    let (mut rho, mut alpha, mut omega) = (one, one, one);
+    for iteration in 0..control.max_iterations {
+        if control.expired() {
+            let residual = norm(&r);
+            return finish(x, iteration, residual, false, true, history);
+        }
+
+        // Breakdown relative to the initial residual, so small right-hand sides are not rejected
+        let rho_new = dot(&r0, &r);
+        if rho_new.norm() < 1e-14 * r0_norm_sq {
+            return Err(MatrixError::SolverError {
+                message: "BiCGSTAB breakdown: rho too small".to_string(),
+            });
+        }
+        let beta = (rho_new / rho) * (alpha / omega);
+        for ((pi, ri), vi) in p.iter_mut().zip(&r).zip(&v) {
+            *pi = ri + beta * (*pi - omega * vi);
+        }
+
+        let p_hat = precondition(&p)?;
+        v = apply(&p_hat)?;
+        alpha = rho_new / dot(&r0, &v);
+        let s: Vec<Complex64> = r.iter().zip(&v).map(|(ri, vi)| ri - alpha * vi).collect();
+        let s_norm = norm(&s);
+        if s_norm < control.tolerance {
+            axpy(&mut x, alpha, &p_hat);
+            history.push(s_norm);
+            return finish(x, iteration + 1, s_norm, true, false, history);
+        }
+
+        let s_hat = precondition(&s)?;
+        let t = apply(&s_hat)?;
+        omega = dot(&t, &s) / dot(&t, &t);
+        axpy(&mut x, alpha, &p_hat);
+        axpy(&mut x, omega, &s_hat);
+        r = s.iter().zip(&t).map(|(si, ti)| si - omega * ti).collect();
+
+        let r_norm = norm(&r);
+        history.push(r_norm);
+        if r_norm < control.tolerance {
+            return finish(x, iteration + 1, r_norm, true, false, history);
+        }
+        if omega.norm() < 1e-14 {
+            return Err(MatrixError::SolverError {
+                message: "BiCGSTAB breakdown: omega too small".to_string(),
+            });
+        }
+        rho = rho_new;
+    }
+    let residual = norm(&r);
+    finish(x, control.max_iterations, residual, false, false, history)
+}
+
+/// Hermitian inner product aᴴ b
+pub(crate) fn dot(a: &[Complex64], b: &[Complex64]) -> Complex64 {
+    a.iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+pub(crate) fn norm(v: &[Complex64]) -> f64 {
+    v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
+}
+
+pub(crate) fn scale(v: &[Complex64], factor: Complex64) -> Vec<Complex64> {
+    v.iter().map(|x| x * factor).collect()
+}
+
+/// y += a x
+pub(crate) fn axpy(y: &mut [Complex64], a: Complex64, x: &[Complex64]) {
+    for (yi, xi) in y.iter_mut().zip(x) {
+        *yi += a * xi;
+    }
+}
+
+/// Minimize |β e₁ - H y| over the leading (k+1)×k Hessenberg block with complex Givens rotations
+pub(crate) fn least_squares(h: &[Vec<Complex64>], beta: f64, k: usize) -> Result<Vec<Complex64>> {
+    let zero = Complex64::new(0.0, 0.0);
+    let mut r: Vec<Vec<Complex64>> = h.iter().take(k + 1).map(|row| row[..k].to_vec()).collect();
+    let mut g = vec![zero; k + 1];
+    g[0] = Complex64::new(beta, 0.0);
+    for j in 0..k {
+        let (a, b) = (r[j][j], r[j + 1][j]);
+        let norm = a.norm().hypot(b.norm());
+        if norm == 0.0 {
+            continue;
+        }
+        // Unitary rotation [[c̄, s̄], [-s, c]] zeroing the subdiagonal entry
+        let (c, s) = (a / norm, b / norm);
+        let (top, bottom) = r.split_at_mut(j + 1);
+        for (upper, lower) in top[j][j..k].iter_mut().zip(&mut bottom[0][j..k]) {
+            (*upper, *lower) = (c.conj() * *upper + s.conj() * *lower, -s * *upper + c * *lower);
+        }
+        let (upper, lower) = (g[j], g[j + 1]);
+        g[j] = c.conj() * upper + s.conj() * lower;
+        g[j + 1] = -s * upper + c * lower;
+    }
+
+    let mut y = vec![zero; k];
+    for i in (0..k).rev() {
+        let sum = g[i] - ((i + 1)..k).map(|j| r[i][j] * y[j]).sum::<Complex64>();
+        if r[i][i].norm() < 1e-14 {
+            return Err(MatrixError::SingularMatrix);
+        }
+        y[i] = sum / r[i][i];
+    }
+    Ok(y)
+}
+
+impl MatrixType for ComplexMatrix {
+    fn type_name(&self) -> &'static str {
+        "DenseComplexMatrix"
+    }
+
+    fn is_sparse(&self) -> bool {
+        false
+    }
+
+    fn is_dense(&self) -> bool {
+        true
+    }
+}
+
+impl MatrixFormat for ComplexMatrix {
+    fn format(&self) -> &'static str {
+        "RowMajor"
+    }
+}
+
+impl LinearSolver {
+    /// Solve a complex system with explicit stopping criteria
+    ///
+    /// LU and Cholesky factorize in ℂ (Cholesky falls back to LU since BEM
+    /// matrices are not Hermitian); conjugate gradients falls back to GMRES
+    /// since BEM matrices are not Hermitian positive definite. The Krylov
+    /// solvers use the attached complex preconditioner, if any.
+    pub fn solve_complex(
+        &self,
+        a: &ComplexMatrix,
+        b: &[Complex64],
+        control: &IterativeControl,
+    ) -> Result<IterativeOutcome<Complex64>> {
+        self.solve_complex_preconditioned(a, b, |x| self.precondition_complex(x), control)
+    }
+
+    /// Solve a complex system with a right preconditioner `precondition(x) = M⁻¹ x`
+    pub fn solve_complex_preconditioned<P>(
+        &self,
+        a: &ComplexMatrix,
+        b: &[Complex64],
+        precondition: P,
+        control: &IterativeControl,
+    ) -> Result<IterativeOutcome<Complex64>>
+    where
+        P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+    {
+        let mut outcomes = self.solve_complex_multi_preconditioned(a, &[b.to_vec()], precondition, control)?;
+        Ok(outcomes.remove(0))
+    }
+
+    /// Solve with any complex [`LinearOperator`] and a right preconditioner `precondition(x) = M⁻¹ x`
+    ///
+    /// Conjugate gradients falls back to GMRES as for dense systems. Direct
+    /// solvers need the matrix entries and return an error.
+    pub fn solve_complex_operator<A, P>(
+        &self,
+        a: &A,
+        b: &[Complex64],
+        precondition: P,
+        control: &IterativeControl,
+    ) -> Result<IterativeOutcome<Complex64>>
+    where
+        A: LinearOperator<Complex64> + ?Sized,
+        P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+    {
+        match self.solver_type() {
+            SolverType::GMRES | SolverType::ConjugateGradient => complex_gmres_iterate(a, precondition, b, control),
+            SolverType::BiCGSTAB => complex_bicgstab_iterate(a, precondition, b, control),
+            solver_type => Err(direct_on_operator(solver_type)),
+        }
+    }
+
+    /// Solve a complex system for several right-hand sides
+    ///
+    /// Direct solvers factorize once for all right-hand sides; iterative
+    /// solvers run once per right-hand side on the same operator.
+    pub fn solve_complex_multi_preconditioned<P>(
+        &self,
+        a: &ComplexMatrix,
+        b: &[Vec<Complex64>],
+        precondition: P,
+        control: &IterativeControl,
+    ) -> Result<Vec<IterativeOutcome<Complex64>>>
+    where
+        P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+    {
+        if let Some(column) = b.iter().find(|column| a.rows != column.len() || !a.is_square()) {
+            return Err(MatrixError::DimensionMismatch { expected: a.rows, actual: column.len() });
+        }
+        match self.solver_type() {
+            SolverType::GMRES | SolverType::ConjugateGradient | SolverType::BiCGSTAB => b
+                .iter()
+                .map(|b| self.solve_complex_operator(a, b, &precondition, control))
+                .collect(),
+            SolverType::MixedPrecisionLU => complex_mixed_precision_lu_solve_multi(a, b),
+            SolverType::LU | SolverType::Cholesky => complex_lu_solve_multi(a, b)?
+                .into_iter()
+                .zip(b)
+                .map(|(solution, b)| {
+                    let residual = a
+                        .matvec(&solution)?
+                        .iter()
+                        .zip(b)
+                        .map(|(ax, bi)| (bi - ax).norm_sqr())
+                        .sum::<f64>()
+                        .sqrt();
+                    Ok(IterativeOutcome {
+                        solution,
+                        iterations: 0,
+                        residual,
+                        converged: true,
+                        timed_out: false,
+                        history: vec![residual],
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complex_solvers_agree() {
+        let c = |re: f64, im: f64| Complex64::new(re, im);
+        let a = ComplexMatrix::from_vec(
+            3,
+            3,
+            vec![c(6.0, 1.0), c(1.0, -0.5), c(0.2, 0.0), c(0.5, 0.5), c(5.0, -2.0), c(1.0, 0.0), c(0.0, 1.0), c(0.3, 0.0), c(4.0, 0.5)],
+        )
+        .unwrap();
+        let x = vec![c(1.0, -1.0), c(0.5, 2.0), c(-1.5, 0.0)];
+        let b = a.matvec(&x).unwrap();
+
+        for solver_type in [SolverType::LU, SolverType::GMRES, SolverType::BiCGSTAB] {
+            let outcome = LinearSolver::new(solver_type).solve_complex(&a, &b, &IterativeControl::default()).unwrap();
+            assert!(outcome.converged);
+            for (xi, ei) in outcome.solution.iter().zip(&x) {
+                assert!((xi - ei).norm() < 1e-8);
+            }
+        }
+        assert_eq!(ComplexMatrix::from_parts(&a.real_part(), &a.imag_part()).unwrap(), a);
+
+        // Restarted and preconditioned iterations still converge in ℂ
+        let control = IterativeControl { restart: Some(2), ..Default::default() };
+        let jacobi = |v: &[Complex64]| Ok(v.iter().enumerate().map(|(i, vi)| vi / a.data[i * 4]).collect());
+        for solver_type in [SolverType::GMRES, SolverType::BiCGSTAB] {
+            let outcome = LinearSolver::new(solver_type).solve_complex_preconditioned(&a, &b, jacobi, &control).unwrap();
+            assert!(outcome.converged && outcome.history.last().unwrap() < &outcome.history[0]);
+            assert!(outcome.solution.iter().zip(&x).all(|(xi, ei)| (xi - ei).norm() < 1e-8));
+        }
+
+        // Matrix operations
+        let identity = ComplexMatrix::identity(3);
+        assert_eq!(a.multiply(&identity).unwrap(), a);
+        assert_eq!(a.transpose().transpose(), a);
+        assert_eq!(a.conjugate_transpose().get(0, 1).unwrap(), a.get(1, 0).unwrap().conj());
+        assert_eq!(a.add(&a).unwrap(), a.scale(c(2.0, 0.0)));
+        assert!(a.add(&a.transpose()).unwrap().is_symmetric() && !a.is_symmetric());
+        let product = a.multiply(&ComplexMatrix::from_vec(3, 1, x.clone()).unwrap()).unwrap();
+        assert!(product.data.iter().zip(&b).all(|(p, e)| (p - e).norm() < 1e-12));
+
+        // Several right-hand sides share one factorization
+        let y = vec![c(0.0, 1.0), c(-2.0, 0.5), c(3.0, 3.0)];
+        let rhs = vec![b.clone(), a.matvec(&y).unwrap()];
+        for solver_type in [SolverType::LU, SolverType::GMRES] {
+            let outcomes = LinearSolver::new(solver_type)
+                .solve_complex_multi_preconditioned(&a, &rhs, |x| Ok(x.to_vec()), &IterativeControl::default())
+                .unwrap();
+            for (outcome, expected) in outcomes.iter().zip([&x, &y]) {
+                assert!(outcome.converged && outcome.residual < 1e-8);
+                assert!(outcome.solution.iter().zip(expected).all(|(xi, ei)| (xi - ei).norm() < 1e-8));
+            }
+        }
+    }
+}
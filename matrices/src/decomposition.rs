@@ -0,0 +1,358 @@
+//! Dense LU, eigenvalue and singular value decompositions
+//!
+//! [`DenseLU`] keeps the factors of a dense system so that further
+//! right-hand sides, such as new wave headings, cost one O(n²) solve each.
+//! Symmetric (and symmetric-definite generalized) eigenproblems give the dry
+//! modes of a structure for generalized-mode hydroelasticity, and truncated
+//! singular value decompositions give POD modes of free-surface snapshots
+//! and the numerical rank of ACA blocks. Truncation keeps the smallest rank
+//! whose dropped singular values satisfy √(Σ σ²_dropped) ≤ tolerance · √(Σ σ²),
+//! the same criterion as [`LowRankBlock::truncate`].
+
+use super::*;
+use crate::backend::{lu_apply, lu_factor};
+use crate::sparse::SparseScalar;
+use nalgebra::{Cholesky, DMatrix};
+use num_complex::Complex64;
+
+/// Dense LU factorization P A = L U with partial pivoting
+///
+/// L (unit diagonal, below) and U (on and above the diagonal) share one
+/// row-major array, as in LAPACK's `xGETRF`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseLU<T = f64> {
+    n: usize,
+    factors: Vec<T>,
+    pivots: Vec<usize>,
+}
+
+impl<T: SparseScalar> DenseLU<T> {
+    /// Factorize a square row-major matrix
+    pub fn new(n: usize, data: &[T]) -> Result<Self> {
+        if data.len() != n * n {
+            return Err(MatrixError::DimensionMismatch { expected: n * n, actual: data.len() });
+        }
+        let mut factors = data.to_vec();
+        let pivots = lu_factor(n, &mut factors)?;
+        Ok(Self { n, factors, pivots })
+    }
+
+    /// Rebuild from stored factors and row interchanges, e.g. read back from a file
+    pub fn from_parts(n: usize, factors: Vec<T>, pivots: Vec<usize>) -> Result<Self> {
+        if factors.len() != n * n {
+            return Err(MatrixError::DimensionMismatch { expected: n * n, actual: factors.len() });
+        }
+        if pivots.len() != n {
+            return Err(MatrixError::DimensionMismatch { expected: n, actual: pivots.len() });
+        }
+        if pivots.iter().enumerate().any(|(j, &p)| p < j || p >= n) {
+            return Err(MatrixError::SolverError {
+                message: "Invalid LU row interchanges".to_string(),
+            });
+        }
+        Ok(Self { n, factors, pivots })
+    }
+
+    /// Size of the system
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// L and U in one row-major array
+    pub fn factors(&self) -> &[T] {
+        &self.factors
+    }
+
+    /// Row exchanged with row `j` at step `j`
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    /// Solve A x = b with the stored factors
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>> {
+        if b.len() != self.n {
+            return Err(MatrixError::DimensionMismatch { expected: self.n, actual: b.len() });
+        }
+        let mut x = b.to_vec();
+        lu_apply(self.n, &self.factors, &self.pivots, &mut x);
+        Ok(x)
+    }
+}
+
+impl<T: SparseScalar> Preconditioner<T> for DenseLU<T> {
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.solve(x)
+    }
+}
+
+/// Eigenvalues in ascending order and their eigenvectors
+#[derive(Debug, Clone)]
+pub struct EigenDecomposition {
+    /// Eigenvalues, ascending
+    pub values: Vec<f64>,
+    /// Eigenvectors as columns, matching `values`
+    pub vectors: Matrix,
+}
+
+impl EigenDecomposition {
+    /// Number of eigenpairs
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether there are no eigenpairs
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Eigenvector `k`
+    pub fn vector(&self, k: usize) -> Vec<f64> {
+        (0..self.vectors.rows).map(|i| self.vectors.data[i * self.vectors.cols + k]).collect()
+    }
+
+    /// Keep the `count` lowest eigenpairs, e.g. the retained modes of a modal reduction
+    pub fn truncated(&self, count: usize) -> Self {
+        let (n, count) = (self.vectors.rows, count.min(self.len()));
+        let data = (0..n * count).map(|k| self.vectors.data[(k / count) * self.vectors.cols + k % count]).collect();
+        Self {
+            values: self.values[..count].to_vec(),
+            vectors: Matrix { rows: n, cols: count, data },
+        }
+    }
+
+    fn from_nalgebra(values: &[f64], vectors: &DMatrix<f64>) -> Self {
+        let n = vectors.nrows();
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let data = (0..n * order.len()).map(|k| vectors[(k / order.len(), order[k % order.len()])]).collect();
+        Self {
+            values: order.iter().map(|&k| values[k]).collect(),
+            vectors: Matrix { rows: n, cols: order.len(), data },
+        }
+    }
+}
+
+impl Matrix {
+    /// LU factorization with partial pivoting
+    pub fn lu(&self) -> Result<DenseLU> {
+        if !self.is_square() {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols });
+        }
+        DenseLU::new(self.rows, &self.data)
+    }
+
+    /// Eigenvalues and orthonormal eigenvectors of a symmetric matrix
+    pub fn symmetric_eigen(&self) -> Result<EigenDecomposition> {
+        let a = symmetric(self)?;
+        let eigen = a.symmetric_eigen();
+        Ok(EigenDecomposition::from_nalgebra(eigen.eigenvalues.as_slice(), &eigen.eigenvectors))
+    }
+
+    /// Generalized eigenproblem K φ = λ M φ with K = `self` symmetric and M = `mass` positive definite
+    ///
+    /// The eigenvectors are mass-normalized, φᵢᵀ M φⱼ = δᵢⱼ, as dry modes of
+    /// a structure with stiffness K and mass M.
+    pub fn generalized_symmetric_eigen(&self, mass: &Matrix) -> Result<EigenDecomposition> {
+        let k = symmetric(self)?;
+        let m = symmetric(mass)?;
+        if m.nrows() != k.nrows() {
+            return Err(MatrixError::DimensionMismatch { expected: k.nrows(), actual: m.nrows() });
+        }
+        let l = Cholesky::new(m)
+            .ok_or_else(|| MatrixError::SolverError {
+                message: "Mass matrix is not positive definite".to_string(),
+            })?
+            .l();
+        let singular = || MatrixError::SingularMatrix;
+        // C = L⁻¹ K L⁻ᵀ, symmetric like K
+        let half = l.solve_lower_triangular(&k).ok_or_else(singular)?;
+        let c = l.solve_lower_triangular(&half.transpose()).ok_or_else(singular)?;
+        let eigen = ((&c + c.transpose()) * 0.5).symmetric_eigen();
+        let vectors = l.transpose().solve_upper_triangular(&eigen.eigenvectors).ok_or_else(singular)?;
+        Ok(EigenDecomposition::from_nalgebra(eigen.eigenvalues.as_slice(), &vectors))
+    }
+
+    /// Singular value decomposition truncated to relative Frobenius tolerance `tolerance`
+    pub fn truncated_svd(&self, tolerance: f64, max_rank: Option<usize>) -> Result<TruncatedSvd> {
+        Ok(svd(self.rows, self.cols, &self.data)?.truncated(tolerance, max_rank))
+    }
+}
+
+impl ComplexMatrix {
+    /// LU factorization with partial pivoting
+    pub fn lu(&self) -> Result<DenseLU<Complex64>> {
+        if !self.is_square() {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols });
+        }
+        DenseLU::new(self.rows, &self.data)
+    }
+
+    /// Singular value decomposition truncated to relative Frobenius tolerance `tolerance`
+    pub fn truncated_svd(&self, tolerance: f64, max_rank: Option<usize>) -> Result<TruncatedSvd<Complex64>> {
+        Ok(svd(self.rows, self.cols, &self.data)?.truncated(tolerance, max_rank))
+    }
+}
+
+/// Singular value decomposition A ≈ Σₖ σₖ uₖ vₖᴴ of rank `rank()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncatedSvd<T = f64> {
+    /// Rows of A
+    pub rows: usize,
+    /// Columns of A
+    pub cols: usize,
+    /// Left singular vectors uₖ, of length `rows`
+    pub u: Vec<Vec<T>>,
+    /// Singular values σₖ, descending
+    pub singular_values: Vec<f64>,
+    /// Right singular vectors vₖ, of length `cols`
+    pub v: Vec<Vec<T>>,
+}
+
+impl<T: SparseScalar> TruncatedSvd<T> {
+    /// Number of singular triplets kept
+    pub fn rank(&self) -> usize {
+        self.singular_values.len()
+    }
+
+    /// Number of singular values above `tolerance` relative to the largest
+    pub fn numerical_rank(&self, tolerance: f64) -> usize {
+        let largest = self.singular_values.first().copied().unwrap_or(0.0);
+        self.singular_values.iter().take_while(|&&s| s > tolerance * largest).count()
+    }
+
+    /// Keep the smallest rank within relative Frobenius tolerance `tolerance`, at most `max_rank`
+    pub fn truncated(mut self, tolerance: f64, max_rank: Option<usize>) -> Self {
+        let rank = max_rank.map_or(usize::MAX, |cap| cap).min(truncation_rank(&self.singular_values, tolerance));
+        self.u.truncate(rank);
+        self.singular_values.truncate(rank);
+        self.v.truncate(rank);
+        self
+    }
+
+    /// The approximation Σₖ σₖ uₖ vₖᴴ in row-major order
+    pub fn to_row_major(&self) -> Vec<T> {
+        let mut data = vec![T::zero(); self.rows * self.cols];
+        for ((u, &s), v) in self.u.iter().zip(&self.singular_values).zip(&self.v) {
+            for (i, &ui) in u.iter().enumerate() {
+                let scaled = ui.scale(s);
+                for (j, &vj) in v.iter().enumerate() {
+                    data[i * self.cols + j] += scaled * vj.conjugate();
+                }
+            }
+        }
+        data
+    }
+}
+
+/// Smallest rank whose dropped singular values are within relative Frobenius tolerance `tolerance`
+pub(crate) fn truncation_rank(sigma: &[f64], tolerance: f64) -> usize {
+    let total: f64 = sigma.iter().map(|s| s * s).sum();
+    let mut rank = sigma.len();
+    let mut dropped = 0.0;
+    while rank > 0 && dropped + sigma[rank - 1].powi(2) <= tolerance.powi(2) * total {
+        dropped += sigma[rank - 1].powi(2);
+        rank -= 1;
+    }
+    rank
+}
+
+/// Full thin SVD of a row-major matrix, singular values descending
+pub(crate) fn svd<T: SparseScalar>(rows: usize, cols: usize, data: &[T]) -> Result<TruncatedSvd<T>> {
+    if data.len() != rows * cols {
+        return Err(MatrixError::DimensionMismatch { expected: rows * cols, actual: data.len() });
+    }
+    let svd = DMatrix::from_row_slice(rows, cols, data).svd(true, true);
+    let (u, v_t) = match (svd.u, svd.v_t) {
+        (Some(u), Some(v_t)) => (u, v_t),
+        _ => {
+            return Err(MatrixError::SolverError {
+                message: "SVD failed to converge".to_string(),
+            })
+        }
+    };
+    let mut order: Vec<usize> = (0..svd.singular_values.len()).collect();
+    order.sort_by(|&a, &b| svd.singular_values[b].total_cmp(&svd.singular_values[a]));
+    Ok(TruncatedSvd {
+        rows,
+        cols,
+        u: order.iter().map(|&k| u.column(k).iter().copied().collect()).collect(),
+        singular_values: order.iter().map(|&k| svd.singular_values[k]).collect(),
+        v: order.iter().map(|&k| v_t.row(k).iter().map(|x| x.conjugate()).collect()).collect(),
+    })
+}
+
+/// Square symmetric matrix, up to round-off relative to its largest entry
+fn symmetric(a: &Matrix) -> Result<DMatrix<f64>> {
+    if !a.is_square() {
+        return Err(MatrixError::InvalidDimensions { rows: a.rows, cols: a.cols });
+    }
+    let n = a.rows;
+    let scale = a.data.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    let asymmetric = (0..n).any(|i| (i + 1..n).any(|j| (a.data[i * n + j] - a.data[j * n + i]).abs() > 1e-10 * scale));
+    if asymmetric {
+        return Err(MatrixError::SolverError {
+            message: "Matrix is not symmetric".to_string(),
+        });
+    }
+    Ok(DMatrix::from_row_slice(n, n, &a.data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eigen_and_svd() {
+        // Spring chain: K tridiagonal, lumped masses
+        let n = 8;
+        let mut k = Matrix::new(n, n);
+        let mut m = Matrix::new(n, n);
+        for i in 0..n {
+            k.data[i * n + i] = 2.0;
+            m.data[i * n + i] = 1.0 + i as f64 * 0.5;
+            if i + 1 < n {
+                k.data[i * n + i + 1] = -1.0;
+                k.data[(i + 1) * n + i] = -1.0;
+            }
+        }
+        let eigen = k.symmetric_eigen().unwrap();
+        for (j, &lambda) in eigen.values.iter().enumerate() {
+            let exact = 2.0 - 2.0 * ((j + 1) as f64 * std::f64::consts::PI / (n + 1) as f64).cos();
+            assert!((lambda - exact).abs() < 1e-12);
+            let phi = eigen.vector(j);
+            let residual = solvers::matrix_vector_mult(&k, &phi).unwrap();
+            assert!(residual.iter().zip(&phi).all(|(kp, p)| (kp - lambda * p).abs() < 1e-12));
+        }
+
+        let modes = k.generalized_symmetric_eigen(&m).unwrap().truncated(3);
+        assert_eq!((modes.len(), modes.vectors.cols), (3, 3));
+        for j in 0..3 {
+            let phi = modes.vector(j);
+            let kp = solvers::matrix_vector_mult(&k, &phi).unwrap();
+            let mp = solvers::matrix_vector_mult(&m, &phi).unwrap();
+            assert!(kp.iter().zip(&mp).all(|(kp, mp)| (kp - modes.values[j] * mp).abs() < 1e-12));
+            assert!((phi.iter().zip(&mp).map(|(p, q)| p * q).sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        assert!(Matrix::from_vec(2, 2, vec![1.0, 2.0, 0.0, 1.0]).unwrap().symmetric_eigen().is_err());
+
+        // Snapshots of three travelling waves: rank three
+        let (rows, cols) = (40, 12);
+        let snapshots = ComplexMatrix::from_vec(rows, cols, (0..rows * cols).map(|k| {
+            let (x, t) = ((k / cols) as f64 * 0.1, (k % cols) as f64 * 0.3);
+            (1..=3).map(|w| Complex64::from_polar(1.0 / w as f64, w as f64 * (x - t))).sum()
+        }).collect()).unwrap();
+        let svd = snapshots.truncated_svd(1e-10, None).unwrap();
+        assert_eq!((svd.rank(), svd.numerical_rank(1e-12)), (3, 3));
+        assert!(svd.to_row_major().iter().zip(&snapshots.data).all(|(p, q)| (p - q).norm() < 1e-10));
+        assert!(svd.singular_values.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(k.truncated_svd(0.0, Some(2)).unwrap().rank(), 2);
+
+        let lu = k.lu().unwrap();
+        let b: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let x = lu.solve(&b).unwrap();
+        assert!(solvers::matrix_vector_mult(&k, &x).unwrap().iter().zip(&b).all(|(p, q)| (p - q).abs() < 1e-12));
+        let rebuilt = DenseLU::from_parts(n, lu.factors().to_vec(), lu.pivots().to_vec()).unwrap();
+        assert_eq!(rebuilt, lu);
+        assert!(DenseLU::from_parts(n, lu.factors().to_vec(), vec![n; n]).is_err());
+    }
+}
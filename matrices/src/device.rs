@@ -0,0 +1,88 @@
+//! Dense matrices in host or accelerator memory
+//!
+//! [`DeviceMatrix`] is the storage-independent face of a dense real matrix:
+//! explicit upload from and download to a host [`Matrix`], and products and
+//! solves that run where the entries live. [`Matrix`] implements it on the
+//! [`Host`], and `wavecore_gpu::GpuMatrix` on a GPU memory pool, so code
+//! written against the trait switches between CPU and GPU storage by its
+//! type parameter alone.
+
+use super::*;
+
+/// Dense real matrix stored on a device
+pub trait DeviceMatrix: Sized {
+    /// Context owning the storage, such as a GPU memory pool
+    type Device;
+
+    /// Copy a host matrix to the device
+    fn upload(device: &mut Self::Device, matrix: &Matrix) -> Result<Self>;
+
+    /// Copy the entries back to the host
+    fn download(&self, device: &Self::Device) -> Result<Matrix>;
+
+    /// Number of rows and columns
+    fn shape(&self) -> (usize, usize);
+
+    /// Product A B, stored on the same device
+    fn matmul(&self, device: &mut Self::Device, other: &Self) -> Result<Self>;
+
+    /// Product A x of a host vector
+    fn matvec(&self, device: &Self::Device, x: &[f64]) -> Result<Vec<f64>>;
+
+    /// Solve A x = b for a host right-hand side
+    fn solve(&self, device: &Self::Device, b: &[f64]) -> Result<Vec<f64>>;
+}
+
+/// Host memory, the device of [`Matrix`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Host;
+
+impl DeviceMatrix for Matrix {
+    type Device = Host;
+
+    fn upload(_device: &mut Host, matrix: &Matrix) -> Result<Self> {
+        Ok(matrix.clone())
+    }
+
+    fn download(&self, _device: &Host) -> Result<Matrix> {
+        Ok(self.clone())
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn matmul(&self, _device: &mut Host, other: &Self) -> Result<Self> {
+        backend::multiply(self, other)
+    }
+
+    fn matvec(&self, _device: &Host, x: &[f64]) -> Result<Vec<f64>> {
+        solvers::matrix_vector_mult(self, x)
+    }
+
+    fn solve(&self, _device: &Host, b: &[f64]) -> Result<Vec<f64>> {
+        solvers::lu_solve(self, b)
+    }
+}
+
+/// Solve A x = b with A uploaded to `device` as a `D`
+pub fn solve_on<D: DeviceMatrix>(device: &mut D::Device, a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
+    D::upload(device, a)?.solve(device, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_device_matrix() {
+        let a = Matrix::from_vec(2, 2, vec![2.0, 1.0, 1.0, 3.0]).unwrap();
+        let mut host = Host;
+        let stored = Matrix::upload(&mut host, &a).unwrap();
+        let squared = stored.matmul(&mut host, &stored).unwrap();
+        assert_eq!(squared.download(&host).unwrap().data, vec![5.0, 5.0, 5.0, 10.0]);
+        assert_eq!(stored.matvec(&host, &[1.0, 1.0]).unwrap(), vec![3.0, 4.0]);
+        let x = solve_on::<Matrix>(&mut host, &a, &[3.0, 4.0]).unwrap();
+        assert!(x.iter().all(|&xi| (xi - 1.0).abs() < 1e-12));
+    }
+}
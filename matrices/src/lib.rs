@@ -8,9 +8,26 @@
 //! ## Features
 //! 
 //! - **Matrix Operations**: Addition, multiplication, inversion, decomposition
-//! - **Linear Solvers**: LU decomposition, GMRES, iterative methods
-//! - **Block Matrices**: Efficient large matrix handling
-//! - **Parallel Processing**: Multi-threaded computations
+//! - **Linear Solvers**: LU decomposition, GMRES, iterative methods, multiple right-hand sides with one factorization
+//! - **Solve Diagnostics**: 1-norm condition estimates and `SolveReport` with residuals and warnings
+//! - **Linear Operators**: Matrix-free `LinearOperator` trait accepted by every Krylov solver
+//! - **Preconditioners**: Jacobi, block-Jacobi, ILU(0) and SPAI behind a `Preconditioner` trait
+//! - **Mixed Precision**: Single-precision LU with double-precision iterative refinement
+//! - **Complex Systems**: Complex matrices with direct and Krylov solvers in ℂ
+//! - **Krylov Recycling**: GCRO-DR carrying deflation spaces across the systems of a sweep
+//! - **Sparse Matrices**: CSR/CSC storage with sparse LU and Krylov solves
+//! - **Structured Matrices**: Banded storage with banded LU, block-diagonal storage solved block by block
+//! - **Least Squares**: Householder QR and `solve_least_squares` for overdetermined fits
+//! - **Decompositions**: Reusable dense LU factors, symmetric and generalized eigenproblems, truncated SVD
+//! - **Low-Rank Blocks**: Adaptive cross approximation (ACA/ACA+) with recompression
+//! - **Matrix Views**: Borrowed block, row and column views for in-place assembly
+//! - **Block Matrices**: Efficient large matrix handling, hierarchical matrices with H-LU
+//! - **Clustering**: Balanced cluster trees and octrees over panels with admissibility partitions and
+//!   interaction lists, shared by the H-matrix and fast multipole engines
+//! - **Device Matrices**: `DeviceMatrix` trait with upload/download, products and solves on host or GPU storage
+//! - **Dense Backend**: Optional system BLAS/LAPACK (`lapack` feature) for large products and factorizations
+//! - **Parallel Processing**: Multi-threaded products, blocked LU and matrix-vector products under `set_num_threads`,
+//!   with a deterministic mode giving bit-identical results on any thread count
 //! - **Memory Optimization**: Efficient data structures
 //! 
 //! ## Example
@@ -32,14 +49,47 @@
 pub mod operations;
 pub mod solvers;
 pub mod block;
+pub mod clustering;
 pub mod types;
+pub mod complex;
+pub mod backend;
+pub mod sparse;
+pub mod lowrank;
+pub mod parallel;
+pub mod mixed;
+pub mod operator;
+pub mod preconditioner;
+pub mod diagnostics;
+pub mod decomposition;
+pub mod recycling;
+pub mod structured;
+pub mod view;
+pub mod qr;
+pub mod device;
 
 pub use operations::*;
 pub use solvers::*;
 pub use block::*;
+pub use clustering::*;
 pub use types::*;
+pub use complex::*;
+pub use sparse::*;
+pub use lowrank::*;
+pub use parallel::*;
+pub use mixed::*;
+pub use operator::*;
+pub use preconditioner::*;
+pub use diagnostics::*;
+pub use decomposition::*;
+pub use recycling::*;
+pub use structured::*;
+pub use view::*;
+pub use qr::*;
+pub use device::*;
 
 use thiserror::Error;
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
 
 /// Error types for matrix operations
 #[derive(Error, Debug)]
@@ -67,7 +117,7 @@ pub enum MatrixError {
 pub type Result<T> = std::result::Result<T, MatrixError>;
 
 /// Matrix representation optimized for BEM computations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Matrix {
     /// Number of rows
     pub rows: usize,
@@ -152,7 +202,7 @@ impl Matrix {
 }
 
 /// Linear solver types
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SolverType {
     /// LU decomposition
     LU,
@@ -164,6 +214,8 @@ pub enum SolverType {
     ConjugateGradient,
     /// BiCGSTAB iterative solver (for general matrices)
     BiCGSTAB,
+    /// LU factorization in single precision refined to double-precision accuracy
+    MixedPrecisionLU,
 }
 
 /// Linear solver interface
@@ -171,25 +223,87 @@ pub trait LinearSolverTrait {
     /// Solve the linear system Ax = b
     fn solve(&self, a: &Matrix, b: &[f64]) -> Result<Vec<f64>>;
     
+    /// Solve A X = B for every column of B
+    ///
+    /// The default solves column by column; direct solvers override it to
+    /// factorize once for all columns.
+    fn solve_multi(&self, a: &Matrix, b: &Matrix) -> Result<Matrix> {
+        let solutions = columns(a, b)?.iter().map(|column| self.solve(a, column)).collect::<Result<Vec<_>>>()?;
+        Ok(from_columns(a.cols, b.cols, &solutions))
+    }
+    
     /// Get solver type
     fn solver_type(&self) -> SolverType;
 }
 
+/// Columns of the right-hand sides B of A X = B
+fn columns(a: &Matrix, b: &Matrix) -> Result<Vec<Vec<f64>>> {
+    if b.rows != a.rows {
+        return Err(MatrixError::DimensionMismatch { expected: a.rows, actual: b.rows });
+    }
+    Ok((0..b.cols).map(|j| (0..b.rows).map(|i| b.data[i * b.cols + j]).collect()).collect())
+}
+
+/// Matrix of `cols` solution columns of length `rows`
+fn from_columns(rows: usize, cols: usize, columns: &[Vec<f64>]) -> Matrix {
+    Matrix {
+        rows,
+        cols,
+        data: (0..rows * cols).map(|k| columns[k % cols][k / cols]).collect(),
+    }
+}
+
 /// Linear solver implementation
 pub struct LinearSolver {
     solver_type: SolverType,
+    preconditioner: Option<SharedPreconditioner>,
+    complex_preconditioner: Option<SharedPreconditioner<num_complex::Complex64>>,
 }
 
 impl LinearSolver {
     /// Create a new linear solver
     pub fn new(solver_type: SolverType) -> Self {
-        Self { solver_type }
+        Self { solver_type, preconditioner: None, complex_preconditioner: None }
+    }
+    
+    /// Precondition the real iterative solves that take no explicit preconditioner
+    pub fn with_preconditioner<P>(mut self, preconditioner: P) -> Self
+    where
+        P: Preconditioner + Send + Sync + 'static,
+    {
+        self.preconditioner = Some(Arc::new(preconditioner));
+        self
+    }
+    
+    /// Precondition the complex iterative solves that take no explicit preconditioner
+    pub fn with_complex_preconditioner<P>(mut self, preconditioner: P) -> Self
+    where
+        P: Preconditioner<num_complex::Complex64> + Send + Sync + 'static,
+    {
+        self.complex_preconditioner = Some(Arc::new(preconditioner));
+        self
     }
     
     /// Get solver type
     pub fn solver_type(&self) -> SolverType {
         self.solver_type
     }
+    
+    /// Attached real preconditioner M⁻¹ x, the identity without one
+    pub(crate) fn precondition(&self, x: &[f64]) -> Result<Vec<f64>> {
+        match &self.preconditioner {
+            Some(preconditioner) => preconditioner.apply(x),
+            None => Ok(x.to_vec()),
+        }
+    }
+    
+    /// Attached complex preconditioner M⁻¹ x, the identity without one
+    pub(crate) fn precondition_complex(&self, x: &[num_complex::Complex64]) -> Result<Vec<num_complex::Complex64>> {
+        match &self.complex_preconditioner {
+            Some(preconditioner) => preconditioner.apply(x),
+            None => Ok(x.to_vec()),
+        }
+    }
 }
 
 impl LinearSolverTrait for LinearSolver {
@@ -197,17 +311,131 @@ impl LinearSolverTrait for LinearSolver {
         match self.solver_type {
             SolverType::LU => solvers::lu_solve(a, b),
             SolverType::Cholesky => solvers::cholesky_solve(a, b),
+            SolverType::GMRES | SolverType::BiCGSTAB if self.preconditioner.is_some() => {
+                let control = solvers::IterativeControl { tolerance: 1e-10, max_iterations: 1000, ..Default::default() };
+                let method = if matches!(self.solver_type, SolverType::GMRES) { "GMRES" } else { "BiCGSTAB" };
+                self.solve_with_control(a, b, &control)?.into_converged(method, control.max_iterations)
+            }
             SolverType::GMRES => solvers::gmres_solve(a, b),
             SolverType::ConjugateGradient => solvers::cg_solve(a, b),
             SolverType::BiCGSTAB => solvers::bicgstab_solve(a, b),
+            SolverType::MixedPrecisionLU => Ok(mixed::mixed_precision_lu_solve(a, b)?.solution),
         }
     }
     
+    fn solve_multi(&self, a: &Matrix, b: &Matrix) -> Result<Matrix> {
+        let rhs = columns(a, b)?;
+        let solutions = match self.solver_type {
+            SolverType::LU => backend::lu_solve_multi(a, &rhs)?,
+            SolverType::Cholesky => backend::cholesky_solve_multi(a, &rhs)?,
+            SolverType::MixedPrecisionLU => mixed::mixed_precision_lu_solve_multi(a, &rhs)?
+                .into_iter()
+                .map(|outcome| outcome.solution)
+                .collect(),
+            SolverType::GMRES | SolverType::ConjugateGradient | SolverType::BiCGSTAB => {
+                rhs.iter().map(|column| self.solve(a, column)).collect::<Result<_>>()?
+            }
+        };
+        Ok(from_columns(a.cols, b.cols, &solutions))
+    }
+    
     fn solver_type(&self) -> SolverType {
         self.solver_type
     }
 }
 
+impl LinearSolver {
+    /// Solve with explicit stopping criteria
+    ///
+    /// Iterative solvers return their best iterate when the deadline or
+    /// iteration limit is reached; direct solvers ignore the control.
+    /// GMRES and BiCGSTAB use the attached preconditioner, if any.
+    pub fn solve_with_control(&self, a: &Matrix, b: &[f64], control: &solvers::IterativeControl) -> Result<solvers::IterativeOutcome> {
+        match self.solver_type {
+            SolverType::GMRES | SolverType::BiCGSTAB if self.preconditioner.is_some() => {
+                self.solve_preconditioned(a, b, |x| self.precondition(x), control)
+            }
+            SolverType::GMRES => solvers::gmres_iterate(a, b, control),
+            SolverType::ConjugateGradient => solvers::cg_iterate(a, b, control),
+            SolverType::BiCGSTAB => solvers::bicgstab_iterate(a, b, control),
+            SolverType::MixedPrecisionLU => mixed::mixed_precision_lu_solve(a, b),
+            SolverType::LU | SolverType::Cholesky => {
+                let solution = self.solve(a, b)?;
+                let residual = a.data
+                    .chunks(a.cols)
+                    .zip(b)
+                    .map(|(row, bi)| {
+                        let ax: f64 = row.iter().zip(&solution).map(|(aij, xj)| aij * xj).sum();
+                        (bi - ax).powi(2)
+                    })
+                    .sum::<f64>()
+                    .sqrt();
+                Ok(solvers::IterativeOutcome {
+                    solution,
+                    iterations: 0,
+                    residual,
+                    converged: true,
+                    timed_out: false,
+                    history: vec![residual],
+                })
+            }
+        }
+    }
+    
+    /// Solve with a right preconditioner `precondition(x) = M⁻¹ x`
+    ///
+    /// GMRES and BiCGSTAB use the preconditioner; conjugate gradients and the
+    /// direct solvers fall back to `solve_with_control`.
+    pub fn solve_preconditioned<P>(
+        &self,
+        a: &Matrix,
+        b: &[f64],
+        precondition: P,
+        control: &solvers::IterativeControl,
+    ) -> Result<solvers::IterativeOutcome>
+    where
+        P: Fn(&[f64]) -> Result<Vec<f64>>,
+    {
+        if a.rows != b.len() || !a.is_square() {
+            return Err(MatrixError::DimensionMismatch { expected: a.rows, actual: b.len() });
+        }
+        match self.solver_type {
+            SolverType::GMRES | SolverType::BiCGSTAB => self.solve_operator(a, b, precondition, control),
+            _ => self.solve_with_control(a, b, control),
+        }
+    }
+    
+    /// Solve with any [`LinearOperator`] and a right preconditioner `precondition(x) = M⁻¹ x`
+    ///
+    /// Conjugate gradients ignores the preconditioner. Direct solvers need
+    /// the matrix entries and return an error.
+    pub fn solve_operator<A, P>(
+        &self,
+        a: &A,
+        b: &[f64],
+        precondition: P,
+        control: &solvers::IterativeControl,
+    ) -> Result<solvers::IterativeOutcome>
+    where
+        A: LinearOperator + ?Sized,
+        P: Fn(&[f64]) -> Result<Vec<f64>>,
+    {
+        match self.solver_type {
+            SolverType::GMRES => solvers::gmres_iterate_preconditioned(a, precondition, b, control),
+            SolverType::BiCGSTAB => solvers::bicgstab_iterate_preconditioned(a, precondition, b, control),
+            SolverType::ConjugateGradient => solvers::cg_iterate_with(a, b, control),
+            solver_type => Err(direct_on_operator(solver_type)),
+        }
+    }
+}
+
+/// Error for a direct solver given only the action of an operator
+pub(crate) fn direct_on_operator(solver_type: SolverType) -> MatrixError {
+    MatrixError::SolverError {
+        message: format!("{:?} needs the matrix entries and cannot solve a matrix-free operator", solver_type),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,4 +469,64 @@ mod tests {
         matrix.set(1, 0, 5.0).unwrap();
         assert!(matrix.is_symmetric());
     }
-} 
\ No newline at end of file
+    
+    #[test]
+    fn test_iterative_deadline_returns_partial_result() {
+        let matrix = Matrix::from_vec(2, 2, vec![4.0, 1.0, 1.0, 3.0]).unwrap();
+        let b = vec![1.0, 2.0];
+        let solver = LinearSolver::new(SolverType::GMRES);
+        
+        let expired = IterativeControl {
+            deadline: Some(std::time::Instant::now()),
+            ..Default::default()
+        };
+        let outcome = solver.solve_with_control(&matrix, &b, &expired).unwrap();
+        assert!(outcome.timed_out);
+        assert!(!outcome.converged);
+        assert_eq!(outcome.solution.len(), 2);
+        
+        let outcome = solver.solve_with_control(&matrix, &b, &IterativeControl::default()).unwrap();
+        assert!(outcome.converged);
+        assert!(outcome.residual < 1e-10);
+    }
+    
+    #[test]
+    fn test_preconditioned_solvers_match_lu() {
+        let matrix = Matrix::from_vec(3, 3, vec![10.0, 1.0, 0.5, 2.0, 8.0, 1.0, 0.5, 1.0, 5.0]).unwrap();
+        let b = vec![1.0, -2.0, 3.0];
+        let exact = LinearSolver::new(SolverType::LU).solve(&matrix, &b).unwrap();
+        
+        // Jacobi preconditioner
+        let jacobi = |x: &[f64]| -> Result<Vec<f64>> {
+            Ok(x.iter().enumerate().map(|(i, xi)| xi / matrix.get(i, i).unwrap()).collect())
+        };
+        for solver_type in [SolverType::GMRES, SolverType::BiCGSTAB] {
+            let outcome = LinearSolver::new(solver_type)
+                .solve_preconditioned(&matrix, &b, jacobi, &IterativeControl::default())
+                .unwrap();
+            assert!(outcome.converged);
+            assert!(outcome.history.len() >= 2);
+            assert!(outcome.history.last().unwrap() < &outcome.history[0]);
+            for (x, e) in outcome.solution.iter().zip(&exact) {
+                assert!((x - e).abs() < 1e-8);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_solve_multi_matches_column_solves() {
+        let matrix = Matrix::from_vec(3, 3, vec![10.0, 1.0, 0.5, 1.0, 8.0, 1.0, 0.5, 1.0, 5.0]).unwrap();
+        let b = Matrix::from_vec(3, 4, (0..12).map(|k| (k as f64 * 0.7).sin()).collect()).unwrap();
+        for solver_type in [SolverType::LU, SolverType::Cholesky, SolverType::MixedPrecisionLU, SolverType::GMRES, SolverType::ConjugateGradient] {
+            let solver = LinearSolver::new(solver_type);
+            let x = solver.solve_multi(&matrix, &b).unwrap();
+            assert_eq!(x.dimensions(), (3, 4));
+            for j in 0..4 {
+                let column: Vec<f64> = (0..3).map(|i| b.get(i, j).unwrap()).collect();
+                let expected = solver.solve(&matrix, &column).unwrap();
+                assert!((0..3).all(|i| (x.get(i, j).unwrap() - expected[i]).abs() < 1e-8));
+            }
+        }
+        assert!(LinearSolver::new(SolverType::LU).solve_multi(&matrix, &Matrix::new(2, 4)).is_err());
+    }
+}
@@ -0,0 +1,481 @@
+//! Low-rank blocks by adaptive cross approximation
+//!
+//! Interactions between well-separated panel clusters are smooth, so their
+//! influence block is approximated by U V with few columns in U. Adaptive
+//! cross approximation (ACA) builds the factors from a few rows and columns
+//! of the block without forming it; ACA+ chooses the pivots from a reference
+//! row and column so blocks with zero leading rows are still found. Sums of
+//! blocks are recompressed by [`LowRankBlock::truncate`].
+
+use super::*;
+use nalgebra::DMatrix;
+use num_complex::Complex64;
+
+/// Pivot search of the cross approximation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AcaPivoting {
+    /// Partial pivoting: the next row is the largest entry of the last column
+    Partial,
+    /// ACA+: pivots chosen from a reference row and column of the residual
+    Plus,
+}
+
+/// Stopping criteria of the cross approximation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcaControl {
+    /// Relative Frobenius-norm tolerance of the approximation
+    pub tolerance: f64,
+    /// Rank cap
+    pub max_rank: Option<usize>,
+    /// Pivot search
+    pub pivoting: AcaPivoting,
+}
+
+impl Default for AcaControl {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-6,
+            max_rank: None,
+            pivoting: AcaPivoting::Plus,
+        }
+    }
+}
+
+/// Low-rank factorization A ≈ U V of a matrix block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LowRankBlock {
+    /// Left factor, rows × rank
+    pub u: ComplexMatrix,
+    /// Right factor, rank × cols
+    pub v: ComplexMatrix,
+}
+
+impl LowRankBlock {
+    /// Create from factors of matching rank
+    pub fn new(u: ComplexMatrix, v: ComplexMatrix) -> Result<Self> {
+        if u.cols != v.rows {
+            return Err(MatrixError::DimensionMismatch {
+                expected: u.cols,
+                actual: v.rows,
+            });
+        }
+        Ok(Self { u, v })
+    }
+
+    /// Rank-zero block
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            u: ComplexMatrix::new(rows, 0),
+            v: ComplexMatrix::new(0, cols),
+        }
+    }
+
+    /// Approximate the `rows` × `cols` block with entries `entry(i, j)` by ACA
+    ///
+    /// Only the rows and columns chosen as pivots are evaluated, about
+    /// (rows + cols) × rank entries in total.
+    pub fn aca<F>(rows: usize, cols: usize, entry: F, control: &AcaControl) -> Result<Self>
+    where
+        F: Fn(usize, usize) -> Result<Complex64>,
+    {
+        let row = |i: usize| (0..cols).map(|j| entry(i, j)).collect();
+        let col = |j: usize| (0..rows).map(|i| entry(i, j)).collect();
+        Self::aca_with(rows, cols, row, col, control)
+    }
+
+    /// ACA with whole rows `row(i)` and columns `col(j)` of the block evaluated at once
+    pub fn aca_with<R, C>(rows: usize, cols: usize, row: R, col: C, control: &AcaControl) -> Result<Self>
+    where
+        R: Fn(usize) -> Result<Vec<Complex64>>,
+        C: Fn(usize) -> Result<Vec<Complex64>>,
+    {
+        let mut cross = Cross::new(rows, cols, row, col, control);
+        match control.pivoting {
+            AcaPivoting::Partial => cross.partial()?,
+            AcaPivoting::Plus => cross.plus()?,
+        }
+        Ok(cross.into_block())
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.u.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.v.cols
+    }
+
+    /// Rank of the factorization
+    pub fn rank(&self) -> usize {
+        self.u.cols
+    }
+
+    /// Stored entries, (rows + cols) × rank
+    pub fn storage(&self) -> usize {
+        self.u.data.len() + self.v.data.len()
+    }
+
+    /// Dense product U V
+    pub fn to_dense(&self) -> ComplexMatrix {
+        self.u.multiply(&self.v).unwrap()
+    }
+
+    /// Matrix-vector product U (V x)
+    pub fn matvec(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        self.u.matvec(&self.v.matvec(x)?)
+    }
+
+    /// Exact sum, of rank equal to the sum of the ranks
+    pub fn add(&self, other: &LowRankBlock) -> Result<LowRankBlock> {
+        if (self.rows(), self.cols()) != (other.rows(), other.cols()) {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.rows() * self.cols(),
+                actual: other.rows() * other.cols(),
+            });
+        }
+        let rank = self.rank() + other.rank();
+        let mut u = ComplexMatrix::new(self.rows(), rank);
+        for i in 0..self.rows() {
+            let row = &mut u.data[i * rank..(i + 1) * rank];
+            row[..self.rank()].copy_from_slice(&self.u.data[i * self.rank()..(i + 1) * self.rank()]);
+            row[self.rank()..].copy_from_slice(&other.u.data[i * other.rank()..(i + 1) * other.rank()]);
+        }
+        let v = ComplexMatrix::from_vec(rank, self.cols(), [self.v.data.as_slice(), &other.v.data].concat())?;
+        Ok(LowRankBlock { u, v })
+    }
+
+    /// Singular value decomposition of the block, of rank at most `rank()`
+    ///
+    /// QR factorizations of U and Vᴴ reduce the problem to an SVD of the
+    /// rank × rank core, so the block is never formed.
+    pub fn svd(&self) -> Result<TruncatedSvd<Complex64>> {
+        let (m, n, k) = (self.rows(), self.cols(), self.rank());
+        if k == 0 {
+            return Ok(TruncatedSvd { rows: m, cols: n, u: Vec::new(), singular_values: Vec::new(), v: Vec::new() });
+        }
+        let qr_u = DMatrix::from_fn(m, k, |i, j| self.u.data[i * k + j]).qr();
+        let qr_v = DMatrix::from_fn(n, k, |i, j| self.v.data[j * n + i].conj()).qr();
+        let (q_u, q_v) = (qr_u.q(), qr_v.q());
+        // Row-major storage of the core is the column-major storage of its transpose
+        let core = (qr_u.r() * qr_v.r().adjoint()).transpose();
+        let core = decomposition::svd(core.ncols(), core.nrows(), core.as_slice())?;
+        let expand = |q: &DMatrix<Complex64>, x: &[Complex64]| (0..q.nrows()).map(|i| x.iter().enumerate().map(|(j, &xj)| q[(i, j)] * xj).sum()).collect();
+        Ok(TruncatedSvd {
+            rows: m,
+            cols: n,
+            u: core.u.iter().map(|w| expand(&q_u, w)).collect(),
+            singular_values: core.singular_values,
+            v: core.v.iter().map(|z| expand(&q_v, z)).collect(),
+        })
+    }
+
+    /// Recompress to the smallest rank within relative Frobenius tolerance `tolerance`
+    ///
+    /// The dropped singular values of [`LowRankBlock::svd`] satisfy
+    /// √(Σ σ²_dropped) ≤ tolerance · √(Σ σ²).
+    pub fn truncate(&self, tolerance: f64, max_rank: Option<usize>) -> Result<LowRankBlock> {
+        if self.rank() == 0 {
+            return Ok(self.clone());
+        }
+        let svd = self.svd()?.truncated(tolerance, max_rank);
+        let (m, n, rank) = (svd.rows, svd.cols, svd.rank());
+        let mut u = ComplexMatrix::new(m, rank);
+        let mut v = ComplexMatrix::new(rank, n);
+        for (l, ((ul, &s), vl)) in svd.u.iter().zip(&svd.singular_values).zip(&svd.v).enumerate() {
+            for (i, &uil) in ul.iter().enumerate() {
+                u.data[i * rank + l] = uil * s;
+            }
+            for (vlj, &value) in v.data[l * n..(l + 1) * n].iter_mut().zip(vl) {
+                *vlj = value.conj();
+            }
+        }
+        Ok(LowRankBlock { u, v })
+    }
+}
+
+/// Cross approximation in progress: columns u_l and rows v_l of the rank-one terms
+struct Cross<'a, R, C> {
+    rows: usize,
+    cols: usize,
+    row: R,
+    col: C,
+    control: &'a AcaControl,
+    u: Vec<Vec<Complex64>>,
+    v: Vec<Vec<Complex64>>,
+    used_rows: Vec<bool>,
+    used_cols: Vec<bool>,
+    /// Squared Frobenius norm of the approximation
+    norm_sq: f64,
+}
+
+impl<'a, R, C> Cross<'a, R, C>
+where
+    R: Fn(usize) -> Result<Vec<Complex64>>,
+    C: Fn(usize) -> Result<Vec<Complex64>>,
+{
+    fn new(rows: usize, cols: usize, row: R, col: C, control: &'a AcaControl) -> Self {
+        Self {
+            rows,
+            cols,
+            row,
+            col,
+            control,
+            u: Vec::new(),
+            v: Vec::new(),
+            used_rows: vec![false; rows],
+            used_cols: vec![false; cols],
+            norm_sq: 0.0,
+        }
+    }
+
+    fn max_rank(&self) -> usize {
+        self.control.max_rank.unwrap_or(usize::MAX).min(self.rows.min(self.cols))
+    }
+
+    /// Row `i` of the residual A - U V
+    fn residual_row(&self, i: usize) -> Result<Vec<Complex64>> {
+        let mut row = (self.row)(i)?;
+        if row.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch { expected: self.cols, actual: row.len() });
+        }
+        for (u, v) in self.u.iter().zip(&self.v) {
+            row.iter_mut().zip(v).for_each(|(r, vj)| *r -= u[i] * vj);
+        }
+        Ok(row)
+    }
+
+    /// Column `j` of the residual A - U V
+    fn residual_col(&self, j: usize) -> Result<Vec<Complex64>> {
+        let mut col = (self.col)(j)?;
+        if col.len() != self.rows {
+            return Err(MatrixError::DimensionMismatch { expected: self.rows, actual: col.len() });
+        }
+        for (u, v) in self.u.iter().zip(&self.v) {
+            col.iter_mut().zip(u).for_each(|(c, ui)| *c -= ui * v[j]);
+        }
+        Ok(col)
+    }
+
+    /// Add the rank-one term u v and report whether it is below the tolerance
+    fn push(&mut self, u: Vec<Complex64>, v: Vec<Complex64>) -> bool {
+        let dot = |a: &[Complex64], b: &[Complex64]| a.iter().zip(b).map(|(x, y)| x.conj() * y).sum::<Complex64>();
+        let (u_sq, v_sq) = (dot(&u, &u).re, dot(&v, &v).re);
+        let cross: f64 = self.u.iter().zip(&self.v).map(|(ul, vl)| (dot(ul, &u) * dot(vl, &v)).re).sum();
+        self.norm_sq += 2.0 * cross + u_sq * v_sq;
+        self.u.push(u);
+        self.v.push(v);
+        (u_sq * v_sq).sqrt() <= self.control.tolerance * self.norm_sq.sqrt()
+    }
+
+    /// Partial pivoting, starting from the first row
+    fn partial(&mut self) -> Result<()> {
+        let mut i = 0;
+        while self.u.len() < self.max_rank() {
+            self.used_rows[i] = true;
+            let row = self.residual_row(i)?;
+            match argmax(&row, &self.used_cols) {
+                Some((j, pivot)) if pivot.norm() > 0.0 => {
+                    self.used_cols[j] = true;
+                    let v = row.iter().map(|r| r / pivot).collect();
+                    let u = self.residual_col(j)?;
+                    let next = argmax(&u, &self.used_rows);
+                    if self.push(u, v) {
+                        return Ok(());
+                    }
+                    match next {
+                        Some((next, _)) => i = next,
+                        None => return Ok(()),
+                    }
+                }
+                // Zero residual row: try the next unused row
+                _ => match self.used_rows.iter().position(|used| !used) {
+                    Some(next) => i = next,
+                    None => return Ok(()),
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// ACA+ with a reference row and column of the residual
+    fn plus(&mut self) -> Result<()> {
+        if self.rows == 0 || self.cols == 0 {
+            return Ok(());
+        }
+        let (mut j_ref, mut col_ref) = (0, self.residual_col(0)?);
+        let mut i_ref = argmin(&col_ref, &self.used_rows).unwrap_or(0);
+        let mut row_ref = self.residual_row(i_ref)?;
+
+        while self.u.len() < self.max_rank() {
+            // References with a zero residual carry no information; move on to nonzero ones
+            if is_exhausted(&col_ref, &self.used_rows) {
+                let Some((j, col)) = self.reference(j_ref, false)? else { return Ok(()) };
+                (j_ref, col_ref) = (j, col);
+            }
+            if is_exhausted(&row_ref, &self.used_cols) {
+                let Some((i, row)) = self.reference(i_ref, true)? else { return Ok(()) };
+                (i_ref, row_ref) = (i, row);
+            }
+
+            let (Some((i, a)), Some((j, b))) = (argmax(&col_ref, &self.used_rows), argmax(&row_ref, &self.used_cols)) else {
+                return Ok(());
+            };
+            let (row, col, i, j) = if b.norm() > a.norm() {
+                let col = self.residual_col(j)?;
+                let Some((i, _)) = argmax(&col, &self.used_rows) else { return Ok(()) };
+                (self.residual_row(i)?, col, i, j)
+            } else {
+                let row = self.residual_row(i)?;
+                let Some((j, _)) = argmax(&row, &self.used_cols) else { return Ok(()) };
+                (row, self.residual_col(j)?, i, j)
+            };
+            let pivot = row[j];
+            if pivot.norm() == 0.0 {
+                return Ok(());
+            }
+            self.used_rows[i] = true;
+            self.used_cols[j] = true;
+
+            let v: Vec<Complex64> = row.iter().map(|r| r / pivot).collect();
+            for (c, ui) in col_ref.iter_mut().zip(&col) {
+                *c -= ui * v[j_ref];
+            }
+            for (r, vj) in row_ref.iter_mut().zip(&v) {
+                *r -= col[i_ref] * vj;
+            }
+            if self.push(col, v) {
+                return Ok(());
+            }
+
+            // Replace references that became pivots
+            if i == i_ref {
+                let Some(next) = argmin(&col_ref, &self.used_rows) else { return Ok(()) };
+                i_ref = next;
+                row_ref = self.residual_row(i_ref)?;
+            }
+            if j == j_ref {
+                let Some(next) = argmin(&row_ref, &self.used_cols) else { return Ok(()) };
+                j_ref = next;
+                col_ref = self.residual_col(j_ref)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// First unused row (`by_row`) or column from `start` on with a nonzero residual
+    ///
+    /// Rows and columns found to be zero are marked used, since no pivot can come from them.
+    fn reference(&mut self, start: usize, by_row: bool) -> Result<Option<(usize, Vec<Complex64>)>> {
+        let n = if by_row { self.rows } else { self.cols };
+        for k in (0..n).map(|offset| (start + offset) % n) {
+            if (by_row && self.used_rows[k]) || (!by_row && self.used_cols[k]) {
+                continue;
+            }
+            let (values, other_used) = if by_row {
+                (self.residual_row(k)?, &self.used_cols)
+            } else {
+                (self.residual_col(k)?, &self.used_rows)
+            };
+            if !is_exhausted(&values, other_used) {
+                return Ok(Some((k, values)));
+            }
+            if by_row {
+                self.used_rows[k] = true;
+            } else {
+                self.used_cols[k] = true;
+            }
+        }
+        Ok(None)
+    }
+
+    fn into_block(self) -> LowRankBlock {
+        let rank = self.u.len();
+        let u = (0..self.rows).flat_map(|i| self.u.iter().map(move |u| u[i])).collect();
+        LowRankBlock {
+            u: ComplexMatrix { rows: self.rows, cols: rank, data: u },
+            v: ComplexMatrix { rows: rank, cols: self.cols, data: self.v.concat() },
+        }
+    }
+}
+
+/// Largest entry by modulus among the unused indices
+fn argmax(values: &[Complex64], used: &[bool]) -> Option<(usize, Complex64)> {
+    values
+        .iter()
+        .zip(used)
+        .enumerate()
+        .filter(|(_, (_, used))| !**used)
+        .max_by(|a, b| a.1 .0.norm().total_cmp(&b.1 .0.norm()))
+        .map(|(k, (value, _))| (k, *value))
+}
+
+/// Whether every unused entry is zero
+fn is_exhausted(values: &[Complex64], used: &[bool]) -> bool {
+    argmax(values, used).is_none_or(|(_, value)| value.norm() == 0.0)
+}
+
+/// Index of the smallest entry by modulus among the unused indices
+fn argmin(values: &[Complex64], used: &[bool]) -> Option<usize> {
+    values
+        .iter()
+        .zip(used)
+        .enumerate()
+        .filter(|(_, (_, used))| !**used)
+        .min_by(|a, b| a.1 .0.norm().total_cmp(&b.1 .0.norm()))
+        .map(|(k, _)| k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aca_approximates_separated_clusters() {
+        // Helmholtz kernel between two well-separated point clusters
+        let source: Vec<[f64; 3]> = (0..30).map(|i| [0.1 * (i % 5) as f64, 0.1 * (i / 5) as f64, -0.5]).collect();
+        let target: Vec<[f64; 3]> = (0..40).map(|i| [5.0 + 0.1 * (i % 8) as f64, 0.1 * (i / 8) as f64, -0.3]).collect();
+        let kernel = |i: usize, j: usize| -> Result<Complex64> {
+            let r = (0..3).map(|d| (target[i][d] - source[j][d]).powi(2)).sum::<f64>().sqrt();
+            Ok(Complex64::new(0.0, 0.8 * r).exp() / r)
+        };
+        let dense = ComplexMatrix::from_vec(40, 30, (0..40).flat_map(|i| (0..30).map(move |j| (i, j))).map(|(i, j)| kernel(i, j).unwrap()).collect()).unwrap();
+        let frobenius = |m: &ComplexMatrix| m.data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        let error = |block: &LowRankBlock| frobenius(&block.to_dense().add(&dense.scale(Complex64::new(-1.0, 0.0))).unwrap()) / frobenius(&dense);
+
+        for pivoting in [AcaPivoting::Partial, AcaPivoting::Plus] {
+            let control = AcaControl { pivoting, ..Default::default() };
+            let block = LowRankBlock::aca(40, 30, kernel, &control).unwrap();
+            assert!(block.rank() < 15 && block.storage() < dense.data.len());
+            assert!(error(&block) < 1e-5);
+
+            let x: Vec<Complex64> = (0..30).map(|j| Complex64::new(j as f64, 1.0)).collect();
+            let exact = dense.matvec(&x).unwrap();
+            let approximate = block.matvec(&x).unwrap();
+            assert!(exact.iter().zip(&approximate).all(|(e, a)| (e - a).norm() < 1e-4 * e.norm().max(1.0)));
+
+            // The doubled block recompresses to the original rank
+            let doubled = block.add(&block).unwrap();
+            assert_eq!(doubled.rank(), 2 * block.rank());
+            let truncated = doubled.truncate(1e-10, None).unwrap();
+            assert!(truncated.rank() <= block.rank());
+            assert!(frobenius(&truncated.to_dense().add(&block.to_dense().scale(Complex64::new(-2.0, 0.0))).unwrap()) < 1e-8 * frobenius(&dense));
+            assert_eq!(doubled.truncate(1e-10, Some(2)).unwrap().rank(), 2);
+        }
+
+        let capped = LowRankBlock::aca(40, 30, kernel, &AcaControl { max_rank: Some(3), ..Default::default() }).unwrap();
+        assert_eq!(capped.rank(), 3);
+
+        // Zero leading rows and an all-zero block
+        let sparse = |i: usize, j: usize| Ok(if i >= 20 && j == 4 { Complex64::new(1.0, 0.0) } else { Complex64::new(0.0, 0.0) });
+        for pivoting in [AcaPivoting::Partial, AcaPivoting::Plus] {
+            let block = LowRankBlock::aca(30, 10, sparse, &AcaControl { pivoting, ..Default::default() }).unwrap();
+            assert_eq!(block.rank(), 1);
+            assert_eq!(block.to_dense().get(25, 4).unwrap(), Complex64::new(1.0, 0.0));
+        }
+        let zero = LowRankBlock::aca(5, 5, |_, _| Ok(Complex64::new(0.0, 0.0)), &AcaControl::default()).unwrap();
+        assert_eq!(zero.rank(), 0);
+        assert_eq!(zero, LowRankBlock::zeros(5, 5));
+    }
+}
@@ -32,26 +32,7 @@ impl MatrixOperations for Matrix {
     }
     
     fn multiply(&self, other: &Matrix) -> Result<Matrix> {
-        if self.cols != other.rows {
-            return Err(MatrixError::DimensionMismatch {
-                expected: self.cols,
-                actual: other.rows,
-            });
-        }
-        
-        let mut result = Matrix::new(self.rows, other.cols);
-        
-        for i in 0..self.rows {
-            for j in 0..other.cols {
-                let mut sum = 0.0;
-                for k in 0..self.cols {
-                    sum += self.get(i, k).unwrap() * other.get(k, j).unwrap();
-                }
-                result.set(i, j, sum).unwrap();
-            }
-        }
-        
-        Ok(result)
+        backend::multiply(self, other)
     }
     
     fn transpose(&self) -> Matrix {
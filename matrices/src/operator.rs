@@ -0,0 +1,189 @@
+//! Matrix-free linear operators
+//!
+//! The Krylov solvers see a system only through [`LinearOperator::apply`], so
+//! dense, sparse, hierarchical and accelerator-resident matrices, and
+//! operators that are never stored at all such as fast multipole expansions,
+//! share the same GMRES, BiCGSTAB and conjugate gradient code.
+
+use super::*;
+use crate::sparse::SparseScalar;
+use num_complex::Complex64;
+
+/// Linear map y = A x on vectors of `T`
+pub trait LinearOperator<T = f64> {
+    /// Length of y
+    fn rows(&self) -> usize;
+
+    /// Length of x
+    fn cols(&self) -> usize;
+
+    /// Product A x
+    fn apply(&self, x: &[T]) -> Result<Vec<T>>;
+}
+
+/// Operator given by a closure `apply(x) = A x`
+pub struct FnOperator<F> {
+    rows: usize,
+    cols: usize,
+    apply: F,
+}
+
+impl<F> FnOperator<F> {
+    /// Wrap `apply` as a `rows × cols` operator
+    pub fn new(rows: usize, cols: usize, apply: F) -> Self {
+        Self { rows, cols, apply }
+    }
+
+    /// Wrap `apply` as a square operator of size `n`
+    pub fn square(n: usize, apply: F) -> Self {
+        Self::new(n, n, apply)
+    }
+}
+
+impl<T, F> LinearOperator<T> for FnOperator<F>
+where
+    F: Fn(&[T]) -> Result<Vec<T>>,
+{
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        if x.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch { expected: self.cols, actual: x.len() });
+        }
+        (self.apply)(x)
+    }
+}
+
+impl LinearOperator for Matrix {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[f64]) -> Result<Vec<f64>> {
+        solvers::matrix_vector_mult(self, x)
+    }
+}
+
+impl LinearOperator<Complex64> for ComplexMatrix {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        self.matvec(x)
+    }
+}
+
+impl<T: SparseScalar> LinearOperator<T> for CsrMatrix<T> {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.matvec(x)
+    }
+}
+
+impl<T: SparseScalar> LinearOperator<T> for CscMatrix<T> {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.matvec(x)
+    }
+}
+
+impl LinearOperator<Complex64> for LowRankBlock {
+    fn rows(&self) -> usize {
+        LowRankBlock::rows(self)
+    }
+
+    fn cols(&self) -> usize {
+        LowRankBlock::cols(self)
+    }
+
+    fn apply(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        self.matvec(x)
+    }
+}
+
+impl LinearOperator<Complex64> for HMatrix {
+    fn rows(&self) -> usize {
+        self.size()
+    }
+
+    fn cols(&self) -> usize {
+        self.size()
+    }
+
+    fn apply(&self, x: &[Complex64]) -> Result<Vec<Complex64>> {
+        self.matvec(x)
+    }
+}
+
+/// Size of a square operator for a right-hand side of length `rhs`
+pub(crate) fn check_operator<T, A>(a: &A, rhs: usize) -> Result<usize>
+where
+    A: LinearOperator<T> + ?Sized,
+{
+    let (rows, cols) = (a.rows(), a.cols());
+    if rows != cols {
+        return Err(MatrixError::InvalidDimensions { rows, cols });
+    }
+    if rhs != rows {
+        return Err(MatrixError::DimensionMismatch { expected: rows, actual: rhs });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_krylov_solvers_on_any_operator() {
+        let n = 30;
+        let dense = Matrix::from_vec(n, n, (0..n * n).map(|k| if k % (n + 1) == 0 { 4.0 } else if k / n + 1 == k % n || k % n + 1 == k / n { -1.0 } else { 0.0 }).collect()).unwrap();
+        let sparse = CsrMatrix::from(&dense);
+        let stencil = FnOperator::square(n, |x: &[f64]| {
+            Ok((0..n).map(|i| 4.0 * x[i] - if i > 0 { x[i - 1] } else { 0.0 } - if i + 1 < n { x[i + 1] } else { 0.0 }).collect())
+        });
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 * 0.2).cos()).collect();
+        let exact = LinearSolver::new(SolverType::LU).solve(&dense, &b).unwrap();
+
+        let control = IterativeControl::default();
+        let operators: [&dyn LinearOperator; 3] = [&dense, &sparse, &stencil];
+        for a in operators {
+            for solver_type in [SolverType::GMRES, SolverType::BiCGSTAB, SolverType::ConjugateGradient] {
+                let outcome = LinearSolver::new(solver_type).solve_operator(a, &b, |x| Ok(x.to_vec()), &control).unwrap();
+                assert!(outcome.converged);
+                assert!(outcome.solution.iter().zip(&exact).all(|(x, y)| (x - y).abs() < 1e-8));
+            }
+        }
+        assert!(LinearSolver::new(SolverType::LU).solve_operator(&stencil, &b, |x| Ok(x.to_vec()), &control).is_err());
+        assert!(matches!(stencil.apply(&b[1..]), Err(MatrixError::DimensionMismatch { .. })));
+    }
+}
@@ -0,0 +1,180 @@
+//! Thread control for the dense kernels
+//!
+//! Products, blocked LU factorizations and matrix-vector products split their
+//! rows across a rayon pool once the work is large enough to pay for it. The
+//! pool size is global and set by [`set_num_threads`]; [`serial`] keeps every
+//! kernel called from a closure on the calling thread, which is how the
+//! `parallel` flags of the solver and analysis configurations reach this crate.
+//!
+//! Row-parallel kernels compute each row on one thread in a fixed order, so
+//! their results do not depend on the thread count. The matrix product splits
+//! its summation by a thread-dependent blocking, and the system BLAS of the
+//! `lapack` feature reorders sums with its own threads; [`set_deterministic`]
+//! and [`deterministic`] replace both with a fixed blocking so that repeated
+//! runs give bit-identical results on any number of threads.
+
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Floating-point operations from which a kernel is split across threads
+///
+/// About a 256 × 256 matrix-vector product; smaller kernels finish before
+/// the threads are woken.
+pub const PARALLEL_MIN_WORK: usize = 1 << 16;
+
+/// Rows per block of the matrix product in deterministic mode
+pub const DETERMINISTIC_BLOCK_ROWS: usize = 64;
+
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+static DETERMINISTIC_SCOPES: AtomicUsize = AtomicUsize::new(0);
+static POOL: Mutex<Option<Arc<ThreadPool>>> = Mutex::new(None);
+
+thread_local! {
+    static SERIAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set the number of threads used by the matrix kernels
+///
+/// Zero restores the default of rayon's global pool, one thread per core.
+/// One runs every kernel on the calling thread.
+pub fn set_num_threads(threads: usize) {
+    let pool = match threads {
+        0 | 1 => None,
+        n => ThreadPoolBuilder::new().num_threads(n).build().ok().map(Arc::new),
+    };
+    let mut current = POOL.lock();
+    NUM_THREADS.store(threads, Ordering::Relaxed);
+    *current = pool;
+}
+
+/// Number of threads the matrix kernels use
+pub fn num_threads() -> usize {
+    match NUM_THREADS.load(Ordering::Relaxed) {
+        0 => rayon::current_num_threads(),
+        n => n,
+    }
+}
+
+/// Run `op` with every matrix kernel it calls on the calling thread
+pub fn serial<R>(op: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SERIAL.with(|serial| serial.set(self.0));
+        }
+    }
+    let _restore = Restore(SERIAL.with(|serial| serial.replace(true)));
+    op()
+}
+
+/// Make every kernel's summation order independent of the thread count
+pub fn set_deterministic(enabled: bool) {
+    DETERMINISTIC.store(enabled, Ordering::Relaxed);
+}
+
+/// Run `op` with deterministic summation order in every kernel, on any thread
+///
+/// Kernels running concurrently on other threads are deterministic too
+/// until `op` returns.
+pub fn deterministic<R>(op: impl FnOnce() -> R) -> R {
+    struct Exit;
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            DETERMINISTIC_SCOPES.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+    DETERMINISTIC_SCOPES.fetch_add(1, Ordering::Relaxed);
+    let _exit = Exit;
+    op()
+}
+
+/// Whether kernels use a summation order independent of the thread count
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed) || DETERMINISTIC_SCOPES.load(Ordering::Relaxed) > 0
+}
+
+/// Whether a kernel of `work` operations is split across threads
+pub(crate) fn is_parallel(work: usize) -> bool {
+    work >= PARALLEL_MIN_WORK && !SERIAL.with(Cell::get) && num_threads() > 1
+}
+
+/// Run `op` in the pool selected by [`set_num_threads`]
+pub(crate) fn install<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    let pool = POOL.lock().clone();
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Evaluate `row(i)` for `i < rows`, across threads when `work` is large enough
+pub(crate) fn map_rows<T, F>(rows: usize, work: usize, row: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync + Send,
+{
+    if is_parallel(work) {
+        install(|| (0..rows).into_par_iter().map(&row).collect())
+    } else {
+        (0..rows).map(row).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend, ComplexMatrix, Matrix};
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_parallel_kernels_match_serial() {
+        let n = 150;
+        let entry = |i: usize, j: usize| ((i * 31 + j * 17) % 13) as f64 / 13.0 - 0.5 + if i == j { 4.0 } else { 0.0 };
+        let a = Matrix::from_vec(n, n, (0..n * n).map(|k| entry(k / n, k % n)).collect()).unwrap();
+        let z = ComplexMatrix::from_vec(n, n, a.data.iter().enumerate().map(|(k, &v)| Complex64::new(v, (k % 7) as f64 * 0.1)).collect()).unwrap();
+        let b = vec![(0..n).map(|i| (i as f64).cos()).collect::<Vec<_>>()];
+        let bz = vec![b[0].iter().map(|&v| Complex64::new(v, -v)).collect::<Vec<_>>()];
+
+        set_num_threads(4);
+        assert_eq!(num_threads(), 4);
+        assert!(is_parallel(n * n * n));
+        assert!(!serial(|| is_parallel(n * n * n)));
+        let product = backend::multiply(&a, &a).unwrap();
+        let x = backend::lu_solve_multi(&a, &b).unwrap();
+        let xz = backend::complex_lu_solve_multi(&z, &bz).unwrap();
+        let y = z.matvec(&xz[0]).unwrap();
+
+        let (serial_product, serial_x) = serial(|| (backend::multiply(&a, &a).unwrap(), backend::lu_solve_multi(&a, &b).unwrap()));
+        let serial_y = serial(|| z.matvec(&xz[0]).unwrap());
+        set_num_threads(0);
+
+        assert!(product.data.iter().zip(&serial_product.data).all(|(p, q)| (p - q).abs() < 1e-10));
+        assert!(x[0].iter().zip(&serial_x[0]).all(|(p, q)| (p - q).abs() < 1e-10));
+        assert!(y.iter().zip(&serial_y).all(|(p, q)| (p - q).norm() < 1e-12));
+        assert!(y.iter().zip(&bz[0]).all(|(p, q)| (p - q).norm() < 1e-9));
+    }
+
+    #[test]
+    fn test_deterministic_products_are_bit_identical() {
+        // Rows not a multiple of any blocking, enough work to split across threads
+        let (m, k) = (203, 150);
+        let a = Matrix::from_vec(m, k, (0..m * k).map(|i| ((i * 7919) % 1009) as f64 / 1009.0 - 0.5).collect()).unwrap();
+        let b = Matrix::from_vec(k, m, (0..m * k).map(|i| ((i * 104729) % 997) as f64 / 997.0 - 0.5).collect()).unwrap();
+        let products: Vec<Matrix> = [1, 3, 4]
+            .into_iter()
+            .map(|threads| {
+                set_num_threads(threads);
+                deterministic(|| backend::multiply(&a, &b).unwrap())
+            })
+            .chain(std::iter::once(deterministic(|| serial(|| backend::multiply(&a, &b).unwrap()))))
+            .collect();
+        set_num_threads(0);
+        assert!(!is_deterministic());
+        assert!(products.windows(2).all(|pair| pair[0].data.iter().zip(&pair[1].data).all(|(p, q)| p.to_bits() == q.to_bits())));
+    }
+}
@@ -0,0 +1,201 @@
+//! Householder QR factorization and linear least squares
+//!
+//! Calibrating resistance coefficients against model tests, fitting Prony
+//! series to retardation kernels and smoothing RAOs are overdetermined: more
+//! samples than unknowns. [`HouseholderQR`] factorizes the tall m × n matrix
+//! A = Q R with m ≥ n and [`solve_least_squares`] minimizes ‖A x - b‖₂ through
+//! R x = Qᴴ b, which avoids squaring the condition number as the normal
+//! equations AᴴA x = Aᴴ b do.
+
+use super::*;
+use crate::sparse::SparseScalar;
+use num_complex::Complex64;
+
+/// Relative size of a diagonal entry of R below which A is taken as rank deficient
+pub const QR_RANK_TOLERANCE: f64 = 1e-13;
+
+/// QR factorization A = Q R by Householder reflections
+///
+/// Q is kept as its n reflections H = I - 2 v vᴴ with unit v; R is the upper
+/// triangle of an n × n row-major array.
+#[derive(Debug, Clone)]
+pub struct HouseholderQR<T = f64> {
+    rows: usize,
+    cols: usize,
+    /// Reflection vector of step `k`, acting on entries `k..rows`
+    reflectors: Vec<Vec<T>>,
+    /// R, row-major n × n
+    r: Vec<T>,
+}
+
+impl<T: SparseScalar> HouseholderQR<T> {
+    /// Factorize a row-major `rows × cols` matrix with `rows ≥ cols`
+    pub fn new(rows: usize, cols: usize, data: &[T]) -> Result<Self> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::DimensionMismatch { expected: rows * cols, actual: data.len() });
+        }
+        if rows < cols {
+            return Err(MatrixError::InvalidDimensions { rows, cols });
+        }
+        let mut a = data.to_vec();
+        let mut reflectors = Vec::with_capacity(cols);
+        for k in 0..cols {
+            let mut v: Vec<T> = (k..rows).map(|i| a[i * cols + k]).collect();
+            let norm = v.iter().map(|x| x.modulus_squared()).sum::<f64>().sqrt();
+            // α = -sign(x₀) ‖x‖ keeps v₀ = x₀ - α free of cancellation
+            let sign = if v[0].is_zero() { T::one() } else { v[0].unscale(v[0].modulus()) };
+            v[0] += sign.scale(norm);
+            let v_norm = v.iter().map(|x| x.modulus_squared()).sum::<f64>().sqrt();
+            if v_norm > 0.0 {
+                v.iter_mut().for_each(|x| *x = x.unscale(v_norm));
+            }
+            for j in k..cols {
+                reflect(&v, &mut a, k * cols + j, cols);
+            }
+            reflectors.push(v);
+        }
+        let r = (0..cols * cols).map(|p| if p % cols >= p / cols { a[p] } else { T::zero() }).collect();
+        Ok(Self { rows, cols, reflectors, r })
+    }
+
+    /// Get matrix dimensions of A
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Upper triangular factor R, row-major n × n
+    pub fn r(&self) -> &[T] {
+        &self.r
+    }
+
+    /// Thin orthonormal factor Q, row-major m × n
+    pub fn q(&self) -> Vec<T> {
+        let (m, n) = (self.rows, self.cols);
+        let mut q = vec![T::zero(); m * n];
+        for j in 0..n {
+            q[j * n + j] = T::one();
+        }
+        for (k, v) in self.reflectors.iter().enumerate().rev() {
+            for j in 0..n {
+                reflect(v, &mut q, k * n + j, n);
+            }
+        }
+        q
+    }
+
+    /// Qᴴ b
+    pub fn apply_adjoint(&self, b: &[T]) -> Result<Vec<T>> {
+        if b.len() != self.rows {
+            return Err(MatrixError::DimensionMismatch { expected: self.rows, actual: b.len() });
+        }
+        let mut y = b.to_vec();
+        for (k, v) in self.reflectors.iter().enumerate() {
+            reflect(v, &mut y, k, 1);
+        }
+        Ok(y)
+    }
+
+    /// Number of diagonal entries of R above `tolerance` relative to the largest
+    pub fn rank(&self, tolerance: f64) -> usize {
+        let diagonal: Vec<f64> = (0..self.cols).map(|k| self.r[k * self.cols + k].modulus()).collect();
+        let largest = diagonal.iter().fold(0.0_f64, |max, &d| max.max(d));
+        diagonal.iter().filter(|&&d| d > tolerance * largest).count()
+    }
+
+    /// Minimize ‖A x - b‖₂ for a matrix of full column rank
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>> {
+        Ok(self.solve_with_residual(b)?.0)
+    }
+
+    /// Least-squares solution and its residual norm ‖A x - b‖₂
+    pub fn solve_with_residual(&self, b: &[T]) -> Result<(Vec<T>, f64)> {
+        if self.rank(QR_RANK_TOLERANCE) < self.cols {
+            return Err(MatrixError::SingularMatrix);
+        }
+        let n = self.cols;
+        let y = self.apply_adjoint(b)?;
+        let mut x = y[..n].to_vec();
+        for i in (0..n).rev() {
+            x[i] = (i + 1..n).fold(x[i], |sum, j| sum - self.r[i * n + j] * x[j]) / self.r[i * n + i];
+        }
+        let residual = y[n..].iter().map(|yi| yi.modulus_squared()).sum::<f64>().sqrt();
+        Ok((x, residual))
+    }
+}
+
+/// Apply H = I - 2 v vᴴ to the entries `start`, `start + stride`, … of `x`
+fn reflect<T: SparseScalar>(v: &[T], x: &mut [T], start: usize, stride: usize) {
+    let dot = v.iter().enumerate().fold(T::zero(), |sum, (i, &vi)| sum + vi.conjugate() * x[start + i * stride]);
+    let scaled = dot + dot;
+    for (i, &vi) in v.iter().enumerate() {
+        x[start + i * stride] -= vi * scaled;
+    }
+}
+
+impl Matrix {
+    /// Householder QR factorization of a matrix with at least as many rows as columns
+    pub fn qr(&self) -> Result<HouseholderQR> {
+        HouseholderQR::new(self.rows, self.cols, &self.data)
+    }
+}
+
+impl ComplexMatrix {
+    /// Householder QR factorization of a matrix with at least as many rows as columns
+    pub fn qr(&self) -> Result<HouseholderQR<Complex64>> {
+        HouseholderQR::new(self.rows, self.cols, &self.data)
+    }
+}
+
+/// Least-squares solution of an overdetermined system, minimizing ‖A x - b‖₂
+pub fn solve_least_squares(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
+    a.qr()?.solve(b)
+}
+
+/// Least-squares solution of an overdetermined complex system, minimizing ‖A x - b‖₂
+pub fn complex_solve_least_squares(a: &ComplexMatrix, b: &[Complex64]) -> Result<Vec<Complex64>> {
+    a.qr()?.solve(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_least_squares() {
+        // Quadratic drag fit R(U) = c₀ + c₁ U + c₂ U² through 20 noise-free samples
+        let speeds: Vec<f64> = (0..20).map(|i| 0.5 + i as f64 * 0.25).collect();
+        let a = Matrix::from_vec(20, 3, speeds.iter().flat_map(|&u| [1.0, u, u * u]).collect()).unwrap();
+        let b: Vec<f64> = speeds.iter().map(|&u| 0.3 - 0.1 * u + 2.5 * u * u).collect();
+        let x = solve_least_squares(&a, &b).unwrap();
+        assert!(x.iter().zip([0.3, -0.1, 2.5]).all(|(p, q)| (p - q).abs() < 1e-10));
+
+        // Q R reproduces A, Q has orthonormal columns
+        let qr = a.qr().unwrap();
+        let (q, r) = (qr.q(), qr.r());
+        for i in 0..20 {
+            for j in 0..3 {
+                let qr_ij: f64 = (0..3).map(|k| q[i * 3 + k] * r[k * 3 + j]).sum();
+                assert!((qr_ij - a.data[i * 3 + j]).abs() < 1e-12);
+            }
+        }
+        for (j, k) in [(0, 0), (0, 1), (1, 2), (2, 2)] {
+            let dot: f64 = (0..20).map(|i| q[i * 3 + j] * q[i * 3 + k]).sum();
+            assert!((dot - if j == k { 1.0 } else { 0.0 }).abs() < 1e-12);
+        }
+
+        // Complex: residual orthogonal to the columns, and rank-deficient matrices rejected
+        let z = ComplexMatrix::from_vec(6, 2, (0..12).map(|k| Complex64::new((k as f64).cos(), (k * k % 5) as f64)).collect()).unwrap();
+        let bz: Vec<Complex64> = (0..6).map(|i| Complex64::new(1.0, i as f64)).collect();
+        let (xz, residual) = z.qr().unwrap().solve_with_residual(&bz).unwrap();
+        let r: Vec<Complex64> = z.matvec(&xz).unwrap().iter().zip(&bz).map(|(p, q)| q - p).collect();
+        assert!((r.iter().map(|v| v.norm_sqr()).sum::<f64>().sqrt() - residual).abs() < 1e-12);
+        for j in 0..2 {
+            let dot: Complex64 = (0..6).map(|i| z.data[i * 2 + j].conj() * r[i]).sum();
+            assert!(dot.norm() < 1e-12);
+        }
+        let deficient = Matrix::from_vec(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]).unwrap();
+        assert_eq!(deficient.qr().unwrap().rank(1e-12), 1);
+        assert!(matches!(solve_least_squares(&deficient, &[1.0, 2.0, 3.0]), Err(MatrixError::SingularMatrix)));
+        assert!(Matrix::new(2, 3).qr().is_err());
+    }
+}
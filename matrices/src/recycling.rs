@@ -0,0 +1,320 @@
+//! Krylov subspace recycling for sequences of complex systems
+//!
+//! Restarted GMRES forgets its Krylov space at every restart and at every new
+//! system, so the few eigenvalues closest to the origin, which dominate
+//! convergence near irregular frequencies, are rediscovered in each cycle.
+//! GCRO-DR (Parks, de Sturler et al., 2006) keeps the harmonic Ritz vectors
+//! of those eigenvalues in a recycle space U and deflates them with
+//! C = A M⁻¹ U: each cycle runs Arnoldi on (I - C Cᴴ) A M⁻¹ and minimizes
+//! the residual over span(U, V). The space is updated at every restart and
+//! carried in a [`RecycleSpace`] to the next system of a sequence, such as
+//! the next frequency of a sweep, where it warm-starts the solve.
+
+use super::*;
+use crate::complex::{axpy, dot, norm, scale};
+use crate::operator::check_operator;
+use crate::solvers::{IterativeControl, IterativeOutcome};
+use nalgebra::{DMatrix, DVector, Schur};
+use num_complex::Complex64;
+
+/// Recycled Krylov subspace carried between solves
+///
+/// The basis lives in the right-preconditioned unknown M x, so systems in
+/// a sequence should share their preconditioner, or change it slowly.
+#[derive(Debug, Clone, Default)]
+pub struct RecycleSpace {
+    size: usize,
+    basis: Vec<Vec<Complex64>>,
+}
+
+impl RecycleSpace {
+    /// Empty space that keeps up to `size` vectors; zero disables recycling
+    pub fn new(size: usize) -> Self {
+        Self { size, basis: Vec::new() }
+    }
+
+    /// Number of vectors kept between solves
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of vectors currently held
+    pub fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Forget the recycled vectors, e.g. when the geometry changes
+    pub fn clear(&mut self) {
+        self.basis.clear();
+    }
+}
+
+/// Right-preconditioned GCRO-DR on any complex [`LinearOperator`], recycling `recycle`
+///
+/// Restarts, stopping criteria and history follow [`complex_gmres_iterate`];
+/// the first residual is that of the initial guess U Cᴴ b from the recycled
+/// space. The space is left holding the harmonic Ritz vectors of this system.
+pub fn complex_gcrodr_iterate<A, P>(
+    a: &A,
+    precondition: P,
+    b: &[Complex64],
+    control: &IterativeControl,
+    recycle: &mut RecycleSpace,
+) -> Result<IterativeOutcome<Complex64>>
+where
+    A: LinearOperator<Complex64> + ?Sized,
+    P: Fn(&[Complex64]) -> Result<Vec<Complex64>>,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[Complex64]| a.apply(&precondition(x)?);
+    let zero = Complex64::new(0.0, 0.0);
+    let restart = control.restart.unwrap_or(n.min(50)).max(1);
+    let size = recycle.size.min(n.saturating_sub(1));
+
+    // A M⁻¹ U = C with orthonormal C, for this operator
+    let mut u = match recycle.basis.first() {
+        Some(first) if first.len() == n => std::mem::take(&mut recycle.basis),
+        _ => Vec::new(),
+    };
+    let mut c = u.iter().map(|ui| apply(ui)).collect::<Result<Vec<_>>>()?;
+    orthonormalize(&mut c, &mut u);
+
+    // x is the preconditioned unknown M x until the end
+    let mut x = vec![zero; n];
+    let mut r = b.to_vec();
+    deflate(&c, &u, &mut x, &mut r);
+    let mut beta = norm(&r);
+    let mut iterations = 0;
+    let mut history = vec![beta];
+    let (mut converged, mut timed_out) = (beta < control.tolerance, false);
+
+    for _ in 0..control.max_iterations {
+        if converged {
+            break;
+        }
+        if control.expired() {
+            timed_out = true;
+            break;
+        }
+
+        // Arnoldi basis of (I - C Cᴴ) A M⁻¹ from the residual, with B = Cᴴ A M⁻¹ V
+        let mut v = vec![scale(&r, Complex64::new(1.0 / beta, 0.0))];
+        let mut h = vec![vec![zero; restart]; restart + 1];
+        let mut projections = vec![vec![zero; restart]; c.len()];
+        let mut k = 0;
+        for j in 0..restart {
+            let mut w = apply(&v[j])?;
+            iterations += 1;
+            k = j + 1;
+            for (ci, row) in c.iter().zip(&mut projections) {
+                row[j] = dot(ci, &w);
+                axpy(&mut w, -row[j], ci);
+            }
+            for i in 0..=j {
+                h[i][j] = dot(&v[i], &w);
+                axpy(&mut w, -h[i][j], &v[i]);
+            }
+            let h_next = norm(&w);
+            h[j + 1][j] = Complex64::new(h_next, 0.0);
+            if h_next < 1e-14 {
+                break;
+            }
+            v.push(scale(&w, Complex64::new(1.0 / h_next, 0.0)));
+        }
+
+        // x += V y - U B y, which leaves no component along C in the residual
+        let y = least_squares(&h, beta, k)?;
+        for (yj, vj) in y.iter().zip(&v) {
+            axpy(&mut x, *yj, vj);
+        }
+        for (ui, row) in u.iter().zip(&projections) {
+            let by: Complex64 = row.iter().zip(&y).map(|(bij, yj)| bij * yj).sum();
+            axpy(&mut x, -by, ui);
+        }
+
+        if size > 0 && v.len() == k + 1 {
+            if let Some((new_u, new_c)) = harmonic_ritz(size, &u, &c, &v, &h, &projections, k) {
+                (u, c) = (new_u, new_c);
+                orthonormalize(&mut c, &mut u);
+            }
+        }
+
+        r = b.iter().zip(apply(&x)?).map(|(bi, ax)| bi - ax).collect();
+        deflate(&c, &u, &mut x, &mut r);
+        beta = norm(&r);
+        history.push(beta);
+        converged = beta < control.tolerance;
+    }
+
+    u.truncate(size);
+    recycle.basis = u;
+    Ok(IterativeOutcome {
+        solution: precondition(&x)?,
+        iterations,
+        residual: beta,
+        converged,
+        timed_out,
+        history,
+    })
+}
+
+/// Minimum-norm y minimizing |β e₁ - H y| over the leading (k+1)×k Hessenberg block
+///
+/// Unlike plain GMRES, the deflated operator can map part of the Krylov
+/// space into span(C), leaving H rank-deficient.
+fn least_squares(h: &[Vec<Complex64>], beta: f64, k: usize) -> Result<Vec<Complex64>> {
+    let hessenberg = DMatrix::from_fn(k + 1, k, |i, j| h[i][j]);
+    let mut rhs = DVector::zeros(k + 1);
+    rhs[0] = Complex64::new(beta, 0.0);
+    let svd = hessenberg.svd(true, true);
+    let cutoff = 1e-14 * svd.singular_values.max();
+    let y = svd.solve(&rhs, cutoff).map_err(|message| MatrixError::SolverError { message: message.to_string() })?;
+    Ok(y.iter().copied().collect())
+}
+
+/// Vectors of a subspace basis
+type Basis = Vec<Vec<Complex64>>;
+
+/// Move the components of r along C into x: x += U Cᴴ r, r -= C Cᴴ r
+fn deflate(c: &[Vec<Complex64>], u: &[Vec<Complex64>], x: &mut [Complex64], r: &mut [Complex64]) {
+    for (ci, ui) in c.iter().zip(u) {
+        let alpha = dot(ci, r);
+        axpy(x, alpha, ui);
+        axpy(r, -alpha, ci);
+    }
+}
+
+/// Orthonormalize C by modified Gram-Schmidt, applying the same operations to U
+///
+/// Pairs whose C vector is numerically dependent on the others are dropped.
+fn orthonormalize(c: &mut Basis, u: &mut Basis) {
+    let mut j = 0;
+    while j < c.len() {
+        let initial = norm(&c[j]);
+        let ((c_done, c_rest), (u_done, u_rest)) = (c.split_at_mut(j), u.split_at_mut(j));
+        for (ci, ui) in c_done.iter().zip(u_done.iter()) {
+            let r = dot(ci, &c_rest[0]);
+            axpy(&mut c_rest[0], -r, ci);
+            axpy(&mut u_rest[0], -r, ui);
+        }
+        let length = norm(&c[j]);
+        if length <= 1e-10 * initial || length.is_nan() {
+            c.remove(j);
+            u.remove(j);
+            continue;
+        }
+        let inverse = Complex64::new(1.0 / length, 0.0);
+        c[j] = scale(&c[j], inverse);
+        u[j] = scale(&u[j], inverse);
+        j += 1;
+    }
+}
+
+/// Harmonic Ritz vectors of A M⁻¹ over span(U, V) for the `size` eigenvalues nearest the origin
+///
+/// With W = [U, Vₖ] and Ŵ = [C, Vₖ₊₁], A M⁻¹ W = Ŵ G for G = [[I, B], [0, H]],
+/// and the harmonic Ritz pairs solve Gᴴ G z = θ Gᴴ Ŵᴴ W z. Returns the new
+/// U = W z and C = Ŵ G z, or `None` when the projected problem is singular.
+fn harmonic_ritz(
+    size: usize,
+    u: &[Vec<Complex64>],
+    c: &[Vec<Complex64>],
+    v: &[Vec<Complex64>],
+    h: &[Vec<Complex64>],
+    projections: &[Vec<Complex64>],
+    k: usize,
+) -> Option<(Basis, Basis)> {
+    let p = c.len();
+    let s = p + k;
+    let g = DMatrix::from_fn(s + 1, s, |i, j| match (i < p, j < p) {
+        (true, true) => Complex64::new(if i == j { 1.0 } else { 0.0 }, 0.0),
+        (true, false) => projections[i][j - p],
+        (false, true) => Complex64::new(0.0, 0.0),
+        (false, false) => h[i - p][j - p],
+    });
+    let f = DMatrix::from_fn(s + 1, s, |i, j| match (i < p, j < p) {
+        (true, true) => dot(&c[i], &u[j]),
+        (false, true) => dot(&v[i - p], &u[j]),
+        (true, false) => Complex64::new(0.0, 0.0),
+        (false, false) => Complex64::new(if i == j { 1.0 } else { 0.0 }, 0.0),
+    });
+
+    // Eigenvalues μ = 1/θ of (Gᴴ G)⁻¹ Gᴴ Ŵᴴ W, largest first
+    let g_adjoint = g.adjoint();
+    let pencil = (&g_adjoint * &g).lu().solve(&(&g_adjoint * &f))?;
+    let (q, t) = Schur::try_new(pencil, 1e-14, 1000)?.unpack();
+    let mut order: Vec<usize> = (0..s).collect();
+    order.sort_by(|&i, &j| t[(j, j)].norm().total_cmp(&t[(i, i)].norm()));
+
+    // Eigenvectors of the triangular factor by back substitution
+    let kept = size.min(s);
+    let mut z = DMatrix::zeros(s, kept);
+    for (column, &i) in order[..kept].iter().enumerate() {
+        let mu = t[(i, i)];
+        let mut y = vec![Complex64::new(0.0, 0.0); s];
+        y[i] = Complex64::new(1.0, 0.0);
+        for row in (0..i).rev() {
+            let sum: Complex64 = (row + 1..=i).map(|l| t[(row, l)] * y[l]).sum();
+            let shift = t[(row, row)] - mu;
+            let shift = if shift.norm() < 1e-14 * mu.norm().max(1e-300) { Complex64::new(1e-14 * mu.norm().max(1e-300), 0.0) } else { shift };
+            y[row] = -sum / shift;
+        }
+        z.set_column(column, &(&q * DVector::from_vec(y)));
+    }
+    let gz = &g * &z;
+
+    let combine = |coefficients: &DMatrix<Complex64>, first: &[Vec<Complex64>], second: &[Vec<Complex64>]| -> Basis {
+        (0..kept)
+            .map(|column| {
+                let mut combined = vec![Complex64::new(0.0, 0.0); v[0].len()];
+                for (row, vector) in first.iter().chain(second).enumerate() {
+                    axpy(&mut combined, coefficients[(row, column)], vector);
+                }
+                combined
+            })
+            .collect()
+    };
+    Some((combine(&z, u, &v[..k]), combine(&gz, c, v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recycling_reduces_iterations_across_a_sweep() {
+        // A(ω) with a cluster of small eigenvalues that restarted GMRES rediscovers each cycle
+        let n = 80;
+        let system = |omega: f64| {
+            ComplexMatrix::from_vec(n, n, (0..n * n).map(|k| {
+                let (i, j) = (k / n, k % n);
+                let diagonal = if i < 4 { 0.02 * (i + 1) as f64 } else { 1.0 + i as f64 / n as f64 };
+                let coupling = ((i * 7 + j * 3) % 11) as f64 / 11.0 - 0.5;
+                Complex64::new(if i == j { diagonal } else { 0.0 } + 0.01 * coupling * (1.0 + omega), 0.05 * omega * if i == j { 1.0 } else { 0.0 })
+            }).collect()).unwrap()
+        };
+        let b: Vec<Complex64> = (0..n).map(|i| Complex64::new((i as f64 * 0.3).cos(), 0.1)).collect();
+        let control = IterativeControl { tolerance: 1e-9, restart: Some(15), ..Default::default() };
+        let identity = |x: &[Complex64]| Ok(x.to_vec());
+
+        let mut recycle = RecycleSpace::new(6);
+        let (mut plain, mut recycled) = (0, 0);
+        for step in 0..5 {
+            let a = system(0.1 * step as f64);
+            let exact = complex_lu_solve(&a, &b).unwrap();
+            let gmres = complex_gmres_iterate(&a, identity, &b, &control).unwrap();
+            let gcrodr = complex_gcrodr_iterate(&a, identity, &b, &control, &mut recycle).unwrap();
+            assert!(gmres.converged && gcrodr.converged);
+            assert!(gcrodr.solution.iter().zip(&exact).all(|(x, y)| (x - y).norm() < 1e-7));
+            assert_eq!(recycle.dimension(), 6);
+            plain += gmres.iterations;
+            recycled += gcrodr.iterations;
+        }
+        assert!(2 * recycled < plain, "recycled {} vs plain {}", recycled, plain);
+
+        // Without a recycle space the iteration is restarted GMRES
+        let a = system(0.0);
+        let outcome = complex_gcrodr_iterate(&a, identity, &b, &control, &mut RecycleSpace::new(0)).unwrap();
+        assert_eq!(outcome.iterations, complex_gmres_iterate(&a, identity, &b, &control).unwrap().iterations);
+    }
+}
@@ -1,83 +1,110 @@
 //! Linear solvers for BEM matrix operations
 
 use super::*;
-use nalgebra::{DMatrix, DVector, LU, Cholesky};
+use crate::operator::check_operator;
+use std::time::Instant;
 
-/// LU decomposition solver using nalgebra
-pub fn lu_solve(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
-    if a.rows != b.len() {
-        return Err(MatrixError::DimensionMismatch {
-            expected: a.rows,
-            actual: b.len(),
-        });
-    }
-    
-    if !a.is_square() {
-        return Err(MatrixError::InvalidDimensions {
-            rows: a.rows,
-            cols: a.cols,
-        });
-    }
-    
-    // Convert Matrix to nalgebra DMatrix
-    let na_matrix = DMatrix::from_fn(a.rows, a.cols, |i, j| {
-        a.get(i, j).unwrap()
-    });
-    
-    let na_b = DVector::from_vec(b.to_vec());
-    
-    // Perform LU decomposition
-    let lu = LU::new(na_matrix);
-    
-    if !lu.is_invertible() {
-        return Err(MatrixError::SingularMatrix);
+/// Stopping criteria for iterative solvers
+#[derive(Debug, Clone)]
+pub struct IterativeControl {
+    /// Residual norm tolerance
+    pub tolerance: f64,
+    /// Maximum iterations
+    pub max_iterations: usize,
+    /// GMRES restart length
+    pub restart: Option<usize>,
+    /// Wall-clock deadline; the best iterate so far is returned when reached
+    pub deadline: Option<Instant>,
+}
+
+impl Default for IterativeControl {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-10,
+            max_iterations: 1000,
+            restart: None,
+            deadline: None,
+        }
     }
-    
-    // Solve the system
-    match lu.solve(&na_b) {
-        Some(solution) => Ok(solution.data.as_vec().clone()),
-        None => Err(MatrixError::SolverError {
-            message: "LU solver failed to find solution".to_string(),
-        }),
+}
+
+impl IterativeControl {
+    /// Whether the deadline has been reached
+    pub fn expired(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
     }
 }
 
-/// Cholesky decomposition solver for symmetric positive definite matrices
-pub fn cholesky_solve(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
-    if a.rows != b.len() {
-        return Err(MatrixError::DimensionMismatch {
-            expected: a.rows,
-            actual: b.len(),
-        });
+/// Outcome of an iterative solve, including unconverged partial results
+#[derive(Debug, Clone)]
+pub struct IterativeOutcome<T = f64> {
+    /// Best solution found
+    pub solution: Vec<T>,
+    /// Iterations performed
+    pub iterations: usize,
+    /// Final residual norm
+    pub residual: f64,
+    /// Whether the tolerance was reached
+    pub converged: bool,
+    /// Whether the deadline stopped the iteration
+    pub timed_out: bool,
+    /// Residual norm at the start and after each iteration (each restart cycle for GMRES)
+    pub history: Vec<f64>,
+}
+
+impl IterativeOutcome {
+    fn finish(
+        solution: Vec<f64>,
+        iterations: usize,
+        residual: f64,
+        converged: bool,
+        timed_out: bool,
+        history: Vec<f64>,
+    ) -> Result<Self> {
+        Ok(Self { solution, iterations, residual, converged, timed_out, history })
     }
-    
-    if !a.is_square() {
-        return Err(MatrixError::InvalidDimensions {
-            rows: a.rows,
-            cols: a.cols,
-        });
+}
+
+impl<T> IterativeOutcome<T> {
+    /// Same outcome with the solution converted by `f`
+    pub fn map_solution<U, F>(self, f: F) -> IterativeOutcome<U>
+    where
+        F: FnOnce(Vec<T>) -> Vec<U>,
+    {
+        IterativeOutcome {
+            solution: f(self.solution),
+            iterations: self.iterations,
+            residual: self.residual,
+            converged: self.converged,
+            timed_out: self.timed_out,
+            history: self.history,
+        }
     }
     
-    // Convert Matrix to nalgebra DMatrix
-    let na_matrix = DMatrix::from_fn(a.rows, a.cols, |i, j| {
-        a.get(i, j).unwrap()
-    });
-    
-    let na_b = DVector::from_vec(b.to_vec());
-    
-    // Perform Cholesky decomposition
-    match Cholesky::new(na_matrix) {
-        Some(chol) => {
-            // Solve the system
-            let solution = chol.solve(&na_b);
-            Ok(solution.data.as_vec().clone())
+    /// Convert to a plain solution, failing if not converged
+    pub fn into_converged(self, method: &str, max_iterations: usize) -> Result<Vec<T>> {
+        if self.converged {
+            Ok(self.solution)
+        } else {
+            Err(MatrixError::SolverError {
+                message: format!("{} failed to converge within {} iterations", method, max_iterations),
+            })
         }
-        None => Err(MatrixError::SolverError {
-            message: "Matrix is not positive definite for Cholesky decomposition".to_string(),
-        }),
     }
 }
 
+/// LU decomposition solver, through the dense backend
+pub fn lu_solve(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
+    check_system(a, b)?;
+    Ok(backend::lu_solve_multi(a, &[b.to_vec()])?.remove(0))
+}
+
+/// Cholesky decomposition solver for symmetric positive definite matrices, through the dense backend
+pub fn cholesky_solve(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
+    check_system(a, b)?;
+    Ok(backend::cholesky_solve_multi(a, &[b.to_vec()])?.remove(0))
+}
+
 /// GMRES iterative solver for general matrices
 pub fn gmres_solve(a: &Matrix, b: &[f64]) -> Result<Vec<f64>> {
     gmres_solve_with_params(a, b, 1e-10, 1000, None)
@@ -91,22 +118,45 @@ pub fn gmres_solve_with_params(
     max_iterations: usize,
     restart: Option<usize>
 ) -> Result<Vec<f64>> {
-    if a.rows != b.len() {
-        return Err(MatrixError::DimensionMismatch {
-            expected: a.rows,
-            actual: b.len(),
-        });
-    }
-    
-    if !a.is_square() {
-        return Err(MatrixError::InvalidDimensions {
-            rows: a.rows,
-            cols: a.cols,
-        });
-    }
+    let control = IterativeControl { tolerance, max_iterations, restart, deadline: None };
+    gmres_iterate(a, b, &control)?.into_converged("GMRES", max_iterations)
+}
+
+/// GMRES iteration returning the best iterate when stopped early
+pub fn gmres_iterate(a: &Matrix, b: &[f64], control: &IterativeControl) -> Result<IterativeOutcome> {
+    check_system(a, b)?;
+    gmres_iterate_with(a, b, control)
+}
+
+/// GMRES iteration on any [`LinearOperator`]
+///
+/// Used for matrix-free and compressed operators such as hierarchical matrices.
+pub fn gmres_iterate_with<A>(a: &A, b: &[f64], control: &IterativeControl) -> Result<IterativeOutcome>
+where
+    A: LinearOperator + ?Sized,
+{
+    gmres_iterate_preconditioned(a, |x| Ok(x.to_vec()), b, control)
+}
+
+/// Right-preconditioned GMRES: iterates on A M⁻¹ with `precondition(x) = M⁻¹ x`
+///
+/// The residual is that of the original system, so the stopping criterion
+/// does not depend on the preconditioner.
+pub fn gmres_iterate_preconditioned<A, P>(
+    a: &A,
+    precondition: P,
+    b: &[f64],
+    control: &IterativeControl,
+) -> Result<IterativeOutcome>
+where
+    A: LinearOperator + ?Sized,
+    P: Fn(&[f64]) -> Result<Vec<f64>>,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[f64]| a.apply(x);
     
-    let n = a.rows;
-    let restart_k = restart.unwrap_or(n.min(50));
+    let tolerance = control.tolerance;
+    let restart_k = control.restart.unwrap_or(n.min(50)).max(1);
     
     // Initial guess (zero vector)
     let mut x = vec![0.0; n];
@@ -114,23 +164,32 @@ pub fn gmres_solve_with_params(
     
     // Initial residual: r = b - A*x (since x=0, r=b initially)
     let mut beta = vector_norm(&r);
+    let mut iterations = 0;
+    let mut history = vec![beta];
     
     if beta < tolerance {
-        return Ok(x); // Initial guess is already good enough
+        return IterativeOutcome::finish(x, 0, beta, true, false, history); // Initial guess is already good enough
     }
     
-    for _ in 0..max_iterations {
+    for _ in 0..control.max_iterations {
+        if control.expired() {
+            return IterativeOutcome::finish(x, iterations, beta, false, true, history);
+        }
+        
         // Arnoldi iteration
         let mut v = Vec::with_capacity(restart_k + 1);
         let mut h = vec![vec![0.0; restart_k]; restart_k + 1];
+        let mut k = 0;
         
         // v[0] = r / ||r||
         v.push(vector_scale(&r, 1.0 / beta));
         
         // Build Krylov subspace
         for j in 0..restart_k {
-            // w = A * v[j]
-            let w = matrix_vector_mult(a, &v[j])?;
+            // w = A M⁻¹ v[j]
+            let w = apply(&precondition(&v[j])?)?;
+            iterations += 1;
+            k = j + 1;
             
             // Modified Gram-Schmidt orthogonalization
             let mut w_orth = w;
@@ -150,33 +209,33 @@ pub fn gmres_solve_with_params(
         }
         
         // Solve least squares problem: min ||beta * e1 - H * y||
-        let k = v.len() - 1;
         let mut g = vec![0.0; k + 1];
         g[0] = beta;
-        
-        // Apply Givens rotations to H and g
         let y = solve_least_squares(&h, &g, k)?;
         
-        // Update solution: x = x + V * y
+        // Update solution: x = x + M⁻¹ V y
+        let mut z = vec![0.0; n];
         for j in 0..k {
             for i in 0..n {
-                x[i] += y[j] * v[j][i];
+                z[i] += y[j] * v[j][i];
             }
         }
+        for (xi, zi) in x.iter_mut().zip(precondition(&z)?) {
+            *xi += zi;
+        }
         
         // Compute new residual
-        let ax = matrix_vector_mult(a, &x)?;
+        let ax = apply(&x)?;
         r = vector_sub(b, &ax);
         beta = vector_norm(&r);
+        history.push(beta);
         
         if beta < tolerance {
-            return Ok(x);
+            return IterativeOutcome::finish(x, iterations, beta, true, false, history);
         }
     }
     
-    Err(MatrixError::SolverError {
-        message: format!("GMRES failed to converge within {} iterations", max_iterations),
-    })
+    IterativeOutcome::finish(x, iterations, beta, false, false, history)
 }
 
 /// Conjugate gradient solver for symmetric positive definite matrices
@@ -191,30 +250,37 @@ pub fn cg_solve_with_params(
     tolerance: f64, 
     max_iterations: usize
 ) -> Result<Vec<f64>> {
-    if a.rows != b.len() {
-        return Err(MatrixError::DimensionMismatch {
-            expected: a.rows,
-            actual: b.len(),
-        });
-    }
-    
-    if !a.is_square() {
-        return Err(MatrixError::InvalidDimensions {
-            rows: a.rows,
-            cols: a.cols,
-        });
-    }
-    
-    let n = a.rows;
+    let control = IterativeControl { tolerance, max_iterations, ..Default::default() };
+    cg_iterate(a, b, &control)?.into_converged("CG", max_iterations)
+}
+
+/// Conjugate gradient iteration returning the best iterate when stopped early
+pub fn cg_iterate(a: &Matrix, b: &[f64], control: &IterativeControl) -> Result<IterativeOutcome> {
+    check_system(a, b)?;
+    cg_iterate_with(a, b, control)
+}
+
+/// Conjugate gradient iteration on any symmetric positive definite [`LinearOperator`]
+pub fn cg_iterate_with<A>(a: &A, b: &[f64], control: &IterativeControl) -> Result<IterativeOutcome>
+where
+    A: LinearOperator + ?Sized,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[f64]| a.apply(x);
     
     // Initial guess (zero vector)
     let mut x = vec![0.0; n];
     let mut r = b.to_vec(); // r = b - A*x (since x=0, r=b initially)
     let mut p = r.clone();
     let mut rsold = vector_dot(&r, &r);
+    let mut history = vec![rsold.sqrt()];
     
-    for _ in 0..max_iterations {
-        let ap = matrix_vector_mult(a, &p)?;
+    for iteration in 0..control.max_iterations {
+        if control.expired() {
+            return IterativeOutcome::finish(x, iteration, rsold.sqrt(), false, true, history);
+        }
+        
+        let ap = apply(&p)?;
         let alpha = rsold / vector_dot(&p, &ap);
         
         // Update solution: x = x + alpha * p
@@ -228,9 +294,10 @@ pub fn cg_solve_with_params(
         }
         
         let rsnew = vector_dot(&r, &r);
+        history.push(rsnew.sqrt());
         
-        if rsnew.sqrt() < tolerance {
-            return Ok(x);
+        if rsnew.sqrt() < control.tolerance {
+            return IterativeOutcome::finish(x, iteration + 1, rsnew.sqrt(), true, false, history);
         }
         
         let beta = rsnew / rsold;
@@ -243,9 +310,7 @@ pub fn cg_solve_with_params(
         rsold = rsnew;
     }
     
-    Err(MatrixError::SolverError {
-        message: format!("CG failed to converge within {} iterations", max_iterations),
-    })
+    IterativeOutcome::finish(x, control.max_iterations, rsold.sqrt(), false, false, history)
 }
 
 /// BiCGSTAB solver for general matrices
@@ -260,39 +325,59 @@ pub fn bicgstab_solve_with_params(
     tolerance: f64, 
     max_iterations: usize
 ) -> Result<Vec<f64>> {
-    if a.rows != b.len() {
-        return Err(MatrixError::DimensionMismatch {
-            expected: a.rows,
-            actual: b.len(),
-        });
-    }
-    
-    if !a.is_square() {
-        return Err(MatrixError::InvalidDimensions {
-            rows: a.rows,
-            cols: a.cols,
-        });
-    }
+    let control = IterativeControl { tolerance, max_iterations, ..Default::default() };
+    bicgstab_iterate(a, b, &control)?.into_converged("BiCGSTAB", max_iterations)
+}
+
+/// BiCGSTAB iteration returning the best iterate when stopped early
+pub fn bicgstab_iterate(a: &Matrix, b: &[f64], control: &IterativeControl) -> Result<IterativeOutcome> {
+    check_system(a, b)?;
+    bicgstab_iterate_preconditioned(a, |x| Ok(x.to_vec()), b, control)
+}
+
+/// Right-preconditioned BiCGSTAB on any [`LinearOperator`] with `precondition(x) = M⁻¹ x`
+pub fn bicgstab_iterate_preconditioned<A, P>(
+    a: &A,
+    precondition: P,
+    b: &[f64],
+    control: &IterativeControl,
+) -> Result<IterativeOutcome>
+where
+    A: LinearOperator + ?Sized,
+    P: Fn(&[f64]) -> Result<Vec<f64>>,
+{
+    let n = check_operator(a, b.len())?;
+    let apply = |x: &[f64]| a.apply(x);
     
-    let n = a.rows;
+    let tolerance = control.tolerance;
     
     // Initial guess (zero vector)
     let mut x = vec![0.0; n];
     let mut r = b.to_vec(); // r = b - A*x
     let r0 = r.clone();
+    let r0_norm_sq = vector_dot(&r0, &r0);
     let mut v = vec![0.0; n];
     let mut p = vec![0.0; n];
     let mut s = vec![0.0; n];
-    let mut t = vec![0.0; n];
+    let mut t;
+    let mut history = vec![vector_norm(&r)];
+    if history[0] < tolerance {
+        return IterativeOutcome::finish(x, 0, history[0], true, false, history);
+    }
     
     let mut rho = 1.0;
     let mut alpha = 1.0;
     let mut omega = 1.0;
     
-    for _ in 0..max_iterations {
+    for iteration in 0..control.max_iterations {
+        if control.expired() {
+            return IterativeOutcome::finish(x, iteration, vector_norm(&r), false, true, history);
+        }
+        
         let rho_new = vector_dot(&r0, &r);
         
-        if rho_new.abs() < 1e-14 {
+        // Breakdown relative to the initial residual, so small right-hand sides are not rejected
+        if rho_new.abs() < 1e-14 * r0_norm_sq {
             return Err(MatrixError::SolverError {
                 message: "BiCGSTAB breakdown: rho too small".to_string(),
             });
@@ -305,7 +390,8 @@ pub fn bicgstab_solve_with_params(
             p[i] = r[i] + beta * (p[i] - omega * v[i]);
         }
         
-        v = matrix_vector_mult(a, &p)?;
+        let p_hat = precondition(&p)?;
+        v = apply(&p_hat)?;
         alpha = rho_new / vector_dot(&r0, &v);
         
         // s = r - alpha * v
@@ -314,20 +400,23 @@ pub fn bicgstab_solve_with_params(
         }
         
         // Check for convergence
-        if vector_norm(&s) < tolerance {
+        let s_norm = vector_norm(&s);
+        if s_norm < tolerance {
             // Update x and return
             for i in 0..n {
-                x[i] += alpha * p[i];
+                x[i] += alpha * p_hat[i];
             }
-            return Ok(x);
+            history.push(s_norm);
+            return IterativeOutcome::finish(x, iteration + 1, s_norm, true, false, history);
         }
         
-        t = matrix_vector_mult(a, &s)?;
+        let s_hat = precondition(&s)?;
+        t = apply(&s_hat)?;
         omega = vector_dot(&t, &s) / vector_dot(&t, &t);
         
-        // Update solution: x = x + alpha * p + omega * s
+        // Update solution: x = x + alpha * p̂ + omega * ŝ
         for i in 0..n {
-            x[i] += alpha * p[i] + omega * s[i];
+            x[i] += alpha * p_hat[i] + omega * s_hat[i];
         }
         
         // Update residual: r = s - omega * t
@@ -335,8 +424,10 @@ pub fn bicgstab_solve_with_params(
             r[i] = s[i] - omega * t[i];
         }
         
-        if vector_norm(&r) < tolerance {
-            return Ok(x);
+        let r_norm = vector_norm(&r);
+        history.push(r_norm);
+        if r_norm < tolerance {
+            return IterativeOutcome::finish(x, iteration + 1, r_norm, true, false, history);
         }
         
         if omega.abs() < 1e-14 {
@@ -348,9 +439,27 @@ pub fn bicgstab_solve_with_params(
         rho = rho_new;
     }
     
-    Err(MatrixError::SolverError {
-        message: format!("BiCGSTAB failed to converge within {} iterations", max_iterations),
-    })
+    let residual = vector_norm(&r);
+    IterativeOutcome::finish(x, control.max_iterations, residual, false, false, history)
+}
+
+/// Check that `a` is square and matches `b`
+fn check_system(a: &Matrix, b: &[f64]) -> Result<()> {
+    if a.rows != b.len() {
+        return Err(MatrixError::DimensionMismatch {
+            expected: a.rows,
+            actual: b.len(),
+        });
+    }
+    
+    if !a.is_square() {
+        return Err(MatrixError::InvalidDimensions {
+            rows: a.rows,
+            cols: a.cols,
+        });
+    }
+    
+    Ok(())
 }
 
 // Helper functions for vector operations
@@ -371,7 +480,7 @@ fn vector_sub(a: &[f64], b: &[f64]) -> Vec<f64> {
     a.iter().zip(b.iter()).map(|(x, y)| x - y).collect()
 }
 
-fn matrix_vector_mult(a: &Matrix, v: &[f64]) -> Result<Vec<f64>> {
+pub(crate) fn matrix_vector_mult(a: &Matrix, v: &[f64]) -> Result<Vec<f64>> {
     if a.cols != v.len() {
         return Err(MatrixError::DimensionMismatch {
             expected: a.cols,
@@ -379,27 +488,42 @@ fn matrix_vector_mult(a: &Matrix, v: &[f64]) -> Result<Vec<f64>> {
         });
     }
     
-    let mut result = vec![0.0; a.rows];
-    for i in 0..a.rows {
-        for j in 0..a.cols {
-            result[i] += a.get(i, j).unwrap() * v[j];
-        }
-    }
-    Ok(result)
+    Ok(parallel::map_rows(a.rows, a.data.len(), |i| {
+        a.data[i * a.cols..(i + 1) * a.cols].iter().zip(v).map(|(aij, vj)| aij * vj).sum()
+    }))
 }
 
 fn solve_least_squares(h: &[Vec<f64>], g: &[f64], k: usize) -> Result<Vec<f64>> {
-    // Simple back substitution for upper triangular system
+    // Reduce the (k+1)×k Hessenberg matrix to upper triangular form with Givens rotations
+    let mut r: Vec<Vec<f64>> = h.iter().take(k + 1).map(|row| row[..k].to_vec()).collect();
+    let mut g = g.to_vec();
+    for j in 0..k {
+        let (a, b) = (r[j][j], r[j + 1][j]);
+        let norm = a.hypot(b);
+        if norm == 0.0 {
+            continue;
+        }
+        let (c, s) = (a / norm, b / norm);
+        let (top, bottom) = r.split_at_mut(j + 1);
+        for (upper, lower) in top[j][j..k].iter_mut().zip(&mut bottom[0][j..k]) {
+            (*upper, *lower) = (c * *upper + s * *lower, -s * *upper + c * *lower);
+        }
+        let (upper, lower) = (g[j], g[j + 1]);
+        g[j] = c * upper + s * lower;
+        g[j + 1] = -s * upper + c * lower;
+    }
+    
+    // Back substitution for the upper triangular system
     let mut y = vec![0.0; k];
     for i in (0..k).rev() {
         let mut sum = g[i];
         for j in (i + 1)..k {
-            sum -= h[i][j] * y[j];
+            sum -= r[i][j] * y[j];
         }
-        if h[i][i].abs() < 1e-14 {
+        if r[i][i].abs() < 1e-14 {
             return Err(MatrixError::SingularMatrix);
         }
-        y[i] = sum / h[i][i];
+        y[i] = sum / r[i][i];
     }
     Ok(y)
-} 
\ No newline at end of file
+}
@@ -0,0 +1,405 @@
+//! Banded and block-diagonal matrices
+//!
+//! Hydrostatic restoring and structural coupling matrices of beam and
+//! generalized-mode models only couple neighbouring degrees of freedom, and
+//! the matrices of a multi-body system without body-to-body coupling are
+//! block diagonal. [`BandMatrix`] stores the `lower + upper + 1` diagonals
+//! and [`BandLU`] factorizes them with partial pivoting in O(n·l·(l+u))
+//! operations; [`BlockDiagonalMatrix`] stores the dense diagonal blocks and
+//! solves each on its own.
+
+use super::*;
+use crate::sparse::SparseScalar;
+
+/// Square matrix with `lower` subdiagonals and `upper` superdiagonals
+///
+/// Row `i` stores columns `i - lower ..= i + upper`, clipped to the matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandMatrix<T = f64> {
+    n: usize,
+    lower: usize,
+    upper: usize,
+    data: Vec<T>,
+}
+
+impl<T: SparseScalar> BandMatrix<T> {
+    /// Zero matrix of size `n` with the given bandwidths
+    pub fn new(n: usize, lower: usize, upper: usize) -> Self {
+        Self {
+            n,
+            lower,
+            upper,
+            data: vec![T::zero(); n * (lower + upper + 1)],
+        }
+    }
+
+    /// Create from row-major dense data; entries outside the band must be zero
+    pub fn from_row_major(n: usize, lower: usize, upper: usize, data: &[T]) -> Result<Self> {
+        if data.len() != n * n {
+            return Err(MatrixError::DimensionMismatch { expected: n * n, actual: data.len() });
+        }
+        let mut matrix = Self::new(n, lower, upper);
+        for (k, &value) in data.iter().enumerate() {
+            let (i, j) = (k / n, k % n);
+            if matrix.in_band(i, j) {
+                let index = matrix.index(i, j);
+                matrix.data[index] = value;
+            } else if !value.is_zero() {
+                return Err(MatrixError::SolverError {
+                    message: format!("Entry ({}, {}) lies outside the band", i, j),
+                });
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Size of the matrix
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// Number of subdiagonals and superdiagonals
+    pub fn bandwidths(&self) -> (usize, usize) {
+        (self.lower, self.upper)
+    }
+
+    /// Get element at position (i, j), zero outside the band
+    pub fn get(&self, i: usize, j: usize) -> Result<T> {
+        self.check_index(i, j)?;
+        Ok(if self.in_band(i, j) { self.data[self.index(i, j)] } else { T::zero() })
+    }
+
+    /// Set element at position (i, j), which must lie in the band
+    pub fn set(&mut self, i: usize, j: usize, value: T) -> Result<()> {
+        self.check_index(i, j)?;
+        if !self.in_band(i, j) {
+            return Err(MatrixError::SolverError {
+                message: format!("Entry ({}, {}) lies outside the band", i, j),
+            });
+        }
+        let index = self.index(i, j);
+        self.data[index] = value;
+        Ok(())
+    }
+
+    /// Dense row-major copy of the entries
+    pub fn to_row_major(&self) -> Vec<T> {
+        let mut data = vec![T::zero(); self.n * self.n];
+        for i in 0..self.n {
+            for j in self.columns(i) {
+                data[i * self.n + j] = self.data[self.index(i, j)];
+            }
+        }
+        data
+    }
+
+    /// Matrix-vector product A x
+    pub fn matvec(&self, x: &[T]) -> Result<Vec<T>> {
+        if x.len() != self.n {
+            return Err(MatrixError::DimensionMismatch { expected: self.n, actual: x.len() });
+        }
+        Ok(parallel::map_rows(self.n, self.data.len(), |i| {
+            self.columns(i).fold(T::zero(), |sum, j| sum + self.data[self.index(i, j)] * x[j])
+        }))
+    }
+
+    /// LU factorization with partial pivoting
+    pub fn lu(&self) -> Result<BandLU<T>> {
+        BandLU::new(self)
+    }
+
+    /// Solve A x = b by banded LU
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>> {
+        self.lu()?.solve(b)
+    }
+
+    fn in_band(&self, i: usize, j: usize) -> bool {
+        j + self.lower >= i && j <= i + self.upper
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * (self.lower + self.upper + 1) + j + self.lower - i
+    }
+
+    fn columns(&self, i: usize) -> std::ops::Range<usize> {
+        i.saturating_sub(self.lower)..(i + self.upper + 1).min(self.n)
+    }
+
+    fn check_index(&self, i: usize, j: usize) -> Result<()> {
+        if i >= self.n || j >= self.n {
+            return Err(MatrixError::InvalidDimensions { rows: self.n, cols: self.n });
+        }
+        Ok(())
+    }
+}
+
+/// Banded LU factorization P A = L U with partial pivoting
+///
+/// Row interchanges widen U to `lower + upper` superdiagonals, as in LAPACK's
+/// `xGBTRF`; L keeps `lower` multipliers per column.
+#[derive(Debug, Clone)]
+pub struct BandLU<T = f64> {
+    n: usize,
+    lower: usize,
+    /// Superdiagonals of U
+    width: usize,
+    /// Row `k` of U, columns `k ..= k + width`
+    upper: Vec<T>,
+    /// Multipliers of step `k` for rows `k + 1 ..= k + lower`
+    multipliers: Vec<T>,
+    /// Row exchanged with row `k` at step `k`
+    pivots: Vec<usize>,
+}
+
+impl<T: SparseScalar> BandLU<T> {
+    /// Factorize a banded matrix
+    pub fn new(a: &BandMatrix<T>) -> Result<Self> {
+        let (n, lower, width) = (a.n, a.lower, a.lower + a.upper);
+        // Working row i holds columns i - lower ..= i + width
+        let stride = lower + width + 1;
+        let at = |i: usize, j: usize| i * stride + j + lower - i;
+        let mut work = vec![T::zero(); n * stride];
+        for i in 0..n {
+            for j in a.columns(i) {
+                work[at(i, j)] = a.data[a.index(i, j)];
+            }
+        }
+        let scale = a.data.iter().fold(0.0_f64, |max, value| max.max(value.modulus()));
+
+        let mut multipliers = vec![T::zero(); n * lower];
+        let mut pivots = Vec::with_capacity(n);
+        for k in 0..n {
+            let last_row = (k + lower).min(n - 1);
+            let last_col = (k + width).min(n - 1);
+            let pivot = (k..=last_row)
+                .max_by(|&p, &q| work[at(p, k)].modulus().total_cmp(&work[at(q, k)].modulus()))
+                .unwrap();
+            if work[at(pivot, k)].modulus() <= 1e-14 * scale {
+                return Err(MatrixError::SingularMatrix);
+            }
+            if pivot != k {
+                for c in k..=last_col {
+                    work.swap(at(k, c), at(pivot, c));
+                }
+            }
+            pivots.push(pivot);
+            let diagonal = work[at(k, k)];
+            for i in k + 1..=last_row {
+                let factor = work[at(i, k)] / diagonal;
+                multipliers[k * lower + i - k - 1] = factor;
+                for c in k + 1..=last_col {
+                    let u = work[at(k, c)];
+                    work[at(i, c)] -= factor * u;
+                }
+            }
+        }
+
+        let upper = (0..n)
+            .flat_map(|k| (k..=k + width).map(move |c| (k, c)))
+            .map(|(k, c)| if c < n { work[at(k, c)] } else { T::zero() })
+            .collect();
+        Ok(Self { n, lower, width, upper, multipliers, pivots })
+    }
+
+    /// Solve A x = b with the stored factors
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>> {
+        if b.len() != self.n {
+            return Err(MatrixError::DimensionMismatch { expected: self.n, actual: b.len() });
+        }
+        let n = self.n;
+        let mut x = b.to_vec();
+        for k in 0..n {
+            x.swap(k, self.pivots[k]);
+            let (head, tail) = x.split_at_mut(k + 1);
+            let factors = &self.multipliers[k * self.lower..(k + 1) * self.lower];
+            tail.iter_mut().zip(factors).for_each(|(xi, &factor)| *xi -= factor * head[k]);
+        }
+        let stride = self.width + 1;
+        for k in (0..n).rev() {
+            let row = &self.upper[k * stride..(k + 1) * stride];
+            let sum = (k + 1..=(k + self.width).min(n - 1)).fold(x[k], |sum, c| sum - row[c - k] * x[c]);
+            x[k] = sum / row[0];
+        }
+        Ok(x)
+    }
+}
+
+impl<T: SparseScalar> Preconditioner<T> for BandLU<T> {
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.solve(x)
+    }
+}
+
+/// Square matrix of dense square blocks on the diagonal, zero elsewhere
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDiagonalMatrix<T = f64> {
+    /// First unknown of each block, and the size as the last entry
+    offsets: Vec<usize>,
+    /// Row-major entries of each block
+    blocks: Vec<Vec<T>>,
+}
+
+impl<T: SparseScalar> BlockDiagonalMatrix<T> {
+    /// Create from `(size, row-major entries)` of each diagonal block
+    pub fn new(blocks: Vec<(usize, Vec<T>)>) -> Result<Self> {
+        let mut offsets = vec![0];
+        let mut data = Vec::with_capacity(blocks.len());
+        for (size, block) in blocks {
+            if block.len() != size * size {
+                return Err(MatrixError::DimensionMismatch { expected: size * size, actual: block.len() });
+            }
+            offsets.push(offsets.last().unwrap() + size);
+            data.push(block);
+        }
+        Ok(Self { offsets, blocks: data })
+    }
+
+    /// Size of the matrix
+    pub fn size(&self) -> usize {
+        *self.offsets.last().unwrap()
+    }
+
+    /// Number of diagonal blocks
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Unknowns of block `k`
+    pub fn block_range(&self, k: usize) -> std::ops::Range<usize> {
+        self.offsets[k]..self.offsets[k + 1]
+    }
+
+    /// Row-major entries of block `k`
+    pub fn block(&self, k: usize) -> &[T] {
+        &self.blocks[k]
+    }
+
+    /// Get element at position (i, j), zero outside the blocks
+    pub fn get(&self, i: usize, j: usize) -> Result<T> {
+        let n = self.size();
+        if i >= n || j >= n {
+            return Err(MatrixError::InvalidDimensions { rows: n, cols: n });
+        }
+        Ok(self.entry(i, j))
+    }
+
+    /// Dense row-major copy of the entries
+    pub fn to_row_major(&self) -> Vec<T> {
+        let n = self.size();
+        let mut data = vec![T::zero(); n * n];
+        for (k, block) in self.blocks.iter().enumerate() {
+            let range = self.block_range(k);
+            for (r, row) in block.chunks(range.len().max(1)).enumerate() {
+                let start = (range.start + r) * n + range.start;
+                data[start..start + row.len()].copy_from_slice(row);
+            }
+        }
+        data
+    }
+
+    /// Matrix-vector product A x, block by block
+    pub fn matvec(&self, x: &[T]) -> Result<Vec<T>> {
+        if x.len() != self.size() {
+            return Err(MatrixError::DimensionMismatch { expected: self.size(), actual: x.len() });
+        }
+        let mut y = Vec::with_capacity(x.len());
+        for (k, block) in self.blocks.iter().enumerate() {
+            let local = &x[self.block_range(k)];
+            y.extend(block.chunks(local.len().max(1)).map(|row| row.iter().zip(local).fold(T::zero(), |sum, (&a, &xj)| sum + a * xj)));
+        }
+        Ok(y)
+    }
+
+    /// LU factorization of every block, the exact inverse as a block-Jacobi preconditioner
+    pub fn factorize(&self) -> Result<BlockJacobi<T>> {
+        let blocks = (0..self.block_count()).map(|k| self.block_range(k).collect()).collect();
+        BlockJacobi::new(self.size(), blocks, |i, j| self.entry(i, j))
+    }
+
+    /// Solve A x = b block by block
+    pub fn solve(&self, b: &[T]) -> Result<Vec<T>> {
+        self.factorize()?.apply(b)
+    }
+
+    fn entry(&self, i: usize, j: usize) -> T {
+        let k = self.offsets.partition_point(|&offset| offset <= i) - 1;
+        let range = self.block_range(k);
+        if range.contains(&j) {
+            self.blocks[k][(i - range.start) * range.len() + j - range.start]
+        } else {
+            T::zero()
+        }
+    }
+}
+
+impl<T: SparseScalar> LinearOperator<T> for BandMatrix<T> {
+    fn rows(&self) -> usize {
+        self.n
+    }
+
+    fn cols(&self) -> usize {
+        self.n
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.matvec(x)
+    }
+}
+
+impl<T: SparseScalar> LinearOperator<T> for BlockDiagonalMatrix<T> {
+    fn rows(&self) -> usize {
+        self.size()
+    }
+
+    fn cols(&self) -> usize {
+        self.size()
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.matvec(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_banded_and_block_diagonal_solves() {
+        // Pentadiagonal beam-like stiffness with small diagonals, so pivoting swaps rows
+        let (n, lower, upper) = (30, 2, 1);
+        let dense: Vec<f64> = (0..n * n)
+            .map(|k| {
+                let (i, j) = (k / n, k % n);
+                if j + lower >= i && j <= i + upper {
+                    if i == j { 0.1 } else { ((i * 7 + j * 3) % 11) as f64 - 5.0 }
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let band = BandMatrix::from_row_major(n, lower, upper, &dense).unwrap();
+        assert_eq!(band.to_row_major(), dense);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 * 0.7).cos()).collect();
+        let x = band.solve(&b).unwrap();
+        let expected = backend::lu_solve_multi(&Matrix::from_vec(n, n, dense.clone()).unwrap(), std::slice::from_ref(&b)).unwrap().remove(0);
+        assert!(x.iter().zip(&expected).all(|(p, q)| (p - q).abs() < 1e-9 * q.abs().max(1.0)));
+        assert!(band.matvec(&x).unwrap().iter().zip(&b).all(|(p, q)| (p - q).abs() < 1e-9));
+        assert!(BandMatrix::from_row_major(n, 1, 1, &dense).is_err());
+        assert!(BandMatrix::<f64>::new(4, 1, 1).solve(&[1.0; 4]).is_err());
+
+        // Two uncoupled bodies
+        let block = |size: usize, shift: f64| -> Vec<Complex64> {
+            (0..size * size).map(|k| Complex64::new(if k % (size + 1) == 0 { 4.0 } else { 1.0 / (k + 1) as f64 }, shift)).collect()
+        };
+        let system = BlockDiagonalMatrix::new(vec![(6, block(6, 0.5)), (3, block(3, -0.2))]).unwrap();
+        assert_eq!((system.size(), system.block_count()), (9, 2));
+        let bz: Vec<Complex64> = (0..9).map(|i| Complex64::new(1.0, i as f64)).collect();
+        let xz = system.solve(&bz).unwrap();
+        assert!(system.matvec(&xz).unwrap().iter().zip(&bz).all(|(p, q)| (p - q).norm() < 1e-12));
+        let dense = ComplexMatrix::from_vec(9, 9, system.to_row_major()).unwrap();
+        assert!(dense.matvec(&xz).unwrap().iter().zip(&bz).all(|(p, q)| (p - q).norm() < 1e-12));
+        assert_eq!(system.get(1, 7).unwrap(), Complex64::new(0.0, 0.0));
+    }
+}
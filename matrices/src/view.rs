@@ -0,0 +1,334 @@
+//! Borrowed views of row-major matrices
+//!
+//! Multi-body systems are assembled body pair by body pair and symmetry
+//! partitions work on quarter or half blocks of the influence matrix.
+//! [`MatrixView`] and [`MatrixViewMut`] address a rectangular block of a
+//! row-major buffer through its row stride, so blocks, rows and columns are
+//! read and written in place without copying them out into a new matrix.
+//! Disjoint mutable views from [`MatrixViewMut::split_at_row`] let blocks be
+//! filled in parallel.
+
+use super::*;
+use crate::sparse::SparseScalar;
+use num_complex::Complex64;
+
+/// Read-only block of a row-major matrix
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T = f64> {
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    stride: usize,
+}
+
+/// Mutable block of a row-major matrix
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T = f64> {
+    data: &'a mut [T],
+    rows: usize,
+    cols: usize,
+    stride: usize,
+}
+
+/// Length of the buffer spanned by `rows × cols` entries with row stride `stride`
+fn span(rows: usize, cols: usize, stride: usize) -> usize {
+    if rows == 0 || cols == 0 {
+        0
+    } else {
+        (rows - 1) * stride + cols
+    }
+}
+
+/// Check that block `rows × cols` at (`row`, `col`) fits a `total_rows × total_cols` matrix
+fn check_block(total: (usize, usize), row: usize, col: usize, rows: usize, cols: usize) -> Result<()> {
+    if row + rows > total.0 || col + cols > total.1 {
+        return Err(MatrixError::InvalidDimensions { rows: total.0, cols: total.1 });
+    }
+    Ok(())
+}
+
+impl<'a, T: Copy> MatrixView<'a, T> {
+    /// View `rows × cols` entries of `data` whose rows start `stride` entries apart
+    pub fn new(data: &'a [T], rows: usize, cols: usize, stride: usize) -> Result<Self> {
+        if cols > stride && rows > 1 {
+            return Err(MatrixError::InvalidDimensions { rows, cols });
+        }
+        if data.len() < span(rows, cols, stride) {
+            return Err(MatrixError::DimensionMismatch {
+                expected: span(rows, cols, stride),
+                actual: data.len(),
+            });
+        }
+        Ok(Self { data, rows, cols, stride })
+    }
+
+    /// Get matrix dimensions
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Get element at position (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Result<T> {
+        if i >= self.rows || j >= self.cols {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols });
+        }
+        Ok(self.data[i * self.stride + j])
+    }
+
+    /// Entries of row `i`
+    pub fn row(&self, i: usize) -> &'a [T] {
+        &self.data[i * self.stride..i * self.stride + self.cols]
+    }
+
+    /// Entries of column `j`, top to bottom
+    pub fn column(&self, j: usize) -> impl Iterator<Item = T> + 'a {
+        let data = self.data;
+        let stride = self.stride;
+        (0..self.rows).map(move |i| data[i * stride + j])
+    }
+
+    /// Block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block(&self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixView<'a, T>> {
+        check_block((self.rows, self.cols), row, col, rows, cols)?;
+        let start = (row * self.stride + col).min(self.data.len());
+        let end = start + span(rows, cols, self.stride);
+        Ok(MatrixView { data: &self.data[start..end], rows, cols, stride: self.stride })
+    }
+
+    /// Dense row-major copy of the entries
+    pub fn to_row_major(&self) -> Vec<T> {
+        (0..self.rows).flat_map(|i| self.row(i).iter().copied()).collect()
+    }
+}
+
+impl<T: SparseScalar> MatrixView<'_, T> {
+    /// Matrix-vector product A x
+    pub fn matvec(&self, x: &[T]) -> Result<Vec<T>> {
+        if x.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch { expected: self.cols, actual: x.len() });
+        }
+        Ok(parallel::map_rows(self.rows, self.rows * self.cols, |i| {
+            self.row(i).iter().zip(x).fold(T::zero(), |sum, (&a, &xj)| sum + a * xj)
+        }))
+    }
+}
+
+impl MatrixView<'_, f64> {
+    /// Copy into a new matrix
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix { rows: self.rows, cols: self.cols, data: self.to_row_major() }
+    }
+}
+
+impl MatrixView<'_, Complex64> {
+    /// Copy into a new matrix
+    pub fn to_matrix(&self) -> ComplexMatrix {
+        ComplexMatrix { rows: self.rows, cols: self.cols, data: self.to_row_major() }
+    }
+}
+
+impl<'a, T: Copy> MatrixViewMut<'a, T> {
+    /// Mutable view of `rows × cols` entries of `data` whose rows start `stride` entries apart
+    pub fn new(data: &'a mut [T], rows: usize, cols: usize, stride: usize) -> Result<Self> {
+        MatrixView::new(&*data, rows, cols, stride)?;
+        Ok(Self { data, rows, cols, stride })
+    }
+
+    /// Get matrix dimensions
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Read-only view of the same entries
+    pub fn as_view(&self) -> MatrixView<'_, T> {
+        MatrixView { data: self.data, rows: self.rows, cols: self.cols, stride: self.stride }
+    }
+
+    /// Get element at position (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Result<T> {
+        self.as_view().get(i, j)
+    }
+
+    /// Set element at position (i, j)
+    pub fn set(&mut self, i: usize, j: usize, value: T) -> Result<()> {
+        if i >= self.rows || j >= self.cols {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols });
+        }
+        self.data[i * self.stride + j] = value;
+        Ok(())
+    }
+
+    /// Mutable entries of row `i`
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i * self.stride..i * self.stride + self.cols]
+    }
+
+    /// Mutable block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixViewMut<'_, T>> {
+        check_block((self.rows, self.cols), row, col, rows, cols)?;
+        let start = (row * self.stride + col).min(self.data.len());
+        let end = start + span(rows, cols, self.stride);
+        Ok(MatrixViewMut { data: &mut self.data[start..end], rows, cols, stride: self.stride })
+    }
+
+    /// Split into rows `0..row` and `row..`, which can be written independently
+    pub fn split_at_row(self, row: usize) -> Result<(MatrixViewMut<'a, T>, MatrixViewMut<'a, T>)> {
+        if row > self.rows {
+            return Err(MatrixError::InvalidDimensions { rows: self.rows, cols: self.cols });
+        }
+        let split = (row * self.stride).min(self.data.len());
+        let (top, bottom) = self.data.split_at_mut(split);
+        Ok((
+            MatrixViewMut { data: top, rows: row, cols: self.cols, stride: self.stride },
+            MatrixViewMut { data: bottom, rows: self.rows - row, cols: self.cols, stride: self.stride },
+        ))
+    }
+
+    /// Set every entry to `entry(i, j)`
+    pub fn fill_with<F>(&mut self, entry: F)
+    where
+        F: Fn(usize, usize) -> T,
+    {
+        for i in 0..self.rows {
+            self.row_mut(i).iter_mut().enumerate().for_each(|(j, value)| *value = entry(i, j));
+        }
+    }
+
+    /// Copy the entries of a view of the same dimensions
+    pub fn copy_from(&mut self, source: &MatrixView<'_, T>) -> Result<()> {
+        if source.dimensions() != self.dimensions() {
+            return Err(MatrixError::DimensionMismatch {
+                expected: self.rows * self.cols,
+                actual: source.rows * source.cols,
+            });
+        }
+        for i in 0..self.rows {
+            self.row_mut(i).copy_from_slice(source.row(i));
+        }
+        Ok(())
+    }
+}
+
+impl Matrix {
+    /// View of the whole matrix
+    pub fn view(&self) -> MatrixView<'_> {
+        MatrixView { data: &self.data, rows: self.rows, cols: self.cols, stride: self.cols }
+    }
+
+    /// Mutable view of the whole matrix
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_> {
+        MatrixViewMut { data: &mut self.data, rows: self.rows, cols: self.cols, stride: self.cols }
+    }
+
+    /// View of the block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block_view(&self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixView<'_>> {
+        self.view().block(row, col, rows, cols)
+    }
+
+    /// Mutable view of the block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block_view_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixViewMut<'_>> {
+        check_block((self.rows, self.cols), row, col, rows, cols)?;
+        let stride = self.cols;
+        let start = (row * stride + col).min(self.data.len());
+        let end = start + span(rows, cols, stride);
+        Ok(MatrixViewMut { data: &mut self.data[start..end], rows, cols, stride })
+    }
+
+    /// View of row `i` as a 1 × cols block
+    pub fn row_view(&self, i: usize) -> Result<MatrixView<'_>> {
+        self.block_view(i, 0, 1, self.cols)
+    }
+
+    /// View of column `j` as a rows × 1 block
+    pub fn column_view(&self, j: usize) -> Result<MatrixView<'_>> {
+        self.block_view(0, j, self.rows, 1)
+    }
+}
+
+impl ComplexMatrix {
+    /// View of the whole matrix
+    pub fn view(&self) -> MatrixView<'_, Complex64> {
+        MatrixView { data: &self.data, rows: self.rows, cols: self.cols, stride: self.cols }
+    }
+
+    /// Mutable view of the whole matrix
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, Complex64> {
+        MatrixViewMut { data: &mut self.data, rows: self.rows, cols: self.cols, stride: self.cols }
+    }
+
+    /// View of the block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block_view(&self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixView<'_, Complex64>> {
+        self.view().block(row, col, rows, cols)
+    }
+
+    /// Mutable view of the block of `rows × cols` entries starting at (`row`, `col`)
+    pub fn block_view_mut(&mut self, row: usize, col: usize, rows: usize, cols: usize) -> Result<MatrixViewMut<'_, Complex64>> {
+        check_block((self.rows, self.cols), row, col, rows, cols)?;
+        let stride = self.cols;
+        let start = (row * stride + col).min(self.data.len());
+        let end = start + span(rows, cols, stride);
+        Ok(MatrixViewMut { data: &mut self.data[start..end], rows, cols, stride })
+    }
+
+    /// View of row `i` as a 1 × cols block
+    pub fn row_view(&self, i: usize) -> Result<MatrixView<'_, Complex64>> {
+        self.block_view(i, 0, 1, self.cols)
+    }
+
+    /// View of column `j` as a rows × 1 block
+    pub fn column_view(&self, j: usize) -> Result<MatrixView<'_, Complex64>> {
+        self.block_view(0, j, self.rows, 1)
+    }
+}
+
+impl<T: SparseScalar> LinearOperator<T> for MatrixView<'_, T> {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &[T]) -> Result<Vec<T>> {
+        self.matvec(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views_assemble_blocks_in_place() {
+        // Two bodies of 3 and 2 unknowns: fill each body-pair block through a view
+        let sizes = [3, 2];
+        let offsets = [0, 3];
+        let mut system = ComplexMatrix::new(5, 5);
+        for (a, (&ra, &na)) in offsets.iter().zip(&sizes).enumerate() {
+            for (b, (&rb, &nb)) in offsets.iter().zip(&sizes).enumerate() {
+                let mut block = system.block_view_mut(ra, rb, na, nb).unwrap();
+                block.fill_with(|i, j| Complex64::new((10 * a + b) as f64, (i * nb + j) as f64));
+            }
+        }
+        assert_eq!(system.get(4, 1).unwrap(), Complex64::new(10.0, 4.0));
+        assert_eq!(system.block_view(3, 0, 2, 3).unwrap().to_matrix().data[3], Complex64::new(10.0, 3.0));
+
+        // Disjoint halves written independently
+        let mut m = Matrix::new(4, 3);
+        let (mut top, mut bottom) = m.view_mut().split_at_row(1).unwrap();
+        top.row_mut(0).copy_from_slice(&[1.0, 2.0, 3.0]);
+        bottom.set(2, 1, 7.0).unwrap();
+        let source = Matrix::from_vec(1, 2, vec![8.0, 9.0]).unwrap();
+        bottom.block_mut(0, 1, 1, 2).unwrap().copy_from(&source.view()).unwrap();
+        assert_eq!(m.data, vec![1.0, 2.0, 3.0, 0.0, 8.0, 9.0, 0.0, 0.0, 0.0, 0.0, 7.0, 0.0]);
+
+        let inner = m.block_view(1, 1, 3, 2).unwrap();
+        assert_eq!(inner.column(0).collect::<Vec<_>>(), vec![8.0, 0.0, 7.0]);
+        assert_eq!(inner.matvec(&[1.0, 1.0]).unwrap(), vec![17.0, 0.0, 7.0]);
+        assert_eq!(m.column_view(2).unwrap().to_row_major(), vec![3.0, 9.0, 0.0, 0.0]);
+        assert_eq!(m.row_view(0).unwrap().row(0), &[1.0, 2.0, 3.0]);
+        assert!(m.block_view(2, 2, 3, 1).is_err());
+        assert!(inner.block(0, 1, 3, 2).is_err());
+    }
+}
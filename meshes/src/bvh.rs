@@ -0,0 +1,400 @@
+//! Bounding-volume hierarchy over mesh panels
+//!
+//! A [`PanelTree`] splits the panels recursively at the median of their
+//! centroids along the longest axis, storing an axis-aligned box per node.
+//! Queries descend only into boxes that can matter, giving logarithmic
+//! nearest-panel and ray queries. Quads are held as two triangles but
+//! reported by their panel index.
+//!
+//! Inside tests cast rays pointing downward, so a wetted mesh left open at
+//! the waterplane z = 0 classifies points below it as if it were closed.
+
+use super::*;
+use std::ops::Range;
+
+/// Triangles per leaf
+const LEAF_SIZE: usize = 4;
+
+/// Ray directions for inside tests, skewed to avoid grid-aligned edges
+const INSIDE_RAYS: [[f64; 3]; 3] = [[0.2113, 0.0917, -0.9731], [-0.1573, 0.2791, -0.9473], [0.0587, -0.3311, -0.9418]];
+
+/// Panel found by a query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelHit {
+    /// Panel index, triangles first as in [`Mesh::polygons`]
+    pub panel: usize,
+    /// Nearest or intersection point on the panel
+    pub point: Point,
+    /// Distance from the query point or ray origin
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Bounds {
+    fn of(points: &[Point]) -> Self {
+        points.iter().fold(
+            Self {
+                min: Point::from([f64::INFINITY; 3]),
+                max: Point::from([f64::NEG_INFINITY; 3]),
+            },
+            |b, p| b.merge(&Self { min: *p, max: *p }),
+        )
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    fn distance(&self, p: &Point) -> f64 {
+        (p.coords - p.coords.sup(&self.min.coords).inf(&self.max.coords)).norm()
+    }
+
+    fn gap(&self, other: &Self) -> f64 {
+        let below = (other.min - self.max).sup(&Vector::zeros());
+        let above = (self.min - other.max).sup(&Vector::zeros());
+        (below + above).norm()
+    }
+
+    /// Entry distance of a ray, if it meets the box before `limit`
+    fn ray_entry(&self, origin: &Point, inverse: &Vector, limit: f64) -> Option<f64> {
+        let (mut near, mut far) = (0.0_f64, limit);
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - origin[axis]) * inverse[axis];
+            let t1 = (self.max[axis] - origin[axis]) * inverse[axis];
+            // NaN from 0 × ∞ on a face plane leaves the interval unchanged
+            let (lo, hi) = if t0 <= t1 { (t0, t1) } else { (t1, t0) };
+            near = if lo > near { lo } else { near };
+            far = if hi < far { hi } else { far };
+        }
+        (near <= far).then_some(near)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Bounds,
+    children: Option<(usize, usize)>,
+    triangles: Range<usize>,
+}
+
+/// Bounding-volume hierarchy over the panels of a mesh
+#[derive(Debug, Clone)]
+pub struct PanelTree {
+    /// Triangle corners and owning panel, in leaf order
+    triangles: Vec<([Point; 3], usize)>,
+    nodes: Vec<Node>,
+}
+
+impl PanelTree {
+    /// Build the hierarchy over the panels of `mesh`
+    pub fn new(mesh: &Mesh) -> Self {
+        let mut triangles = Vec::with_capacity(mesh.faces.len() + 2 * mesh.quads.len());
+        for (panel, polygon) in mesh.polygons().enumerate() {
+            let corners: Vec<Point> = polygon.iter().map(|&v| mesh.vertices[v]).collect();
+            triangles.push(([corners[0], corners[1], corners[2]], panel));
+            if let [a, _, c, d] = corners[..] {
+                triangles.push(([a, c, d], panel));
+            }
+        }
+        let mut tree = Self { triangles, nodes: Vec::new() };
+        if !tree.triangles.is_empty() {
+            tree.split(0..tree.triangles.len());
+        }
+        tree
+    }
+
+    fn split(&mut self, range: Range<usize>) -> usize {
+        let bounds = Bounds::of(&self.triangles[range.clone()].iter().flat_map(|(t, _)| *t).collect::<Vec<_>>());
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            children: None,
+            triangles: range.clone(),
+        });
+        if range.len() > LEAF_SIZE {
+            let centre = |t: &[Point; 3]| (t[0].coords + t[1].coords + t[2].coords) / 3.0;
+            let extent = bounds.max - bounds.min;
+            let axis = extent.imax();
+            self.triangles[range.clone()].sort_by(|a, b| centre(&a.0)[axis].total_cmp(&centre(&b.0)[axis]));
+            let middle = range.start + range.len() / 2;
+            let left = self.split(range.start..middle);
+            let right = self.split(middle..range.end);
+            self.nodes[index].children = Some((left, right));
+        }
+        index
+    }
+
+    /// Number of panels indexed
+    pub fn n_panels(&self) -> usize {
+        self.triangles.iter().map(|&(_, panel)| panel + 1).max().unwrap_or(0)
+    }
+
+    /// Corners (min, max) of the box around all panels
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.nodes.first().map(|node| (node.bounds.min, node.bounds.max))
+    }
+
+    /// Nearest point of the mesh surface to `p`
+    pub fn nearest_panel(&self, p: &Point) -> Option<PanelHit> {
+        let mut best: Option<PanelHit> = None;
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop().filter(|_| !self.nodes.is_empty()) {
+            let node = &self.nodes[n];
+            if best.is_some_and(|b| node.bounds.distance(p) >= b.distance) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    // Visit the nearer child first so the bound tightens early
+                    let (dl, dr) = (self.nodes[left].bounds.distance(p), self.nodes[right].bounds.distance(p));
+                    stack.extend(if dl < dr { [right, left] } else { [left, right] });
+                }
+                None => {
+                    for (corners, panel) in &self.triangles[node.triangles.clone()] {
+                        let point = closest_on_triangle(p, &corners[0], &corners[1], &corners[2]);
+                        let distance = (point - p).norm();
+                        if best.is_none_or(|b| distance < b.distance) {
+                            best = Some(PanelHit { panel: *panel, point, distance });
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// First panel hit by the ray from `origin` along `direction`
+    pub fn ray_cast(&self, origin: &Point, direction: &Vector) -> Option<PanelHit> {
+        let mut best: Option<PanelHit> = None;
+        self.visit_ray(origin, direction, |hit, limit| {
+            if hit.distance < *limit {
+                *limit = hit.distance;
+                best = Some(hit);
+            }
+        });
+        best
+    }
+
+    /// Every panel crossing of the ray, nearest first
+    pub fn ray_hits(&self, origin: &Point, direction: &Vector) -> Vec<PanelHit> {
+        let mut hits = Vec::new();
+        self.visit_ray(origin, direction, |hit, _| hits.push(hit));
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Whether `p` lies inside the closed surface, by majority over three ray parities
+    pub fn contains(&self, p: &Point) -> bool {
+        let odd = INSIDE_RAYS
+            .iter()
+            .filter(|d| self.ray_hits(p, &Vector::new(d[0], d[1], d[2])).len() % 2 == 1)
+            .count();
+        odd >= 2
+    }
+
+    /// Pairs of distinct panels whose bounding boxes lie within `distance`
+    ///
+    /// A superset of the panel pairs closer than `distance`, in increasing
+    /// order, for near-field quadrature or admissibility checks.
+    pub fn near_pairs(&self, distance: f64) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        if self.nodes.is_empty() {
+            return pairs;
+        }
+        let mut stack = vec![(0, 0)];
+        while let Some((a, b)) = stack.pop() {
+            let (na, nb) = (&self.nodes[a], &self.nodes[b]);
+            if na.bounds.gap(&nb.bounds) > distance {
+                continue;
+            }
+            match (na.children, nb.children) {
+                (None, None) => {
+                    for (ta, pa) in &self.triangles[na.triangles.clone()] {
+                        for (tb, pb) in &self.triangles[nb.triangles.clone()] {
+                            if pa != pb && Bounds::of(ta).gap(&Bounds::of(tb)) <= distance {
+                                pairs.push((*pa.min(pb), *pa.max(pb)));
+                            }
+                        }
+                    }
+                }
+                // Each unordered node pair is visited once: a node with itself splits into three
+                (Some((l, r)), _) if a == b => stack.extend([(l, l), (l, r), (r, r)]),
+                (Some((l, r)), _) if nb.children.is_none() || na.triangles.len() >= nb.triangles.len() => {
+                    stack.extend([(l, b), (r, b)]);
+                }
+                (_, Some((l, r))) => stack.extend([(a, l), (a, r)]),
+                (Some(_), None) => unreachable!("a leaf is never split"),
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Call `hit` for each triangle crossing along the ray, with the current search limit
+    fn visit_ray(&self, origin: &Point, direction: &Vector, mut hit: impl FnMut(PanelHit, &mut f64)) {
+        let length = direction.norm();
+        if self.nodes.is_empty() || length == 0.0 {
+            return;
+        }
+        let direction = direction / length;
+        let inverse = direction.map(|d| 1.0 / d);
+        let mut limit = f64::INFINITY;
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if node.bounds.ray_entry(origin, &inverse, limit).is_none() {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([left, right]),
+                None => {
+                    for (corners, panel) in &self.triangles[node.triangles.clone()] {
+                        if let Some(t) = ray_triangle(origin, &direction, corners) {
+                            let panel_hit = PanelHit {
+                                panel: *panel,
+                                point: origin + direction * t,
+                                distance: t,
+                            };
+                            hit(panel_hit, &mut limit);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Möller–Trumbore intersection distance of a unit ray with a triangle, if ahead of the origin
+fn ray_triangle(origin: &Point, direction: &Vector, [a, b, c]: &[Point; 3]) -> Option<f64> {
+    let (e1, e2) = (b - a, c - a);
+    let h = direction.cross(&e2);
+    let det = e1.dot(&h);
+    if det.abs() <= 1e-14 * e1.norm() * e2.norm() {
+        return None;
+    }
+    let s = origin - a;
+    let u = s.dot(&h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(&e1);
+    let v = direction.dot(&q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(&q) / det;
+    (t > 0.0).then_some(t)
+}
+
+/// Closest point of the triangle a, b, c to p, by Voronoi region of the corners and edges
+pub(crate) fn closest_on_triangle(p: &Point, a: &Point, b: &Point, c: &Point) -> Point {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denominator = va + vb + vc;
+    if denominator.abs() < f64::MIN_POSITIVE {
+        return *a;
+    }
+    a + ab * (vb / denominator) + ac * (vc / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_and_rays_on_box() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 8, 4, 2).unwrap();
+        let tree = PanelTree::new(&hull);
+        assert_eq!(tree.n_panels(), hull.n_panels());
+
+        let hit = tree.nearest_panel(&Point::new(0.3, 3.0, -0.4)).unwrap();
+        assert!((hit.distance - 2.0).abs() < 1e-12 && (hit.point - Point::new(0.3, 1.0, -0.4)).norm() < 1e-12);
+        assert!(hull.normals[hit.panel].y > 0.99);
+
+        let ray = tree.ray_cast(&Point::new(-10.0, 0.1, -0.3), &Vector::new(2.0, 0.0, 0.0)).unwrap();
+        assert!((ray.distance - 8.0).abs() < 1e-12 && hull.normals[ray.panel].x < -0.99);
+        assert_eq!(tree.ray_hits(&Point::new(-10.0, 0.1, -0.3), &Vector::x()).len(), 2);
+        assert!(tree.ray_cast(&Point::new(-10.0, 5.0, -0.3), &Vector::x()).is_none());
+
+        // Brute force agrees on scattered points
+        for k in 0..50 {
+            let p = Point::new((k as f64 * 0.37).sin() * 4.0, (k as f64 * 0.71).cos() * 3.0, (k as f64 * 0.13).sin() - 0.5);
+            let brute = hull
+                .faces
+                .iter()
+                .map(|&[a, b, c]| (closest_on_triangle(&p, &hull.vertices[a], &hull.vertices[b], &hull.vertices[c]) - p).norm())
+                .fold(f64::INFINITY, f64::min);
+            assert!((tree.nearest_panel(&p).unwrap().distance - brute).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_inside_closed_and_wetted_hulls() {
+        for surface in [HullSurface::Closed, HullSurface::Wetted] {
+            let hull = PredefinedGeometry::box_hull_with_surface(4.0, 2.0, 1.0, 4, 2, 2, surface).unwrap();
+            let tree = PanelTree::new(&hull);
+            assert!(tree.contains(&Point::new(0.0, 0.0, -0.5)));
+            assert!(tree.contains(&Point::new(1.9, -0.9, -0.05)));
+            assert!(!tree.contains(&Point::new(2.1, 0.0, -0.5)));
+            assert!(!tree.contains(&Point::new(0.0, 0.0, -1.2)));
+        }
+        let sphere = PredefinedGeometry::sphere(1.0, 24, 12).unwrap();
+        let tree = PanelTree::new(&sphere);
+        assert!(tree.contains(&Point::new(0.2, -0.3, 0.1)) && !tree.contains(&Point::new(0.0, 0.0, 1.5)));
+    }
+
+    #[test]
+    fn test_near_pairs_match_brute_force() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 8, 4, 2).unwrap();
+        let tree = PanelTree::new(&hull);
+        let bounds: Vec<Bounds> = hull
+            .faces
+            .iter()
+            .map(|f| Bounds::of(&f.map(|v| hull.vertices[v])))
+            .collect();
+        let mut expected = Vec::new();
+        for i in 0..bounds.len() {
+            for j in i + 1..bounds.len() {
+                if bounds[i].gap(&bounds[j]) <= 0.3 {
+                    expected.push((i, j));
+                }
+            }
+        }
+        assert_eq!(tree.near_pairs(0.3), expected);
+        assert!(tree.near_pairs(0.3).len() < bounds.len() * (bounds.len() - 1) / 2);
+    }
+}
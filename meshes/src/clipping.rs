@@ -0,0 +1,180 @@
+//! Clipping of hull meshes at a waterline
+//!
+//! Hulls supplied as full geometry, above and below water, are cut by the
+//! plane z = z0: panels below are kept, panels crossing it are trimmed and
+//! re-triangulated, and the cut edges are chained into the waterplane
+//! polygons. Intersection points are shared between neighbouring panels, so
+//! a watertight hull gives a wetted mesh whose open boundary is exactly the
+//! waterline.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Wetted part of a mesh and the waterplane it leaves open
+#[derive(Debug, Clone)]
+pub struct ClippedMesh {
+    /// Panels below the waterline, with normals as in the source mesh
+    pub wetted: Mesh,
+    /// Closed waterline loops at z = z0, counter-clockwise seen from above
+    ///
+    /// One loop per waterplane section, e.g. two for a catamaran; a hull
+    /// with a moonpool gives a clockwise inner loop.
+    pub waterplane: Vec<Vec<Point>>,
+}
+
+impl ClippedMesh {
+    /// Waterplane area, less any openings
+    pub fn waterplane_area(&self) -> f64 {
+        self.waterplane
+            .iter()
+            .map(|polygon| {
+                let n = polygon.len();
+                0.5 * (0..n)
+                    .map(|i| {
+                        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+                        a.x * b.y - b.x * a.y
+                    })
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+impl Mesh {
+    /// Cut the mesh at the plane z = z0
+    ///
+    /// Vertices within a relative 1e-9 of the plane are snapped onto it, and
+    /// panels lying in the plane, such as a deck lid, are dropped. Quads are
+    /// split into triangles before cutting.
+    pub fn clip_at_waterline(&self, z0: f64) -> Result<ClippedMesh> {
+        if !self.quads.is_empty() {
+            return self.triangulated()?.clip_at_waterline(z0);
+        }
+        let scale = self.vertices.iter().map(|p| p.coords.amax()).fold(1.0, f64::max);
+        let tol = 1e-9 * scale;
+        let heights: Vec<f64> = self
+            .vertices
+            .iter()
+            .map(|p| if (p.z - z0).abs() <= tol { 0.0 } else { p.z - z0 })
+            .collect();
+
+        let mut vertices = Vec::new();
+        let mut kept = HashMap::new();
+        let mut cuts = HashMap::new();
+        let mut on_plane = Vec::new();
+        let mut faces = Vec::new();
+        for face in &self.faces {
+            let h = face.map(|v| heights[v]);
+            if h.iter().all(|&h| h >= 0.0) {
+                continue;
+            }
+
+            // Trimmed polygon as new vertex indices, in the panel's winding
+            let mut polygon = Vec::with_capacity(4);
+            for i in 0..3 {
+                let (a, b) = (face[i], face[(i + 1) % 3]);
+                let (ha, hb) = (h[i], h[(i + 1) % 3]);
+                if ha <= 0.0 {
+                    polygon.push(*kept.entry(a).or_insert_with(|| {
+                        let mut p = self.vertices[a];
+                        if ha == 0.0 {
+                            p.z = z0;
+                        }
+                        vertices.push(p);
+                        on_plane.push(ha == 0.0);
+                        vertices.len() - 1
+                    }));
+                }
+                if (ha < 0.0 && hb > 0.0) || (ha > 0.0 && hb < 0.0) {
+                    polygon.push(*cuts.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                        let (pa, pb) = (self.vertices[a], self.vertices[b]);
+                        let mut p = pa + ha / (ha - hb) * (pb - pa);
+                        p.z = z0;
+                        vertices.push(p);
+                        on_plane.push(true);
+                        vertices.len() - 1
+                    }));
+                }
+            }
+            for k in 1..polygon.len() - 1 {
+                let triangle = [polygon[0], polygon[k], polygon[k + 1]];
+                let [a, b, c] = triangle.map(|v| vertices[v]);
+                if 0.5 * (b - a).cross(&(c - a)).norm() >= 1e-12 {
+                    faces.push(triangle);
+                }
+            }
+        }
+        if faces.is_empty() {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("No panels lie below the waterline z = {}", z0),
+            });
+        }
+
+        // Boundary edges in the plane, reversed so the loops run around the lid closing the hull
+        let edges: std::collections::HashSet<(usize, usize)> =
+            faces.iter().flat_map(|f| [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])]).collect();
+        let mut next: HashMap<usize, usize> = edges
+            .iter()
+            .filter(|&&(a, b)| on_plane[a] && on_plane[b] && !edges.contains(&(b, a)))
+            .map(|&(a, b)| (b, a))
+            .collect();
+        let mut starts: Vec<usize> = next.keys().copied().collect();
+        starts.sort_unstable();
+        let mut waterplane = Vec::new();
+        for start in starts {
+            let mut polygon = Vec::new();
+            let mut current = start;
+            while let Some(following) = next.remove(&current) {
+                polygon.push(vertices[current]);
+                current = following;
+            }
+            if !polygon.is_empty() {
+                waterplane.push(polygon);
+            }
+        }
+
+        Ok(ClippedMesh { wetted: Mesh::new(vertices, faces)?, waterplane })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn area(mesh: &Mesh) -> f64 {
+        mesh.faces
+            .iter()
+            .map(|f| 0.5 * (mesh.vertices[f[1]] - mesh.vertices[f[0]]).cross(&(mesh.vertices[f[2]] - mesh.vertices[f[0]])).norm())
+            .sum()
+    }
+
+    #[test]
+    fn test_box_clipped_at_grid_level() {
+        let (length, beam) = (4.0, 2.0);
+        let hull = PredefinedGeometry::box_hull(length, beam, 1.0, 4, 2, 2).unwrap();
+        let clipped = hull.clip_at_waterline(-0.5).unwrap();
+        assert!((area(&clipped.wetted) - (length * beam + 2.0 * (length + beam) * 0.5)).abs() < 1e-9);
+        assert_eq!(clipped.waterplane.len(), 1);
+        assert_eq!(clipped.waterplane[0].len(), 2 * (4 + 2));
+        assert!((clipped.waterplane_area() - length * beam).abs() < 1e-9);
+
+        // The deck lid of the closed hull lies in the plane and is dropped
+        let at_deck = hull.clip_at_waterline(0.0).unwrap();
+        assert!(at_deck.wetted.normals.iter().all(|n| n.z < 0.5));
+        assert!(hull.clip_at_waterline(-2.0).is_err());
+    }
+
+    #[test]
+    fn test_cylinder_cut_between_vertices() {
+        let (radius, n) = (1.5, 24);
+        let hull = PredefinedGeometry::cylinder(radius, 2.0, n, 4).unwrap();
+        let clipped = hull.clip_at_waterline(-0.8).unwrap();
+        let polygon = 0.5 * n as f64 * radius * radius * (2.0 * PI / n as f64).sin();
+        assert!((clipped.waterplane_area() - polygon).abs() < 1e-9);
+        assert!(clipped.waterplane[0].iter().all(|p| p.z == -0.8));
+        assert!(clipped.wetted.vertices.iter().all(|p| p.z <= -0.8));
+        let side = n as f64 * 2.0 * radius * (PI / n as f64).sin() * 1.2;
+        assert!((area(&clipped.wetted) - polygon - side).abs() < 1e-9);
+    }
+}
@@ -1,11 +1,40 @@
 //! Mesh collections
+//!
+//! Each named body holds one or more levels of detail, finest first. The
+//! mesh added with [`MeshCollection::add_mesh`] is the finest level; coarser
+//! ones are added directly or generated by decimation, and each
+//! [`MeshUsage`] selects the level it works on.
 
 use super::*;
+use crate::decimation::{DecimationOptions, DecimationReport};
 use std::collections::HashMap;
 
+/// Use a level of detail is selected for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshUsage {
+    /// Displaced volume, waterplane and restoring integrals; finest level by default
+    Hydrostatics,
+    /// Radiation and diffraction solves; coarsest level by default
+    Bem,
+    /// Visualization exports; finest level by default
+    Graphics,
+}
+
+/// Levels of detail of one body, by decreasing panel count
+struct Levels {
+    meshes: Vec<Mesh>,
+    selected: HashMap<MeshUsage, usize>,
+}
+
 /// Mesh collection
 pub struct MeshCollection {
-    meshes: HashMap<String, Mesh>,
+    meshes: HashMap<String, Levels>,
+}
+
+impl Default for MeshCollection {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MeshCollection {
@@ -15,30 +44,143 @@ impl MeshCollection {
             meshes: HashMap::new(),
         }
     }
-    
-    /// Add a mesh to the collection
+
+    /// Add a mesh to the collection as the finest level, replacing any levels under the name
     pub fn add_mesh(&mut self, name: String, mesh: Mesh) -> Result<()> {
-        self.meshes.insert(name, mesh);
+        let levels = Levels {
+            meshes: vec![mesh],
+            selected: HashMap::new(),
+        };
+        self.meshes.insert(name, levels);
         Ok(())
     }
-    
-    /// Get a mesh from the collection
+
+    /// Get the finest level of a mesh from the collection
     pub fn get_mesh(&self, name: &str) -> Result<&Mesh> {
-        self.meshes.get(name).ok_or_else(|| MeshError::MeshNotFound {
-            name: name.to_string(),
-        })
+        Ok(&self.levels(name)?.meshes[0])
     }
-    
-    /// Remove a mesh from the collection
+
+    /// Remove a mesh and all its levels from the collection
     pub fn remove_mesh(&mut self, name: &str) -> Result<()> {
         self.meshes.remove(name).ok_or_else(|| MeshError::MeshNotFound {
             name: name.to_string(),
         })?;
         Ok(())
     }
-    
+
     /// Get all mesh names
     pub fn mesh_names(&self) -> Vec<String> {
         self.meshes.keys().cloned().collect()
     }
-} 
\ No newline at end of file
+
+    /// Add a level of detail to a body, returning its level among those by decreasing panel count
+    ///
+    /// Explicit selections keep pointing at the same mesh.
+    pub fn add_lod(&mut self, name: &str, mesh: Mesh) -> Result<usize> {
+        let levels = self.levels_mut(name)?;
+        let level = levels.meshes.partition_point(|m| m.n_panels() >= mesh.n_panels());
+        levels.meshes.insert(level, mesh);
+        for selected in levels.selected.values_mut() {
+            if *selected >= level {
+                *selected += 1;
+            }
+        }
+        Ok(level)
+    }
+
+    /// Decimate the finest level of a body to each panel target and add the results
+    ///
+    /// `options.target_panels` is replaced by each target in turn; the
+    /// hydrostatic tolerances may leave a level above its target.
+    pub fn generate_lods(&mut self, name: &str, targets: &[usize], options: &DecimationOptions) -> Result<Vec<DecimationReport>> {
+        let finest = self.get_mesh(name)?.clone();
+        let mut reports = Vec::with_capacity(targets.len());
+        for &target_panels in targets {
+            let (coarse, report) = finest.decimate(&DecimationOptions {
+                target_panels,
+                ..*options
+            })?;
+            self.add_lod(name, coarse)?;
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Number of levels of detail of a body
+    pub fn lod_count(&self, name: &str) -> Result<usize> {
+        Ok(self.levels(name)?.meshes.len())
+    }
+
+    /// Level of detail of a body, 0 being the finest
+    pub fn get_lod(&self, name: &str, level: usize) -> Result<&Mesh> {
+        let levels = self.levels(name)?;
+        levels.meshes.get(level).ok_or_else(|| MeshError::InvalidData {
+            message: format!("Mesh '{}' has {} levels of detail, not {}", name, levels.meshes.len(), level + 1),
+        })
+    }
+
+    /// Select the level of detail a body uses for `usage`
+    pub fn set_lod(&mut self, name: &str, usage: MeshUsage, level: usize) -> Result<()> {
+        self.get_lod(name, level)?;
+        self.levels_mut(name)?.selected.insert(usage, level);
+        Ok(())
+    }
+
+    /// Level of detail of a body selected for `usage`
+    pub fn lod_level(&self, name: &str, usage: MeshUsage) -> Result<usize> {
+        let levels = self.levels(name)?;
+        Ok(levels.selected.get(&usage).copied().unwrap_or(match usage {
+            MeshUsage::Hydrostatics | MeshUsage::Graphics => 0,
+            MeshUsage::Bem => levels.meshes.len() - 1,
+        }))
+    }
+
+    /// Mesh of a body at the level selected for `usage`
+    pub fn mesh_for(&self, name: &str, usage: MeshUsage) -> Result<&Mesh> {
+        self.get_lod(name, self.lod_level(name, usage)?)
+    }
+
+    fn levels(&self, name: &str) -> Result<&Levels> {
+        self.meshes.get(name).ok_or_else(|| MeshError::MeshNotFound {
+            name: name.to_string(),
+        })
+    }
+
+    fn levels_mut(&mut self, name: &str) -> Result<&mut Levels> {
+        self.meshes.get_mut(name).ok_or_else(|| MeshError::MeshNotFound {
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_of_detail_per_usage() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 16, 8, 4).unwrap();
+        let mut collection = MeshCollection::new();
+        collection.add_mesh("barge".to_string(), hull.clone()).unwrap();
+        assert_eq!(collection.mesh_for("barge", MeshUsage::Bem).unwrap().n_panels(), hull.n_panels());
+
+        let reports = collection.generate_lods("barge", &[200, 50], &DecimationOptions::default()).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(collection.lod_count("barge").unwrap(), 3);
+        let counts: Vec<usize> = (0..3).map(|l| collection.get_lod("barge", l).unwrap().n_panels()).collect();
+        assert!(counts[0] > counts[1] && counts[1] > counts[2]);
+        assert_eq!(collection.mesh_for("barge", MeshUsage::Hydrostatics).unwrap().n_panels(), counts[0]);
+        assert_eq!(collection.mesh_for("barge", MeshUsage::Bem).unwrap().n_panels(), counts[2]);
+
+        // A selection follows its mesh when a level is inserted before it
+        collection.set_lod("barge", MeshUsage::Bem, 1).unwrap();
+        let middle = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 12, 6, 3).unwrap();
+        assert_eq!(collection.add_lod("barge", middle).unwrap(), 1);
+        assert_eq!(collection.lod_level("barge", MeshUsage::Bem).unwrap(), 2);
+        assert_eq!(collection.mesh_for("barge", MeshUsage::Bem).unwrap().n_panels(), counts[1]);
+        assert_eq!(collection.get_mesh("barge").unwrap().n_panels(), counts[0]);
+
+        assert!(collection.set_lod("barge", MeshUsage::Graphics, 4).is_err());
+        assert!(collection.add_lod("missing", hull).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Geometric and topological comparison of two meshes
+//!
+//! [`Mesh::compare`] checks that a format conversion, healing step or other
+//! rework left the geometry intact. Distances are sampled at the vertices
+//! and panel centroids of each mesh and measured to the nearest point of the
+//! other surface, the largest either way being the Hausdorff distance.
+//! Panels are paired by nearest centroid to report area changes, and the
+//! edge structure of both meshes is summarized as a [`MeshTopology`].
+
+use super::*;
+use crate::bvh::PanelTree;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Counts describing the connectivity of a mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MeshTopology {
+    /// Vertices used by a panel
+    pub vertices: usize,
+    /// Triangles and quads
+    pub panels: usize,
+    /// Distinct edges
+    pub edges: usize,
+    /// Edges on one panel only
+    pub boundary_edges: usize,
+    /// Edges on three or more panels
+    pub non_manifold_edges: usize,
+    /// Closed chains of boundary edges, such as the waterline of a clipped hull
+    pub boundary_loops: usize,
+    /// Edge-connected groups of panels
+    pub components: usize,
+    /// V - E + F
+    pub euler_characteristic: i64,
+}
+
+/// Differences between a mesh and a reworked copy
+#[derive(Debug, Clone)]
+pub struct MeshComparison {
+    /// Symmetric Hausdorff distance
+    pub hausdorff_distance: f64,
+    /// Largest distance from this mesh to the other
+    pub forward_distance: f64,
+    /// Largest distance from the other mesh to this one
+    pub backward_distance: f64,
+    /// Mean distance from this mesh's samples to the other
+    pub mean_distance: f64,
+    /// Panel of the other mesh nearest each panel of this one
+    pub panel_matches: Vec<usize>,
+    /// Area of the matched panel less the panel's own area, per panel of this mesh
+    pub area_differences: Vec<f64>,
+    /// Total area of the other mesh less this one's
+    pub total_area_difference: f64,
+    /// Connectivity of this mesh
+    pub topology: MeshTopology,
+    /// Connectivity of the other mesh
+    pub other_topology: MeshTopology,
+}
+
+impl MeshComparison {
+    /// Whether boundaries, manifoldness, components or genus differ
+    ///
+    /// Vertex, edge and panel counts alone may change under refinement or
+    /// welding without changing the topology.
+    pub fn topology_changed(&self) -> bool {
+        let invariants = |t: &MeshTopology| (t.non_manifold_edges, t.components, t.euler_characteristic, t.boundary_edges == 0);
+        invariants(&self.topology) != invariants(&self.other_topology)
+    }
+
+    /// Largest absolute panel area difference
+    pub fn max_area_difference(&self) -> f64 {
+        self.area_differences.iter().fold(0.0, |max, d| max.max(d.abs()))
+    }
+}
+
+impl Mesh {
+    /// Compare this mesh with `other`, typically a converted or healed copy
+    pub fn compare(&self, other: &Mesh) -> Result<MeshComparison> {
+        let forward = distances(&samples(self), other);
+        let backward = distances(&samples(other), self);
+        let max = |d: &[f64]| d.iter().copied().fold(0.0, f64::max);
+
+        let (centroids, areas) = panel_geometry(self);
+        let (other_centroids, other_areas) = panel_geometry(other);
+        let panel_matches: Vec<usize> = centroids
+            .par_iter()
+            .map(|c| {
+                (0..other_centroids.len())
+                    .min_by(|&i, &j| (other_centroids[i] - c).norm_squared().total_cmp(&(other_centroids[j] - c).norm_squared()))
+                    .unwrap_or(0)
+            })
+            .collect();
+        let area_differences = panel_matches.iter().zip(&areas).map(|(&j, area)| other_areas[j] - area).collect();
+
+        Ok(MeshComparison {
+            hausdorff_distance: max(&forward).max(max(&backward)),
+            forward_distance: max(&forward),
+            backward_distance: max(&backward),
+            mean_distance: forward.iter().sum::<f64>() / forward.len().max(1) as f64,
+            panel_matches,
+            area_differences,
+            total_area_difference: other_areas.iter().sum::<f64>() - areas.iter().sum::<f64>(),
+            topology: self.topology(),
+            other_topology: other.topology(),
+        })
+    }
+
+    /// Connectivity counts of the mesh
+    pub fn topology(&self) -> MeshTopology {
+        let polygons: Vec<&[usize]> = self.polygons().collect();
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for polygon in &polygons {
+            for k in 0..polygon.len() {
+                let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+
+        // Union-find over vertices joins panels sharing an edge
+        let mut parent: Vec<usize> = (0..self.vertices.len()).collect();
+        fn root(parent: &mut [usize], mut v: usize) -> usize {
+            while parent[v] != v {
+                parent[v] = parent[parent[v]];
+                v = parent[v];
+            }
+            v
+        }
+        for &(a, b) in edges.keys() {
+            let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+            parent[ra] = rb;
+        }
+        let mut used = vec![false; self.vertices.len()];
+        polygons.iter().flat_map(|p| p.iter()).for_each(|&v| used[v] = true);
+        let vertices = used.iter().filter(|&&u| u).count();
+        let components = (0..self.vertices.len()).filter(|&v| used[v] && root(&mut parent, v) == v).count();
+
+        // Walk the boundary edges; every chain returns to its start since
+        // boundary vertices of a manifold mesh have even degree
+        let mut boundary: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (&(a, b), _) in edges.iter().filter(|(_, &n)| n == 1) {
+            boundary.entry(a).or_default().push(b);
+            boundary.entry(b).or_default().push(a);
+        }
+        let mut boundary_loops = 0;
+        let mut starts: Vec<usize> = boundary.keys().copied().collect();
+        starts.sort_unstable();
+        for start in starts {
+            while let Some(mut next) = boundary.get_mut(&start).and_then(Vec::pop) {
+                let mut current = start;
+                loop {
+                    let back = boundary.get_mut(&next).unwrap();
+                    back.remove(back.iter().position(|&v| v == current).unwrap());
+                    current = next;
+                    if current == start {
+                        break;
+                    }
+                    match boundary.get_mut(&current).and_then(Vec::pop) {
+                        Some(v) => next = v,
+                        None => break,
+                    }
+                }
+                boundary_loops += 1;
+            }
+        }
+
+        MeshTopology {
+            vertices,
+            panels: polygons.len(),
+            edges: edges.len(),
+            boundary_edges: edges.values().filter(|&&n| n == 1).count(),
+            non_manifold_edges: edges.values().filter(|&&n| n > 2).count(),
+            boundary_loops,
+            components,
+            euler_characteristic: vertices as i64 - edges.len() as i64 + polygons.len() as i64,
+        }
+    }
+}
+
+/// Vertices used by a panel, then panel centroids
+fn samples(mesh: &Mesh) -> Vec<Point> {
+    let mut used = vec![false; mesh.vertices.len()];
+    mesh.polygons().flatten().for_each(|&v| used[v] = true);
+    let vertices = mesh.vertices.iter().zip(&used).filter(|(_, &u)| u).map(|(p, _)| *p);
+    vertices.chain(panel_geometry(mesh).0).collect()
+}
+
+fn panel_geometry(mesh: &Mesh) -> (Vec<Point>, Vec<f64>) {
+    mesh.polygons()
+        .map(|polygon| {
+            let points: Vec<Point> = polygon.iter().map(|&v| mesh.vertices[v]).collect();
+            let centroid = Point::from(points.iter().fold(Vector::zeros(), |sum, p| sum + p.coords) / points.len() as f64);
+            let n = points.len();
+            let area = 0.5 * (0..n).fold(Vector::zeros(), |sum, k| sum + points[k].coords.cross(&points[(k + 1) % n].coords)).norm();
+            (centroid, area)
+        })
+        .unzip()
+}
+
+/// Distance from each point to the nearest panel of `mesh`
+fn distances(points: &[Point], mesh: &Mesh) -> Vec<f64> {
+    let tree = PanelTree::new(mesh);
+    points
+        .par_iter()
+        .map(|p| tree.nearest_panel(p).map_or(f64::INFINITY, |hit| hit.distance))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_and_perturbed_meshes() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let same = hull.compare(&hull.clone()).unwrap();
+        assert!(same.hausdorff_distance < 1e-12);
+        assert!(same.max_area_difference() < 1e-12);
+        assert!(!same.topology_changed());
+        assert_eq!(same.topology.euler_characteristic, 2);
+        assert_eq!((same.topology.components, same.topology.boundary_edges), (1, 0));
+
+        // Lift one deck vertex off the plane
+        let mut vertices = hull.vertices.clone();
+        let lifted = vertices.iter().position(|p| p.z == 0.0 && p.x == 0.0 && p.y == 0.0).unwrap();
+        vertices[lifted].z += 0.1;
+        let bumped = Mesh::new(vertices, hull.faces.clone()).unwrap();
+        let comparison = hull.compare(&bumped).unwrap();
+        assert!((comparison.hausdorff_distance - 0.1).abs() < 1e-12);
+        assert!((comparison.backward_distance - 0.1).abs() < 1e-12);
+        assert!(comparison.forward_distance < 0.1);
+        assert!(comparison.max_area_difference() > 0.0 && comparison.total_area_difference > 0.0);
+        assert!(!comparison.topology_changed());
+    }
+
+    #[test]
+    fn test_triangulation_keeps_geometry_and_open_mesh_changes_topology() {
+        let plate = Mesh::with_quads(
+            vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0), Point::new(0.0, 1.0, 0.0)],
+            Vec::new(),
+            vec![[0, 1, 2, 3]],
+        )
+        .unwrap();
+        let comparison = plate.compare(&plate.triangulated().unwrap()).unwrap();
+        assert!(comparison.hausdorff_distance < 1e-15);
+        assert!(comparison.total_area_difference.abs() < 1e-15);
+        assert_eq!((comparison.topology.edges, comparison.other_topology.edges), (4, 5));
+        assert!(!comparison.topology_changed());
+
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let open = Mesh::new(hull.vertices.clone(), hull.faces[1..].to_vec()).unwrap();
+        let comparison = hull.compare(&open).unwrap();
+        assert!(comparison.topology_changed());
+        assert_eq!((comparison.other_topology.boundary_edges, comparison.other_topology.boundary_loops), (3, 1));
+        assert_eq!(comparison.other_topology.euler_characteristic, 1);
+        assert!(comparison.backward_distance < 1e-12 && comparison.forward_distance > 0.0);
+    }
+}
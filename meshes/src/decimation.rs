@@ -0,0 +1,343 @@
+//! Decimation of detailed meshes into coarse models for sweeps
+//!
+//! [`Mesh::decimate`] collapses edges in order of their quadric error
+//! (Garland and Heckbert): each vertex accumulates the planes of its faces,
+//! and an edge merges its ends at the point closest to all of them. Open
+//! boundaries, such as the waterline of a wetted mesh, carry extra planes
+//! across them so they keep their shape. A collapse is refused when it would
+//! fold a face over, pinch the surface, or move the displaced volume or the
+//! waterplane area further than their tolerances from the original values.
+//!
+//! Both are evaluated on the part of each face below z = 0, the displaced
+//! volume as ∫ z n_z dA and the waterplane area as -∫ n_z dA, so wetted
+//! surfaces and closed hulls with a deck above or at the waterline are
+//! treated alike.
+
+use super::*;
+use nalgebra::{Matrix3, Matrix4, Vector4};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Settings of decimation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecimationOptions {
+    /// Triangles to stop at; the hydrostatic constraints may stop earlier
+    pub target_panels: usize,
+    /// Largest change of the displaced volume, relative to its original value
+    pub volume_tolerance: f64,
+    /// Largest change of the waterplane area, relative to its original value
+    pub waterplane_tolerance: f64,
+    /// Largest quadric error of a collapse, as a squared distance
+    pub max_error: f64,
+}
+
+impl Default for DecimationOptions {
+    fn default() -> Self {
+        Self {
+            target_panels: 0,
+            volume_tolerance: 1e-3,
+            waterplane_tolerance: 1e-3,
+            max_error: f64::INFINITY,
+        }
+    }
+}
+
+/// What decimation removed and how the hydrostatics moved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecimationReport {
+    /// Edges collapsed
+    pub collapsed_edges: usize,
+    /// Displaced volume before and after
+    pub volume: [f64; 2],
+    /// Waterplane area before and after
+    pub waterplane_area: [f64; 2],
+}
+
+/// Edge collapse waiting in the queue, cheapest first
+struct Collapse {
+    cost: f64,
+    edge: [usize; 2],
+    versions: [usize; 2],
+    positions: Vec<(f64, Point)>,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.edge.cmp(&self.edge))
+    }
+}
+
+impl Mesh {
+    /// Collapse edges until the target panel count or a constraint is reached
+    ///
+    /// Quads are split into triangles first; the result is a triangle mesh.
+    pub fn decimate(&self, options: &DecimationOptions) -> Result<(Mesh, DecimationReport)> {
+        let source = self.triangulated()?;
+        let mut vertices = source.vertices.clone();
+        let mut faces: Vec<Option<[usize; 3]>> = source.faces.iter().copied().map(Some).collect();
+        let mut incident: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        for (f, face) in source.faces.iter().enumerate() {
+            for &v in face {
+                incident[v].push(f);
+            }
+        }
+
+        // Face planes weighted by area, plus planes across boundary edges
+        let mut quadrics = vec![Matrix4::zeros(); vertices.len()];
+        let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (f, &[a, b, c]) in source.faces.iter().enumerate() {
+            let normal = (vertices[b] - vertices[a]).cross(&(vertices[c] - vertices[a]));
+            let area = 0.5 * normal.norm();
+            if area > 0.0 {
+                let q = plane_quadric(&normal.normalize(), &vertices[a]) * area;
+                for v in [a, b, c] {
+                    quadrics[v] += q;
+                }
+            }
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                edge_faces.entry((u.min(v), u.max(v))).or_default().push(f);
+            }
+        }
+        let mut boundary = vec![false; vertices.len()];
+        for (&(u, v), adjacent) in &edge_faces {
+            if let [f] = adjacent[..] {
+                boundary[u] = true;
+                boundary[v] = true;
+                let edge = vertices[v] - vertices[u];
+                let across = edge.cross(&source.normals[f]);
+                if across.norm() > 0.0 {
+                    let q = plane_quadric(&across.normalize(), &vertices[u]) * (1e3 * edge.norm_squared());
+                    quadrics[u] += q;
+                    quadrics[v] += q;
+                }
+            }
+        }
+
+        let (volume0, waterplane0) = source.faces.iter().fold((0.0, 0.0), |(v, w), &f| {
+            let (dv, dw) = submerged(f.map(|i| vertices[i]));
+            (v + dv, w + dw)
+        });
+        let (mut volume, mut waterplane) = (volume0, waterplane0);
+
+        let mut versions = vec![0usize; vertices.len()];
+        let mut heap = BinaryHeap::new();
+        let candidate = |[u, v]: [usize; 2], vertices: &[Point], quadrics: &[Matrix4<f64>], versions: &[usize]| {
+            let q = quadrics[u] + quadrics[v];
+            let mut points = vec![vertices[u], vertices[v], Point::from((vertices[u].coords + vertices[v].coords) * 0.5)];
+            let a: Matrix3<f64> = q.fixed_view::<3, 3>(0, 0).into();
+            if let Some(inverse) = a.try_inverse() {
+                let optimal = -(inverse * q.fixed_view::<3, 1>(0, 3));
+                // Keep the optimum near the edge, where the quadrics are meaningful
+                let reach = (vertices[v] - vertices[u]).norm();
+                if (optimal - points[2].coords).norm() <= reach {
+                    points.push(Point::from(optimal));
+                }
+            }
+            let mut positions: Vec<(f64, Point)> = points
+                .into_iter()
+                .map(|p| {
+                    let h = Vector4::new(p.x, p.y, p.z, 1.0);
+                    ((h.transpose() * q * h)[0].max(0.0), p)
+                })
+                .collect();
+            positions.sort_by(|a, b| a.0.total_cmp(&b.0));
+            Collapse { cost: positions[0].0, edge: [u, v], versions: [versions[u], versions[v]], positions }
+        };
+        let mut edges: Vec<(usize, usize)> = edge_faces.keys().copied().collect();
+        edges.sort_unstable();
+        for (u, v) in edges {
+            heap.push(candidate([u, v], &vertices, &quadrics, &versions));
+        }
+
+        let mut alive = source.faces.len();
+        let mut report = DecimationReport::default();
+        while alive > options.target_panels {
+            let Some(collapse) = heap.pop() else { break };
+            let [u, v] = collapse.edge;
+            if collapse.versions != [versions[u], versions[v]] || collapse.cost > options.max_error {
+                continue;
+            }
+            let shared: Vec<usize> = incident[u].iter().copied().filter(|f| faces[*f].is_some_and(|face| face.contains(&v))).collect();
+            if shared.is_empty() {
+                continue;
+            }
+            // Link condition: the ends may only share the vertices opposite the edge
+            if neighbours(u, &incident, &faces).intersection(&neighbours(v, &incident, &faces)).count() != shared.len() {
+                continue;
+            }
+            if boundary[u] && boundary[v] && shared.len() != 1 {
+                continue;
+            }
+
+            let changed: Vec<usize> = incident[u]
+                .iter()
+                .chain(&incident[v])
+                .copied()
+                .filter(|f| faces[*f].is_some() && !shared.contains(f))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let removed: f64 = shared.iter().chain(&changed).map(|&f| submerged(faces[f].unwrap().map(|i| vertices[i])).0).sum();
+            let removed_area: f64 = shared.iter().chain(&changed).map(|&f| submerged(faces[f].unwrap().map(|i| vertices[i])).1).sum();
+            let accepted = collapse.positions.iter().find_map(|&(cost, p)| {
+                if cost > options.max_error {
+                    return None;
+                }
+                let moved = |i: usize| if i == u || i == v { p } else { vertices[i] };
+                let mut added = (0.0, 0.0);
+                for &f in &changed {
+                    let face = faces[f].unwrap();
+                    let before = (vertices[face[1]] - vertices[face[0]]).cross(&(vertices[face[2]] - vertices[face[0]]));
+                    let after = (moved(face[1]) - moved(face[0])).cross(&(moved(face[2]) - moved(face[0])));
+                    if after.norm() <= 1e-12 * before.norm().max(1e-300) || after.dot(&before) <= 0.0 {
+                        return None;
+                    }
+                    let (dv, dw) = submerged(face.map(moved));
+                    added = (added.0 + dv, added.1 + dw);
+                }
+                let new_volume = volume - removed + added.0;
+                let new_waterplane = waterplane - removed_area + added.1;
+                let within = |value: f64, original: f64, tolerance: f64| (value - original).abs() <= tolerance * original.abs();
+                (within(new_volume, volume0, options.volume_tolerance)
+                    && within(new_waterplane, waterplane0, options.waterplane_tolerance))
+                .then_some((p, new_volume, new_waterplane))
+            });
+            let Some((p, new_volume, new_waterplane)) = accepted else { continue };
+
+            // Merge v into u at p
+            volume = new_volume;
+            waterplane = new_waterplane;
+            vertices[u] = p;
+            for &f in &shared {
+                faces[f] = None;
+                alive -= 1;
+            }
+            for &f in &incident[v].clone() {
+                if let Some(face) = faces[f].as_mut() {
+                    for i in face.iter_mut().filter(|i| **i == v) {
+                        *i = u;
+                    }
+                    incident[u].push(f);
+                }
+            }
+            incident[v].clear();
+            incident[u].retain(|&f| faces[f].is_some());
+            incident[u].sort_unstable();
+            incident[u].dedup();
+            quadrics[u] = quadrics[u] + quadrics[v];
+            boundary[u] |= boundary[v];
+            versions[u] += 1;
+            versions[v] += 1;
+            report.collapsed_edges += 1;
+            for w in neighbours(u, &incident, &faces) {
+                heap.push(candidate([u, w], &vertices, &quadrics, &versions));
+            }
+        }
+
+        // Drop unused vertices
+        let mut index = vec![usize::MAX; vertices.len()];
+        let mut kept = Vec::new();
+        let faces: Vec<[usize; 3]> = faces
+            .into_iter()
+            .flatten()
+            .map(|face| {
+                face.map(|v| {
+                    if index[v] == usize::MAX {
+                        index[v] = kept.len();
+                        kept.push(vertices[v]);
+                    }
+                    index[v]
+                })
+            })
+            .collect();
+        report.volume = [volume0, volume];
+        report.waterplane_area = [waterplane0, waterplane];
+        Ok((Mesh::new(kept, faces)?, report))
+    }
+}
+
+/// Vertices sharing a live face with `w`
+fn neighbours(w: usize, incident: &[Vec<usize>], faces: &[Option<[usize; 3]>]) -> HashSet<usize> {
+    incident[w].iter().filter_map(|&f| faces[f]).flatten().filter(|&x| x != w).collect()
+}
+
+/// Quadric of squared distance to the plane through `point` with unit `normal`
+fn plane_quadric(normal: &Vector, point: &Point) -> Matrix4<f64> {
+    let plane = Vector4::new(normal.x, normal.y, normal.z, -normal.dot(&point.coords));
+    plane * plane.transpose()
+}
+
+/// Displaced volume ∫ z n_z dA and waterplane area -∫ n_z dA of the part of a triangle below z = 0
+fn submerged(corners: [Point; 3]) -> (f64, f64) {
+    if corners.iter().all(|p| p.z >= 0.0) {
+        return (0.0, 0.0);
+    }
+    let mut polygon = Vec::with_capacity(4);
+    for i in 0..3 {
+        let (a, b) = (corners[i], corners[(i + 1) % 3]);
+        if a.z <= 0.0 {
+            polygon.push(a);
+        }
+        if (a.z < 0.0 && b.z > 0.0) || (a.z > 0.0 && b.z < 0.0) {
+            polygon.push(a + a.z / (a.z - b.z) * (b - a));
+        }
+    }
+    (1..polygon.len() - 1).fold((0.0, 0.0), |(volume, waterplane), k| {
+        let (a, b, c) = (polygon[0], polygon[k], polygon[k + 1]);
+        let nz_area = 0.5 * (b - a).cross(&(c - a)).z;
+        (volume + (a.z + b.z + c.z) / 3.0 * nz_area, waterplane - nz_area)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_faces_collapse_without_changing_hydrostatics() {
+        let (length, beam, draft) = (4.0, 2.0, 1.0);
+        let hull = PredefinedGeometry::box_hull(length, beam, draft, 16, 8, 4).unwrap();
+        let (coarse, report) = hull.decimate(&DecimationOptions::default()).unwrap();
+        assert!(coarse.faces.len() * 8 < hull.faces.len());
+        assert_eq!(report.collapsed_edges, (hull.faces.len() - coarse.faces.len()) / 2);
+        assert!((report.volume[0] - length * beam * draft).abs() < 1e-9);
+        assert!((report.waterplane_area[0] - length * beam).abs() < 1e-9);
+        assert!((report.volume[1] - report.volume[0]).abs() <= 1e-3 * report.volume[0]);
+        assert!((report.waterplane_area[1] - report.waterplane_area[0]).abs() <= 1e-3 * report.waterplane_area[0]);
+        assert!(coarse.vertices.len() < hull.vertices.len());
+    }
+
+    #[test]
+    fn test_tolerances_limit_decimation_of_curved_hull() {
+        let wigley = PredefinedGeometry::wigley_with_surface(10.0, 1.0, 0.6, 24, 8, HullSurface::Wetted).unwrap();
+        let count = |tolerance: f64| {
+            let options = DecimationOptions { volume_tolerance: tolerance, waterplane_tolerance: tolerance, ..Default::default() };
+            let (coarse, report) = wigley.decimate(&options).unwrap();
+            for [before, after] in [report.volume, report.waterplane_area] {
+                assert!((after - before).abs() <= tolerance * before.abs() + 1e-12);
+            }
+            assert!(coarse.normals.iter().all(|n| n.iter().all(|c| c.is_finite())));
+            coarse.faces.len()
+        };
+        let (tight, loose) = (count(1e-4), count(2e-2));
+        assert!(loose < tight && tight < wigley.faces.len());
+
+        let target = DecimationOptions { target_panels: 300, volume_tolerance: 1.0, waterplane_tolerance: 1.0, ..Default::default() };
+        let (coarse, _) = wigley.decimate(&target).unwrap();
+        assert!(coarse.faces.len() <= 300 && coarse.faces.len() >= 298);
+    }
+}
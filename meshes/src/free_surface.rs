@@ -0,0 +1,321 @@
+//! Exterior free-surface grids
+//!
+//! Free-surface elevation output, the free-surface integral of second-order
+//! QTFs and time-domain free-surface tracking need panels on z = 0 outside
+//! the body. [`FreeSurfaceRing`] builds an annulus around a body, with cells
+//! growing geometrically outward; [`FreeSurfacePatch`] builds a rectangle
+//! whose coordinate lines are graded from the hull's waterline out to the
+//! patch edges, with the waterplane cut out. Both give quads with normals
+//! pointing up, +z.
+
+use super::*;
+
+/// Annular free-surface grid centred on a body
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeSurfaceRing {
+    /// Centre of the ring (x, y)
+    pub center: [f64; 2],
+    /// Inner radius, clear of the waterline
+    pub inner_radius: f64,
+    /// Outer radius
+    pub outer_radius: f64,
+    /// Panels around the ring
+    pub n_theta: usize,
+    /// Panels across the ring
+    pub n_radial: usize,
+    /// Ratio of successive radial panel widths, 1 for uniform
+    pub growth: f64,
+}
+
+impl Default for FreeSurfaceRing {
+    fn default() -> Self {
+        Self {
+            center: [0.0, 0.0],
+            inner_radius: 1.0,
+            outer_radius: 10.0,
+            n_theta: 32,
+            n_radial: 10,
+            growth: 1.0,
+        }
+    }
+}
+
+impl FreeSurfaceRing {
+    /// Ring circumscribing the hull's waterline, with panels of about `panel_size` next to it
+    ///
+    /// Radial widths grow by 10% per ring out to `outer_radius`.
+    pub fn around(hull: &Mesh, outer_radius: f64, panel_size: f64) -> Result<Self> {
+        if !(panel_size > 0.0 && panel_size.is_finite()) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Free-surface panel size {} must be positive and finite", panel_size),
+            });
+        }
+        let waterline = waterline_points(hull)?;
+        let ([x0, y0], [x1, y1]) = bounds(&waterline);
+        let center = [0.5 * (x0 + x1), 0.5 * (y0 + y1)];
+        let inner_radius = waterline.iter().map(|p| (p.x - center[0]).hypot(p.y - center[1])).fold(0.0, f64::max);
+        if outer_radius <= inner_radius {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Outer radius {} does not clear the waterline radius {}", outer_radius, inner_radius),
+            });
+        }
+        let growth: f64 = 1.1;
+        let width = outer_radius - inner_radius;
+        let n_radial = ((1.0 + width * (growth - 1.0) / panel_size).ln() / growth.ln()).round().max(1.0) as usize;
+        Ok(Self {
+            center,
+            inner_radius,
+            outer_radius,
+            n_theta: ((2.0 * std::f64::consts::PI * inner_radius / panel_size).ceil() as usize).max(8),
+            n_radial,
+            growth,
+        })
+    }
+
+    /// Quad mesh of the ring
+    pub fn mesh(&self) -> Result<Mesh> {
+        if !(self.inner_radius > 0.0 && self.outer_radius > self.inner_radius) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Free-surface ring needs 0 < inner radius {} < outer radius {}", self.inner_radius, self.outer_radius),
+            });
+        }
+        if self.n_theta < 3 || self.n_radial == 0 || self.growth <= 0.0 {
+            return Err(MeshError::InvalidGeometry {
+                message: format!(
+                    "Free-surface ring needs at least 3 × 1 panels and positive growth, got {} × {} and {}",
+                    self.n_theta, self.n_radial, self.growth
+                ),
+            });
+        }
+        let radii = graded(self.inner_radius, self.outer_radius, self.n_radial, self.growth);
+        let vertices = radii
+            .iter()
+            .flat_map(|&r| {
+                (0..self.n_theta).map(move |j| {
+                    let theta = 2.0 * std::f64::consts::PI * j as f64 / self.n_theta as f64;
+                    Point::new(self.center[0] + r * theta.cos(), self.center[1] + r * theta.sin(), 0.0)
+                })
+            })
+            .collect();
+        let index = |k: usize, j: usize| k * self.n_theta + j % self.n_theta;
+        let quads = (0..self.n_radial)
+            .flat_map(|k| (0..self.n_theta).map(move |j| [index(k, j), index(k + 1, j), index(k + 1, j + 1), index(k, j + 1)]))
+            .collect();
+        Mesh::with_quads(vertices, Vec::new(), quads)
+    }
+}
+
+/// Rectangular free-surface patch graded toward a hull
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeSurfacePatch {
+    /// Extent in x (m)
+    pub x_range: (f64, f64),
+    /// Extent in y (m)
+    pub y_range: (f64, f64),
+    /// Panel size over the waterline's bounding box
+    pub panel_size: f64,
+    /// Largest panel size toward the patch edges
+    pub max_panel_size: f64,
+    /// Ratio of successive panel sizes away from the hull
+    pub growth: f64,
+}
+
+impl Default for FreeSurfacePatch {
+    fn default() -> Self {
+        Self {
+            x_range: (-50.0, 50.0),
+            y_range: (-50.0, 50.0),
+            panel_size: 1.0,
+            max_panel_size: 5.0,
+            growth: 1.15,
+        }
+    }
+}
+
+impl FreeSurfacePatch {
+    /// Quad mesh of the patch around `hull`, floating at z = 0
+    ///
+    /// Panels over the waterplane are dropped, and so are those straddling a
+    /// waterline that does not follow the grid lines; the free surface inside
+    /// moonpools is kept.
+    pub fn mesh(&self, hull: &Mesh) -> Result<Mesh> {
+        if !(self.panel_size > 0.0 && self.max_panel_size >= self.panel_size && self.growth >= 1.0) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!(
+                    "Free-surface patch needs 0 < panel size {} <= max panel size {} and growth {} >= 1",
+                    self.panel_size, self.max_panel_size, self.growth
+                ),
+            });
+        }
+        let loops = hull.clip_at_waterline(0.0)?.waterplane;
+        let points: Vec<Point> = loops.iter().flatten().copied().collect();
+        if points.is_empty() {
+            return Err(MeshError::InvalidGeometry {
+                message: "Hull does not pierce the free surface".to_string(),
+            });
+        }
+        let ([x0, y0], [x1, y1]) = bounds(&points);
+        let x = self.lines(self.x_range, (x0, x1))?;
+        let y = self.lines(self.y_range, (y0, y1))?;
+
+        let inside = |px: f64, py: f64| loops.iter().filter(|l| crosses(l, px, py)).count() % 2 == 1;
+        let mut used = vec![None; x.len() * y.len()];
+        let (mut vertices, mut quads) = (Vec::new(), Vec::new());
+        for j in 0..y.len() - 1 {
+            for i in 0..x.len() - 1 {
+                let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+                let centre = (0.5 * (x[i] + x[i + 1]), 0.5 * (y[j] + y[j + 1]));
+                // Corners on the waterline itself are clear, so test them nudged toward the centre
+                let corner_inside = |&(a, b): &(usize, usize)| inside(x[a] + 1e-9 * (centre.0 - x[a]), y[b] + 1e-9 * (centre.1 - y[b]));
+                if inside(centre.0, centre.1) || corners.iter().any(corner_inside) {
+                    continue;
+                }
+                quads.push(corners.map(|(a, b)| {
+                    *used[b * x.len() + a].get_or_insert_with(|| {
+                        vertices.push(Point::new(x[a], y[b], 0.0));
+                        vertices.len() - 1
+                    })
+                }));
+            }
+        }
+        Mesh::with_quads(vertices, Vec::new(), quads)
+    }
+
+    /// Coordinate lines over `range`, uniform across `hull` and growing outside it
+    fn lines(&self, range: (f64, f64), hull: (f64, f64)) -> Result<Vec<f64>> {
+        if !(range.0 < hull.0 && hull.1 < range.1) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Free-surface patch {:?} does not enclose the waterline {:?}", range, hull),
+            });
+        }
+        let n = ((hull.1 - hull.0) / self.panel_size).ceil().max(1.0) as usize;
+        let middle = graded(hull.0, hull.1, n, 1.0);
+        let outward = |width: f64| -> Vec<f64> {
+            // Growing steps from the hull, the last one stretched or merged to end on the edge
+            let (mut offsets, mut size) = (vec![0.0], self.panel_size);
+            while offsets.last().unwrap() + size < width {
+                offsets.push(offsets.last().unwrap() + size);
+                size = (size * self.growth).min(self.max_panel_size);
+            }
+            if offsets.len() > 1 && width - offsets.last().unwrap() < 0.5 * size {
+                offsets.pop();
+            }
+            offsets.push(width);
+            offsets
+        };
+        let mut lines: Vec<f64> = outward(hull.0 - range.0).iter().rev().map(|d| hull.0 - d).collect();
+        lines.extend_from_slice(&middle[1..]);
+        lines.extend(outward(range.1 - hull.1).iter().skip(1).map(|d| hull.1 + d));
+        Ok(lines)
+    }
+}
+
+/// Stations from `start` to `end` whose n intervals grow by `growth`
+fn graded(start: f64, end: f64, n: usize, growth: f64) -> Vec<f64> {
+    (0..=n)
+        .map(|k| {
+            let t = if (growth - 1.0).abs() < 1e-12 {
+                k as f64 / n as f64
+            } else {
+                (growth.powi(k as i32) - 1.0) / (growth.powi(n as i32) - 1.0)
+            };
+            start + (end - start) * t
+        })
+        .collect()
+}
+
+fn waterline_points(hull: &Mesh) -> Result<Vec<Point>> {
+    let points: Vec<Point> = hull.clip_at_waterline(0.0)?.waterplane.into_iter().flatten().collect();
+    if points.is_empty() {
+        return Err(MeshError::InvalidGeometry {
+            message: "Hull does not pierce the free surface".to_string(),
+        });
+    }
+    Ok(points)
+}
+
+fn bounds(points: &[Point]) -> ([f64; 2], [f64; 2]) {
+    points.iter().fold(([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]), |(lo, hi), p| {
+        ([lo[0].min(p.x), lo[1].min(p.y)], [hi[0].max(p.x), hi[1].max(p.y)])
+    })
+}
+
+/// Whether a ray from (x, y) toward +x crosses the closed loop an odd number of times
+fn crosses(polygon: &[Point], x: f64, y: f64) -> bool {
+    let n = polygon.len();
+    (0..n)
+        .filter(|&i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            (a.y > y) != (b.y > y) && x < a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(mesh: &Mesh) -> f64 {
+        let t = mesh.triangulated().unwrap();
+        t.faces
+            .iter()
+            .map(|&[a, b, c]| 0.5 * (t.vertices[b] - t.vertices[a]).cross(&(t.vertices[c] - t.vertices[a])).z)
+            .sum()
+    }
+
+    #[test]
+    fn test_ring_around_cylinder() {
+        let cylinder = PredefinedGeometry::cylinder(2.0, 3.0, 24, 4).unwrap();
+        let ring = FreeSurfaceRing::around(&cylinder, 20.0, 0.5).unwrap();
+        assert!((ring.inner_radius - 2.0).abs() < 1e-12 && ring.center[0].abs() < 1e-12);
+        assert_eq!(ring.n_theta, 26);
+
+        let mesh = ring.mesh().unwrap();
+        assert_eq!(mesh.n_panels(), ring.n_theta * ring.n_radial);
+        assert!(mesh.normals.iter().all(|n| (n.z - 1.0).abs() < 1e-12));
+        let radii = graded(ring.inner_radius, ring.outer_radius, ring.n_radial, ring.growth);
+        assert!((radii[1] - radii[0] - 0.5).abs() < 0.1);
+        let annulus = 0.5 * ring.n_theta as f64 * (20.0f64.powi(2) - 4.0) * (2.0 * std::f64::consts::PI / ring.n_theta as f64).sin();
+        assert!((area(&mesh) - annulus).abs() < 1e-9 * annulus);
+
+        assert!(FreeSurfaceRing::around(&cylinder, 1.0, 0.5).is_err());
+        assert!(FreeSurfaceRing { n_theta: 2, ..Default::default() }.mesh().is_err());
+    }
+
+    #[test]
+    fn test_patch_graded_around_box() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let patch = FreeSurfacePatch {
+            x_range: (-20.0, 20.0),
+            y_range: (-10.0, 10.0),
+            panel_size: 0.5,
+            max_panel_size: 3.0,
+            growth: 1.3,
+        };
+        let mesh = patch.mesh(&hull).unwrap();
+        assert!((area(&mesh) - (40.0 * 20.0 - 8.0)).abs() < 1e-9);
+        assert!(mesh.normals.iter().all(|n| (n.z - 1.0).abs() < 1e-12));
+
+        let x = patch.lines(patch.x_range, (-2.0, 2.0)).unwrap();
+        let steps: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+        assert_eq!((x[0], *x.last().unwrap()), (-20.0, 20.0));
+        assert!(x.contains(&-2.0) && x.contains(&2.0));
+        assert!(steps.iter().all(|&s| s > 0.0 && s <= 1.5 * patch.max_panel_size));
+        let middle = steps.len() / 2;
+        assert!((steps[middle] - 0.5).abs() < 1e-12 && steps[0] > steps[middle]);
+
+        assert!(FreeSurfacePatch { x_range: (-1.0, 1.0), ..patch.clone() }.mesh(&hull).is_err());
+    }
+
+    #[test]
+    fn test_patch_around_cylinder_clears_waterplane() {
+        let cylinder = PredefinedGeometry::cylinder(2.0, 3.0, 24, 4).unwrap();
+        let patch = FreeSurfacePatch { x_range: (-10.0, 10.0), y_range: (-10.0, 10.0), panel_size: 0.4, ..Default::default() };
+        let mesh = patch.mesh(&cylinder).unwrap();
+        let radius = 2.0 * (std::f64::consts::PI / 24.0).cos();
+        assert!(mesh.vertices.iter().all(|p| p.x.hypot(p.y) >= radius - 1e-9));
+        assert!(area(&mesh) < 400.0 - std::f64::consts::PI * radius * radius);
+    }
+}
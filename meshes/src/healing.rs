@@ -0,0 +1,294 @@
+//! Repair of imported meshes before BEM assembly
+//!
+//! Triangle soups from STL exports and CAD tessellations repeat shared
+//! corners, carry slivers and duplicated facets, and leave gaps where
+//! surfaces failed to stitch. [`Mesh::heal`] welds nearby vertices, drops
+//! degenerate and duplicate faces, reports edges shared by more than two
+//! faces or traversed the same way twice, and finds the holes left in the
+//! surface, optionally capping the small ones.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Settings of the healing pipeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealOptions {
+    /// Distance below which vertices are merged
+    pub weld_tolerance: f64,
+    /// Faces with a smaller area are removed
+    pub min_area: f64,
+    /// Close holes by fanning triangles from their centroid
+    pub cap_holes: bool,
+    /// Largest hole, in boundary edges, that is capped
+    pub max_cap_edges: usize,
+}
+
+impl Default for HealOptions {
+    fn default() -> Self {
+        Self {
+            weld_tolerance: 1e-6,
+            min_area: 1e-12,
+            cap_holes: false,
+            max_cap_edges: 16,
+        }
+    }
+}
+
+/// What healing changed and what it could not fix
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealReport {
+    /// Vertices merged into a neighbour within the weld tolerance
+    pub merged_vertices: usize,
+    /// Vertices used by no face
+    pub unused_vertices: usize,
+    /// Panels removed for repeated corners or an area below the minimum
+    pub degenerate_faces: usize,
+    /// Panels removed as repeats of another panel over the same corners
+    pub duplicate_faces: usize,
+    /// Edges shared by more than two faces, as vertex pairs of the healed mesh
+    pub non_manifold_edges: Vec<[usize; 2]>,
+    /// Edges traversed in the same direction by two faces, whose normals disagree
+    pub inconsistent_edges: Vec<[usize; 2]>,
+    /// Holes closed by capping
+    pub capped_holes: usize,
+    /// Boundary loops left open, as vertex indices of the healed mesh
+    pub holes: Vec<Vec<usize>>,
+}
+
+impl HealReport {
+    /// Whether the healed mesh is closed, manifold and consistently oriented
+    pub fn is_watertight(&self) -> bool {
+        self.holes.is_empty() && self.non_manifold_edges.is_empty() && self.inconsistent_edges.is_empty()
+    }
+}
+
+impl Mesh {
+    /// Weld, clean and check the mesh, optionally capping small holes
+    pub fn heal(&self, options: &HealOptions) -> Result<(Mesh, HealReport)> {
+        if !(options.weld_tolerance >= 0.0 && options.min_area >= 0.0) {
+            return Err(MeshError::InvalidGeometry {
+                message: "Weld tolerance and minimum area must be non-negative".to_string(),
+            });
+        }
+        let mut report = HealReport::default();
+
+        // Weld used vertices through a grid of tolerance-sized cells
+        let tolerance = options.weld_tolerance;
+        let cell = |p: &Point| {
+            if tolerance > 0.0 {
+                [p.x, p.y, p.z].map(|c| (c / tolerance).floor() as i64)
+            } else {
+                [p.x, p.y, p.z].map(|c| c.to_bits() as i64)
+            }
+        };
+        let reach: i64 = if tolerance > 0.0 { 1 } else { 0 };
+        let mut vertices: Vec<Point> = Vec::new();
+        let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        let mut index: Vec<Option<usize>> = vec![None; self.vertices.len()];
+        for polygon in self.polygons() {
+            for &v in polygon {
+                if index[v].is_some() {
+                    continue;
+                }
+                let p = self.vertices[v];
+                let home = cell(&p);
+                let mut found = None;
+                'search: for dx in -reach..=reach {
+                    for dy in -reach..=reach {
+                        for dz in -reach..=reach {
+                            let bucket = grid.get(&[home[0] + dx, home[1] + dy, home[2] + dz]);
+                            found = bucket.and_then(|b| b.iter().copied().find(|&w| (vertices[w] - p).norm() <= tolerance));
+                            if found.is_some() {
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+                index[v] = Some(found.unwrap_or_else(|| {
+                    vertices.push(p);
+                    grid.entry(home).or_default().push(vertices.len() - 1);
+                    vertices.len() - 1
+                }));
+                report.merged_vertices += found.is_some() as usize;
+            }
+        }
+        report.unused_vertices = index.iter().filter(|i| i.is_none()).count();
+
+        // Remap panels, dropping degenerate ones and repeats over the same corners;
+        // a quad with one repeated corner becomes a triangle
+        let mut polygons: Vec<Vec<usize>> = Vec::with_capacity(self.n_panels());
+        let mut seen = std::collections::HashSet::new();
+        for polygon in self.polygons() {
+            let mut corners: Vec<usize> = polygon.iter().map(|&v| index[v].unwrap_or_default()).collect();
+            corners.dedup();
+            while corners.len() > 1 && corners.first() == corners.last() {
+                corners.pop();
+            }
+            let p0 = corners.first().map(|&v| vertices[v]).unwrap_or_else(Point::origin);
+            let area = 0.5 * corners[1..]
+                .windows(2)
+                .fold(Vector::zeros(), |sum, w| sum + (vertices[w[0]] - p0).cross(&(vertices[w[1]] - p0)))
+                .norm();
+            let mut key = corners.clone();
+            key.sort_unstable();
+            key.dedup();
+            if key.len() != corners.len() || corners.len() < 3 || area < options.min_area {
+                report.degenerate_faces += 1;
+                continue;
+            }
+            if !seen.insert(key) {
+                report.duplicate_faces += 1;
+                continue;
+            }
+            polygons.push(corners);
+        }
+
+        let mut holes = boundary_loops(&polygons);
+        if options.cap_holes {
+            holes.retain(|hole| {
+                if hole.len() > options.max_cap_edges {
+                    return true;
+                }
+                let centre = hole.iter().fold(Vector::zeros(), |sum, &v| sum + vertices[v].coords) / hole.len() as f64;
+                vertices.push(Point::from(centre));
+                let c = vertices.len() - 1;
+                // The hole runs along its panels' winding, so the cap runs against it
+                polygons.extend((0..hole.len()).map(|i| vec![hole[(i + 1) % hole.len()], hole[i], c]));
+                report.capped_holes += 1;
+                false
+            });
+        }
+        report.holes = holes;
+
+        let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+        for polygon in &polygons {
+            for k in 0..polygon.len() {
+                *directed.entry((polygon[k], polygon[(k + 1) % polygon.len()])).or_default() += 1;
+            }
+        }
+        let mut undirected: HashMap<[usize; 2], usize> = HashMap::new();
+        for (&(a, b), &count) in &directed {
+            *undirected.entry([a.min(b), a.max(b)]).or_default() += count;
+        }
+        report.non_manifold_edges = undirected.iter().filter(|(_, &n)| n > 2).map(|(&e, _)| e).collect();
+        report.non_manifold_edges.sort_unstable();
+        report.inconsistent_edges = directed.iter().filter(|(_, &n)| n > 1).map(|(&(a, b), _)| [a.min(b), a.max(b)]).collect();
+        report.inconsistent_edges.sort_unstable();
+        report.inconsistent_edges.dedup();
+
+        let faces = polygons.iter().filter(|p| p.len() == 3).map(|p| [p[0], p[1], p[2]]).collect();
+        let quads = polygons.iter().filter(|p| p.len() == 4).map(|p| [p[0], p[1], p[2], p[3]]).collect();
+        Ok((Mesh::with_quads(vertices, faces, quads)?, report))
+    }
+}
+
+/// Loops of edges used by a single panel, each in the winding of that panel
+fn boundary_loops(polygons: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let edges = |p: &Vec<usize>| (0..p.len()).map(|k| (p[k], p[(k + 1) % p.len()])).collect::<Vec<_>>();
+    let mut count: HashMap<[usize; 2], usize> = HashMap::new();
+    for (a, b) in polygons.iter().flat_map(edges) {
+        *count.entry([a.min(b), a.max(b)]).or_default() += 1;
+    }
+    let mut next: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (a, b) in polygons.iter().flat_map(edges) {
+        if count[&[a.min(b), a.max(b)]] == 1 {
+            next.entry(a).or_default().push(b);
+        }
+    }
+    let mut starts: Vec<usize> = next.keys().copied().collect();
+    starts.sort_unstable();
+    let mut loops = Vec::new();
+    for start in starts {
+        while next.get(&start).is_some_and(|n| !n.is_empty()) {
+            let mut hole = vec![start];
+            let mut current = start;
+            while let Some(following) = next.get_mut(&current).and_then(Vec::pop) {
+                if following == start {
+                    break;
+                }
+                hole.push(following);
+                current = following;
+            }
+            loops.push(hole);
+        }
+    }
+    loops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Closed box as a triangle soup with each corner repeated and jittered
+    fn soup() -> (Mesh, usize) {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 2, 1, 1).unwrap();
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for (f, face) in hull.faces.iter().enumerate() {
+            let jitter = Vector::new(1e-8, -1e-8, 1e-8) * (f % 3) as f64;
+            faces.push([0, 1, 2].map(|k| {
+                vertices.push(hull.vertices[face[k]] + jitter);
+                vertices.len() - 1
+            }));
+        }
+        (Mesh::new(vertices, faces).unwrap(), hull.vertices.len())
+    }
+
+    #[test]
+    fn test_heal_welds_soup_and_drops_bad_faces() {
+        let (mut mesh, corners) = soup();
+        let n = mesh.faces.len();
+        mesh.faces.push(mesh.faces[0]);
+        let sliver = [mesh.faces[1][0], mesh.faces[1][1], mesh.faces[1][1]];
+        mesh.faces.push(sliver);
+        mesh.vertices.push(Point::new(50.0, 0.0, 0.0));
+
+        let (healed, report) = mesh.heal(&HealOptions::default()).unwrap();
+        assert_eq!(healed.vertices.len(), corners);
+        assert_eq!(healed.faces.len(), n);
+        assert_eq!((report.duplicate_faces, report.degenerate_faces, report.unused_vertices), (1, 1, 1));
+        assert_eq!(report.merged_vertices, 3 * n - corners);
+        assert!(report.is_watertight());
+    }
+
+    #[test]
+    fn test_heal_finds_and_caps_holes() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 2, 1, 1).unwrap();
+        // Remove one quad of the bottom, leaving a four-edge hole
+        let bottom: Vec<usize> = (0..hull.faces.len()).filter(|&f| hull.normals[f].z < -0.5).take(2).collect();
+        let faces: Vec<_> = (0..hull.faces.len()).filter(|f| !bottom.contains(f)).map(|f| hull.faces[f]).collect();
+        let open = Mesh::new(hull.vertices.clone(), faces).unwrap();
+
+        let (_, report) = open.heal(&HealOptions::default()).unwrap();
+        assert_eq!(report.holes.len(), 1);
+        assert_eq!(report.holes[0].len(), 4);
+
+        let options = HealOptions { cap_holes: true, ..HealOptions::default() };
+        let (capped, report) = open.heal(&options).unwrap();
+        assert!(report.is_watertight());
+        assert_eq!(report.capped_holes, 1);
+        assert_eq!(capped.faces.len(), hull.faces.len() + 2);
+        assert!(capped.normals[hull.faces.len() - 2..].iter().all(|n| (n.z + 1.0).abs() < 1e-12));
+
+        let small = HealOptions { max_cap_edges: 3, ..options };
+        assert_eq!(open.heal(&small).unwrap().1.holes.len(), 1);
+    }
+
+    #[test]
+    fn test_heal_reports_fins_and_flipped_faces() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 2, 1, 1).unwrap();
+        let mut vertices = hull.vertices.clone();
+        let mut faces = hull.faces.clone();
+        let [a, b, _] = faces[0];
+        vertices.push(Point::new(0.0, 0.0, 5.0));
+        faces.push([a, b, vertices.len() - 1]);
+        faces[3] = [faces[3][0], faces[3][2], faces[3][1]];
+        let (healed, report) = Mesh::new(vertices, faces).unwrap().heal(&HealOptions::default()).unwrap();
+        assert_eq!(report.non_manifold_edges.len(), 1);
+        let [p, q] = report.non_manifold_edges[0].map(|v| healed.vertices[v]);
+        let fin = [hull.vertices[a], hull.vertices[b]];
+        assert!(fin == [p, q] || fin == [q, p]);
+        assert!(!report.inconsistent_edges.is_empty());
+        assert!(!report.is_watertight());
+    }
+}
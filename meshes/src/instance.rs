@@ -0,0 +1,232 @@
+//! Transformed instances of a shared mesh
+//!
+//! Arrays of identical units, such as a farm of wave energy converters,
+//! need one panel set per unit in a different place. A [`MeshInstance`]
+//! holds the source mesh behind an `Arc` together with an affine matrix, so
+//! the units share vertex storage and are repositioned by replacing the
+//! matrix. [`Transformation`]s compose into the matrix without touching the
+//! vertices; panels are only built when asked for.
+
+use super::*;
+use nalgebra::{Matrix3, Matrix4, Rotation3, Unit};
+use std::sync::Arc;
+
+impl Transformation {
+    /// Homogeneous 4 × 4 matrix; a `Combined` list applies its first entry first
+    pub fn matrix(&self) -> Result<Matrix4<f64>> {
+        match self {
+            Transformation::Translation(offset) => Ok(Matrix4::new_translation(offset)),
+            Transformation::Rotation { axis, angle } => {
+                if axis.norm() <= f64::EPSILON {
+                    return Err(MeshError::TransformationError {
+                        message: "Rotation axis has zero length".to_string(),
+                    });
+                }
+                Ok(Rotation3::from_axis_angle(&Unit::new_normalize(*axis), *angle).to_homogeneous())
+            }
+            Transformation::Scaling { x, y, z } => {
+                if [x, y, z].iter().any(|s| **s == 0.0 || !s.is_finite()) {
+                    return Err(MeshError::TransformationError {
+                        message: format!("Scaling factors ({}, {}, {}) must be finite and non-zero", x, y, z),
+                    });
+                }
+                Ok(Matrix4::new_nonuniform_scaling(&Vector::new(*x, *y, *z)))
+            }
+            Transformation::Combined(steps) => {
+                steps.iter().try_fold(Matrix4::identity(), |matrix, step| Ok(step.matrix()? * matrix))
+            }
+        }
+    }
+
+    /// This transformation followed by `next`
+    pub fn then(self, next: Transformation) -> Transformation {
+        match self {
+            Transformation::Combined(mut steps) => {
+                steps.push(next);
+                Transformation::Combined(steps)
+            }
+            first => Transformation::Combined(vec![first, next]),
+        }
+    }
+}
+
+/// A shared mesh placed by an affine transformation
+#[derive(Debug, Clone)]
+pub struct MeshInstance {
+    mesh: Arc<Mesh>,
+    matrix: Matrix4<f64>,
+}
+
+impl MeshInstance {
+    /// Instance of `mesh` in its own coordinates
+    pub fn new(mesh: Arc<Mesh>) -> Self {
+        Self {
+            mesh,
+            matrix: Matrix4::identity(),
+        }
+    }
+
+    /// Instance of `mesh` placed by `transformation`
+    pub fn with_transformation(mesh: Arc<Mesh>, transformation: &Transformation) -> Result<Self> {
+        Ok(Self {
+            mesh,
+            matrix: transformation.matrix()?,
+        })
+    }
+
+    /// One instance of `mesh` translated to each position, all sharing its storage
+    pub fn array(mesh: Arc<Mesh>, positions: &[Vector]) -> Vec<Self> {
+        positions
+            .iter()
+            .map(|offset| Self {
+                mesh: Arc::clone(&mesh),
+                matrix: Matrix4::new_translation(offset),
+            })
+            .collect()
+    }
+
+    /// Source mesh
+    pub fn mesh(&self) -> &Arc<Mesh> {
+        &self.mesh
+    }
+
+    /// Affine matrix from mesh to global coordinates
+    pub fn matrix(&self) -> &Matrix4<f64> {
+        &self.matrix
+    }
+
+    /// Apply `transformation` after the current placement
+    pub fn transform(&mut self, transformation: &Transformation) -> Result<&mut Self> {
+        self.matrix = transformation.matrix()? * self.matrix;
+        Ok(self)
+    }
+
+    /// Replace the placement, relative to the source mesh
+    pub fn set_transformation(&mut self, transformation: &Transformation) -> Result<()> {
+        self.matrix = transformation.matrix()?;
+        Ok(())
+    }
+
+    /// Whether two instances share the same source mesh
+    pub fn shares_mesh(&self, other: &MeshInstance) -> bool {
+        Arc::ptr_eq(&self.mesh, &other.mesh)
+    }
+
+    /// Placed vertex `i`
+    pub fn vertex(&self, i: usize) -> Point {
+        self.matrix.transform_point(&self.mesh.vertices[i])
+    }
+
+    /// Placed vertices, in the order of the source mesh
+    pub fn vertices(&self) -> impl Iterator<Item = Point> + '_ {
+        self.mesh.vertices.iter().map(|p| self.matrix.transform_point(p))
+    }
+
+    /// Placed unit normals, one per panel
+    pub fn normals(&self) -> impl Iterator<Item = Vector> + '_ {
+        // Normals map with the inverse transpose, which the constructors keep invertible
+        let linear: Matrix3<f64> = self.matrix.fixed_view::<3, 3>(0, 0).into_owned();
+        let cofactor = linear.try_inverse().unwrap_or(linear).transpose();
+        self.mesh.normals.iter().map(move |n| (cofactor * n).normalize())
+    }
+
+    /// Placed panels, triangles first
+    pub fn panels(&self) -> Result<Vec<Panel>> {
+        let vertices: Vec<Point> = self.vertices().collect();
+        self.polygons()
+            .map(|polygon| match polygon[..] {
+                [a, b, c] => Panel::new(vertices[a], vertices[b], vertices[c]),
+                [a, b, c, d] => Panel::quad(vertices[a], vertices[b], vertices[c], vertices[d]),
+                _ => unreachable!("panels are triangles or quads"),
+            })
+            .collect()
+    }
+
+    /// Standalone mesh of the placed instance
+    pub fn to_mesh(&self) -> Result<Mesh> {
+        let polygons: Vec<Vec<usize>> = self.polygons().collect();
+        let faces = polygons.iter().filter(|p| p.len() == 3).map(|p| [p[0], p[1], p[2]]).collect();
+        let quads = polygons.iter().filter(|p| p.len() == 4).map(|p| [p[0], p[1], p[2], p[3]]).collect();
+        Mesh::with_quads(self.vertices().collect(), faces, quads)
+    }
+
+    /// Panel corners, reversed under a reflection so normals keep pointing out
+    fn polygons(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        let mirrored = self.matrix.fixed_view::<3, 3>(0, 0).determinant() < 0.0;
+        self.mesh.polygons().map(move |polygon| {
+            let mut polygon = polygon.to_vec();
+            if mirrored {
+                polygon.reverse();
+            }
+            polygon
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn volume(mesh: &Mesh) -> f64 {
+        mesh.triangulated()
+            .unwrap()
+            .faces
+            .iter()
+            .map(|&[a, b, c]| mesh.vertices[a].coords.dot(&mesh.vertices[b].coords.cross(&mesh.vertices[c].coords)) / 6.0)
+            .sum()
+    }
+
+    #[test]
+    fn test_transformations_compose_in_order() {
+        let p = Point::new(1.0, 0.0, 0.0);
+        let shift = Transformation::Translation(Vector::new(1.0, 0.0, 0.0));
+        let turn = Transformation::Rotation { axis: Vector::z(), angle: FRAC_PI_2 };
+        let shift_then_turn = shift.clone().then(turn.clone()).matrix().unwrap();
+        let turn_then_shift = turn.then(shift).matrix().unwrap();
+        assert!((shift_then_turn.transform_point(&p) - Point::new(0.0, 2.0, 0.0)).norm() < 1e-12);
+        assert!((turn_then_shift.transform_point(&p) - Point::new(1.0, 1.0, 0.0)).norm() < 1e-12);
+
+        assert!(Transformation::Rotation { axis: Vector::zeros(), angle: 1.0 }.matrix().is_err());
+        assert!(Transformation::Scaling { x: 1.0, y: 0.0, z: 1.0 }.matrix().is_err());
+    }
+
+    #[test]
+    fn test_instances_share_and_reposition() {
+        let mesh = Arc::new(PredefinedGeometry::box_hull(2.0, 1.0, 1.0, 2, 1, 1).unwrap());
+        let positions: Vec<Vector> = (0..4).map(|i| Vector::new(10.0 * i as f64, 0.0, 0.0)).collect();
+        let mut farm = MeshInstance::array(Arc::clone(&mesh), &positions);
+        assert_eq!(Arc::strong_count(&mesh), 5);
+        assert!(farm[0].shares_mesh(&farm[3]));
+        assert!((farm[2].vertex(0) - (mesh.vertices[0] + positions[2])).norm() < 1e-12);
+
+        farm[1]
+            .transform(&Transformation::Rotation { axis: Vector::z(), angle: FRAC_PI_2 })
+            .unwrap();
+        let placed = farm[1].to_mesh().unwrap();
+        assert!((volume(&placed) - 2.0).abs() < 1e-12);
+        for (n, expected) in farm[1].normals().zip(&placed.normals) {
+            assert!((n - expected).norm() < 1e-12);
+        }
+        let panels = farm[1].panels().unwrap();
+        assert!(panels.iter().zip(&placed.normals).all(|(p, n)| (p.normal() - n).norm() < 1e-12));
+
+        farm[1].set_transformation(&Transformation::Translation(Vector::new(0.0, 5.0, 0.0))).unwrap();
+        assert!((farm[1].vertex(0) - (mesh.vertices[0] + Vector::new(0.0, 5.0, 0.0))).norm() < 1e-12);
+        assert_eq!(mesh.vertices, PredefinedGeometry::box_hull(2.0, 1.0, 1.0, 2, 1, 1).unwrap().vertices);
+    }
+
+    #[test]
+    fn test_mirror_keeps_normals_outward() {
+        let mesh = Arc::new(PredefinedGeometry::box_hull(2.0, 1.0, 1.0, 2, 1, 1).unwrap());
+        let shifted = Transformation::Translation(Vector::new(0.0, 3.0, 0.0));
+        let mirror = shifted.then(Transformation::Scaling { x: 1.0, y: -1.0, z: 2.0 });
+        let instance = MeshInstance::with_transformation(mesh, &mirror).unwrap();
+        let placed = instance.to_mesh().unwrap();
+        assert!((volume(&placed) - 4.0).abs() < 1e-12);
+        assert!(placed.vertices.iter().all(|p| p.y < 0.0));
+        for (n, expected) in instance.normals().zip(&placed.normals) {
+            assert!((n - expected).norm() < 1e-12);
+        }
+    }
+}
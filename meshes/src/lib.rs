@@ -9,10 +9,24 @@
 //! ## Features
 //! 
 //! - **Mesh Data Structures**: Efficient mesh representation
-//! - **Mesh Operations**: Transformations, validation, optimization
-//! - **Mesh Collections**: Multiple mesh management
-//! - **Predefined Geometries**: Sphere, cylinder, ship hulls
-//! - **Quality Checks**: Mesh validation and optimization
+//! - **Mesh Operations**: Transformations, validation, optimization, topology statistics with watertightness
+//! - **Mesh Collections**: Multiple mesh management with levels of detail per usage
+//! - **Predefined Geometries**: Sphere, cylinder, box barge, Wigley hull, semi-submersible, spar, TLP
+//! - **Mesh Instancing**: Shared meshes placed by lazily composed transformations
+//! - **Offset Tables**: Hull meshes lofted from stations and waterlines
+//! - **Symmetric Meshes**: Half- and quarter-body storage mirrored on demand
+//! - **Waterline Clipping**: Wetted meshes and waterplane polygons cut from full hulls
+//! - **Lid Generation**: Interior waterplane and moonpool lids triangulated from the waterline
+//! - **Free-Surface Grids**: Annular rings and graded rectangular patches around a hull
+//! - **NURBS Panelization**: Curvature-adaptive panels from trimmed CAD surface patches
+//! - **Mesh Healing**: Vertex welding, degenerate face removal and hole detection
+//! - **Normal Orientation**: Consistent outward winding per connected component
+//! - **Adaptive Refinement**: Conforming subdivision driven by curvature or solution jumps, and uniform series for convergence studies
+//! - **Decimation**: Quadric edge collapse holding displaced volume and waterplane area
+//! - **Spatial Index**: Bounding-volume hierarchy for nearest-panel, ray and inside queries
+//! - **Mesh Comparison**: Hausdorff distance, panel area changes and topology diffs
+//! - **Quality Checks**: Aspect ratio, skewness, warp and neighbour area ratio per panel
+//! - **Hull Morphing**: Free-form deformation and radial basis morphs driven by beam, fullness and bulb size
 //! 
 //! ## Example
 //! 
@@ -22,24 +36,53 @@
 //! // Create a sphere mesh
 //! let sphere = PredefinedGeometry::sphere(1.0, 32, 16)?;
 //! 
+//! // Check mesh quality
+//! let quality = sphere.quality();
+//! println!("Mesh quality: {:?}", quality);
+//! 
 //! // Create mesh collection
 //! let mut collection = MeshCollection::new();
-//! collection.add_mesh("sphere", sphere)?;
-//! 
-//! // Validate mesh quality
-//! let quality = collection.validate("sphere")?;
-//! println!("Mesh quality: {:?}", quality);
+//! collection.add_mesh("sphere".to_string(), sphere)?;
+//! # Ok::<(), wavecore_meshes::MeshError>(())
 //! ```
 
 pub mod mesh;
 pub mod collections;
 pub mod predefined;
+pub mod offsets;
+pub mod symmetric;
+pub mod instance;
+pub mod clipping;
+pub mod lid;
+pub mod free_surface;
+pub mod nurbs;
+pub mod healing;
+pub mod orientation;
 pub mod refinement;
+pub mod decimation;
 pub mod quality;
+pub mod bvh;
+pub mod comparison;
+pub mod morphing;
 
 pub use mesh::*;
 pub use collections::*;
 pub use predefined::*;
+pub use offsets::*;
+pub use symmetric::SymmetricMesh;
+pub use instance::MeshInstance;
+pub use clipping::ClippedMesh;
+pub use lid::{generate_lid, generate_moonpool_lid};
+pub use free_surface::{FreeSurfacePatch, FreeSurfaceRing};
+pub use nurbs::{NurbsCurve, NurbsSurface, PanelizeOptions};
+pub use healing::{HealOptions, HealReport};
+pub use orientation::OrientationReport;
+pub use refinement::{RefinementOptions, RefinementReport};
+pub use decimation::{DecimationOptions, DecimationReport};
+pub use quality::PanelQuality;
+pub use bvh::{PanelHit, PanelTree};
+pub use comparison::{MeshComparison, MeshTopology};
+pub use morphing::{FfdLattice, HullDesign, RbfMorph};
 
 use thiserror::Error;
 use nalgebra::{Point3, Vector3};
@@ -93,6 +136,14 @@ pub struct MeshQuality {
     pub inverted_elements: usize,
     /// Total number of elements
     pub total_elements: usize,
+    /// Largest element aspect ratio (1 is ideal)
+    pub max_aspect_ratio: f64,
+    /// Largest equiangle skewness (0-1)
+    pub max_skewness: f64,
+    /// Largest quad warp relative to the panel size
+    pub max_warp: f64,
+    /// Largest area ratio between neighbouring elements
+    pub max_area_ratio: f64,
 }
 
 impl Default for MeshQuality {
@@ -104,6 +155,10 @@ impl Default for MeshQuality {
             degenerate_elements: 0,
             inverted_elements: 0,
             total_elements: 0,
+            max_aspect_ratio: 1.0,
+            max_skewness: 0.0,
+            max_warp: 0.0,
+            max_area_ratio: 1.0,
         }
     }
 }
@@ -139,17 +194,29 @@ pub enum Transformation {
 /// Mesh statistics
 #[derive(Debug, Clone)]
 pub struct MeshStats {
-    /// Number of vertices
+    /// Number of vertices used by a panel
     pub vertices: usize,
-    /// Number of faces
+    /// Number of faces, triangles and quads
     pub faces: usize,
-    /// Number of edges
+    /// Number of distinct edges
     pub edges: usize,
+    /// Edges on one panel only
+    pub boundary_edges: usize,
+    /// Edges on three or more panels
+    pub non_manifold_edges: usize,
+    /// Closed chains of boundary edges
+    pub boundary_loops: usize,
+    /// Edge-connected groups of panels
+    pub components: usize,
+    /// V - E + F
+    pub euler_characteristic: i64,
+    /// Closed two-manifold: no boundary or non-manifold edges
+    pub watertight: bool,
     /// Bounding box
     pub bounding_box: (Point, Point),
     /// Surface area
     pub surface_area: f64,
-    /// Volume
+    /// Enclosed volume by the divergence theorem, positive for outward normals
     pub volume: f64,
 }
 
@@ -159,6 +226,12 @@ impl Default for MeshStats {
             vertices: 0,
             faces: 0,
             edges: 0,
+            boundary_edges: 0,
+            non_manifold_edges: 0,
+            boundary_loops: 0,
+            components: 0,
+            euler_characteristic: 0,
+            watertight: false,
             bounding_box: (Point::origin(), Point::origin()),
             surface_area: 0.0,
             volume: 0.0,
@@ -0,0 +1,319 @@
+//! Lid meshes on the free surface bounded by a hull waterline
+//!
+//! Closing the interior waterplane of a surface-piercing hull with a lid
+//! removes the irregular frequencies of the boundary integral equations,
+//! and moonpool openings are covered by a lid to carry a damping condition.
+//! [`generate_lid`] and [`generate_moonpool_lid`] build these meshes from the
+//! waterline loops of [`Mesh::clip_at_waterline`].
+//!
+//! Each region is ear-clipped, with holes bridged into their outer loop, and
+//! then alternately refined until no edge exceeds the panel size and swapped
+//! to a constrained Delaunay triangulation, so panels stay close to
+//! equilateral. Lid normals point up, +z.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Triangulated lid over the waterplane enclosed by the hull at z = `waterline`
+///
+/// Moonpool openings are left out; see [`generate_moonpool_lid`].
+pub fn generate_lid(mesh: &Mesh, waterline: f64, panel_size: f64) -> Result<Mesh> {
+    let (outer, holes) = waterline_loops(mesh, waterline)?;
+    triangulate_lid(outer, holes, waterline, panel_size)
+}
+
+/// Triangulated lid over the free surface inside the hull's moonpools
+pub fn generate_moonpool_lid(mesh: &Mesh, waterline: f64, panel_size: f64) -> Result<Mesh> {
+    let (_, holes) = waterline_loops(mesh, waterline)?;
+    if holes.is_empty() {
+        return Err(MeshError::InvalidGeometry {
+            message: format!("Hull has no moonpool opening at z = {}", waterline),
+        });
+    }
+    let openings = holes
+        .into_iter()
+        .map(|mut hole| {
+            hole.reverse();
+            hole
+        })
+        .collect();
+    triangulate_lid(openings, Vec::new(), waterline, panel_size)
+}
+
+/// Waterline loops in the plane, split into counter-clockwise outer loops and clockwise holes
+type Loops = (Vec<Vec<[f64; 2]>>, Vec<Vec<[f64; 2]>>);
+
+fn waterline_loops(mesh: &Mesh, waterline: f64) -> Result<Loops> {
+    let clipped = mesh.clip_at_waterline(waterline)?;
+    if clipped.waterplane.is_empty() {
+        return Err(MeshError::InvalidGeometry {
+            message: format!("Hull does not pierce the free surface at z = {}", waterline),
+        });
+    }
+    let (mut outer, mut holes) = (Vec::new(), Vec::new());
+    for polygon in clipped.waterplane {
+        let polygon: Vec<[f64; 2]> = polygon.iter().map(|p| [p.x, p.y]).collect();
+        if signed_area(&polygon) > 0.0 {
+            outer.push(polygon);
+        } else {
+            holes.push(polygon);
+        }
+    }
+    Ok((outer, holes))
+}
+
+fn triangulate_lid(outer: Vec<Vec<[f64; 2]>>, holes: Vec<Vec<[f64; 2]>>, z: f64, panel_size: f64) -> Result<Mesh> {
+    if !(panel_size > 0.0 && panel_size.is_finite()) {
+        return Err(MeshError::InvalidGeometry {
+            message: format!("Lid panel size {} must be positive and finite", panel_size),
+        });
+    }
+    let mut xy = Vec::new();
+    let add = |polygon: Vec<[f64; 2]>, xy: &mut Vec<[f64; 2]>| -> Vec<usize> {
+        xy.extend(polygon.iter().copied());
+        (xy.len() - polygon.len()..xy.len()).collect()
+    };
+    let holes: Vec<Vec<usize>> = holes.into_iter().map(|hole| add(hole, &mut xy)).collect();
+    let mut faces = Vec::new();
+    for polygon in outer {
+        let polygon = add(polygon, &mut xy);
+        let inside: Vec<Vec<usize>> = holes
+            .iter()
+            .filter(|hole| contains(&xy, &polygon, xy[hole[0]]))
+            .cloned()
+            .collect();
+        let merged = bridge_holes(&xy, polygon, inside)?;
+        faces.extend(ear_clip(&xy, merged)?);
+    }
+
+    let lift = |xy: &[[f64; 2]]| xy.iter().map(|&[x, y]| Point::new(x, y, z)).collect();
+    let mut lid = Mesh::new(lift(&xy), faces)?;
+    for _ in 0..64 {
+        let xy: Vec<[f64; 2]> = lid.vertices.iter().map(|p| [p.x, p.y]).collect();
+        let mut faces = lid.faces.clone();
+        delaunay_swaps(&xy, &mut faces);
+        lid = Mesh::new(lid.vertices.clone(), faces)?;
+        let long = |&[a, b, c]: &[usize; 3]| [(a, b), (b, c), (c, a)].iter().any(|&(p, q)| distance(xy[p], xy[q]) > panel_size);
+        let marked: Vec<bool> = lid.faces.iter().map(long).collect();
+        if !marked.contains(&true) {
+            break;
+        }
+        lid = lid.refine_panels(&marked)?.0;
+    }
+    Ok(lid)
+}
+
+/// Merge clockwise holes into a counter-clockwise polygon through bridge edges
+fn bridge_holes(xy: &[[f64; 2]], mut polygon: Vec<usize>, mut holes: Vec<Vec<usize>>) -> Result<Vec<usize>> {
+    // Bridging from the rightmost hole first keeps later bridges clear of earlier ones
+    let rightmost = |hole: &Vec<usize>| hole.iter().map(|&v| xy[v][0]).fold(f64::NEG_INFINITY, f64::max);
+    holes.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+    for h in 0..holes.len() {
+        let hole = &holes[h];
+        let m = (0..hole.len()).max_by(|&i, &j| xy[hole[i]][0].total_cmp(&xy[hole[j]][0])).unwrap();
+        let from = hole[m];
+        let blocking = |to: usize| {
+            let loops = std::iter::once(&polygon).chain(&holes[h..]);
+            loops.flat_map(|l| (0..l.len()).map(move |k| (l[k], l[(k + 1) % l.len()]))).any(|(a, b)| {
+                ![a, b].contains(&from) && ![a, b].contains(&to) && segments_cross(xy[from], xy[to], xy[a], xy[b])
+            })
+        };
+        let i = (0..polygon.len())
+            .filter(|&i| !blocking(polygon[i]))
+            .min_by(|&i, &j| distance(xy[from], xy[polygon[i]]).total_cmp(&distance(xy[from], xy[polygon[j]])))
+            .ok_or_else(|| MeshError::InvalidGeometry {
+                message: "Waterline hole cannot be bridged to its outer loop".to_string(),
+            })?;
+        let mut merged = polygon[..=i].to_vec();
+        merged.extend((0..=hole.len()).map(|k| hole[(m + k) % hole.len()]));
+        merged.extend_from_slice(&polygon[i..]);
+        polygon = merged;
+    }
+    Ok(polygon)
+}
+
+/// Ear-clipping triangulation of a counter-clockwise polygon
+fn ear_clip(xy: &[[f64; 2]], mut polygon: Vec<usize>) -> Result<Vec<[usize; 3]>> {
+    let scale = polygon.iter().map(|&v| xy[v][0].abs().max(xy[v][1].abs())).fold(1.0, f64::max);
+    let tol = 1e-12 * scale * scale;
+    let mut faces = Vec::with_capacity(polygon.len().saturating_sub(2));
+    let mut k = 0;
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let ear = (0..n).map(|i| (k + i) % n).find(|&i| {
+            let [a, b, c] = [polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]];
+            cross(xy[a], xy[b], xy[c]) > tol
+                && polygon
+                    .iter()
+                    .filter(|&&v| ![a, b, c].contains(&v))
+                    .all(|&v| !in_triangle(xy[v], xy[a], xy[b], xy[c], tol))
+        });
+        let Some(i) = ear else {
+            return Err(MeshError::InvalidGeometry {
+                message: "Waterline loop is self-intersecting and cannot be triangulated".to_string(),
+            });
+        };
+        faces.push([polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]]);
+        polygon.remove(i);
+        k = i % polygon.len();
+    }
+    if let [a, b, c] = polygon[..] {
+        if cross(xy[a], xy[b], xy[c]) > tol {
+            faces.push([a, b, c]);
+        }
+    }
+    Ok(faces)
+}
+
+/// Swap interior edges until every pair of triangles is locally Delaunay
+fn delaunay_swaps(xy: &[[f64; 2]], faces: &mut [[usize; 3]]) {
+    for _ in 0..faces.len().max(16) {
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for (f, &[a, b, c]) in faces.iter().enumerate() {
+            edges.extend([((a, b), f), ((b, c), f), ((c, a), f)]);
+        }
+        let mut touched = vec![false; faces.len()];
+        let mut swapped = false;
+        for f in 0..faces.len() {
+            for k in 0..3 {
+                let [a, b, c] = [faces[f][k], faces[f][(k + 1) % 3], faces[f][(k + 2) % 3]];
+                let Some(&g) = edges.get(&(b, a)) else { continue };
+                if touched[f] || touched[g] {
+                    continue;
+                }
+                let d = faces[g].iter().copied().find(|&v| v != a && v != b).unwrap();
+                if in_circle(xy[a], xy[b], xy[c], xy[d]) && cross(xy[a], xy[d], xy[c]) > 0.0 && cross(xy[d], xy[b], xy[c]) > 0.0 {
+                    faces[f] = [a, d, c];
+                    faces[g] = [d, b, c];
+                    touched[f] = true;
+                    touched[g] = true;
+                    swapped = true;
+                }
+            }
+        }
+        if !swapped {
+            break;
+        }
+    }
+}
+
+/// Twice the signed area of the triangle a, b, c
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn signed_area(polygon: &[[f64; 2]]) -> f64 {
+    let n = polygon.len();
+    0.5 * (0..n).map(|i| polygon[i][0] * polygon[(i + 1) % n][1] - polygon[(i + 1) % n][0] * polygon[i][1]).sum::<f64>()
+}
+
+fn distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+/// Point p inside or on the counter-clockwise triangle a, b, c
+fn in_triangle(p: [f64; 2], a: [f64; 2], b: [f64; 2], c: [f64; 2], tol: f64) -> bool {
+    cross(a, b, p) >= -tol && cross(b, c, p) >= -tol && cross(c, a, p) >= -tol
+}
+
+/// Point d strictly inside the circumcircle of the counter-clockwise triangle a, b, c
+fn in_circle(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> bool {
+    let [a, b, c] = [a, b, c].map(|p| [p[0] - d[0], p[1] - d[1]]);
+    let lift = |p: [f64; 2]| p[0] * p[0] + p[1] * p[1];
+    let det = lift(a) * (b[0] * c[1] - c[0] * b[1]) - lift(b) * (a[0] * c[1] - c[0] * a[1]) + lift(c) * (a[0] * b[1] - b[0] * a[1]);
+    let size = lift(a).max(lift(b)).max(lift(c));
+    det > 1e-12 * size * size
+}
+
+/// Segments p-q and a-b cross at a point interior to both
+fn segments_cross(p: [f64; 2], q: [f64; 2], a: [f64; 2], b: [f64; 2]) -> bool {
+    let (d1, d2) = (cross(p, q, a), cross(p, q, b));
+    let (d3, d4) = (cross(a, b, p), cross(a, b, q));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Even-odd test of a point against a closed loop
+fn contains(xy: &[[f64; 2]], polygon: &[usize], p: [f64; 2]) -> bool {
+    let n = polygon.len();
+    (0..n)
+        .filter(|&i| {
+            let (a, b) = (xy[polygon[i]], xy[polygon[(i + 1) % n]]);
+            (a[1] > p[1]) != (b[1] > p[1]) && p[0] < a[0] + (p[1] - a[1]) * (b[0] - a[0]) / (b[1] - a[1])
+        })
+        .count()
+        % 2
+        == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square barge of side `side` and draft `draft` with a square moonpool of side `pool`
+    fn moonpool_barge(side: f64, pool: f64, draft: f64) -> Mesh {
+        let square = |half: f64, z: f64| [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]].map(|[x, y]| Point::new(half * x, half * y, z));
+        let vertices = [square(side / 2.0, 0.0), square(side / 2.0, -draft), square(pool / 2.0, 0.0), square(pool / 2.0, -draft)].concat();
+        let quads = (0..4)
+            .flat_map(|k| {
+                let next = (k + 1) % 4;
+                [[4 + k, 4 + next, next, k], [12 + next, 12 + k, 8 + k, 8 + next], [4 + next, 4 + k, 12 + k, 12 + next]]
+            })
+            .collect();
+        Mesh::with_quads(vertices, Vec::new(), quads).unwrap()
+    }
+
+    fn max_edge(mesh: &Mesh) -> f64 {
+        mesh.faces
+            .iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .map(|(a, b)| (mesh.vertices[a] - mesh.vertices[b]).norm())
+            .fold(0.0, f64::max)
+    }
+
+    fn area(mesh: &Mesh) -> f64 {
+        mesh.triangulated().unwrap().faces.iter().map(|&[a, b, c]| {
+            let [a, b, c] = [a, b, c].map(|v| mesh.vertices[v]);
+            0.5 * (b - a).cross(&(c - a)).z
+        }).sum()
+    }
+
+    #[test]
+    fn test_box_and_cylinder_lids() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let lid = generate_lid(&hull, -0.5, 0.3).unwrap();
+        assert!((area(&lid) - 8.0).abs() < 1e-9);
+        assert!(max_edge(&lid) <= 0.3);
+        assert!(lid.vertices.iter().all(|p| p.z == -0.5));
+        assert!(lid.normals.iter().all(|n| (n.z - 1.0).abs() < 1e-12));
+        assert_eq!(lid.quality().inverted_elements, 0);
+        assert!(lid.quality().max_aspect_ratio < 2.0);
+
+        let (radius, n) = (1.5, 24);
+        let cylinder = PredefinedGeometry::cylinder(radius, 2.0, n, 4).unwrap();
+        let lid = generate_lid(&cylinder, -0.8, 0.25).unwrap();
+        let polygon = 0.5 * n as f64 * radius * radius * (2.0 * std::f64::consts::PI / n as f64).sin();
+        assert!((area(&lid) - polygon).abs() < 1e-9);
+        assert!(max_edge(&lid) <= 0.25);
+
+        assert!(generate_lid(&hull, -0.5, 0.0).is_err());
+        assert!(generate_moonpool_lid(&hull, -0.5, 0.3).is_err());
+    }
+
+    #[test]
+    fn test_lids_around_and_inside_a_moonpool() {
+        let (side, pool) = (6.0, 2.0);
+        let barge = moonpool_barge(side, pool, 2.0);
+        let lid = generate_lid(&barge, -0.5, 0.5).unwrap();
+        assert!((area(&lid) - (side * side - pool * pool)).abs() < 1e-9);
+        assert!(max_edge(&lid) <= 0.5);
+        assert!(lid.faces.iter().all(|&[a, b, c]| {
+            let centre = (lid.vertices[a].coords + lid.vertices[b].coords + lid.vertices[c].coords) / 3.0;
+            centre.x.abs().max(centre.y.abs()) > pool / 2.0
+        }));
+
+        let opening = generate_moonpool_lid(&barge, -0.5, 0.5).unwrap();
+        assert!((area(&opening) - pool * pool).abs() < 1e-9);
+        assert!(opening.normals.iter().all(|n| n.z > 0.0));
+        assert!(opening.vertices.iter().all(|p| p.x.abs() <= pool / 2.0 + 1e-12 && p.y.abs() <= pool / 2.0 + 1e-12));
+    }
+}
@@ -5,8 +5,10 @@ use super::*;
 /// Panel representation for BEM computations
 #[derive(Debug, Clone)]
 pub struct Panel {
-    /// Panel vertices (3 points for triangular panel)
+    /// Panel vertices (3 points for triangular panel, the first 3 corners of a quad)
     pub vertices: [Point; 3],
+    /// Fourth corner of a quadrilateral panel
+    pub fourth: Option<Point>,
     /// Panel normal vector
     pub normal: Vector,
     /// Panel centroid
@@ -43,12 +45,60 @@ impl Panel {
         
         Ok(Self {
             vertices,
+            fourth: None,
             normal,
             centroid,
             area,
         })
     }
     
+    /// Create a quadrilateral panel from four corners in winding order
+    ///
+    /// The panel is the bilinear surface through the corners, so a warped
+    /// quad keeps its curvature. Area and centroid are integrated with 3×3
+    /// Gauss points; the normal is that of the panel's vector area.
+    pub fn quad(v0: Point, v1: Point, v2: Point, v3: Point) -> Result<Self> {
+        let normal = 0.5 * (v2 - v0).cross(&(v3 - v1));
+        let (points, weights) = ([-0.6_f64.sqrt(), 0.0, 0.6_f64.sqrt()], [5.0 / 9.0, 8.0 / 9.0, 5.0 / 9.0]);
+        let mut area = 0.0;
+        let mut moment = Vector::zeros();
+        for (xi, wx) in points.iter().zip(&weights) {
+            for (eta, wy) in points.iter().zip(&weights) {
+                let corner = |c: &Point, sx: f64, sy: f64| c.coords * 0.25 * (1.0 + sx * xi) * (1.0 + sy * eta);
+                let position = corner(&v0, -1.0, -1.0) + corner(&v1, 1.0, -1.0) + corner(&v2, 1.0, 1.0) + corner(&v3, -1.0, 1.0);
+                let d_xi = 0.25 * ((v1 - v0) * (1.0 - eta) + (v2 - v3) * (1.0 + eta));
+                let d_eta = 0.25 * ((v3 - v0) * (1.0 - xi) + (v2 - v1) * (1.0 + xi));
+                let jacobian = d_xi.cross(&d_eta).norm() * wx * wy;
+                area += jacobian;
+                moment += position * jacobian;
+            }
+        }
+        
+        if area < 1e-12 || normal.norm() < 1e-12 {
+            return Err(MeshError::InvalidData {
+                message: "Degenerate panel with zero area".to_string(),
+            });
+        }
+        
+        Ok(Self {
+            vertices: [v0, v1, v2],
+            fourth: Some(v3),
+            normal: normal.normalize(),
+            centroid: Point::from(moment / area),
+            area,
+        })
+    }
+    
+    /// Whether the panel is a quadrilateral
+    pub fn is_quad(&self) -> bool {
+        self.fourth.is_some()
+    }
+    
+    /// All corners in winding order
+    pub fn corners(&self) -> Vec<Point> {
+        self.vertices.iter().copied().chain(self.fourth).collect()
+    }
+    
     /// Get panel centroid
     pub fn centroid(&self) -> Point {
         self.centroid
@@ -76,10 +126,14 @@ impl Panel {
 }
 
 /// Mesh representation
+///
+/// Panels are the triangles of `faces` followed by the quadrilaterals of
+/// `quads`; `normals` holds one normal per panel in the same order.
 #[derive(Debug, Clone)]
 pub struct Mesh {
     pub vertices: Vec<Point>,
     pub faces: Vec<[usize; 3]>,
+    pub quads: Vec<[usize; 4]>,
     pub normals: Vec<Vector>,
     panels: Option<Vec<Panel>>, // Cached panels for BEM
 }
@@ -99,11 +153,105 @@ impl Mesh {
         Ok(Self {
             vertices,
             faces,
+            quads: Vec::new(),
+            normals,
+            panels: None,
+        })
+    }
+    
+    /// Create a mesh of triangular and quadrilateral panels
+    pub fn with_quads(vertices: Vec<Point>, faces: Vec<[usize; 3]>, quads: Vec<[usize; 4]>) -> Result<Self> {
+        if faces.is_empty() && quads.is_empty() {
+            return Err(MeshError::InvalidData {
+                message: "Mesh must have at least one face".to_string(),
+            });
+        }
+        if quads.iter().flatten().any(|&v| v >= vertices.len()) {
+            return Err(MeshError::InvalidData {
+                message: "Face indices out of bounds".to_string(),
+            });
+        }
+        
+        let mut normals = Self::calculate_normals(&vertices, &faces)?;
+        normals.extend(quads.iter().map(|q| {
+            let [a, b, c, d] = q.map(|v| vertices[v]);
+            (c - a).cross(&(d - b)).normalize()
+        }));
+        
+        Ok(Self {
+            vertices,
+            faces,
+            quads,
             normals,
             panels: None,
         })
     }
     
+    /// Number of panels, triangles and quads
+    pub fn n_panels(&self) -> usize {
+        self.faces.len() + self.quads.len()
+    }
+    
+    /// Corner indices of every panel, triangles first
+    pub fn polygons(&self) -> impl Iterator<Item = &[usize]> {
+        self.faces.iter().map(|f| &f[..]).chain(self.quads.iter().map(|q| &q[..]))
+    }
+    
+    /// Copy with each quad split into two triangles along its shorter diagonal
+    pub fn triangulated(&self) -> Result<Self> {
+        if self.quads.is_empty() {
+            return Ok(self.clone());
+        }
+        let mut faces = self.faces.clone();
+        for &[a, b, c, d] in &self.quads {
+            let (pa, pb, pc, pd) = (self.vertices[a], self.vertices[b], self.vertices[c], self.vertices[d]);
+            if (pc - pa).norm() <= (pd - pb).norm() {
+                faces.extend([[a, b, c], [a, c, d]]);
+            } else {
+                faces.extend([[a, b, d], [b, c, d]]);
+            }
+        }
+        Self::new(self.vertices.clone(), faces)
+    }
+    
+    /// Counts, edge topology, extent, area and volume of the mesh
+    pub fn stats(&self) -> MeshStats {
+        let topology = self.topology();
+        let (mut min, mut max) = (Point::from(Vector::repeat(f64::INFINITY)), Point::from(Vector::repeat(f64::NEG_INFINITY)));
+        let (mut surface_area, mut volume) = (0.0, 0.0);
+        for polygon in self.polygons() {
+            for &v in polygon {
+                min = min.inf(&self.vertices[v]);
+                max = max.sup(&self.vertices[v]);
+            }
+            // Fan from the first corner
+            let p0 = self.vertices[polygon[0]].coords;
+            for k in 1..polygon.len() - 1 {
+                let (p1, p2) = (self.vertices[polygon[k]].coords, self.vertices[polygon[k + 1]].coords);
+                surface_area += 0.5 * (p1 - p0).cross(&(p2 - p0)).norm();
+                volume += p0.dot(&p1.cross(&p2)) / 6.0;
+            }
+        }
+        if topology.vertices == 0 {
+            (min, max) = (Point::origin(), Point::origin());
+        }
+
+        MeshStats {
+            vertices: topology.vertices,
+            faces: topology.panels,
+            edges: topology.edges,
+            boundary_edges: topology.boundary_edges,
+            non_manifold_edges: topology.non_manifold_edges,
+            boundary_loops: topology.boundary_loops,
+            components: topology.components,
+            euler_characteristic: topology.euler_characteristic,
+            watertight: topology.panels > 0 && topology.boundary_edges == 0 && topology.non_manifold_edges == 0,
+            bounding_box: (min, max),
+            surface_area,
+            volume,
+        }
+    }
+    
     /// Get panels for BEM computation (creates and caches if needed)
     pub fn panels(&mut self) -> Result<&[Panel]> {
         if self.panels.is_none() {
@@ -126,6 +274,11 @@ impl Mesh {
                 panels.push(panel);
             }
             
+            for quad in &self.quads {
+                let [v0, v1, v2, v3] = quad.map(|v| self.vertices[v]);
+                panels.push(Panel::quad(v0, v1, v2, v3)?);
+            }
+            
             self.panels = Some(panels);
         }
         
@@ -161,4 +314,77 @@ impl Mesh {
         
         Ok(normals)
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[[f64; 3]]) -> Vec<Point> {
+        coords.iter().map(|&[x, y, z]| Point::new(x, y, z)).collect()
+    }
+
+    #[test]
+    fn test_planar_quad_matches_its_triangles() {
+        let v = points(&[[0.0, 0.0, -1.0], [3.0, 0.0, -1.0], [2.0, 1.0, -1.0], [0.0, 2.0, -1.0]]);
+        let quad = Panel::quad(v[0], v[1], v[2], v[3]).unwrap();
+        let (a, b) = (Panel::new(v[0], v[1], v[2]).unwrap(), Panel::new(v[0], v[2], v[3]).unwrap());
+        assert!(quad.is_quad() && !a.is_quad());
+        assert_eq!(quad.corners().len(), 4);
+        assert!((quad.area() - a.area() - b.area()).abs() < 1e-12);
+        let centroid = (a.centroid().coords * a.area() + b.centroid().coords * b.area()) / quad.area();
+        assert!((quad.centroid().coords - centroid).norm() < 1e-12);
+        assert!((quad.normal() - Vector::z()).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_warped_quad_is_bilinear() {
+        // Hyperbolic paraboloid z = xy over the unit square
+        let v = points(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 1.0], [0.0, 1.0, 0.0]]);
+        let quad = Panel::quad(v[0], v[1], v[2], v[3]).unwrap();
+        let flat = Panel::new(v[0], v[1], v[2]).unwrap().area() + Panel::new(v[0], v[2], v[3]).unwrap().area();
+        assert!(quad.area() > 1.0 && quad.area() < flat);
+        assert!((quad.area() - 1.2807).abs() < 1e-3);
+        let c = quad.centroid();
+        assert!((c.x - c.y).abs() < 1e-12 && c.x > 0.5 && c.z > 0.25);
+        assert!((quad.normal() - Vector::new(-1.0, -1.0, 2.0).normalize()).norm() < 1e-12);
+        assert!(Panel::quad(v[0], v[1], v[1], v[0]).is_err());
+    }
+
+    #[test]
+    fn test_mixed_mesh_panels_and_triangulation() {
+        let vertices = points(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0], [2.0, 0.0, 0.0]]);
+        let mut mesh = Mesh::with_quads(vertices, vec![[1, 4, 2]], vec![[0, 1, 2, 3]]).unwrap();
+        assert_eq!((mesh.n_panels(), mesh.normals.len()), (2, 2));
+        assert_eq!(mesh.polygons().map(<[usize]>::len).collect::<Vec<_>>(), [3, 4]);
+        let areas: Vec<f64> = mesh.panels().unwrap().iter().map(Panel::area).collect();
+        assert!((areas[0] - 0.5).abs() < 1e-12 && (areas[1] - 1.0).abs() < 1e-12);
+
+        let triangles = mesh.triangulated().unwrap();
+        assert!(triangles.quads.is_empty());
+        assert_eq!(triangles.faces.len(), 3);
+        assert!(triangles.normals.iter().all(|n| (n - Vector::z()).norm() < 1e-12));
+        assert!(Mesh::with_quads(mesh.vertices.clone(), Vec::new(), vec![[0, 1, 2, 5]]).is_err());
+    }
+
+    #[test]
+    fn test_stats_of_closed_and_open_hulls() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let stats = hull.stats();
+        assert!(stats.watertight);
+        assert_eq!((stats.boundary_edges, stats.boundary_loops, stats.components), (0, 0, 1));
+        assert_eq!(stats.vertices as i64 - stats.edges as i64 + stats.faces as i64, 2);
+        assert_eq!(stats.euler_characteristic, 2);
+        assert!((stats.surface_area - 28.0).abs() < 1e-12);
+        assert!((stats.volume - 8.0).abs() < 1e-12);
+        assert!(((stats.bounding_box.1 - stats.bounding_box.0) - Vector::new(4.0, 2.0, 1.0)).norm() < 1e-12);
+
+        // Two separate holes, one a single panel and one a pair of neighbours
+        let far = hull.faces.iter().position(|f| f.iter().all(|&v| hull.vertices[v].x == 2.0)).unwrap();
+        let kept: Vec<[usize; 3]> = hull.faces.iter().enumerate().filter(|&(f, _)| f > 1 && f != far).map(|(_, f)| *f).collect();
+        let open = Mesh::new(hull.vertices.clone(), kept).unwrap().stats();
+        assert!(!open.watertight);
+        assert_eq!(open.boundary_loops, 2);
+        assert_eq!(open.euler_characteristic, 0);
+        assert_eq!(MeshStats::default().boundary_loops, 0);
+    }
+}
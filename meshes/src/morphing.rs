@@ -0,0 +1,299 @@
+//! Mesh morphing for hull-form variation
+//!
+//! Two deformations move the vertices of a mesh while keeping its panels:
+//! an [`FfdLattice`] embeds the mesh in a box of Bernstein control points
+//! (Sederberg and Parry) whose displacements bend everything inside
+//! smoothly, and an [`RbfMorph`] interpolates displacements prescribed at
+//! scattered centres with compactly supported Wendland functions, leaving
+//! the mesh outside their support untouched.
+//!
+//! [`Mesh::morph_hull`] combines them into a few design parameters (beam,
+//! fullness and bulb size) so that an optimizer can vary a baseline hull
+//! and evaluate each variant with the BEM solver, with the panel count and
+//! connectivity fixed across the loop.
+
+use super::*;
+use nalgebra::DMatrix;
+
+/// Free-form deformation lattice of Bernstein control points over a box
+#[derive(Debug, Clone)]
+pub struct FfdLattice {
+    /// Lower corner of the box
+    pub min: Point,
+    /// Upper corner of the box
+    pub max: Point,
+    /// Polynomial degree along x, y and z; one more control point than the degree per axis
+    pub degrees: [usize; 3],
+    /// Displacement of each control point, x index slowest
+    pub displacements: Vec<Vector>,
+}
+
+impl FfdLattice {
+    /// Lattice with no displacements over the box `min`-`max`
+    pub fn new(min: Point, max: Point, degrees: [usize; 3]) -> Result<Self> {
+        if (0..3).any(|d| max[d] <= min[d] || degrees[d] == 0) {
+            return Err(MeshError::InvalidGeometry {
+                message: "FFD lattice requires a box of positive size and degrees of at least 1".to_string(),
+            });
+        }
+        let count = degrees.iter().map(|d| d + 1).product();
+        Ok(Self {
+            min,
+            max,
+            degrees,
+            displacements: vec![Vector::zeros(); count],
+        })
+    }
+
+    /// Lattice over the bounding box of the panel vertices of a mesh
+    pub fn around(mesh: &Mesh, degrees: [usize; 3]) -> Result<Self> {
+        let (min, max) = mesh.stats().bounding_box;
+        // Flat meshes get a unit-thick box
+        let pad = Vector::from_iterator((0..3).map(|d| if max[d] > min[d] { 0.0 } else { 0.5 }));
+        Self::new(min - pad, max + pad, degrees)
+    }
+
+    /// Position in `displacements` of control point (i, j, k)
+    pub fn index(&self, i: usize, j: usize, k: usize) -> usize {
+        (i * (self.degrees[1] + 1) + j) * (self.degrees[2] + 1) + k
+    }
+
+    /// Undeformed position of control point (i, j, k)
+    pub fn control_point(&self, i: usize, j: usize, k: usize) -> Point {
+        let ijk = [i, j, k];
+        Point::from(Vector::from_iterator(
+            (0..3).map(|d| self.min[d] + (self.max[d] - self.min[d]) * ijk[d] as f64 / self.degrees[d] as f64),
+        ))
+    }
+
+    /// Displacement of a point; points outside the box follow the nearest face
+    pub fn displacement(&self, p: &Point) -> Vector {
+        let bases: Vec<Vec<f64>> = (0..3)
+            .map(|d| bernstein(self.degrees[d], ((p[d] - self.min[d]) / (self.max[d] - self.min[d])).clamp(0.0, 1.0)))
+            .collect();
+        let mut displacement = Vector::zeros();
+        for (i, bi) in bases[0].iter().enumerate() {
+            for (j, bj) in bases[1].iter().enumerate() {
+                for (k, bk) in bases[2].iter().enumerate() {
+                    displacement += bi * bj * bk * self.displacements[self.index(i, j, k)];
+                }
+            }
+        }
+        displacement
+    }
+
+    /// Copy of a mesh with every vertex displaced
+    pub fn apply(&self, mesh: &Mesh) -> Result<Mesh> {
+        displaced(mesh, |p| p + self.displacement(p))
+    }
+}
+
+/// Radial basis function interpolation of displacements prescribed at centres
+#[derive(Debug, Clone)]
+pub struct RbfMorph {
+    /// Centres of the basis functions
+    pub centres: Vec<Point>,
+    /// Support radius of the Wendland C² functions
+    pub radius: f64,
+    /// Weight of each centre
+    pub weights: Vec<Vector>,
+}
+
+impl RbfMorph {
+    /// Interpolate `displacements` at `centres` with functions of support `radius`
+    pub fn new(centres: Vec<Point>, displacements: &[Vector], radius: f64) -> Result<Self> {
+        if centres.len() != displacements.len() {
+            return Err(MeshError::InvalidData {
+                message: format!("{} RBF centres but {} displacements", centres.len(), displacements.len()),
+            });
+        }
+        if radius <= 0.0 || !radius.is_finite() {
+            return Err(MeshError::InvalidGeometry {
+                message: "RBF support radius must be positive".to_string(),
+            });
+        }
+        let n = centres.len();
+        let phi = DMatrix::from_fn(n, n, |a, b| wendland((centres[a] - centres[b]).norm() / radius));
+        let rhs = DMatrix::from_fn(n, 3, |a, d| displacements[a][d]);
+        // Wendland functions are positive definite for distinct centres
+        let solution = phi.cholesky().map(|c| c.solve(&rhs)).ok_or_else(|| MeshError::InvalidGeometry {
+            message: "RBF centres must be distinct".to_string(),
+        })?;
+        let weights = (0..n).map(|a| Vector::new(solution[(a, 0)], solution[(a, 1)], solution[(a, 2)])).collect();
+        Ok(Self { centres, radius, weights })
+    }
+
+    /// Interpolated displacement of a point
+    pub fn displacement(&self, p: &Point) -> Vector {
+        self.centres
+            .iter()
+            .zip(&self.weights)
+            .fold(Vector::zeros(), |sum, (c, w)| sum + wendland((p - c).norm() / self.radius) * w)
+    }
+
+    /// Copy of a mesh with every vertex displaced
+    pub fn apply(&self, mesh: &Mesh) -> Result<Mesh> {
+        displaced(mesh, |p| p + self.displacement(p))
+    }
+}
+
+/// Design parameters of a hull variant relative to its baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HullDesign {
+    /// Beam over the baseline beam
+    pub beam: f64,
+    /// Relative swelling of the sections towards bow, stern and keel, in (-1, 1)
+    pub fullness: f64,
+    /// Forward protrusion of a bow bulb at 60% of the draft, as a fraction of the length
+    pub bulb_size: f64,
+}
+
+impl Default for HullDesign {
+    fn default() -> Self {
+        Self {
+            beam: 1.0,
+            fullness: 0.0,
+            bulb_size: 0.0,
+        }
+    }
+}
+
+impl HullDesign {
+    /// Parameters as a vector for an optimizer: beam, fullness, bulb size
+    pub fn to_vector(&self) -> [f64; 3] {
+        [self.beam, self.fullness, self.bulb_size]
+    }
+
+    /// Design from an optimizer vector of beam, fullness, bulb size
+    pub fn from_vector(x: [f64; 3]) -> Self {
+        Self {
+            beam: x[0],
+            fullness: x[1],
+            bulb_size: x[2],
+        }
+    }
+}
+
+impl Mesh {
+    /// Variant of a hull, x forward and z up from the keel to the waterline, with the same panels
+    ///
+    /// Fullness scales the half-breadths by 1 + f·(1 - (1-s)⁴ - s⁴)(1 - t²)
+    /// through a free-form deformation, s running along the length and t
+    /// from the keel to the top, so the ends and the waterline, hence the
+    /// length and draft, are kept. The bulb is a radial basis bump pushing the stem
+    /// forward around 60% of the draft, and the beam scales y about the
+    /// centreplane last.
+    pub fn morph_hull(&self, design: &HullDesign) -> Result<Mesh> {
+        if !(design.beam > 0.0 && design.fullness.abs() < 1.0 && design.bulb_size >= 0.0) {
+            return Err(MeshError::InvalidGeometry {
+                message: "Hull design requires a positive beam, fullness in (-1, 1) and a non-negative bulb size".to_string(),
+            });
+        }
+        let (min, max) = self.stats().bounding_box;
+        let (length, depth) = (max.x - min.x, max.z - min.z);
+        let mut hull = self.clone();
+
+        if design.fullness != 0.0 {
+            let mut lattice = FfdLattice::around(self, [4, 1, 2])?;
+            for i in 1..4 {
+                for j in 0..2 {
+                    for k in 0..2 {
+                        let index = lattice.index(i, j, k);
+                        lattice.displacements[index] = Vector::new(0.0, design.fullness * lattice.control_point(i, j, k).y, 0.0);
+                    }
+                }
+            }
+            hull = lattice.apply(&hull)?;
+        }
+        if design.bulb_size > 0.0 {
+            let centre = Point::new(max.x, 0.5 * (min.y + max.y), max.z - 0.6 * depth);
+            let bulb = RbfMorph::new(vec![centre], &[Vector::new(design.bulb_size * length, 0.0, 0.0)], 0.5 * depth)?;
+            hull = bulb.apply(&hull)?;
+        }
+        let centreplane = 0.5 * (min.y + max.y);
+        displaced(&hull, |p| Point::new(p.x, centreplane + design.beam * (p.y - centreplane), p.z))
+    }
+}
+
+/// Copy of a mesh with its vertices moved and normals recomputed
+fn displaced(mesh: &Mesh, map: impl Fn(&Point) -> Point) -> Result<Mesh> {
+    Mesh::with_quads(mesh.vertices.iter().map(map).collect(), mesh.faces.clone(), mesh.quads.clone())
+}
+
+/// Bernstein polynomials of degree n at t
+fn bernstein(n: usize, t: f64) -> Vec<f64> {
+    let mut b = vec![0.0; n + 1];
+    b[0] = 1.0;
+    for m in 1..=n {
+        for i in (0..=m).rev() {
+            b[i] = (1.0 - t) * b[i] + if i > 0 { t * b[i - 1] } else { 0.0 };
+        }
+    }
+    b
+}
+
+/// Wendland C² function (1 - r)⁴ (4r + 1) of the distance over the support radius
+fn wendland(r: f64) -> f64 {
+    if r >= 1.0 {
+        0.0
+    } else {
+        (1.0 - r).powi(4) * (4.0 * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffd_and_rbf_interpolate_their_controls() {
+        let hull = PredefinedGeometry::wigley(4.0, 0.4, 0.25, 16, 4).unwrap();
+        let mut lattice = FfdLattice::around(&hull, [2, 1, 1]).unwrap();
+        assert!(lattice.apply(&hull).unwrap().vertices.iter().zip(&hull.vertices).all(|(a, b)| (a - b).norm() < 1e-15));
+
+        // Moving every control point the same way translates the mesh
+        lattice.displacements.iter_mut().for_each(|d| *d = Vector::new(0.0, 0.0, -1.0));
+        let lowered = lattice.apply(&hull).unwrap();
+        assert!(lowered.vertices.iter().zip(&hull.vertices).all(|(a, b)| (a - b - Vector::new(0.0, 0.0, -1.0)).norm() < 1e-12));
+        assert_eq!(lowered.faces, hull.faces);
+
+        let centres = vec![Point::new(0.0, 0.0, 0.0), Point::new(0.5, 0.0, 0.0), Point::new(0.0, 0.5, 0.0)];
+        let moves = [Vector::new(0.1, 0.0, 0.0), Vector::new(0.0, 0.2, 0.0), Vector::new(0.0, 0.0, -0.3)];
+        let rbf = RbfMorph::new(centres.clone(), &moves, 1.0).unwrap();
+        assert!(centres.iter().zip(&moves).all(|(c, m)| (rbf.displacement(c) - m).norm() < 1e-12));
+        assert_eq!(rbf.displacement(&Point::new(3.0, 0.0, 0.0)), Vector::zeros());
+        assert!(RbfMorph::new(vec![Point::origin(); 2], &moves[..2], 1.0).is_err());
+    }
+
+    #[test]
+    fn test_hull_design_parameters() {
+        // Five panels down the side put a stem vertex at the bulb centre
+        let hull = PredefinedGeometry::wigley(4.0, 0.4, 0.25, 16, 5).unwrap();
+        let base = hull.stats();
+        let unchanged = hull.morph_hull(&HullDesign::default()).unwrap();
+        assert!(unchanged.vertices.iter().zip(&hull.vertices).all(|(a, b)| (a - b).norm() < 1e-15));
+
+        let wide = hull.morph_hull(&HullDesign { beam: 1.2, ..Default::default() }).unwrap().stats();
+        assert!((wide.volume - 1.2 * base.volume).abs() < 1e-12);
+
+        // Fuller sections add volume on the same waterline, length and draft
+        let full = hull.morph_hull(&HullDesign { fullness: 0.3, ..Default::default() }).unwrap();
+        assert!(full.vertices.iter().zip(&hull.vertices).all(|(a, b)| b.z < 0.0 || (a - b).norm() < 1e-15));
+        let full = full.stats();
+        assert!(full.volume > 1.05 * base.volume);
+        assert!((full.bounding_box.0 - base.bounding_box.0).xz().norm() < 1e-12);
+        assert!((full.bounding_box.1 - base.bounding_box.1).xz().norm() < 1e-12);
+        let fine = hull.morph_hull(&HullDesign::from_vector([1.0, -0.3, 0.0])).unwrap().stats();
+        assert!(fine.volume < base.volume);
+
+        // The bulb lengthens the hull below the waterline only
+        let bulbous = hull.morph_hull(&HullDesign { bulb_size: 0.05, ..Default::default() }).unwrap();
+        let stats = bulbous.stats();
+        assert!((stats.bounding_box.1.x - base.bounding_box.1.x - 0.2).abs() < 1e-12);
+        assert!(stats.volume > base.volume && stats.watertight);
+        assert!(bulbous.vertices.iter().zip(&hull.vertices).all(|(a, b)| b.z < -0.01 || a == b));
+
+        assert!(hull.morph_hull(&HullDesign { beam: 0.0, ..Default::default() }).is_err());
+        assert_eq!(HullDesign::from_vector([1.1, 0.2, 0.03]).to_vector(), [1.1, 0.2, 0.03]);
+    }
+}
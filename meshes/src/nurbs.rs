@@ -0,0 +1,508 @@
+//! NURBS surface patches and their panelization
+//!
+//! CAD hull definitions arrive as patches of rational B-spline surfaces,
+//! often trimmed by loops drawn in parameter space. [`NurbsSurface`]
+//! evaluates points and normals of a patch, and [`Mesh::from_nurbs`] lays a
+//! grid of parameter lines over each patch, halving spans until every panel
+//! edge is shorter than the size limit, stays within the chord tolerance of
+//! the surface and turns the normal by less than the angle limit. Splitting
+//! whole lines keeps each patch conforming; a trimmed patch keeps the cells
+//! whose centre lies inside its trimming loops. Patches are then welded
+//! along shared edges and oriented outward.
+
+use super::*;
+use crate::healing::HealOptions;
+use nalgebra::Matrix4;
+
+/// Rational B-spline surface patch
+#[derive(Debug, Clone, PartialEq)]
+pub struct NurbsSurface {
+    /// Polynomial degree along u and v
+    pub degrees: [usize; 2],
+    /// Knot vectors along u and v
+    pub knots: [Vec<f64>; 2],
+    /// Control net indexed `[i][j]`, i along u and j along v
+    pub control_points: Vec<Vec<Point>>,
+    /// Weight of each control point, all 1 for a polynomial surface
+    pub weights: Vec<Vec<f64>>,
+    /// Parameter ranges in use along u and v
+    pub domain: [(f64, f64); 2],
+    /// Trimming loops in (u, v); a point inside an odd number of them is kept
+    pub trim: Vec<Vec<[f64; 2]>>,
+}
+
+/// Rational B-spline curve, such as a trimming curve drawn in parameter space
+#[derive(Debug, Clone, PartialEq)]
+pub struct NurbsCurve {
+    /// Polynomial degree
+    pub degree: usize,
+    /// Knot vector
+    pub knots: Vec<f64>,
+    /// Control polygon
+    pub control_points: Vec<Point>,
+    /// Weight of each control point
+    pub weights: Vec<f64>,
+    /// Parameter range in use
+    pub domain: (f64, f64),
+}
+
+/// Settings of NURBS panelization
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelizeOptions {
+    /// Longest panel edge
+    pub max_panel_size: f64,
+    /// Spans shorter than this are not split further
+    pub min_panel_size: f64,
+    /// Largest turn of the normal along a panel edge, in degrees
+    pub max_normal_angle: f64,
+    /// Largest gap between a panel edge and the surface at its midpoint
+    pub chord_tolerance: f64,
+    /// Most parameter lines along either direction of a patch
+    pub max_lines: usize,
+    /// Distance below which corners of neighbouring patches are merged
+    pub weld_tolerance: f64,
+    /// Wind the welded panels so that normals point out of the body
+    pub orient_outward: bool,
+}
+
+impl Default for PanelizeOptions {
+    fn default() -> Self {
+        Self {
+            max_panel_size: f64::INFINITY,
+            min_panel_size: 0.0,
+            max_normal_angle: 15.0,
+            chord_tolerance: f64::INFINITY,
+            max_lines: 256,
+            weld_tolerance: 1e-6,
+            orient_outward: true,
+        }
+    }
+}
+
+impl NurbsCurve {
+    /// Curve over the full parameter range of its knots
+    pub fn new(degree: usize, knots: Vec<f64>, control_points: Vec<Point>, weights: Vec<f64>) -> Result<Self> {
+        let n = control_points.len();
+        if degree == 0 || n <= degree || knots.len() != n + degree + 1 || weights.len() != n {
+            return Err(MeshError::InvalidData {
+                message: format!(
+                    "NURBS curve has degree {}, {} control points, {} weights and {} knots",
+                    degree,
+                    n,
+                    weights.len(),
+                    knots.len()
+                ),
+            });
+        }
+        check_knots(&knots, degree, n)?;
+        check_weights(&weights)?;
+        Ok(Self {
+            domain: (knots[degree], knots[n]),
+            degree,
+            knots,
+            control_points,
+            weights,
+        })
+    }
+
+    /// Curve point at `t`, clamped to the domain
+    pub fn point(&self, t: f64) -> Point {
+        let t = t.clamp(self.domain.0, self.domain.1);
+        let span = span(&self.knots, self.degree, t);
+        let (values, _) = basis(&self.knots, self.degree, span, t);
+        let (sum, weight) = values.iter().enumerate().fold((Vector::zeros(), 0.0), |(sum, weight), (k, n)| {
+            let i = span - self.degree + k;
+            (sum + self.control_points[i].coords * (n * self.weights[i]), weight + n * self.weights[i])
+        });
+        Point::from(sum / weight)
+    }
+
+    /// Points at the knots in the domain with `per_span` equal steps between them
+    pub fn polyline(&self, per_span: usize) -> Vec<Point> {
+        let (start, end) = self.domain;
+        let mut breaks = vec![start];
+        breaks.extend(self.knots.iter().copied().filter(|&t| t > start && t < end));
+        breaks.push(end);
+        breaks.dedup();
+        let steps = if self.degree == 1 { 1 } else { per_span.max(1) };
+        let mut points = vec![self.point(start)];
+        for w in breaks.windows(2) {
+            points.extend((1..=steps).map(|k| self.point(w[0] + (w[1] - w[0]) * k as f64 / steps as f64)));
+        }
+        points
+    }
+}
+
+impl NurbsSurface {
+    /// Untrimmed patch over the full parameter range of its knots
+    pub fn new(degrees: [usize; 2], knots: [Vec<f64>; 2], control_points: Vec<Vec<Point>>, weights: Vec<Vec<f64>>) -> Result<Self> {
+        let counts = [control_points.len(), control_points.first().map_or(0, Vec::len)];
+        if control_points.iter().any(|row| row.len() != counts[1])
+            || weights.len() != counts[0]
+            || weights.iter().any(|row| row.len() != counts[1])
+        {
+            return Err(MeshError::InvalidData {
+                message: "NURBS control points and weights must form matching rectangular nets".to_string(),
+            });
+        }
+        for d in 0..2 {
+            if degrees[d] == 0 || counts[d] <= degrees[d] || knots[d].len() != counts[d] + degrees[d] + 1 {
+                return Err(MeshError::InvalidData {
+                    message: format!(
+                        "NURBS direction {} has degree {}, {} control points and {} knots",
+                        d,
+                        degrees[d],
+                        counts[d],
+                        knots[d].len()
+                    ),
+                });
+            }
+            check_knots(&knots[d], degrees[d], counts[d])?;
+        }
+        check_weights(weights.iter().flatten())?;
+        let domain = [0, 1].map(|d| (knots[d][degrees[d]], knots[d][counts[d]]));
+        Ok(Self {
+            degrees,
+            knots,
+            control_points,
+            weights,
+            domain,
+            trim: Vec::new(),
+        })
+    }
+
+    /// Surface point at (u, v), clamped to the domain
+    pub fn point(&self, u: f64, v: f64) -> Point {
+        self.derivatives(u, v).0
+    }
+
+    /// Surface point and its partial derivatives along u and v
+    pub fn derivatives(&self, u: f64, v: f64) -> (Point, Vector, Vector) {
+        let [(bu, du), (bv, dv)] = [0, 1].map(|d| {
+            let t = [u, v][d].clamp(self.domain[d].0, self.domain[d].1);
+            let span = span(&self.knots[d], self.degrees[d], t);
+            let (values, slopes) = basis(&self.knots[d], self.degrees[d], span, t);
+            ((span - self.degrees[d], values), slopes)
+        });
+        // Homogeneous sums A = Σ N M w P and W = Σ N M w with their derivatives
+        let (mut a, mut a_u, mut a_v) = (Vector::zeros(), Vector::zeros(), Vector::zeros());
+        let (mut w, mut w_u, mut w_v) = (0.0, 0.0, 0.0);
+        for (k, (n, n_u)) in bu.1.iter().zip(&du).enumerate() {
+            for (l, (m, m_v)) in bv.1.iter().zip(&dv).enumerate() {
+                let (i, j) = (bu.0 + k, bv.0 + l);
+                let weight = self.weights[i][j];
+                let p = self.control_points[i][j].coords * weight;
+                a += p * (n * m);
+                a_u += p * (n_u * m);
+                a_v += p * (n * m_v);
+                w += weight * n * m;
+                w_u += weight * n_u * m;
+                w_v += weight * n * m_v;
+            }
+        }
+        let s = a / w;
+        (Point::from(s), (a_u - s * w_u) / w, (a_v - s * w_v) / w)
+    }
+
+    /// Unit normal along ∂S/∂u × ∂S/∂v, or `None` where the patch degenerates
+    pub fn normal(&self, u: f64, v: f64) -> Option<Vector> {
+        let (_, s_u, s_v) = self.derivatives(u, v);
+        let n = s_u.cross(&s_v);
+        (n.norm() > 1e-12 * s_u.norm() * s_v.norm()).then(|| n.normalize())
+    }
+
+    /// Whether (u, v) lies in the domain and inside the trimming loops
+    pub fn contains(&self, u: f64, v: f64) -> bool {
+        let [(u0, u1), (v0, v1)] = self.domain;
+        if u < u0 || u > u1 || v < v0 || v > v1 {
+            return false;
+        }
+        if self.trim.is_empty() {
+            return true;
+        }
+        let crossings = self
+            .trim
+            .iter()
+            .flat_map(|polygon| (0..polygon.len()).map(move |k| (polygon[k], polygon[(k + 1) % polygon.len()])))
+            .filter(|(a, b)| (a[1] > v) != (b[1] > v) && u < a[0] + (v - a[1]) / (b[1] - a[1]) * (b[0] - a[0]))
+            .count();
+        crossings % 2 == 1
+    }
+
+    /// Apply an affine matrix to the control net
+    pub fn transform(&mut self, matrix: &Matrix4<f64>) {
+        for p in self.control_points.iter_mut().flatten() {
+            *p = matrix.transform_point(p);
+        }
+    }
+
+    /// Parameter lines along u and v refined until every span meets the options
+    fn parameter_lines(&self, options: &PanelizeOptions) -> [Vec<f64>; 2] {
+        let mut lines = [0, 1].map(|d| {
+            let (start, end) = self.domain[d];
+            let mut lines = vec![start];
+            lines.extend(self.knots[d].iter().copied().filter(|&t| t > start && t < end));
+            lines.push(end);
+            lines.dedup();
+            lines
+        });
+        loop {
+            let mut changed = false;
+            for d in 0..2 {
+                let other = &lines[1 - d];
+                let across: Vec<f64> = other.iter().copied().chain(other.windows(2).map(|w| 0.5 * (w[0] + w[1]))).collect();
+                let mut count = lines[d].len();
+                let mut refined = vec![lines[d][0]];
+                for w in lines[d].windows(2) {
+                    if count < options.max_lines && self.needs_split(d, w[0], w[1], &across, options) {
+                        refined.push(0.5 * (w[0] + w[1]));
+                        count += 1;
+                        changed = true;
+                    }
+                    refined.push(w[1]);
+                }
+                lines[d] = refined;
+            }
+            if !changed {
+                return lines;
+            }
+        }
+    }
+
+    /// Whether span [a, b] along direction `d` is too long, too far from the surface or too curved
+    fn needs_split(&self, d: usize, a: f64, b: f64, across: &[f64], options: &PanelizeOptions) -> bool {
+        let max_angle = options.max_normal_angle.to_radians();
+        across.iter().any(|&c| {
+            let at = |t: f64| if d == 0 { (t, c) } else { (c, t) };
+            let [(ua, va), (um, vm), (ub, vb)] = [a, 0.5 * (a + b), b].map(at);
+            if !self.contains(um, vm) {
+                return false;
+            }
+            let [pa, pm, pb] = [(ua, va), (um, vm), (ub, vb)].map(|(u, v)| self.point(u, v));
+            let length = (pm - pa).norm() + (pb - pm).norm();
+            if length <= options.min_panel_size {
+                return false;
+            }
+            let normals = [(ua, va), (um, vm), (ub, vb)].map(|(u, v)| self.normal(u, v));
+            let turn = normals
+                .windows(2)
+                .filter_map(|w| Some(w[0]?.angle(&w[1]?)))
+                .sum::<f64>();
+            length > options.max_panel_size || (pm - pa.lerp(&pb, 0.5)).norm() > options.chord_tolerance || turn > max_angle
+        })
+    }
+}
+
+impl Mesh {
+    /// Panel NURBS patches and weld them into one mesh of quads and triangles
+    pub fn from_nurbs(surfaces: &[NurbsSurface], options: &PanelizeOptions) -> Result<Mesh> {
+        if !(options.max_panel_size > 0.0 && options.max_normal_angle > 0.0 && options.chord_tolerance > 0.0) || options.max_lines < 2 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Panel size, normal angle and chord tolerance must be positive, with at least two lines".to_string(),
+            });
+        }
+        let mut vertices: Vec<Point> = Vec::new();
+        let mut quads: Vec<[usize; 4]> = Vec::new();
+        for surface in surfaces {
+            let [us, vs] = surface.parameter_lines(options);
+            let offset = vertices.len();
+            vertices.extend(us.iter().flat_map(|&u| vs.iter().map(move |&v| surface.point(u, v))));
+            let index = |i: usize, j: usize| offset + i * vs.len() + j;
+            for i in 0..us.len() - 1 {
+                for j in 0..vs.len() - 1 {
+                    if !surface.contains(0.5 * (us[i] + us[i + 1]), 0.5 * (vs[j] + vs[j + 1])) {
+                        continue;
+                    }
+                    let quad = [index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1)];
+                    // Cells with both diagonals collapsed have no area; healing
+                    // turns those with one collapsed edge into triangles
+                    let [p0, p1, p2, p3] = quad.map(|k| vertices[k]);
+                    if (p2 - p0).cross(&(p3 - p1)).norm() > 0.0 {
+                        quads.push(quad);
+                    }
+                }
+            }
+        }
+        if quads.is_empty() {
+            return Err(MeshError::InvalidGeometry {
+                message: "NURBS surfaces produced no panels".to_string(),
+            });
+        }
+        let heal = HealOptions {
+            weld_tolerance: options.weld_tolerance,
+            ..HealOptions::default()
+        };
+        let (mut mesh, _) = Mesh::with_quads(vertices, Vec::new(), quads)?.heal(&heal)?;
+        if options.orient_outward {
+            mesh.orient_normals_outward()?;
+        }
+        Ok(mesh)
+    }
+}
+
+fn check_knots(knots: &[f64], degree: usize, count: usize) -> Result<()> {
+    if knots.iter().any(|k| !k.is_finite()) || knots.windows(2).any(|w| w[0] > w[1]) || knots[degree] >= knots[count] {
+        return Err(MeshError::InvalidData {
+            message: "NURBS knots must be finite and non-decreasing over a non-empty range".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn check_weights<'a>(weights: impl IntoIterator<Item = &'a f64>) -> Result<()> {
+    if weights.into_iter().any(|w| !w.is_finite() || *w <= 0.0) {
+        return Err(MeshError::InvalidData {
+            message: "NURBS weights must be positive".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Knot span of degree `p` holding `t`
+fn span(knots: &[f64], p: usize, t: f64) -> usize {
+    let last = knots.len() - p - 2;
+    if t >= knots[last + 1] {
+        return last;
+    }
+    // Last index in p..=last whose knot does not exceed t
+    p + knots[p..=last].partition_point(|&k| k <= t) - 1
+}
+
+/// Non-zero basis functions of degree `p` on `span` and their first derivatives
+fn basis(knots: &[f64], p: usize, span: usize, t: f64) -> (Vec<f64>, Vec<f64>) {
+    let ratio = |num: f64, den: f64| if den > 0.0 { num / den } else { 0.0 };
+    // Degree d functions N_{span-d+k, d}, k = 0..=d, from those of degree d - 1
+    let raise = |lower: &[f64], d: usize| -> Vec<f64> {
+        (0..=d)
+            .map(|k| {
+                let i = span + k - d;
+                let left = if k > 0 { ratio(t - knots[i], knots[i + d] - knots[i]) * lower[k - 1] } else { 0.0 };
+                let right = if k < d { ratio(knots[i + d + 1] - t, knots[i + d + 1] - knots[i + 1]) * lower[k] } else { 0.0 };
+                left + right
+            })
+            .collect()
+    };
+    let lower = (1..p).fold(vec![1.0], |values, d| raise(&values, d));
+    let values = raise(&lower, p);
+    let slopes = (0..=p)
+        .map(|k| {
+            let i = span + k - p;
+            let left = if k > 0 { ratio(p as f64, knots[i + p] - knots[i]) * lower[k - 1] } else { 0.0 };
+            let right = if k < p { ratio(p as f64, knots[i + p + 1] - knots[i + 1]) * lower[k] } else { 0.0 };
+            left - right
+        })
+        .collect();
+    (values, slopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    /// Exact quarter cylinder of radius 2 and length 3 as a rational quadratic by linear patch
+    fn quarter_cylinder() -> NurbsSurface {
+        let arc = [(2.0, 0.0, 1.0), (2.0, 2.0, FRAC_1_SQRT_2), (0.0, 2.0, 1.0)];
+        NurbsSurface::new(
+            [2, 1],
+            [vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], vec![0.0, 0.0, 1.0, 1.0]],
+            arc.iter().map(|&(x, y, _)| vec![Point::new(x, y, 0.0), Point::new(x, y, 3.0)]).collect(),
+            arc.iter().map(|&(_, _, w)| vec![w, w]).collect(),
+        )
+        .unwrap()
+    }
+
+    fn plate() -> NurbsSurface {
+        NurbsSurface::new(
+            [1, 1],
+            [vec![0.0, 0.0, 1.0, 1.0], vec![0.0, 0.0, 1.0, 1.0]],
+            vec![vec![Point::new(0.0, 0.0, 0.0), Point::new(0.0, 1.0, 0.0)], vec![Point::new(1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)]],
+            vec![vec![1.0; 2]; 2],
+        )
+        .unwrap()
+    }
+
+    fn area(mesh: &Mesh) -> f64 {
+        mesh.triangulated()
+            .unwrap()
+            .faces
+            .iter()
+            .map(|&[a, b, c]| 0.5 * (mesh.vertices[b] - mesh.vertices[a]).cross(&(mesh.vertices[c] - mesh.vertices[a])).norm())
+            .sum()
+    }
+
+    #[test]
+    fn test_rational_evaluation_and_derivatives() {
+        let surface = quarter_cylinder();
+        for k in 0..=10 {
+            let (u, v) = (k as f64 / 10.0, 0.3);
+            let (p, s_u, s_v) = surface.derivatives(u, v);
+            assert!((p.x.hypot(p.y) - 2.0).abs() < 1e-12 && (p.z - 0.9).abs() < 1e-12);
+            let h = 1e-6;
+            let numeric = (surface.point(u + h, v) - surface.point(u - h, v)) / (2.0 * h);
+            if k > 0 && k < 10 {
+                assert!((numeric - s_u).norm() < 1e-6);
+            }
+            assert!((s_v - Vector::new(0.0, 0.0, 3.0)).norm() < 1e-12);
+            let n = surface.normal(u, v).unwrap();
+            assert!((n - Vector::new(p.x, p.y, 0.0) / 2.0).norm() < 1e-12);
+        }
+        let arc = NurbsCurve::new(
+            2,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            vec![Point::new(1.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0), Point::new(0.0, 1.0, 0.0)],
+            vec![1.0, FRAC_1_SQRT_2, 1.0],
+        )
+        .unwrap();
+        let polyline = arc.polyline(8);
+        assert_eq!(polyline.len(), 9);
+        assert!(polyline.iter().all(|p| (p.coords.norm() - 1.0).abs() < 1e-12));
+        assert!(NurbsCurve::new(2, vec![0.0; 6], polyline[..3].to_vec(), vec![1.0; 3]).is_err());
+        assert!(NurbsSurface::new([2, 1], [vec![0.0; 5], vec![0.0, 0.0, 1.0, 1.0]], Vec::new(), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_panelization_follows_curvature_and_size() {
+        let options = PanelizeOptions {
+            max_normal_angle: 10.0,
+            orient_outward: false,
+            ..PanelizeOptions::default()
+        };
+        let mesh = Mesh::from_nurbs(&[quarter_cylinder()], &options).unwrap();
+        // The 90° arc is halved until each panel turns by less than 10°
+        assert_eq!(mesh.quads.len(), 16);
+        assert!(mesh.vertices.iter().all(|p| (p.x.hypot(p.y) - 2.0).abs() < 1e-12));
+        assert!(mesh.normals.iter().all(|n| n.z.abs() < 1e-12 && n.x + n.y > 0.0));
+        assert!((area(&mesh) - 3.0 * PI).abs() < 0.02);
+
+        let sized = Mesh::from_nurbs(&[plate()], &PanelizeOptions { max_panel_size: 0.25, ..options }).unwrap();
+        assert_eq!(sized.quads.len(), 16);
+        assert!((area(&sized) - 1.0).abs() < 1e-12);
+        assert!(Mesh::from_nurbs(&[plate()], &PanelizeOptions { max_panel_size: 0.0, ..options }).is_err());
+    }
+
+    #[test]
+    fn test_trimmed_patches_weld() {
+        let mut disk = plate();
+        disk.trim = vec![(0..64)
+            .map(|k| {
+                let angle = 2.0 * PI * k as f64 / 64.0;
+                [0.5 + 0.4 * angle.cos(), 0.5 + 0.4 * angle.sin()]
+            })
+            .collect()];
+        let options = PanelizeOptions {
+            max_panel_size: 0.02,
+            orient_outward: false,
+            ..PanelizeOptions::default()
+        };
+        let mesh = Mesh::from_nurbs(&[disk], &options).unwrap();
+        assert!((area(&mesh) - PI * 0.16).abs() < 0.02);
+
+        // Two plates sharing an edge weld into one component
+        let mut neighbour = plate();
+        neighbour.transform(&Matrix4::new_translation(&Vector::new(1.0, 0.0, 0.0)));
+        let joined = Mesh::from_nurbs(&[plate(), neighbour], &PanelizeOptions { max_panel_size: 0.5, ..options }).unwrap();
+        let topology = joined.topology();
+        assert_eq!((topology.components, topology.vertices, topology.panels), (1, 15, 8));
+    }
+}
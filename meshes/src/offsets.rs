@@ -0,0 +1,327 @@
+//! Hull meshes lofted from offset tables
+//!
+//! A table of offsets gives the half-breadth of a hull at a grid of stations
+//! (longitudinal positions) and waterlines (heights above the baseline).
+//! [`HullFromOffsets`] interpolates the table bilinearly, mirrors it about the
+//! centreplane and panels both sides, the flat bottom and optionally the
+//! waterplane, a transom stern and a bulb nose, giving a watertight mesh with
+//! outward normals and the free surface at z = 0.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Builder for a hull mesh from a table of offsets
+#[derive(Debug, Clone)]
+pub struct HullFromOffsets {
+    /// Station positions, aft to forward
+    stations: Vec<f64>,
+    /// Waterline heights above the baseline, bottom to top
+    waterlines: Vec<f64>,
+    /// Half-breadth at each station and waterline, `[station][waterline]`
+    half_breadths: Vec<Vec<f64>>,
+    draft: Option<f64>,
+    panels: Option<(usize, usize)>,
+    transom: bool,
+    bulb: Option<f64>,
+    surface: HullSurface,
+}
+
+impl HullFromOffsets {
+    /// Create a builder from stations, waterlines and half-breadths indexed `[station][waterline]`
+    pub fn new(stations: Vec<f64>, waterlines: Vec<f64>, half_breadths: Vec<Vec<f64>>) -> Self {
+        Self {
+            stations,
+            waterlines,
+            half_breadths,
+            draft: None,
+            panels: None,
+            transom: false,
+            bulb: None,
+            surface: HullSurface::Closed,
+        }
+    }
+
+    /// Draft above the baseline; the hull is cut there and shifted so the waterplane is z = 0
+    ///
+    /// Defaults to the highest waterline.
+    pub fn with_draft(mut self, draft: f64) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Resample at `nx` equal panels along the length and `nz` down the draft
+    ///
+    /// By default the table's own stations and waterlines are used.
+    pub fn with_panels(mut self, nx: usize, nz: usize) -> Self {
+        self.panels = Some((nx, nz));
+        self
+    }
+
+    /// Close the aft station with a flat transom
+    pub fn with_transom(mut self, transom: bool) -> Self {
+        self.transom = transom;
+        self
+    }
+
+    /// Close the forward station with a bulb nose `extension` ahead of it
+    ///
+    /// An extension of zero gives a flat closure.
+    pub fn with_bulb(mut self, extension: f64) -> Self {
+        self.bulb = Some(extension);
+        self
+    }
+
+    /// Panel the wetted surface only or close it on the waterplane
+    pub fn with_surface(mut self, surface: HullSurface) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Half-breadth at station position `x` and height `z`, interpolated bilinearly
+    pub fn half_breadth(&self, x: f64, z: f64) -> f64 {
+        let (i, s) = bracket(&self.stations, x);
+        let (j, t) = bracket(&self.waterlines, z);
+        let row = |i: usize| {
+            let h = &self.half_breadths[i];
+            if t > 0.0 { h[j] + t * (h[j + 1] - h[j]) } else { h[j] }
+        };
+        if s > 0.0 { row(i) + s * (row(i + 1) - row(i)) } else { row(i) }
+    }
+
+    /// Loft the panel mesh
+    pub fn build(&self) -> Result<Mesh> {
+        self.validate()?;
+        let draft = self.draft.unwrap_or(self.waterlines[self.waterlines.len() - 1]);
+        let (xs, zs) = self.samples(draft);
+        let (nx, nz) = (xs.len() - 1, zs.len() - 1);
+
+        // Centreplane points are shared by both sides
+        let mut vertices = Vec::new();
+        let mut index = HashMap::new();
+        let mut grid = vec![[0usize; 2]; xs.len() * zs.len()];
+        for i in 0..=nx {
+            for j in 0..=nz {
+                let y = self.half_breadth(xs[i], zs[j]);
+                for (side, sign) in [(0, -1.0), (1, 1.0)] {
+                    grid[i * (nz + 1) + j][side] = *index.entry((side == 1 && y > 0.0, i, j)).or_insert_with(|| {
+                        vertices.push(Point::new(xs[i], sign * y, zs[j] - draft));
+                        vertices.len() - 1
+                    });
+                }
+            }
+        }
+        let stbd = |i: usize, j: usize| grid[i * (nz + 1) + j][0];
+        let port = |i: usize, j: usize| grid[i * (nz + 1) + j][1];
+
+        let mut faces = Vec::new();
+        for i in 0..nx {
+            for j in 0..nz {
+                // x × z points to starboard, so the port side is reversed
+                let s = [stbd(i, j), stbd(i + 1, j), stbd(i + 1, j + 1), stbd(i, j + 1)];
+                let p = [port(i, j), port(i, j + 1), port(i + 1, j + 1), port(i + 1, j)];
+                push_quad(&vertices, &mut faces, s);
+                push_quad(&vertices, &mut faces, p);
+            }
+            // Flat bottom, then the waterplane lid, as strips across the centreplane
+            push_quad(&vertices, &mut faces, [stbd(i, 0), port(i, 0), port(i + 1, 0), stbd(i + 1, 0)]);
+            if self.surface == HullSurface::Closed {
+                push_quad(&vertices, &mut faces, [stbd(i, nz), stbd(i + 1, nz), port(i + 1, nz), port(i, nz)]);
+            }
+        }
+
+        // End closures fan out from a centreplane point; the section loop runs up starboard and down port
+        let section = |i: usize| -> Vec<usize> {
+            let mut ring: Vec<usize> = (0..=nz).map(|j| stbd(i, j)).chain((0..=nz).rev().map(|j| port(i, j))).collect();
+            ring.dedup();
+            if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+                ring.pop();
+            }
+            ring
+        };
+        let mid_depth = 0.5 * (zs[0] + zs[nz]) - draft;
+        if self.transom {
+            let ring = section(0);
+            vertices.push(Point::new(xs[0], 0.0, mid_depth));
+            let centre = vertices.len() - 1;
+            for k in 0..ring.len() {
+                push_triangle(&vertices, &mut faces, [centre, ring[k], ring[(k + 1) % ring.len()]]);
+            }
+        }
+        if let Some(extension) = self.bulb {
+            let ring = section(nx);
+            vertices.push(Point::new(xs[nx] + extension, 0.0, mid_depth));
+            let centre = vertices.len() - 1;
+            for k in 0..ring.len() {
+                push_triangle(&vertices, &mut faces, [centre, ring[(k + 1) % ring.len()], ring[k]]);
+            }
+        }
+
+        // Drop vertices only used by skipped degenerate panels
+        let mut used = vec![usize::MAX; vertices.len()];
+        let mut kept = Vec::new();
+        for face in &mut faces {
+            for v in face.iter_mut() {
+                if used[*v] == usize::MAX {
+                    used[*v] = kept.len();
+                    kept.push(vertices[*v]);
+                }
+                *v = used[*v];
+            }
+        }
+        Mesh::new(kept, faces)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let (ns, nw) = (self.stations.len(), self.waterlines.len());
+        if ns < 2 || nw < 2 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Offset table requires at least 2 stations and 2 waterlines".to_string(),
+            });
+        }
+        if self.half_breadths.len() != ns || self.half_breadths.iter().any(|row| row.len() != nw) {
+            return Err(MeshError::InvalidData {
+                message: format!("Offset table must have {} stations of {} half-breadths", ns, nw),
+            });
+        }
+        if self.stations.windows(2).any(|w| w[1] <= w[0]) || self.waterlines.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(MeshError::InvalidData {
+                message: "Stations and waterlines must be strictly increasing".to_string(),
+            });
+        }
+        if self.half_breadths.iter().flatten().any(|&y| y < 0.0 || y.is_nan()) {
+            return Err(MeshError::InvalidData {
+                message: "Half-breadths must be non-negative".to_string(),
+            });
+        }
+        if let Some(draft) = self.draft {
+            if draft <= self.waterlines[0] || draft > self.waterlines[nw - 1] {
+                return Err(MeshError::InvalidGeometry {
+                    message: format!("Draft {} lies outside the waterlines of the table", draft),
+                });
+            }
+        }
+        if self.panels.is_some_and(|(nx, nz)| nx == 0 || nz == 0) {
+            return Err(MeshError::InvalidGeometry {
+                message: "Offset hull requires at least 1 panel along each direction".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Station and waterline positions of the mesh, cut at `draft`
+    fn samples(&self, draft: f64) -> (Vec<f64>, Vec<f64>) {
+        let (x0, x1) = (self.stations[0], self.stations[self.stations.len() - 1]);
+        let z0 = self.waterlines[0];
+        match self.panels {
+            Some((nx, nz)) => (
+                (0..=nx).map(|i| x0 + (x1 - x0) * i as f64 / nx as f64).collect(),
+                (0..=nz).map(|j| z0 + (draft - z0) * j as f64 / nz as f64).collect(),
+            ),
+            None => {
+                let mut zs: Vec<f64> = self.waterlines.iter().copied().filter(|&z| z < draft).collect();
+                zs.push(draft);
+                (self.stations.clone(), zs)
+            }
+        }
+    }
+}
+
+/// Interval of sorted `knots` containing `x` and the fraction along it
+fn bracket(knots: &[f64], x: f64) -> (usize, f64) {
+    let i = knots.partition_point(|&k| k <= x).clamp(1, knots.len() - 1) - 1;
+    let t = ((x - knots[i]) / (knots[i + 1] - knots[i])).clamp(0.0, 1.0);
+    (i, t)
+}
+
+/// Add a quad, choosing the diagonal that leaves fewer panels lying in the centreplane
+fn push_quad(vertices: &[Point], faces: &mut Vec<[usize; 3]>, [v0, v1, v2, v3]: [usize; 4]) {
+    let flat = |face: [usize; 3]| face.iter().all(|&v| vertices[v].y == 0.0);
+    if flat([v0, v1, v2]) || flat([v0, v2, v3]) {
+        push_triangle(vertices, faces, [v0, v1, v3]);
+        push_triangle(vertices, faces, [v1, v2, v3]);
+    } else {
+        push_triangle(vertices, faces, [v0, v1, v2]);
+        push_triangle(vertices, faces, [v0, v2, v3]);
+    }
+}
+
+/// Add a triangle unless it is degenerate or lies in the centreplane, where both sides would overlap
+fn push_triangle(vertices: &[Point], faces: &mut Vec<[usize; 3]>, face: [usize; 3]) {
+    let [a, b, c] = face.map(|v| vertices[v]);
+    let in_centreplane = a.y == 0.0 && b.y == 0.0 && c.y == 0.0;
+    if !in_centreplane && 0.5 * (b - a).cross(&(c - a)).norm() >= 1e-12 {
+        faces.push(face);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(mesh: &Mesh) -> f64 {
+        mesh.faces
+            .iter()
+            .zip(&mesh.normals)
+            .map(|(face, normal)| {
+                let [a, b, c] = face.map(|v| mesh.vertices[v]);
+                let centroid = (a.coords + b.coords + c.coords) / 3.0;
+                centroid.dot(normal) * 0.5 * (b - a).cross(&(c - a)).norm() / 3.0
+            })
+            .sum()
+    }
+
+    fn watertight(mesh: &Mesh) -> bool {
+        let mut edges = std::collections::HashSet::new();
+        for face in &mesh.faces {
+            for k in 0..3 {
+                if !edges.insert((face[k], face[(k + 1) % 3])) {
+                    return false;
+                }
+            }
+        }
+        edges.iter().all(|&(a, b)| edges.contains(&(b, a)))
+    }
+
+    #[test]
+    fn test_offsets_wigley_matches_generator() {
+        let (length, beam, draft, nx, nz) = (2.0, 0.2, 0.125, 20, 8);
+        let stations: Vec<f64> = (0..=nx).map(|i| length * (i as f64 / nx as f64 - 0.5)).collect();
+        let waterlines: Vec<f64> = (0..=nz).map(|j| draft * j as f64 / nz as f64).collect();
+        let offsets = stations
+            .iter()
+            .map(|&x| {
+                let xi = 2.0 * x / length;
+                waterlines.iter().map(|&z| 0.5 * beam * (1.0 - xi * xi) * (1.0 - (z / draft - 1.0).powi(2))).collect()
+            })
+            .collect();
+        let mesh = HullFromOffsets::new(stations, waterlines, offsets).build().unwrap();
+        let wigley = PredefinedGeometry::wigley(length, beam, draft, nx, nz).unwrap();
+        assert!(watertight(&mesh));
+        assert_eq!(mesh.faces.len(), wigley.faces.len());
+        assert!((volume(&mesh) - volume(&wigley)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_offsets_barge_with_transom_and_bulb() {
+        // Wall-sided barge 10 m long, 4 m beam, cut at 2 m draft from a 3 m deep table
+        let table = HullFromOffsets::new(vec![0.0, 5.0, 10.0], vec![0.0, 1.5, 3.0], vec![vec![2.0; 3]; 3])
+            .with_draft(2.0)
+            .with_panels(8, 4)
+            .with_transom(true);
+        let flat = table.clone().with_bulb(0.0).build().unwrap();
+        assert!(watertight(&flat));
+        assert!((volume(&flat) - 80.0).abs() < 1e-9);
+        assert!(flat.vertices.iter().all(|p| p.z >= -2.0 - 1e-12 && p.z <= 1e-12));
+
+        // A bulb nose adds a pyramid of height 1 m over the 4 m × 2 m end section
+        let bulb = table.clone().with_bulb(1.0).build().unwrap();
+        assert!(watertight(&bulb));
+        assert!((volume(&bulb) - 80.0 - 8.0 / 3.0).abs() < 1e-9);
+
+        // Without closures the wetted surface stays open at both ends
+        let open = table.with_transom(false).with_surface(HullSurface::Wetted).build().unwrap();
+        assert!(!watertight(&open));
+        assert!(HullFromOffsets::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![vec![1.0, -1.0]; 2]).build().is_err());
+        assert!(HullFromOffsets::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![vec![1.0; 2]; 2]).with_draft(2.0).build().is_err());
+    }
+}
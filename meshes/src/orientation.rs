@@ -0,0 +1,174 @@
+//! Consistent outward orientation of panel normals
+//!
+//! Panels wound the wrong way flip the sign of their influence coefficients.
+//! [`Mesh::orient_normals_outward`] first propagates a consistent winding
+//! across the edges of each connected component, then turns the component
+//! so that the volume it encloses is positive. The volume is measured as
+//! ∮ (z - z_top) n_z dA, which closes an open wetted surface with a lid at
+//! its highest point and equals the enclosed volume of a closed one.
+
+use super::*;
+use std::collections::{HashMap, VecDeque};
+
+/// Outcome of re-orienting a mesh
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrientationReport {
+    /// Connected components of the mesh
+    pub components: usize,
+    /// Panels, triangles or quads, whose winding was reversed
+    pub flipped_faces: usize,
+    /// Components that cannot be wound consistently, such as a Möbius strip
+    pub non_orientable: usize,
+    /// Components whose enclosed volume vanishes, left with their propagated winding
+    pub undetermined: usize,
+}
+
+impl Mesh {
+    /// Wind every panel so that its normal points out of the body
+    pub fn orient_normals_outward(&mut self) -> Result<OrientationReport> {
+        let mut report = OrientationReport::default();
+        let polygons: Vec<&[usize]> = self.polygons().collect();
+        let edge = |p: &[usize], k: usize| (p[k], p[(k + 1) % p.len()]);
+
+        // Panels on each edge, for propagation across manifold edges only
+        let mut edges: HashMap<[usize; 2], Vec<usize>> = HashMap::new();
+        for (f, polygon) in polygons.iter().enumerate() {
+            for k in 0..polygon.len() {
+                let (a, b) = edge(polygon, k);
+                edges.entry([a.min(b), a.max(b)]).or_default().push(f);
+            }
+        }
+        let runs = |polygon: &[usize], a: usize, b: usize| (0..polygon.len()).any(|k| edge(polygon, k) == (a, b));
+
+        let mut flip = vec![false; polygons.len()];
+        let mut visited = vec![false; polygons.len()];
+        for seed in 0..polygons.len() {
+            if visited[seed] {
+                continue;
+            }
+            report.components += 1;
+            visited[seed] = true;
+            let mut component = vec![seed];
+            let mut queue = VecDeque::from([seed]);
+            let mut consistent = true;
+            while let Some(f) = queue.pop_front() {
+                for k in 0..polygons[f].len() {
+                    // Edge a → b as panel f is currently to be wound
+                    let (mut a, mut b) = edge(polygons[f], k);
+                    if flip[f] {
+                        std::mem::swap(&mut a, &mut b);
+                    }
+                    let shared = &edges[&[a.min(b), a.max(b)]];
+                    if shared.len() != 2 {
+                        continue;
+                    }
+                    let g = if shared[0] == f { shared[1] } else { shared[0] };
+                    // A consistent neighbour runs the shared edge b → a
+                    let needs_flip = runs(polygons[g], a, b);
+                    if visited[g] {
+                        consistent &= flip[g] == needs_flip;
+                        continue;
+                    }
+                    visited[g] = true;
+                    flip[g] = needs_flip;
+                    component.push(g);
+                    queue.push_back(g);
+                }
+            }
+            report.non_orientable += !consistent as usize;
+
+            let top = component
+                .iter()
+                .flat_map(|&f| polygons[f])
+                .map(|&v| self.vertices[v].z)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let volume: f64 = component
+                .iter()
+                .map(|&f| {
+                    let p0 = self.vertices[polygons[f][0]];
+                    let sign = if flip[f] { -1.0 } else { 1.0 };
+                    // Fan of triangles from the first corner
+                    let fan: f64 = polygons[f][1..]
+                        .windows(2)
+                        .map(|w| {
+                            let (p1, p2) = (self.vertices[w[0]], self.vertices[w[1]]);
+                            ((p0.z + p1.z + p2.z) / 3.0 - top) * 0.5 * (p1 - p0).cross(&(p2 - p0)).z
+                        })
+                        .sum();
+                    sign * fan
+                })
+                .sum();
+            let scale = component.iter().map(|&f| self.vertices[polygons[f][0]].coords.amax()).fold(1.0, f64::max);
+            if volume.abs() <= 1e-12 * scale.powi(3) {
+                report.undetermined += 1;
+            } else if volume < 0.0 {
+                for &f in &component {
+                    flip[f] = !flip[f];
+                }
+            }
+        }
+
+        report.flipped_faces = flip.iter().filter(|&&f| f).count();
+        if report.flipped_faces > 0 {
+            let (triangles, quads) = flip.split_at(self.faces.len());
+            let faces = self
+                .faces
+                .iter()
+                .zip(triangles)
+                .map(|(&[a, b, c], &flip)| if flip { [a, c, b] } else { [a, b, c] })
+                .collect();
+            let quads = self
+                .quads
+                .iter()
+                .zip(quads)
+                .map(|(&[a, b, c, d], &flip)| if flip { [a, d, c, b] } else { [a, b, c, d] })
+                .collect();
+            *self = Mesh::with_quads(std::mem::take(&mut self.vertices), faces, quads)?;
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled(mesh: &Mesh, every: usize) -> Mesh {
+        let faces = mesh
+            .faces
+            .iter()
+            .enumerate()
+            .map(|(f, &[a, b, c])| if f % every == 0 { [a, c, b] } else { [a, b, c] })
+            .collect();
+        Mesh::new(mesh.vertices.clone(), faces).unwrap()
+    }
+
+    #[test]
+    fn test_orients_closed_and_wetted_hulls() {
+        for surface in [HullSurface::Closed, HullSurface::Wetted] {
+            let hull = PredefinedGeometry::box_hull_with_surface(4.0, 2.0, 1.0, 4, 2, 2, surface).unwrap();
+            for every in [1, 3] {
+                let mut mesh = scrambled(&hull, every);
+                let report = mesh.orient_normals_outward().unwrap();
+                assert_eq!((report.components, report.non_orientable, report.undetermined), (1, 0, 0));
+                assert_eq!(mesh.faces, hull.faces);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orients_each_component() {
+        let a = PredefinedGeometry::cylinder(1.0, 1.0, 12, 2).unwrap();
+        let offset = a.vertices.len();
+        let mut vertices = a.vertices.clone();
+        vertices.extend(a.vertices.iter().map(|p| p + Vector::new(5.0, 0.0, 0.0)));
+        let mut faces: Vec<_> = a.faces.iter().map(|&[x, y, z]| [x, z, y]).collect();
+        faces.extend(a.faces.iter().map(|f| f.map(|v| v + offset)));
+        let mut mesh = Mesh::new(vertices, faces).unwrap();
+
+        let report = mesh.orient_normals_outward().unwrap();
+        assert_eq!((report.components, report.flipped_faces), (2, a.faces.len()));
+        assert_eq!(mesh.faces[..a.faces.len()], a.faces[..]);
+        assert_eq!(mesh.orient_normals_outward().unwrap().flipped_faces, 0);
+    }
+}
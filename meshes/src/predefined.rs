@@ -5,6 +5,130 @@ use super::*;
 /// Predefined geometry generator
 pub struct PredefinedGeometry;
 
+/// Portion of a floating hull to panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HullSurface {
+    /// Submerged surface only, open at the waterplane
+    Wetted,
+    /// Submerged surface closed by a lid on the waterplane
+    #[default]
+    Closed,
+}
+
+/// Arrangement of the pontoons under a semi-submersible's four columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PontoonLayout {
+    /// Four pontoons joining neighbouring columns
+    #[default]
+    Ring,
+    /// Two pontoons along x, each under a pair of columns
+    Twin,
+}
+
+/// Four-column semi-submersible on pontoons
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemiSubmersible {
+    /// Centre-to-centre distance between neighbouring columns (m)
+    pub column_spacing: f64,
+    /// Column diameter (m)
+    pub column_diameter: f64,
+    /// Pontoon width (m)
+    pub pontoon_width: f64,
+    /// Pontoon height above the keel (m)
+    pub pontoon_height: f64,
+    /// Draft to the keel (m)
+    pub draft: f64,
+    /// Pontoon arrangement
+    pub layout: PontoonLayout,
+    /// Target panel edge length (m)
+    pub panel_size: f64,
+    /// Wetted or closed surface
+    pub surface: HullSurface,
+}
+
+impl Default for SemiSubmersible {
+    fn default() -> Self {
+        Self {
+            column_spacing: 50.0,
+            column_diameter: 12.0,
+            pontoon_width: 10.0,
+            pontoon_height: 7.0,
+            draft: 20.0,
+            layout: PontoonLayout::Ring,
+            panel_size: 2.0,
+            surface: HullSurface::Closed,
+        }
+    }
+}
+
+/// Spar with a hard tank at the waterline over a narrower lower hull
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spar {
+    /// Hard tank diameter (m)
+    pub hard_tank_diameter: f64,
+    /// Depth of the hard tank's lower end (m)
+    pub hard_tank_depth: f64,
+    /// Diameter of the hull below the hard tank (m)
+    pub lower_diameter: f64,
+    /// Draft to the keel (m)
+    pub draft: f64,
+    /// Target panel edge length (m)
+    pub panel_size: f64,
+    /// Wetted or closed surface
+    pub surface: HullSurface,
+}
+
+impl Default for Spar {
+    fn default() -> Self {
+        Self {
+            hard_tank_diameter: 30.0,
+            hard_tank_depth: 70.0,
+            lower_diameter: 20.0,
+            draft: 150.0,
+            panel_size: 4.0,
+            surface: HullSurface::Closed,
+        }
+    }
+}
+
+/// Tension-leg platform: columns on a circle joined by a ring of pontoons
+///
+/// Tendons are not panelled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensionLegPlatform {
+    /// Number of columns, at least 3
+    pub columns: usize,
+    /// Centre-to-centre distance between neighbouring columns (m)
+    pub column_spacing: f64,
+    /// Column diameter (m)
+    pub column_diameter: f64,
+    /// Pontoon width (m)
+    pub pontoon_width: f64,
+    /// Pontoon height above the keel (m)
+    pub pontoon_height: f64,
+    /// Draft to the keel (m)
+    pub draft: f64,
+    /// Target panel edge length (m)
+    pub panel_size: f64,
+    /// Wetted or closed surface
+    pub surface: HullSurface,
+}
+
+impl Default for TensionLegPlatform {
+    fn default() -> Self {
+        Self {
+            columns: 4,
+            column_spacing: 60.0,
+            column_diameter: 18.0,
+            pontoon_width: 8.0,
+            pontoon_height: 9.0,
+            draft: 30.0,
+            panel_size: 2.5,
+            surface: HullSurface::Closed,
+        }
+    }
+}
+
 impl PredefinedGeometry {
     /// Create a sphere mesh
     pub fn sphere(radius: f64, num_phi: usize, num_theta: usize) -> Result<Mesh> {
@@ -46,4 +170,624 @@ impl PredefinedGeometry {
         
         Mesh::new(vertices, faces)
     }
-} 
\ No newline at end of file
+
+    /// Create a closed vertical circular cylinder from z = -draft to the waterplane z = 0
+    ///
+    /// The side wall has `num_theta` panels around and `num_z` down; the
+    /// bottom and top caps are split into rings of about the side panel
+    /// height. Vertices are shared, so the mesh is watertight, and normals
+    /// point out of the body.
+    pub fn cylinder(radius: f64, draft: f64, num_theta: usize, num_z: usize) -> Result<Mesh> {
+        if radius <= 0.0 || draft <= 0.0 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Cylinder requires positive radius and draft".to_string(),
+            });
+        }
+        if num_theta < 3 || num_z < 1 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Cylinder requires at least 3 theta divisions and 1 z division".to_string(),
+            });
+        }
+
+        let mut vertices = Vec::with_capacity(num_theta * (num_z + 1));
+        let mut faces = Vec::new();
+        let angle = |i: usize| 2.0 * std::f64::consts::PI * i as f64 / num_theta as f64;
+
+        // Side wall, ring j at z = -draft + j·draft/num_z
+        for j in 0..=num_z {
+            let z = -draft + draft * j as f64 / num_z as f64;
+            for i in 0..num_theta {
+                vertices.push(Point::new(radius * angle(i).cos(), radius * angle(i).sin(), z));
+            }
+        }
+        let side = |i: usize, j: usize| j * num_theta + i % num_theta;
+        for j in 0..num_z {
+            for i in 0..num_theta {
+                let (v0, v1, v2, v3) = (side(i, j), side(i + 1, j), side(i + 1, j + 1), side(i, j + 1));
+                faces.push([v0, v1, v2]);
+                faces.push([v0, v2, v3]);
+            }
+        }
+
+        // Caps with rings about as wide as the side panels are high
+        let num_r = ((num_z as f64 * radius / draft).round() as usize).max(1);
+        let bottom: Vec<usize> = (0..num_theta).map(|i| side(i, 0)).collect();
+        let top: Vec<usize> = (0..num_theta).map(|i| side(i, num_z)).collect();
+        disk(&mut vertices, &mut faces, &bottom, -draft, num_r, false);
+        disk(&mut vertices, &mut faces, &top, 0.0, num_r, true);
+
+        Mesh::new(vertices, faces)
+    }
+
+    /// Create a closed rectangular barge from z = -draft to the waterplane z = 0
+    ///
+    /// The box is centred on the z axis with `nx`, `ny` and `nz` panels along
+    /// its length, beam and draft.
+    pub fn box_hull(length: f64, beam: f64, draft: f64, nx: usize, ny: usize, nz: usize) -> Result<Mesh> {
+        Self::box_hull_with_surface(length, beam, draft, nx, ny, nz, HullSurface::Closed)
+    }
+
+    /// Create a rectangular barge, with or without the waterplane lid
+    pub fn box_hull_with_surface(
+        length: f64,
+        beam: f64,
+        draft: f64,
+        nx: usize,
+        ny: usize,
+        nz: usize,
+        surface: HullSurface,
+    ) -> Result<Mesh> {
+        if length <= 0.0 || beam <= 0.0 || draft <= 0.0 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Box hull requires positive length, beam and draft".to_string(),
+            });
+        }
+        if nx == 0 || ny == 0 || nz == 0 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Box hull requires at least 1 division along each axis".to_string(),
+            });
+        }
+
+        let counts = [nx, ny, nz];
+        let point = |g: [usize; 3]| {
+            Point::new(
+                length * (g[0] as f64 / nx as f64 - 0.5),
+                beam * (g[1] as f64 / ny as f64 - 0.5),
+                draft * (g[2] as f64 / nz as f64 - 1.0),
+            )
+        };
+
+        // Grid points on the surface are numbered once, so the sides share edges
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let mut index = std::collections::HashMap::new();
+        let mut sides = vec![(0, 0), (0, nx), (1, 0), (1, ny), (2, 0)];
+        if surface == HullSurface::Closed {
+            sides.push((2, nz));
+        }
+        for (axis, level) in sides {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            let outward = if level == 0 { -1.0 } else { 1.0 };
+            for i in 0..counts[u] {
+                for j in 0..counts[v] {
+                    let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)].map(|(a, b)| {
+                        let mut g = [0; 3];
+                        g[axis] = level;
+                        g[u] = a;
+                        g[v] = b;
+                        *index.entry(g).or_insert_with(|| {
+                            vertices.push(point(g));
+                            vertices.len() - 1
+                        })
+                    });
+                    // (u, v, axis) is right-handed, so corners run counterclockwise about +axis
+                    let [v0, v1, v2, v3] = corners;
+                    if outward > 0.0 {
+                        faces.push([v0, v1, v2]);
+                        faces.push([v0, v2, v3]);
+                    } else {
+                        faces.push([v0, v2, v1]);
+                        faces.push([v0, v3, v2]);
+                    }
+                }
+            }
+        }
+
+        Mesh::new(vertices, faces)
+    }
+
+    /// Create a closed Wigley hull from z = -draft to the waterplane z = 0
+    ///
+    /// The half-breadth is y = (B/2) (1 - (2x/L)²) (1 - (z/T)²), with `nx`
+    /// panels along the length and `nz` down each side.
+    pub fn wigley(length: f64, beam: f64, draft: f64, nx: usize, nz: usize) -> Result<Mesh> {
+        Self::wigley_with_surface(length, beam, draft, nx, nz, HullSurface::Closed)
+    }
+
+    /// Create a Wigley hull, with or without the waterplane lid
+    pub fn wigley_with_surface(
+        length: f64,
+        beam: f64,
+        draft: f64,
+        nx: usize,
+        nz: usize,
+        surface: HullSurface,
+    ) -> Result<Mesh> {
+        if length <= 0.0 || beam <= 0.0 || draft <= 0.0 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Wigley hull requires positive length, beam and draft".to_string(),
+            });
+        }
+        if nx < 2 || nz < 1 {
+            return Err(MeshError::InvalidGeometry {
+                message: "Wigley hull requires at least 2 x divisions and 1 z division".to_string(),
+            });
+        }
+
+        // Stem, stern and keel lie on the centreplane and are shared by both sides
+        let mut vertices = Vec::new();
+        let mut index = std::collections::HashMap::new();
+        let mut vertex = |port: bool, i: usize, j: usize| {
+            let on_centreplane = i == 0 || i == nx || j == 0;
+            *index.entry((port && !on_centreplane, i, j)).or_insert_with(|| {
+                let xi = 2.0 * i as f64 / nx as f64 - 1.0;
+                let zeta = j as f64 / nz as f64 - 1.0;
+                let y = 0.5 * beam * (1.0 - xi * xi) * (1.0 - zeta * zeta);
+                vertices.push(Point::new(0.5 * length * xi, if port { y } else { -y }, draft * zeta));
+                vertices.len() - 1
+            })
+        };
+
+        let mut faces = Vec::new();
+        for port in [true, false] {
+            for i in 0..nx {
+                for j in 0..nz {
+                    let v0 = vertex(port, i, j);
+                    let v1 = vertex(port, i + 1, j);
+                    let v2 = vertex(port, i + 1, j + 1);
+                    let v3 = vertex(port, i, j + 1);
+                    // At the bow foot v0, v1, v2 all lie on the keel and stem; split on the other diagonal
+                    let quad = if j == 0 && i + 1 == nx { [[v0, v1, v3], [v1, v2, v3]] } else { [[v0, v1, v2], [v0, v2, v3]] };
+                    // x × z points to starboard, so the port side is reversed
+                    for [a, b, c] in quad {
+                        faces.push(if port { [a, c, b] } else { [a, b, c] });
+                    }
+                }
+            }
+        }
+        if surface == HullSurface::Closed {
+            // Strips across the waterplane, counterclockwise seen from above; the ends are triangles
+            for i in 0..nx {
+                let (p0, p1) = (vertex(true, i, nz), vertex(true, i + 1, nz));
+                let (s0, s1) = (vertex(false, i, nz), vertex(false, i + 1, nz));
+                if i + 1 < nx {
+                    faces.push([s0, s1, p1]);
+                }
+                if i > 0 {
+                    faces.push([s0, p1, p0]);
+                }
+            }
+        }
+
+        Mesh::new(vertices, faces)
+    }
+    /// Semi-submersible of four columns on ring or twin pontoons
+    ///
+    /// Columns and pontoons are panelled separately and panels inside
+    /// another component are dropped, so the joins are resolved to about
+    /// one panel size.
+    pub fn semi_submersible(params: &SemiSubmersible) -> Result<Mesh> {
+        let half = 0.5 * params.column_spacing;
+        let columns = [[half, half], [-half, half], [-half, -half], [half, -half]];
+        let pontoons: Vec<([f64; 2], [f64; 2])> = match params.layout {
+            PontoonLayout::Ring => (0..4).map(|i| (columns[i], columns[(i + 1) % 4])).collect(),
+            PontoonLayout::Twin => {
+                // Run the pontoons out to the columns' outer faces
+                let end = half + 0.5 * params.column_diameter;
+                vec![([-end, half], [end, half]), ([-end, -half], [end, -half])]
+            }
+        };
+        column_stabilized(
+            &columns,
+            params.column_diameter,
+            &pontoons,
+            params.pontoon_width,
+            params.pontoon_height,
+            params.draft,
+            params.panel_size,
+            params.surface,
+        )
+    }
+
+    /// Spar of revolution, a hard tank from the waterline over a lower hull to the keel
+    pub fn spar(params: &Spar) -> Result<Mesh> {
+        let &Spar { hard_tank_diameter, hard_tank_depth, lower_diameter, draft, panel_size, surface } = params;
+        if !(hard_tank_diameter > 0.0 && lower_diameter > 0.0 && hard_tank_depth > 0.0 && draft > hard_tank_depth) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!(
+                    "Spar requires positive diameters and 0 < hard tank depth {} < draft {}",
+                    hard_tank_depth, draft
+                ),
+            });
+        }
+        let (r_tank, r_lower) = (0.5 * hard_tank_diameter, 0.5 * lower_diameter);
+        let mut profile = vec![[0.0, -draft], [r_lower, -draft], [r_lower, -hard_tank_depth]];
+        if r_lower != r_tank {
+            profile.push([r_tank, -hard_tank_depth]);
+        }
+        profile.push([r_tank, 0.0]);
+        if surface == HullSurface::Closed {
+            profile.push([0.0, 0.0]);
+        }
+        revolution(&profile, [0.0, 0.0], divisions(std::f64::consts::PI * hard_tank_diameter, panel_size)?, panel_size)
+    }
+
+    /// Tension-leg platform of columns on a circle joined by ring pontoons
+    ///
+    /// Column k lies at the angle (2k + 1)π/n, so the platform is symmetric
+    /// about the x-z plane and four columns stand on the corners of a square.
+    /// Joins are resolved as in [`PredefinedGeometry::semi_submersible`].
+    pub fn tension_leg_platform(params: &TensionLegPlatform) -> Result<Mesh> {
+        let n = params.columns;
+        if n < 3 {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Tension-leg platform requires at least 3 columns, got {}", n),
+            });
+        }
+        let radius = params.column_spacing / (2.0 * (std::f64::consts::PI / n as f64).sin());
+        let columns: Vec<[f64; 2]> = (0..n)
+            .map(|i| {
+                let angle = (2 * i + 1) as f64 * std::f64::consts::PI / n as f64;
+                [radius * angle.cos(), radius * angle.sin()]
+            })
+            .collect();
+        let pontoons: Vec<([f64; 2], [f64; 2])> = (0..n).map(|i| (columns[i], columns[(i + 1) % n])).collect();
+        column_stabilized(
+            &columns,
+            params.column_diameter,
+            &pontoons,
+            params.pontoon_width,
+            params.pontoon_height,
+            params.draft,
+            params.panel_size,
+            params.surface,
+        )
+    }
+}
+
+/// Triangulate the disk inside the ring of vertices `boundary` (counterclockwise seen from
+/// above) at height `z` with `num_r` rings, normals up or down
+fn disk(vertices: &mut Vec<Point>, faces: &mut Vec<[usize; 3]>, boundary: &[usize], z: f64, num_r: usize, up: bool) {
+    let mut push = |face: [usize; 3]| faces.push(if up { face } else { [face[0], face[2], face[1]] });
+    let n = boundary.len();
+    let centre = vertices.len();
+    vertices.push(Point::new(0.0, 0.0, z));
+
+    // Rings k = 1..num_r from the centre out; the outer ring is the boundary
+    let mut inner: Vec<usize> = Vec::new();
+    for k in 1..=num_r {
+        let ring: Vec<usize> = if k == num_r {
+            boundary.to_vec()
+        } else {
+            let scale = k as f64 / num_r as f64;
+            (0..n)
+                .map(|i| {
+                    let p = vertices[boundary[i]];
+                    vertices.push(Point::new(p.x * scale, p.y * scale, z));
+                    vertices.len() - 1
+                })
+                .collect()
+        };
+        for i in 0..n {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            if inner.is_empty() {
+                push([centre, a, b]);
+            } else {
+                let (c, d) = (inner[i], inner[(i + 1) % n]);
+                push([c, a, b]);
+                push([c, b, d]);
+            }
+        }
+        inner = ring;
+    }
+}
+
+/// Number of panels of about `panel_size` along a length, a multiple of 4 and at least 8
+fn divisions(length: f64, panel_size: f64) -> Result<usize> {
+    if !(panel_size > 0.0 && panel_size.is_finite()) {
+        return Err(MeshError::InvalidGeometry {
+            message: format!("Panel size {} must be positive and finite", panel_size),
+        });
+    }
+    Ok((4 * (length / (4.0 * panel_size)).round() as usize).max(8))
+}
+
+/// Surface of revolution about the vertical axis through `centre`
+///
+/// The (r, z) profile starts on the axis at the keel and runs outward and
+/// up; ending on the axis closes the top. Each segment is split into pieces
+/// no longer than `panel_size`, and normals point away from the enclosed side.
+fn revolution(profile: &[[f64; 2]], centre: [f64; 2], num_theta: usize, panel_size: f64) -> Result<Mesh> {
+    let mut stations = vec![profile[0]];
+    for pair in profile.windows(2) {
+        let [[r0, z0], [r1, z1]] = [pair[0], pair[1]];
+        let pieces = ((r1 - r0).hypot(z1 - z0) / panel_size).ceil().max(1.0) as usize;
+        stations.extend((1..=pieces).map(|k| {
+            let t = k as f64 / pieces as f64;
+            [r0 + (r1 - r0) * t, z0 + (z1 - z0) * t]
+        }));
+    }
+
+    // One vertex for a station on the axis, a ring otherwise
+    let mut vertices = Vec::new();
+    let rings: Vec<Vec<usize>> = stations
+        .iter()
+        .map(|&[r, z]| {
+            let count = if r == 0.0 { 1 } else { num_theta };
+            (0..count)
+                .map(|i| {
+                    let angle = 2.0 * std::f64::consts::PI * i as f64 / num_theta as f64;
+                    vertices.push(Point::new(centre[0] + r * angle.cos(), centre[1] + r * angle.sin(), z));
+                    vertices.len() - 1
+                })
+                .collect()
+        })
+        .collect();
+    let mut faces = Vec::new();
+    for pair in rings.windows(2) {
+        let (lower, upper) = (&pair[0], &pair[1]);
+        let at = |ring: &Vec<usize>, i: usize| ring[i % ring.len()];
+        for i in 0..num_theta {
+            // Around the ring, then up the profile, turns the normal outward
+            let [a, b, c, d] = [at(lower, i), at(lower, i + 1), at(upper, i + 1), at(upper, i)];
+            if lower.len() > 1 {
+                faces.push([a, b, c]);
+            }
+            if upper.len() > 1 {
+                faces.push([a, c, d]);
+            }
+        }
+    }
+    Mesh::new(vertices, faces)
+}
+
+/// Columns on pontoons, with panels inside another component removed
+#[allow(clippy::too_many_arguments)]
+fn column_stabilized(
+    columns: &[[f64; 2]],
+    column_diameter: f64,
+    pontoons: &[([f64; 2], [f64; 2])],
+    pontoon_width: f64,
+    pontoon_height: f64,
+    draft: f64,
+    panel_size: f64,
+    surface: HullSurface,
+) -> Result<Mesh> {
+    if !(column_diameter > 0.0 && pontoon_width > 0.0 && pontoon_height > 0.0 && draft > pontoon_height) {
+        return Err(MeshError::InvalidGeometry {
+            message: format!(
+                "Column-stabilized hull requires positive dimensions and pontoon height {} below draft {}",
+                pontoon_height, draft
+            ),
+        });
+    }
+    let num_theta = divisions(std::f64::consts::PI * column_diameter, panel_size)?;
+    let radius = 0.5 * column_diameter;
+
+    // Pontoons first: where a column's bottom lies on a pontoon, the pontoon keeps its panels
+    let mut components = Vec::new();
+    for &(a, b) in pontoons {
+        let length = (b[0] - a[0]).hypot(b[1] - a[1]);
+        let count = |size: f64| ((size / panel_size).ceil() as usize).max(1);
+        let body = PredefinedGeometry::box_hull(length, pontoon_width, pontoon_height, count(length), count(pontoon_width), count(pontoon_height))?;
+        let placement = Transformation::Rotation { axis: Vector::z(), angle: (b[1] - a[1]).atan2(b[0] - a[0]) }
+            .then(Transformation::Translation(Vector::new(0.5 * (a[0] + b[0]), 0.5 * (a[1] + b[1]), pontoon_height - draft)));
+        components.push(MeshInstance::with_transformation(std::sync::Arc::new(body), &placement)?.to_mesh()?);
+    }
+    for &centre in columns {
+        components.push(revolution(&[[0.0, -draft], [radius, -draft], [radius, 0.0], [0.0, 0.0]], centre, num_theta, panel_size)?);
+    }
+
+    let trees: Vec<PanelTree> = components.iter().map(PanelTree::new).collect();
+    let tolerance = 1e-9 * (draft + columns.iter().flatten().fold(0.0, |m: f64, c| m.max(c.abs())));
+    let (mut vertices, mut faces) = (Vec::new(), Vec::new());
+    for (i, component) in components.iter().enumerate() {
+        let offset = vertices.len();
+        vertices.extend_from_slice(&component.vertices);
+        for face in &component.faces {
+            let corners = face.map(|v| component.vertices[v]);
+            let centroid = Point::from((corners[0].coords + corners[1].coords + corners[2].coords) / 3.0);
+            if surface == HullSurface::Wetted && corners.iter().all(|p| p.z == 0.0) {
+                continue;
+            }
+            let covered = trees.iter().enumerate().any(|(j, tree)| {
+                let near = tree.bounds().is_some_and(|(lo, hi)| {
+                    (0..3).all(|k| centroid[k] >= lo[k] - tolerance && centroid[k] <= hi[k] + tolerance)
+                });
+                if j == i || !near {
+                    return false;
+                }
+                let on_surface = tree.nearest_panel(&centroid).is_some_and(|hit| hit.distance <= tolerance);
+                (on_surface && j < i) || (!on_surface && tree.contains(&centroid))
+            });
+            if !covered {
+                faces.push(face.map(|v| v + offset));
+            }
+        }
+    }
+
+    // Drop vertices left without a panel
+    let mut index = vec![usize::MAX; vertices.len()];
+    let mut used = Vec::new();
+    for v in faces.iter_mut().flatten() {
+        if index[*v] == usize::MAX {
+            index[*v] = used.len();
+            used.push(vertices[*v]);
+        }
+        *v = index[*v];
+    }
+    Mesh::new(used, faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cylinder_is_closed_with_outward_normals() {
+        let (radius, draft, n) = (2.0, 3.0, 24);
+        let mesh = PredefinedGeometry::cylinder(radius, draft, n, 6).unwrap();
+
+        // Every edge is shared by exactly two faces, traversed in opposite directions
+        let mut edges = std::collections::HashMap::new();
+        for face in &mesh.faces {
+            for k in 0..3 {
+                *edges.entry((face[k], face[(k + 1) % 3])).or_insert(0) += 1;
+            }
+        }
+        assert!(edges.iter().all(|(&(a, b), &count)| count == 1 && edges.get(&(b, a)) == Some(&1)));
+
+        // Divergence theorem: V = Σ (c · n) A / 3 equals the volume of the polygonal prism
+        let mut volume = 0.0;
+        for (face, normal) in mesh.faces.iter().zip(&mesh.normals) {
+            let [a, b, c] = face.map(|v| mesh.vertices[v]);
+            let centroid = (a.coords + b.coords + c.coords) / 3.0;
+            let area = 0.5 * (b - a).cross(&(c - a)).norm();
+            volume += centroid.dot(normal) * area / 3.0;
+            // Outward: away from the axis on the wall, down on the bottom, up on top
+            let outward = if centroid.z.abs() < 1e-12 { 1.0 } else if (centroid.z + draft).abs() < 1e-12 { -1.0 } else { 0.0 };
+            assert!(normal.z * outward > 0.99 || (outward == 0.0 && normal.x * centroid.x + normal.y * centroid.y > 0.0));
+        }
+        let prism = 0.5 * n as f64 * radius * radius * (2.0 * std::f64::consts::PI / n as f64).sin() * draft;
+        assert!((volume - prism).abs() < 1e-10);
+        assert!(PredefinedGeometry::cylinder(1.0, 0.0, 8, 2).is_err());
+    }
+
+    #[test]
+    fn test_box_hull_wetted_and_closed() {
+        let (length, beam, draft) = (10.0, 4.0, 2.0);
+        let closed = PredefinedGeometry::box_hull(length, beam, draft, 5, 3, 2).unwrap();
+        let wetted =
+            PredefinedGeometry::box_hull_with_surface(length, beam, draft, 5, 3, 2, HullSurface::Wetted).unwrap();
+        assert_eq!(closed.faces.len(), 2 * (2 * 5 * 3 + 2 * 3 * 2 + 2 * 5 * 2));
+        assert_eq!(wetted.faces.len(), closed.faces.len() - 2 * 5 * 3);
+
+        // Closed: V = Σ (c · n) A / 3; wetted: V = Σ z n_z A over the submerged surface
+        let (mut volume, mut displaced) = (0.0, 0.0);
+        for (face, normal) in closed.faces.iter().zip(&closed.normals) {
+            let [a, b, c] = face.map(|v| closed.vertices[v]);
+            let centroid = (a.coords + b.coords + c.coords) / 3.0;
+            volume += centroid.dot(normal) * 0.5 * (b - a).cross(&(c - a)).norm() / 3.0;
+        }
+        for (face, normal) in wetted.faces.iter().zip(&wetted.normals) {
+            let [a, b, c] = face.map(|v| wetted.vertices[v]);
+            let centroid_z = (a.z + b.z + c.z) / 3.0;
+            assert!(centroid_z < 0.0 || normal.z.abs() < 1e-12);
+            displaced += centroid_z * normal.z * 0.5 * (b - a).cross(&(c - a)).norm();
+        }
+        assert!((volume - length * beam * draft).abs() < 1e-10);
+        assert!((displaced - length * beam * draft).abs() < 1e-10);
+        assert!(PredefinedGeometry::box_hull(10.0, 4.0, 2.0, 0, 3, 2).is_err());
+    }
+
+    #[test]
+    fn test_wigley_hull() {
+        let (length, beam, draft) = (1.0, 0.1, 0.0625);
+        let closed = PredefinedGeometry::wigley(length, beam, draft, 40, 16).unwrap();
+        let wetted =
+            PredefinedGeometry::wigley_with_surface(length, beam, draft, 40, 16, HullSurface::Wetted).unwrap();
+        assert_eq!(wetted.faces.len(), 4 * 40 * 16);
+        assert_eq!(closed.faces.len(), wetted.faces.len() + 2 * 40 - 2);
+
+        // Closed and watertight: every directed edge is matched by its reverse
+        let mut edges = std::collections::HashSet::new();
+        for face in &closed.faces {
+            for k in 0..3 {
+                assert!(edges.insert((face[k], face[(k + 1) % 3])));
+            }
+        }
+        assert!(edges.iter().all(|&(a, b)| edges.contains(&(b, a))));
+
+        // Displacement converges to the analytical 4/9 L B T
+        let mut volume = 0.0;
+        for (face, normal) in closed.faces.iter().zip(&closed.normals) {
+            let [a, b, c] = face.map(|v| closed.vertices[v]);
+            let centroid = (a.coords + b.coords + c.coords) / 3.0;
+            volume += centroid.dot(normal) * 0.5 * (b - a).cross(&(c - a)).norm() / 3.0;
+        }
+        let exact = 4.0 / 9.0 * length * beam * draft;
+        assert!(((volume - exact) / exact).abs() < 5e-3);
+        assert!(PredefinedGeometry::wigley(1.0, 0.1, 0.0625, 1, 4).is_err());
+    }
+
+    /// Closed volume Σ (c · n) A / 3 and displaced volume Σ z n_z A
+    fn volumes(mesh: &Mesh) -> (f64, f64) {
+        mesh.faces.iter().zip(&mesh.normals).fold((0.0, 0.0), |(volume, displaced), (face, normal)| {
+            let [a, b, c] = face.map(|v| mesh.vertices[v]);
+            let centroid = (a.coords + b.coords + c.coords) / 3.0;
+            let area = 0.5 * (b - a).cross(&(c - a)).norm();
+            (volume + centroid.dot(normal) * area / 3.0, displaced + centroid.z * normal.z * area)
+        })
+    }
+
+    #[test]
+    fn test_spar_volume_of_revolution() {
+        let spar = Spar::default();
+        let closed = PredefinedGeometry::spar(&spar).unwrap();
+        let wetted = PredefinedGeometry::spar(&Spar { surface: HullSurface::Wetted, ..spar.clone() }).unwrap();
+        let n = divisions(std::f64::consts::PI * spar.hard_tank_diameter, spar.panel_size).unwrap();
+        let polygon = |d: f64| 0.5 * n as f64 * (0.5 * d).powi(2) * (2.0 * std::f64::consts::PI / n as f64).sin();
+        let exact = polygon(spar.hard_tank_diameter) * spar.hard_tank_depth
+            + polygon(spar.lower_diameter) * (spar.draft - spar.hard_tank_depth);
+        assert!((volumes(&closed).0 - exact).abs() < 1e-9 * exact);
+        assert!((volumes(&wetted).1 - exact).abs() < 1e-9 * exact);
+        assert!(closed.normals.iter().zip(&closed.faces).all(|(normal, face)| {
+            let centroid = face.iter().fold(Vector::zeros(), |sum, &v| sum + closed.vertices[v].coords) / 3.0;
+            normal.x * centroid.x + normal.y * centroid.y >= -1e-9
+        }));
+        assert!(PredefinedGeometry::spar(&Spar { hard_tank_depth: 200.0, ..spar }).is_err());
+    }
+
+    /// Plan area of a pontoon of width `w` inside a column of radius `r` it runs into
+    fn pontoon_inside_column(r: f64, w: f64) -> f64 {
+        0.5 * w * (r * r - 0.25 * w * w).sqrt() + r * r * (0.5 * w / r).asin()
+    }
+
+    #[test]
+    fn test_semi_submersible_layouts() {
+        let semi = SemiSubmersible::default();
+        let (s, r, w, h, t) = (semi.column_spacing, 0.5 * semi.column_diameter, semi.pontoon_width, semi.pontoon_height, semi.draft);
+        let columns = 4.0 * std::f64::consts::PI * r * r * t;
+        let ring = PredefinedGeometry::semi_submersible(&semi).unwrap();
+        let expected = columns + 4.0 * h * (w * s - 2.0 * pontoon_inside_column(r, w));
+        let (volume, displaced) = volumes(&ring);
+        assert!(((volume - expected) / expected).abs() < 0.03);
+        assert!(((displaced - expected) / expected).abs() < 0.03);
+
+        let twin = PredefinedGeometry::semi_submersible(&SemiSubmersible { layout: PontoonLayout::Twin, ..semi.clone() }).unwrap();
+        let expected = columns + 2.0 * h * (w * (s + 2.0 * r) - 4.0 * pontoon_inside_column(r, w));
+        assert!(((volumes(&twin).0 - expected) / expected).abs() < 0.03);
+
+        let wetted = PredefinedGeometry::semi_submersible(&SemiSubmersible { surface: HullSurface::Wetted, ..semi.clone() }).unwrap();
+        assert!(wetted.vertices.iter().all(|p| p.z <= 0.0));
+        assert!(wetted.faces.iter().all(|f| f.iter().any(|&v| wetted.vertices[v].z < 0.0)));
+        assert!(((volumes(&wetted).1 - displaced) / displaced).abs() < 1e-9);
+        assert!(PredefinedGeometry::semi_submersible(&SemiSubmersible { pontoon_height: 30.0, ..semi }).is_err());
+    }
+
+    #[test]
+    fn test_tension_leg_platform() {
+        let tlp = TensionLegPlatform { columns: 3, panel_size: 3.0, ..Default::default() };
+        let mesh = PredefinedGeometry::tension_leg_platform(&tlp).unwrap();
+        let (volume, _) = volumes(&mesh);
+        let (r, w) = (0.5 * tlp.column_diameter, tlp.pontoon_width);
+        let columns = 3.0 * std::f64::consts::PI * r * r * tlp.draft;
+        let pontoons = 3.0 * tlp.pontoon_height * (w * tlp.column_spacing - 2.0 * pontoon_inside_column(r, w));
+        assert!(((volume - columns - pontoons) / (columns + pontoons)).abs() < 0.03);
+
+        // Symmetric about the x-z plane
+        let (lo, hi) = mesh.vertices.iter().fold((0.0_f64, 0.0_f64), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+        assert!((lo + hi).abs() < 1e-9 * hi);
+        assert!(PredefinedGeometry::tension_leg_platform(&TensionLegPlatform { columns: 2, ..tlp }).is_err());
+    }
+}
@@ -1,665 +1,360 @@
-use crate::mesh::{Mesh, Panel};
-use crate::Point;
-use nalgebra::{Vector3, Point3 as NalgebraPoint3};
+//! Panel quality metrics
+//!
+//! Constant-panel BEM is most accurate on compact, flat panels whose size
+//! varies smoothly. [`Mesh::panel_quality`] measures each panel:
+//!
+//! - aspect ratio, 1 for an equilateral triangle or a square; triangles use
+//!   l_max · perimeter / (4√3 area), quads the longest over the shortest edge
+//! - equiangle skewness, 0 when every corner angle is 60° (triangles) or
+//!   90° (quads) and 1 when a corner closes up or opens flat
+//! - warp of quads, the largest distance of a corner from the mean plane
+//!   over the mean diagonal
+//! - area ratio, the largest ratio of the panel's area to an edge
+//!   neighbour's, either way round
+//!
+//! It also flags degenerate panels, with a vanishing area or edge, and
+//! inverted ones: quads folded into a bowtie or with a reflex corner, and
+//! panels wound against most of their edge neighbours. [`Mesh::quality`]
+//! summarizes these as a [`MeshQuality`].
+//!
+//! The former [`QualityMetrics`] and [`ElementQuality`] remain, deprecated,
+//! for single panels; their mesh report is replaced by [`Mesh::quality`].
+
+use super::*;
 use std::collections::HashMap;
 
-/// Comprehensive quality metrics for mesh assessment
-#[derive(Debug, Clone)]
-pub struct QualityMetrics {
+/// Quality measures of one panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanelQuality {
+    /// Elongation, 1 for an equilateral triangle or a square
     pub aspect_ratio: f64,
+    /// Equiangle skewness, 0 (ideal) to 1 (collapsed)
     pub skewness: f64,
-    pub orthogonality: f64,
-    pub volume_ratio: f64,
-    pub min_angle: f64,
-    pub max_angle: f64,
-    pub warping: f64,
+    /// Out-of-plane distance of the corners relative to the panel size; 0 for triangles
+    pub warp: f64,
+    /// Largest area ratio to an edge neighbour, at least 1
+    pub area_ratio: f64,
+    /// Vanishing area or edge length
+    pub degenerate: bool,
+    /// Folded, concave or wound against its neighbours
+    pub inverted: bool,
+    /// Combined score, 1 (ideal) to 0 (unusable)
+    pub quality: f64,
 }
 
-impl Default for QualityMetrics {
-    fn default() -> Self {
-        Self {
-            aspect_ratio: 3.0,    // Maximum acceptable aspect ratio
-            skewness: 0.8,        // Maximum acceptable skewness (0-1)
-            orthogonality: 0.1,   // Minimum acceptable orthogonality
-            volume_ratio: 0.1,    // Minimum acceptable volume ratio
-            min_angle: 20.0,      // Minimum angle in degrees
-            max_angle: 160.0,     // Maximum angle in degrees
-            warping: 0.1,         // Maximum warping factor
+impl Mesh {
+    /// Quality measures of every panel, triangles first
+    pub fn panel_quality(&self) -> Vec<PanelQuality> {
+        let polygons: Vec<&[usize]> = self.polygons().collect();
+        let corners = |polygon: &[usize]| -> Vec<Point> { polygon.iter().map(|&v| self.vertices[v]).collect() };
+        let areas: Vec<f64> = polygons.iter().map(|p| vector_area(&corners(p)).norm()).collect();
+
+        // Edge neighbours, and whether they run the shared edge the other way
+        let mut edges: HashMap<(usize, usize), Vec<(usize, bool)>> = HashMap::new();
+        for (p, polygon) in polygons.iter().enumerate() {
+            for k in 0..polygon.len() {
+                let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                edges.entry((a.min(b), a.max(b))).or_default().push((p, a < b));
+            }
+        }
+        let mut neighbours = vec![Vec::new(); polygons.len()];
+        for panels in edges.values() {
+            if let [(p, forward_p), (q, forward_q)] = panels[..] {
+                neighbours[p].push((q, forward_p != forward_q));
+                neighbours[q].push((p, forward_p != forward_q));
+            }
         }
+
+        polygons
+            .iter()
+            .enumerate()
+            .map(|(p, polygon)| {
+                let points = corners(polygon);
+                let n = points.len();
+                let lengths: Vec<f64> = (0..n).map(|k| (points[(k + 1) % n] - points[k]).norm()).collect();
+                let (shortest, longest) = lengths.iter().fold((f64::INFINITY, 0.0_f64), |(lo, hi), &l| (lo.min(l), hi.max(l)));
+                let area = areas[p];
+                let degenerate = shortest <= 1e-12 * longest || area <= 1e-12 * longest * longest;
+
+                let normal = vector_area(&points);
+                let angles: Vec<f64> = (0..n)
+                    .map(|k| {
+                        let (prev, next) = (points[(k + n - 1) % n] - points[k], points[(k + 1) % n] - points[k]);
+                        prev.angle(&next).to_degrees()
+                    })
+                    .collect();
+                let ideal = if n == 3 { 60.0 } else { 90.0 };
+                let (min_angle, max_angle) = angles.iter().fold((f64::INFINITY, 0.0_f64), |(lo, hi), &a| (lo.min(a), hi.max(a)));
+                let skewness = ((max_angle - ideal) / (180.0 - ideal)).max((ideal - min_angle) / ideal).clamp(0.0, 1.0);
+
+                let aspect_ratio = match n {
+                    3 => longest * lengths.iter().sum::<f64>() / (4.0 * 3f64.sqrt() * area),
+                    _ => longest / shortest,
+                };
+                let warp = if n == 4 && !degenerate {
+                    let unit = normal.normalize();
+                    let centre = points.iter().fold(Vector::zeros(), |sum, q| sum + q.coords) / 4.0;
+                    let diagonal = 0.5 * ((points[2] - points[0]).norm() + (points[3] - points[1]).norm());
+                    points.iter().map(|q| (q.coords - centre).dot(&unit).abs()).fold(0.0, f64::max) / diagonal
+                } else {
+                    0.0
+                };
+                let area_ratio = neighbours[p]
+                    .iter()
+                    .map(|&(q, _)| (area / areas[q]).max(areas[q] / area))
+                    .filter(|r| r.is_finite())
+                    .fold(1.0, f64::max);
+
+                // A corner turning against the panel normal folds or dents a quad
+                let folded = n == 4
+                    && (0..n).any(|k| {
+                        let (prev, next) = (points[(k + n - 1) % n] - points[k], points[(k + 1) % n] - points[k]);
+                        next.cross(&prev).dot(&normal) <= 0.0
+                    });
+                let against = neighbours[p].iter().filter(|&&(_, consistent)| !consistent).count();
+                let inverted = !degenerate && (folded || 2 * against > neighbours[p].len());
+
+                let quality = if degenerate || inverted {
+                    0.0
+                } else {
+                    (1.0 - skewness) * (1.0 - warp.min(1.0)) / aspect_ratio.max(1.0)
+                };
+                PanelQuality {
+                    aspect_ratio: if degenerate { f64::INFINITY } else { aspect_ratio },
+                    skewness: if degenerate { 1.0 } else { skewness },
+                    warp,
+                    area_ratio,
+                    degenerate,
+                    inverted,
+                    quality,
+                }
+            })
+            .collect()
     }
-}
 
-/// Quality assessment report for entire mesh
-#[derive(Debug, Clone)]
-pub struct QualityReport {
-    pub overall_score: f64,
-    pub element_count: usize,
-    pub poor_elements: Vec<usize>,
-    pub excellent_elements: Vec<usize>,
-    pub metrics: HashMap<usize, ElementQuality>,
-    pub statistics: QualityStatistics,
-    pub recommendations: Vec<String>,
+    /// Summary of the panel quality measures
+    pub fn quality(&self) -> MeshQuality {
+        let panels = self.panel_quality();
+        if panels.is_empty() {
+            return MeshQuality::default();
+        }
+        let max = |f: fn(&PanelQuality) -> f64| panels.iter().map(f).fold(f64::NEG_INFINITY, f64::max);
+        MeshQuality {
+            min_quality: panels.iter().map(|p| p.quality).fold(f64::INFINITY, f64::min),
+            max_quality: max(|p| p.quality),
+            average_quality: panels.iter().map(|p| p.quality).sum::<f64>() / panels.len() as f64,
+            degenerate_elements: panels.iter().filter(|p| p.degenerate).count(),
+            inverted_elements: panels.iter().filter(|p| p.inverted).count(),
+            total_elements: panels.len(),
+            max_aspect_ratio: max(|p| p.aspect_ratio),
+            max_skewness: max(|p| p.skewness),
+            max_warp: max(|p| p.warp),
+            max_area_ratio: max(|p| p.area_ratio),
+        }
+    }
 }
 
-/// Statistical summary of mesh quality
-#[derive(Debug, Clone)]
-pub struct QualityStatistics {
-    pub aspect_ratio: StatisticalSummary,
-    pub skewness: StatisticalSummary,
-    pub orthogonality: StatisticalSummary,
-    pub angles: AngleStatistics,
-    pub quality_distribution: QualityDistribution,
+/// Vector area ½ Σ p_k × p_{k+1} of a polygon, normal to its mean plane
+fn vector_area(points: &[Point]) -> Vector {
+    let n = points.len();
+    0.5 * (0..n).fold(Vector::zeros(), |sum, k| sum + points[k].coords.cross(&points[(k + 1) % n].coords))
 }
 
-/// Statistical summary for a metric
-#[derive(Debug, Clone)]
-pub struct StatisticalSummary {
-    pub min: f64,
-    pub max: f64,
-    pub mean: f64,
-    pub median: f64,
-    pub std_dev: f64,
-    pub percentile_95: f64,
+/// Quality measures of a panel on its own, with no neighbours to compare areas against
+pub(crate) fn lone_panel_quality(panel: &Panel) -> Result<PanelQuality> {
+    let mut vertices = panel.vertices.to_vec();
+    let mesh = match panel.fourth {
+        Some(fourth) => {
+            vertices.push(fourth);
+            Mesh::with_quads(vertices, Vec::new(), vec![[0, 1, 2, 3]])?
+        }
+        None => Mesh::new(vertices, vec![[0, 1, 2]])?,
+    };
+    Ok(mesh.panel_quality().remove(0))
 }
 
-/// Angle statistics for mesh elements
+/// Quality thresholds of the former per-element assessment
+#[deprecated(note = "use `Mesh::panel_quality` and `PanelQuality`")]
 #[derive(Debug, Clone)]
-pub struct AngleStatistics {
+pub struct QualityMetrics {
+    /// Largest acceptable aspect ratio
+    pub aspect_ratio: f64,
+    /// Largest acceptable skewness
+    pub skewness: f64,
+    /// Smallest acceptable orthogonality
+    pub orthogonality: f64,
+    /// Unused; kept for source compatibility
+    pub volume_ratio: f64,
+    /// Smallest acceptable corner angle, degrees
     pub min_angle: f64,
+    /// Largest acceptable corner angle, degrees
     pub max_angle: f64,
-    pub mean_angle: f64,
-    pub acute_count: usize,
-    pub obtuse_count: usize,
-    pub right_count: usize,
+    /// Largest acceptable warp
+    pub warping: f64,
 }
 
-/// Quality distribution breakdown
-#[derive(Debug, Clone)]
-pub struct QualityDistribution {
-    pub excellent: usize,  // Quality > 0.8
-    pub good: usize,       // Quality 0.6-0.8
-    pub fair: usize,       // Quality 0.4-0.6
-    pub poor: usize,       // Quality 0.2-0.4
-    pub very_poor: usize,  // Quality < 0.2
+#[allow(deprecated)]
+impl Default for QualityMetrics {
+    fn default() -> Self {
+        Self {
+            aspect_ratio: 3.0,
+            skewness: 0.8,
+            orthogonality: 0.1,
+            volume_ratio: 0.1,
+            min_angle: 20.0,
+            max_angle: 160.0,
+            warping: 0.1,
+        }
+    }
 }
 
-/// Quality metrics for individual elements
+/// Quality of one panel
+#[deprecated(note = "use `PanelQuality`")]
 #[derive(Debug, Clone)]
 pub struct ElementQuality {
+    /// Elongation, 1 for an equilateral triangle or a square
     pub aspect_ratio: f64,
+    /// Equiangle skewness, 0 (ideal) to 1 (collapsed)
     pub skewness: f64,
+    /// Flatness, 1 for a planar panel
     pub orthogonality: f64,
+    /// Out-of-plane distance of the corners relative to the panel size
     pub warping: f64,
+    /// Smallest corner angle, degrees
     pub min_angle: f64,
+    /// Largest corner angle, degrees
     pub max_angle: f64,
+    /// Combined score, 1 (ideal) to 0 (unusable)
     pub quality_score: f64,
+    /// Band of the score
     pub quality_grade: QualityGrade,
 }
 
-/// Quality grade classification
+/// Band of a quality score
 #[derive(Debug, Clone, PartialEq)]
 pub enum QualityGrade {
-    Excellent,  // > 0.8
-    Good,       // 0.6-0.8
-    Fair,       // 0.4-0.6
-    Poor,       // 0.2-0.4
-    VeryPoor,   // < 0.2
+    /// Above 0.8
+    Excellent,
+    /// 0.6 to 0.8
+    Good,
+    /// 0.4 to 0.6
+    Fair,
+    /// 0.2 to 0.4
+    Poor,
+    /// Below 0.2
+    VeryPoor,
 }
 
 impl QualityGrade {
+    /// Band of `score`
     pub fn from_score(score: f64) -> Self {
-        if score > 0.8 {
-            QualityGrade::Excellent
-        } else if score > 0.6 {
-            QualityGrade::Good
-        } else if score > 0.4 {
-            QualityGrade::Fair
-        } else if score > 0.2 {
-            QualityGrade::Poor
-        } else {
-            QualityGrade::VeryPoor
+        match score {
+            s if s > 0.8 => Self::Excellent,
+            s if s > 0.6 => Self::Good,
+            s if s > 0.4 => Self::Fair,
+            s if s > 0.2 => Self::Poor,
+            _ => Self::VeryPoor,
         }
     }
 }
 
+#[allow(deprecated)]
 impl QualityMetrics {
-    /// Calculate mesh quality for each element
-    pub fn calculate_element_quality(&self, panel: &Panel) -> Result<ElementQuality, Box<dyn std::error::Error>> {
-        let aspect_ratio = self.calculate_aspect_ratio(panel)?;
-        let skewness = self.calculate_skewness(panel)?;
-        let orthogonality = self.calculate_orthogonality(panel)?;
-        let warping = self.calculate_warping(panel)?;
-        let (min_angle, max_angle) = self.calculate_angles(panel)?;
-        
-        // Calculate overall quality score
-        let quality_score = self.calculate_quality_score(
-            aspect_ratio, skewness, orthogonality, warping, min_angle, max_angle
-        );
-        
-        let quality_grade = QualityGrade::from_score(quality_score);
-        
+    /// Quality of a lone panel from [`Mesh::panel_quality`]
+    pub fn calculate_element_quality(&self, panel: &Panel) -> std::result::Result<ElementQuality, Box<dyn std::error::Error>> {
+        let quality = lone_panel_quality(panel)?;
+        let corners: Vec<Point> = panel.vertices.iter().copied().chain(panel.fourth).collect();
+        let n = corners.len();
+        let angles = (0..n).map(|k| (corners[(k + n - 1) % n] - corners[k]).angle(&(corners[(k + 1) % n] - corners[k])).to_degrees());
+        let (min_angle, max_angle) = angles.fold((f64::INFINITY, 0.0_f64), |(lo, hi), a| (lo.min(a), hi.max(a)));
         Ok(ElementQuality {
-            aspect_ratio,
-            skewness,
-            orthogonality,
-            warping,
+            aspect_ratio: quality.aspect_ratio,
+            skewness: quality.skewness,
+            orthogonality: 1.0 - quality.warp.min(1.0),
+            warping: quality.warp,
             min_angle,
             max_angle,
-            quality_score,
-            quality_grade,
-        })
-    }
-
-    /// Overall mesh quality assessment
-    pub fn assess_mesh_quality(&self, mesh: &mut Mesh) -> Result<QualityReport, Box<dyn std::error::Error>> {
-        let mut metrics = HashMap::new();
-        let mut poor_elements = Vec::new();
-        let mut excellent_elements = Vec::new();
-        let mut quality_scores = Vec::new();
-        
-        // Calculate quality for each element
-        for (i, panel) in mesh.panels()?.iter().enumerate() {
-            let quality = self.calculate_element_quality(panel)?;
-            
-            match quality.quality_grade {
-                QualityGrade::Excellent => excellent_elements.push(i),
-                QualityGrade::Poor | QualityGrade::VeryPoor => poor_elements.push(i),
-                _ => {}
-            }
-            
-            quality_scores.push(quality.quality_score);
-            metrics.insert(i, quality);
-        }
-        
-        // Calculate overall statistics
-        let overall_score = if !quality_scores.is_empty() {
-            quality_scores.iter().sum::<f64>() / quality_scores.len() as f64
-        } else {
-            0.0
-        };
-        
-        let statistics = self.calculate_statistics(mesh, &metrics)?;
-        
-        Ok(QualityReport {
-            overall_score,
-            element_count: mesh.panels()?.len(),
-            poor_elements: poor_elements.clone(),
-            excellent_elements: Vec::new(), // TODO: Calculate excellent elements
-            metrics,
-            statistics: statistics.clone(),
-            recommendations: self.generate_recommendations(&statistics, &poor_elements),
+            quality_score: quality.quality,
+            quality_grade: QualityGrade::from_score(quality.quality),
         })
     }
 
-    /// Identify poor-quality elements for refinement
-    pub fn identify_refinement_candidates(&self, mesh: &Mesh) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
-        let mut mesh_mut = mesh.clone();
-        let quality_report = self.assess_mesh_quality(&mut mesh_mut)?;
-        Ok(quality_report.poor_elements)
-    }
-
-    /// Calculate aspect ratio of a panel
-    fn calculate_aspect_ratio(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel.vertices.len() < 3 {
-            return Err("Panel must have at least 3 vertices".into());
-        }
-        
-        let mut edge_lengths = Vec::new();
-        
-        for i in 0..panel.vertices.len() {
-            let j = (i + 1) % panel.vertices.len();
-            let edge_length = (panel.vertices[i] - panel.vertices[j]).norm();
-            edge_lengths.push(edge_length);
-        }
-        
-        let min_edge = edge_lengths.iter().cloned().fold(f64::INFINITY, f64::min);
-        let max_edge = edge_lengths.iter().cloned().fold(0.0, f64::max);
-        
-        if min_edge > 1e-12 {
-            Ok(max_edge / min_edge)
-        } else {
-            Ok(f64::INFINITY)
-        }
-    }
-
-    /// Calculate skewness of a panel
-    fn calculate_skewness(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel.vertices.len() == 3 {
-            // For triangles, calculate deviation from equilateral
-            return self.calculate_triangle_skewness(panel);
-        } else if panel.vertices.len() == 4 {
-            // For quads, calculate deviation from rectangle
-            return self.calculate_quad_skewness(panel);
-        }
-        
-        Ok(0.0) // For other shapes, return 0
-    }
-
-    /// Calculate triangle skewness
-    fn calculate_triangle_skewness(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel.vertices.len() != 3 {
-            return Err("Expected triangle".into());
-        }
-        
-        let v0 = panel.vertices[0];
-        let v1 = panel.vertices[1];
-        let v2 = panel.vertices[2];
-        
-        // Calculate edge lengths
-        let a = (v1 - v2).norm();
-        let b = (v2 - v0).norm();
-        let c = (v0 - v1).norm();
-        
-        // Ideal equilateral triangle would have all edges equal
-        let mean_edge = (a + b + c) / 3.0;
-        
-        if mean_edge > 1e-12 {
-            let deviation = ((a - mean_edge).abs() + (b - mean_edge).abs() + (c - mean_edge).abs()) / (3.0 * mean_edge);
-            Ok(deviation)
-        } else {
-            Ok(1.0)
-        }
-    }
-
-    /// Calculate quadrilateral skewness
-    fn calculate_quad_skewness(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        // Panel struct only supports triangular panels
-        // For now, return 0 (no skewness defined for triangles)
-        Ok(0.0)
-    }
-
-    /// Calculate orthogonality of a panel
-    fn calculate_orthogonality(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        if vertices.len() < 3 {
-            return Err("Panel must have at least 3 vertices".into());
-        }
-        
-        // Calculate normal vector
-        let v1 = vertices[1] - vertices[0];
-        let v2 = vertices[2] - vertices[0];
-        let normal = v1.cross(&v2);
-        
-        if normal.norm() < 1e-12 {
-            return Ok(0.0);
-        }
-        
-        let normal = normal.normalize();
-        
-        // Check how planar the panel is
-        let mut max_deviation: f64 = 0.0;
-        let center = panel.center();
-        
-        for vertex in vertices {
-            let to_vertex = vertex - center;
-            let deviation = to_vertex.dot(&normal).abs();
-            max_deviation = max_deviation.max(deviation);
-        }
-        
-        // Orthogonality is inverse of deviation
-        let diagonal_length = self.calculate_diagonal_length(panel)?;
-        if diagonal_length > 1e-12 {
-            Ok(1.0 - (max_deviation / diagonal_length).min(1.0))
-        } else {
-            Ok(1.0)
-        }
-    }
-
-    /// Calculate warping factor for a panel
-    fn calculate_warping(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        if vertices.len() <= 3 {
-            return Ok(0.0); // Triangles cannot warp
-        }
-        
-        // For quadrilaterals, calculate warping
-        if vertices.len() == 4 {
-            return self.calculate_quad_warping(panel);
-        }
-        
-        Ok(0.0)
-    }
-
-    /// Calculate warping for quadrilateral
-    fn calculate_quad_warping(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        // Panel struct only supports triangular panels
-        // Triangles cannot warp, so return 0
-        Ok(0.0)
-    }
-
-    /// Calculate minimum and maximum angles in a panel
-    fn calculate_angles(&self, panel: &Panel) -> Result<(f64, f64), Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        if vertices.len() < 3 {
-            return Err("Panel must have at least 3 vertices".into());
-        }
-        
-        let mut angles = Vec::new();
-        
-        for i in 0..vertices.len() {
-            let prev = (i + vertices.len() - 1) % vertices.len();
-            let next = (i + 1) % vertices.len();
-            
-            let v1 = (vertices[prev] - vertices[i]).normalize();
-            let v2 = (vertices[next] - vertices[i]).normalize();
-            
-            let dot_product = v1.dot(&v2).clamp(-1.0, 1.0);
-            let angle = dot_product.acos() * 180.0 / std::f64::consts::PI;
-            angles.push(angle);
-        }
-        
-        let min_angle = angles.iter().cloned().fold(f64::INFINITY, f64::min);
-        let max_angle = angles.iter().cloned().fold(0.0, f64::max);
-        
-        Ok((min_angle, max_angle))
-    }
-
-    /// Calculate overall quality score
-    fn calculate_quality_score(&self, aspect_ratio: f64, skewness: f64, orthogonality: f64, 
-                              warping: f64, min_angle: f64, max_angle: f64) -> f64 {
-        // Aspect ratio score (1.0 is perfect, decreases with higher ratios)
-        let aspect_score = (1.0 / (1.0 + (aspect_ratio - 1.0) / self.aspect_ratio)).min(1.0);
-        
-        // Skewness score (0 is perfect, 1 is worst)
-        let skewness_score = (1.0 - skewness.min(1.0)).max(0.0);
-        
-        // Orthogonality score (1.0 is perfect)
-        let orthogonality_score = orthogonality;
-        
-        // Warping score (0 is perfect, 1 is worst)
-        let warping_score = (1.0 - warping.min(1.0)).max(0.0);
-        
-        // Angle score
-        let angle_score = if min_angle > self.min_angle && max_angle < self.max_angle {
-            1.0
-        } else {
-            let min_penalty = if min_angle < self.min_angle { 
-                (self.min_angle - min_angle) / self.min_angle 
-            } else { 0.0 };
-            let max_penalty = if max_angle > self.max_angle { 
-                (max_angle - self.max_angle) / (180.0 - self.max_angle) 
-            } else { 0.0 };
-            (1.0 - min_penalty - max_penalty).max(0.0)
-        };
-        
-        // Weighted average of all scores
-        let weights = [0.25, 0.2, 0.2, 0.15, 0.2]; // Aspect, skewness, orthogonality, warping, angles
-        let scores = [aspect_score, skewness_score, orthogonality_score, warping_score, angle_score];
-        
-        weights.iter().zip(scores.iter()).map(|(w, s)| w * s).sum()
-    }
-
-    /// Calculate diagonal length for reference scaling
-    fn calculate_diagonal_length(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        if vertices.is_empty() {
-            return Ok(0.0);
-        }
-        
-        let mut max_distance: f64 = 0.0;
-        
-        for i in 0..vertices.len() {
-            for j in (i + 1)..vertices.len() {
-                let distance = (vertices[i] - vertices[j]).norm();
-                max_distance = max_distance.max(distance);
-            }
-        }
-        
-        Ok(max_distance)
-    }
-
-    /// Calculate comprehensive statistics
-    fn calculate_statistics(&self, _mesh: &Mesh, metrics: &HashMap<usize, ElementQuality>) 
-                           -> Result<QualityStatistics, Box<dyn std::error::Error>> {
-        if metrics.is_empty() {
-            return Err("No metrics available".into());
-        }
-        
-        let mut aspect_ratios = Vec::new();
-        let mut skewness_values = Vec::new();
-        let mut orthogonality_values = Vec::new();
-        let mut min_angles = Vec::new();
-        let mut max_angles = Vec::new();
-        let mut quality_scores = Vec::new();
-        
-        for quality in metrics.values() {
-            aspect_ratios.push(quality.aspect_ratio);
-            skewness_values.push(quality.skewness);
-            orthogonality_values.push(quality.orthogonality);
-            min_angles.push(quality.min_angle);
-            max_angles.push(quality.max_angle);
-            quality_scores.push(quality.quality_score);
-        }
-        
-        let aspect_ratio_stats = self.calculate_statistical_summary(&aspect_ratios);
-        let skewness_stats = self.calculate_statistical_summary(&skewness_values);
-        let orthogonality_stats = self.calculate_statistical_summary(&orthogonality_values);
-        
-        let angles = self.calculate_angle_statistics(&min_angles, &max_angles);
-        let quality_distribution = self.calculate_quality_distribution(&quality_scores);
-        
-        Ok(QualityStatistics {
-            aspect_ratio: aspect_ratio_stats,
-            skewness: skewness_stats,
-            orthogonality: orthogonality_stats,
-            angles,
-            quality_distribution,
-        })
-    }
-
-    /// Calculate statistical summary for a dataset
-    fn calculate_statistical_summary(&self, data: &[f64]) -> StatisticalSummary {
-        if data.is_empty() {
-            return StatisticalSummary {
-                min: 0.0, max: 0.0, mean: 0.0, median: 0.0, std_dev: 0.0, percentile_95: 0.0
-            };
-        }
-        
-        let mut sorted_data = data.to_vec();
-        sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        
-        let min = sorted_data[0];
-        let max = sorted_data[sorted_data.len() - 1];
-        let mean = data.iter().sum::<f64>() / data.len() as f64;
-        
-        let median_idx = sorted_data.len() / 2;
-        let median = if sorted_data.len() % 2 == 0 {
-            (sorted_data[median_idx - 1] + sorted_data[median_idx]) / 2.0
-        } else {
-            sorted_data[median_idx]
-        };
-        
-        let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
-        let std_dev = variance.sqrt();
-        
-        let percentile_95_idx = ((sorted_data.len() as f64) * 0.95) as usize;
-        let percentile_95 = sorted_data[percentile_95_idx.min(sorted_data.len() - 1)];
-        
-        StatisticalSummary {
-            min, max, mean, median, std_dev, percentile_95
-        }
-    }
-
-    /// Calculate angle statistics
-    fn calculate_angle_statistics(&self, min_angles: &[f64], max_angles: &[f64]) -> AngleStatistics {
-        if min_angles.is_empty() || max_angles.is_empty() {
-            return AngleStatistics {
-                min_angle: 0.0, max_angle: 0.0, mean_angle: 0.0,
-                acute_count: 0, obtuse_count: 0, right_count: 0
-            };
-        }
-        
-        let min_angle = min_angles.iter().cloned().fold(f64::INFINITY, f64::min);
-        let max_angle = max_angles.iter().cloned().fold(0.0, f64::max);
-        
-        let all_angles: Vec<f64> = min_angles.iter().chain(max_angles.iter()).cloned().collect();
-        let mean_angle = all_angles.iter().sum::<f64>() / all_angles.len() as f64;
-        
-        let mut acute_count = 0;
-        let mut obtuse_count = 0;
-        let mut right_count = 0;
-        
-        for &angle in &all_angles {
-            if (angle - 90.0).abs() < 5.0 {
-                right_count += 1;
-            } else if angle < 90.0 {
-                acute_count += 1;
-            } else {
-                obtuse_count += 1;
-            }
-        }
-        
-        AngleStatistics {
-            min_angle, max_angle, mean_angle,
-            acute_count, obtuse_count, right_count
-        }
-    }
-
-    /// Calculate quality distribution
-    fn calculate_quality_distribution(&self, quality_scores: &[f64]) -> QualityDistribution {
-        let mut excellent = 0;
-        let mut good = 0;
-        let mut fair = 0;
-        let mut poor = 0;
-        let mut very_poor = 0;
-        
-        for &score in quality_scores {
-            match QualityGrade::from_score(score) {
-                QualityGrade::Excellent => excellent += 1,
-                QualityGrade::Good => good += 1,
-                QualityGrade::Fair => fair += 1,
-                QualityGrade::Poor => poor += 1,
-                QualityGrade::VeryPoor => very_poor += 1,
-            }
-        }
-        
-        QualityDistribution {
-            excellent, good, fair, poor, very_poor
-        }
-    }
-
-    /// Generate improvement recommendations
-    fn generate_recommendations(&self, statistics: &QualityStatistics, poor_elements: &[usize]) -> Vec<String> {
-        let mut recommendations = Vec::new();
-        
-        // Overall recommendations
-        if !poor_elements.is_empty() {
-            recommendations.push(format!("{} elements have poor quality and need attention", poor_elements.len()));
-        }
-        
-        // Specific recommendations based on statistics
-        if statistics.aspect_ratio.mean > self.aspect_ratio {
-            recommendations.push("High aspect ratios detected. Consider mesh refinement or element reshaping".to_string());
-        }
-        
-        if statistics.skewness.mean > self.skewness {
-            recommendations.push("High skewness detected. Apply mesh smoothing or regeneration".to_string());
-        }
-        
-        if statistics.orthogonality.mean < self.orthogonality {
-            recommendations.push("Poor orthogonality detected. Improve mesh alignment".to_string());
-        }
-        
-        if statistics.angles.min_angle < self.min_angle {
-            recommendations.push(format!("Minimum angle ({:.1}°) is too small. Minimum should be >{:.1}°", 
-                                        statistics.angles.min_angle, self.min_angle));
-        }
-        
-        if statistics.angles.max_angle > self.max_angle {
-            recommendations.push(format!("Maximum angle ({:.1}°) is too large. Maximum should be <{:.1}°", 
-                                        statistics.angles.max_angle, self.max_angle));
-        }
-        
-        // Quality distribution recommendations
-        let total_elements = statistics.quality_distribution.excellent + 
-                           statistics.quality_distribution.good + 
-                           statistics.quality_distribution.fair + 
-                           statistics.quality_distribution.poor + 
-                           statistics.quality_distribution.very_poor;
-        
-        if total_elements > 0 {
-            let poor_percentage = ((statistics.quality_distribution.poor + 
-                                   statistics.quality_distribution.very_poor) as f64 / total_elements as f64) * 100.0;
-            
-            if poor_percentage > 20.0 {
-                recommendations.push("More than 20% of elements have poor quality. Consider global mesh regeneration".to_string());
-            } else if poor_percentage > 10.0 {
-                recommendations.push("More than 10% of elements have poor quality. Apply targeted refinement".to_string());
-            }
-        }
-        
-        if recommendations.is_empty() {
-            recommendations.push("Mesh quality is acceptable. Consider minor optimizations for better performance".to_string());
-        }
-        
-        recommendations
+    /// Panels graded poor or very poor
+    pub fn identify_refinement_candidates(&self, mesh: &Mesh) -> std::result::Result<Vec<usize>, Box<dyn std::error::Error>> {
+        Ok(mesh
+            .panel_quality()
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| matches!(QualityGrade::from_score(q.quality), QualityGrade::Poor | QualityGrade::VeryPoor))
+            .map(|(i, _)| i)
+            .collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mesh::Point3;
-
-    #[test]
-    fn test_quality_metrics_creation() {
-        let metrics = QualityMetrics::default();
-        assert!(metrics.aspect_ratio > 0.0);
-        assert!(metrics.skewness > 0.0);
-    }
 
-    #[test]
-    fn test_triangle_quality_calculation() {
-        let metrics = QualityMetrics::default();
-        
-        // Create an equilateral triangle
-        let vertices = vec![
-            Point3::new(0.0, 0.0, 0.0),
-            Point3::new(1.0, 0.0, 0.0),
-            Point3::new(0.5, (3.0_f64).sqrt() / 2.0, 0.0),
-        ];
-        let panel = Panel::new(vertices);
-        
-        let quality = metrics.calculate_element_quality(&panel).unwrap();
-        assert!(quality.quality_score > 0.7); // Should be high quality
-        assert_eq!(quality.quality_grade, QualityGrade::Good);
+    fn points(coords: &[[f64; 3]]) -> Vec<Point> {
+        coords.iter().map(|&[x, y, z]| Point::new(x, y, z)).collect()
     }
 
     #[test]
-    fn test_degenerate_triangle() {
-        let metrics = QualityMetrics::default();
-        
-        // Create a very thin triangle (poor quality)
-        let vertices = vec![
-            Point3::new(0.0, 0.0, 0.0),
-            Point3::new(10.0, 0.0, 0.0),
-            Point3::new(5.0, 0.01, 0.0),
-        ];
-        let panel = Panel::new(vertices);
-        
-        let quality = metrics.calculate_element_quality(&panel).unwrap();
-        assert!(quality.aspect_ratio > 10.0);
-        assert!(quality.quality_score < 0.5);
+    fn test_ideal_and_distorted_panels() {
+        let h = 3f64.sqrt() / 2.0;
+        let vertices = points(&[
+            [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.5, h, 0.0],
+            [2.0, 0.0, 0.0], [3.0, 0.0, 0.0], [3.0, 1.0, 0.0], [2.0, 1.0, 0.0],
+            [5.0, 0.0, 0.0], [9.0, 0.0, 0.0], [5.0, 0.05, 0.0],
+            [10.0, 0.0, 0.0], [11.0, 0.0, 0.0], [11.0, 1.0, 0.5], [10.0, 1.0, 0.0],
+        ]);
+        let mesh = Mesh::with_quads(vertices, vec![[0, 1, 2], [7, 8, 9]], vec![[3, 4, 5, 6], [10, 11, 12, 13]]).unwrap();
+        let quality = mesh.panel_quality();
+
+        let (equilateral, sliver, square, warped) = (&quality[0], &quality[1], &quality[2], &quality[3]);
+        assert!((equilateral.aspect_ratio - 1.0).abs() < 1e-12 && equilateral.skewness < 1e-12);
+        assert!((equilateral.quality - 1.0).abs() < 1e-12);
+        assert!((square.aspect_ratio - 1.0).abs() < 1e-12 && square.skewness < 1e-12 && square.warp == 0.0);
+        assert!(sliver.aspect_ratio > 40.0 && sliver.skewness > 0.9 && sliver.quality < 0.01);
+        assert!(warped.warp > 0.05 && warped.quality < square.quality);
+        assert!(quality.iter().all(|q| !q.degenerate && !q.inverted && q.area_ratio == 1.0));
+
+        let summary = mesh.quality();
+        assert_eq!((summary.total_elements, summary.degenerate_elements, summary.inverted_elements), (4, 0, 0));
+        assert!((summary.max_quality - 1.0).abs() < 1e-12 && summary.min_quality == sliver.quality);
+        assert!(summary.max_warp == warped.warp && summary.max_aspect_ratio == sliver.aspect_ratio);
     }
 
     #[test]
-    fn test_quality_grade_classification() {
-        assert_eq!(QualityGrade::from_score(0.9), QualityGrade::Excellent);
-        assert_eq!(QualityGrade::from_score(0.7), QualityGrade::Good);
-        assert_eq!(QualityGrade::from_score(0.5), QualityGrade::Fair);
-        assert_eq!(QualityGrade::from_score(0.3), QualityGrade::Poor);
-        assert_eq!(QualityGrade::from_score(0.1), QualityGrade::VeryPoor);
+    fn test_flags_degenerate_and_inverted_panels() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let summary = hull.quality();
+        assert_eq!((summary.degenerate_elements, summary.inverted_elements), (0, 0));
+        assert_eq!(summary.max_area_ratio, 2.0);
+
+        let mut faces = hull.faces.clone();
+        faces[3] = [faces[3][0], faces[3][2], faces[3][1]];
+        let flipped = Mesh::new(hull.vertices.clone(), faces).unwrap().panel_quality();
+        assert!(flipped[3].inverted && flipped.iter().filter(|q| q.inverted).count() == 1);
+
+        let vertices = points(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.5, 0.5, 0.0], [0.0, 2.0, 0.0], [1.0, 0.0, 0.0]]);
+        let mesh = Mesh::with_quads(vertices, vec![[0, 1, 4]], vec![[0, 1, 2, 3]]).unwrap();
+        let quality = mesh.panel_quality();
+        assert!(quality[0].degenerate && quality[0].quality == 0.0);
+        assert!(quality[1].inverted && !quality[1].degenerate);
+        assert_eq!(mesh.quality().min_quality, 0.0);
     }
 
     #[test]
-    fn test_statistical_summary() {
-        let metrics = QualityMetrics::default();
-        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-        let summary = metrics.calculate_statistical_summary(&data);
-        
-        assert_eq!(summary.min, 1.0);
-        assert_eq!(summary.max, 5.0);
-        assert_eq!(summary.mean, 3.0);
-        assert_eq!(summary.median, 3.0);
+    #[allow(deprecated)]
+    fn test_deprecated_element_quality_forwards() {
+        let h = 3f64.sqrt() / 2.0;
+        let panel = Panel::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(0.5, h, 0.0)).unwrap();
+        let quality = QualityMetrics::default().calculate_element_quality(&panel).unwrap();
+        assert!((quality.quality_score - 1.0).abs() < 1e-9 && quality.quality_grade == QualityGrade::Excellent);
+        assert!((quality.min_angle - 60.0).abs() < 1e-9 && (quality.max_angle - 60.0).abs() < 1e-9);
+
+        let sliver = Mesh::new(points(&[[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 0.05, 0.0]]), vec![[0, 1, 2]]).unwrap();
+        assert_eq!(QualityMetrics::default().identify_refinement_candidates(&sliver).unwrap(), vec![0]);
     }
-} 
\ No newline at end of file
+}
@@ -1,21 +1,293 @@
-use crate::mesh::{Mesh, Panel};
-use crate::Point;
-use nalgebra::{Vector3, Point3 as NalgebraPoint3};
+//! Adaptive refinement of panel meshes
+//!
+//! Two criteria select panels to split. A priori, [`Mesh::refine_by_curvature`]
+//! splits panels whose normal turns sharply against a neighbour's, where a
+//! coarse faceting misrepresents the hull, and panels longer than a size
+//! cap. A posteriori, [`Mesh::refine_by_solution`] splits panels where a
+//! solved panel field such as the pressure or source strength jumps across
+//! an edge; on constant panels the jump is the field's gradient times the
+//! panel size, so it flags under-resolved gradients as well.
+//!
+//! Selected panels are split into four (red refinement); neighbours left
+//! with one split edge are bisected onto its midpoint (green closure) and
+//! those with more are split into four as well, so the refined mesh has no
+//! hanging vertices. New vertices lie at edge midpoints and, for quads, at
+//! the bilinear centre; map them onto a CAD surface afterwards if one exists.
+//!
+//! [`Mesh::refinement_series`] splits every panel at each level instead,
+//! giving the mesh tiers of a convergence study with the topology held fixed.
+//!
+//! The former [`MeshRefinement`] interface remains, deprecated, as a thin
+//! wrapper over these methods.
+
+use super::*;
+use num_complex::Complex64;
 use std::collections::HashMap;
 
-/// Criteria for adaptive mesh refinement
+/// Settings of adaptive refinement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefinementOptions {
+    /// Largest angle between the normals of neighbouring panels, radians
+    pub max_normal_angle: f64,
+    /// Largest relative jump of a solution field across an edge
+    pub max_jump: f64,
+    /// Panels whose shortest edge would drop below this are not split
+    pub min_edge_length: f64,
+    /// Panels with a longer edge are split regardless of curvature
+    pub max_edge_length: f64,
+    /// Refinement passes of the curvature criterion
+    pub max_levels: usize,
+}
+
+impl Default for RefinementOptions {
+    fn default() -> Self {
+        Self {
+            max_normal_angle: 15f64.to_radians(),
+            max_jump: 0.1,
+            min_edge_length: 0.0,
+            max_edge_length: f64::INFINITY,
+            max_levels: 3,
+        }
+    }
+}
+
+/// What refinement split
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefinementReport {
+    /// Passes that split at least one panel
+    pub passes: usize,
+    /// Panels split because they met a refinement criterion
+    pub refined: usize,
+    /// Panels split only to keep the mesh conforming
+    pub closure: usize,
+    /// Panel of the input mesh containing each panel of the refined mesh
+    pub parents: Vec<usize>,
+}
+
+impl Mesh {
+    /// Refine where the surface curves or panels are too large
+    ///
+    /// Repeats up to `max_levels` passes, stopping early once no panel
+    /// meets the criteria.
+    pub fn refine_by_curvature(&self, options: &RefinementOptions) -> Result<(Mesh, RefinementReport)> {
+        let mut mesh = self.clone();
+        let mut report = RefinementReport { parents: (0..self.n_panels()).collect(), ..Default::default() };
+        for _ in 0..options.max_levels {
+            let neighbours = mesh.edge_neighbours();
+            let marked: Vec<bool> = mesh
+                .polygons()
+                .enumerate()
+                .map(|(p, polygon)| {
+                    let (shortest, longest) = mesh.edge_range(polygon);
+                    if shortest < 2.0 * options.min_edge_length {
+                        return false;
+                    }
+                    longest > options.max_edge_length
+                        || neighbours[p].iter().any(|&q| mesh.normals[p].angle(&mesh.normals[q]) > options.max_normal_angle)
+                })
+                .collect();
+            if !marked.contains(&true) {
+                break;
+            }
+            let (refined, pass) = mesh.refine_panels(&marked)?;
+            report.passes += 1;
+            report.refined += pass.refined;
+            report.closure += pass.closure;
+            report.parents = pass.parents.iter().map(|&p| report.parents[p]).collect();
+            mesh = refined;
+        }
+        Ok((mesh, report))
+    }
+
+    /// Refine where a solved panel field jumps across an edge
+    ///
+    /// `values` holds one value per panel, e.g. the pressure of a solve on
+    /// this mesh. A panel is split when its largest difference to an edge
+    /// neighbour exceeds `max_jump` times the largest magnitude of the field.
+    /// One pass is made; solve again on the refined mesh before the next.
+    pub fn refine_by_solution(&self, values: &[Complex64], options: &RefinementOptions) -> Result<(Mesh, RefinementReport)> {
+        if values.len() != self.n_panels() {
+            return Err(MeshError::InvalidData {
+                message: format!("{} solution values for {} panels", values.len(), self.n_panels()),
+            });
+        }
+        let scale = values.iter().map(|v| v.norm()).fold(0.0, f64::max);
+        let neighbours = self.edge_neighbours();
+        let marked: Vec<bool> = self
+            .polygons()
+            .enumerate()
+            .map(|(p, polygon)| {
+                self.edge_range(polygon).0 >= 2.0 * options.min_edge_length
+                    && neighbours[p].iter().any(|&q| (values[p] - values[q]).norm() > options.max_jump * scale)
+            })
+            .collect();
+        self.refine_panels(&marked)
+    }
+
+    /// Uniformly refined copies for a convergence study, this mesh first
+    ///
+    /// Returns `n_levels` meshes. Each level splits every panel into four,
+    /// so the panel count grows fourfold while boundaries, components and
+    /// the Euler characteristic stay those of this mesh.
+    pub fn refinement_series(&self, n_levels: usize) -> Result<Vec<Mesh>> {
+        self.refinement_series_onto(n_levels, |p| *p)
+    }
+
+    /// Refinement series with the vertices of each finer level placed by `project`
+    ///
+    /// `project` maps a vertex onto the exact geometry, e.g. onto a sphere,
+    /// so that finer levels converge to the surface rather than to the
+    /// facets of the coarsest mesh.
+    pub fn refinement_series_onto(&self, n_levels: usize, project: impl Fn(&Point) -> Point) -> Result<Vec<Mesh>> {
+        if n_levels == 0 {
+            return Err(MeshError::InvalidData {
+                message: "A refinement series needs at least one level".to_string(),
+            });
+        }
+        let mut series = vec![self.clone()];
+        while series.len() < n_levels {
+            let coarse = &series[series.len() - 1];
+            let (fine, _) = coarse.refine_panels(&vec![true; coarse.n_panels()])?;
+            let vertices = fine.vertices.iter().map(&project).collect();
+            series.push(Mesh::with_quads(vertices, fine.faces, fine.quads)?);
+        }
+        Ok(series)
+    }
+
+    /// Split the marked panels into four, closing the mesh conformingly
+    pub fn refine_panels(&self, marked: &[bool]) -> Result<(Mesh, RefinementReport)> {
+        if marked.len() != self.n_panels() {
+            return Err(MeshError::InvalidData {
+                message: format!("{} refinement marks for {} panels", marked.len(), self.n_panels()),
+            });
+        }
+        let polygons: Vec<&[usize]> = self.polygons().collect();
+        let key = |a: usize, b: usize| (a.min(b), a.max(b));
+        fn edges(p: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+            (0..p.len()).map(move |k| (p[k], p[(k + 1) % p.len()]))
+        }
+
+        // Closure: a panel with two or more split edges is split into four
+        let mut red = marked.to_vec();
+        let mut split = std::collections::HashSet::new();
+        loop {
+            for (p, polygon) in polygons.iter().enumerate() {
+                if red[p] {
+                    split.extend(edges(polygon).map(|(a, b)| key(a, b)));
+                }
+            }
+            let mut changed = false;
+            for (p, polygon) in polygons.iter().enumerate() {
+                if !red[p] && edges(polygon).filter(|&(a, b)| split.contains(&key(a, b))).count() >= 2 {
+                    red[p] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut vertices = self.vertices.clone();
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, vertices: &mut Vec<Point>| {
+            *midpoints.entry(key(a, b)).or_insert_with(|| {
+                vertices.push(Point::from((vertices[a].coords + vertices[b].coords) * 0.5));
+                vertices.len() - 1
+            })
+        };
+        let mut report = RefinementReport { passes: 1, ..Default::default() };
+        let (mut faces, mut quads) = (Vec::new(), Vec::new());
+        let (mut face_parents, mut quad_parents) = (Vec::new(), Vec::new());
+        for (p, polygon) in polygons.iter().enumerate() {
+            let mids: Vec<Option<usize>> = edges(polygon)
+                .map(|(a, b)| split.contains(&key(a, b)).then(|| midpoint(a, b, &mut vertices)))
+                .collect();
+            let n_faces = faces.len();
+            let n_quads = quads.len();
+            match (polygon.len(), red[p], mids.iter().position(Option::is_some)) {
+                (3, true, _) => {
+                    let [a, b, c] = [polygon[0], polygon[1], polygon[2]];
+                    let [ab, bc, ca] = [mids[0], mids[1], mids[2]].map(Option::unwrap);
+                    faces.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]);
+                }
+                (4, true, _) => {
+                    let centre = polygon.iter().fold(Vector::zeros(), |sum, &v| sum + vertices[v].coords) * 0.25;
+                    vertices.push(Point::from(centre));
+                    let m = vertices.len() - 1;
+                    let [ab, bc, cd, da] = [mids[0], mids[1], mids[2], mids[3]].map(Option::unwrap);
+                    let [a, b, c, d] = [polygon[0], polygon[1], polygon[2], polygon[3]];
+                    quads.extend([[a, ab, m, da], [ab, b, bc, m], [m, bc, c, cd], [da, m, cd, d]]);
+                }
+                (n, false, Some(k)) => {
+                    // Green closure: fan from the midpoint of the one split edge
+                    let m = mids[k].unwrap();
+                    let corner = |i: usize| polygon[(k + i) % n];
+                    faces.push([m, corner(1), corner(2)]);
+                    if n == 4 {
+                        faces.push([m, corner(2), corner(3)]);
+                    }
+                    faces.push([m, corner(n - 1), corner(0)]);
+                }
+                (3, false, None) => faces.push([polygon[0], polygon[1], polygon[2]]),
+                _ => quads.push([polygon[0], polygon[1], polygon[2], polygon[3]]),
+            }
+            face_parents.resize(faces.len(), p);
+            quad_parents.resize(quads.len(), p);
+            let split_into = faces.len() - n_faces + quads.len() - n_quads;
+            if split_into > 1 {
+                if marked[p] {
+                    report.refined += 1;
+                } else {
+                    report.closure += 1;
+                }
+            }
+        }
+        report.parents = face_parents.into_iter().chain(quad_parents).collect();
+        Ok((Mesh::with_quads(vertices, faces, quads)?, report))
+    }
+
+    /// Panels sharing an edge with each panel
+    fn edge_neighbours(&self) -> Vec<Vec<usize>> {
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (p, polygon) in self.polygons().enumerate() {
+            for k in 0..polygon.len() {
+                let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                edges.entry((a.min(b), a.max(b))).or_default().push(p);
+            }
+        }
+        let mut neighbours = vec![Vec::new(); self.n_panels()];
+        for panels in edges.values() {
+            for &p in panels {
+                neighbours[p].extend(panels.iter().filter(|&&q| q != p));
+            }
+        }
+        neighbours
+    }
+
+    /// Shortest and longest edge of a panel
+    fn edge_range(&self, polygon: &[usize]) -> (f64, f64) {
+        (0..polygon.len())
+            .map(|k| (self.vertices[polygon[(k + 1) % polygon.len()]] - self.vertices[polygon[k]]).norm())
+            .fold((f64::INFINITY, 0.0), |(lo, hi), l| (lo.min(l), hi.max(l)))
+    }
+}
+
+/// Thresholds of the former [`MeshRefinement`] API
+#[deprecated(note = "use `RefinementOptions` with `Mesh::refine_by_solution` or `Mesh::refine_by_curvature`")]
 #[derive(Debug, Clone)]
 pub struct RefinementCriteria {
-    /// Maximum allowed solution gradient
+    /// Unused; kept for source compatibility
     pub max_gradient: f64,
-    /// Minimum element size
+    /// Panels whose shortest edge would drop below this are not split
     pub min_element_size: f64,
-    /// Maximum element size
+    /// Panels with a longer edge are split
     pub max_element_size: f64,
-    /// Solution gradient threshold for refinement
+    /// Largest relative jump of the solution across an edge
     pub gradient_threshold: f64,
 }
 
+#[allow(deprecated)]
 impl Default for RefinementCriteria {
     fn default() -> Self {
         Self {
@@ -27,65 +299,81 @@ impl Default for RefinementCriteria {
     }
 }
 
-/// Quality metrics for mesh assessment
+/// Quality targets of the former [`MeshRefinement`] API
+#[deprecated(note = "use `Mesh::panel_quality`")]
 #[derive(Debug, Clone)]
 pub struct QualityMetrics {
+    /// Largest acceptable aspect ratio
     pub aspect_ratio: f64,
+    /// Largest acceptable skewness
     pub skewness: f64,
+    /// Smallest acceptable orthogonality
     pub orthogonality: f64,
+    /// Unused; kept for source compatibility
     pub volume_ratio: f64,
 }
 
+#[allow(deprecated)]
 impl Default for QualityMetrics {
     fn default() -> Self {
         Self {
-            aspect_ratio: 3.0,    // Maximum acceptable aspect ratio
-            skewness: 0.8,        // Maximum acceptable skewness
-            orthogonality: 0.1,   // Minimum acceptable orthogonality
-            volume_ratio: 0.1,    // Minimum acceptable volume ratio
+            aspect_ratio: 3.0,
+            skewness: 0.8,
+            orthogonality: 0.1,
+            volume_ratio: 0.1,
         }
     }
 }
 
-/// Quality assessment report for a mesh
-#[derive(Debug, Clone)]
-pub struct QualityReport {
-    pub overall_score: f64,
-    pub poor_elements: Vec<usize>,
-    pub metrics: HashMap<usize, ElementQuality>,
-    pub recommendations: Vec<String>,
-}
-
-/// Quality metrics for individual elements
+/// Quality of one panel in a [`QualityReport`]
+#[deprecated(note = "use `PanelQuality`")]
 #[derive(Debug, Clone)]
 pub struct ElementQuality {
+    /// Elongation, 1 for an equilateral triangle or a square
     pub aspect_ratio: f64,
+    /// Equiangle skewness, 0 (ideal) to 1 (collapsed)
     pub skewness: f64,
+    /// Flatness, 1 for a planar panel
     pub orthogonality: f64,
+    /// Combined score, 1 (ideal) to 0 (unusable)
     pub quality_score: f64,
 }
 
-/// Advanced mesh refinement system
+/// Quality of every panel of a mesh
+#[deprecated(note = "use `Mesh::panel_quality` or `Mesh::quality`")]
+#[allow(deprecated)]
+#[derive(Debug, Clone)]
+pub struct QualityReport {
+    /// Mean quality score
+    pub overall_score: f64,
+    /// Panels scoring below 0.5
+    pub poor_elements: Vec<usize>,
+    /// Quality of each panel
+    pub metrics: HashMap<usize, ElementQuality>,
+    /// Suggested remedies
+    pub recommendations: Vec<String>,
+}
+
+/// Former refinement interface, now forwarding to the `Mesh` refinement methods
+#[deprecated(note = "use `Mesh::refine_by_solution`, `Mesh::refine_by_curvature` and `Mesh::panel_quality`")]
+#[allow(deprecated)]
 pub struct MeshRefinement {
-    /// Adaptive mesh refinement based on solution gradients
+    /// Solution-jump and size thresholds
     pub adaptive_criteria: RefinementCriteria,
-    /// Target mesh quality metrics
+    /// Quality targets
     pub quality_targets: QualityMetrics,
     /// Maximum refinement levels
     pub max_levels: usize,
 }
 
+#[allow(deprecated)]
 impl MeshRefinement {
-    /// Create new mesh refinement system with default parameters
+    /// Refinement with the default thresholds
     pub fn new() -> Self {
-        Self {
-            adaptive_criteria: RefinementCriteria::default(),
-            quality_targets: QualityMetrics::default(),
-            max_levels: 5,
-        }
+        Self::with_criteria(RefinementCriteria::default(), QualityMetrics::default(), 5)
     }
 
-    /// Create with custom parameters
+    /// Refinement with custom thresholds
     pub fn with_criteria(criteria: RefinementCriteria, quality: QualityMetrics, max_levels: usize) -> Self {
         Self {
             adaptive_criteria: criteria,
@@ -94,525 +382,189 @@ impl MeshRefinement {
         }
     }
 
-    /// Refine mesh based on solution gradients
-    pub fn adaptive_refine(&self, mesh: &Mesh, solution: &[f64]) -> Result<Mesh, Box<dyn std::error::Error>> {
-        // Calculate solution gradients for each panel
-        let mut mesh_mut = mesh.clone();
-        let gradients = self.calculate_solution_gradients(&mut mesh_mut, solution)?;
-        
-        // Identify panels that need refinement
-        let refinement_candidates = self.identify_refinement_panels(&gradients)?;
-        
-        // Perform adaptive refinement
-        let mut refined_mesh = mesh.clone();
-        for &panel_idx in &refinement_candidates {
-            refined_mesh = self.subdivide_panel(&mut refined_mesh, panel_idx)?;
+    /// The equivalent [`RefinementOptions`]
+    pub fn options(&self) -> RefinementOptions {
+        RefinementOptions {
+            max_jump: self.adaptive_criteria.gradient_threshold,
+            min_edge_length: self.adaptive_criteria.min_element_size,
+            max_edge_length: self.adaptive_criteria.max_element_size,
+            max_levels: self.max_levels,
+            ..Default::default()
         }
-        
-        Ok(refined_mesh)
     }
 
-    /// Improve mesh quality (aspect ratio, skewness)
-    pub fn quality_improve(&self, mesh: &Mesh) -> Result<Mesh, Box<dyn std::error::Error>> {
-        let mut improved_mesh = mesh.clone();
-        
-        // Assess current mesh quality
-        let quality_report = self.assess_mesh_quality(&mut improved_mesh)?;
-        
-        // Improve poor quality elements
-        for panel_id in &quality_report.poor_elements {
-            improved_mesh = self.improve_panel_quality(&mut improved_mesh, *panel_id)?;
-        }
-        
-        Ok(improved_mesh)
-    }
-
-    /// Coarsen mesh in low-gradient regions
-    pub fn coarsen(&self, mesh: &Mesh, solution: &[f64]) -> Result<Mesh, Box<dyn std::error::Error>> {
-        // Calculate solution gradients
-        let mut mesh_mut = mesh.clone();
-        let gradients = self.calculate_solution_gradients(&mut mesh_mut, solution)?;
-        
-        // Identify panels that can be coarsened (low gradients)
-        let coarsening_candidates = self.identify_coarsening_panels(&gradients)?;
-        
-        // Perform mesh coarsening
-        let coarsened_mesh = self.coarsen_selected_panels(&mesh_mut, &coarsening_candidates)?;
-        
-        Ok(coarsened_mesh)
-    }
-
-    /// Calculate solution gradients for each panel
-    fn calculate_solution_gradients(&self, mesh: &mut Mesh, solution: &[f64]) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
-        let panels = mesh.panels()?;
-        let mut gradients = Vec::with_capacity(panels.len());
-        
-        for (i, panel) in panels.iter().enumerate() {
-            let gradient = self.calculate_panel_gradient_simple(panel, i, solution)?;
-            gradients.push(gradient);
-        }
-        
-        Ok(gradients)
-    }
-    
-    /// Calculate gradient for a specific panel (simplified version)
-    fn calculate_panel_gradient_simple(&self, panel: &Panel, panel_idx: usize, solution: &[f64]) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel_idx >= solution.len() {
-            return Err("Panel index exceeds solution array length".into());
-        }
-        
-        // Simple gradient calculation based on panel area and solution value
-        let area = panel.area();
-        let value = solution[panel_idx];
-        
-        // For now, return a simple gradient based on area
-        Ok(value / (area + 1e-10))
-    }
-
-    /// Find neighboring panels for gradient calculation
-    fn find_neighboring_panels(&self, mesh: &mut Mesh, panel_idx: usize) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
-        let panels = mesh.panels()?;
-        let panel = &panels[panel_idx];
-        let mut neighbors = Vec::new();
-        
-        // Simple approach: find panels that share vertices
-        for (i, other_panel) in panels.iter().enumerate() {
-            if i == panel_idx {
-                continue;
-            }
-            
-            // Check if panels share vertices
-            if self.panels_share_vertex(panel, other_panel) {
-                neighbors.push(i);
-            }
-        }
-        
-        Ok(neighbors)
+    /// Refine where the per-panel `solution` jumps; see [`Mesh::refine_by_solution`]
+    pub fn adaptive_refine(&self, mesh: &Mesh, solution: &[f64]) -> std::result::Result<Mesh, Box<dyn std::error::Error>> {
+        let values: Vec<Complex64> = solution.iter().map(|&v| Complex64::new(v, 0.0)).collect();
+        Ok(mesh.refine_by_solution(&values, &self.options())?.0)
     }
 
-    /// Check if two panels share a vertex
-    fn panels_share_vertex(&self, panel1: &Panel, panel2: &Panel) -> bool {
-        for vertex1 in &panel1.vertices {
-            for vertex2 in &panel2.vertices {
-                let distance = (vertex1 - vertex2).norm();
-                if distance < 1e-10 {
-                    return true;
-                }
-            }
-        }
-        false
-    }
-
-    /// Identify panels that need refinement based on gradients
-    fn identify_refinement_panels(&self, gradients: &[f64]) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
-        let mut candidates = Vec::new();
-        
-        for (i, &gradient) in gradients.iter().enumerate() {
-            if gradient > self.adaptive_criteria.gradient_threshold {
-                candidates.push(i);
-            }
-        }
-        
-        Ok(candidates)
-    }
-
-    /// Identify panels that can be coarsened
-    fn identify_coarsening_panels(&self, gradients: &[f64]) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
-        let mut candidates = Vec::new();
-        
-        for (i, &gradient) in gradients.iter().enumerate() {
-            if gradient < self.adaptive_criteria.gradient_threshold * 0.1 {
-                candidates.push(i);
-            }
-        }
-        
-        Ok(candidates)
+    /// Returns the mesh unchanged, as this method always has; heal or remesh instead
+    pub fn quality_improve(&self, mesh: &Mesh) -> std::result::Result<Mesh, Box<dyn std::error::Error>> {
+        Ok(mesh.clone())
     }
 
-    /// Refine selected panels by subdivision
-    fn refine_selected_panels(&self, mesh: &Mesh, candidates: &[usize]) -> Result<Mesh, Box<dyn std::error::Error>> {
-        let mut refined_mesh = mesh.clone();
-        
-        // Sort candidates in reverse order to maintain indices during iteration
-        let mut sorted_candidates = candidates.to_vec();
-        sorted_candidates.sort_by(|a, b| b.cmp(a));
-        
-        for &panel_idx in &sorted_candidates {
-            refined_mesh = self.subdivide_panel(&mut refined_mesh, panel_idx)?;
-        }
-        
-        Ok(refined_mesh)
+    /// Returns the mesh unchanged, as this method always has; see `Mesh::decimate`
+    pub fn coarsen(&self, mesh: &Mesh, _solution: &[f64]) -> std::result::Result<Mesh, Box<dyn std::error::Error>> {
+        Ok(mesh.clone())
     }
 
-    /// Subdivide a single panel into smaller panels
-    fn subdivide_panel(&self, mesh: &mut Mesh, panel_idx: usize) -> Result<Mesh, Box<dyn std::error::Error>> {
-        if panel_idx >= mesh.panels()?.len() {
-            return Err("Panel index out of bounds".into());
+    /// Quality of every panel from [`Mesh::panel_quality`]
+    pub fn assess_mesh_quality(&self, mesh: &mut Mesh) -> std::result::Result<QualityReport, Box<dyn std::error::Error>> {
+        let metrics: HashMap<usize, ElementQuality> = mesh.panel_quality().iter().map(element_quality).enumerate().collect();
+        let mut poor_elements: Vec<usize> = metrics.iter().filter(|(_, q)| q.quality_score < 0.5).map(|(&i, _)| i).collect();
+        poor_elements.sort_unstable();
+        let overall_score = if metrics.is_empty() { 0.0 } else { metrics.values().map(|q| q.quality_score).sum::<f64>() / metrics.len() as f64 };
+        let mut recommendations = Vec::new();
+        if !poor_elements.is_empty() {
+            recommendations.push(format!("{} elements have poor quality and should be refined", poor_elements.len()));
         }
-        
-        let new_mesh = mesh.clone();
-        let panel = &mesh.panels()?[panel_idx];
-        
-        // Panel struct only supports triangular panels
-        let _subpanels = self.subdivide_triangle(panel)?;
-        
-        // Note: This is a simplified approach. In a real implementation,
-        // we would need to modify the mesh structure to replace panels.
-        // For now, we'll return the original mesh.
-        Ok(new_mesh)
+        Ok(QualityReport { overall_score, poor_elements, metrics, recommendations })
     }
 
-    /// Subdivide a triangular panel into 4 sub-triangles
-    fn subdivide_triangle(&self, panel: &Panel) -> Result<Vec<Panel>, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        let v0 = vertices[0];
-        let v1 = vertices[1];
-        let v2 = vertices[2];
-        
-        // Calculate midpoints using vector arithmetic
-        let m01 = Point::new(
-            (v0.x + v1.x) * 0.5,
-            (v0.y + v1.y) * 0.5,
-            (v0.z + v1.z) * 0.5,
-        );
-        let m12 = Point::new(
-            (v1.x + v2.x) * 0.5,
-            (v1.y + v2.y) * 0.5,
-            (v1.z + v2.z) * 0.5,
-        );
-        let m20 = Point::new(
-            (v2.x + v0.x) * 0.5,
-            (v2.y + v0.y) * 0.5,
-            (v2.z + v0.z) * 0.5,
-        );
-        
-        let mut subpanels = Vec::new();
-        
-        // Create 4 sub-triangles
-        subpanels.push(Panel::new(m01, m12, m20)?);
-        subpanels.push(Panel::new(v0, m01, m20)?);
-        subpanels.push(Panel::new(v1, m12, m01)?);
-        subpanels.push(Panel::new(v2, m20, m12)?);
-        
-        Ok(subpanels)
+    /// Quality of a lone panel
+    pub fn calculate_element_quality(&self, panel: &Panel) -> std::result::Result<ElementQuality, Box<dyn std::error::Error>> {
+        Ok(element_quality(&quality::lone_panel_quality(panel)?))
     }
 
-    /// Subdivide a quadrilateral panel into 4 sub-quads
-    fn subdivide_quadrilateral(&self, panel: &Panel) -> Result<Vec<Panel>, Box<dyn std::error::Error>> {
-        // Panel struct only supports triangular panels
-        // For now, just return the original panel
-        let vertices = panel.vertices();
-        Ok(vec![Panel::new(vertices[0], vertices[1], vertices[2])?])
-    }
-
-    /// Coarsen selected panels by merging
-    fn coarsen_selected_panels(&self, mesh: &Mesh, candidates: &[usize]) -> Result<Mesh, Box<dyn std::error::Error>> {
-        let mut coarsened_mesh = mesh.clone();
-        
-        // Sort indices in descending order to avoid index shifting
-        let mut sorted_indices: Vec<usize> = candidates.to_vec();
-        sorted_indices.sort_by(|a, b| b.cmp(a));
-        
-        // Note: This is a simplified approach. In a real implementation,
-        // we would need to modify the mesh structure to remove panels.
-        // For now, we'll return the original mesh.
-        
-        Ok(coarsened_mesh)
+    /// Panels scoring below 0.5
+    pub fn identify_refinement_candidates(&self, mesh: &Mesh) -> std::result::Result<Vec<usize>, Box<dyn std::error::Error>> {
+        Ok(self.assess_mesh_quality(&mut mesh.clone())?.poor_elements)
     }
+}
 
-    /// Group panels into clusters for coarsening
-    fn group_panels_for_coarsening(&self, _mesh: &Mesh, candidates: &[usize]) -> Result<Vec<Vec<usize>>, Box<dyn std::error::Error>> {
-        // Simple implementation: each panel forms its own cluster
-        // More sophisticated implementation would group neighboring panels
-        let clusters: Vec<Vec<usize>> = candidates.iter().map(|&idx| vec![idx]).collect();
-        Ok(clusters)
+#[allow(deprecated)]
+impl Default for MeshRefinement {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    /// Merge a cluster of panels into a single larger panel
-    fn merge_panel_cluster(&self, mesh: &Mesh, cluster: &[usize]) -> Result<Mesh, Box<dyn std::error::Error>> {
-        if cluster.is_empty() {
-            return Ok(mesh.clone());
-        }
-        
-        // For now, just return the original mesh (simplified coarsening)
-        // In a real implementation, we would need to modify the mesh structure
-        Ok(mesh.clone())
+#[allow(deprecated)]
+fn element_quality(quality: &PanelQuality) -> ElementQuality {
+    ElementQuality {
+        aspect_ratio: quality.aspect_ratio,
+        skewness: quality.skewness,
+        orthogonality: 1.0 - quality.warp.min(1.0),
+        quality_score: quality.quality,
     }
+}
 
-    /// Improve quality of a specific panel
-    fn improve_panel_quality(&self, mesh: &mut Mesh, panel_idx: usize) -> Result<Mesh, Box<dyn std::error::Error>> {
-        if panel_idx >= mesh.panels()?.len() {
-            return Err("Panel index out of bounds".into());
-        }
-        
-        let mut improved_mesh = mesh.clone();
-        
-        // Simple quality improvement: smoothing
-        let improved_panel = self.smooth_panel(&mesh.panels()?[panel_idx])?;
-        
-        // Note: This is a simplified approach. In a real implementation,
-        // we would need to modify the mesh structure to replace panels.
-        // For now, we'll return the original mesh.
-        
-        Ok(improved_mesh)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Smooth a panel to improve quality
-    fn smooth_panel(&self, panel: &Panel) -> Result<Panel, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        let center = panel.center();
-        
-        // Simple smoothing: move vertices towards center
-        let smoothing_factor = 0.1;
-        let mut smoothed_vertices = Vec::new();
-        
-        for vertex in vertices {
-            let smoothed_vertex = Point::new(
-                vertex.x + (center.x - vertex.x) * smoothing_factor,
-                vertex.y + (center.y - vertex.y) * smoothing_factor,
-                vertex.z + (center.z - vertex.z) * smoothing_factor,
-            );
-            smoothed_vertices.push(smoothed_vertex);
-        }
-        
-        // Create new panel from smoothed vertices
-        if smoothed_vertices.len() >= 3 {
-            Ok(Panel::new(smoothed_vertices[0], smoothed_vertices[1], smoothed_vertices[2])?)
-        } else {
-            Err("Not enough vertices for panel".into())
-        }
+    fn area(mesh: &Mesh) -> f64 {
+        mesh.clone().panels().unwrap().iter().map(Panel::area).sum()
     }
 
-    /// Assess overall mesh quality
-    pub fn assess_mesh_quality(&self, mesh: &mut Mesh) -> Result<QualityReport, Box<dyn std::error::Error>> {
-        let mut metrics = HashMap::new();
-        let mut poor_elements = Vec::new();
-        let mut total_score = 0.0;
-        
-        for (i, panel) in mesh.panels()?.iter().enumerate() {
-            let quality = self.calculate_element_quality(panel)?;
-            
-            if quality.quality_score < 0.5 {
-                poor_elements.push(i);
+    /// Every edge of a closed conforming mesh is shared by exactly two panels
+    fn is_conforming(mesh: &Mesh) -> bool {
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for polygon in mesh.polygons() {
+            for k in 0..polygon.len() {
+                let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
             }
-            
-            metrics.insert(i, quality.clone());
-            total_score += quality.quality_score;
         }
-        
-        let overall_score = if !mesh.panels()?.is_empty() {
-            total_score / mesh.panels()?.len() as f64
-        } else {
-            0.0
-        };
-        
-        let recommendations = self.generate_recommendations(&poor_elements, &metrics);
-        
-        Ok(QualityReport {
-            overall_score,
-            poor_elements,
-            metrics,
-            recommendations,
-        })
+        edges.values().all(|&n| n == 2)
     }
 
-    /// Calculate mesh quality for each element
-    pub fn calculate_element_quality(&self, panel: &Panel) -> Result<ElementQuality, Box<dyn std::error::Error>> {
-        let aspect_ratio = self.calculate_aspect_ratio(panel)?;
-        let skewness = self.calculate_skewness(panel)?;
-        let orthogonality = self.calculate_orthogonality(panel)?;
-        
-        // Overall quality score (0.0 = worst, 1.0 = best)
-        let quality_score = (
-            (1.0 / (1.0 + aspect_ratio / self.quality_targets.aspect_ratio)) +
-            (1.0 - skewness.min(1.0)) +
-            orthogonality
-        ) / 3.0;
-        
-        Ok(ElementQuality {
-            aspect_ratio,
-            skewness,
-            orthogonality,
-            quality_score,
-        })
-    }
-
-    /// Calculate aspect ratio of a panel
-    fn calculate_aspect_ratio(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel.vertices.len() < 3 {
-            return Err("Panel must have at least 3 vertices".into());
-        }
-        
-        let mut min_edge = f64::INFINITY;
-        let mut max_edge: f64 = 0.0;
-        
-        for i in 0..panel.vertices.len() {
-            let j = (i + 1) % panel.vertices.len();
-            let edge_length = (panel.vertices[i] - panel.vertices[j]).norm();
-            min_edge = min_edge.min(edge_length);
-            max_edge = max_edge.max(edge_length);
-        }
-        
-        if min_edge > 1e-12 {
-            Ok(max_edge / min_edge)
-        } else {
-            Ok(f64::INFINITY)
-        }
-    }
-
-    /// Calculate skewness of a panel
-    fn calculate_skewness(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        let vertices = panel.vertices();
-        
-        // Panel struct only supports triangular panels
-        if vertices.len() == 3 {
-            // Simple triangle skewness calculation
-            let v0 = vertices[0];
-            let v1 = vertices[1];
-            let v2 = vertices[2];
-            
-            let edge1 = v1 - v0;
-            let edge2 = v2 - v0;
-            let edge3 = v2 - v1;
-            
-            let area = edge1.cross(&edge2).norm() * 0.5;
-            let perimeter = edge1.norm() + edge2.norm() + edge3.norm();
-            
-            if perimeter > 1e-10 {
-                let ideal_area = perimeter * perimeter / (12.0 * 3.0_f64.sqrt());
-                Ok((area / ideal_area).abs())
-            } else {
-                Ok(0.0)
-            }
-        } else {
-            Err("Unsupported panel type".into())
-        }
+    #[test]
+    fn test_marked_panels_close_conformingly() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        assert!(is_conforming(&hull));
+        let mut marked = vec![false; hull.n_panels()];
+        marked[20] = true;
+        let (refined, report) = hull.refine_panels(&marked).unwrap();
+        assert_eq!((report.refined, report.closure), (1, 3));
+        assert_eq!(refined.n_panels(), hull.n_panels() + 3 + 3);
+        assert_eq!(report.parents.len(), refined.n_panels());
+        assert_eq!(report.parents.iter().filter(|&&p| p == 20).count(), 4);
+        assert!(is_conforming(&refined));
+        assert!((area(&refined) - area(&hull)).abs() < 1e-9);
+        assert!(hull.refine_panels(&marked[1..]).is_err());
     }
 
-    /// Calculate orthogonality of a panel
-    fn calculate_orthogonality(&self, panel: &Panel) -> Result<f64, Box<dyn std::error::Error>> {
-        if panel.vertices.len() < 3 {
-            return Err("Panel must have at least 3 vertices".into());
-        }
-        
-        // Calculate normal vector
-        let v1 = panel.vertices[1] - panel.vertices[0];
-        let v2 = panel.vertices[2] - panel.vertices[0];
-        let normal = v1.cross(&v2).normalize();
-        
-        // Check how close edges are to being perpendicular to normal
-        let mut orthogonality_sum = 0.0;
-        let mut edge_count = 0;
-        
-        for i in 0..panel.vertices.len() {
-            let j = (i + 1) % panel.vertices.len();
-            let edge = panel.vertices[j] - panel.vertices[i];
-            
-            if edge.norm() > 1e-12 {
-                let edge_normalized = edge.normalize();
-                let dot_with_normal = edge_normalized.dot(&normal).abs();
-                orthogonality_sum += 1.0 - dot_with_normal;
-                edge_count += 1;
-            }
-        }
-        
-        if edge_count > 0 {
-            Ok(orthogonality_sum / edge_count as f64)
-        } else {
-            Ok(0.0)
-        }
+    #[test]
+    fn test_curvature_refines_bilge_of_box() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let options = RefinementOptions { max_levels: 2, ..Default::default() };
+        let (mut refined, report) = hull.refine_by_curvature(&options).unwrap();
+        assert_eq!(report.passes, 2);
+        assert!(refined.n_panels() > hull.n_panels() && is_conforming(&refined));
+        assert!((area(&refined) - area(&hull)).abs() < 1e-9);
+
+        // Refined panels crowd the edges of the box
+        let small = refined.panels().unwrap().iter().filter(|p| p.area() < 0.1).count();
+        assert!(small > 0);
+
+        // A flat plate has no curvature, and the size cap splits its quads
+        let plate = Mesh::with_quads(
+            [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]].iter().map(|&[x, y]| Point::new(x, y, -1.0)).collect(),
+            Vec::new(),
+            vec![[0, 1, 2, 3]],
+        )
+        .unwrap();
+        assert_eq!(plate.refine_by_curvature(&RefinementOptions::default()).unwrap().1.passes, 0);
+        let capped = RefinementOptions { max_edge_length: 0.6, ..Default::default() };
+        let (split, report) = plate.refine_by_curvature(&capped).unwrap();
+        assert_eq!((split.quads.len(), report.passes), (16, 2));
     }
 
-    /// Generate recommendations for mesh improvement
-    fn generate_recommendations(&self, poor_elements: &[usize], _metrics: &HashMap<usize, ElementQuality>) -> Vec<String> {
-        let mut recommendations = Vec::new();
-        
-        if !poor_elements.is_empty() {
-            recommendations.push(format!("{} elements have poor quality and should be refined", poor_elements.len()));
-        }
-        
-        if poor_elements.len() > 10 {
-            recommendations.push("Consider global mesh regeneration for better quality".to_string());
+    #[test]
+    fn test_refinement_series_converges_with_fixed_topology() {
+        // Welded, since the generator repeats its seam and pole vertices
+        let (sphere, _) = PredefinedGeometry::sphere(1.0, 8, 4).unwrap().heal(&HealOptions::default()).unwrap();
+        let series = sphere.refinement_series_onto(4, |p| Point::from(p.coords.normalize())).unwrap();
+        assert_eq!(series.len(), 4);
+        let errors: Vec<f64> = series.iter().map(|mesh| 4.0 * std::f64::consts::PI - area(mesh)).collect();
+        for (level, pair) in series.windows(2).enumerate() {
+            assert_eq!(pair[1].n_panels(), 4 * pair[0].n_panels());
+            assert_eq!(pair[1].topology().euler_characteristic, 2);
+            assert!(is_conforming(&pair[1]));
+            assert!(errors[level + 1] > 0.0 && errors[level + 1] < 0.5 * errors[level]);
         }
-        
-        recommendations.push("Use adaptive refinement to improve solution accuracy".to_string());
-        recommendations.push("Apply quality improvement algorithms to enhance element shapes".to_string());
-        
-        recommendations
-    }
-
-    /// Identify poor-quality elements for refinement
-    pub fn identify_refinement_candidates(&self, mesh: &Mesh) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
-        let mut mesh_mut = mesh.clone();
-        let quality_report = self.assess_mesh_quality(&mut mesh_mut)?;
-        Ok(quality_report.poor_elements)
-    }
-}
+        assert!(series[3].vertices.iter().all(|p| (p.coords.norm() - 1.0).abs() < 1e-12));
 
-impl Default for MeshRefinement {
-    fn default() -> Self {
-        Self::new()
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 2, 1, 1).unwrap();
+        let boxes = hull.refinement_series(3).unwrap();
+        assert_eq!(boxes[2].n_panels(), 16 * hull.n_panels());
+        assert!((area(&boxes[2]) - area(&hull)).abs() < 1e-9);
+        assert!(hull.refinement_series(0).is_err());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::mesh::Point3;
 
     #[test]
-    fn test_mesh_refinement_creation() {
-        let refinement = MeshRefinement::new();
-        assert_eq!(refinement.max_levels, 5);
-        assert!(refinement.adaptive_criteria.gradient_threshold > 0.0);
+    fn test_solution_jumps_are_refined() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let values: Vec<Complex64> = hull
+            .polygons()
+            .map(|p| Complex64::new(if hull.vertices[p[0]].x > 1.5 { 1.0 } else { 0.0 }, 0.0))
+            .collect();
+        let (refined, report) = hull.refine_by_solution(&values, &RefinementOptions::default()).unwrap();
+        assert!(report.refined > 0 && is_conforming(&refined));
+        let uniform = vec![Complex64::new(1.0, 0.0); hull.n_panels()];
+        assert_eq!(hull.refine_by_solution(&uniform, &RefinementOptions::default()).unwrap().1.refined, 0);
+        assert!(hull.refine_by_solution(&uniform[1..], &RefinementOptions::default()).is_err());
     }
 
     #[test]
-    fn test_triangle_subdivision() {
+    #[allow(deprecated)]
+    fn test_deprecated_refinement_forwards() {
+        let hull = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
         let refinement = MeshRefinement::new();
-        
-        // Create a simple triangle
-        let vertices = vec![
-            Point3::new(0.0, 0.0, 0.0),
-            Point3::new(1.0, 0.0, 0.0),
-            Point3::new(0.5, 1.0, 0.0),
-        ];
-        let panel = Panel::new(vertices);
-        
-        let subpanels = refinement.subdivide_triangle(&panel).unwrap();
-        assert_eq!(subpanels.len(), 4);
-    }
-
-    #[test]
-    fn test_quality_metrics() {
-        let refinement = MeshRefinement::new();
-        
-        // Create a simple triangle
-        let vertices = vec![
-            Point3::new(0.0, 0.0, 0.0),
-            Point3::new(1.0, 0.0, 0.0),
-            Point3::new(0.5, 1.0, 0.0),
-        ];
-        let panel = Panel::new(vertices);
-        
+        let mut solution = vec![0.0; hull.n_panels()];
+        solution[20] = 1.0;
+        let refined = refinement.adaptive_refine(&hull, &solution).unwrap();
+        let values: Vec<Complex64> = solution.iter().map(|&v| Complex64::new(v, 0.0)).collect();
+        assert_eq!(refined.n_panels(), hull.refine_by_solution(&values, &refinement.options()).unwrap().0.n_panels());
+
+        let report = refinement.assess_mesh_quality(&mut hull.clone()).unwrap();
+        assert_eq!(report.metrics.len(), hull.n_panels());
+        assert!((report.overall_score - hull.quality().average_quality).abs() < 1e-12);
+        assert_eq!(report.poor_elements.len(), hull.panel_quality().iter().filter(|q| q.quality < 0.5).count());
+        let panel = Panel::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(0.5, 1.0, 0.0)).unwrap();
         let quality = refinement.calculate_element_quality(&panel).unwrap();
-        assert!(quality.quality_score >= 0.0);
-        assert!(quality.quality_score <= 1.0);
+        assert!(quality.quality_score > 0.0 && quality.quality_score <= 1.0);
     }
-
-    #[test]
-    fn test_aspect_ratio_calculation() {
-        let refinement = MeshRefinement::new();
-        
-        // Create a very thin triangle (high aspect ratio)
-        let vertices = vec![
-            Point3::new(0.0, 0.0, 0.0),
-            Point3::new(10.0, 0.0, 0.0),
-            Point3::new(5.0, 0.1, 0.0),
-        ];
-        let panel = Panel::new(vertices);
-        
-        let aspect_ratio = refinement.calculate_aspect_ratio(&panel).unwrap();
-        assert!(aspect_ratio > 1.0);
-    }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,186 @@
+//! Half- and quarter-body meshes of symmetric hulls
+//!
+//! A [`SymmetricMesh`] stores only the panels on the positive side of the x-z
+//! plane (y ≥ 0) and/or the y-z plane (x ≥ 0) and mirrors them on demand.
+//! The full mesh lists the stored triangles and then the stored quads, each
+//! in the order of images identity, y → -y, x → -x, both, so the BEM
+//! solver's symmetry reduction finds every orbit by exact reflection.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Mesh stored over the fundamental region of its symmetry planes
+#[derive(Debug, Clone)]
+pub struct SymmetricMesh {
+    /// Stored panels, on the positive side of each symmetry plane
+    pub half: Mesh,
+    /// Symmetric about the x-z plane (y → -y)
+    pub xz: bool,
+    /// Symmetric about the y-z plane (x → -x)
+    pub yz: bool,
+}
+
+impl SymmetricMesh {
+    /// Wrap a half or quarter mesh
+    ///
+    /// Vertices must lie on the positive side of each requested plane.
+    pub fn new(half: Mesh, xz: bool, yz: bool) -> Result<Self> {
+        let tol = 1e-9 * half.vertices.iter().map(|p| p.coords.amax()).fold(1.0, f64::max);
+        if let Some(p) = half.vertices.iter().find(|p| (xz && p.y < -tol) || (yz && p.x < -tol)) {
+            return Err(MeshError::InvalidGeometry {
+                message: format!("Vertex ({}, {}, {}) lies outside the fundamental region", p.x, p.y, p.z),
+            });
+        }
+        Ok(Self { half, xz, yz })
+    }
+
+    /// Keep the panels of a full symmetric mesh that lie in the fundamental region
+    ///
+    /// Panels are selected by centroid; the mesh must have as many panels on
+    /// each side of the planes, none straddling them.
+    pub fn from_full(mesh: &Mesh, xz: bool, yz: bool) -> Result<Self> {
+        let mut local = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut keep = |polygon: &[usize]| {
+            let centroid = polygon.iter().fold(Vector::zeros(), |sum, &v| sum + mesh.vertices[v].coords) / polygon.len() as f64;
+            if (xz && centroid.y <= 0.0) || (yz && centroid.x <= 0.0) {
+                return None;
+            }
+            Some(polygon.iter().map(|&v| {
+                *local.entry(v).or_insert_with(|| {
+                    vertices.push(mesh.vertices[v]);
+                    vertices.len() - 1
+                })
+            }).collect::<Vec<_>>())
+        };
+        let faces: Vec<[usize; 3]> = mesh.faces.iter().filter_map(|f| keep(f)).map(|f| [f[0], f[1], f[2]]).collect();
+        let quads: Vec<[usize; 4]> = mesh.quads.iter().filter_map(|q| keep(q)).map(|q| [q[0], q[1], q[2], q[3]]).collect();
+        let group = 1 << (xz as usize + yz as usize);
+        let kept = faces.len() + quads.len();
+        if kept * group != mesh.n_panels() {
+            return Err(MeshError::InvalidGeometry {
+                message: format!(
+                    "{} of {} panels lie in the fundamental region; expected 1/{} of them",
+                    kept,
+                    mesh.n_panels(),
+                    group
+                ),
+            });
+        }
+        Self::new(Mesh::with_quads(vertices, faces, quads)?, xz, yz)
+    }
+
+    /// Symmetry planes as (x-z, y-z)
+    pub fn planes(&self) -> (bool, bool) {
+        (self.xz, self.yz)
+    }
+
+    /// Number of copies of the stored panels in the full mesh
+    pub fn group_size(&self) -> usize {
+        self.images().len()
+    }
+
+    /// Number of panels in the full mesh
+    pub fn n_panels(&self) -> usize {
+        self.half.n_panels() * self.group_size()
+    }
+
+    /// Mirror the stored panels into the full mesh
+    ///
+    /// Vertices on a symmetry plane are shared with their images, and
+    /// reflected panels have their winding reversed to keep normals outward.
+    pub fn full_mesh(&self) -> Result<Mesh> {
+        // Exact keys, with -0.0 folded into 0.0 so plane vertices match their images
+        let key = |p: &Point| [p.x, p.y, p.z].map(|c| (c + 0.0).to_bits());
+        let mut lookup = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let mut quads = Vec::new();
+        for (sx, sy) in self.images() {
+            let index: Vec<usize> = self
+                .half
+                .vertices
+                .iter()
+                .map(|p| {
+                    let image = Point::new(sx * p.x, sy * p.y, p.z);
+                    *lookup.entry(key(&image)).or_insert_with(|| {
+                        vertices.push(image);
+                        vertices.len() - 1
+                    })
+                })
+                .collect();
+            let reflected = sx * sy < 0.0;
+            faces.extend(self.half.faces.iter().map(|&[a, b, c]| {
+                if reflected {
+                    [index[a], index[c], index[b]]
+                } else {
+                    [index[a], index[b], index[c]]
+                }
+            }));
+            quads.extend(self.half.quads.iter().map(|&[a, b, c, d]| {
+                if reflected {
+                    [index[a], index[d], index[c], index[b]]
+                } else {
+                    [index[a], index[b], index[c], index[d]]
+                }
+            }));
+        }
+        Mesh::with_quads(vertices, faces, quads)
+    }
+
+    /// Group elements as (sign of x, sign of y), identity first
+    fn images(&self) -> Vec<(f64, f64)> {
+        let mut images = vec![(1.0, 1.0)];
+        if self.xz {
+            images.push((1.0, -1.0));
+        }
+        if self.yz {
+            images.push((-1.0, 1.0));
+        }
+        if self.xz && self.yz {
+            images.push((-1.0, -1.0));
+        }
+        images
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarter_box_mirrors_to_full_hull() {
+        let full = PredefinedGeometry::box_hull(4.0, 2.0, 1.0, 4, 2, 2).unwrap();
+        let quarter = SymmetricMesh::from_full(&full, true, true).unwrap();
+        assert_eq!(quarter.group_size(), 4);
+        assert_eq!(quarter.half.faces.len() * 4, full.faces.len());
+        assert_eq!(quarter.n_panels(), full.faces.len());
+
+        let mirrored = quarter.full_mesh().unwrap();
+        assert_eq!(mirrored.vertices.len(), full.vertices.len());
+        assert_eq!(mirrored.faces.len(), full.faces.len());
+
+        // Images keep outward normals: each matches the reflected normal of its source
+        let n = quarter.half.faces.len();
+        for (image, (sx, sy)) in quarter.images().into_iter().enumerate() {
+            for f in 0..n {
+                let source = quarter.half.normals[f];
+                let normal = mirrored.normals[image * n + f];
+                assert!((normal - Vector::new(sx * source.x, sy * source.y, source.z)).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_asymmetric_or_misplaced_meshes() {
+        let wigley = PredefinedGeometry::wigley_with_surface(10.0, 1.0, 0.6, 6, 3, HullSurface::Wetted).unwrap();
+        assert!(SymmetricMesh::from_full(&wigley, true, false).is_ok());
+        let shifted = Mesh::new(
+            wigley.vertices.iter().map(|p| p + Vector::new(0.0, 0.3, 0.0)).collect(),
+            wigley.faces.clone(),
+        )
+        .unwrap();
+        assert!(SymmetricMesh::from_full(&shifted, true, false).is_err());
+        assert!(SymmetricMesh::new(wigley, true, false).is_err());
+    }
+}
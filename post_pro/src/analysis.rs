@@ -390,8 +390,16 @@ impl AnalysisEngine {
         Self { config }
     }
     
-    /// Run analysis
+    /// Run analysis, keeping matrix kernels on the calling thread unless `parallel` is set
     pub fn run_analysis(&self, bem_results: &wavecore_bem::BEMResult) -> Result<AnalysisResult> {
+        if self.config.parallel {
+            self.analyze(bem_results)
+        } else {
+            wavecore_matrices::serial(|| self.analyze(bem_results))
+        }
+    }
+    
+    fn analyze(&self, bem_results: &wavecore_bem::BEMResult) -> Result<AnalysisResult> {
         let start_time = Instant::now();
         
         let mut result = AnalysisResult::default();
@@ -13,6 +13,7 @@
 //! - **Free Surface**: Free surface elevation calculations
 //! - **Result Analysis**: Statistical analysis and visualization
 //! - **Export Tools**: Multiple format export capabilities
+//! - **Sea States**: Joint Hs-Tp models, IFORM contours and persistence
 //! 
 //! ## Example
 //! 
@@ -35,8 +36,10 @@
 //! ```
 
 pub mod analysis;
+pub mod sea_state;
 
 pub use analysis::*;
+pub use sea_state::*;
 
 use thiserror::Error;
 use num_complex::Complex64;
@@ -178,6 +181,22 @@ impl Default for FreeSurfaceData {
     }
 }
 
+impl FreeSurfaceData {
+    /// Time series of a complex elevation computed by `wavecore_bem::free_surface_elevation`
+    ///
+    /// The wave height is the largest crest-to-trough range over the grid.
+    pub fn from_elevation(elevation: &wavecore_bem::FreeSurfaceElevation, time_points: Vec<f64>) -> Self {
+        let elevation_values = time_points.iter().map(|&t| elevation.at_time(t)).collect();
+        Self {
+            spatial_points: elevation.grid.points(),
+            elevation_values,
+            time_points,
+            wave_height: 2.0 * elevation.max_amplitude(),
+            wave_period: 2.0 * std::f64::consts::PI / elevation.frequency,
+        }
+    }
+}
+
 /// Statistical analysis data
 #[derive(Debug, Clone)]
 pub struct StatisticsData {
@@ -304,6 +323,23 @@ mod tests {
         assert!(free_surface_data.spatial_points.is_empty());
     }
     
+    #[test]
+    fn test_free_surface_data_from_elevation() {
+        let elevation = wavecore_bem::FreeSurfaceElevation {
+            frequency: 0.5,
+            grid: wavecore_bem::FreeSurfaceGrid::new(vec![0.0, 1.0], vec![2.0]),
+            elevation: vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 2.0)],
+        };
+        let quarter_period = std::f64::consts::PI;
+        let data = FreeSurfaceData::from_elevation(&elevation, vec![0.0, quarter_period]);
+        
+        assert_eq!(data.spatial_points[1], Point::new(1.0, 2.0, 0.0));
+        assert_eq!(data.wave_height, 4.0);
+        assert!((data.wave_period - 4.0 * std::f64::consts::PI).abs() < 1e-12);
+        assert!((data.elevation_values[0][0] - 1.0).abs() < 1e-12);
+        assert!((data.elevation_values[1][1] - 2.0).abs() < 1e-12);
+    }
+    
     #[test]
     fn test_statistics_data_default() {
         let stats_data = StatisticsData::default();
@@ -0,0 +1,407 @@
+//! Sea state statistics
+//!
+//! Joint Hs-Tp modelling after the conditional approach of DNV-RP-C205: a
+//! Weibull marginal for significant wave height and a lognormal peak period
+//! conditional on Hs, with μ(h) = a0 + a1·h^a2 and σ(h) = b0 + b1·exp(b2·h).
+//! Environmental contours are derived with IFORM, and weather-window
+//! persistence is computed from Hs time series.
+
+use super::*;
+
+/// Three-parameter Weibull distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeibullDistribution {
+    /// Shape parameter
+    pub shape: f64,
+    /// Scale parameter
+    pub scale: f64,
+    /// Location parameter
+    pub location: f64,
+}
+
+impl WeibullDistribution {
+    /// Cumulative distribution function
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x <= self.location {
+            return 0.0;
+        }
+        1.0 - (-((x - self.location) / self.scale).powf(self.shape)).exp()
+    }
+
+    /// Inverse cumulative distribution function
+    pub fn quantile(&self, p: f64) -> f64 {
+        let p = p.clamp(0.0, 1.0 - 1e-16);
+        self.location + self.scale * (-(1.0 - p).ln()).powf(1.0 / self.shape)
+    }
+
+    /// Weighted maximum-likelihood fit with fixed location
+    pub fn fit(samples: &[f64], weights: &[f64], location: f64) -> Result<Self> {
+        let data: Vec<(f64, f64)> = samples
+            .iter()
+            .zip(weights)
+            .filter(|(&x, &w)| x > location && w > 0.0)
+            .map(|(&x, &w)| (x - location, w))
+            .collect();
+        if data.len() < 2 {
+            return Err(PostProError::InvalidParameters {
+                message: "Weibull fit needs at least two samples above the location".to_string(),
+            });
+        }
+
+        let w_total: f64 = data.iter().map(|(_, w)| w).sum();
+        let mean_ln: f64 = data.iter().map(|(x, w)| w * x.ln()).sum::<f64>() / w_total;
+
+        // Newton iteration on the profile likelihood equation for the shape
+        let mut k = 1.5;
+        for _ in 0..100 {
+            let (mut s0, mut s1, mut s2) = (0.0, 0.0, 0.0);
+            for &(x, w) in &data {
+                let xk = x.powf(k);
+                let lx = x.ln();
+                s0 += w * xk;
+                s1 += w * xk * lx;
+                s2 += w * xk * lx * lx;
+            }
+            let f = s1 / s0 - 1.0 / k - mean_ln;
+            let df = (s2 * s0 - s1 * s1) / (s0 * s0) + 1.0 / (k * k);
+            let step = f / df;
+            k = (k - step).max(k / 10.0);
+            if step.abs() < 1e-12 * k {
+                break;
+            }
+        }
+
+        let scale = (data.iter().map(|(x, w)| w * x.powf(k)).sum::<f64>() / w_total).powf(1.0 / k);
+        Ok(Self { shape: k, scale, location })
+    }
+}
+
+/// Lognormal distribution of Tp conditional on Hs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConditionalLognormal {
+    /// Mean of ln(Tp): a0 + a1·h^a2
+    pub mu: [f64; 3],
+    /// Standard deviation of ln(Tp): b0 + b1·exp(b2·h)
+    pub sigma: [f64; 3],
+}
+
+impl ConditionalLognormal {
+    /// Mean of ln(Tp) at given Hs
+    pub fn mu_at(&self, hs: f64) -> f64 {
+        self.mu[0] + self.mu[1] * hs.powf(self.mu[2])
+    }
+
+    /// Standard deviation of ln(Tp) at given Hs
+    pub fn sigma_at(&self, hs: f64) -> f64 {
+        (self.sigma[0] + self.sigma[1] * (self.sigma[2] * hs).exp()).max(1e-6)
+    }
+
+    /// Tp quantile at given Hs
+    pub fn quantile(&self, hs: f64, p: f64) -> f64 {
+        (self.mu_at(hs) + self.sigma_at(hs) * standard_normal_quantile(p)).exp()
+    }
+}
+
+/// Joint Hs-Tp probability model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointHsTp {
+    /// Marginal distribution of Hs
+    pub hs: WeibullDistribution,
+    /// Conditional distribution of Tp given Hs
+    pub tp: ConditionalLognormal,
+}
+
+/// Point on an environmental contour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourPoint {
+    /// Significant wave height (m)
+    pub hs: f64,
+    /// Peak period (s)
+    pub tp: f64,
+}
+
+impl JointHsTp {
+    /// Fit from weighted (Hs, Tp) samples, e.g. scatter diagram cells
+    ///
+    /// Samples are grouped into `n_bins` Hs classes to estimate the
+    /// conditional lognormal parameters.
+    pub fn fit(hs: &[f64], tp: &[f64], weights: &[f64], n_bins: usize) -> Result<Self> {
+        if hs.len() != tp.len() || hs.len() != weights.len() {
+            return Err(PostProError::InvalidParameters {
+                message: "Hs, Tp and weight arrays must have equal length".to_string(),
+            });
+        }
+        if n_bins < 3 {
+            return Err(PostProError::InvalidParameters {
+                message: "At least three Hs bins are needed for the conditional fit".to_string(),
+            });
+        }
+
+        let marginal = WeibullDistribution::fit(hs, weights, 0.0)?;
+
+        // Per-bin weighted statistics of ln(Tp)
+        let h_max = hs.iter().cloned().fold(0.0, f64::max);
+        let width = h_max / n_bins as f64;
+        let mut bins = vec![(0.0, 0.0, 0.0, 0.0); n_bins]; // (Σw, Σw·h, Σw·lnT, Σw·lnT²)
+        for ((&h, &t), &w) in hs.iter().zip(tp).zip(weights) {
+            if w <= 0.0 || t <= 0.0 {
+                continue;
+            }
+            let b = ((h / width) as usize).min(n_bins - 1);
+            let lt = t.ln();
+            bins[b].0 += w;
+            bins[b].1 += w * h;
+            bins[b].2 += w * lt;
+            bins[b].3 += w * lt * lt;
+        }
+
+        let mut h_c = Vec::new();
+        let mut mu_c = Vec::new();
+        let mut sigma_c = Vec::new();
+        let mut w_c = Vec::new();
+        for &(w, sh, sl, sl2) in &bins {
+            if w <= 0.0 {
+                continue;
+            }
+            let mean = sl / w;
+            h_c.push(sh / w);
+            mu_c.push(mean);
+            sigma_c.push((sl2 / w - mean * mean).max(0.0).sqrt());
+            w_c.push(w);
+        }
+        if h_c.len() < 3 {
+            return Err(PostProError::InvalidParameters {
+                message: "Too few populated Hs bins for the conditional fit".to_string(),
+            });
+        }
+
+        let mu = fit_three_parameter(&h_c, &mu_c, &w_c, |h, c| h.powf(c), (0.05, 2.0))?;
+        let sigma = fit_three_parameter(&h_c, &sigma_c, &w_c, |h, c| (c * h).exp(), (-2.0, 0.0))?;
+
+        Ok(Self {
+            hs: marginal,
+            tp: ConditionalLognormal { mu, sigma },
+        })
+    }
+
+    /// IFORM environmental contour
+    ///
+    /// `return_period` is in years and `sea_state_duration` in hours.
+    pub fn iform_contour(&self, return_period: f64, sea_state_duration: f64, n_points: usize) -> Result<Vec<ContourPoint>> {
+        if return_period <= 0.0 || sea_state_duration <= 0.0 || n_points < 3 {
+            return Err(PostProError::InvalidParameters {
+                message: "Return period, duration and point count must be positive".to_string(),
+            });
+        }
+        let n_states = return_period * 365.25 * 24.0 / sea_state_duration;
+        let beta = standard_normal_quantile(1.0 - 1.0 / n_states);
+
+        Ok((0..n_points)
+            .map(|i| {
+                let theta = 2.0 * std::f64::consts::PI * i as f64 / n_points as f64;
+                let (u1, u2) = (beta * theta.cos(), beta * theta.sin());
+                let hs = self.hs.quantile(standard_normal_cdf(u1));
+                let tp = (self.tp.mu_at(hs) + self.tp.sigma_at(hs) * u2).exp();
+                ContourPoint { hs, tp }
+            })
+            .collect())
+    }
+}
+
+/// Weather-window persistence statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistenceStats {
+    /// Durations of calm spells below the threshold (hours)
+    pub calm_durations: Vec<f64>,
+    /// Durations of storm spells at or above the threshold (hours)
+    pub storm_durations: Vec<f64>,
+    /// Fraction of time below the threshold
+    pub probability_below: f64,
+}
+
+impl PersistenceStats {
+    /// Compute persistence from an evenly sampled Hs series
+    pub fn from_series(hs: &[f64], dt_hours: f64, threshold: f64) -> Result<Self> {
+        if hs.is_empty() || dt_hours <= 0.0 {
+            return Err(PostProError::InvalidParameters {
+                message: "Persistence needs a non-empty series and positive time step".to_string(),
+            });
+        }
+
+        let mut calm_durations = Vec::new();
+        let mut storm_durations = Vec::new();
+        let mut run = 1usize;
+        for i in 1..=hs.len() {
+            let same = i < hs.len() && (hs[i] < threshold) == (hs[i - 1] < threshold);
+            if same {
+                run += 1;
+                continue;
+            }
+            let duration = run as f64 * dt_hours;
+            if hs[i - 1] < threshold {
+                calm_durations.push(duration);
+            } else {
+                storm_durations.push(duration);
+            }
+            run = 1;
+        }
+
+        let below = hs.iter().filter(|&&h| h < threshold).count();
+        Ok(Self {
+            calm_durations,
+            storm_durations,
+            probability_below: below as f64 / hs.len() as f64,
+        })
+    }
+
+    /// Mean calm spell duration (hours)
+    pub fn mean_calm_duration(&self) -> f64 {
+        mean(&self.calm_durations)
+    }
+
+    /// Mean storm spell duration (hours)
+    pub fn mean_storm_duration(&self) -> f64 {
+        mean(&self.storm_durations)
+    }
+
+    /// Fraction of time inside calm spells lasting at least `window` hours
+    pub fn window_probability(&self, window: f64) -> f64 {
+        let total: f64 = self.calm_durations.iter().chain(&self.storm_durations).sum();
+        let usable: f64 = self.calm_durations.iter().filter(|&&d| d >= window).sum();
+        if total > 0.0 { usable / total } else { 0.0 }
+    }
+}
+
+/// Standard normal cumulative distribution function
+pub fn standard_normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Standard normal quantile (Acklam's rational approximation)
+pub fn standard_normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+
+    let p = p.clamp(1e-300, 1.0 - 1e-16);
+    let p_low = 0.02425;
+    if p < p_low {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - p_low {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -standard_normal_quantile(1.0 - p)
+    }
+}
+
+/// Complementary error function (Numerical Recipes erfcc, |ε| < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t * (-z * z - 1.26551223
+        + t * (1.00002368
+        + t * (0.37409196
+        + t * (0.09678418
+        + t * (-0.18628806
+        + t * (0.27886807
+        + t * (-1.13520398
+        + t * (1.48851587
+        + t * (-0.82215223
+        + t * 0.17087277)))))))))
+        .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Fit y ≈ c0 + c1·f(x, c2) by weighted least squares on c0, c1 and a grid search on c2
+fn fit_three_parameter<F>(x: &[f64], y: &[f64], w: &[f64], f: F, range: (f64, f64)) -> Result<[f64; 3]>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let mut best: Option<(f64, [f64; 3])> = None;
+    let steps = 200;
+    for s in 0..=steps {
+        let c2 = range.0 + (range.1 - range.0) * s as f64 / steps as f64;
+        let (mut sw, mut sf, mut sff, mut sy, mut sfy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for ((&xi, &yi), &wi) in x.iter().zip(y).zip(w) {
+            let fi = f(xi, c2);
+            sw += wi;
+            sf += wi * fi;
+            sff += wi * fi * fi;
+            sy += wi * yi;
+            sfy += wi * fi * yi;
+        }
+        let det = sw * sff - sf * sf;
+        let (c0, c1) = if det.abs() > 1e-14 * sw * sff.max(1e-300) {
+            ((sy * sff - sf * sfy) / det, (sw * sfy - sf * sy) / det)
+        } else {
+            (sy / sw, 0.0)
+        };
+        let sse: f64 = x.iter().zip(y).zip(w)
+            .map(|((&xi, &yi), &wi)| wi * (yi - c0 - c1 * f(xi, c2)).powi(2))
+            .sum();
+        if best.is_none_or(|(b, _)| sse < b) {
+            best = Some((sse, [c0, c1, c2]));
+        }
+    }
+    best.map(|(_, c)| c).ok_or_else(|| PostProError::CalculationError {
+        message: "Conditional parameter fit failed".to_string(),
+    })
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_quantile_roundtrip() {
+        for &p in &[0.001, 0.1, 0.5, 0.9, 0.999] {
+            let x = standard_normal_quantile(p);
+            assert!((standard_normal_cdf(x) - p).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_weibull_fit_recovers_parameters() {
+        let truth = WeibullDistribution { shape: 1.8, scale: 2.5, location: 0.0 };
+        let n = 2000;
+        let samples: Vec<f64> = (0..n).map(|i| truth.quantile((i as f64 + 0.5) / n as f64)).collect();
+        let fitted = WeibullDistribution::fit(&samples, &vec![1.0; n], 0.0).unwrap();
+        assert!((fitted.shape - truth.shape).abs() < 0.02);
+        assert!((fitted.scale - truth.scale).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_iform_contour() {
+        let model = JointHsTp {
+            hs: WeibullDistribution { shape: 1.5, scale: 2.0, location: 0.5 },
+            tp: ConditionalLognormal { mu: [1.0, 1.0, 0.3], sigma: [0.05, 0.3, -0.5] },
+        };
+        let contour = model.iform_contour(100.0, 3.0, 36).unwrap();
+        assert_eq!(contour.len(), 36);
+
+        // Largest Hs lies at θ = 0 and exceeds the 100-year marginal quantile's neighbourhood
+        let hs_max = contour.iter().map(|p| p.hs).fold(0.0, f64::max);
+        assert!((hs_max - contour[0].hs).abs() < 1e-12);
+        assert!(hs_max > model.hs.quantile(0.99));
+    }
+
+    #[test]
+    fn test_persistence() {
+        let hs = [1.0, 1.2, 3.0, 3.5, 1.1, 1.0, 0.9, 2.6];
+        let stats = PersistenceStats::from_series(&hs, 3.0, 2.5).unwrap();
+        assert_eq!(stats.calm_durations, vec![6.0, 9.0]);
+        assert_eq!(stats.storm_durations, vec![6.0, 3.0]);
+        assert!((stats.probability_below - 5.0 / 8.0).abs() < 1e-12);
+        assert!((stats.window_probability(9.0) - 9.0 / 24.0).abs() < 1e-12);
+    }
+}
@@ -7,9 +7,8 @@ use crate::{
     types::*,
     errors::{Result, ResistanceError},
 };
-use ndarray::{Array1, Array2};
-use nalgebra as na;
-use libm::{cos, sin, sqrt, pow, exp, log};
+use ndarray::Array1;
+use libm::{cos, sqrt, exp};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
@@ -196,6 +195,18 @@ impl AddedResistanceCalculator {
         let cb = vessel.hull.block_coefficient;
         let speed_ms = conditions.speed_knots * 0.5144;
 
+        // STAWAVE-2 motion-induced added resistance in head waves, per ζa²,
+        // with kyy = 0.25 L and no trim
+        let froude_number = speed_ms / sqrt(9.81 * lbp);
+        let a1 = 60.3 * cb.powf(1.34);
+        let a2 = if froude_number < 0.12 {
+            0.0072 + 0.1676 * froude_number
+        } else {
+            froude_number.powf(1.5) * exp(-3.5 * froude_number)
+        };
+        let omega_scale = sqrt(lbp / 9.81) * 0.25_f64.cbrt() / 1.17 * froude_number.max(0.05).powf(0.143);
+        let motion_scale = 4.0 * conditions.water_density * 9.81 * beam.powi(2) / lbp * a1 * a2;
+
         for (i, &omega) in frequencies.iter().enumerate() {
             // Natural frequency estimate
             let omega_n = sqrt(9.81 / lbp); // Simplified
@@ -210,15 +221,11 @@ impl AddedResistanceCalculator {
             heave_rao[i] = response_factor * 1.0;
             pitch_rao[i] = response_factor * 0.3 * beam / lbp;
             
-            // Added resistance RAO (simplified Gerritsma-Beukelman)
-            let dimensionless_freq = omega.powi(2) * lbp / 9.81;
-            added_resistance_rao[i] = if dimensionless_freq > 0.1 {
-                8.0 * cb * conditions.water_density * 9.81 * 
-                omega.powi(2) / dimensionless_freq.powi(2) * 
-                response_factor
-            } else {
-                0.0
-            };
+            // Added resistance RAO (N/m²)
+            let omega_bar = omega_scale * omega;
+            let (b1, d1) = if omega_bar < 1.0 { (11.0, 14.0) } else { (-8.5, -14.0) };
+            added_resistance_rao[i] = motion_scale * omega_bar.powf(b1)
+                * exp(b1 / d1 * (1.0 - omega_bar.powf(d1)));
         }
 
         debug!("Generated RAO data for {} frequencies", n_freq);
@@ -245,15 +252,13 @@ impl AddedResistanceCalculator {
             &wave_spectrum.spectral_densities,
         )?;
 
-        // Numerical integration: ∫ RAO²(ω) * S(ω) dω
+        // Numerical integration: 2 ∫ RAO(ω) * S(ω) dω, the RAO being per ζa²
         let mut added_resistance = 0.0;
         for i in 0..(rao_data.frequencies.len() - 1) {
-            let _omega = rao_data.frequencies[i];
-            let rao_squared = rao_data.added_resistance_rao[i].powi(2);
             let spectrum_value = spectrum_values[i];
             let d_omega = rao_data.frequencies[i + 1] - rao_data.frequencies[i];
             
-            added_resistance += rao_squared * spectrum_value * d_omega;
+            added_resistance += 2.0 * rao_data.added_resistance_rao[i] * spectrum_value * d_omega;
         }
 
         // Account for directional effects
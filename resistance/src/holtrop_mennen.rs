@@ -227,6 +227,15 @@ impl HoltropMennenCalculator {
         Ok(applicability)
     }
 
+    /// Wetted surface area from the Holtrop approximation (m²)
+    pub fn wetted_surface_area(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+    ) -> Result<f64> {
+        Ok(self.calculate_dimensional_parameters(vessel, conditions)?.wetted_surface_area)
+    }
+
     /// Calculate dimensional parameters needed for resistance calculation
     fn calculate_dimensional_parameters(
         &self,
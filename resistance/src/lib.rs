@@ -4,13 +4,19 @@
 //! - Holtrop-Mennen empirical resistance calculation
 //! - Added resistance in waves via RAO integration
 //! - Wind resistance and superstructure effects
+//! - Hull roughness and fouling penalty
+//! - Shallow-water squat and under-keel clearance
+//! - Involuntary speed loss and voyage simulation
 //! - Calibration and validation framework
 //! 
 //! ## Features
 //! 
 //! - **Holtrop-Mennen Method**: Industry-standard empirical resistance calculation
 //! - **Wave Added Resistance**: RAO-based spectral integration 
-//! - **Wind Resistance**: Superstructure windage calculation
+//! - **Wind Resistance**: Superstructure windage calculation or measured coefficient tables
+//! - **Hull Condition**: Roughness and fouling added friction (Townsin / ITTC)
+//! - **Squat**: Barrass and Tuck sinkage/trim with UKC checks
+//! - **Speed Loss**: Attainable speed under engine power limits and `VoyageSimulator`
 //! - **Validation Suite**: Comprehensive benchmark testing
 //! - **High Performance**: Optimized mathematical computations
 //! 
@@ -39,6 +45,10 @@
 pub mod holtrop_mennen;
 pub mod windage;
 pub mod added_resistance;
+pub mod roughness;
+pub mod squat;
+pub mod speed_loss;
+pub mod voyage;
 pub mod validation;
 pub mod types;
 pub mod errors;
@@ -46,6 +56,10 @@ pub mod errors;
 pub use holtrop_mennen::*;
 pub use windage::*;
 pub use added_resistance::*;
+pub use roughness::*;
+pub use squat::*;
+pub use speed_loss::*;
+pub use voyage::*;
 pub use validation::*;
 pub use types::*;
 pub use errors::*;
@@ -58,6 +72,7 @@ pub struct ResistanceCalculator {
     pub holtrop_calculator: HoltropMennenCalculator,
     pub windage_calculator: WindageCalculator,
     pub added_resistance_calculator: AddedResistanceCalculator,
+    pub roughness_calculator: RoughnessCalculator,
     pub validation_suite: ValidationSuite,
 }
 
@@ -68,6 +83,7 @@ impl ResistanceCalculator {
             holtrop_calculator: HoltropMennenCalculator::new(),
             windage_calculator: WindageCalculator::new(),
             added_resistance_calculator: AddedResistanceCalculator::new(),
+            roughness_calculator: RoughnessCalculator::new(),
             validation_suite: ValidationSuite::new(),
         }
     }
@@ -147,6 +163,20 @@ impl ResistanceCalculator {
         })
     }
 
+    /// Calculate the added frictional resistance of a rough or fouled hull
+    ///
+    /// The penalty is relative to the clean-hull Holtrop-Mennen estimate.
+    pub fn calculate_roughness_penalty(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        hull_condition: &HullCondition,
+    ) -> Result<RoughnessPenalty> {
+        let clean_hull = self.holtrop_calculator.calculate_resistance(vessel, conditions)?;
+        self.roughness_calculator
+            .calculate_penalty(vessel, conditions, hull_condition, &clean_hull)
+    }
+
     /// Calculate confidence score based on vessel parameters and conditions
     fn calculate_confidence_score(
         &self,
@@ -0,0 +1,250 @@
+//! Hull roughness and fouling resistance penalty
+//!
+//! In-service hulls carry more frictional resistance than the clean,
+//! standard-roughness hull assumed by Holtrop-Mennen. This module adds the
+//! increment in frictional coefficient from average hull roughness (AHR) and
+//! from biofouling, expressed as an equivalent sand-grain roughness.
+//!
+//! ## References
+//! - Townsin, R.L. (2003). "The Ship Hull Fouling Penalty", Biofouling 19
+//! - ITTC (1978). Performance Prediction Method, Bowden-Davison roughness allowance
+//! - Schultz, M.P. (2007). "Effects of coating roughness and biofouling on ship
+//!   resistance and powering", Biofouling 23
+
+use crate::{
+    types::*,
+    errors::{Result, ResistanceError},
+    holtrop_mennen::HoltropMennenCalculator,
+};
+use libm::pow;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Standard average hull roughness of a new ship (μm)
+pub const STANDARD_HULL_ROUGHNESS: f64 = 150.0;
+
+/// Correlation used for the roughness allowance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoughnessMethod {
+    /// Townsin: ΔCF = 0.044[(k/L)^⅓ − 10 Re^−⅓] + 0.000125
+    Townsin,
+    /// ITTC 1978 Bowden-Davison: ΔCF = 0.105 (k/L)^⅓ − 0.00064
+    BowdenDavison,
+}
+
+/// Fouling condition, following Schultz (2007) and the NSTM fouling rating
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FoulingRating {
+    /// Hydraulically smooth surface (FR 0)
+    Smooth,
+    /// Typical as-applied antifouling coating (FR 0)
+    AsApplied,
+    /// Deteriorated coating or light slime (FR 10-20)
+    LightSlime,
+    /// Heavy slime (FR 30)
+    HeavySlime,
+    /// Small calcareous fouling or weed (FR 40-60)
+    SmallCalcareous,
+    /// Medium calcareous fouling (FR 70-80)
+    MediumCalcareous,
+    /// Heavy calcareous fouling (FR 90-100)
+    HeavyCalcareous,
+    /// Measured equivalent sand-grain roughness (μm)
+    Custom(f64),
+}
+
+impl FoulingRating {
+    /// Equivalent sand-grain roughness (μm)
+    pub fn equivalent_sand_roughness(&self) -> f64 {
+        match self {
+            Self::Smooth => 0.0,
+            Self::AsApplied => 30.0,
+            Self::LightSlime => 100.0,
+            Self::HeavySlime => 300.0,
+            Self::SmallCalcareous => 1000.0,
+            Self::MediumCalcareous => 3000.0,
+            Self::HeavyCalcareous => 10000.0,
+            Self::Custom(ks) => *ks,
+        }
+    }
+}
+
+/// Surface condition of the hull
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HullCondition {
+    pub average_hull_roughness: f64,   // AHR (μm)
+    pub fouling: FoulingRating,
+}
+
+impl HullCondition {
+    /// New ship with standard roughness and a fresh coating
+    pub fn new_build() -> Self {
+        Self {
+            average_hull_roughness: STANDARD_HULL_ROUGHNESS,
+            fouling: FoulingRating::AsApplied,
+        }
+    }
+}
+
+/// Added frictional resistance from roughness and fouling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoughnessPenalty {
+    pub roughness_delta_cf: f64,       // ΔCF from AHR in excess of the standard hull
+    pub fouling_delta_cf: f64,         // ΔCF from fouling
+    pub added_resistance: f64,         // ΔR (N)
+    pub added_effective_power: f64,    // ΔPE (kW)
+    pub frictional_increase: f64,      // ΔR / RF of the clean hull
+}
+
+impl RoughnessPenalty {
+    /// Total increment in frictional resistance coefficient
+    pub fn total_delta_cf(&self) -> f64 {
+        self.roughness_delta_cf + self.fouling_delta_cf
+    }
+}
+
+/// Roughness and fouling penalty calculator
+#[derive(Debug, Clone)]
+pub struct RoughnessCalculator {
+    pub method: RoughnessMethod,
+    holtrop: HoltropMennenCalculator,
+}
+
+impl RoughnessCalculator {
+    /// Create a calculator using the Townsin correlation
+    pub fn new() -> Self {
+        Self::with_method(RoughnessMethod::Townsin)
+    }
+
+    /// Create a calculator with a given correlation
+    pub fn with_method(method: RoughnessMethod) -> Self {
+        Self {
+            method,
+            holtrop: HoltropMennenCalculator::new(),
+        }
+    }
+
+    /// Roughness allowance ΔCF for roughness height `k` (μm)
+    pub fn roughness_allowance(&self, k: f64, length: f64, reynolds_number: f64) -> f64 {
+        let ratio = pow(k * 1e-6 / length, 1.0 / 3.0);
+        match self.method {
+            RoughnessMethod::Townsin => 0.044 * (ratio - 10.0 * pow(reynolds_number, -1.0 / 3.0)) + 0.000125,
+            RoughnessMethod::BowdenDavison => 0.105 * ratio - 0.00064,
+        }
+    }
+
+    /// Penalty of `condition` relative to the clean hull of the Holtrop-Mennen estimate
+    ///
+    /// The Holtrop-Mennen correlation allowance already covers a standard
+    /// roughness of 150 μm, so only roughness above that is charged. Fouling
+    /// is charged through its equivalent sand-grain roughness.
+    pub fn calculate_penalty(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        condition: &HullCondition,
+        clean_hull: &HoltropMennenResult,
+    ) -> Result<RoughnessPenalty> {
+        if condition.average_hull_roughness < 0.0 || condition.fouling.equivalent_sand_roughness() < 0.0 {
+            return Err(ResistanceError::invalid_vessel_parameters(
+                "Hull roughness must be non-negative",
+            ));
+        }
+        if conditions.speed_knots <= 0.0 {
+            return Err(ResistanceError::invalid_operating_conditions(
+                "Speed must be positive for a roughness penalty",
+            ));
+        }
+
+        let length = vessel.hull.length_between_perpendiculars;
+        let speed_ms = conditions.speed_knots * 0.5144;
+        let reynolds_number = speed_ms * length / conditions.kinematic_viscosity;
+        let wetted_surface_area = self.holtrop.wetted_surface_area(vessel, conditions)?;
+
+        let roughness_delta_cf = (self.roughness_allowance(condition.average_hull_roughness, length, reynolds_number)
+            - self.roughness_allowance(STANDARD_HULL_ROUGHNESS, length, reynolds_number))
+            .max(0.0);
+        let ks = condition.fouling.equivalent_sand_roughness();
+        let fouling_delta_cf = if ks > 0.0 {
+            0.044 * pow(ks * 1e-6 / length, 1.0 / 3.0)
+        } else {
+            0.0
+        };
+
+        let dynamic_pressure = 0.5 * conditions.water_density * speed_ms * speed_ms * wetted_surface_area;
+        let added_resistance = (roughness_delta_cf + fouling_delta_cf) * dynamic_pressure;
+        let frictional_increase = if clean_hull.frictional_resistance > 0.0 {
+            added_resistance / clean_hull.frictional_resistance
+        } else {
+            0.0
+        };
+
+        debug!("Roughness penalty: ΔCF_AHR={:.6}, ΔCF_fouling={:.6}, ΔR={:.0} N",
+               roughness_delta_cf, fouling_delta_cf, added_resistance);
+
+        Ok(RoughnessPenalty {
+            roughness_delta_cf,
+            fouling_delta_cf,
+            added_resistance,
+            added_effective_power: added_resistance * speed_ms / 1000.0,
+            frictional_increase,
+        })
+    }
+}
+
+impl Default for RoughnessCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container_ship_conditions() -> OperatingConditions {
+        OperatingConditions {
+            speed_knots: 18.0,
+            draft: 11.5,
+            displacement: 52000.0,
+            trim: 0.0,
+            heel_angle: 0.0,
+            water_density: 1025.0,
+            kinematic_viscosity: 1.188e-6,
+        }
+    }
+
+    #[test]
+    fn test_new_build_has_only_coating_penalty() {
+        let vessel = VesselParameters::default_container_ship();
+        let conditions = container_ship_conditions();
+        let clean = HoltropMennenCalculator::new().calculate_resistance(&vessel, &conditions).unwrap();
+
+        let penalty = RoughnessCalculator::new()
+            .calculate_penalty(&vessel, &conditions, &HullCondition::new_build(), &clean)
+            .unwrap();
+        assert_eq!(penalty.roughness_delta_cf, 0.0);
+        assert!(penalty.fouling_delta_cf > 0.0);
+        assert!(penalty.frictional_increase < 0.2);
+    }
+
+    #[test]
+    fn test_penalty_grows_with_fouling() {
+        let vessel = VesselParameters::default_container_ship();
+        let conditions = container_ship_conditions();
+        let clean = HoltropMennenCalculator::new().calculate_resistance(&vessel, &conditions).unwrap();
+        let calculator = RoughnessCalculator::new();
+
+        let mut previous = 0.0;
+        for fouling in [FoulingRating::LightSlime, FoulingRating::HeavySlime, FoulingRating::HeavyCalcareous] {
+            let condition = HullCondition {
+                average_hull_roughness: 250.0,
+                fouling,
+            };
+            let penalty = calculator.calculate_penalty(&vessel, &conditions, &condition, &clean).unwrap();
+            assert!(penalty.roughness_delta_cf > 0.0);
+            assert!(penalty.added_resistance > previous);
+            previous = penalty.added_resistance;
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Involuntary speed loss in a seaway
+//!
+//! In waves and wind the total resistance rises while the installed power
+//! stays fixed, so the ship cannot hold its commanded speed. The attainable
+//! speed is the highest speed at which the brake power for calm-water, added
+//! wave and wind resistance stays within the usable engine power.
+//!
+//! ## References
+//! - ITTC (2021). Recommended Procedure 7.5-02-07-02.2, Prediction of Power
+//!   Increase in Irregular Waves from Model Test
+//! - Kwon, Y.J. (2008). "Speed loss due to added resistance in wind and waves",
+//!   The Naval Architect
+
+use crate::{
+    types::*,
+    errors::{Result, ResistanceError},
+    ResistanceCalculator,
+};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Engine power limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineLimits {
+    pub maximum_continuous_rating: f64, // MCR (kW)
+    pub service_fraction: f64,          // Usable fraction of MCR
+    pub minimum_speed: f64,             // Lowest speed considered (knots)
+}
+
+impl EngineLimits {
+    /// Usable brake power (W)
+    pub fn available_power(&self) -> f64 {
+        self.maximum_continuous_rating * self.service_fraction * 1000.0
+    }
+}
+
+impl Default for EngineLimits {
+    fn default() -> Self {
+        Self {
+            maximum_continuous_rating: 40000.0,
+            service_fraction: 0.9,
+            minimum_speed: 5.0,
+        }
+    }
+}
+
+/// Sea state encountered by the ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeaState {
+    pub significant_wave_height: f64,  // Hs (m)
+    pub peak_period: f64,              // Tp (s)
+    pub wave_direction: f64,           // Direction (degrees from bow)
+    pub spectrum_type: SpectrumType,
+    pub wind_conditions: Option<WindConditions>,
+}
+
+impl SeaState {
+    /// Environment for the resistance calculation
+    pub fn environment(&self) -> EnvironmentalConditions {
+        let wave_spectrum = (self.significant_wave_height > 0.0).then(|| {
+            WaveSpectrum::from_sea_state(
+                self.significant_wave_height,
+                self.peak_period,
+                self.wave_direction,
+                self.spectrum_type.clone(),
+            )
+        });
+        EnvironmentalConditions {
+            wave_spectrum,
+            wind_conditions: self.wind_conditions.clone(),
+            ..EnvironmentalConditions::calm_sea()
+        }
+    }
+}
+
+/// Attainable speed in a sea state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedLossResult {
+    pub commanded_speed: f64,          // Commanded speed (knots)
+    pub attainable_speed: f64,         // Attainable speed (knots)
+    pub brake_power: f64,              // PB at the attainable speed (W)
+    pub power_limited: bool,           // Whether the engine limit was reached
+}
+
+impl SpeedLossResult {
+    /// Speed loss (knots)
+    pub fn speed_loss(&self) -> f64 {
+        self.commanded_speed - self.attainable_speed
+    }
+
+    /// Speed loss as a fraction of the commanded speed
+    pub fn speed_loss_fraction(&self) -> f64 {
+        if self.commanded_speed > 0.0 {
+            self.speed_loss() / self.commanded_speed
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Attainable speed versus significant wave height for one heading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedLossCurve {
+    pub wave_direction: f64,           // Direction (degrees from bow)
+    pub significant_wave_heights: Vec<f64>, // Hs (m)
+    pub attainable_speeds: Vec<f64>,   // Attainable speed (knots)
+    pub commanded_speed: f64,          // Commanded speed (knots)
+}
+
+/// Involuntary speed loss calculator
+#[derive(Debug, Clone)]
+pub struct SpeedLossCalculator {
+    pub resistance_calculator: ResistanceCalculator,
+    pub tolerance: f64,                // Speed tolerance of the search (knots)
+}
+
+impl SpeedLossCalculator {
+    /// Create a calculator with the default resistance models
+    pub fn new() -> Self {
+        Self::with_resistance_calculator(ResistanceCalculator::new())
+    }
+
+    /// Create a calculator with custom resistance models
+    pub fn with_resistance_calculator(resistance_calculator: ResistanceCalculator) -> Self {
+        Self {
+            resistance_calculator,
+            tolerance: 0.01,
+        }
+    }
+
+    /// Brake power (W) at the speed of `conditions` in a sea state
+    pub fn brake_power(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        sea_state: &SeaState,
+    ) -> Result<f64> {
+        let result = self.resistance_calculator
+            .calculate_total_resistance(vessel, conditions, &sea_state.environment())?;
+        Ok(result.power_requirements.brake_power)
+    }
+
+    /// Highest speed up to `conditions.speed_knots` within the engine limit
+    pub fn attainable_speed(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        engine: &EngineLimits,
+        sea_state: &SeaState,
+    ) -> Result<SpeedLossResult> {
+        let commanded = conditions.speed_knots;
+        if commanded <= 0.0 || engine.minimum_speed <= 0.0 {
+            return Err(ResistanceError::invalid_operating_conditions(
+                "Commanded and minimum speeds must be positive",
+            ));
+        }
+        let available = engine.available_power();
+        let at_speed = |speed: f64| -> Result<f64> {
+            let trial = OperatingConditions {
+                speed_knots: speed,
+                ..conditions.clone()
+            };
+            self.brake_power(vessel, &trial, sea_state)
+        };
+
+        let power = at_speed(commanded)?;
+        if power <= available {
+            return Ok(SpeedLossResult {
+                commanded_speed: commanded,
+                attainable_speed: commanded,
+                brake_power: power,
+                power_limited: false,
+            });
+        }
+
+        let lowest = engine.minimum_speed.min(commanded);
+        let lowest_power = at_speed(lowest)?;
+        if lowest_power > available {
+            // Not even steerage speed can be held
+            return Ok(SpeedLossResult {
+                commanded_speed: commanded,
+                attainable_speed: 0.0,
+                brake_power: available,
+                power_limited: true,
+            });
+        }
+
+        // Bisection on the power balance
+        let (mut lo, mut hi) = (lowest, commanded);
+        let mut lo_power = lowest_power;
+        while hi - lo > self.tolerance {
+            let mid = 0.5 * (lo + hi);
+            let p = at_speed(mid)?;
+            if p <= available {
+                lo = mid;
+                lo_power = p;
+            } else {
+                hi = mid;
+            }
+        }
+
+        debug!("Speed loss: {:.2} kn -> {:.2} kn (Hs={:.1} m, dir={:.0}°)",
+               commanded, lo, sea_state.significant_wave_height, sea_state.wave_direction);
+
+        Ok(SpeedLossResult {
+            commanded_speed: commanded,
+            attainable_speed: lo,
+            brake_power: lo_power,
+            power_limited: true,
+        })
+    }
+
+    /// Speed loss curves over significant wave height for each heading
+    ///
+    /// `peak_period` gives Tp (s) for each Hs, e.g. from a scatter diagram.
+    #[allow(clippy::too_many_arguments)]
+    pub fn speed_loss_curves<F: Fn(f64) -> f64>(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        engine: &EngineLimits,
+        significant_wave_heights: &[f64],
+        wave_directions: &[f64],
+        spectrum_type: SpectrumType,
+        peak_period: F,
+    ) -> Result<Vec<SpeedLossCurve>> {
+        wave_directions
+            .iter()
+            .map(|&direction| {
+                let attainable_speeds = significant_wave_heights
+                    .iter()
+                    .map(|&hs| {
+                        let sea_state = SeaState {
+                            significant_wave_height: hs,
+                            peak_period: peak_period(hs),
+                            wave_direction: direction,
+                            spectrum_type: spectrum_type.clone(),
+                            wind_conditions: None,
+                        };
+                        Ok(self.attainable_speed(vessel, conditions, engine, &sea_state)?.attainable_speed)
+                    })
+                    .collect::<Result<Vec<f64>>>()?;
+                Ok(SpeedLossCurve {
+                    wave_direction: direction,
+                    significant_wave_heights: significant_wave_heights.to_vec(),
+                    attainable_speeds,
+                    commanded_speed: conditions.speed_knots,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SpeedLossCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions() -> OperatingConditions {
+        OperatingConditions {
+            speed_knots: 18.0,
+            draft: 11.5,
+            displacement: 52000.0,
+            trim: 0.0,
+            heel_angle: 0.0,
+            water_density: 1025.0,
+            kinematic_viscosity: 1.188e-6,
+        }
+    }
+
+    #[test]
+    fn test_speed_loss_grows_with_wave_height() {
+        let vessel = VesselParameters::default_container_ship();
+        let calculator = SpeedLossCalculator::new();
+        let calm = SeaState {
+            significant_wave_height: 0.0,
+            peak_period: 8.0,
+            wave_direction: 0.0,
+            spectrum_type: SpectrumType::JONSWAP,
+            wind_conditions: None,
+        };
+
+        // Engine sized with a 5 % margin over calm water
+        let calm_power = calculator.brake_power(&vessel, &conditions(), &calm).unwrap();
+        let engine = EngineLimits {
+            maximum_continuous_rating: 1.05 * calm_power / 1000.0,
+            service_fraction: 1.0,
+            minimum_speed: 5.0,
+        };
+
+        let in_calm = calculator.attainable_speed(&vessel, &conditions(), &engine, &calm).unwrap();
+        assert!(!in_calm.power_limited);
+        assert_eq!(in_calm.speed_loss(), 0.0);
+
+        let curves = calculator
+            .speed_loss_curves(&vessel, &conditions(), &engine, &[0.0, 3.0, 6.0], &[0.0], SpectrumType::JONSWAP, |hs| {
+                4.5 * hs.sqrt().max(1.0)
+            })
+            .unwrap();
+        let speeds = &curves[0].attainable_speeds;
+        assert_eq!(speeds[0], 18.0);
+        assert!(speeds[2] <= speeds[1] && speeds[1] <= speeds[0]);
+        assert!(speeds[2] < 18.0);
+    }
+}
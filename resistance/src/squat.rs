@@ -0,0 +1,282 @@
+//! Shallow-water squat prediction
+//!
+//! A ship moving in shallow water sinks bodily and trims as the flow
+//! accelerates under the hull. This module estimates the resulting squat
+//! with the Barrass and Tuck/ICORELS formulas and checks the remaining
+//! under-keel clearance.
+//!
+//! ## References
+//! - Barrass, C.B. (2004). "Thirty-Two Years of Research into Ship Squat"
+//! - Tuck, E.O. (1966). "Shallow-water flows past slender bodies", JFM 26
+//! - PIANC (1997). Approach Channels: A Guide for Design (ICORELS)
+
+use crate::{
+    types::*,
+    errors::{Result, ResistanceError},
+};
+use libm::{pow, sqrt};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Waterway in which the ship sails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Waterway {
+    /// Unrestricted width
+    OpenWater,
+    /// Channel or canal of given width at the water surface (m)
+    Channel { width: f64 },
+}
+
+/// Squat formula
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SquatMethod {
+    /// Barrass maximum squat from blockage and block coefficient
+    Barrass,
+    /// Tuck slender-body sinkage and trim (ICORELS coefficient for maximum squat)
+    Tuck,
+}
+
+/// Squat calculation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquatConfig {
+    pub method: SquatMethod,
+    pub minimum_ukc: f64,              // Required under-keel clearance (m)
+    pub minimum_ukc_fraction: f64,     // Required UKC as fraction of draft
+}
+
+impl Default for SquatConfig {
+    fn default() -> Self {
+        Self {
+            method: SquatMethod::Barrass,
+            minimum_ukc: 0.5,
+            minimum_ukc_fraction: 0.1,
+        }
+    }
+}
+
+/// Squat prediction result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquatResult {
+    pub sinkage: f64,                  // Mean bodily sinkage (m)
+    pub trim: f64,                     // Dynamic trim (m, positive by the bow)
+    pub maximum_squat: f64,            // Squat at bow or stern, whichever is larger (m)
+    pub depth_froude_number: f64,      // Fnh = V / √(gh)
+    pub blockage_factor: f64,          // S = As / Ac
+    pub under_keel_clearance: f64,     // Static UKC less maximum squat (m)
+    pub warnings: Vec<String>,
+}
+
+impl SquatResult {
+    /// Whether the required under-keel clearance is respected
+    pub fn is_safe(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Squat calculator
+#[derive(Debug, Clone)]
+pub struct SquatCalculator {
+    config: SquatConfig,
+}
+
+impl SquatCalculator {
+    /// Create a calculator with default configuration
+    pub fn new() -> Self {
+        Self::with_config(SquatConfig::default())
+    }
+
+    /// Create calculator with custom configuration
+    pub fn with_config(config: SquatConfig) -> Self {
+        Self { config }
+    }
+
+    /// Predict squat at the current speed in water of depth `water_depth` (m)
+    pub fn calculate_squat(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        water_depth: f64,
+        waterway: &Waterway,
+    ) -> Result<SquatResult> {
+        let draft = conditions.draft;
+        if water_depth <= draft {
+            return Err(ResistanceError::invalid_operating_conditions(format!(
+                "Water depth {:.2} m does not exceed draft {:.2} m",
+                water_depth, draft
+            )));
+        }
+
+        let length = vessel.hull.length_between_perpendiculars;
+        let beam = vessel.hull.beam;
+        let cb = vessel.hull.block_coefficient;
+        let speed_ms = conditions.speed_knots * 0.5144;
+        let depth_froude_number = speed_ms / sqrt(9.81 * water_depth);
+
+        // Blockage: midship section over the effective channel cross-section
+        let effective_width = match waterway {
+            Waterway::OpenWater => beam * (7.7 + 20.0 * (1.0 - cb).powi(2)),
+            Waterway::Channel { width } => *width,
+        };
+        if effective_width <= beam {
+            return Err(ResistanceError::invalid_operating_conditions(
+                "Channel width must exceed the beam",
+            ));
+        }
+        let blockage_factor = beam * draft / (effective_width * water_depth);
+
+        let mut warnings = Vec::new();
+        let (sinkage, trim, maximum_squat) = match self.config.method {
+            SquatMethod::Barrass => {
+                let maximum = cb * pow(blockage_factor, 0.81) * pow(conditions.speed_knots.max(0.0), 2.08) / 20.0;
+                // Full ships squat by the bow, fine ships by the stern; the
+                // other end sinks by roughly the bodily sinkage alone
+                let trim_ratio = ((cb - 0.7) / 0.1).clamp(-1.0, 1.0);
+                let sinkage = maximum * (1.0 - 0.5 * trim_ratio.abs());
+                let trim = 2.0 * (maximum - sinkage) * trim_ratio.signum();
+                (sinkage, trim, maximum)
+            }
+            SquatMethod::Tuck => {
+                if depth_froude_number >= 1.0 {
+                    return Err(ResistanceError::invalid_operating_conditions(
+                        "Tuck squat is undefined at or above the critical speed",
+                    ));
+                }
+                let volume = conditions.displacement / conditions.water_density * 1000.0;
+                let factor = depth_froude_number.powi(2) / sqrt(1.0 - depth_froude_number.powi(2));
+                let sinkage = 1.46 * volume / (length * length) * factor;
+                let maximum = 2.4 * volume / (length * length) * factor;
+                let trim = 2.0 * (maximum - sinkage) * if cb >= 0.7 { 1.0 } else { -1.0 };
+                (sinkage, trim, maximum)
+            }
+        };
+
+        if depth_froude_number > 0.7 {
+            warnings.push(format!(
+                "Depth Froude number {:.2} is beyond the validated range of the squat formulas",
+                depth_froude_number
+            ));
+        }
+
+        let under_keel_clearance = water_depth - draft - maximum_squat;
+        let required = self.config.minimum_ukc.max(self.config.minimum_ukc_fraction * draft);
+        if under_keel_clearance < required {
+            warnings.push(format!(
+                "Under-keel clearance {:.2} m is below the required {:.2} m",
+                under_keel_clearance, required
+            ));
+        }
+        for message in &warnings {
+            warn!("{}: {}", vessel.name, message);
+        }
+
+        debug!("Squat: Fnh={:.3}, S={:.3}, sinkage={:.3} m, max squat={:.3} m, UKC={:.2} m",
+               depth_froude_number, blockage_factor, sinkage, maximum_squat, under_keel_clearance);
+
+        Ok(SquatResult {
+            sinkage,
+            trim,
+            maximum_squat,
+            depth_froude_number,
+            blockage_factor,
+            under_keel_clearance,
+            warnings,
+        })
+    }
+
+    /// Highest speed (knots) at which the required under-keel clearance holds
+    pub fn maximum_safe_speed(
+        &self,
+        vessel: &VesselParameters,
+        conditions: &OperatingConditions,
+        water_depth: f64,
+        waterway: &Waterway,
+    ) -> Result<f64> {
+        let is_safe = |speed: f64| -> Result<bool> {
+            let trial = OperatingConditions {
+                speed_knots: speed,
+                ..conditions.clone()
+            };
+            let result = self.calculate_squat(vessel, &trial, water_depth, waterway)?;
+            let required = self.config.minimum_ukc.max(self.config.minimum_ukc_fraction * conditions.draft);
+            Ok(result.under_keel_clearance >= required)
+        };
+
+        if !is_safe(0.0)? {
+            return Ok(0.0);
+        }
+        // Bisection below the critical speed
+        let (mut lo, mut hi) = (0.0, 0.99 * sqrt(9.81 * water_depth) / 0.5144);
+        if is_safe(hi)? {
+            return Ok(hi);
+        }
+        for _ in 0..50 {
+            let mid = 0.5 * (lo + hi);
+            if is_safe(mid)? {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
+}
+
+impl Default for SquatCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(speed_knots: f64) -> OperatingConditions {
+        OperatingConditions {
+            speed_knots,
+            draft: 11.5,
+            displacement: 52000.0,
+            trim: 0.0,
+            heel_angle: 0.0,
+            water_density: 1025.0,
+            kinematic_viscosity: 1.188e-6,
+        }
+    }
+
+    #[test]
+    fn test_squat_increases_with_speed_and_confinement() {
+        let vessel = VesselParameters::default_container_ship();
+        let calculator = SquatCalculator::new();
+
+        let slow = calculator.calculate_squat(&vessel, &conditions(6.0), 14.0, &Waterway::OpenWater).unwrap();
+        let fast = calculator.calculate_squat(&vessel, &conditions(12.0), 14.0, &Waterway::OpenWater).unwrap();
+        let canal = calculator
+            .calculate_squat(&vessel, &conditions(12.0), 14.0, &Waterway::Channel { width: 150.0 })
+            .unwrap();
+
+        assert!(fast.maximum_squat > slow.maximum_squat);
+        assert!(canal.maximum_squat > fast.maximum_squat);
+        assert!(canal.blockage_factor > fast.blockage_factor);
+    }
+
+    #[test]
+    fn test_under_keel_clearance_warning() {
+        let vessel = VesselParameters::default_container_ship();
+        let calculator = SquatCalculator::with_config(SquatConfig {
+            method: SquatMethod::Tuck,
+            ..Default::default()
+        });
+
+        let result = calculator.calculate_squat(&vessel, &conditions(14.0), 13.0, &Waterway::OpenWater).unwrap();
+        assert!(!result.is_safe());
+
+        let safe_speed = calculator
+            .maximum_safe_speed(&vessel, &conditions(14.0), 13.0, &Waterway::OpenWater)
+            .unwrap();
+        assert!(safe_speed < 14.0);
+        let at_safe = calculator
+            .calculate_squat(&vessel, &conditions(safe_speed), 13.0, &Waterway::OpenWater)
+            .unwrap();
+        assert!(at_safe.under_keel_clearance >= 1.15 - 1e-6);
+    }
+}
@@ -305,6 +305,43 @@ impl EnvironmentalConditions {
     }
 }
 
+impl WaveSpectrum {
+    /// Discretized spectrum from sea-state parameters (0.2-3.0 rad/s)
+    ///
+    /// JONSWAP uses a peak enhancement factor of 3.3; BMKG is evaluated with
+    /// the Pierson-Moskowitz shape.
+    pub fn from_sea_state(
+        significant_wave_height: f64,
+        peak_period: f64,
+        wave_direction: f64,
+        spectrum_type: SpectrumType,
+    ) -> Self {
+        let gamma: f64 = match spectrum_type {
+            SpectrumType::JONSWAP => 3.3,
+            SpectrumType::PiersonMoskowitz | SpectrumType::BMKG => 1.0,
+        };
+        let wp = 2.0 * std::f64::consts::PI / peak_period;
+        let frequencies = Array1::range(0.2, 3.0, 0.02);
+        let spectral_densities = frequencies.mapv(|w: f64| {
+            let sigma = if w <= wp { 0.07 } else { 0.09 };
+            let peak = gamma.powf((-(w - wp).powi(2) / (2.0 * sigma * sigma * wp * wp)).exp());
+            // Pierson-Moskowitz in Hs and ωp, with the Goda normalization of γ
+            let pm = 5.0 / 16.0 * significant_wave_height.powi(2) * wp.powi(4) / w.powi(5)
+                * (-1.25 * (wp / w).powi(4)).exp();
+            pm * peak * (1.0 - 0.287 * gamma.ln())
+        });
+
+        Self {
+            significant_wave_height,
+            peak_period,
+            wave_direction,
+            spectrum_type,
+            frequencies,
+            spectral_densities,
+        }
+    }
+}
+
 impl AddedResistanceResult {
     /// Create zero added resistance result
     pub fn zero() -> Self {
@@ -0,0 +1,163 @@
+//! Voyage simulation with involuntary speed loss
+//!
+//! A voyage is split into legs, each sailed in one sea state. On every leg
+//! the ship runs at the commanded speed unless the engine limit forces it
+//! slower, giving attainable speeds, leg durations, delay and brake energy.
+
+use crate::{
+    types::*,
+    errors::{Result, ResistanceError},
+    speed_loss::{EngineLimits, SeaState, SpeedLossCalculator},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Voyage leg sailed in a single sea state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoyageLeg {
+    pub distance: f64,                 // Leg length (nautical miles)
+    pub sea_state: SeaState,
+}
+
+/// Outcome of one leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegResult {
+    pub attainable_speed: f64,         // Speed achieved (knots)
+    pub speed_loss: f64,               // Involuntary speed loss (knots)
+    pub duration: f64,                 // Sailing time (hours)
+    pub brake_energy: f64,             // Brake energy (kWh)
+}
+
+/// Outcome of a voyage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoyageResult {
+    pub legs: Vec<LegResult>,
+    pub total_distance: f64,           // Distance (nautical miles)
+    pub total_duration: f64,           // Sailing time (hours)
+    pub delay: f64,                    // Time lost to speed loss (hours)
+    pub total_brake_energy: f64,       // Brake energy (kWh)
+}
+
+impl VoyageResult {
+    /// Average speed over ground (knots)
+    pub fn average_speed(&self) -> f64 {
+        if self.total_duration > 0.0 {
+            self.total_distance / self.total_duration
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Voyage simulator
+#[derive(Debug, Clone)]
+pub struct VoyageSimulator {
+    pub vessel: VesselParameters,
+    pub engine: EngineLimits,
+    pub speed_loss: SpeedLossCalculator,
+}
+
+impl VoyageSimulator {
+    /// Create a simulator with default resistance models
+    pub fn new(vessel: VesselParameters, engine: EngineLimits) -> Self {
+        Self {
+            vessel,
+            engine,
+            speed_loss: SpeedLossCalculator::new(),
+        }
+    }
+
+    /// Sail `legs` at the commanded speed of `conditions`
+    pub fn simulate(&self, conditions: &OperatingConditions, legs: &[VoyageLeg]) -> Result<VoyageResult> {
+        info!("Simulating {} voyage legs for {}", legs.len(), self.vessel.name);
+
+        let mut results = Vec::with_capacity(legs.len());
+        let (mut total_distance, mut total_duration, mut planned_duration, mut total_energy) = (0.0, 0.0, 0.0, 0.0);
+        for (index, leg) in legs.iter().enumerate() {
+            if leg.distance < 0.0 {
+                return Err(ResistanceError::invalid_operating_conditions(format!(
+                    "Leg {} has negative distance",
+                    index
+                )));
+            }
+            let outcome = self.speed_loss
+                .attainable_speed(&self.vessel, conditions, &self.engine, &leg.sea_state)?;
+            if outcome.attainable_speed <= 0.0 {
+                return Err(ResistanceError::calculation_error(format!(
+                    "Leg {}: engine cannot hold the minimum speed of {:.1} kn",
+                    index, self.engine.minimum_speed
+                )));
+            }
+            if outcome.power_limited {
+                warn!("Leg {}: speed reduced to {:.2} kn", index, outcome.attainable_speed);
+            }
+
+            let duration = leg.distance / outcome.attainable_speed;
+            let brake_energy = outcome.brake_power / 1000.0 * duration;
+            total_distance += leg.distance;
+            total_duration += duration;
+            planned_duration += leg.distance / conditions.speed_knots;
+            total_energy += brake_energy;
+            results.push(LegResult {
+                attainable_speed: outcome.attainable_speed,
+                speed_loss: outcome.speed_loss(),
+                duration,
+                brake_energy,
+            });
+        }
+
+        Ok(VoyageResult {
+            legs: results,
+            total_distance,
+            total_duration,
+            delay: total_duration - planned_duration,
+            total_brake_energy: total_energy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heavy_weather_leg_delays_voyage() {
+        let vessel = VesselParameters::default_container_ship();
+        let conditions = OperatingConditions {
+            speed_knots: 18.0,
+            draft: 11.5,
+            displacement: 52000.0,
+            trim: 0.0,
+            heel_angle: 0.0,
+            water_density: 1025.0,
+            kinematic_viscosity: 1.188e-6,
+        };
+        let sea = |hs: f64| SeaState {
+            significant_wave_height: hs,
+            peak_period: 10.0,
+            wave_direction: 0.0,
+            spectrum_type: SpectrumType::JONSWAP,
+            wind_conditions: None,
+        };
+
+        let calculator = SpeedLossCalculator::new();
+        let calm_power = calculator.brake_power(&vessel, &conditions, &sea(0.0)).unwrap();
+        let engine = EngineLimits {
+            maximum_continuous_rating: 1.05 * calm_power / 1000.0,
+            service_fraction: 1.0,
+            minimum_speed: 3.0,
+        };
+        let simulator = VoyageSimulator::new(vessel, engine);
+
+        let legs = vec![
+            VoyageLeg { distance: 180.0, sea_state: sea(0.0) },
+            VoyageLeg { distance: 180.0, sea_state: sea(6.0) },
+        ];
+        let result = simulator.simulate(&conditions, &legs).unwrap();
+
+        assert!((result.legs[0].duration - 10.0).abs() < 1e-9);
+        assert!(result.legs[1].speed_loss >= 0.0);
+        assert!(result.delay >= 0.0);
+        assert!(result.average_speed() <= 18.0 + 1e-9);
+    }
+}
@@ -1,7 +1,9 @@
 //! Windage resistance calculation module
 //! 
 //! This module implements wind resistance calculations for ships, including
-//! superstructure effects and relative wind calculation.
+//! superstructure effects and relative wind calculation. Measured coefficient
+//! curves (wind tunnel tests, OCIMF tables) can replace the built-in
+//! superstructure estimate.
 
 use crate::{
     types::*,
@@ -24,6 +26,7 @@ pub struct WindageConfig {
     pub use_relative_wind: bool,
     pub include_heel_effects: bool,
     pub apply_gust_factor: bool,
+    pub coefficient_table: Option<WindCoefficientTable>,
 }
 
 impl Default for WindageConfig {
@@ -32,10 +35,153 @@ impl Default for WindageConfig {
             use_relative_wind: true,
             include_heel_effects: false,
             apply_gust_factor: true,
+            coefficient_table: None,
         }
     }
 }
 
+/// Interpolation between tabulated angles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterpolationPolicy {
+    /// Piecewise linear
+    Linear,
+    /// Value of the nearest tabulated angle
+    Nearest,
+}
+
+/// Handling of angles outside the tabulated range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtrapolationPolicy {
+    /// Hold the end values
+    Clamp,
+    /// Extend the end segments linearly
+    Linear,
+    /// Reject the angle
+    Error,
+}
+
+/// Measured wind coefficient curves versus relative wind angle
+///
+/// Forces follow the OCIMF convention: Fx = ½ρV²·Cx·A_F, Fy = ½ρV²·Cy·A_L and
+/// Mz = ½ρV²·Cn·A_L·L_OA, with Cx positive opposing forward motion (matching
+/// `WindResistance::longitudinal_force`) and angles in degrees from the bow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindCoefficientTable {
+    pub angles: Vec<f64>,              // Relative wind angle (degrees), ascending
+    pub cx: Vec<f64>,                  // Longitudinal coefficient
+    pub cy: Vec<f64>,                  // Lateral coefficient
+    pub cn: Vec<f64>,                  // Yaw moment coefficient
+    pub symmetric: bool,               // Table covers 0-180° and port mirrors starboard
+    pub interpolation: InterpolationPolicy,
+    pub extrapolation: ExtrapolationPolicy,
+}
+
+impl WindCoefficientTable {
+    /// Create a validated table
+    pub fn new(angles: Vec<f64>, cx: Vec<f64>, cy: Vec<f64>, cn: Vec<f64>, symmetric: bool) -> Result<Self> {
+        let table = Self {
+            angles,
+            cx,
+            cy,
+            cn,
+            symmetric,
+            interpolation: InterpolationPolicy::Linear,
+            extrapolation: ExtrapolationPolicy::Clamp,
+        };
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Parse `angle, cx, cy, cn` rows; blank lines, `#` comments and a
+    /// non-numeric header row are skipped
+    pub fn from_csv(text: &str, symmetric: bool) -> Result<Self> {
+        let (mut angles, mut cx, mut cy, mut cn) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split([',', ';', '\t']).map(str::trim).collect();
+            let values: std::result::Result<Vec<f64>, _> = fields.iter().map(|f| f.parse::<f64>()).collect();
+            match values {
+                Ok(v) if v.len() >= 4 => {
+                    angles.push(v[0]);
+                    cx.push(v[1]);
+                    cy.push(v[2]);
+                    cn.push(v[3]);
+                }
+                Err(_) if angles.is_empty() => continue, // header
+                _ => {
+                    return Err(ResistanceError::InvalidWindConditions {
+                        message: format!("Invalid wind coefficient row {}: '{}'", line_no + 1, line),
+                    })
+                }
+            }
+        }
+        Self::new(angles, cx, cy, cn, symmetric)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let n = self.angles.len();
+        if n < 2 || self.cx.len() != n || self.cy.len() != n || self.cn.len() != n {
+            return Err(ResistanceError::InvalidWindConditions {
+                message: "Wind coefficient table needs at least two rows of equal length".to_string(),
+            });
+        }
+        if self.angles.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(ResistanceError::InvalidWindConditions {
+                message: "Wind coefficient angles must be strictly ascending".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Coefficients (Cx, Cy, Cn) at relative wind angle (degrees)
+    pub fn coefficients(&self, angle_deg: f64) -> Result<(f64, f64, f64)> {
+        // Map to [0, 360), then to the starboard half for symmetric tables
+        let mut angle = angle_deg.rem_euclid(360.0);
+        let mut side = 1.0;
+        if self.symmetric && angle > 180.0 {
+            angle = 360.0 - angle;
+            side = -1.0;
+        }
+
+        let cx = self.interpolate(&self.cx, angle)?;
+        let cy = self.interpolate(&self.cy, angle)?;
+        let cn = self.interpolate(&self.cn, angle)?;
+        Ok((cx, side * cy, side * cn))
+    }
+
+    fn interpolate(&self, values: &[f64], angle: f64) -> Result<f64> {
+        let a = &self.angles;
+        let n = a.len();
+        let segment = if angle < a[0] || angle > a[n - 1] {
+            match self.extrapolation {
+                ExtrapolationPolicy::Clamp => {
+                    return Ok(if angle < a[0] { values[0] } else { values[n - 1] });
+                }
+                ExtrapolationPolicy::Linear => if angle < a[0] { 0 } else { n - 2 },
+                ExtrapolationPolicy::Error => {
+                    return Err(ResistanceError::InterpolationError {
+                        message: format!("Wind angle {:.1}° outside table range {:.1}°-{:.1}°", angle, a[0], a[n - 1]),
+                    });
+                }
+            }
+        } else {
+            a.windows(2).position(|w| angle <= w[1]).unwrap_or(n - 2)
+        };
+
+        let (a0, a1) = (a[segment], a[segment + 1]);
+        let t = (angle - a0) / (a1 - a0);
+        Ok(match self.interpolation {
+            InterpolationPolicy::Nearest if (0.0..=1.0).contains(&t) => {
+                if t < 0.5 { values[segment] } else { values[segment + 1] }
+            }
+            _ => values[segment] + t * (values[segment + 1] - values[segment]),
+        })
+    }
+}
+
 impl WindageCalculator {
     /// Create a new windage calculator
     pub fn new() -> Self {
@@ -132,6 +278,16 @@ impl WindageCalculator {
             1.0
         };
 
+        // Measured coefficients take precedence over the superstructure estimate
+        if let Some(ref table) = self.config.coefficient_table {
+            let (cx, cy, cn) = table.coefficients(relative_wind.direction)?;
+            let q = dynamic_pressure * gust_factor;
+            let fx = q * cx * superstructure.frontal_area;
+            let fy = q * cy * superstructure.lateral_area;
+            let mz = q * cn * superstructure.lateral_area * vessel.hull.length_overall;
+            return Ok((fx, fy, mz));
+        }
+
         // Longitudinal force (drag in heading direction)
         let cd_longitudinal = self.interpolate_drag_coefficient(
             wind_angle_rad.abs(),
@@ -171,6 +327,10 @@ impl WindageCalculator {
 
     /// Assess model confidence for wind resistance calculation
     pub fn assess_model_confidence(&self, vessel: &VesselParameters) -> Result<f64> {
+        if self.config.coefficient_table.is_some() {
+            // Measured coefficients
+            return Ok(0.95);
+        }
         let superstructure = &vessel.superstructure;
         let mut confidence_factors = Vec::new();
 
@@ -277,6 +437,42 @@ mod tests {
         assert!(score > 0.5); // Should be reasonable for default vessel
     }
 
+    #[test]
+    fn test_coefficient_table_import() {
+        let csv = "angle,cx,cy,cn\n0,0.8,0.0,0.0\n90,0.0,0.9,0.05\n180,-0.6,0.0,0.0\n";
+        let mut table = WindCoefficientTable::from_csv(csv, true).unwrap();
+
+        let (cx, cy, cn) = table.coefficients(45.0).unwrap();
+        assert_relative_eq!(cx, 0.4, epsilon = 1e-12);
+        assert_relative_eq!(cy, 0.45, epsilon = 1e-12);
+        assert_relative_eq!(cn, 0.025, epsilon = 1e-12);
+
+        // Port side mirrors starboard
+        let (_, cy_port, cn_port) = table.coefficients(-90.0).unwrap();
+        assert_relative_eq!(cy_port, -0.9, epsilon = 1e-12);
+        assert_relative_eq!(cn_port, -0.05, epsilon = 1e-12);
+
+        table.symmetric = false;
+        table.extrapolation = ExtrapolationPolicy::Error;
+        assert!(table.coefficients(200.0).is_err());
+
+        let calculator = WindageCalculator::with_config(WindageConfig {
+            use_relative_wind: false,
+            coefficient_table: Some(WindCoefficientTable::from_csv(csv, true).unwrap()),
+            ..Default::default()
+        });
+        let vessel = VesselParameters::default_container_ship();
+        let wind = WindConditions {
+            wind_speed: 10.0,
+            wind_direction: 0.0,
+            air_density: 1.225,
+            gust_factor: 1.0,
+        };
+        let result = calculator.calculate_wind_resistance(&vessel, &wind).unwrap();
+        let expected = 0.5 * 1.225 * 100.0 * 0.8 * vessel.superstructure.frontal_area;
+        assert_relative_eq!(result.longitudinal_force, expected, epsilon = 1e-6);
+    }
+
     #[test]
     fn test_drag_coefficient_interpolation() {
         let calculator = WindageCalculator::new();
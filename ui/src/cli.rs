@@ -40,6 +40,9 @@ impl CLIServer {
             CLICommand::Benchmark { test_cases, output } => {
                 self.run_benchmarks(test_cases, output).await
             }
+            CLICommand::Migrate { archive, dry_run } => {
+                self.migrate_archive(archive, dry_run).await
+            }
         };
         
         let processing_time = start_time.elapsed().as_secs_f64();
@@ -214,20 +217,55 @@ impl CLIServer {
         }
         
         // Load mesh
-        let mesh_data = fs::read_to_string(&mesh)
-            .map_err(|e| UIError::IOError(wavecore_io::IOError::MemoryMapError(e)))?;
+        let extension = Path::new(&mesh).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        let mesh_data = match extension.as_str() {
+            "stl" => wavecore_io::read_stl(&mesh, &wavecore_io::StlOptions::default())?,
+            "obj" => wavecore_io::read_obj(&mesh)?.to_mesh()?,
+            _ => {
+                return Err(UIError::ValidationError {
+                    message: format!("Unsupported mesh format: {}", mesh),
+                });
+            }
+        };
         
-        // Perform mesh validation (simplified)
+        // Edge topology and closure
+        let stats = mesh_data.stats();
+        let mut issues = Vec::new();
+        if stats.boundary_edges > 0 {
+            issues.push(format!("{} boundary edges in {} loops", stats.boundary_edges, stats.boundary_loops));
+        }
+        if stats.non_manifold_edges > 0 {
+            issues.push(format!("{} non-manifold edges", stats.non_manifold_edges));
+        }
+        let (min, max) = stats.bounding_box;
         let validation_report = format!(
             "Mesh Validation Report\n\
              File: {}\n\
-             Size: {} bytes\n\
-             Status: Valid\n\
-             Vertices: ~1000\n\
-             Faces: ~2000\n\
-             Quality: Good\n\
-             Issues: None",
-            mesh, mesh_data.len()
+             Status: {}\n\
+             Vertices: {}\n\
+             Faces: {}\n\
+             Edges: {}\n\
+             Boundary loops: {}\n\
+             Components: {}\n\
+             Euler characteristic: {}\n\
+             Watertight: {}\n\
+             Bounding box: ({:.4}, {:.4}, {:.4}) - ({:.4}, {:.4}, {:.4})\n\
+             Surface area: {:.6}\n\
+             Volume: {:.6}\n\
+             Issues: {}",
+            mesh,
+            if stats.watertight { "Valid" } else { "Open" },
+            stats.vertices,
+            stats.faces,
+            stats.edges,
+            stats.boundary_loops,
+            stats.components,
+            stats.euler_characteristic,
+            if stats.watertight { "yes" } else { "no" },
+            min.x, min.y, min.z, max.x, max.y, max.z,
+            stats.surface_area,
+            stats.volume,
+            if issues.is_empty() { "None".to_string() } else { issues.join("; ") },
         );
         
         // Output report
@@ -300,6 +338,29 @@ impl CLIServer {
         
         Ok(())
     }
+    
+    /// Migrate result archive
+    async fn migrate_archive(&self, archive: String, dry_run: bool) -> Result<()> {
+        if !Path::new(&archive).exists() {
+            return Err(UIError::ValidationError {
+                message: format!("Archive file not found: {}", archive),
+            });
+        }
+        
+        let report = wavecore_io::ArchiveMigrator::migrate_file(&archive, dry_run)?;
+        
+        if !self.config.quiet {
+            if report.from_version == report.to_version {
+                println!("{} is already at schema v{}", archive, report.to_version);
+            } else if report.rewritten {
+                println!("Migrated {} from schema v{} to v{}", archive, report.from_version, report.to_version);
+            } else {
+                println!("{} would be migrated from schema v{} to v{}", archive, report.from_version, report.to_version);
+            }
+        }
+        
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -363,13 +424,18 @@ mod tests {
         
         let command = CLICommand::Validate {
             mesh: mesh.to_string(),
-            report: None,
+            report: Some("test_mesh_report.txt".to_string()),
         };
         
         let result = server.run(command).await;
         assert!(result.is_ok());
+        let report = fs::read_to_string("test_mesh_report.txt").unwrap();
+        assert!(report.contains("Edges: 3\n"));
+        assert!(report.contains("Boundary loops: 1\n"));
+        assert!(report.contains("Watertight: no\n"));
         
         // Cleanup
         fs::remove_file(mesh).unwrap();
+        fs::remove_file("test_mesh_report.txt").unwrap();
     }
 } 
\ No newline at end of file
@@ -35,9 +35,11 @@
 
 pub mod web;
 pub mod cli;
+pub mod stream;
 
 pub use web::*;
 pub use cli::*;
+pub use stream::*;
 
 use thiserror::Error;
 use serde::{Deserialize, Serialize};
@@ -100,6 +102,8 @@ pub struct ServerConfig {
     pub log_level: String,
     /// Verbose output
     pub verbose: bool,
+    /// WebSocket stream throttling
+    pub stream: StreamConfig,
 }
 
 impl Default for ServerConfig {
@@ -114,6 +118,7 @@ impl Default for ServerConfig {
             enable_logging: true,
             log_level: "info".to_string(),
             verbose: false,
+            stream: StreamConfig::default(),
         }
     }
 }
@@ -255,6 +260,13 @@ pub enum CLICommand {
         /// Output file
         output: String,
     },
+    /// Migrate result archive to the current schema
+    Migrate {
+        /// Archive file
+        archive: String,
+        /// Report only, do not rewrite
+        dry_run: bool,
+    },
 }
 
 /// CLI configuration
@@ -0,0 +1,336 @@
+//! Progress stream decimation
+//!
+//! Live solver output (convergence histories, free-surface snapshots) can be
+//! produced far faster than a browser can render it. The decimator limits the
+//! message rate, coalesces superseded updates and thins large numeric arrays so
+//! that a slow client never blocks the producer.
+
+use super::*;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Stream throttling configuration
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    /// Maximum messages per second (0 disables rate limiting)
+    pub max_message_rate: f64,
+    /// Maximum serialized payload size (bytes)
+    pub max_payload_bytes: usize,
+    /// Maximum points kept per numeric array
+    pub max_array_points: usize,
+    /// Outgoing queue capacity per client
+    pub queue_capacity: usize,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            max_message_rate: 10.0,
+            max_payload_bytes: 256 * 1024, // 256KB
+            max_array_points: 2000,
+            queue_capacity: 64,
+        }
+    }
+}
+
+/// Rate limiter and payload reducer for WebSocket messages
+#[derive(Debug)]
+pub struct StreamDecimator {
+    config: StreamConfig,
+    last_emit: Option<Instant>,
+    pending_progress: Option<WebSocketMessage>,
+    pending_data: BTreeMap<String, WebSocketMessage>,
+    dropped: usize,
+}
+
+impl StreamDecimator {
+    /// Create a new decimator
+    pub fn new(config: StreamConfig) -> Self {
+        Self {
+            config,
+            last_emit: None,
+            pending_progress: None,
+            pending_data: BTreeMap::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Offer a message, returning the messages that may be sent now
+    ///
+    /// Status and error messages always pass. Progress and data messages are
+    /// coalesced (latest wins, per data type) until the rate limit allows.
+    pub fn offer(&mut self, message: WebSocketMessage, now: Instant) -> Vec<WebSocketMessage> {
+        match message {
+            WebSocketMessage::Status { .. } | WebSocketMessage::Error { .. } => {
+                let mut ready = vec![message];
+                ready.extend(self.poll(now));
+                ready
+            }
+            WebSocketMessage::Progress { .. } => {
+                if self.pending_progress.replace(message).is_some() {
+                    self.dropped += 1;
+                }
+                self.poll(now)
+            }
+            WebSocketMessage::Data { ref data_type, .. } => {
+                let key = data_type.clone();
+                if self.pending_data.insert(key, message).is_some() {
+                    self.dropped += 1;
+                }
+                self.poll(now)
+            }
+        }
+    }
+
+    /// Release pending messages if the rate limit allows
+    pub fn poll(&mut self, now: Instant) -> Vec<WebSocketMessage> {
+        if !self.has_pending() || !self.interval_elapsed(now) {
+            return Vec::new();
+        }
+        self.last_emit = Some(now);
+        self.drain_pending()
+    }
+
+    /// Release all pending messages regardless of rate (e.g. at end of run)
+    pub fn flush(&mut self) -> Vec<WebSocketMessage> {
+        self.drain_pending()
+    }
+
+    /// Whether messages are waiting for the next send window
+    pub fn has_pending(&self) -> bool {
+        self.pending_progress.is_some() || !self.pending_data.is_empty()
+    }
+
+    /// Number of superseded messages that were never sent
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Thin numeric arrays until the payload fits the configured size
+    pub fn reduce_payload(&self, content: &serde_json::Value) -> serde_json::Value {
+        let mut max_points = self.config.max_array_points.max(2);
+        let mut reduced = decimate_arrays(content, max_points);
+
+        while payload_size(&reduced) > self.config.max_payload_bytes && max_points > 2 {
+            max_points /= 2;
+            reduced = decimate_arrays(content, max_points.max(2));
+        }
+
+        reduced
+    }
+
+    fn interval_elapsed(&self, now: Instant) -> bool {
+        if self.config.max_message_rate <= 0.0 {
+            return true;
+        }
+        let min_interval = Duration::from_secs_f64(1.0 / self.config.max_message_rate);
+        match self.last_emit {
+            Some(last) => now.saturating_duration_since(last) >= min_interval,
+            None => true,
+        }
+    }
+
+    fn drain_pending(&mut self) -> Vec<WebSocketMessage> {
+        let mut ready: Vec<WebSocketMessage> = self.pending_progress.take().into_iter().collect();
+        let data = std::mem::take(&mut self.pending_data);
+        for (_, message) in data {
+            if let WebSocketMessage::Data { data_type, content } = message {
+                ready.push(WebSocketMessage::Data {
+                    content: self.reduce_payload(&content),
+                    data_type,
+                });
+            }
+        }
+        ready
+    }
+}
+
+/// Non-blocking sender feeding a bounded per-client queue
+///
+/// When the client falls behind, messages stay coalesced in the decimator
+/// instead of blocking the solver thread.
+pub struct StreamSender {
+    decimator: StreamDecimator,
+    backlog: Vec<WebSocketMessage>,
+    tx: mpsc::Sender<WebSocketMessage>,
+}
+
+impl StreamSender {
+    /// Create a sender and the receiving end for the client task
+    pub fn channel(config: StreamConfig) -> (Self, mpsc::Receiver<WebSocketMessage>) {
+        let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+        let sender = Self {
+            decimator: StreamDecimator::new(config),
+            backlog: Vec::new(),
+            tx,
+        };
+        (sender, rx)
+    }
+
+    /// Publish a message without waiting on the client
+    pub fn send(&mut self, message: WebSocketMessage) -> Result<()> {
+        let ready = self.decimator.offer(message, Instant::now());
+        self.push(ready)
+    }
+
+    /// Send the coalesced messages once the rate limit allows
+    pub fn poll(&mut self) -> Result<()> {
+        let ready = self.decimator.poll(Instant::now());
+        self.push(ready)
+    }
+
+    /// Send everything still pending
+    pub fn flush(&mut self) -> Result<()> {
+        let ready = self.decimator.flush();
+        self.push(ready)
+    }
+
+    /// Whether the client has gone away
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Access the underlying decimator
+    pub fn decimator(&self) -> &StreamDecimator {
+        &self.decimator
+    }
+
+    fn push(&mut self, ready: Vec<WebSocketMessage>) -> Result<()> {
+        self.backlog.extend(ready);
+        while !self.backlog.is_empty() {
+            match self.tx.try_send(self.backlog.remove(0)) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(message)) => {
+                    // Client is behind: keep only the newest update of each kind
+                    self.backlog.insert(0, message);
+                    self.coalesce_backlog();
+                    break;
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    return Err(UIError::WebSocketError {
+                        message: "Client disconnected".to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn coalesce_backlog(&mut self) {
+        let mut kept: Vec<WebSocketMessage> = Vec::with_capacity(self.backlog.len());
+        for message in self.backlog.drain(..) {
+            let superseded = |m: &WebSocketMessage| match (m, &message) {
+                (WebSocketMessage::Progress { .. }, WebSocketMessage::Progress { .. }) => true,
+                (WebSocketMessage::Data { data_type: a, .. }, WebSocketMessage::Data { data_type: b, .. }) => a == b,
+                _ => false,
+            };
+            kept.retain(|m| !superseded(m));
+            kept.push(message);
+        }
+        self.backlog = kept;
+    }
+}
+
+/// Keep at most `max_points` evenly spaced entries of each numeric array
+fn decimate_arrays(value: &serde_json::Value, max_points: usize) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(items) if items.len() > max_points && items.iter().all(|v| v.is_number()) => {
+            let n = items.len();
+            let reduced = (0..max_points)
+                .map(|i| items[i * (n - 1) / (max_points - 1)].clone())
+                .collect();
+            Value::Array(reduced)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| decimate_arrays(v, max_points)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), decimate_arrays(v, max_points)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn payload_size(value: &serde_json::Value) -> usize {
+    serde_json::to_vec(value).map(|v| v.len()).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(p: f64) -> WebSocketMessage {
+        WebSocketMessage::Progress {
+            progress: p,
+            message: "solving".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_coalesces_progress() {
+        let mut decimator = StreamDecimator::new(StreamConfig {
+            max_message_rate: 2.0,
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+
+        assert_eq!(decimator.offer(progress(0.1), t0).len(), 1);
+        assert!(decimator.offer(progress(0.2), t0 + Duration::from_millis(100)).is_empty());
+        assert!(decimator.offer(progress(0.3), t0 + Duration::from_millis(200)).is_empty());
+
+        let ready = decimator.poll(t0 + Duration::from_millis(600));
+        assert_eq!(ready.len(), 1);
+        assert!(matches!(ready[0], WebSocketMessage::Progress { progress, .. } if progress == 0.3));
+        assert_eq!(decimator.dropped(), 1);
+    }
+
+    #[test]
+    fn test_errors_bypass_rate_limit() {
+        let mut decimator = StreamDecimator::new(StreamConfig::default());
+        let t0 = Instant::now();
+        decimator.offer(progress(0.1), t0);
+
+        let error = WebSocketMessage::Error {
+            message: "diverged".to_string(),
+            code: None,
+        };
+        assert_eq!(decimator.offer(error, t0).len(), 1);
+    }
+
+    #[test]
+    fn test_payload_reduction() {
+        let decimator = StreamDecimator::new(StreamConfig {
+            max_array_points: 11,
+            ..Default::default()
+        });
+        let values: Vec<f64> = (0..1001).map(|i| i as f64).collect();
+        let content = serde_json::json!({ "elevation": values, "label": "fs" });
+
+        let reduced = decimator.reduce_payload(&content);
+        let elevation = reduced["elevation"].as_array().unwrap();
+        assert_eq!(elevation.len(), 11);
+        assert_eq!(elevation[0], 0.0);
+        assert_eq!(elevation[10], 1000.0);
+        assert_eq!(reduced["label"], "fs");
+    }
+
+    #[tokio::test]
+    async fn test_sender_does_not_block_on_full_queue() {
+        let (mut sender, mut rx) = StreamSender::channel(StreamConfig {
+            max_message_rate: 0.0,
+            queue_capacity: 1,
+            ..Default::default()
+        });
+
+        for i in 0..10 {
+            sender.send(progress(i as f64 / 10.0)).unwrap();
+        }
+
+        assert!(matches!(rx.recv().await, Some(WebSocketMessage::Progress { .. })));
+        sender.flush().unwrap();
+        assert!(matches!(rx.recv().await, Some(WebSocketMessage::Progress { progress, .. }) if progress == 0.9));
+    }
+}
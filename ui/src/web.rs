@@ -1,19 +1,30 @@
 //! Web interface implementation
+//!
+//! Handlers publish their progress and results as `WebSocketMessage`s. Each
+//! client of `/ws` receives them through its own `StreamSender`, so the
+//! server configuration's stream limits apply to every outgoing message.
 
 use super::*;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use axum::{
     routing::{get, post},
-    http::{StatusCode, HeaderMap},
+    http::{header, StatusCode, HeaderMap},
     response::{Json, Response},
-    extract::{Path, State, Query},
+    extract::{Path, Request, State, Query},
     body::Body,
 };
+use base64::Engine;
 use serde_json::Value;
+use sha1::{Digest, Sha1};
 use tower_http::cors::{CorsLayer, Any};
 
+/// GUID appended to the client key in the WebSocket handshake (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
 /// Web server
 pub struct WebServer {
     config: ServerConfig,
@@ -26,6 +37,54 @@ struct AppState {
     config: ServerConfig,
     sessions: Arc<RwLock<HashMap<String, SessionData>>>,
     metrics: Arc<RwLock<PerformanceMetrics>>,
+    events: broadcast::Sender<WebSocketMessage>,
+}
+
+impl AppState {
+    fn new(config: ServerConfig) -> Self {
+        let (events, _) = broadcast::channel(config.stream.queue_capacity.max(1));
+        Self {
+            config,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
+            events,
+        }
+    }
+
+    /// Publish a message to every connected client
+    fn publish(&self, message: WebSocketMessage) {
+        // No subscribers is not an error
+        let _ = self.events.send(message);
+    }
+
+    /// Subscribe a client, returning its decimated message stream
+    fn client_stream(&self) -> mpsc::Receiver<WebSocketMessage> {
+        let (mut sender, rx) = StreamSender::channel(self.config.stream.clone());
+        let mut events = self.events.subscribe();
+        let rate = self.config.stream.max_message_rate;
+        let period = if rate > 0.0 { Duration::from_secs_f64(1.0 / rate) } else { Duration::from_millis(100) };
+
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                let sent = tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(message) => sender.send(message),
+                        Err(broadcast::error::RecvError::Lagged(_)) => Ok(()),
+                        Err(broadcast::error::RecvError::Closed) => {
+                            let _ = sender.flush();
+                            break;
+                        }
+                    },
+                    _ = tick.tick() => sender.poll(),
+                };
+                if sent.is_err() || sender.is_closed() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
 }
 
 /// Session data
@@ -40,15 +99,16 @@ struct SessionData {
 impl WebServer {
     /// Create a new web server
     pub fn new(config: ServerConfig) -> Self {
-        let state = Arc::new(AppState {
-            config: config.clone(),
-            sessions: Arc::new(RwLock::new(HashMap::new())),
-            metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
-        });
+        let state = Arc::new(AppState::new(config.clone()));
         
         Self { config, state }
     }
     
+    /// Publish a message to every connected WebSocket client
+    pub fn publish(&self, message: WebSocketMessage) {
+        self.state.publish(message);
+    }
+    
     /// Start the web server
     pub async fn start(&self) -> Result<()> {
         if self.config.verbose {
@@ -65,21 +125,7 @@ impl WebServer {
             CorsLayer::new()
         };
         
-        // Create router
-        let app = axum::Router::new()
-            .route("/", get(Self::index_handler))
-            .route("/api/status", get(Self::status_handler))
-            .route("/api/solve", post(Self::solve_handler))
-            .route("/api/analyze", post(Self::analyze_handler))
-            .route("/api/convert", post(Self::convert_handler))
-            .route("/api/validate", post(Self::validate_handler))
-            .route("/api/benchmark", post(Self::benchmark_handler))
-            .route("/api/metrics", get(Self::metrics_handler))
-            .route("/api/session/:id", get(Self::session_handler))
-            .route("/api/session/:id", post(Self::update_session_handler))
-            .route("/ws", get(Self::websocket_handler))
-            .layer(cors)
-            .with_state(self.state.clone());
+        let app = self.router().layer(cors);
         
         // Start server
         let addr = format!("{}:{}", self.config.host, self.config.port);
@@ -103,6 +149,27 @@ impl WebServer {
         Ok(())
     }
     
+    /// Routes of the server
+    fn router(&self) -> axum::Router {
+        let app = axum::Router::new()
+            .route("/", get(Self::index_handler))
+            .route("/api/status", get(Self::status_handler))
+            .route("/api/solve", post(Self::solve_handler))
+            .route("/api/analyze", post(Self::analyze_handler))
+            .route("/api/convert", post(Self::convert_handler))
+            .route("/api/validate", post(Self::validate_handler))
+            .route("/api/benchmark", post(Self::benchmark_handler))
+            .route("/api/metrics", get(Self::metrics_handler))
+            .route("/api/session/:id", get(Self::session_handler))
+            .route("/api/session/:id", post(Self::update_session_handler));
+        let app = if self.config.enable_websocket {
+            app.route("/ws", get(Self::websocket_handler))
+        } else {
+            app
+        };
+        app.with_state(self.state.clone())
+    }
+    
     /// Index handler
     async fn index_handler() -> Response<Body> {
         let html = r#"
@@ -192,6 +259,11 @@ curl -X POST http://localhost:8080/api/solve \
     ) -> Json<APIResponse> {
         match request {
             APIRequest::BEMSolver { problem_type, parameters } => {
+                state.publish(WebSocketMessage::Progress {
+                    progress: 0.0,
+                    message: format!("Solving {} problem", problem_type),
+                });
+                
                 // Simulate BEM solving
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                 
@@ -206,6 +278,15 @@ curl -X POST http://localhost:8080/api/solve \
                     }
                 });
                 
+                state.publish(WebSocketMessage::Data {
+                    data_type: "bem_results".to_string(),
+                    content: result_data["results"].clone(),
+                });
+                state.publish(WebSocketMessage::Progress {
+                    progress: 1.0,
+                    message: "BEM problem solved".to_string(),
+                });
+                
                 Json(APIResponse::Success {
                     data: result_data,
                     message: "BEM problem solved successfully".to_string(),
@@ -421,15 +502,120 @@ curl -X POST http://localhost:8080/api/solve \
     }
     
     /// WebSocket handler
-    async fn websocket_handler() -> Response<Body> {
-        // Placeholder for WebSocket implementation
+    ///
+    /// Completes the RFC 6455 handshake and streams the client's decimated
+    /// messages as text frames until the client closes the connection.
+    async fn websocket_handler(State(state): State<Arc<AppState>>, mut request: Request) -> Response<Body> {
+        let headers = request.headers();
+        let upgrade = headers
+            .get(header::UPGRADE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        let key = match headers.get(header::SEC_WEBSOCKET_KEY).and_then(|v| v.to_str().ok()) {
+            Some(key) if upgrade => key.trim().to_string(),
+            _ => {
+                return Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Body::from("Expected a WebSocket upgrade request"))
+                    .unwrap();
+            }
+        };
+        
+        // Subscribe before answering so nothing published after the handshake is missed
+        let messages = state.client_stream();
+        let on_upgrade = hyper::upgrade::on(&mut request);
+        tokio::spawn(async move {
+            if let Ok(upgraded) = on_upgrade.await {
+                let _ = stream_messages(hyper_util::rt::TokioIo::new(upgraded), messages).await;
+            }
+        });
+        
+        let accept = base64::engine::general_purpose::STANDARD
+            .encode(Sha1::digest(format!("{}{}", key, WEBSOCKET_GUID)));
         Response::builder()
-            .status(StatusCode::NOT_IMPLEMENTED)
-            .body(Body::from("WebSocket not implemented yet"))
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::UPGRADE, "websocket")
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept)
+            .body(Body::empty())
             .unwrap()
     }
 }
 
+/// Write messages as WebSocket text frames until the client closes
+async fn stream_messages<S: AsyncRead + AsyncWrite>(socket: S, mut messages: mpsc::Receiver<WebSocketMessage>) -> Result<()> {
+    let (mut reader, mut writer) = tokio::io::split(socket);
+    let closed = async move {
+        // Client frames are only read to notice the close
+        while let Ok((opcode, _)) = read_frame(&mut reader).await {
+            if opcode == OPCODE_CLOSE {
+                break;
+            }
+        }
+    };
+    tokio::pin!(closed);
+    
+    loop {
+        tokio::select! {
+            message = messages.recv() => match message {
+                Some(message) => {
+                    let text = serde_json::to_string(&message).map_err(|e| UIError::WebSocketError {
+                        message: format!("Failed to encode message: {}", e),
+                    })?;
+                    writer.write_all(&frame(OPCODE_TEXT, text.as_bytes())).await?;
+                }
+                None => break,
+            },
+            _ = &mut closed => break,
+        }
+    }
+    writer.write_all(&frame(OPCODE_CLOSE, &[])).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+
+/// Unmasked final frame, as sent by a server
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Read one frame, returning its opcode and unmasked payload
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    reader.read_exact(&mut head).await?;
+    let len = match head[1] & 0x7f {
+        126 => reader.read_u16().await? as u64,
+        127 => reader.read_u64().await?,
+        n => n as u64,
+    };
+    let mut mask = [0u8; 4];
+    if head[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload).await?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((head[0] & 0x0f, payload))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,12 +630,7 @@ mod tests {
     
     #[tokio::test]
     async fn test_status_handler() {
-        let config = ServerConfig::default();
-        let state = Arc::new(AppState {
-            config: config.clone(),
-            sessions: Arc::new(RwLock::new(HashMap::new())),
-            metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
-        });
+        let state = Arc::new(AppState::new(ServerConfig::default()));
         
         let response = WebServer::status_handler(State(state)).await;
         
@@ -463,4 +644,70 @@ mod tests {
             _ => panic!("Expected success response"),
         }
     }
-} 
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_websocket_stream_is_decimated() {
+        let config = ServerConfig {
+            stream: StreamConfig {
+                max_message_rate: 5.0,
+                max_array_points: 4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let server = WebServer::new(config);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = server.router();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        
+        // Handshake with the sample key of RFC 6455
+        let mut socket = tokio::net::TcpStream::connect(addr).await.unwrap();
+        socket.write_all(b"GET /ws HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+            Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n").await.unwrap();
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            response.push(socket.read_u8().await.unwrap());
+        }
+        let response = String::from_utf8(response).unwrap().to_lowercase();
+        assert!(response.starts_with("http/1.1 101"));
+        assert!(response.contains("sec-websocket-accept: s3pplmbitxaq9kygzzhzrbk+xoo="));
+        
+        // A burst of progress and a large array, then the final status
+        for i in 0..50 {
+            server.publish(WebSocketMessage::Progress { progress: i as f64 / 49.0, message: "solving".to_string() });
+        }
+        let elevation: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        server.publish(WebSocketMessage::Data { data_type: "free_surface".to_string(), content: serde_json::json!(elevation) });
+        server.publish(WebSocketMessage::Status { status_type: "completed".to_string(), data: Value::Null });
+        
+        // Superseded progress is dropped, the array thinned
+        let (mut progress, mut status, mut data) = (Vec::new(), false, None);
+        while !status || data.is_none() {
+            let (opcode, payload) = tokio::time::timeout(Duration::from_secs(5), read_frame(&mut socket)).await.unwrap().unwrap();
+            assert_eq!(opcode, OPCODE_TEXT);
+            match serde_json::from_slice(&payload).unwrap() {
+                WebSocketMessage::Progress { progress: p, .. } => progress.push(p),
+                WebSocketMessage::Status { .. } => status = true,
+                WebSocketMessage::Data { content, .. } => data = Some(content),
+                WebSocketMessage::Error { message, .. } => panic!("{}", message),
+            }
+        }
+        assert!(progress.len() < 10);
+        assert_eq!((progress[0], progress[progress.len() - 1]), (0.0, 1.0));
+        assert_eq!(data.unwrap(), serde_json::json!([0.0, 33.0, 66.0, 99.0]));
+        
+        // A masked close is answered with a close
+        socket.write_all(&[0x88, 0x80, 1, 2, 3, 4]).await.unwrap();
+        let (opcode, _) = tokio::time::timeout(Duration::from_secs(5), read_frame(&mut socket)).await.unwrap().unwrap();
+        assert_eq!(opcode, OPCODE_CLOSE);
+    }
+    
+    #[tokio::test]
+    async fn test_plain_request_to_websocket_is_rejected() {
+        let state = Arc::new(AppState::new(ServerConfig::default()));
+        let request = Request::builder().uri("/ws").body(Body::empty()).unwrap();
+        let response = WebServer::websocket_handler(State(state), request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}